@@ -6,15 +6,20 @@
 
 use std::path::PathBuf;
 
-use clap::{ArgAction, Parser};
-use gps::SignalGeneratorBuilder;
+use clap::{ArgAction, Parser, Subcommand};
+use gps::{HealthMode, SignalGeneratorBuilder, TruthFormat};
 
-use crate::Error;
+use crate::{Error, plan::PlanArgs, rtcm::RtcmArgs};
 
 /*
 
 Options:
-  -e <gps_nav>     RINEX navigation file for GPS ephemerides (required)
+  -e <gps_nav>...  RINEX navigation files or glob patterns for GPS ephemerides (required)
+  --almanac <files>... YUMA or SEM almanac files or glob patterns used instead of -e
+  --nominal <count>  Synthetic nominal constellation of 24 to 32 satellites used instead of -e
+  --lenient        Skip malformed navigation file lines with a warning
+  --sp3 <files>... SP3 precise orbit files or glob patterns for the simulated ranges
+  --clk <files>... RINEX clock files or glob patterns for the simulated ranges
   -u <user_motion> User motion file in ECEF x, y, z format (dynamic mode)
   -x <user_motion> User motion file in lat, lon, height format (dynamic mode)
   -g <nmea_gga>    NMEA GGA stream (dynamic mode)
@@ -30,6 +35,44 @@ Options:
   -i               Disable ionospheric delay for spacecraft scenario
   -p [fixed_gain]  Disable path loss and hold power level constant
   -v               Show details about simulated channels
+  --include <prns>         Only simulate these PRNs e.g. 1,5,12,24
+  --exclude <prns>         Never simulate these PRNs
+  --max-sats <count>       Maximum number of simultaneously simulated satellites
+  --health-mode <mode>     Unhealthy satellites [simulate/skip/healthy]
+  --health-override <list> Broadcast health per PRN e.g. 5:63,12:0
+  --elevation-mask <deg>   Elevation mask in degrees
+  --truth <file>           Truth log of simulated observables
+  --truth-format <format>  Truth log format [csv/jsonl/nmea]
+  --rinex-obs <file>       RINEX 3 observation file of simulated measurements
+  --rinex-interval <sec>   RINEX observation interval (default: 1.0)
+  --phase-from-range       Start carrier phases aligned with pseudoranges (RTK)
+  --superframe             Cycle subframes 4 and 5 through all 25 almanac and health pages
+  --special-message <text> Subframe 4 page 17 message of up to 22 characters (implies --superframe)
+  --compatible-subframes   Broadcast zero URA index, L2 P data flag, fit interval flag and AODO as gps-sdr-sim
+  --galileo-codes <file>   Galileo E1-B/E1-C code table, adds Galileo E1 OS signals
+  --galileo-cboc           Modulate Galileo E1 with CBOC instead of BOC(1,1)
+  --beidou                 Add BeiDou B1I signals (needs the centre frequency and sampling frequency to cover 1561.098 MHz)
+  --glonass                Add GLONASS L1OF signals (needs the centre frequency and sampling frequency to cover 1598-1606 MHz)
+  --qzss                   Add QZSS L1 C/A signals
+  --sbas                   Add SBAS L1 signals of GEO satellites, broadcasting message type 0 (test mode)
+  --sbas-messages <file>   SBAS message file to broadcast (implies --sbas)
+  --l2c                    Add GPS L2C signals with CNAV (needs the centre frequency and sampling frequency to cover 1227.6 MHz)
+  --l5                     Add GPS L5 signals with CNAV (needs the centre frequency and sampling frequency to cover 1176.45 MHz +/- 10.23 MHz)
+  --l1c                    Add GPS L1C signals with CNAV-2 (needs the sampling frequency to cover 1575.42 MHz +/- 2.046 MHz)
+  --center-frequency <hz>  RF centre frequency of the output [Hz] (default: 1575420000)
+  --intermediate-frequency <hz> Frequency the centre frequency is shifted to in the output [Hz] (default: 0)
+  --real                   Write real samples at the intermediate frequency instead of I/Q samples
+  --iq-imbalance <db,deg>  Transmitter I/Q gain [dB] and phase [deg] imbalance e.g. 0.5,2
+  --dc-offset <i,q>        Transmitter DC offset in 16-bit sample units e.g. 40,-25
+  --frequency-offset <ppm> Transmitter LO frequency offset [ppm]
+  --phase-noise <mask>     Transmitter LO phase noise as OFFSET:DBC points e.g. 1000:-80,10000:-90,100000:-110
+  --predistort             Compensate the given transmitter impairments instead of emulating them
+  --oversampling <factor>  Generate at a multiple of the sampling frequency, then filter and decimate [1-16] (default: 1)
+  --receiver-bandwidth <hz> Two-sided receiver pre-correlation bandwidth [Hz], filters the output
+
+Subcommands:
+  plan             Satellite visibility, DOP and sky plot without samples
+  rtcm             RTCM 3 stream of a virtual reference station
 */
 /// Command-line arguments for the GPS signal simulator.
 ///
@@ -40,10 +83,222 @@ Options:
 #[command(term_width = 0)]
 #[command(version, about="gps-sdr-sim compatible", long_about = None)]
 #[command(propagate_version = true)]
+#[command(args_conflicts_with_subcommands = true)]
+#[command(subcommand_negates_reqs = true)]
 pub struct Args {
-    /// RINEX navigation file for GPS ephemerides (required)
-    #[arg(short, long, value_hint = clap::ValueHint::FilePath)]
-    ephemerides: std::path::PathBuf,
+    /// Scenario options shared with the subcommands
+    #[command(flatten)]
+    scenario: ScenarioArgs,
+
+    /// I/Q sampling data file (default: gpssim.bin)
+    #[arg(short = 'o', long)]
+    output: Option<PathBuf>,
+
+    /// Sampling frequency [Hz] (default: 2600000)
+    #[arg(short = 's', long, default_value_t = 2600000)]
+    frequency: usize,
+
+    /// I/Q data format [1/8/16] (default: 16)
+    #[arg(short = 'b', long, default_value_t = 16)]
+    bits: usize,
+
+    /// Disable path loss and hold power level constant [`fixed_gain`]
+    #[arg(short = 'p', long)]
+    path_loss: Option<i32>,
+
+    /// Show details about simulated channels
+    #[arg(short = 'v', long,default_value_t = false, action = ArgAction::SetTrue)]
+    verbose: bool,
+
+    /// Truth log of the simulated observables per update step and channel
+    #[arg(long, value_hint = clap::ValueHint::FilePath)]
+    truth: Option<PathBuf>,
+
+    /// Truth log format [csv/jsonl/nmea] (default: csv)
+    #[arg(long)]
+    truth_format: Option<TruthFormat>,
+
+    /// RINEX 3 observation file of the simulated measurements (C1C, L1C, D1C,
+    /// S1C)
+    #[arg(long, value_hint = clap::ValueHint::FilePath)]
+    rinex_obs: Option<PathBuf>,
+
+    /// RINEX observation interval in seconds (default: 1.0)
+    #[arg(long)]
+    rinex_interval: Option<f64>,
+
+    /// Start carrier phases aligned with the pseudoranges, matching the
+    /// ambiguities of the `rtcm` base station
+    #[arg(long, default_value_t = false)]
+    phase_from_range: bool,
+
+    /// Cycle subframes 4 and 5 through all 25 pages, with the almanac of
+    /// every satellite and the health pages
+    #[arg(long, default_value_t = false)]
+    superframe: bool,
+
+    /// Special message of up to 22 ASCII characters in subframe 4 page 17
+    /// (implies --superframe)
+    #[arg(long, value_name = "TEXT")]
+    special_message: Option<String>,
+
+    /// Broadcast zero URA index, L2 P data flag, fit interval flag and age of
+    /// data offset, as gps-sdr-sim, instead of the navigation file values
+    #[arg(long, default_value_t = false)]
+    compatible_subframes: bool,
+
+    /// Galileo E1-B and E1-C code table; adds the Galileo E1 Open Service
+    /// signals of the navigation files to the output
+    #[arg(long, value_name = "FILE", value_hint = clap::ValueHint::FilePath)]
+    galileo_codes: Option<PathBuf>,
+
+    /// Modulate Galileo E1 with CBOC instead of BOC(1,1) (needs a sampling
+    /// frequency above 14 MHz)
+    #[arg(long, default_value_t = false)]
+    galileo_cboc: bool,
+
+    /// Add the BeiDou B1I signals of the navigation files (needs the
+    /// centre frequency and sampling frequency to cover 1561.098 MHz)
+    #[arg(long, default_value_t = false)]
+    beidou: bool,
+
+    /// Add the GLONASS L1OF signals of the navigation files (needs the
+    /// centre frequency and sampling frequency to cover the frequency
+    /// channels between 1598.0625 and 1605.375 MHz)
+    #[arg(long, default_value_t = false)]
+    glonass: bool,
+
+    /// Add the QZSS L1 C/A signals of the navigation files
+    #[arg(long, default_value_t = false)]
+    qzss: bool,
+
+    /// Add the SBAS L1 signals of the GEO satellites of the navigation
+    /// files, broadcasting message type 0 (test mode)
+    #[arg(long, default_value_t = false)]
+    sbas: bool,
+
+    /// SBAS message file to broadcast instead of message type 0 (implies
+    /// --sbas)
+    #[arg(long, value_name = "FILE", value_hint = clap::ValueHint::FilePath)]
+    sbas_messages: Option<PathBuf>,
+
+    /// Add the GPS L2C signals with CNAV messages (needs the centre
+    /// frequency and sampling frequency to cover 1227.6 MHz)
+    #[arg(long, default_value_t = false)]
+    l2c: bool,
+
+    /// Add the GPS L5 signals with CNAV messages (needs the centre frequency
+    /// and sampling frequency to cover 1176.45 MHz +/- 10.23 MHz)
+    #[arg(long, default_value_t = false)]
+    l5: bool,
+
+    /// Add the GPS L1C signals with CNAV-2 messages (needs the sampling
+    /// frequency to cover 1575.42 MHz +/- 2.046 MHz)
+    #[arg(long, default_value_t = false)]
+    l1c: bool,
+
+    /// RF centre frequency of the output [Hz] (default: 1575420000)
+    #[arg(long, value_name = "HZ")]
+    center_frequency: Option<f64>,
+
+    /// Frequency the centre frequency is shifted to in the output, e.g. to
+    /// keep the signals off the DC spike of the transmitter [Hz] (default: 0)
+    #[arg(long, value_name = "HZ", allow_negative_numbers = true)]
+    intermediate_frequency: Option<f64>,
+
+    /// Write real samples at the intermediate frequency instead of I/Q
+    /// samples, as front-ends like the MAX2769 deliver them
+    #[arg(long, default_value_t = false)]
+    real: bool,
+
+    /// Transmitter I/Q gain imbalance [dB] and phase imbalance [deg] e.g.
+    /// 0.5,2
+    #[arg(long, value_name = "DB,DEG", value_parser = parse_pair, allow_hyphen_values = true)]
+    iq_imbalance: Option<(f64, f64)>,
+
+    /// Transmitter DC offset of the I and Q samples, in 16-bit sample units
+    /// e.g. 40,-25
+    #[arg(long, value_name = "I,Q", value_parser = parse_pair, allow_hyphen_values = true)]
+    dc_offset: Option<(f64, f64)>,
+
+    /// Transmitter LO frequency offset [ppm]
+    #[arg(long, value_name = "PPM", allow_negative_numbers = true)]
+    frequency_offset: Option<f64>,
+
+    /// Transmitter LO phase noise as OFFSET:DBC points in Hz and dBc/Hz
+    /// e.g. 1000:-80,10000:-90,100000:-110
+    #[arg(long, value_name = "MASK", value_parser = parse_phase_noise, value_delimiter = ',')]
+    phase_noise: Option<Vec<(f64, f64)>>,
+
+    /// Compensate the transmitter impairments instead of emulating them
+    #[arg(long, default_value_t = false)]
+    predistort: bool,
+
+    /// Generate at a multiple of the sampling frequency, then filter and
+    /// decimate [1-16] (default: 1)
+    #[arg(long, value_name = "FACTOR")]
+    oversampling: Option<usize>,
+
+    /// Two-sided receiver pre-correlation bandwidth, filters the output [Hz]
+    #[arg(long, value_name = "HZ")]
+    receiver_bandwidth: Option<f64>,
+
+    /// Subcommand to run instead of generating samples
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// Subcommands of the GPS signal simulator.
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Print satellite visibility, DOP and a sky plot without generating
+    /// samples
+    Plan(PlanArgs),
+    /// Stream the observations of a virtual reference station as RTCM 3
+    Rtcm(RtcmArgs),
+}
+
+/// Scenario options shared by signal generation and the subcommands.
+///
+/// These options describe the constellation, the receiver trajectory and the
+/// time span of the scenario.
+#[derive(clap::Args, Debug)]
+pub struct ScenarioArgs {
+    /// RINEX navigation files or glob patterns for GPS ephemerides, merged
+    /// per satellite (required unless --almanac or --nominal is given)
+    #[arg(
+        short,
+        long,
+        required_unless_present_any = ["almanac", "nominal"],
+        num_args = 1..,
+        value_hint = clap::ValueHint::FilePath
+    )]
+    ephemerides: Vec<PathBuf>,
+
+    /// YUMA or SEM almanac files or glob patterns for approximate orbits of
+    /// long or future-date scenarios, used instead of navigation files
+    #[arg(long, num_args = 1.., conflicts_with = "ephemerides", value_hint = clap::ValueHint::FilePath)]
+    almanac: Option<Vec<PathBuf>>,
+
+    /// Synthetic nominal constellation of 24 baseline slots, 27 with expanded
+    /// slots, up to 32 satellites with spares, used instead of navigation
+    /// files for any date
+    #[arg(long, value_name = "COUNT", conflicts_with_all = ["ephemerides", "almanac"])]
+    nominal: Option<usize>,
+
+    /// Skip unknown header labels and malformed navigation file lines with a
+    /// warning
+    #[arg(long, default_value_t = false)]
+    lenient: bool,
+
+    /// SP3 precise orbit files or glob patterns; the simulated ranges follow
+    /// these orbits while the navigation message stays broadcast
+    #[arg(long, num_args = 1.., value_hint = clap::ValueHint::FilePath)]
+    sp3: Option<Vec<PathBuf>>,
+
+    /// RINEX clock files or glob patterns replacing the SP3 satellite clocks
+    #[arg(long, num_args = 1.., requires = "sp3", value_hint = clap::ValueHint::FilePath)]
+    clk: Option<Vec<PathBuf>>,
 
     /// User motion file in ECEF x, y, z format (dynamic mode)
     #[arg(short = 'u', long, value_hint = clap::ValueHint::FilePath)]
@@ -83,43 +338,60 @@ pub struct Args {
     #[arg(short = 'd', long)]
     duration: Option<f64>,
 
-    /// I/Q sampling data file (default: gpssim.bin)
-    #[arg(short = 'o', long)]
-    output: Option<PathBuf>,
-
-    /// Sampling frequency [Hz] (default: 2600000)
-    #[arg(short = 's', long, default_value_t = 2600000)]
-    frequency: usize,
-
-    /// I/Q data format [1/8/16] (default: 16)
-    #[arg(short = 'b', long, default_value_t = 16)]
-    bits: usize,
-
     /// Disable ionospheric delay for spacecraft scenario
     #[arg(short = 'i', long, default_value_t = false, action = ArgAction::SetFalse)]
     ionospheric_disable: bool,
 
-    /// Disable path loss and hold power level constant [`fixed_gain`]
-    #[arg(short = 'p', long)]
-    path_loss: Option<i32>,
+    /// Elevation mask [deg] (default: 0)
+    #[arg(long)]
+    elevation_mask: Option<f64>,
 
-    /// Show details about simulated channels
-    #[arg(short = 'v', long,default_value_t = false, action = ArgAction::SetTrue)]
-    verbose: bool,
+    /// Only simulate these GPS PRNs e.g. 1,5,12,24
+    #[arg(long, value_parser, value_delimiter = ',')]
+    include: Option<Vec<usize>>,
+
+    /// Never simulate these GPS PRNs e.g. 3,17
+    #[arg(long, value_parser, value_delimiter = ',')]
+    exclude: Option<Vec<usize>>,
+
+    /// Maximum number of simultaneously simulated satellites per system
+    #[arg(long)]
+    max_sats: Option<usize>,
+
+    /// Unhealthy satellites [simulate/skip/healthy] (default: simulate)
+    #[arg(long)]
+    health_mode: Option<HealthMode>,
+
+    /// Broadcast health per PRN as PRN:HEALTH e.g. 5:63,12:0
+    #[arg(long, value_parser = parse_health_override, value_delimiter = ',')]
+    health_override: Option<Vec<(usize, i32)>>,
 }
 
-impl Args {
-    /// Runs the GPS signal simulation based on the command-line arguments.
-    ///
-    /// This method configures the signal generator with the provided options,
-    /// initializes it, and runs the simulation.
+impl ScenarioArgs {
+    /// Creates a signal generator builder configured with the scenario
+    /// options.
     ///
     /// # Returns
-    /// * `Ok(())` - If the simulation completes successfully
-    /// * `Err(Error)` - If an error occurs during simulation
-    pub fn run(&self) -> Result<(), Error> {
+    /// * `Ok(SignalGeneratorBuilder)` - The configured builder
+    /// * `Err(Error)` - If an option is invalid or a file cannot be read
+    pub fn builder(&self) -> Result<SignalGeneratorBuilder, Error> {
         let builder = SignalGeneratorBuilder::default()
-            .navigation_file(Some(self.ephemerides.clone()))?
+            .lenient_navigation(Some(self.lenient))
+            .navigation_files(
+                (!self.ephemerides.is_empty())
+                    .then(|| expand_globs(&self.ephemerides))
+                    .transpose()?,
+            )?
+            .almanac_files(
+                self.almanac.as_deref().map(expand_globs).transpose()?,
+            )?
+            .nominal_constellation(self.nominal)?
+            .precise_orbit_files(
+                self.sp3.as_deref().map(expand_globs).transpose()?,
+            )?
+            .precise_clock_files(
+                self.clk.as_deref().map(expand_globs).transpose()?,
+            )?
             .user_motion_file(self.user_motion_ecef.clone())?
             .user_motion_llh_file(self.user_motion_llh.clone())?
             .user_motion_nmea_gga_file(self.nmea_gga.clone())?
@@ -129,12 +401,172 @@ impl Args {
             .time(self.time.clone())?
             .time_override(self.time_override)
             .duration(self.duration)
+            .ionospheric_disable(Some(self.ionospheric_disable))
+            .elevation_mask(self.elevation_mask)?
+            .satellite_include(self.include.clone())?
+            .satellite_exclude(self.exclude.clone())?
+            .max_satellites(self.max_sats)?
+            .health_mode(self.health_mode)
+            .health_override(self.health_override.clone())?;
+        Ok(builder)
+    }
+}
+
+/// Expands glob patterns of file arguments.
+///
+/// Arguments naming an existing file are kept as they are, so that file names
+/// containing pattern characters still work. The files matching a pattern are
+/// sorted by name.
+///
+/// # Arguments
+/// * `paths` - Files or glob patterns such as "brdc*.24n"
+///
+/// # Returns
+/// * `Ok(files)` - The files
+/// * `Err(Error)` - If a pattern is invalid or matches no file
+fn expand_globs(paths: &[PathBuf]) -> Result<Vec<PathBuf>, Error> {
+    let mut files = Vec::new();
+    for path in paths {
+        let pattern = path.to_string_lossy();
+        if path.exists() || !pattern.contains(['*', '?', '[']) {
+            files.push(path.clone());
+            continue;
+        }
+        let matches = glob::glob(&pattern)
+            .map_err(|e| Error::cli_error(format!("pattern '{pattern}': {e}")))?
+            .filter_map(Result::ok)
+            .collect::<Vec<_>>();
+        if matches.is_empty() {
+            return Err(Error::cli_error(format!(
+                "no file matches '{pattern}'"
+            )));
+        }
+        files.extend(matches);
+    }
+    Ok(files)
+}
+
+/// Parses a `PRN:HEALTH` pair for the `--health-override` option.
+///
+/// # Arguments
+/// * `value` - A string such as "5:63"
+///
+/// # Returns
+/// * `Ok((prn, health))` - The parsed pair
+/// * `Err(String)` - A description of the formatting problem
+fn parse_health_override(value: &str) -> Result<(usize, i32), String> {
+    let (prn, health) = value
+        .split_once(':')
+        .ok_or_else(|| format!("expected PRN:HEALTH, got '{value}'"))?;
+    let prn = prn
+        .trim()
+        .parse()
+        .map_err(|e| format!("invalid PRN '{prn}': {e}"))?;
+    let health = health
+        .trim()
+        .parse()
+        .map_err(|e| format!("invalid health '{health}': {e}"))?;
+    Ok((prn, health))
+}
+
+/// Parses a comma-separated pair of numbers.
+///
+/// # Arguments
+/// * `value` - A string such as "0.5,2"
+///
+/// # Returns
+/// * `Ok((first, second))` - The parsed pair
+/// * `Err(String)` - A description of the formatting problem
+fn parse_pair(value: &str) -> Result<(f64, f64), String> {
+    let (first, second) = value
+        .split_once(',')
+        .ok_or_else(|| format!("expected two numbers, got '{value}'"))?;
+    let first = first
+        .trim()
+        .parse()
+        .map_err(|e| format!("invalid number '{first}': {e}"))?;
+    let second = second
+        .trim()
+        .parse()
+        .map_err(|e| format!("invalid number '{second}': {e}"))?;
+    Ok((first, second))
+}
+
+/// Parses an `OFFSET:DBC` point for the `--phase-noise` option.
+///
+/// # Arguments
+/// * `value` - A string such as "1000:-80"
+///
+/// # Returns
+/// * `Ok((offset, level))` - The offset in Hz and level in dBc/Hz
+/// * `Err(String)` - A description of the formatting problem
+fn parse_phase_noise(value: &str) -> Result<(f64, f64), String> {
+    let (offset, level) = value
+        .split_once(':')
+        .ok_or_else(|| format!("expected OFFSET:DBC, got '{value}'"))?;
+    let offset = offset
+        .trim()
+        .parse()
+        .map_err(|e| format!("invalid offset '{offset}': {e}"))?;
+    let level = level
+        .trim()
+        .parse()
+        .map_err(|e| format!("invalid level '{level}': {e}"))?;
+    Ok((offset, level))
+}
+
+impl Args {
+    /// Runs the GPS signal simulation based on the command-line arguments.
+    ///
+    /// This method configures the signal generator with the provided options,
+    /// initializes it, and runs the simulation. When a subcommand is given,
+    /// the subcommand is run instead.
+    ///
+    /// # Returns
+    /// * `Ok(())` - If the simulation completes successfully
+    /// * `Err(Error)` - If an error occurs during simulation
+    pub fn run(&self) -> Result<(), Error> {
+        match &self.command {
+            Some(Command::Plan(plan)) => return plan.run(),
+            Some(Command::Rtcm(rtcm)) => return rtcm.run(),
+            None => {}
+        }
+        let builder = self
+            .scenario
+            .builder()?
             .output_file(self.output.clone())
             .frequency(Some(self.frequency))?
             .data_format(Some(self.bits))?
-            .ionospheric_disable(Some(self.ionospheric_disable))
             .path_loss(self.path_loss)
-            .verbose(Some(self.verbose));
+            .verbose(Some(self.verbose))
+            .truth_file(self.truth.clone())
+            .truth_format(self.truth_format)
+            .observation_file(self.rinex_obs.clone())
+            .observation_interval(self.rinex_interval)?
+            .phase_from_range(Some(self.phase_from_range))
+            .superframe_pages(Some(self.superframe))
+            .special_message(self.special_message.clone())
+            .compatible_subframes(Some(self.compatible_subframes))
+            .galileo_codes(self.galileo_codes.clone())?
+            .galileo_cboc(Some(self.galileo_cboc))
+            .beidou(Some(self.beidou))
+            .glonass(Some(self.glonass))
+            .qzss(Some(self.qzss))
+            .sbas(Some(self.sbas))
+            .sbas_messages(self.sbas_messages.clone())?
+            .l2c(Some(self.l2c))
+            .l5(Some(self.l5))
+            .l1c(Some(self.l1c))
+            .center_frequency(self.center_frequency)?
+            .intermediate_frequency(self.intermediate_frequency)?
+            .real_output(Some(self.real))
+            .iq_imbalance(self.iq_imbalance)?
+            .dc_offset(self.dc_offset)?
+            .frequency_offset(self.frequency_offset)?
+            .phase_noise(self.phase_noise.clone())?
+            .predistortion(Some(self.predistort))
+            .oversampling(self.oversampling)?
+            .receiver_bandwidth(self.receiver_bandwidth)?;
         let mut generator = builder.build()?;
         generator.initialize()?;
         generator.run_simulation()?;
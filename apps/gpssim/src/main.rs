@@ -10,6 +10,10 @@
 mod cli;
 /// Error types for the application
 mod error;
+/// Satellite visibility planning subcommand
+mod plan;
+/// RTCM 3 virtual reference station subcommand
+mod rtcm;
 /// Utility functions for logging and diagnostics
 mod utils;
 
@@ -0,0 +1,58 @@
+//! Satellite visibility planning subcommand.
+//!
+//! This module implements `gpssim plan`, which reports what a receiver will
+//! see during a scenario (visible satellites, their geometry and the DOP)
+//! without generating any I/Q samples.
+
+use std::path::PathBuf;
+
+use gps::PlanFormat;
+
+use crate::{Error, cli::ScenarioArgs};
+
+/// Command-line arguments of the `plan` subcommand.
+#[derive(clap::Args, Debug)]
+pub struct PlanArgs {
+    /// Scenario options shared with signal generation
+    #[command(flatten)]
+    scenario: ScenarioArgs,
+
+    /// Time between planned epochs [sec] (default: 60)
+    #[arg(long, default_value_t = 60.0)]
+    interval: f64,
+
+    /// Output format [csv/json] (default: csv)
+    #[arg(short = 'f', long, default_value = "csv")]
+    format: PlanFormat,
+
+    /// Print an ASCII sky plot of the satellite tracks to stderr
+    #[arg(long, default_value_t = false)]
+    sky_plot: bool,
+
+    /// Plan output file (default: stdout)
+    #[arg(short = 'o', long)]
+    output: Option<PathBuf>,
+}
+
+impl PlanArgs {
+    /// Computes the visibility plan and writes it out.
+    ///
+    /// # Returns
+    /// * `Ok(())` - If the plan was written successfully
+    /// * `Err(Error)` - If the scenario is invalid or the output cannot be
+    ///   written
+    pub fn run(&self) -> Result<(), Error> {
+        let mut generator =
+            self.scenario.builder()?.data_format(Some(16))?.build()?;
+        let plan = generator.plan(self.interval)?;
+        let text = plan.render(self.format);
+        match &self.output {
+            Some(file) => std::fs::write(file, text)?,
+            None => print!("{text}"),
+        }
+        if self.sky_plot {
+            eprint!("{}", plan.sky_plot());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+//! RTCM 3 virtual reference station subcommand.
+//!
+//! This module implements `gpssim rtcm`, which streams the observations of a
+//! base station in the simulated sky as RTCM 3 messages (1005, 1019 and
+//! MSM4/MSM7) to a file or to TCP clients, so that an RTK receiver fed with
+//! the simulated rover signal can compute a fixed solution.
+
+use gps::{BaseStation, MsmType, RtcmOutput};
+
+use crate::{Error, cli::ScenarioArgs};
+
+/// Command-line arguments of the `rtcm` subcommand.
+#[derive(clap::Args, Debug)]
+pub struct RtcmArgs {
+    /// Scenario options shared with signal generation
+    #[command(flatten)]
+    scenario: ScenarioArgs,
+
+    /// Base station lat, lon, height e.g. 35.681298,139.766247,10.0
+    /// (default: initial receiver position)
+    #[arg(long, value_parser, value_delimiter = ',')]
+    base: Option<Vec<f64>>,
+
+    /// Base station ECEF X,Y,Z in meters
+    #[arg(long, value_parser, value_delimiter = ',', conflicts_with = "base")]
+    base_ecef: Option<Vec<f64>>,
+
+    /// Reference station ID [0-4095] (default: 0)
+    #[arg(long, default_value_t = 0)]
+    station_id: u16,
+
+    /// Observation message type [4/7] (default: 7)
+    #[arg(long, default_value = "7")]
+    msm: MsmType,
+
+    /// Time between epochs [sec] (default: 1.0)
+    #[arg(long, default_value_t = 1.0)]
+    interval: f64,
+
+    /// Output file or TCP server port e.g. tcp:2101
+    #[arg(short = 'o', long, default_value = "gpssim.rtcm3")]
+    output: String,
+}
+
+impl RtcmArgs {
+    /// Streams the base station observations.
+    ///
+    /// # Returns
+    /// * `Ok(())` - If the stream was written successfully
+    /// * `Err(Error)` - If the scenario or the base station is invalid or the
+    ///   output cannot be written
+    pub fn run(&self) -> Result<(), Error> {
+        if self.station_id > 4095 {
+            return Err(Error::cli_error("Station ID must be in 0-4095"));
+        }
+        let mut generator =
+            self.scenario.builder()?.data_format(Some(16))?.build()?;
+        let mut base = BaseStation {
+            station_id: self.station_id,
+            position: generator.positions[0],
+            interval: self.interval,
+            msm: self.msm,
+        };
+        if let Some(llh) = &self.base {
+            base = base.with_location(llh)?;
+        }
+        if let Some(xyz) = &self.base_ecef {
+            base = base.with_ecef(xyz)?;
+        }
+        let mut output = RtcmOutput::open(&self.output)?;
+        let epochs = generator.rtcm_stream(&base, &mut output)?;
+        eprintln!("Sent {epochs} RTCM epochs");
+        Ok(())
+    }
+}
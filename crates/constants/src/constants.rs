@@ -106,14 +106,60 @@ pub const POW2_M27: f64 = 7.450_580_596_923_828e-9;
 /// Used in GPS navigation message parameter scaling
 pub const POW2_M24: f64 = 5.960_464_477_539_063e-8;
 
+/// Precomputed value of 2^(-11)
+/// Used in GPS almanac parameter scaling
+pub const POW2_M11: f64 = 4.882_812_5e-4;
+
+/// Precomputed value of 2^(-20)
+/// Used in GPS almanac parameter scaling
+pub const POW2_M20: f64 = 9.536_743_164_062_5e-7;
+
+/// Precomputed value of 2^(-21)
+/// Used in GPS almanac parameter scaling
+pub const POW2_M21: f64 = 4.768_371_582_031_25e-7;
+
+/// Precomputed value of 2^(-23)
+/// Used in GPS almanac parameter scaling
+pub const POW2_M23: f64 = 1.192_092_895_507_812_5e-7;
+
+/// Precomputed value of 2^(-38)
+/// Used in GPS almanac parameter scaling
+pub const POW2_M38: f64 = 3.637_978_807_091_713e-12;
+
 /// Earth's gravitational constant (μ) in m³/s²
 /// Standard value from GPS Interface Control Document (ICD-GPS-200)
 pub const GM_EARTH: f64 = 3.986_005e14;
 
+/// Earth's gravitational constant (μ) of the Galileo system in m³/s²
+/// Standard value from the Galileo OS SIS ICD
+pub const GM_GALILEO: f64 = 3.986_004_418e14;
+
 /// Earth's rotation rate (ω) in rad/s
 /// Standard value from GPS Interface Control Document (ICD-GPS-200)
 pub const OMEGA_EARTH: f64 = 7.292_115_146_7e-5;
 
+/// CGCS2000 Earth's gravitational constant (μ) in m³/s²
+/// Standard value from the BeiDou SIS ICD
+pub const GM_CGCS2000: f64 = 3.986_004_418e14;
+
+/// CGCS2000 Earth's rotation rate (ω) in rad/s
+/// Standard value from the BeiDou SIS ICD
+pub const OMEGA_EARTH_CGCS2000: f64 = 7.292_115e-5;
+
+/// PZ-90 Earth's gravitational constant (μ) in m³/s²
+/// Standard value from the GLONASS ICD
+pub const GM_PZ90: f64 = 3.986_004_418e14;
+
+/// PZ-90 Earth's rotation rate (ω) in rad/s
+/// Standard value from the GLONASS ICD
+pub const OMEGA_EARTH_PZ90: f64 = 7.292_115e-5;
+
+/// PZ-90 second zonal harmonic coefficient of the geopotential (J2)
+pub const PZ90_J2: f64 = 1.082_625_75e-3;
+
+/// PZ-90 ellipsoid semi-major axis (equatorial radius) in meters
+pub const PZ90_RADIUS: f64 = 6_378_136.0;
+
 /// WGS-84 ellipsoid semi-major axis (equatorial radius) in meters
 pub const WGS84_RADIUS: f64 = 6_378_137.0;
 
@@ -121,6 +167,12 @@ pub const WGS84_RADIUS: f64 = 6_378_137.0;
 /// Defines the flattening of the ellipsoid
 pub const WGS84_ECCENTRICITY: f64 = 0.081_819_190_842_6;
 
+/// CGCS2000 ellipsoid semi-major axis (equatorial radius) in meters
+pub const CGCS2000_RADIUS: f64 = 6_378_137.0;
+
+/// CGCS2000 ellipsoid flattening
+pub const CGCS2000_FLATTENING: f64 = 1.0 / 298.257_222_101;
+
 /// Conversion factor from radians to degrees (180/π)
 /// Used to convert angular measurements
 pub const R2D: f64 = 57.295_779_513_1;
@@ -152,6 +204,67 @@ pub const CODE_FREQ: f64 = 1.023e6;
 /// rate (1.023 MHz)
 pub const CARR_TO_CODE: f64 = 1.0 / 1540.0;
 
+/// GPS L2 carrier frequency in Hz (1227.60 MHz)
+pub const L2_FREQ: f64 = 1227.60e6;
+
+/// GPS L5 carrier frequency in Hz (1176.45 MHz)
+pub const L5_FREQ: f64 = 1176.45e6;
+
+/// GPS L2 civil moderate (CM) code length in chips (20 ms at 511.5 kHz)
+pub const L2CM_CODE_LEN: usize = 10230;
+
+/// GPS L2 civil long (CL) code length in chips (1.5 s at 511.5 kHz)
+pub const L2CL_CODE_LEN: usize = 767_250;
+
+/// GPS L5 I5 and Q5 code chipping rate in Hz (10.23 MHz)
+pub const L5_CODE_FREQ: f64 = 10.23e6;
+
+/// GPS L5 I5 and Q5 code length in chips (1 ms)
+pub const L5_CODE_LEN: usize = 10230;
+
+/// GPS L1CD and L1CP ranging code length in chips (10 ms at 1.023 MHz)
+pub const L1C_CODE_LEN: usize = 10230;
+
+/// Galileo E1-B and E1-C primary code length in chips (4 ms at 1.023 MHz)
+pub const E1_CODE_LEN: usize = 4092;
+
+/// Number of weeks between the GPS and Galileo week origins
+/// GST week 0 starts on August 22, 1999, GPS week 1024
+pub const GST_WEEK_OFFSET: i32 = 1024;
+
+/// BeiDou B1I carrier frequency in Hz (1561.098 MHz)
+pub const B1I_FREQ: f64 = 1561.098e6;
+
+/// BeiDou B1I ranging code chipping rate in Hz (2.046 MHz)
+pub const B1I_CODE_FREQ: f64 = 2.046e6;
+
+/// BeiDou B1I ranging code length in chips (1 ms)
+pub const B1I_CODE_LEN: usize = 2046;
+
+/// Number of weeks between the GPS and BeiDou week origins
+/// BDT week 0 starts on January 1, 2006, GPS week 1356
+pub const BDT_WEEK_OFFSET: i32 = 1356;
+
+/// Offset of BeiDou Time behind GPS time in seconds
+/// GPS time was 14 leap seconds ahead of UTC at the BDT origin
+pub const BDT_GPS_OFFSET: f64 = 14.0;
+
+/// GLONASS L1 carrier frequency of frequency channel 0 in Hz (1602 MHz)
+pub const G1_FREQ: f64 = 1602.0e6;
+
+/// Spacing of the GLONASS L1 frequency channels in Hz (562.5 kHz)
+pub const G1_CHANNEL_SPACING: f64 = 562.5e3;
+
+/// GLONASS standard accuracy (ST) ranging code chipping rate in Hz
+/// (0.511 MHz)
+pub const ST_CODE_FREQ: f64 = 0.511e6;
+
+/// GLONASS ST ranging code length in chips (1 ms)
+pub const ST_CODE_LEN: usize = 511;
+
+/// Offset of GLONASS time ahead of UTC(SU) in seconds (Moscow time, 3 hours)
+pub const GLONASS_UTC_OFFSET: f64 = 10_800.0;
+
 /// Sampling data format: 1-bit I/Q samples
 /// Used for compact file size at the cost of signal quality
 pub const SC01: i32 = 1;
@@ -165,10 +278,5 @@ pub const SC08: i32 = 8;
 #[allow(dead_code)]
 pub const SC16: i32 = 16;
 
-/// Size of the ephemeris array for storing satellite orbit data
-/// Set to 15 to accommodate a full day of GPS broadcast ephemeris data
-/// Each element represents approximately 1.6 hours of data
-pub const EPHEM_ARRAY_SIZE: usize = 15;
-
 /// Default sample rate for simulation updates in seconds (10 Hz)
 pub const SAMPLE_RATE: f64 = 0.1;
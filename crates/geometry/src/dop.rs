@@ -0,0 +1,126 @@
+use crate::{Error, coordinates::Azel};
+
+/// Dilution of precision (DOP) values of a satellite geometry.
+///
+/// The values are derived from the diagonal of `(GᵀG)⁻¹`, where `G` is the
+/// geometry matrix built from the east/north/up line-of-sight unit vectors
+/// and a receiver clock column:
+/// - GDOP: Geometric (position and time)
+/// - PDOP: Position (3D)
+/// - HDOP: Horizontal (east and north)
+/// - VDOP: Vertical (up)
+/// - TDOP: Time (receiver clock)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Dop {
+    /// Geometric dilution of precision
+    pub gdop: f64,
+    /// Position dilution of precision
+    pub pdop: f64,
+    /// Horizontal dilution of precision
+    pub hdop: f64,
+    /// Vertical dilution of precision
+    pub vdop: f64,
+    /// Time dilution of precision
+    pub tdop: f64,
+}
+
+impl Dop {
+    /// Builds the geometry matrix for a set of satellite directions.
+    ///
+    /// Each row contains the negated east, north and up components of the
+    /// unit line-of-sight vector followed by 1.0 for the receiver clock.
+    ///
+    /// # Arguments
+    /// * `azels` - Azimuth and elevation of each satellite in radians
+    ///
+    /// # Returns
+    /// One `[east, north, up, clock]` row per satellite
+    pub fn geometry_matrix(azels: &[Azel]) -> Vec<[f64; 4]> {
+        azels
+            .iter()
+            .map(|azel| {
+                let (sin_el, cos_el) = azel.el.sin_cos();
+                let (sin_az, cos_az) = azel.az.sin_cos();
+                [-cos_el * sin_az, -cos_el * cos_az, -sin_el, 1.0]
+            })
+            .collect()
+    }
+
+    /// Computes the dilution of precision for a set of satellite directions.
+    ///
+    /// # Arguments
+    /// * `azels` - Azimuth and elevation of each satellite in radians
+    ///
+    /// # Returns
+    /// * `Ok(Dop)` - The DOP values of the geometry
+    /// * `Err(Error)` - If the geometry cannot be solved
+    ///
+    /// # Errors
+    /// * `Error::InsufficientSatellites` - If fewer than four satellites are
+    ///   given
+    /// * `Error::SingularGeometry` - If the satellites are degenerate (for
+    ///   example all in the same direction)
+    pub fn from_azel(azels: &[Azel]) -> Result<Self, Error> {
+        if azels.len() < 4 {
+            return Err(Error::InsufficientSatellites(azels.len()));
+        }
+        let g = Self::geometry_matrix(azels);
+        // Normal matrix GᵀG
+        let mut normal = [[0.0; 4]; 4];
+        for row in &g {
+            for i in 0..4 {
+                for j in 0..4 {
+                    normal[i][j] += row[i] * row[j];
+                }
+            }
+        }
+        let q = invert4(normal).ok_or(Error::SingularGeometry)?;
+        let (qe, qn, qu, qt) = (q[0][0], q[1][1], q[2][2], q[3][3]);
+        Ok(Self {
+            gdop: (qe + qn + qu + qt).sqrt(),
+            pdop: (qe + qn + qu).sqrt(),
+            hdop: (qe + qn).sqrt(),
+            vdop: qu.sqrt(),
+            tdop: qt.sqrt(),
+        })
+    }
+}
+
+/// Inverts a 4x4 matrix by Gauss-Jordan elimination with partial pivoting.
+///
+/// # Arguments
+/// * `m` - Matrix to invert
+///
+/// # Returns
+/// * `Some(inverse)` - If the matrix is invertible
+/// * `None` - If the matrix is (numerically) singular
+fn invert4(mut m: [[f64; 4]; 4]) -> Option<[[f64; 4]; 4]> {
+    let mut inv = [[0.0; 4]; 4];
+    for (i, row) in inv.iter_mut().enumerate() {
+        row[i] = 1.0;
+    }
+    for col in 0..4 {
+        let pivot = (col..4)
+            .max_by(|&a, &b| m[a][col].abs().total_cmp(&m[b][col].abs()))?;
+        if m[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        m.swap(col, pivot);
+        inv.swap(col, pivot);
+        let scale = m[col][col].recip();
+        for j in 0..4 {
+            m[col][j] *= scale;
+            inv[col][j] *= scale;
+        }
+        for row in 0..4 {
+            if row != col {
+                let factor = m[row][col];
+                for j in 0..4 {
+                    m[row][j] -= factor * m[col][j];
+                    inv[row][j] -= factor * inv[col][j];
+                }
+            }
+        }
+    }
+    Some(inv)
+}
@@ -44,6 +44,14 @@ pub enum Error {
         el: f64,
     },
 
+    /// Error when too few satellites are available to solve a geometry
+    #[error("Insufficient satellites for geometry: {0} (need at least 4)")]
+    InsufficientSatellites(usize),
+
+    /// Error when the satellite geometry matrix cannot be inverted
+    #[error("Singular satellite geometry")]
+    SingularGeometry,
+
     /// Error during coordinate system conversion
     #[error("Coordinate conversion error: {0}")]
     ConversionError(String),
@@ -6,12 +6,15 @@
 //! - Earth-Centered, Earth-Fixed (ECEF) coordinates
 //! - North-East-Up (NEU) local tangent plane coordinates
 //! - Azimuth/Elevation (`AzEl`) coordinates
+//! - Dilution of precision (DOP) of a satellite geometry
 //!
 //! It implements formulas from <http://www.movable-type.co.uk/scripts/latlong.html>
 //! and standard WGS-84 coordinate transformations.
 
 /// Coordinate system types and implementations
 mod coordinates;
+/// Dilution of precision from satellite geometry
+mod dop;
 /// Error types for geometry operations
 mod error;
 #[cfg(test)]
@@ -21,5 +24,6 @@ mod traits;
 /// Coordinate system transformation functions
 mod transformation;
 pub use coordinates::{Azel, Ecef, Location, NavigationTarget, Neu};
+pub use dop::Dop;
 pub use error::Error;
 pub use traits::LocationMath;
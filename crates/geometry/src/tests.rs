@@ -1,4 +1,4 @@
-use crate::{coordinates::*, traits::LocationMath};
+use crate::{Dop, coordinates::*, traits::LocationMath};
 const LLH: [f64; 3] = [35.274_143_229, 137.014_853_084, 99.998];
 const XYZ: [f64; 3] = [-3_813_477.954, 3_554_276.552, 3_662_785.237];
 const EPS: f64 = 1e-8;
@@ -81,7 +81,7 @@ fn test_geometry_ecef2neu() {
     let neu_from_ecef = Neu::from_ecef(&ecef, tmat);
     println!("Neu from old: {neu:?}");
     println!("Neu from new: {neu_from_ecef:?}");
-    assert!(neu.precise(&neu_from_ecef, EPS), "Not equal!",);
+    assert!(neu.precise(&neu_from_ecef, EPS), "Not equal!");
 }
 #[test]
 fn test_geometry_neu2azel() {
@@ -105,3 +105,45 @@ fn test_geometry_neu2azel() {
         "Not equal!"
     );
 }
+#[test]
+fn test_geometry_dop_orthogonal() {
+    use constants::PI;
+    // Line-of-sight vectors along +-east, +-north and +-up: GtG is
+    // diag(2, 2, 2, 6), so the DOP values are known in closed form.
+    let azels = [
+        Azel {
+            az: PI / 2.0,
+            el: 0.0,
+        },
+        Azel {
+            az: -PI / 2.0,
+            el: 0.0,
+        },
+        Azel { az: 0.0, el: 0.0 },
+        Azel { az: PI, el: 0.0 },
+        Azel {
+            az: 0.0,
+            el: PI / 2.0,
+        },
+        Azel {
+            az: 0.0,
+            el: -PI / 2.0,
+        },
+    ];
+    let Ok(dop) = Dop::from_azel(&azels) else {
+        panic!("Orthogonal geometry must be solvable");
+    };
+    println!("Dop: {dop:?}");
+    assert!((dop.hdop - 1.0).abs() <= EPS);
+    assert!((dop.vdop - 0.5f64.sqrt()).abs() <= EPS);
+    assert!((dop.pdop - 1.5f64.sqrt()).abs() <= EPS);
+    assert!((dop.tdop - (1.0f64 / 6.0).sqrt()).abs() <= EPS);
+    assert!((dop.gdop - (1.5f64 + 1.0 / 6.0).sqrt()).abs() <= EPS);
+}
+#[test]
+fn test_geometry_dop_degenerate() {
+    let azels = [Azel { az: 0.1, el: 0.5 }; 3];
+    assert!(Dop::from_azel(&azels).is_err());
+    let azels = [Azel { az: 0.1, el: 0.5 }; 5];
+    assert!(Dop::from_azel(&azels).is_err());
+}
@@ -0,0 +1,17 @@
+//! BeiDou B1I open service signal generation.
+//!
+//! This module simulates the B1I signals of the BeiDou-2 and BeiDou-3
+//! satellites at 1561.098 MHz, 14.322 MHz below GPS L1, in the same composite
+//! output. The ranging codes are generated by shift registers, and the D1 and
+//! D2 navigation messages are built from the BeiDou ephemerides of RINEX 3 or
+//! RINEX 4 navigation files.
+
+/// B1I ranging codes and the Neumann-Hoffman code
+mod codes;
+/// D1 and D2 navigation message subframes
+mod dnav;
+/// BeiDou satellite system driven by the signal generator
+mod system;
+
+pub(crate) use codes::b1i_code;
+pub use system::BeidouSystem;
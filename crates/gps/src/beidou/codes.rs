@@ -0,0 +1,124 @@
+use constants::B1I_CODE_LEN;
+
+/// Number of BeiDou satellites with B1I codes of the phase assignment table.
+pub const B1I_CODES: usize = 63;
+
+/// Initial phase of both B1I shift registers, stages 1 to 11.
+const INITIAL_PHASE: u16 = 0b010_1010_1010;
+
+/// Feedback stages of the G1 register, 1 + x + x^7 + x^8 + x^9 + x^10 +
+/// x^11.
+const G1_TAPS: [usize; 6] = [1, 7, 8, 9, 10, 11];
+
+/// Feedback stages of the G2 register, 1 + x + x^2 + x^3 + x^4 + x^5 + x^8 +
+/// x^9 + x^11.
+const G2_TAPS: [usize; 8] = [1, 2, 3, 4, 5, 8, 9, 11];
+
+/// G2 output stages of the B1I codes of PRN 1 to 63, two stages for PRN 1
+/// to 37 and three for PRN 38 to 63.
+const G2_PHASES: [&[usize]; B1I_CODES] = [
+    &[1, 3],
+    &[1, 4],
+    &[1, 5],
+    &[1, 6],
+    &[1, 8],
+    &[1, 9],
+    &[1, 10],
+    &[1, 11],
+    &[2, 7],
+    &[3, 4],
+    &[3, 5],
+    &[3, 6],
+    &[3, 8],
+    &[3, 9],
+    &[3, 10],
+    &[3, 11],
+    &[4, 5],
+    &[4, 6],
+    &[4, 8],
+    &[4, 9],
+    &[4, 10],
+    &[4, 11],
+    &[5, 6],
+    &[5, 8],
+    &[5, 9],
+    &[5, 10],
+    &[5, 11],
+    &[6, 8],
+    &[6, 9],
+    &[6, 10],
+    &[6, 11],
+    &[8, 9],
+    &[8, 10],
+    &[8, 11],
+    &[9, 10],
+    &[9, 11],
+    &[10, 11],
+    &[1, 2, 7],
+    &[1, 3, 4],
+    &[1, 3, 6],
+    &[1, 3, 8],
+    &[1, 3, 10],
+    &[1, 3, 11],
+    &[1, 4, 5],
+    &[1, 4, 9],
+    &[1, 5, 6],
+    &[1, 5, 7],
+    &[1, 5, 8],
+    &[1, 5, 10],
+    &[1, 5, 11],
+    &[1, 6, 9],
+    &[1, 7, 8],
+    &[1, 7, 9],
+    &[1, 7, 11],
+    &[1, 8, 9],
+    &[1, 8, 11],
+    &[1, 9, 10],
+    &[1, 10, 11],
+    &[2, 3, 5],
+    &[2, 3, 9],
+    &[2, 5, 6],
+    &[2, 5, 7],
+    &[2, 5, 11],
+];
+
+/// Neumann-Hoffman code of the D1 navigation message (first bit first).
+const NH_CODE: &str = "00000100110101001110";
+
+/// Generates the B1I ranging code of a satellite.
+///
+/// The code is the sum of the G1 sequence and a phase of the G2 sequence,
+/// selected by the sum of two or three G2 stages,
+/// both 11-stage shift registers starting at 01010101010, truncated by one
+/// chip to 2046 chips. Chips of binary value 0 are transmitted as +1 and
+/// chips of value 1 as -1.
+///
+/// # Arguments
+/// * `prn` - PRN number of the satellite (1 to 63)
+///
+/// # Returns
+/// The 2046 chips (+1 or -1), or `None` for other PRNs
+pub fn b1i_code(prn: usize) -> Option<Vec<i8>> {
+    let phase = *G2_PHASES.get(prn.checked_sub(1)?)?;
+    let stage = |register: u16, stage: usize| register >> (stage - 1) & 1;
+    let feedback = |register: u16, taps: &[usize]| {
+        taps.iter().fold(0, |sum, &tap| sum ^ stage(register, tap))
+    };
+    let (mut g1, mut g2) = (INITIAL_PHASE, INITIAL_PHASE);
+    let mut chips = Vec::with_capacity(B1I_CODE_LEN);
+    for _ in 0..B1I_CODE_LEN {
+        let bit = stage(g1, 11) ^ feedback(g2, phase);
+        chips.push(if bit == 0 { 1 } else { -1 });
+        g1 = (g1 << 1 | feedback(g1, &G1_TAPS)) & 0x7ff;
+        g2 = (g2 << 1 | feedback(g2, &G2_TAPS)) & 0x7ff;
+    }
+    Some(chips)
+}
+
+/// Returns the Neumann-Hoffman code of the D1 message as +1 or -1 chips.
+pub fn nh_code() -> Vec<i8> {
+    NH_CODE
+        .bytes()
+        .map(|bit| if bit == b'0' { 1 } else { -1 })
+        .collect()
+}
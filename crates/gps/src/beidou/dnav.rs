@@ -0,0 +1,296 @@
+use constants::PI;
+
+use crate::{datetime::GpsTime, ephemeris::Ephemeris, signal::Bits};
+
+#[cfg(test)]
+mod tests;
+
+/// Duration of a D1 subframe in seconds (300 bits at 50 bps).
+pub const D1_SUBFRAME_SECONDS: f64 = 6.0;
+
+/// Duration of a D2 frame of five subframes in seconds (1500 bits at 500
+/// bps).
+pub const D2_FRAME_SECONDS: f64 = 3.0;
+
+/// Number of bits of a subframe.
+const SUBFRAME_BITS: usize = 300;
+
+/// Number of information bits of a subframe, parity bits excluded.
+const INFO_BITS: usize = 224;
+
+/// Preamble at the start of every subframe.
+const PREAMBLE: u64 = 0b111_0001_0010;
+
+/// Number of D1 subframe 4 and 5 pages.
+const D1_PAGES: u64 = 24;
+
+/// Number of D2 subframe 1 pages.
+const D2_PAGES: u64 = 10;
+
+/// Lengths of the fields of the D2 ephemeris stream carried by subframe 1
+/// pages 3 to 10.
+const D2_PAGE_BITS: [usize; 8] = [28, 64, 64, 64, 64, 66, 42, 41];
+
+/// Content of the D1 and D2 navigation messages of a BeiDou satellite on
+/// B1I.
+///
+/// The ephemeris is in BeiDou Time and carries the BeiDou values: AODE as
+/// IODE, AODC as IODC, the URA index, the autonomous satellite health flag
+/// `SatH1` as health, and the group delays TGD1 and TGD2 as TGD and second
+/// group delay.
+pub struct DnavMessage<'a> {
+    /// Broadcast ephemeris of the satellite
+    pub eph: &'a Ephemeris,
+    /// Klobuchar ionospheric parameters alpha0 to alpha3
+    pub alpha: [f64; 4],
+    /// Klobuchar ionospheric parameters beta0 to beta3
+    pub beta: [f64; 4],
+}
+
+impl DnavMessage<'_> {
+    /// Generates the D1 symbols of consecutive subframes.
+    ///
+    /// Subframes 1 to 3 carry the clock, ionospheric and ephemeris
+    /// parameters; the almanac pages of subframes 4 and 5 only carry their
+    /// page number. The seconds of week of a subframe are its start.
+    ///
+    /// # Arguments
+    /// * `start` - Start of the first subframe (BDT, a multiple of 6 seconds of
+    ///   the week)
+    /// * `subframes` - Number of subframes
+    ///
+    /// # Returns
+    /// 300 symbols per subframe, +1 for binary 0 and -1 for binary 1
+    pub fn d1_symbols(&self, start: &GpsTime, subframes: usize) -> Vec<i8> {
+        let mut symbols = Vec::with_capacity(subframes * SUBFRAME_BITS);
+        for subframe in 0..subframes {
+            let time = start.add_secs(subframe as f64 * D1_SUBFRAME_SECONDS);
+            let sow = time.sec.round() as u64;
+            let frame_id = (sow / 6 % 5 + 1) as u8;
+            symbols.extend(to_symbols(&self.d1_subframe(frame_id, &time)));
+        }
+        symbols
+    }
+
+    /// Generates the D2 symbols of consecutive frames.
+    ///
+    /// Subframe 1 cycles through its ten pages of basic navigation data,
+    /// one page per frame; subframes 2 to 5 only carry their header. All
+    /// subframes of a frame carry the seconds of week of the frame start.
+    ///
+    /// # Arguments
+    /// * `start` - Start of the first frame (BDT, a multiple of 3 seconds of
+    ///   the week)
+    /// * `frames` - Number of frames
+    ///
+    /// # Returns
+    /// 1500 symbols per frame, +1 for binary 0 and -1 for binary 1
+    pub fn d2_symbols(&self, start: &GpsTime, frames: usize) -> Vec<i8> {
+        let mut symbols = Vec::with_capacity(frames * 5 * SUBFRAME_BITS);
+        for frame in 0..frames {
+            let time = start.add_secs(frame as f64 * D2_FRAME_SECONDS);
+            let sow = time.sec.round() as u64;
+            for frame_id in 1..=5 {
+                let mut bits = header(frame_id, sow);
+                if frame_id == 1 {
+                    let page = sow / 3 % D2_PAGES + 1;
+                    bits.push(page, 4);
+                    self.d2_page(&mut bits, page, &time);
+                }
+                symbols.extend(to_symbols(&encode(bits)));
+            }
+        }
+        symbols
+    }
+
+    /// Builds the 300 bits of a D1 subframe.
+    ///
+    /// # Arguments
+    /// * `frame_id` - Subframe number (1 to 5)
+    /// * `time` - BDT at the start of the subframe
+    fn d1_subframe(&self, frame_id: u8, time: &GpsTime) -> Vec<u8> {
+        let eph = self.eph;
+        let sow = time.sec.round() as u64;
+        let mut bits = header(frame_id, sow);
+        match frame_id {
+            1 => {
+                self.clock_bits(&mut bits, time);
+                self.iono_bits(&mut bits);
+                bits.push_scaled(eph.af2, 66, 11);
+                bits.push_scaled(eph.af0, 33, 24);
+                bits.push_scaled(eph.af1, 50, 22);
+                bits.push(eph.iode as u64, 5);
+            }
+            2 => {
+                bits.push_scaled(eph.deltan / PI, 43, 16);
+                bits.push_scaled(eph.cuc, 31, 18);
+                bits.push_scaled(eph.m0 / PI, 31, 32);
+                bits.push((eph.ecc * 2f64.powi(33)).round() as u64, 32);
+                bits.push_scaled(eph.cus, 31, 18);
+                bits.push_scaled(eph.crc, 6, 18);
+                bits.push_scaled(eph.crs, 6, 18);
+                bits.push((eph.sqrta * 2f64.powi(19)).round() as u64, 32);
+                bits.push(toe(eph) >> 15, 2);
+            }
+            3 => {
+                bits.push(toe(eph), 15);
+                bits.push_scaled(eph.inc0 / PI, 31, 32);
+                bits.push_scaled(eph.cic, 31, 18);
+                bits.push_scaled(eph.omgdot / PI, 43, 24);
+                bits.push_scaled(eph.cis, 31, 18);
+                bits.push_scaled(eph.idot / PI, 43, 14);
+                bits.push_scaled(eph.omg0 / PI, 31, 32);
+                bits.push_scaled(eph.aop / PI, 31, 32);
+            }
+            _ => {
+                // Reserved bit and page number of the almanac pages
+                bits.zeros(1);
+                bits.push(sow / 30 % D1_PAGES + 1, 7);
+            }
+        }
+        encode(bits)
+    }
+
+    /// Appends the fields of a D2 subframe 1 page after its page number.
+    ///
+    /// Pages 1 and 2 carry the clock and ionospheric parameters; pages 3 to
+    /// 10 carry consecutive parts of the clock correction and ephemeris
+    /// fields.
+    ///
+    /// # Arguments
+    /// * `bits` - Subframe bits up to the page number
+    /// * `page` - Page number (1 to 10)
+    /// * `time` - BDT at the start of the frame
+    fn d2_page(&self, bits: &mut Bits, page: u64, time: &GpsTime) {
+        match page {
+            1 => self.clock_bits(bits, time),
+            2 => self.iono_bits(bits),
+            _ => {
+                let stream = self.d2_ephemeris_bits();
+                let part = page as usize - 3;
+                let start = D2_PAGE_BITS[..part].iter().sum::<usize>();
+                bits.0.extend_from_slice(
+                    &stream.0[start..start + D2_PAGE_BITS[part]],
+                );
+            }
+        }
+    }
+
+    /// Builds the clock correction and ephemeris fields of D2 pages 3 to 10
+    /// in their transmission order.
+    fn d2_ephemeris_bits(&self) -> Bits {
+        let eph = self.eph;
+        let mut bits = Bits::default();
+        bits.push_scaled(eph.af0, 33, 24);
+        bits.push_scaled(eph.af1, 50, 22);
+        bits.push_scaled(eph.af2, 66, 11);
+        bits.push(eph.iode as u64, 5);
+        bits.push_scaled(eph.deltan / PI, 43, 16);
+        bits.push_scaled(eph.cuc, 31, 18);
+        bits.push_scaled(eph.m0 / PI, 31, 32);
+        bits.push_scaled(eph.cus, 31, 18);
+        bits.push((eph.ecc * 2f64.powi(33)).round() as u64, 32);
+        bits.push((eph.sqrta * 2f64.powi(19)).round() as u64, 32);
+        bits.push_scaled(eph.cic, 31, 18);
+        bits.push_scaled(eph.cis, 31, 18);
+        bits.push(toe(eph), 17);
+        bits.push_scaled(eph.inc0 / PI, 31, 32);
+        bits.push_scaled(eph.crc, 6, 18);
+        bits.push_scaled(eph.crs, 6, 18);
+        bits.push_scaled(eph.omgdot / PI, 43, 24);
+        bits.push_scaled(eph.omg0 / PI, 31, 32);
+        bits.push_scaled(eph.aop / PI, 31, 32);
+        bits.push_scaled(eph.idot / PI, 43, 14);
+        bits
+    }
+
+    /// Appends the health, ages of data, accuracy, week, time of clock and
+    /// group delay fields.
+    fn clock_bits(&self, bits: &mut Bits, time: &GpsTime) {
+        let eph = self.eph;
+        bits.push(eph.svhlth as u64 & 1, 1);
+        bits.push(eph.iodc as u64, 5);
+        bits.push(eph.ura as u64, 4);
+        bits.push(time.week as u64, 13);
+        bits.push((eph.toc.sec / 8.0).round() as u64, 17);
+        // Group delays in units of 0.1 ns
+        bits.push((eph.tgd * 1e10).round() as i64 as u64, 10);
+        bits.push((eph.tgd2 * 1e10).round() as i64 as u64, 10);
+    }
+
+    /// Appends the Klobuchar ionospheric parameters.
+    fn iono_bits(&self, bits: &mut Bits) {
+        let [alpha0, alpha1, alpha2, alpha3] = self.alpha;
+        let [beta0, beta1, beta2, beta3] = self.beta;
+        bits.push_scaled(alpha0, 30, 8);
+        bits.push_scaled(alpha1, 27, 8);
+        bits.push_scaled(alpha2, 24, 8);
+        bits.push_scaled(alpha3, 24, 8);
+        bits.push_scaled(beta0, -11, 8);
+        bits.push_scaled(beta1, -14, 8);
+        bits.push_scaled(beta2, -16, 8);
+        bits.push_scaled(beta3, -16, 8);
+    }
+}
+
+/// Returns the time of ephemeris in units of 8 seconds.
+fn toe(eph: &Ephemeris) -> u64 {
+    (eph.toe.sec / 8.0).round() as u64
+}
+
+/// Starts the information bits of a subframe with the preamble, the
+/// reserved bits, the subframe number and the seconds of week.
+fn header(frame_id: u8, sow: u64) -> Bits {
+    let mut bits = Bits::default();
+    bits.push(PREAMBLE, 11);
+    bits.zeros(4);
+    bits.push(u64::from(frame_id), 3);
+    bits.push(sow, 20);
+    bits
+}
+
+/// Encodes the information bits of a subframe into its ten words.
+///
+/// The first 15 bits of word 1 are sent as is and its next 11 bits as one
+/// BCH(15,11) codeword. The 22 information bits of every other word form two
+/// codewords, whose bits are interleaved.
+///
+/// # Arguments
+/// * `bits` - Information bits, padded to 224 bits
+///
+/// # Returns
+/// The 300 bits of the subframe
+fn encode(mut bits: Bits) -> Vec<u8> {
+    bits.pad(INFO_BITS);
+    let info = bits.0;
+    let mut encoded = Vec::with_capacity(SUBFRAME_BITS);
+    encoded.extend_from_slice(&info[..15]);
+    encoded.extend(bch(&info[15..26]));
+    for word in info[26..].chunks(22) {
+        let first = bch(&word[..11]);
+        let second = bch(&word[11..]);
+        encoded.extend(first.into_iter().zip(second).flat_map(|(a, b)| [a, b]));
+    }
+    encoded
+}
+
+/// Encodes 11 information bits into a BCH(15,11) codeword with the
+/// generator polynomial x^4 + x + 1, parity bits last.
+fn bch(info: &[u8]) -> [u8; 15] {
+    let mut codeword = [0; 15];
+    let mut register = 0u8;
+    for (slot, &bit) in codeword.iter_mut().zip(info) {
+        *slot = bit;
+        let feedback = bit ^ (register >> 3 & 1);
+        register = (register << 1 & 0xf) ^ (feedback * 0b0011);
+    }
+    for (slot, bit) in codeword[11..].iter_mut().zip((0..4).rev()) {
+        *slot = register >> bit & 1;
+    }
+    codeword
+}
+
+/// Converts binary values into symbols, +1 for 0 and -1 for 1.
+fn to_symbols(bits: &[u8]) -> impl Iterator<Item = i8> + '_ {
+    bits.iter().map(|&bit| if bit == 0 { 1 } else { -1 })
+}
@@ -0,0 +1,157 @@
+use super::{
+    D1_SUBFRAME_SECONDS, D2_FRAME_SECONDS, DnavMessage, INFO_BITS, PREAMBLE,
+    SUBFRAME_BITS, bch,
+};
+use crate::{datetime::GpsTime, ephemeris::Ephemeris};
+
+/// Remainder of a codeword divided by the generator polynomial x^4 + x + 1.
+fn syndrome(codeword: &[u8]) -> u8 {
+    codeword.iter().fold(0u8, |remainder, &bit| {
+        let remainder = remainder << 1 | bit;
+        if remainder & 0x10 == 0 {
+            remainder
+        } else {
+            remainder ^ 0b1_0011
+        }
+    })
+}
+
+/// Reads an unsigned field of a bit sequence.
+fn field(bits: &[u8], start: usize, len: usize) -> u64 {
+    bits[start..start + len]
+        .iter()
+        .fold(0, |value, &bit| value << 1 | u64::from(bit))
+}
+
+/// Checks the BCH codewords of an encoded subframe and returns its 224
+/// information bits.
+///
+/// # Arguments
+/// * `symbols` - The 300 symbols of the subframe
+fn decode(symbols: &[i8]) -> Vec<u8> {
+    let bits = symbols
+        .iter()
+        .map(|&symbol| u8::from(symbol < 0))
+        .collect::<Vec<_>>();
+    assert_eq!(bits.len(), SUBFRAME_BITS);
+    let mut info = bits[..15].to_vec();
+    let mut codewords = vec![bits[15..30].to_vec()];
+    for word in bits[30..].chunks(30) {
+        // Bits of the two codewords alternate
+        codewords.push(word.iter().step_by(2).copied().collect());
+        codewords.push(word.iter().skip(1).step_by(2).copied().collect());
+    }
+    for codeword in codewords {
+        assert_eq!(syndrome(&codeword), 0);
+        info.extend_from_slice(&codeword[..11]);
+    }
+    assert_eq!(info.len(), INFO_BITS);
+    info
+}
+
+/// Returns an ephemeris with distinct clock and orbit fields.
+fn ephemeris() -> Ephemeris {
+    Ephemeris {
+        iode: 17,
+        iodc: 9,
+        ura: 2,
+        toc: GpsTime {
+            week: 950,
+            sec: 345_600.0,
+        },
+        toe: GpsTime {
+            week: 950,
+            sec: 345_600.0,
+        },
+        sqrta: 5282.6,
+        ecc: 0.004,
+        ..Ephemeris::default()
+    }
+}
+
+#[test]
+fn bch_codewords() {
+    for value in 0u16..1 << 11 {
+        let info = (0..11)
+            .rev()
+            .map(|bit| (value >> bit & 1) as u8)
+            .collect::<Vec<_>>();
+        let codeword = bch(&info);
+        assert_eq!(codeword[..11], info[..]);
+        assert_eq!(syndrome(&codeword), 0);
+    }
+    // The first information bit is x^14, x^14 mod (x^4 + x + 1) = x^3 + 1
+    let mut info = [0; 11];
+    info[0] = 1;
+    assert_eq!(bch(&info)[11..], [1, 0, 0, 1]);
+}
+
+#[test]
+fn d1_subframes() {
+    let eph = ephemeris();
+    let message = DnavMessage {
+        eph: &eph,
+        alpha: [0.0; 4],
+        beta: [0.0; 4],
+    };
+    // Subframe 4 of a frame starts 18 seconds into the frame
+    let start = GpsTime {
+        week: 950,
+        sec: 345_618.0,
+    };
+    let symbols = message.d1_symbols(&start, 5);
+    for (subframe, symbols) in symbols.chunks(SUBFRAME_BITS).enumerate() {
+        let info = decode(symbols);
+        let sow = 345_618 + subframe as u64 * D1_SUBFRAME_SECONDS as u64;
+        assert_eq!(field(&info, 0, 11), PREAMBLE);
+        assert_eq!(field(&info, 11, 4), 0);
+        assert_eq!(field(&info, 15, 3), [4, 5, 1, 2, 3][subframe]);
+        assert_eq!(field(&info, 18, 20), sow);
+    }
+    // Subframe 1: SatH1, AODC, URAI and week number
+    let subframe1 = decode(&symbols[2 * SUBFRAME_BITS..3 * SUBFRAME_BITS]);
+    assert_eq!(field(&subframe1, 38, 1), 0);
+    assert_eq!(field(&subframe1, 39, 5), 9);
+    assert_eq!(field(&subframe1, 44, 4), 2);
+    assert_eq!(field(&subframe1, 48, 13), 950);
+    assert_eq!(field(&subframe1, 61, 17), 345_600 / 8);
+    // Page numbers of the 24 page cycle, 345600 s being a multiple of the
+    // 720 s cycle
+    let subframe4 = decode(&symbols[..SUBFRAME_BITS]);
+    assert_eq!(field(&subframe4, 39, 7), 1);
+    let next = message.d1_symbols(&start.add_secs(30.0), 2);
+    assert_eq!(field(&decode(&next[SUBFRAME_BITS..]), 39, 7), 2);
+}
+
+#[test]
+fn d2_frames() {
+    let eph = ephemeris();
+    let message = DnavMessage {
+        eph: &eph,
+        alpha: [0.0; 4],
+        beta: [0.0; 4],
+    };
+    let start = GpsTime {
+        week: 950,
+        sec: 345_600.0,
+    };
+    let frames = 10;
+    let symbols = message.d2_symbols(&start, frames);
+    assert_eq!(symbols.len(), frames * 5 * SUBFRAME_BITS);
+    for (index, symbols) in symbols.chunks(SUBFRAME_BITS).enumerate() {
+        let info = decode(symbols);
+        let (frame, subframe) = (index / 5, index % 5);
+        let sow = 345_600 + frame as u64 * D2_FRAME_SECONDS as u64;
+        assert_eq!(field(&info, 0, 11), PREAMBLE);
+        assert_eq!(field(&info, 15, 3), subframe as u64 + 1);
+        assert_eq!(field(&info, 18, 20), sow);
+        if subframe == 0 {
+            // Page number of subframe 1, then SatH1 and AODC on page 1
+            let page = sow / 3 % 10 + 1;
+            assert_eq!(field(&info, 38, 4), page);
+            if page == 1 {
+                assert_eq!(field(&info, 43, 5), 9);
+            }
+        }
+    }
+}
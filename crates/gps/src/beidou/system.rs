@@ -0,0 +1,467 @@
+use std::ops::RangeInclusive;
+
+use constants::{
+    B1I_CODE_FREQ, B1I_CODE_LEN, B1I_FREQ, BDT_GPS_OFFSET, BDT_WEEK_OFFSET,
+    CARR_FREQ, GM_CGCS2000, OMEGA_EARTH_CGCS2000, R2D, SECONDS_IN_WEEK,
+};
+use rinex::navigation::{NavRecord, SatelliteSystem};
+
+use crate::{
+    Error,
+    beidou::{
+        codes::{b1i_code, nh_code},
+        dnav::{D1_SUBFRAME_SECONDS, D2_FRAME_SECONDS, DnavMessage},
+    },
+    datetime::{DateTime, GpsTime, TimeRange},
+    ephemeris::Ephemeris,
+    generator::{SatelliteSelection, ura_index},
+    propagation::{SatelliteState, compute_range},
+    signal::{
+        SignalChannel, SignalComponent, SignalSystem, Subcarrier,
+        SystemContext, SystemNavigation, allocate_channels, band_offset,
+        print_channel_status, signal_gain,
+    },
+    timeline::EphemerisTimeline,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Number of D1 subframes of the symbol buffer of a MEO or IGSO channel.
+///
+/// The buffer starts with the subframe being transmitted at a frame boundary
+/// and reaches past the next boundary, where it is regenerated.
+const D1_WINDOW_SUBFRAMES: usize = 7;
+
+/// Number of D2 frames of the symbol buffer of a GEO channel.
+const D2_WINDOW_FRAMES: usize = 12;
+
+/// Number of B1I chips per D1 symbol (20 ms at 50 bps).
+const D1_CHIPS_PER_SYMBOL: u64 = 20 * B1I_CODE_LEN as u64;
+
+/// Number of B1I chips per D2 symbol (2 ms at 500 bps).
+const D2_CHIPS_PER_SYMBOL: u64 = 2 * B1I_CODE_LEN as u64;
+
+/// Half the bandwidth of the B1I main lobe around the carrier (Hz).
+const HALF_BANDWIDTH: f64 = B1I_CODE_FREQ;
+
+/// Ratio of the ionospheric delays on B1I and GPS L1.
+const IONO_SCALE: f64 = (CARR_FREQ / B1I_FREQ) * (CARR_FREQ / B1I_FREQ);
+
+/// Inclination of the GEO orbit computation frame, -5 degrees (radians).
+const GEO_FRAME_TILT: f64 = -5.0 / R2D;
+
+/// Highest PRN number of the BeiDou satellites.
+const BEIDOU_SATELLITES: usize = 63;
+
+/// PRN numbers of the GEO satellites of BeiDou-2 and BeiDou-3.
+const GEO_PRNS: [RangeInclusive<usize>; 2] = [1..=5, 59..=63];
+
+/// BeiDou B1I open service signals.
+///
+/// Each visible BeiDou satellite, PRN 1 to 63, transmits its B1I code at
+/// 1561.098 MHz. The GEO satellites, PRN 1 to 5 and 59 to 63, carry the D2
+/// message at 500 bps; the MEO and IGSO satellites carry the D1 message at 50
+/// bps with the Neumann-Hoffman secondary code. BeiDou Time is simulated 14
+/// seconds behind GPS time, with the BDT week counted from January 1, 2006.
+///
+/// The satellite orbits and clocks follow the broadcast BeiDou ephemerides
+/// in the CGCS2000 frame. The ranges are delayed by the Klobuchar model of
+/// the GPS message, scaled to the B1I frequency; the BDS ionospheric
+/// parameters of the navigation files are only broadcast.
+pub struct BeidouSystem {
+    /// Broadcast ephemerides of all BeiDou satellites
+    timeline: EphemerisTimeline,
+    /// Ephemeris in use of each satellite (index PRN - 1)
+    ephemerides: Vec<Option<Ephemeris>>,
+    /// Neumann-Hoffman code of the D1 channels
+    nh_code: Vec<i8>,
+    /// Klobuchar alpha parameters of the navigation files, if given
+    alpha: Option<[f64; 4]>,
+    /// Klobuchar beta parameters of the navigation files, if given
+    beta: Option<[f64; 4]>,
+    /// Satellites allowed to occupy a channel
+    selection: SatelliteSelection,
+    /// B1I carrier frequency minus the output centre frequency, or `None` if
+    /// B1I lies outside the output band
+    carrier_offset: Option<f64>,
+    /// Channels of the visible satellites
+    channels: Vec<SignalChannel>,
+}
+
+impl BeidouSystem {
+    /// Creates the BeiDou system from the navigation data.
+    ///
+    /// # Arguments
+    /// * `navigation` - Navigation data of the systems other than GPS
+    /// * `selection` - Satellites allowed to occupy a channel, without PRN
+    ///   lists
+    ///
+    /// # Returns
+    /// * `Ok(BeidouSystem)` - The system ready to be initialized
+    /// * `Err(Error)` - If there is no usable BeiDou ephemeris
+    ///
+    /// # Errors
+    /// * `Error::NoSystemEphemeris` - If the navigation data holds no ephemeris
+    ///   of a satellite with PRN 1 to 63
+    /// * Returns an error if the time of clock of a record is out of range
+    pub fn new(
+        navigation: &SystemNavigation, selection: SatelliteSelection,
+    ) -> Result<Self, Error> {
+        let mut timeline = EphemerisTimeline::new(BEIDOU_SATELLITES);
+        for record in navigation.ephemerides(SatelliteSystem::Beidou) {
+            let prn = record.satellite.prn;
+            if prn <= BEIDOU_SATELLITES {
+                let mut eph = convert_beidou_record(record)?;
+                eph.svhlth =
+                    selection
+                        .health_mode
+                        .broadcast_health(prn, eph.svhlth, &[]);
+                timeline.insert(prn, eph);
+            }
+        }
+        if timeline.is_empty() {
+            return Err(Error::no_system_ephemeris("BeiDou"));
+        }
+        let klobuchar = |kind: &str| {
+            navigation
+                .ionospheric_correction(kind)
+                .map(|correction| correction.values)
+        };
+        Ok(Self {
+            timeline,
+            ephemerides: vec![None; BEIDOU_SATELLITES],
+            nh_code: nh_code(),
+            alpha: klobuchar("BDSA"),
+            beta: klobuchar("BDSB"),
+            selection,
+            carrier_offset: None,
+            channels: Vec::new(),
+        })
+    }
+
+    /// Takes the ephemeris due at a time into use for every satellite.
+    fn select_ephemerides(&mut self, time: &GpsTime) {
+        let time = bdt(time);
+        for (sv, current) in self.ephemerides.iter_mut().enumerate() {
+            if let Some(eph) = self.timeline.select(sv + 1, &time) {
+                *current = Some(eph.clone());
+            }
+        }
+    }
+
+    /// Releases the channels of satellites below the elevation mask and
+    /// allocates channels to the newly visible satellites.
+    fn allocate_channels(&mut self, context: &SystemContext) {
+        let Some(carrier_offset) = self.carrier_offset else {
+            return;
+        };
+        let nh_code = &self.nh_code;
+        allocate_channels(
+            &mut self.channels,
+            &self.ephemerides,
+            1,
+            &self.selection,
+            |prn, eph| {
+                b1i_range(prn, eph, context).azel.el * R2D
+                    > context.elevation_mask
+            },
+            |prn, eph| {
+                let code = b1i_code(prn)?;
+                let geo = is_geo(prn);
+                let component = SignalComponent {
+                    code,
+                    secondary: if geo { Vec::new() } else { nh_code.clone() },
+                    data: true,
+                    amplitude: 1.0,
+                    subcarrier: Subcarrier::Bpsk,
+                    quadrature: false,
+                };
+                let mut channel = SignalChannel::new(
+                    prn,
+                    vec![component],
+                    B1I_CODE_FREQ,
+                    B1I_FREQ,
+                    carrier_offset,
+                    if geo {
+                        D2_CHIPS_PER_SYMBOL
+                    } else {
+                        D1_CHIPS_PER_SYMBOL
+                    },
+                );
+                channel.start(b1i_range(prn, eph, context));
+                Some(channel)
+            },
+        );
+    }
+
+    /// Regenerates the D1 and D2 symbols of all channels for the frame
+    /// starting at a time.
+    fn generate_messages(&mut self, context: &SystemContext) {
+        // The buffers start with the subframe or frame transmitted a second
+        // before the current reception time
+        let time = bdt(context.time).add_secs(-1.0);
+        let alpha = self.alpha.unwrap_or([
+            context.ionoutc.alpha0,
+            context.ionoutc.alpha1,
+            context.ionoutc.alpha2,
+            context.ionoutc.alpha3,
+        ]);
+        let beta = self.beta.unwrap_or([
+            context.ionoutc.beta0,
+            context.ionoutc.beta1,
+            context.ionoutc.beta2,
+            context.ionoutc.beta3,
+        ]);
+        for channel in &mut self.channels {
+            let Some(eph) = &self.ephemerides[channel.prn - 1] else {
+                continue;
+            };
+            let message = DnavMessage { eph, alpha, beta };
+            let mut start = time.clone();
+            let symbols = if is_geo(channel.prn) {
+                start.sec =
+                    (start.sec / D2_FRAME_SECONDS).floor() * D2_FRAME_SECONDS;
+                message.d2_symbols(&start, D2_WINDOW_FRAMES)
+            } else {
+                start.sec = (start.sec / D1_SUBFRAME_SECONDS).floor()
+                    * D1_SUBFRAME_SECONDS;
+                message.d1_symbols(&start, D1_WINDOW_SUBFRAMES)
+            };
+            channel.set_symbols(&gps_time(&start), symbols);
+        }
+    }
+}
+
+impl SignalSystem for BeidouSystem {
+    fn name(&self) -> &'static str {
+        "BeiDou"
+    }
+
+    fn carrier_frequency(&self) -> f64 {
+        B1I_FREQ
+    }
+
+    fn initialize(&mut self, context: &SystemContext) {
+        self.carrier_offset = band_offset(context, B1I_FREQ, HALF_BANDWIDTH);
+        if self.carrier_offset.is_none() {
+            eprintln!(
+                "Warning: BeiDou B1I lies outside the output band; BeiDou \
+                 satellites are not simulated."
+            );
+        }
+        self.select_ephemerides(context.time);
+        self.allocate_channels(context);
+        self.generate_messages(context);
+    }
+
+    fn update_channels(&mut self, context: &SystemContext) {
+        for channel in &mut self.channels {
+            let Some(eph) = &self.ephemerides[channel.prn - 1] else {
+                continue;
+            };
+            let rho = b1i_range(channel.prn, eph, context);
+            channel.update(&rho, context.step, context.sampling_period);
+            channel.gain =
+                signal_gain(&rho, context.fixed_gain, context.antenna_pattern);
+        }
+    }
+
+    fn update_navigation(&mut self, context: &SystemContext) {
+        self.select_ephemerides(context.time);
+        self.allocate_channels(context);
+        self.generate_messages(context);
+    }
+
+    fn next_sample(&mut self, sampling_period: f64) -> (i32, i32) {
+        self.channels
+            .iter_mut()
+            .fold((0, 0), |(i_acc, q_acc), channel| {
+                let (ip, qp) = channel.next_sample(sampling_period);
+                (i_acc + ip, q_acc + qp)
+            })
+    }
+
+    fn print_status(&self) {
+        for channel in &self.channels {
+            print_channel_status(
+                &format!("C{:02}", channel.prn),
+                channel.azel(),
+                channel.rho0(),
+                None,
+            );
+        }
+    }
+}
+
+/// Orbit of a BeiDou satellite computed from its broadcast ephemeris.
+///
+/// MEO and IGSO orbits follow the GPS algorithm with the CGCS2000 Earth
+/// rotation rate. GEO orbits are computed in a frame tilted by -5 degrees
+/// and rotated into CGCS2000 by the Earth rotation since the time of
+/// ephemeris.
+struct BeidouOrbit<'a> {
+    /// Broadcast ephemeris in BeiDou Time
+    eph: &'a Ephemeris,
+    /// Whether the satellite is a GEO satellite
+    geo: bool,
+}
+
+impl SatelliteState for BeidouOrbit<'_> {
+    fn satellite_state(
+        &self, time: &GpsTime,
+    ) -> ([f64; 3], [f64; 3], [f64; 2]) {
+        let time = bdt(time);
+        let (pos, vel, clk) =
+            self.eph.compute_orbit_state(&time, OMEGA_EARTH_CGCS2000);
+        if !self.geo {
+            return (pos, vel, clk);
+        }
+        // Rotation about the x axis by the frame tilt
+        let (sx, cx) = GEO_FRAME_TILT.sin_cos();
+        let tilt =
+            |v: [f64; 3]| [v[0], cx * v[1] + sx * v[2], -sx * v[1] + cx * v[2]];
+        let (pos, vel) = (tilt(pos), tilt(vel));
+        // Rotation about the z axis by the Earth rotation since the time of
+        // ephemeris, and its rate
+        let tk = time.diff_secs(&self.eph.toe);
+        let (sz, cz) = (OMEGA_EARTH_CGCS2000 * tk).sin_cos();
+        let rotated =
+            |v: [f64; 3]| [cz * v[0] + sz * v[1], -sz * v[0] + cz * v[1], v[2]];
+        let rate = [
+            OMEGA_EARTH_CGCS2000 * (-sz * pos[0] + cz * pos[1]),
+            OMEGA_EARTH_CGCS2000 * (-cz * pos[0] - sz * pos[1]),
+            0.0,
+        ];
+        let vel = rotated(vel);
+        (
+            rotated(pos),
+            [vel[0] + rate[0], vel[1] + rate[1], vel[2]],
+            clk,
+        )
+    }
+}
+
+/// Computes the B1I range of a satellite at the current receiver time.
+///
+/// The ionospheric delay of the GPS Klobuchar model is scaled from L1 to the
+/// B1I frequency.
+fn b1i_range(
+    prn: usize, eph: &Ephemeris, context: &SystemContext,
+) -> TimeRange {
+    let orbit = BeidouOrbit {
+        eph,
+        geo: is_geo(prn),
+    };
+    let mut rho =
+        compute_range(&orbit, context.ionoutc, context.time, context.location);
+    let iono_delay = rho.iono_delay * IONO_SCALE;
+    rho.range += iono_delay - rho.iono_delay;
+    rho.iono_delay = iono_delay;
+    rho
+}
+
+/// Returns whether a PRN belongs to a GEO satellite.
+fn is_geo(prn: usize) -> bool {
+    GEO_PRNS.iter().any(|prns| prns.contains(&prn))
+}
+
+/// Converts a GPS time into BeiDou Time.
+fn bdt(time: &GpsTime) -> GpsTime {
+    let mut bdt = GpsTime {
+        week: time.week - BDT_WEEK_OFFSET,
+        sec: time.sec - BDT_GPS_OFFSET,
+    };
+    if bdt.sec < 0.0 {
+        bdt.sec += SECONDS_IN_WEEK;
+        bdt.week -= 1;
+    }
+    bdt
+}
+
+/// Converts a BeiDou Time into GPS time.
+fn gps_time(bdt: &GpsTime) -> GpsTime {
+    let mut time = GpsTime {
+        week: bdt.week + BDT_WEEK_OFFSET,
+        sec: bdt.sec + BDT_GPS_OFFSET,
+    };
+    if time.sec >= SECONDS_IN_WEEK {
+        time.sec -= SECONDS_IN_WEEK;
+        time.week += 1;
+    }
+    time
+}
+
+/// Converts a BeiDou RINEX ephemeris record into the ephemeris used by the
+/// simulation, with its derived orbit constants.
+///
+/// The BeiDou parameters are kept in the GPS fields: AODE as IODE, AODC as
+/// IODC, `SatH1` as health, TGD1 as TGD and TGD2 as second group delay. The
+/// times of clock and ephemeris stay in BeiDou Time, with BDT weeks. The
+/// right ascension rate of GEO satellites is not corrected for the Earth
+/// rotation, which `BeidouOrbit` applies after the frame tilt.
+///
+/// # Arguments
+/// * `record` - BeiDou ephemeris record
+///
+/// # Returns
+/// * `Ok(Ephemeris)` - The converted ephemeris
+/// * `Err(Error)` - If the time of clock cannot be converted
+///
+/// # Errors
+/// * Returns an error if the time of clock is out of range
+pub fn convert_beidou_record(record: &NavRecord) -> Result<Ephemeris, Error> {
+    let datetime = DateTime::from(record.epoch.in_tz("UTC")?);
+    let mut toc = GpsTime::from(&datetime);
+    toc.week -= BDT_WEEK_OFFSET;
+    let [aode, crs, deltan, m0] = record.orbit(1);
+    let [cuc, ecc, cus, sqrta] = record.orbit(2);
+    let [toe, cic, omg0, cis] = record.orbit(3);
+    let [inc0, crc, aop, omgdot] = record.orbit(4);
+    let [idot, _, week, _] = record.orbit(5);
+    let [accuracy, sath1, tgd1, tgd2] = record.orbit(6);
+    let [_, aodc, _, _] = record.orbit(7);
+    let mut eph = Ephemeris {
+        vflg: true,
+        t: datetime,
+        toc,
+        toe: GpsTime {
+            week: week as i32,
+            sec: toe,
+        },
+        iodc: aodc as i32,
+        iode: aode as i32,
+        deltan,
+        cuc,
+        cus,
+        cic,
+        cis,
+        crc,
+        crs,
+        ecc,
+        sqrta,
+        m0,
+        omg0,
+        inc0,
+        aop,
+        omgdot,
+        idot,
+        af0: record.values[0],
+        af1: record.values[1],
+        af2: record.values[2],
+        tgd: tgd1,
+        tgd2,
+        svhlth: sath1 as i32,
+        ura: ura_index(accuracy),
+        ..Ephemeris::default()
+    };
+    eph.A = eph.sqrta * eph.sqrta;
+    eph.n = (GM_CGCS2000 / (eph.A * eph.A * eph.A)).sqrt() + eph.deltan;
+    eph.sq1e2 = (1.0 - eph.ecc * eph.ecc).sqrt();
+    eph.omgkdot = if is_geo(record.satellite.prn) {
+        eph.omgdot
+    } else {
+        eph.omgdot - OMEGA_EARTH_CGCS2000
+    };
+    Ok(eph)
+}
@@ -0,0 +1,192 @@
+use constants::{
+    B1I_CODE_FREQ, B1I_CODE_LEN, B1I_FREQ, CARR_FREQ, GM_CGCS2000,
+    OMEGA_EARTH_CGCS2000, R2D,
+};
+use geometry::{Ecef, Location};
+use test_case::test_case;
+
+use super::{
+    BEIDOU_SATELLITES, BeidouOrbit, BeidouSystem, D1_CHIPS_PER_SYMBOL, bdt,
+    gps_time, is_geo,
+};
+use crate::{
+    beidou::codes::{b1i_code, nh_code},
+    datetime::GpsTime,
+    ephemeris::Ephemeris,
+    generator::SatelliteSelection,
+    ionoutc::IonoUtc,
+    propagation::SatelliteState,
+    signal::{SignalChannel, SignalComponent, Subcarrier, SystemContext},
+    timeline::EphemerisTimeline,
+};
+
+#[test_case(1, true)]
+#[test_case(5, true)]
+#[test_case(6, false)]
+#[test_case(37, false)]
+#[test_case(58, false)]
+#[test_case(59, true)]
+#[test_case(63, true)]
+fn geo_satellites(prn: usize, geo: bool) {
+    assert_eq!(is_geo(prn), geo);
+}
+
+#[test]
+fn nh_code_of_the_icd() {
+    let value = nh_code()
+        .iter()
+        .fold(0u32, |value, &chip| value << 1 | u32::from(chip < 0));
+    assert_eq!(value, 0x04d4e);
+}
+
+#[test]
+fn nh_overlay_follows_symbols() -> Result<(), crate::Error> {
+    let code = b1i_code(6).ok_or_else(|| crate::Error::msg("No B1I code"))?;
+    let component = SignalComponent {
+        code: code.clone(),
+        secondary: nh_code(),
+        data: true,
+        amplitude: 1.0,
+        subcarrier: Subcarrier::Bpsk,
+        quadrature: false,
+    };
+    let mut channel = SignalChannel::new(
+        6,
+        vec![component],
+        B1I_CODE_FREQ,
+        B1I_CODE_FREQ,
+        0.0,
+        D1_CHIPS_PER_SYMBOL,
+    );
+    channel.gain = 1;
+    // Symbols start at a D1 subframe boundary of BDT
+    let start = GpsTime {
+        week: 950,
+        sec: 345_606.0,
+    };
+    let symbols = [1, -1, -1, 1];
+    channel.set_symbols(&start, symbols.to_vec());
+    let nh = nh_code();
+    let sampling_period = 1.0 / B1I_CODE_FREQ;
+    for chip in 0..symbols.len() * D1_CHIPS_PER_SYMBOL as usize {
+        let (i, _) = channel.next_sample(sampling_period);
+        let period = chip / B1I_CODE_LEN;
+        // One NH code period per symbol, starting with the symbol
+        let expected = i32::from(code[chip % B1I_CODE_LEN])
+            * i32::from(nh[period % nh.len()])
+            * i32::from(symbols[period / nh.len()]);
+        assert_eq!(i.signum(), expected, "chip {chip}");
+    }
+    Ok(())
+}
+
+#[test]
+fn time_conversion() {
+    let time = GpsTime {
+        week: 2306,
+        sec: 5.0,
+    };
+    let bdt_time = bdt(&time);
+    assert_eq!(bdt_time.week, 2306 - 1356 - 1);
+    assert!((bdt_time.sec - 604_791.0).abs() < 1e-9);
+    let back = gps_time(&bdt_time);
+    assert_eq!(back.week, time.week);
+    assert!((back.sec - time.sec).abs() < 1e-9);
+}
+
+/// Returns the ephemeris of a geostationary satellite above a longitude in
+/// the tilted GEO frame: a circular orbit at the rotation rate of the Earth
+/// whose inclination of -5 degrees undoes the frame tilt.
+fn geostationary(longitude: f64) -> Ephemeris {
+    let toe = GpsTime {
+        week: 950,
+        sec: 345_600.0,
+    };
+    let radius = (GM_CGCS2000 / OMEGA_EARTH_CGCS2000.powi(2)).cbrt();
+    Ephemeris {
+        vflg: true,
+        toc: toe.clone(),
+        sqrta: radius.sqrt(),
+        A: radius,
+        n: OMEGA_EARTH_CGCS2000,
+        sq1e2: 1.0,
+        inc0: -5.0 / R2D,
+        // The node of the GEO frame at the weekly epoch
+        omg0: OMEGA_EARTH_CGCS2000 * toe.sec,
+        m0: longitude / R2D,
+        toe,
+        ..Ephemeris::default()
+    }
+}
+
+#[test_case(0.0)]
+#[test_case(3600.0)]
+#[test_case(-7200.0)]
+#[test_case(43_200.0)]
+fn geo_orbit_rotation(elapsed: f64) {
+    let eph = geostationary(110.5);
+    let orbit = BeidouOrbit {
+        eph: &eph,
+        geo: true,
+    };
+    let time = gps_time(&eph.toe.add_secs(elapsed));
+    let (pos, vel, _) = orbit.satellite_state(&time);
+    // Fixed above the equator at the longitude of the ephemeris
+    assert!(pos[2].abs() < 1e-3, "{pos:?}");
+    let longitude = pos[1].atan2(pos[0]) * R2D;
+    assert!((longitude - 110.5).abs() < 1e-9, "{longitude}");
+    let radius = pos.iter().map(|x| x * x).sum::<f64>().sqrt();
+    assert!((radius - eph.A).abs() < 1e-3);
+    assert!(vel.iter().all(|v| v.abs() < 1e-6), "{vel:?}");
+    // The same orbit computed as a MEO orbit is not geostationary
+    let meo = BeidouOrbit {
+        eph: &eph,
+        geo: false,
+    };
+    let (pos, ..) = meo.satellite_state(&time);
+    assert!(pos[2].abs() > 1e3);
+}
+
+#[test]
+fn channels_of_beidou3_satellites() {
+    // Satellites above the receiver: PRN 38 to 63 have three-tap codes, and
+    // the GEO satellites PRN 59 and 63 carry D2 like PRN 1
+    let prns = [1, 37, 38, 58, 59, 63];
+    let eph = geostationary(110.5);
+    let mut ephemerides = vec![None; BEIDOU_SATELLITES];
+    for prn in prns {
+        ephemerides[prn - 1] = Some(eph.clone());
+    }
+    let mut system = BeidouSystem {
+        timeline: EphemerisTimeline::new(BEIDOU_SATELLITES),
+        ephemerides,
+        nh_code: nh_code(),
+        alpha: None,
+        beta: None,
+        selection: SatelliteSelection::default(),
+        carrier_offset: Some(B1I_FREQ - CARR_FREQ),
+        channels: Vec::new(),
+    };
+    let context = SystemContext {
+        time: &gps_time(&eph.toe),
+        location: &Ecef::from(&Location::new(0.0, 110.5, 0.0).to_rad()),
+        ionoutc: &IonoUtc::default(),
+        elevation_mask: 10.0,
+        step: 0.1,
+        sampling_period: 1.0 / 4e6,
+        sample_frequency: 4e6,
+        center_frequency: CARR_FREQ,
+        intermediate_frequency: 0.0,
+        real_output: false,
+        fixed_gain: None,
+        antenna_pattern: &[0.0; 37],
+    };
+    system.allocate_channels(&context);
+    system.generate_messages(&context);
+    let allocated = system
+        .channels
+        .iter()
+        .map(|channel| channel.prn)
+        .collect::<Vec<_>>();
+    assert_eq!(allocated, prns);
+}
@@ -1,8 +1,7 @@
 use constants::{
     CA_SEQ_LEN, CA_SEQ_LEN_FLOAT, CARR_TO_CODE, CODE_FREQ, LAMBDA_L1_INV,
-    N_DWRD, N_DWRD_SBF, N_SBF, PI, POW2_M5, POW2_M19, POW2_M24, POW2_M27,
-    POW2_M29, POW2_M30, POW2_M31, POW2_M33, POW2_M43, POW2_M50, POW2_M55,
-    SPEED_OF_LIGHT_INV,
+    N_DWRD, N_DWRD_SBF, N_SBF, PI, POW2_M5, POW2_M19, POW2_M29, POW2_M31,
+    POW2_M33, POW2_M43, POW2_M55, SPEED_OF_LIGHT_INV,
 };
 use geometry::{Azel, Ecef};
 
@@ -10,9 +9,18 @@ use crate::{
     datetime::{GpsTime, TimeRange},
     ephemeris::Ephemeris,
     ionoutc::IonoUtc,
-    propagation::compute_range,
+    propagation::{SatelliteState, compute_range},
+    superframe::{SuperframePages, iono_utc_page, reserved_page},
     table::*,
 };
+
+#[cfg(test)]
+mod tests;
+
+/// Time over which the range offset of an ephemeris switch fades out, in
+/// seconds.
+pub const EPHEMERIS_FADE_TIME: f64 = 60.0;
+
 /// Represents a single GPS satellite channel being tracked by the receiver.
 ///
 /// This structure maintains the complete state of a satellite signal channel,
@@ -31,12 +39,14 @@ use crate::{
 /// accurately model the changing satellite-receiver geometry and signal
 /// characteristics.
 pub struct Channel {
-    /// Satellite PRN (Pseudorandom Noise) number (1-32)
+    /// Satellite PRN (Pseudorandom Noise) number (1-32, or 193-202 for QZSS)
     pub prn: usize,
     /// C/A code sequence chips for this satellite (1023 chips)
     ca_sequence: [i32; CA_SEQ_LEN],
     /// Current carrier frequency with Doppler shift (Hz)
     carrier_frequency: f64,
+    /// L1 carrier frequency minus the output centre frequency (Hz)
+    carrier_offset: f64,
     /// Current code frequency with Doppler effect (Hz)
     code_frequency: f64,
     /// Current carrier phase accumulator (fixed-point representation)
@@ -65,6 +75,18 @@ pub struct Channel {
     azel: Azel,
     /// Previous pseudorange measurement and associated data
     rho0: TimeRange,
+    /// Carrier phase of the generated signal in cycles, at the end of the
+    /// last update step
+    carrier_cycles: f64,
+    /// Ideal carrier phase in cycles derived from the pseudorange
+    reference_cycles: f64,
+    /// Whether the carrier phase step is steered to the ideal phase
+    phase_locked: bool,
+    /// Pseudorange offset at the last ephemeris switch, in meters, that
+    /// keeps the range continuous while it fades out
+    ephemeris_offset: f64,
+    /// Receiver time of the last ephemeris switch
+    ephemeris_switch_time: GpsTime,
 }
 impl Default for Channel {
     fn default() -> Self {
@@ -72,6 +94,7 @@ impl Default for Channel {
             prn: 0,
             ca_sequence: [0; CA_SEQ_LEN],
             carrier_frequency: 0.0,
+            carrier_offset: 0.0,
             code_frequency: 0.0,
             carrier_phase: 0,
             carrier_phase_step: 0,
@@ -86,6 +109,11 @@ impl Default for Channel {
             current_code_chip: 0,
             azel: Azel::default(),
             rho0: TimeRange::default(),
+            carrier_cycles: 0.0,
+            reference_cycles: 0.0,
+            phase_locked: false,
+            ephemeris_offset: 0.0,
+            ephemeris_switch_time: GpsTime::default(),
         }
     }
 }
@@ -100,6 +128,54 @@ impl Channel {
         &self.azel
     }
 
+    /// Returns the current carrier frequency (Doppler shift) in Hz.
+    pub fn carrier_frequency(&self) -> f64 {
+        self.carrier_frequency
+    }
+
+    /// Returns the current code phase in chips.
+    pub fn code_phase(&self) -> f64 {
+        self.code_phase
+    }
+
+    /// Returns the carrier phase of the generated signal in cycles at the
+    /// last update step.
+    pub fn carrier_cycles(&self) -> f64 {
+        self.carrier_cycles
+    }
+
+    /// Sets the frequency of the L1 carrier in the output.
+    ///
+    /// The generated carrier runs at the Doppler shift plus this offset,
+    /// while the carrier phase reported in observations keeps following the
+    /// Doppler shift alone.
+    ///
+    /// # Arguments
+    /// * `offset` - L1 carrier frequency minus the centre frequency, plus the
+    ///   intermediate frequency, in Hz
+    pub fn set_carrier_offset(&mut self, offset: f64) {
+        self.carrier_offset = offset;
+    }
+
+    /// Aligns the carrier phase with the pseudorange of the satellite.
+    ///
+    /// The initial phase is set to the fractional part of `-range / λ`, and
+    /// from then on the carrier phase step of every update is chosen so that
+    /// the generated phase follows the pseudorange instead of accumulating
+    /// rounding errors. The carrier phases of all channels are then
+    /// consistent up to integer ambiguities, which RTK ambiguity resolution
+    /// against a virtual reference station relies on.
+    ///
+    /// Must be called right after `update_for_satellite`.
+    pub fn align_carrier_phase(&mut self) {
+        let phase = -self.rho0.range * LAMBDA_L1_INV;
+        let phase_ini = phase - phase.floor();
+        self.carrier_phase = (512.0 * 65536.0 * phase_ini) as u32;
+        self.carrier_cycles = phase_ini;
+        self.reference_cycles = phase_ini;
+        self.phase_locked = true;
+    }
+
     /// Initializes or updates the channel state for a specific satellite.
     ///
     /// This involves setting the PRN, generating C/A code and navigation
@@ -109,14 +185,18 @@ impl Channel {
     /// # Arguments
     /// * `prn` - The PRN number of the satellite.
     /// * `eph` - The ephemeris data for the satellite.
+    /// * `state` - Source of the satellite state for the pseudorange.
     /// * `ionoutc` - Ionospheric and UTC parameters.
     /// * `receiver_gps_time` - The current GPS time at the receiver.
     /// * `xyz` - The receiver's position in ECEF coordinates.
     /// * `azel` - The satellite's azimuth and elevation as seen from the
     ///   receiver.
+    /// * `pages` - Subframe 4 and 5 page schedule, if any
+    #[allow(clippy::too_many_arguments)]
     pub fn update_for_satellite(
-        &mut self, prn: usize, eph: &Ephemeris, ionoutc: &IonoUtc,
-        receiver_gps_time: &GpsTime, xyz: &Ecef, azel: Azel,
+        &mut self, prn: usize, eph: &Ephemeris, state: &impl SatelliteState,
+        ionoutc: &IonoUtc, receiver_gps_time: &GpsTime, xyz: &Ecef, azel: Azel,
+        pages: Option<&SuperframePages>,
     ) {
         // Initialize channel
         self.prn = prn;
@@ -128,9 +208,9 @@ impl Channel {
         // Generate navigation message
         // Populate the first full navigation message cycle (30 seconds / 5
         // subframes)
-        self.generate_nav_msg(receiver_gps_time, true);
+        self.generate_nav_msg(receiver_gps_time, true, pages);
         // Initialize pseudorange
-        let rho = compute_range(eph, ionoutc, receiver_gps_time, xyz);
+        let rho = compute_range(state, ionoutc, receiver_gps_time, xyz);
         self.rho0 = rho;
         // Initialize carrier phase
         // r_xyz = rho.range;
@@ -150,6 +230,57 @@ impl Channel {
         // #else
         phase_ini -= phase_ini.floor();
         self.carrier_phase = (512.0 * 65536.0 * phase_ini) as u32;
+        self.carrier_cycles = phase_ini;
+        self.reference_cycles = phase_ini;
+        self.phase_locked = false;
+        self.carrier_frequency = 0.0;
+        self.ephemeris_offset = 0.0;
+    }
+
+    /// Switches the channel to a new ephemeris of its satellite.
+    ///
+    /// The navigation subframes are regenerated from the new ephemeris, so
+    /// that the next navigation message frame carries the new IODE and IODC
+    /// in subframes 1 to 3 together. When the range is computed from the
+    /// ephemeris, the new range differs from the old one by the difference of
+    /// the two orbit fits; this difference is added back to the pseudorange
+    /// and faded out over `EPHEMERIS_FADE_TIME` so that the generated signal
+    /// has no range jump.
+    ///
+    /// # Arguments
+    /// * `eph` - The new ephemeris
+    /// * `previous` - Source of the satellite state used until now
+    /// * `state` - Source of the satellite state from now on
+    /// * `ionoutc` - Ionospheric and UTC parameters
+    /// * `receiver_gps_time` - The current GPS time at the receiver
+    /// * `xyz` - The receiver's position in ECEF coordinates
+    pub fn switch_ephemeris(
+        &mut self, eph: &Ephemeris, previous: &impl SatelliteState,
+        state: &impl SatelliteState, ionoutc: &IonoUtc,
+        receiver_gps_time: &GpsTime, xyz: &Ecef,
+    ) {
+        self.generate_navigation_subframes(eph, ionoutc);
+        let old_range =
+            compute_range(previous, ionoutc, receiver_gps_time, xyz);
+        let new_range = compute_range(state, ionoutc, receiver_gps_time, xyz);
+        self.ephemeris_offset = self.ephemeris_offset(receiver_gps_time)
+            + old_range.range
+            - new_range.range;
+        self.ephemeris_switch_time = receiver_gps_time.clone();
+    }
+
+    /// Returns the remaining pseudorange offset of the last ephemeris switch.
+    ///
+    /// # Arguments
+    /// * `receiver_gps_time` - The current GPS time at the receiver
+    ///
+    /// # Returns
+    /// The offset in meters, fading linearly to zero over
+    /// `EPHEMERIS_FADE_TIME`
+    pub fn ephemeris_offset(&self, receiver_gps_time: &GpsTime) -> f64 {
+        let elapsed = receiver_gps_time.diff_secs(&self.ephemeris_switch_time);
+        let weight = (1.0 - elapsed / EPHEMERIS_FADE_TIME).clamp(0.0, 1.0);
+        self.ephemeris_offset * weight
     }
 
     /// Updates the channel's state based on new pseudorange information and
@@ -170,13 +301,31 @@ impl Channel {
         // Update azimuth/elevation information
         // Update code phase and data bit counters
         self.azel = rho1.azel;
+        self.reference_cycles -= (rho1.range - self.rho0.range) * LAMBDA_L1_INV;
         // Calculate code phase (C/A code offset)
         self.compute_code_phase(rho1, dt);
-        self.carrier_phase_step = (512.0
-            * 65536.0
-            * self.carrier_frequency
-            * sampling_period)
-            .round() as i32;
+        let samples = (dt / sampling_period).round();
+        // Cycles of the carrier offset over the interval
+        let offset_cycles = self.carrier_offset * samples * sampling_period;
+        self.carrier_phase_step = if self.phase_locked {
+            // Steer the phase to the ideal value at the end of the interval
+            ((self.reference_cycles - self.carrier_cycles + offset_cycles)
+                * 512.0
+                * 65536.0
+                / samples)
+                .round() as i32
+        } else {
+            (512.0
+                * 65536.0
+                * (self.carrier_frequency + self.carrier_offset)
+                * sampling_period)
+                .round() as i32
+        };
+        // Phase of the generated signal at the end of the interval, without
+        // the carrier offset
+        self.carrier_cycles += f64::from(self.carrier_phase_step) * samples
+            / (512.0 * 65536.0)
+            - offset_cycles;
     }
 
     ///  \brief Compute the code phase for a given channel (satellite)
@@ -242,24 +391,28 @@ impl Channel {
     ///
     /// The generated sequence is stored in the channel's `ca_sequence` field
     /// and is used for spreading the navigation data bits during signal
-    /// generation.
+    /// generation. PRN 1 to 63 get the codes of their G2 delays and PRN 64 to
+    /// 210, including the SBAS and QZSS PRNs, the codes of their G2 initial
+    /// states; the sequence of other PRNs is left unchanged.
     #[inline]
     pub fn codegen(&mut self) {
-        let delay: [usize; 32] = [
-            5, 6, 7, 8, 17, 18, 139, 140, 141, 251, 252, 254, 255, 256, 257,
-            258, 469, 470, 471, 472, 473, 474, 509, 512, 513, 514, 515, 516,
-            859, 860, 861, 862,
-        ];
+        let (mut r2, delay) = match g2_setting(self.prn) {
+            Some(G2Setting::Delay(delay)) => ([-1; N_DWRD_SBF], delay),
+            Some(G2Setting::InitialState(state)) => {
+                // Binary 1 is held as -1, stage 1 in the first element
+                let mut r2 = [1; N_DWRD_SBF];
+                for (stage, r) in r2.iter_mut().enumerate() {
+                    if state >> stage & 1 == 1 {
+                        *r = -1;
+                    }
+                }
+                (r2, 0)
+            }
+            None => return,
+        };
         let mut g1: [i32; CA_SEQ_LEN] = [0; CA_SEQ_LEN];
         let mut g2: [i32; CA_SEQ_LEN] = [0; CA_SEQ_LEN];
         let mut r1: [i32; N_DWRD_SBF] = [-1; N_DWRD_SBF];
-        let mut r2: [i32; N_DWRD_SBF] = [-1; N_DWRD_SBF];
-        // if !(self.prn <= 32 || self.prn >= 1) {
-        //     return;
-        // }
-        if !(1..=32).contains(&self.prn) {
-            return;
-        }
         for i in 0..CA_SEQ_LEN {
             g1[i] = r1[9];
             g2[i] = r2[9];
@@ -273,10 +426,11 @@ impl Channel {
             r2[0] = c2;
         }
 
-        let mut j = CA_SEQ_LEN - delay[self.prn - 1];
-        for (ica, ig1) in self.ca_sequence.iter_mut().zip(g1) {
+        let start = CA_SEQ_LEN - delay;
+        for (j, (ica, ig1)) in
+            (start..).zip(self.ca_sequence.iter_mut().zip(g1))
+        {
             *ica = (1 - ig1 * g2[j % CA_SEQ_LEN]) / 2;
-            j += 1;
         }
     }
 
@@ -291,7 +445,11 @@ impl Channel {
     /// * `time` - The current GPS time used to calculate TOW and WN.
     /// * `init` - Flag indicating if this is the initial generation (handles
     ///   subframe 5 differently).]
-    pub fn generate_nav_msg(&mut self, time: &GpsTime, init: bool) {
+    /// * `pages` - Subframe 4 and 5 page schedule, or `None` to repeat the
+    ///   pages of `generate_navigation_subframes` in every frame
+    pub fn generate_nav_msg(
+        &mut self, time: &GpsTime, init: bool, pages: Option<&SuperframePages>,
+    ) {
         let mut time_init = GpsTime::default();
         let mut sbfwrd: u32;
         let mut prevwrd: u32 = 0;
@@ -302,13 +460,19 @@ impl Channel {
 
         let wn = (time_init.week % 1024) as u32;
         let mut tow = (time_init.sec as u32).wrapping_div(6);
+        // Subframe 5 of the previous frame, sent first on initialization
+        let mut previous_subframe5 = self.subframes[4];
+        if let Some(pages) = pages {
+            previous_subframe5 = pages.frame(&time_init.add_secs(-30.0))[1];
+            [self.subframes[3], self.subframes[4]] = *pages.frame(&time_init);
+        }
         self.nav_message_start_time = time_init; // Data bit reference time
 
         if init {
             // Initialize subframe 5
             prevwrd = 0;
-            for iwrd in 0..N_DWRD_SBF {
-                sbfwrd = self.subframes[4][iwrd];
+            for (iwrd, &word) in previous_subframe5.iter().enumerate() {
+                sbfwrd = word;
                 // Add TOW-count message into HOW
                 if iwrd == 1 {
                     sbfwrd |= (tow & 0x1ffff) << 13;
@@ -600,12 +764,15 @@ impl Channel {
     ///
     /// # Implementation Details
     /// - Subframes 1-3 contain the fundamental ephemeris and clock correction
-    ///   data needed for precise positioning
+    ///   data needed for precise positioning, with the URA index, L2 P data
+    ///   flag, fit interval flag and age of data offset of the ephemeris
     /// - Subframe 4 page 18 includes:
     ///   - Ionospheric α/β coefficients (Klobuchar model parameters)
     ///   - UTC parameters (`A0`, `A1`, `ΔtLS`)
     ///   - Leap second transition parameters
     /// - Subframe 5 page 25 is reserved (zero-filled in this implementation)
+    /// - Both pages are replaced frame by frame when the navigation message
+    ///   follows a `SuperframePages` schedule
     /// - All value conversions follow GPS-ICD-defined scaling factors and
     ///   bit-field layouts
     /// - The constructed subframes are stored in the channel's `subframes`
@@ -614,14 +781,10 @@ impl Channel {
     pub fn generate_navigation_subframes(
         &mut self, eph: &Ephemeris, ionoutc: &IonoUtc,
     ) {
-        let ura = 0;
+        let ura = eph.ura as u32;
         let data_id = 1;
         let sbf4_page25_sv_id = 63;
         let sbf5_page25_sv_id = 51;
-        let wnlsf;
-        let dtlsf;
-        let dn;
-        let sbf4_page18_sv_id = 56;
 
         // FIXED: This has to be the "transmission" week number, not for the
         // ephemeris reference time wn = (unsigned long)(self.toe.week%1024);
@@ -650,39 +813,15 @@ impl Channel {
         let af2 = (eph.af2 / POW2_M55) as i32;
         let tgd = (eph.tgd / POW2_M31) as i32;
         let svhlth = eph.svhlth as u32 as i32;
+        let l2p_flag = eph.l2p_flag as u32;
+        let fit_flag = u32::from(eph.fit_interval > 4.0);
+        // The age of data offset is broadcast in units of 900 seconds
+        let aodo = ((eph.aodo / 900.0) as u32).min(0x1f);
 
         #[allow(non_snake_case)]
         let codeL2 = eph.codeL2 as u32 as i32;
         let wna = (eph.toe.week % 256) as u32;
         let toa = (eph.toe.sec / 4096.0) as u32;
-        let alpha0 = (ionoutc.alpha0 / POW2_M30).round() as i32;
-        let alpha1 = (ionoutc.alpha1 / POW2_M27).round() as i32;
-        let alpha2 = (ionoutc.alpha2 / POW2_M24).round() as i32;
-        let alpha3 = (ionoutc.alpha3 / POW2_M24).round() as i32;
-        let beta0 = (ionoutc.beta0 / 2048.0).round() as i32;
-        let beta1 = (ionoutc.beta1 / 16384.0).round() as i32;
-        let beta2 = (ionoutc.beta2 / 65536.0).round() as i32;
-        let beta3 = (ionoutc.beta3 / 65536.0).round() as i32;
-
-        #[allow(non_snake_case)]
-        let A0 = (ionoutc.A0 / POW2_M30).round() as i32;
-
-        #[allow(non_snake_case)]
-        let A1 = (ionoutc.A1 / POW2_M50).round() as i32;
-        let dtls = ionoutc.dtls;
-        let tot = (ionoutc.tot / 4096) as u32;
-        let week_number = (ionoutc.week_number % 256) as u32;
-        // 2016/12/31 (Sat) -> WNlsf = 1929, DN = 7 (http://navigationservices.agi.com/GNSSWeb/)
-        // Days are counted from 1 to 7 (Sunday is 1).
-        if ionoutc.leapen == 1 {
-            wnlsf = (ionoutc.wnlsf % 256) as u32;
-            dn = ionoutc.day_number as u32;
-            dtlsf = ionoutc.dtlsf as u32;
-        } else {
-            wnlsf = (1929 % 256) as u32;
-            dn = 7;
-            dtlsf = 18;
-        }
         // Subframe 1
         self.subframes[0] = [
             0x008b_0000 << 6,
@@ -692,7 +831,7 @@ impl Channel {
                 | (ura & 0xf) << 14
                 | (svhlth as u32 & 0x3f) << 8
                 | (iodc >> 8 & 0x3) << 6,
-            0,
+            (l2p_flag & 0x1) << 29,
             0,
             0,
             (tgd as u32 & 0xff) << 6,
@@ -711,7 +850,7 @@ impl Channel {
             (ecc & 0x00ff_ffff) << 6,
             (cus as u32 & 0xffff) << 14 | (sqrta >> 24 & 0xff) << 6,
             (sqrta & 0x00ff_ffff) << 6,
-            (toe & 0xffff) << 14,
+            (toe & 0xffff) << 14 | (fit_flag & 0x1) << 13 | (aodo & 0x1f) << 8,
         ];
         // Subframe 3
         self.subframes[2] = [
@@ -726,46 +865,13 @@ impl Channel {
             (omgdot as u32 & 0x00ff_ffff) << 6,
             (iode & 0xff) << 22 | (idot as u32 & 0x3fff) << 8,
         ];
-        if ionoutc.vflg {
+        self.subframes[3] = if ionoutc.vflg {
             // Subframe 4, page 18
-            self.subframes[3] = [
-                0x008b_0000 << 6,
-                0x4 << 8,
-                data_id << 28
-                    | sbf4_page18_sv_id << 22
-                    | (alpha0 as u32 & 0xff) << 14
-                    | (alpha1 as u32 & 0xff) << 6,
-                (alpha2 as u32 & 0xff) << 22
-                    | (alpha3 as u32 & 0xff) << 14
-                    | (beta0 as u32 & 0xff) << 6,
-                (beta1 as u32 & 0xff) << 22
-                    | (beta2 as u32 & 0xff) << 14
-                    | (beta3 as u32 & 0xff) << 6,
-                (A1 as u32 & 0x00ff_ffff) << 6,
-                ((A0 >> 8) as u32 & 0x00ff_ffff) << 6,
-                (A0 as u32 & 0xff) << 22
-                    | (tot & 0xff) << 14
-                    | (week_number & 0xff) << 6,
-                (dtls as u32 & 0xff) << 22
-                    | (wnlsf & 0xff) << 14
-                    | (dn & 0xff) << 6,
-                (dtlsf & 0xff) << 22,
-            ];
+            iono_utc_page(ionoutc)
         } else {
             // Subframe 4, page 25
-            self.subframes[3] = [
-                0x008b_0000 << 6,
-                0x4 << 8,
-                data_id << 28 | sbf4_page25_sv_id << 22,
-                0,
-                0,
-                0,
-                0,
-                0,
-                0,
-                0,
-            ];
-        }
+            reserved_page(4, sbf4_page25_sv_id)
+        };
         // Subframe 5, page 25
         self.subframes[4] = [
             0x008b_0000 << 6,
@@ -784,3 +890,88 @@ impl Channel {
         ];
     }
 }
+
+/// G2 delays in chips of the C/A codes of GPS PRN 1 to 63.
+const GPS_G2_DELAYS: [usize; 63] = [
+    5, 6, 7, 8, 17, 18, 139, 140, 141, 251, 252, 254, 255, 256, 257, 258, 469,
+    470, 471, 472, 473, 474, 509, 512, 513, 514, 515, 516, 859, 860, 861, 862,
+    863, 950, 947, 948, 950, 67, 103, 91, 19, 679, 225, 625, 946, 638, 161,
+    1001, 554, 280, 710, 709, 775, 864, 558, 220, 397, 55, 898, 759, 367, 299,
+    1018,
+];
+
+/// Initial states of the G2 register of the C/A codes of PRN 64 to 210 in
+/// IS-GPS-200 table 3-Ib, which include the SBAS (120 to 158) and QZSS (193
+/// to 202) codes. The octal digits hold stages 10 to 1, the most significant
+/// bit being the first G2 output.
+const G2_INITIAL_STATES: [u16; 147] = [
+    0o0254, 0o1602, 0o1160, 0o1114, 0o1342, 0o0025, 0o1523, 0o1046, 0o0404,
+    0o1445, 0o1054, 0o0072, 0o0262, 0o0077, 0o0521, 0o1400, 0o1010, 0o1441,
+    0o0365, 0o0270, 0o0263, 0o0613, 0o0277, 0o1562, 0o1674, 0o1113, 0o1245,
+    0o0606, 0o0136, 0o0256, 0o1550, 0o1234, 0o0260, 0o1455, 0o1535, 0o0746,
+    0o1033, 0o1213, 0o0710, 0o0721, 0o1763, 0o1751, 0o0435, 0o0735, 0o0771,
+    0o0140, 0o0111, 0o0656, 0o1016, 0o0462, 0o1011, 0o0552, 0o0045, 0o1104,
+    0o0557, 0o0364, 0o1106, 0o1241, 0o0267, 0o0232, 0o1617, 0o1076, 0o1764,
+    0o0717, 0o1532, 0o1250, 0o0341, 0o0551, 0o0520, 0o1731, 0o0706, 0o1216,
+    0o0740, 0o1007, 0o0450, 0o0305, 0o1653, 0o1411, 0o1644, 0o1312, 0o1060,
+    0o1560, 0o0035, 0o0355, 0o0335, 0o1254, 0o1041, 0o0142, 0o1641, 0o1504,
+    0o0751, 0o1774, 0o0107, 0o1153, 0o1542, 0o1223, 0o1702, 0o0436, 0o1735,
+    0o1662, 0o1570, 0o1573, 0o0201, 0o0635, 0o1737, 0o1670, 0o0134, 0o1224,
+    0o1460, 0o1362, 0o1654, 0o0510, 0o0242, 0o1142, 0o1017, 0o1070, 0o0501,
+    0o0455, 0o1566, 0o0215, 0o1003, 0o1454, 0o1665, 0o0471, 0o1750, 0o0307,
+    0o0272, 0o0764, 0o1422, 0o1050, 0o1607, 0o1747, 0o1305, 0o0540, 0o1363,
+    0o0727, 0o0147, 0o1206, 0o1045, 0o0476, 0o0604, 0o1757, 0o1330, 0o0663,
+    0o1436, 0o0753, 0o0731,
+];
+
+/// Setting of the G2 register of a C/A code.
+#[derive(Debug, Clone, Copy)]
+enum G2Setting {
+    /// Delay in chips of the G2 sequence from its all-ones state
+    Delay(usize),
+    /// Initial state, stage 10 in the most significant bit
+    InitialState(u16),
+}
+
+/// Returns the G2 setting of the C/A code of a PRN.
+///
+/// # Arguments
+/// * `prn` - PRN number of a satellite (1 to 210)
+///
+/// # Returns
+/// The G2 delay of PRN 1 to 63 or the G2 initial state of PRN 64 to 210, or
+/// `None` for other PRNs
+fn g2_setting(prn: usize) -> Option<G2Setting> {
+    match prn {
+        1..=63 => GPS_G2_DELAYS.get(prn - 1).copied().map(G2Setting::Delay),
+        64..=210 => G2_INITIAL_STATES
+            .get(prn - 64)
+            .copied()
+            .map(G2Setting::InitialState),
+        _ => None,
+    }
+}
+
+/// Generates the C/A code of a GPS, SBAS or QZSS satellite.
+///
+/// # Arguments
+/// * `prn` - PRN number of the satellite
+///
+/// # Returns
+/// The 1023 chips (+1 for binary 0, -1 for binary 1), or `None` for PRNs
+/// without a C/A code
+pub fn ca_code(prn: usize) -> Option<Vec<i8>> {
+    g2_setting(prn)?;
+    let mut channel = Channel {
+        prn,
+        ..Channel::default()
+    };
+    channel.codegen();
+    Some(
+        channel
+            .ca_sequence
+            .iter()
+            .map(|&chip| if chip == 0 { 1 } else { -1 })
+            .collect(),
+    )
+}
@@ -0,0 +1,194 @@
+use constants::N_DWRD_SBF;
+use geometry::{Azel, Ecef};
+use test_case::test_case;
+
+use super::{Channel, EPHEMERIS_FADE_TIME};
+use crate::{
+    Error, HealthMode, NominalConstellation, datetime::GpsTime,
+    ephemeris::Ephemeris, ionoutc::IonoUtc, propagation::compute_range,
+};
+
+/// Extracts a field of a 30-bit navigation word as stored in the subframes,
+/// where bit 1 of the word is the most significant of the 30 bits.
+fn field(word: u32, first_bit: u32, length: u32) -> u32 {
+    (word >> (30 - first_bit - length + 1)) & ((1 << length) - 1)
+}
+
+#[test_case(HealthMode::Simulate, 42, &[], 42)]
+#[test_case(HealthMode::ForceHealthy, 42, &[], 0)]
+#[test_case(HealthMode::Skip, 42, &[], 42)]
+#[test_case(HealthMode::Simulate, 0, &[(7, 63)], 63)]
+#[test_case(HealthMode::ForceHealthy, 42, &[(7, 1)], 1)]
+#[test_case(HealthMode::Simulate, 42, &[(8, 1)], 42)]
+#[test_case(HealthMode::Simulate, 42, &[(7, 1), (7, 0)], 0)]
+fn health_override_in_subframe1(
+    mode: HealthMode, health: i32, overrides: &[(usize, i32)], expected: u32,
+) {
+    let eph = Ephemeris {
+        svhlth: mode.broadcast_health(7, health, overrides),
+        ..Ephemeris::default()
+    };
+    let mut channel = Channel {
+        prn: 7,
+        ..Channel::default()
+    };
+    channel.generate_navigation_subframes(&eph, &IonoUtc::default());
+    // Word 3, bits 17 to 22
+    assert_eq!(field(channel.subframes[0][2], 17, 6), expected);
+}
+
+/// Receiver in Tokyo
+const RECEIVER: Ecef = Ecef {
+    x: -3_961_904.9,
+    y: 3_348_993.8,
+    z: 3_698_211.7,
+};
+
+/// Returns the ephemeris of a nominal satellite and a second data set of
+/// the same satellite whose orbit fit is a few meters off.
+fn ephemeris_pair() -> Result<(Ephemeris, Ephemeris), Error> {
+    let toe = GpsTime {
+        week: 2190,
+        sec: 7200.0,
+    };
+    let first = NominalConstellation::new(24)?
+        .ephemeris(5, &toe)
+        .ok_or_else(|| Error::msg("No nominal satellite"))?;
+    let second = Ephemeris {
+        iode: first.iode + 1,
+        iodc: first.iodc + 1,
+        m0: first.m0 + 2e-6,
+        ..first.clone()
+    };
+    Ok((first, second))
+}
+
+/// Decodes the 24 data bits of a transmitted word, whose data bits are
+/// inverted when the last bit of the previous word is set.
+fn data_bits(words: &[u32], index: usize) -> u32 {
+    let data = words[index] >> 6 & 0x00ff_ffff;
+    if words[index - 1] & 1 == 0 {
+        data
+    } else {
+        !data & 0x00ff_ffff
+    }
+}
+
+/// Returns the IODC LSBs and the IODEs of subframes 2 and 3 of the frame in
+/// transmission, which follows subframe 5 of the previous frame.
+fn frame_iods(channel: &Channel) -> [u32; 3] {
+    let words = &channel.data_words;
+    [
+        data_bits(words, N_DWRD_SBF + 7) >> 16,
+        data_bits(words, 2 * N_DWRD_SBF + 2) >> 16,
+        data_bits(words, 3 * N_DWRD_SBF + 9) >> 16,
+    ]
+}
+
+#[test]
+fn ephemeris_switch_at_frame_boundary() -> Result<(), Error> {
+    let (first, second) = ephemeris_pair()?;
+    let ionoutc = IonoUtc::default();
+    let start = first.toe.clone();
+    let mut channel = Channel::default();
+    channel.update_for_satellite(
+        5,
+        &first,
+        &first,
+        &ionoutc,
+        &start,
+        &RECEIVER,
+        Azel::default(),
+        None,
+    );
+    let old = first.iode as u32;
+    assert_eq!(frame_iods(&channel), [old; 3]);
+    // The switch regenerates the subframes, but the frame in transmission
+    // keeps the old data set until the next frame boundary
+    let boundary = start.add_secs(30.0);
+    channel.switch_ephemeris(
+        &second, &first, &second, &ionoutc, &boundary, &RECEIVER,
+    );
+    assert_eq!(frame_iods(&channel), [old; 3]);
+    channel.generate_nav_msg(&boundary, false, None);
+    assert_eq!(frame_iods(&channel), [second.iode as u32; 3]);
+    Ok(())
+}
+
+#[test]
+fn ephemeris_switch_keeps_range_continuous() -> Result<(), Error> {
+    let (first, second) = ephemeris_pair()?;
+    let ionoutc = IonoUtc::default();
+    let start = first.toe.clone();
+    let mut channel = Channel::default();
+    channel.update_for_satellite(
+        5,
+        &first,
+        &first,
+        &ionoutc,
+        &start,
+        &RECEIVER,
+        Azel::default(),
+        None,
+    );
+    let switch = start.add_secs(30.0);
+    // Pseudoranges of the generator every 100 ms, switching at 30 s
+    let mut ranges = Vec::new();
+    for step in 0..1200 {
+        let time = start.add_secs(f64::from(step) * 0.1);
+        if step == 300 {
+            channel.switch_ephemeris(
+                &second, &first, &second, &ionoutc, &switch, &RECEIVER,
+            );
+        }
+        let eph = if step < 300 { &first } else { &second };
+        let range = compute_range(eph, &ionoutc, &time, &RECEIVER).range
+            + channel.ephemeris_offset(&time);
+        let old = compute_range(&first, &ionoutc, &time, &RECEIVER).range;
+        let new = compute_range(&second, &ionoutc, &time, &RECEIVER).range;
+        ranges.push((range, old, new));
+    }
+    let jump = ranges[300].2 - ranges[300].1;
+    assert!(jump.abs() > 1.0, "orbit fits differ by {jump} m only");
+    // No step at the switch, then a linear fade to the new orbit fit over
+    // 60 s
+    let max_step = jump.abs() / (EPHEMERIS_FADE_TIME / 0.1) + 1e-4;
+    for pair in ranges.windows(2) {
+        let (previous, current) =
+            (pair[0].0 - pair[0].1, pair[1].0 - pair[1].1);
+        assert!((current - previous).abs() <= max_step);
+    }
+    assert!((ranges[300].0 - ranges[300].1).abs() < 1e-6);
+    assert!((ranges[900].0 - ranges[900].2).abs() < 1e-6);
+    assert!((ranges[1199].0 - ranges[1199].2).abs() < 1e-6);
+    Ok(())
+}
+
+#[test_case(0, 0, 0.0, 0.0, 0, 0; "defaults")]
+#[test_case(6, 1, 6.0, 1.0, 1, 0; "flags")]
+#[test_case(15, 0, 4.0, 899.0, 0, 0; "aodo below one unit")]
+#[test_case(9, 0, 4.0, 7200.0, 0, 8; "aodo in 900 s units")]
+#[test_case(9, 0, 4.0, 27900.0, 0, 31; "largest aodo")]
+#[test_case(9, 0, 4.0, 1e6, 0, 31; "aodo saturates")]
+fn subframe_flags(
+    ura: i32, l2p_flag: i32, fit_interval: f64, aodo: f64, fit_flag: u32,
+    aodo_units: u32,
+) {
+    let eph = Ephemeris {
+        ura,
+        l2p_flag,
+        fit_interval,
+        aodo,
+        ..Ephemeris::default()
+    };
+    let mut channel = Channel::default();
+    channel.generate_navigation_subframes(&eph, &IonoUtc::default());
+    let [subframe1, subframe2, ..] = &channel.subframes;
+    // Subframe 1: word 3 bits 13 to 16 and word 4 bit 1
+    assert_eq!(field(subframe1[2], 13, 4), ura as u32);
+    assert_eq!(field(subframe1[3], 1, 1), l2p_flag as u32);
+    assert_eq!(field(subframe1[3], 2, 23), 0);
+    // Subframe 2: word 10 bit 17 and bits 18 to 22
+    assert_eq!(field(subframe2[9], 17, 1), fit_flag);
+    assert_eq!(field(subframe2[9], 18, 5), aodo_units);
+}
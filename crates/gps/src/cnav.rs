@@ -0,0 +1,17 @@
+//! GPS L2C and L5 signal generation.
+//!
+//! This module simulates the modernized civil signals of the GPS satellites,
+//! L2C at 1227.60 MHz and L5 at 1176.45 MHz, each at its offset from the
+//! output centre frequency. The ranging codes are generated by shift
+//! registers, and the CNAV messages are built from the GPS ephemerides with
+//! the inter-signal corrections of RINEX 4 CNAV records.
+
+/// L2 CM/CL and L5 I5/Q5 ranging codes and the Neumann-Hoffman codes
+mod codes;
+/// CNAV message types and FEC encoding
+mod message;
+/// L2C and L5 signals driven by the signal generator
+mod system;
+
+pub(crate) use codes::{l2cl_code, l2cm_code, l5_codes};
+pub use system::{CnavSignal, CnavSystem};
@@ -0,0 +1,227 @@
+use constants::{L2CL_CODE_LEN, L2CM_CODE_LEN, L5_CODE_LEN};
+
+/// Number of GPS satellites with L2C and L5 codes.
+pub const CNAV_CODES: usize = 32;
+
+/// Feedback of the L2C shift register in its modular form, 1 + x^3 + x^4 +
+/// x^5 + x^6 + x^9 + x^11 + x^13 + x^16 + x^19 + x^21 + x^24 + x^27.
+const L2C_FEEDBACK: u32 = 0o445_112_474;
+
+/// Initial states of the L2 CM register of PRN 1 to 32.
+const L2CM_INITIAL_STATES: [u32; CNAV_CODES] = [
+    0o742_417_664,
+    0o756_014_035,
+    0o002_747_144,
+    0o066_265_724,
+    0o601_403_471,
+    0o703_232_733,
+    0o124_510_070,
+    0o617_316_361,
+    0o047_541_621,
+    0o733_031_046,
+    0o713_512_145,
+    0o024_437_606,
+    0o021_264_003,
+    0o230_655_351,
+    0o001_314_400,
+    0o222_021_506,
+    0o540_264_026,
+    0o205_521_705,
+    0o064_022_144,
+    0o120_161_274,
+    0o044_023_533,
+    0o724_744_327,
+    0o045_743_577,
+    0o741_201_660,
+    0o700_274_134,
+    0o010_247_261,
+    0o713_433_445,
+    0o737_324_162,
+    0o311_627_434,
+    0o710_452_007,
+    0o722_462_133,
+    0o050_172_213,
+];
+
+/// Initial states of the L2 CL register of PRN 1 to 32.
+const L2CL_INITIAL_STATES: [u32; CNAV_CODES] = [
+    0o624_145_772,
+    0o506_610_362,
+    0o220_360_016,
+    0o710_406_104,
+    0o001_143_345,
+    0o053_023_326,
+    0o652_521_276,
+    0o206_124_777,
+    0o015_563_374,
+    0o561_522_076,
+    0o023_163_525,
+    0o117_776_450,
+    0o606_516_355,
+    0o003_037_343,
+    0o046_515_565,
+    0o671_511_621,
+    0o605_402_220,
+    0o002_576_207,
+    0o525_163_451,
+    0o266_527_765,
+    0o006_760_703,
+    0o501_474_556,
+    0o743_747_443,
+    0o615_534_726,
+    0o763_621_420,
+    0o720_727_474,
+    0o700_521_043,
+    0o222_567_263,
+    0o132_765_304,
+    0o746_332_245,
+    0o102_300_466,
+    0o255_231_716,
+];
+
+/// Feedback stages of the L5 XA register, 1 + x^9 + x^10 + x^12 + x^13.
+const XA_TAPS: [usize; 4] = [9, 10, 12, 13];
+
+/// Feedback stages of the L5 XB register, 1 + x + x^3 + x^4 + x^6 + x^7 +
+/// x^8 + x^12 + x^13.
+const XB_TAPS: [usize; 8] = [1, 3, 4, 6, 7, 8, 12, 13];
+
+/// Number of chips after which the XA register is reset to all ones.
+const XA_PERIOD: usize = 8190;
+
+/// Advance of the XB sequence of the I5 codes of PRN 1 to 32, in chips.
+const I5_XB_ADVANCES: [usize; CNAV_CODES] = [
+    266, 365, 804, 1138, 1509, 1559, 1756, 2084, 2170, 2303, 2527, 2687, 2930,
+    3471, 3940, 4132, 4332, 4924, 5343, 5443, 5641, 5816, 5898, 5918, 5955,
+    6243, 6345, 6477, 6518, 6875, 7168, 7187,
+];
+
+/// Advance of the XB sequence of the Q5 codes of PRN 1 to 32, in chips.
+const Q5_XB_ADVANCES: [usize; CNAV_CODES] = [
+    1701, 323, 5292, 2020, 5429, 7136, 1041, 5947, 4315, 148, 535, 1939, 5206,
+    5910, 3595, 5135, 6082, 6990, 3546, 1523, 4548, 4484, 1893, 3961, 7106,
+    5299, 4660, 276, 4389, 3783, 1591, 1601,
+];
+
+/// Neumann-Hoffman code of the I5 component (first bit first).
+const NH10_CODE: &str = "0000110101";
+
+/// Neumann-Hoffman code of the Q5 component (first bit first).
+const NH20_CODE: &str = "00000100110101001110";
+
+/// Generates the L2 CM code of a satellite.
+///
+/// The CM and CL codes are sections of the same 27-stage maximal length
+/// sequence, starting at the initial states of IS-GPS-200. Chips of binary
+/// value 0 are transmitted as +1 and chips of value 1 as -1.
+///
+/// # Arguments
+/// * `prn` - PRN number of the satellite (1 to 32)
+///
+/// # Returns
+/// The 10230 chips (+1 or -1), or `None` for other PRNs
+pub fn l2cm_code(prn: usize) -> Option<Vec<i8>> {
+    let state = *L2CM_INITIAL_STATES.get(prn.checked_sub(1)?)?;
+    Some(l2c_sequence(state, L2CM_CODE_LEN))
+}
+
+/// Generates the L2 CL code of a satellite.
+///
+/// # Arguments
+/// * `prn` - PRN number of the satellite (1 to 32)
+///
+/// # Returns
+/// The 767250 chips (+1 or -1), or `None` for other PRNs
+pub fn l2cl_code(prn: usize) -> Option<Vec<i8>> {
+    let state = *L2CL_INITIAL_STATES.get(prn.checked_sub(1)?)?;
+    Some(l2c_sequence(state, L2CL_CODE_LEN))
+}
+
+/// Generates the L2C codes of a satellite, time-multiplexed at 1.023 MHz.
+///
+/// The CM and CL codes, clocked at 511.5 kHz each, alternate chip by chip,
+/// CM first, so each code is returned at 1.023 MHz with zeros in the chips
+/// of the other one.
+///
+/// # Arguments
+/// * `prn` - PRN number of the satellite (1 to 32)
+///
+/// # Returns
+/// The 20460 CM and 1534500 CL chips (+1, -1 or 0), or `None` for other
+/// PRNs
+pub fn l2c_codes(prn: usize) -> Option<(Vec<i8>, Vec<i8>)> {
+    let cm = l2cm_code(prn)?;
+    let cl = l2cl_code(prn)?;
+    let multiplexed = |code: Vec<i8>, first: bool| {
+        code.into_iter()
+            .flat_map(|chip| if first { [chip, 0] } else { [0, chip] })
+            .collect()
+    };
+    Some((multiplexed(cm, true), multiplexed(cl, false)))
+}
+
+/// Generates a section of the L2C sequence from an initial register state.
+fn l2c_sequence(initial_state: u32, len: usize) -> Vec<i8> {
+    let mut register = initial_state;
+    let mut chips = Vec::with_capacity(len);
+    for _ in 0..len {
+        chips.push(if register & 1 == 0 { 1 } else { -1 });
+        register = (register >> 1) ^ ((register & 1) * L2C_FEEDBACK);
+    }
+    chips
+}
+
+/// Generates the I5 and Q5 codes of a satellite.
+///
+/// Each code is the sum of the XA sequence, reset to all ones after 8190
+/// chips, and the XB sequence advanced by the chips of IS-GPS-705 for the
+/// satellite, both 13-stage shift registers starting at all ones. Chips of
+/// binary value 0 are transmitted as +1 and chips of value 1 as -1.
+///
+/// # Arguments
+/// * `prn` - PRN number of the satellite (1 to 32)
+///
+/// # Returns
+/// The 10230 I5 and Q5 chips (+1 or -1), or `None` for other PRNs
+pub fn l5_codes(prn: usize) -> Option<(Vec<i8>, Vec<i8>)> {
+    let sv = prn.checked_sub(1)?;
+    Some((
+        l5_code(*I5_XB_ADVANCES.get(sv)?),
+        l5_code(*Q5_XB_ADVANCES.get(sv)?),
+    ))
+}
+
+/// Generates an L5 code from the advance of its XB sequence.
+fn l5_code(advance: usize) -> Vec<i8> {
+    let stage = |register: u16, stage: usize| register >> (stage - 1) & 1;
+    let shift = |register: u16, taps: &[usize]| {
+        let feedback =
+            taps.iter().fold(0, |sum, &tap| sum ^ stage(register, tap));
+        (register << 1 | feedback) & 0x1fff
+    };
+    let mut xa = 0x1fff;
+    let mut xb = (0..advance).fold(0x1fff, |xb, _| shift(xb, &XB_TAPS));
+    let mut chips = Vec::with_capacity(L5_CODE_LEN);
+    for chip in 0..L5_CODE_LEN {
+        let bit = stage(xa, 13) ^ stage(xb, 13);
+        chips.push(if bit == 0 { 1 } else { -1 });
+        xa = if (chip + 1) % XA_PERIOD == 0 {
+            0x1fff
+        } else {
+            shift(xa, &XA_TAPS)
+        };
+        xb = shift(xb, &XB_TAPS);
+    }
+    chips
+}
+
+/// Returns the Neumann-Hoffman codes of the I5 and Q5 components as +1 or
+/// -1 chips.
+pub fn nh_codes() -> (Vec<i8>, Vec<i8>) {
+    let chips = |code: &str| {
+        code.bytes()
+            .map(|bit| if bit == b'0' { 1 } else { -1 })
+            .collect()
+    };
+    (chips(NH10_CODE), chips(NH20_CODE))
+}
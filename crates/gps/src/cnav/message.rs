@@ -0,0 +1,233 @@
+use constants::{PI, SECONDS_IN_WEEK};
+use rtcm::crc::crc24q_bits;
+
+use crate::{
+    datetime::GpsTime, ephemeris::Ephemeris, ionoutc::IonoUtc, signal::Bits,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Number of FEC symbols of a message (300 bits at rate 1/2).
+const MESSAGE_SYMBOLS: usize = 600;
+
+/// Number of bits of a message before the CRC.
+const PAYLOAD_BITS: usize = 276;
+
+/// Preamble at the start of every message.
+const PREAMBLE: u64 = 0b1000_1011;
+
+/// Message types broadcast in turn: ephemeris 1 and 2, clock with
+/// ionospheric and group delay parameters, and clock with UTC parameters.
+const MESSAGE_TYPES: [u8; 4] = [10, 11, 30, 33];
+
+/// Unit of the message time of week count in seconds.
+const TOW_UNIT: f64 = 6.0;
+
+/// Reference semi-major axis of the CNAV ephemeris (meters).
+const A_REF: f64 = 26_559_710.0;
+
+/// Reference rate of right ascension of the CNAV ephemeris
+/// (semi-circles/second).
+const OMEGA_DOT_REF: f64 = -2.6e-9;
+
+/// Unit of the CNAV times of ephemeris, clock and prediction in seconds.
+const TIME_UNIT: f64 = 300.0;
+
+/// Generator polynomial G1 of the convolutional code (octal 171).
+const CONV_G1: u32 = 0o171;
+
+/// Generator polynomial G2 of the convolutional code (octal 133).
+const CONV_G2: u32 = 0o133;
+
+/// Inter-signal corrections of message type 30 (seconds).
+///
+/// The clock correction of a signal is the one of the LNAV message, TGD
+/// included, plus the correction of the signal.
+#[derive(Clone, Copy, Default)]
+pub struct InterSignalCorrections {
+    /// Correction of L1 C/A
+    pub l1ca: f64,
+    /// Correction of L2C
+    pub l2c: f64,
+    /// Correction of L5 I5
+    pub l5i5: f64,
+    /// Correction of L5 Q5
+    pub l5q5: f64,
+}
+
+/// Content of the CNAV message of a GPS satellite on L2C or L5.
+///
+/// The ephemeris and clock of the LNAV ephemeris are broadcast in the CNAV
+/// representation, with the rates of the semi-major axis and the mean motion
+/// difference zero and the times of ephemeris and clock rounded to 300
+/// seconds.
+pub struct CnavMessage<'a> {
+    /// Satellite PRN
+    pub prn: usize,
+    /// Broadcast ephemeris of the satellite
+    pub eph: &'a Ephemeris,
+    /// Inter-signal corrections of the satellite
+    pub isc: InterSignalCorrections,
+    /// Ionospheric and UTC parameters
+    pub ionoutc: &'a IonoUtc,
+    /// Duration of a message in seconds: 12 on L2C, 6 on L5
+    pub message_seconds: f64,
+}
+
+impl CnavMessage<'_> {
+    /// Generates the FEC symbols of consecutive messages.
+    ///
+    /// Messages start at multiples of their duration from the start of the
+    /// week, with the message types following each other in turn. They are
+    /// encoded continuously by the rate 1/2, constraint length 7
+    /// convolutional code, starting with the message before, so that the
+    /// first symbols are those a satellite transmitting without interruption
+    /// sends.
+    ///
+    /// # Arguments
+    /// * `start` - Start of the first message
+    /// * `messages` - Number of messages
+    ///
+    /// # Returns
+    /// `MESSAGE_SYMBOLS` symbols per message, +1 for binary 0 and -1 for
+    /// binary 1
+    pub fn symbols(&self, start: &GpsTime, messages: usize) -> Vec<i8> {
+        let mut state = 0u32;
+        let mut symbols = Vec::with_capacity((messages + 1) * MESSAGE_SYMBOLS);
+        for index in 0..=messages {
+            let time =
+                start.add_secs((index as f64 - 1.0) * self.message_seconds);
+            for bit in self.message(&time) {
+                state = (state >> 1) | u32::from(bit) << 6;
+                symbols.push((state & CONV_G1).count_ones() & 1);
+                symbols.push((state & CONV_G2).count_ones() & 1);
+            }
+        }
+        symbols
+            .into_iter()
+            .skip(MESSAGE_SYMBOLS)
+            .map(|symbol| if symbol == 0 { 1 } else { -1 })
+            .collect()
+    }
+
+    /// Builds the 300 bits of the message transmitted from a time on.
+    ///
+    /// # Arguments
+    /// * `time` - GPS time at the start of the message
+    fn message(&self, time: &GpsTime) -> Vec<u8> {
+        let eph = self.eph;
+        let index = (time.sec / self.message_seconds).round() as usize;
+        let message_type = MESSAGE_TYPES[index % MESSAGE_TYPES.len()];
+        // Time of week count at the start of the next message
+        let tow = ((time.sec + self.message_seconds) / TOW_UNIT).round()
+            % (SECONDS_IN_WEEK / TOW_UNIT);
+        let toe = (eph.toe.sec / TIME_UNIT).round() as u64;
+        let mut bits = Bits::default();
+        bits.push(PREAMBLE, 8);
+        bits.push(self.prn as u64, 6);
+        bits.push(u64::from(message_type), 6);
+        bits.push(tow as u64, 17);
+        // No alert
+        bits.push(0, 1);
+        match message_type {
+            10 => {
+                let health = u64::from(eph.svhlth != 0);
+                bits.push(time.week as u64, 13);
+                // L1, L2 and L5 health
+                bits.push(health * 0b111, 3);
+                bits.push(toe, 11);
+                bits.push(eph.ura as u64, 5);
+                bits.push(toe, 11);
+                bits.push_scaled(eph.A - A_REF, 9, 26);
+                // Rate of change of the semi-major axis
+                bits.zeros(25);
+                bits.push_scaled(eph.deltan / PI, 44, 17);
+                // Rate of change of the mean motion difference
+                bits.zeros(23);
+                bits.push_scaled(eph.m0 / PI, 32, 33);
+                bits.push((eph.ecc * 2f64.powi(34)).round() as u64, 33);
+                bits.push_scaled(eph.aop / PI, 32, 33);
+                // Integrity status flag and L2C phasing
+                bits.zeros(2);
+            }
+            11 => {
+                bits.push(toe, 11);
+                bits.push_scaled(eph.omg0 / PI, 32, 33);
+                bits.push_scaled(eph.inc0 / PI, 32, 33);
+                bits.push_scaled(eph.omgdot / PI - OMEGA_DOT_REF, 44, 17);
+                bits.push_scaled(eph.idot / PI, 44, 15);
+                bits.push_scaled(eph.cis, 30, 16);
+                bits.push_scaled(eph.cic, 30, 16);
+                bits.push_scaled(eph.crs, 8, 24);
+                bits.push_scaled(eph.crc, 8, 24);
+                bits.push_scaled(eph.cus, 30, 21);
+                bits.push_scaled(eph.cuc, 30, 21);
+            }
+            _ => {
+                self.push_clock(&mut bits, toe);
+                if message_type == 30 {
+                    self.push_delays(&mut bits, time);
+                } else {
+                    self.push_utc(&mut bits);
+                }
+            }
+        }
+        bits.pad(PAYLOAD_BITS);
+        // CRC-24Q over the bits packed MSB first
+        let mut bytes = vec![0u8; PAYLOAD_BITS.div_ceil(8)];
+        for (i, &bit) in bits.0.iter().enumerate() {
+            bytes[i / 8] |= bit << (7 - i % 8);
+        }
+        bits.push(u64::from(crc24q_bits(&bytes, PAYLOAD_BITS)), 24);
+        bits.0
+    }
+
+    /// Appends the clock parameters of message types 30 to 37.
+    fn push_clock(&self, bits: &mut Bits, top: u64) {
+        let eph = self.eph;
+        bits.push(top, 11);
+        // URA_NED0, URA_NED1 and URA_NED2
+        bits.zeros(11);
+        bits.push((eph.toc.sec / TIME_UNIT).round() as u64, 11);
+        bits.push_scaled(eph.af0, 35, 26);
+        bits.push_scaled(eph.af1, 48, 20);
+        bits.push_scaled(eph.af2, 60, 10);
+    }
+
+    /// Appends the group delays and ionospheric parameters of message type
+    /// 30.
+    fn push_delays(&self, bits: &mut Bits, time: &GpsTime) {
+        let ionoutc = self.ionoutc;
+        bits.push_scaled(self.eph.tgd, 35, 13);
+        bits.push_scaled(self.isc.l1ca, 35, 13);
+        bits.push_scaled(self.isc.l2c, 35, 13);
+        bits.push_scaled(self.isc.l5i5, 35, 13);
+        bits.push_scaled(self.isc.l5q5, 35, 13);
+        bits.push_scaled(ionoutc.alpha0, 30, 8);
+        bits.push_scaled(ionoutc.alpha1, 27, 8);
+        bits.push_scaled(ionoutc.alpha2, 24, 8);
+        bits.push_scaled(ionoutc.alpha3, 24, 8);
+        bits.push_scaled(ionoutc.beta0, -11, 8);
+        bits.push_scaled(ionoutc.beta1, -14, 8);
+        bits.push_scaled(ionoutc.beta2, -16, 8);
+        bits.push_scaled(ionoutc.beta3, -16, 8);
+        // Week of the prediction time
+        bits.push(time.week as u64, 8);
+    }
+
+    /// Appends the UTC parameters of message type 33.
+    fn push_utc(&self, bits: &mut Bits) {
+        let ionoutc = self.ionoutc;
+        bits.push_scaled(ionoutc.A0, 35, 16);
+        bits.push_scaled(ionoutc.A1, 51, 13);
+        // Second-order term
+        bits.zeros(7);
+        bits.push(ionoutc.dtls as u64, 8);
+        bits.push((ionoutc.tot >> 4) as u64, 16);
+        bits.push(ionoutc.week_number as u64, 13);
+        bits.push(ionoutc.wnlsf as u64, 13);
+        bits.push(ionoutc.day_number as u64, 4);
+        bits.push(ionoutc.dtlsf as u64, 8);
+    }
+}
@@ -0,0 +1,259 @@
+use constants::PI;
+use rtcm::crc::crc24q_bits;
+use test_case::test_case;
+
+use super::{A_REF, CnavMessage, InterSignalCorrections, OMEGA_DOT_REF};
+use crate::{datetime::GpsTime, ephemeris::Ephemeris, ionoutc::IonoUtc};
+
+/// Start of the message of type 10 of the L5 message sequence used in the
+/// tests; the types 11, 30 and 33 follow every 6 seconds.
+const START: f64 = 345_600.0;
+
+/// Returns `raw` in units of 2^-`scale` semi-circles, in radians.
+fn semicircles(raw: f64, scale: i32) -> f64 {
+    raw * 2f64.powi(-scale) * PI
+}
+
+/// Returns `raw` in units of 2^-`scale`.
+fn scaled(raw: f64, scale: i32) -> f64 {
+    raw * 2f64.powi(-scale)
+}
+
+/// Creates an ephemeris whose parameters are whole numbers of their CNAV
+/// units.
+fn ephemeris() -> Ephemeris {
+    let time = GpsTime {
+        week: 2190,
+        sec: START,
+    };
+    Ephemeris {
+        toc: time.clone(),
+        toe: time,
+        ura: 3,
+        A: A_REF + 1000.5,
+        deltan: semicircles(48.0, 44),
+        m0: semicircles(-123_456_789.0, 32),
+        ecc: 0.01,
+        aop: semicircles(987_654_321.0, 32),
+        omg0: semicircles(-555_555_555.0, 32),
+        inc0: semicircles(1_288_490_189.0, 32),
+        omgdot: (OMEGA_DOT_REF + scaled(-1000.0, 44)) * PI,
+        idot: semicircles(-200.0, 44),
+        cis: scaled(100.0, 30),
+        cic: scaled(-50.0, 30),
+        crs: scaled(12345.0, 8),
+        crc: scaled(-6789.0, 8),
+        cus: scaled(4000.0, 30),
+        cuc: scaled(-3000.0, 30),
+        af0: scaled(-1_000_000.0, 35),
+        af1: scaled(2000.0, 48),
+        af2: scaled(-3.0, 60),
+        tgd: scaled(-100.0, 35),
+        ..Ephemeris::default()
+    }
+}
+
+/// Creates ionospheric and UTC parameters in whole numbers of their units.
+fn ionoutc() -> IonoUtc {
+    IonoUtc {
+        alpha0: scaled(11.0, 30),
+        alpha1: scaled(-7.0, 27),
+        alpha2: scaled(-60.0, 24),
+        alpha3: scaled(60.0, 24),
+        beta0: scaled(90.0, -11),
+        beta1: scaled(-20.0, -14),
+        beta2: scaled(-64.0, -16),
+        beta3: scaled(100.0, -16),
+        A0: scaled(-5000.0, 35),
+        A1: scaled(100.0, 51),
+        dtls: 18,
+        tot: 405_504,
+        week_number: 2190,
+        wnlsf: 2185,
+        day_number: 7,
+        dtlsf: 18,
+        ..IonoUtc::default()
+    }
+}
+
+/// Builds the L5 message of PRN 17 starting `offset` seconds after `START`.
+fn message_bits(offset: f64) -> Vec<u8> {
+    let eph = ephemeris();
+    let ionoutc = ionoutc();
+    let message = CnavMessage {
+        prn: 17,
+        eph: &eph,
+        isc: InterSignalCorrections {
+            l1ca: scaled(20.0, 35),
+            l2c: scaled(-30.0, 35),
+            l5i5: scaled(40.0, 35),
+            l5q5: scaled(-50.0, 35),
+        },
+        ionoutc: &ionoutc,
+        message_seconds: 6.0,
+    };
+    message.message(&GpsTime {
+        week: 2190,
+        sec: START + offset,
+    })
+}
+
+/// Returns the two's complement value of the bits `first` (1-based, as in
+/// IS-GPS-200) to `first + len - 1`.
+fn field(bits: &[u8], first: usize, len: usize) -> i64 {
+    let value = bits[first - 1..first - 1 + len]
+        .iter()
+        .fold(0i64, |value, &bit| value << 1 | i64::from(bit));
+    if len > 1 && bits[first - 1] == 1 {
+        value - (1 << len)
+    } else {
+        value
+    }
+}
+
+/// Returns the unsigned value of the bits `first` (1-based) to
+/// `first + len - 1`.
+fn unsigned(bits: &[u8], first: usize, len: usize) -> i64 {
+    bits[first - 1..first - 1 + len]
+        .iter()
+        .fold(0, |value, &bit| value << 1 | i64::from(bit))
+}
+
+/// Packs bits into bytes, most significant bit first.
+fn pack(bits: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![0; bits.len().div_ceil(8)];
+    for (i, &bit) in bits.iter().enumerate() {
+        bytes[i / 8] |= bit << (7 - i % 8);
+    }
+    bytes
+}
+
+#[test]
+fn crc24q_check_value() {
+    assert_eq!(crc24q_bits(b"123456789", 72), 0xcd_e703);
+}
+
+#[test_case(0.0, 10; "type 10")]
+#[test_case(6.0, 11; "type 11")]
+#[test_case(12.0, 30; "type 30")]
+#[test_case(18.0, 33; "type 33")]
+#[test_case(24.0, 10; "next type 10")]
+fn message_header(offset: f64, message_type: i64) {
+    let bits = message_bits(offset);
+    assert_eq!(bits.len(), 300);
+    assert_eq!(unsigned(&bits, 1, 8), 0x8b);
+    assert_eq!(unsigned(&bits, 9, 6), 17);
+    assert_eq!(unsigned(&bits, 15, 6), message_type);
+    // Time of week count of the start of the next message
+    let tow = (START + offset + 6.0) / 6.0;
+    assert_eq!(unsigned(&bits, 21, 17), tow as i64);
+    assert_eq!(unsigned(&bits, 38, 1), 0);
+    // CRC-24Q over bits 1 to 276 in bits 277 to 300: zero residue
+    assert_eq!(crc24q_bits(&pack(&bits), 300), 0);
+    let payload = pack(&bits[..276]);
+    assert_eq!(
+        unsigned(&bits, 277, 24),
+        i64::from(crc24q_bits(&payload, 276))
+    );
+}
+
+#[test]
+fn message_type_10() {
+    let bits = message_bits(0.0);
+    assert_eq!(unsigned(&bits, 39, 13), 2190);
+    assert_eq!(unsigned(&bits, 52, 3), 0);
+    assert_eq!(unsigned(&bits, 55, 11), 1152);
+    assert_eq!(field(&bits, 66, 5), 3);
+    assert_eq!(unsigned(&bits, 71, 11), 1152);
+    assert_eq!(field(&bits, 82, 26), 512_256);
+    assert_eq!(field(&bits, 108, 25), 0);
+    assert_eq!(field(&bits, 133, 17), 48);
+    assert_eq!(field(&bits, 150, 23), 0);
+    assert_eq!(field(&bits, 173, 33), -123_456_789);
+    assert_eq!(unsigned(&bits, 206, 33), 171_798_692);
+    assert_eq!(field(&bits, 239, 33), 987_654_321);
+    assert_eq!(unsigned(&bits, 272, 5), 0);
+}
+
+#[test]
+fn message_type_10_health() {
+    let eph = Ephemeris {
+        svhlth: 0x3f,
+        ..ephemeris()
+    };
+    let ionoutc = ionoutc();
+    let message = CnavMessage {
+        prn: 17,
+        eph: &eph,
+        isc: InterSignalCorrections::default(),
+        ionoutc: &ionoutc,
+        message_seconds: 12.0,
+    };
+    // L2C message of type 10
+    let bits = message.message(&GpsTime {
+        week: 2190,
+        sec: START,
+    });
+    assert_eq!(unsigned(&bits, 15, 6), 10);
+    assert_eq!(unsigned(&bits, 21, 17), (START as i64 + 12) / 6);
+    assert_eq!(unsigned(&bits, 52, 3), 0b111);
+}
+
+#[test]
+fn message_type_11() {
+    let bits = message_bits(6.0);
+    assert_eq!(unsigned(&bits, 39, 11), 1152);
+    assert_eq!(field(&bits, 50, 33), -555_555_555);
+    assert_eq!(field(&bits, 83, 33), 1_288_490_189);
+    assert_eq!(field(&bits, 116, 17), -1000);
+    assert_eq!(field(&bits, 133, 15), -200);
+    assert_eq!(field(&bits, 148, 16), 100);
+    assert_eq!(field(&bits, 164, 16), -50);
+    assert_eq!(field(&bits, 180, 24), 12345);
+    assert_eq!(field(&bits, 204, 24), -6789);
+    assert_eq!(field(&bits, 228, 21), 4000);
+    assert_eq!(field(&bits, 249, 21), -3000);
+    assert_eq!(unsigned(&bits, 270, 7), 0);
+}
+
+/// Checks the clock parameters of message types 30 to 37.
+fn assert_clock(bits: &[u8]) {
+    assert_eq!(unsigned(bits, 39, 11), 1152);
+    assert_eq!(unsigned(bits, 50, 11), 0);
+    assert_eq!(unsigned(bits, 61, 11), 1152);
+    assert_eq!(field(bits, 72, 26), -1_000_000);
+    assert_eq!(field(bits, 98, 20), 2000);
+    assert_eq!(field(bits, 118, 10), -3);
+}
+
+#[test]
+fn message_type_30() {
+    let bits = message_bits(12.0);
+    assert_clock(&bits);
+    let delays = [-100, 20, -30, 40, -50];
+    for (i, delay) in delays.into_iter().enumerate() {
+        assert_eq!(field(&bits, 128 + 13 * i, 13), delay, "delay {i}");
+    }
+    let klobuchar = [11, -7, -60, 60, 90, -20, -64, 100];
+    for (i, parameter) in klobuchar.into_iter().enumerate() {
+        assert_eq!(field(&bits, 193 + 8 * i, 8), parameter, "parameter {i}");
+    }
+    assert_eq!(unsigned(&bits, 257, 8), 2190 % 256);
+    assert_eq!(unsigned(&bits, 265, 12), 0);
+}
+
+#[test]
+fn message_type_33() {
+    let bits = message_bits(18.0);
+    assert_clock(&bits);
+    assert_eq!(field(&bits, 128, 16), -5000);
+    assert_eq!(field(&bits, 144, 13), 100);
+    assert_eq!(field(&bits, 157, 7), 0);
+    assert_eq!(field(&bits, 164, 8), 18);
+    assert_eq!(unsigned(&bits, 172, 16), 405_504 / 16);
+    assert_eq!(unsigned(&bits, 188, 13), 2190);
+    assert_eq!(unsigned(&bits, 201, 13), 2185);
+    assert_eq!(unsigned(&bits, 214, 4), 7);
+    assert_eq!(field(&bits, 218, 8), 18);
+    assert_eq!(unsigned(&bits, 226, 51), 0);
+}
@@ -0,0 +1,407 @@
+use constants::{
+    CARR_FREQ, CODE_FREQ, L2_FREQ, L2CM_CODE_LEN, L5_CODE_FREQ, L5_CODE_LEN,
+    L5_FREQ, SPEED_OF_LIGHT,
+};
+use rinex::navigation::SatelliteSystem;
+
+use crate::{
+    Error,
+    cnav::{
+        codes::{CNAV_CODES, l2c_codes, l5_codes, nh_codes},
+        message::{CnavMessage, InterSignalCorrections},
+    },
+    datetime::{DateTime, GpsTime, TimeRange},
+    ephemeris::Ephemeris,
+    generator::SatelliteSelection,
+    propagation::compute_range,
+    signal::{
+        SignalChannel, SignalComponent, SignalSystem, Subcarrier,
+        SystemContext, SystemNavigation, allocate_channels, band_offset,
+        is_visible, print_channel_status, signal_gain,
+    },
+    timeline::EphemerisTimeline,
+};
+
+/// Duration of the symbol buffer of a channel in seconds.
+///
+/// The buffer starts with the message being transmitted at a frame boundary
+/// and reaches past the next boundary, where it is regenerated.
+const WINDOW_SECONDS: f64 = 72.0;
+
+/// GPS signal carrying the CNAV message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CnavSignal {
+    /// L2C at 1227.60 MHz: CM with the message at 25 bps and the CL pilot,
+    /// time-multiplexed at 1.023 MHz
+    L2c,
+    /// L5 at 1176.45 MHz: I5 with the message at 50 bps and the Q5 pilot in
+    /// quadrature, at 10.23 MHz with Neumann-Hoffman codes
+    L5,
+}
+
+impl CnavSignal {
+    /// Returns the name of the signal.
+    fn name(self) -> &'static str {
+        match self {
+            Self::L2c => "L2C",
+            Self::L5 => "L5",
+        }
+    }
+
+    /// Returns the carrier frequency in Hz.
+    fn carrier(self) -> f64 {
+        match self {
+            Self::L2c => L2_FREQ,
+            Self::L5 => L5_FREQ,
+        }
+    }
+
+    /// Returns the chip rate in Hz, which is also half the bandwidth of the
+    /// main lobe around the carrier.
+    fn chip_rate(self) -> f64 {
+        match self {
+            Self::L2c => CODE_FREQ,
+            Self::L5 => L5_CODE_FREQ,
+        }
+    }
+
+    /// Returns the duration of a message in seconds.
+    fn message_seconds(self) -> f64 {
+        match self {
+            Self::L2c => 12.0,
+            Self::L5 => 6.0,
+        }
+    }
+
+    /// Returns the number of chips per FEC symbol: one CM period on L2C, ten
+    /// I5 periods on L5.
+    fn chips_per_symbol(self) -> u64 {
+        match self {
+            Self::L2c => 2 * L2CM_CODE_LEN as u64,
+            Self::L5 => 10 * L5_CODE_LEN as u64,
+        }
+    }
+
+    /// Returns the inter-signal correction of the signal.
+    fn isc(self, isc: &InterSignalCorrections) -> f64 {
+        match self {
+            Self::L2c => isc.l2c,
+            Self::L5 => isc.l5i5,
+        }
+    }
+}
+
+/// GPS L2C or L5 signals with the CNAV message.
+///
+/// Each visible GPS satellite allowed by the satellite selection transmits
+/// the signal with the CNAV message types 10, 11, 30 and 33 in turn, built
+/// from its LNAV ephemeris and the ionospheric and UTC parameters of the
+/// navigation files. All satellites with an ephemeris are simulated,
+/// whether or not their block broadcasts the signal.
+///
+/// The ranges follow the broadcast ephemerides. They are delayed by the
+/// Klobuchar model scaled from L1 to the carrier frequency, and by the group
+/// delay of the signal: TGD plus the inter-signal correction of the RINEX 4
+/// CNAV record closest in time. Without CNAV records, the correction is
+/// derived from TGD as for the P(Y) code, TGD (1 - (f1/f)²), and broadcast
+/// in message type 30.
+pub struct CnavSystem {
+    /// Simulated signal
+    signal: CnavSignal,
+    /// Broadcast ephemerides of all GPS satellites
+    timeline: EphemerisTimeline,
+    /// Ephemeris in use of each satellite (index PRN - 1)
+    ephemerides: Vec<Option<Ephemeris>>,
+    /// Inter-signal corrections of the CNAV records of each satellite (index
+    /// PRN - 1), with their reference times
+    corrections: Vec<Vec<(GpsTime, InterSignalCorrections)>>,
+    /// Satellites allowed to occupy a channel
+    selection: SatelliteSelection,
+    /// Carrier frequency minus the output centre frequency, or `None` if the
+    /// signal lies outside the output band
+    carrier_offset: Option<f64>,
+    /// Channels of the visible satellites
+    channels: Vec<SignalChannel>,
+}
+
+impl CnavSystem {
+    /// Creates the L2C or L5 system from the GPS ephemerides.
+    ///
+    /// # Arguments
+    /// * `signal` - Simulated signal
+    /// * `timeline` - Broadcast ephemerides of the GPS satellites
+    /// * `navigation` - Navigation data with the GPS CNAV records, if any
+    /// * `selection` - Satellites allowed to occupy a channel
+    ///
+    /// # Returns
+    /// * `Ok(CnavSystem)` - The system ready to be initialized
+    /// * `Err(Error)` - If a CNAV record cannot be converted
+    ///
+    /// # Errors
+    /// * Returns an error if the time of clock of a CNAV record is out of range
+    pub fn new(
+        signal: CnavSignal, timeline: EphemerisTimeline,
+        navigation: &SystemNavigation, selection: SatelliteSelection,
+    ) -> Result<Self, Error> {
+        let mut corrections = vec![Vec::new(); CNAV_CODES];
+        for record in navigation.ephemerides(SatelliteSystem::Gps) {
+            let Some(satellite) = record
+                .satellite
+                .prn
+                .checked_sub(1)
+                .and_then(|sv| corrections.get_mut(sv))
+            else {
+                continue;
+            };
+            let datetime = DateTime::from(record.epoch.in_tz("UTC")?);
+            let [l1ca, l2c, l5i5, l5q5] = record.orbit(7);
+            satellite.push((
+                GpsTime::from(&datetime),
+                InterSignalCorrections {
+                    l1ca,
+                    l2c,
+                    l5i5,
+                    l5q5,
+                },
+            ));
+        }
+        Ok(Self {
+            signal,
+            timeline,
+            ephemerides: vec![None; CNAV_CODES],
+            corrections,
+            selection,
+            carrier_offset: None,
+            channels: Vec::new(),
+        })
+    }
+
+    /// Takes the ephemeris due at a time into use for every satellite.
+    fn select_ephemerides(&mut self, time: &GpsTime) {
+        for (sv, current) in self.ephemerides.iter_mut().enumerate() {
+            if let Some(eph) = self.timeline.select(sv + 1, time) {
+                *current = Some(eph.clone());
+            }
+        }
+    }
+
+    /// Releases the channels of satellites below the elevation mask and
+    /// allocates channels to the newly visible satellites.
+    fn allocate_channels(&mut self, context: &SystemContext) {
+        let Some(carrier_offset) = self.carrier_offset else {
+            return;
+        };
+        let (signal, corrections) = (self.signal, &self.corrections);
+        allocate_channels(
+            &mut self.channels,
+            &self.ephemerides,
+            1,
+            &self.selection,
+            |_, eph| is_visible(eph, context),
+            |prn, eph| {
+                let mut channel = SignalChannel::new(
+                    prn,
+                    components(signal, prn)?,
+                    signal.chip_rate(),
+                    signal.carrier(),
+                    carrier_offset,
+                    signal.chips_per_symbol(),
+                );
+                let isc = closest_corrections(
+                    &corrections[prn - 1],
+                    eph,
+                    context.time,
+                );
+                channel.start(cnav_range(signal, eph, &isc, context));
+                Some(channel)
+            },
+        );
+    }
+
+    /// Regenerates the message symbols of all channels for the frame
+    /// starting at a time.
+    fn generate_messages(&mut self, context: &SystemContext) {
+        // The buffers start with the message transmitted a second before the
+        // current reception time
+        let message_seconds = self.signal.message_seconds();
+        let mut start = context.time.add_secs(-1.0);
+        start.sec = (start.sec / message_seconds).floor() * message_seconds;
+        let messages = (WINDOW_SECONDS / message_seconds) as usize;
+        for channel in &mut self.channels {
+            let Some(eph) = &self.ephemerides[channel.prn - 1] else {
+                continue;
+            };
+            let message = CnavMessage {
+                prn: channel.prn,
+                eph,
+                isc: closest_corrections(
+                    &self.corrections[channel.prn - 1],
+                    eph,
+                    &start,
+                ),
+                ionoutc: context.ionoutc,
+                message_seconds,
+            };
+            channel.set_symbols(&start, message.symbols(&start, messages));
+        }
+    }
+}
+
+impl SignalSystem for CnavSystem {
+    fn name(&self) -> &'static str {
+        match self.signal {
+            CnavSignal::L2c => "GPS L2C",
+            CnavSignal::L5 => "GPS L5",
+        }
+    }
+
+    fn carrier_frequency(&self) -> f64 {
+        self.signal.carrier()
+    }
+
+    fn initialize(&mut self, context: &SystemContext) {
+        self.carrier_offset = band_offset(
+            context,
+            self.signal.carrier(),
+            self.signal.chip_rate(),
+        );
+        if self.carrier_offset.is_none() {
+            eprintln!(
+                "Warning: GPS {} lies outside the output band; GPS {} signals \
+                 are not simulated.",
+                self.signal.name(),
+                self.signal.name()
+            );
+        }
+        self.select_ephemerides(context.time);
+        self.allocate_channels(context);
+        self.generate_messages(context);
+    }
+
+    fn update_channels(&mut self, context: &SystemContext) {
+        for channel in &mut self.channels {
+            let Some(eph) = &self.ephemerides[channel.prn - 1] else {
+                continue;
+            };
+            let isc = closest_corrections(
+                &self.corrections[channel.prn - 1],
+                eph,
+                context.time,
+            );
+            let rho = cnav_range(self.signal, eph, &isc, context);
+            channel.update(&rho, context.step, context.sampling_period);
+            channel.gain =
+                signal_gain(&rho, context.fixed_gain, context.antenna_pattern);
+        }
+    }
+
+    fn update_navigation(&mut self, context: &SystemContext) {
+        self.select_ephemerides(context.time);
+        self.allocate_channels(context);
+        self.generate_messages(context);
+    }
+
+    fn next_sample(&mut self, sampling_period: f64) -> (i32, i32) {
+        self.channels
+            .iter_mut()
+            .fold((0, 0), |(i_acc, q_acc), channel| {
+                let (ip, qp) = channel.next_sample(sampling_period);
+                (i_acc + ip, q_acc + qp)
+            })
+    }
+
+    fn print_status(&self) {
+        for channel in &self.channels {
+            print_channel_status(
+                &format!("G{:02}", channel.prn),
+                channel.azel(),
+                channel.rho0(),
+                Some(self.signal.name()),
+            );
+        }
+    }
+}
+
+/// Returns the data and pilot components of a satellite's signal.
+fn components(signal: CnavSignal, prn: usize) -> Option<Vec<SignalComponent>> {
+    let component =
+        |code, secondary, data, amplitude, quadrature| SignalComponent {
+            code,
+            secondary,
+            data,
+            amplitude,
+            subcarrier: Subcarrier::Bpsk,
+            quadrature,
+        };
+    Some(match signal {
+        CnavSignal::L2c => {
+            let (cm, cl) = l2c_codes(prn)?;
+            vec![
+                component(cm, Vec::new(), true, 1.0, false),
+                component(cl, Vec::new(), false, 1.0, false),
+            ]
+        }
+        CnavSignal::L5 => {
+            let (i5, q5) = l5_codes(prn)?;
+            let (nh10, nh20) = nh_codes();
+            let amplitude = std::f64::consts::FRAC_1_SQRT_2;
+            vec![
+                component(i5, nh10, true, amplitude, false),
+                component(q5, nh20, false, amplitude, true),
+            ]
+        }
+    })
+}
+
+/// Returns the inter-signal corrections of the CNAV record closest to a
+/// time, or the corrections derived from TGD without CNAV records.
+///
+/// # Arguments
+/// * `corrections` - Corrections of the CNAV records of the satellite
+/// * `eph` - Ephemeris in use
+/// * `time` - Current GPS time
+fn closest_corrections(
+    corrections: &[(GpsTime, InterSignalCorrections)], eph: &Ephemeris,
+    time: &GpsTime,
+) -> InterSignalCorrections {
+    corrections
+        .iter()
+        .min_by(|a, b| {
+            time.diff_secs(&a.0)
+                .abs()
+                .total_cmp(&time.diff_secs(&b.0).abs())
+        })
+        .map_or_else(
+            || {
+                let p_code = |freq: f64| {
+                    eph.tgd * (1.0 - (CARR_FREQ / freq) * (CARR_FREQ / freq))
+                };
+                InterSignalCorrections {
+                    l1ca: 0.0,
+                    l2c: p_code(L2_FREQ),
+                    l5i5: p_code(L5_FREQ),
+                    l5q5: p_code(L5_FREQ),
+                }
+            },
+            |(_, isc)| *isc,
+        )
+}
+
+/// Computes the range of a satellite on a signal at the current receiver
+/// time.
+///
+/// The ionospheric delay of the GPS Klobuchar model is scaled from L1 to the
+/// carrier frequency, and the inter-signal correction of the signal is added
+/// to the clock correction, which includes TGD.
+fn cnav_range(
+    signal: CnavSignal, eph: &Ephemeris, isc: &InterSignalCorrections,
+    context: &SystemContext,
+) -> TimeRange {
+    let mut rho =
+        compute_range(eph, context.ionoutc, context.time, context.location);
+    let ratio = CARR_FREQ / signal.carrier();
+    let iono_delay = rho.iono_delay * ratio * ratio;
+    rho.range += iono_delay - rho.iono_delay - SPEED_OF_LIGHT * signal.isc(isc);
+    rho.iono_delay = iono_delay;
+    rho
+}
@@ -0,0 +1,118 @@
+use constants::{
+    B1I_CODE_FREQ, B1I_CODE_LEN, CA_SEQ_LEN, CODE_FREQ, E1_CODE_LEN,
+    L1C_CODE_LEN, L2CL_CODE_LEN, L2CM_CODE_LEN, L5_CODE_FREQ, L5_CODE_LEN,
+};
+
+pub use crate::galileo::GalileoCodes;
+use crate::{
+    beidou::b1i_code,
+    channel::ca_code,
+    cnav::{l2cl_code, l2cm_code, l5_codes},
+    l1c::l1c_codes,
+};
+
+/// Ranging code of a signal component.
+///
+/// The codes are the ones the simulator spreads its signals with, chip by
+/// chip, at their nominal chip rate and without subcarrier, secondary code
+/// or time multiplexing. Chips of binary value 0 are returned as +1 and
+/// chips of value 1 as -1. The GPS and BeiDou codes are generated by shift
+/// registers or Legendre sequences, while the Galileo E1 memory codes are
+/// read from the code table of the ICD, see `GalileoCodes`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RangingCode {
+    /// GPS L1 C/A code of PRN 1 to 210, assigned to GPS (1 to 63), SBAS (120
+    /// to 158) and QZSS (193 to 202) satellites among others
+    GpsCa,
+    /// GPS L2 civil moderate code (PRN 1 to 32, the PRNs the simulator
+    /// transmits L2C on; the codes of further PRNs are not generated)
+    GpsL2cm,
+    /// GPS L2 civil long code (PRN 1 to 32, as `GpsL2cm`)
+    GpsL2cl,
+    /// GPS L5 in-phase code I5 (PRN 1 to 32, the PRNs the simulator transmits
+    /// L5 on; the codes of further PRNs are not generated)
+    GpsL5i,
+    /// GPS L5 quadrature code Q5 (PRN 1 to 32, as `GpsL5i`)
+    GpsL5q,
+    /// GPS L1C data component code L1CD (PRN 1 to 63)
+    GpsL1cd,
+    /// GPS L1C pilot component code L1CP (PRN 1 to 63)
+    GpsL1cp,
+    /// BeiDou B1I code (PRN 1 to 63)
+    BeidouB1i,
+    /// Galileo E1-B data component memory code (PRN 1 to 50)
+    GalileoE1b,
+    /// Galileo E1-C pilot component memory code (PRN 1 to 50)
+    GalileoE1c,
+}
+
+impl RangingCode {
+    /// Returns the chip rate of the code in Hz.
+    pub fn chip_rate(self) -> f64 {
+        match self {
+            Self::GpsCa
+            | Self::GpsL1cd
+            | Self::GpsL1cp
+            | Self::GalileoE1b
+            | Self::GalileoE1c => CODE_FREQ,
+            Self::GpsL2cm | Self::GpsL2cl => CODE_FREQ / 2.0,
+            Self::GpsL5i | Self::GpsL5q => L5_CODE_FREQ,
+            Self::BeidouB1i => B1I_CODE_FREQ,
+        }
+    }
+
+    /// Returns the length of the code in chips.
+    pub fn length(self) -> usize {
+        match self {
+            Self::GpsCa => CA_SEQ_LEN,
+            Self::GpsL2cm => L2CM_CODE_LEN,
+            Self::GpsL2cl => L2CL_CODE_LEN,
+            Self::GpsL5i | Self::GpsL5q => L5_CODE_LEN,
+            Self::GpsL1cd | Self::GpsL1cp => L1C_CODE_LEN,
+            Self::BeidouB1i => B1I_CODE_LEN,
+            Self::GalileoE1b | Self::GalileoE1c => E1_CODE_LEN,
+        }
+    }
+
+    /// Generates the code of a satellite.
+    ///
+    /// # Arguments
+    /// * `prn` - PRN number of the satellite within its system
+    ///
+    /// # Returns
+    /// The chips of the code (+1 or -1), `length()` of them, or `None` if the
+    /// ICD assigns no code to the PRN or the code is a memory code, see
+    /// `table_chips`
+    pub fn chips(self, prn: usize) -> Option<Vec<i8>> {
+        match self {
+            Self::GpsCa => ca_code(prn),
+            Self::GpsL2cm => l2cm_code(prn),
+            Self::GpsL2cl => l2cl_code(prn),
+            Self::GpsL5i => l5_codes(prn).map(|(i5, _)| i5),
+            Self::GpsL5q => l5_codes(prn).map(|(_, q5)| q5),
+            Self::GpsL1cd => l1c_codes(prn).map(|(l1cd, _)| l1cd),
+            Self::GpsL1cp => l1c_codes(prn).map(|(_, l1cp)| l1cp),
+            Self::BeidouB1i => b1i_code(prn),
+            Self::GalileoE1b | Self::GalileoE1c => None,
+        }
+    }
+
+    /// Returns the code of a satellite, reading memory codes from a table.
+    ///
+    /// # Arguments
+    /// * `prn` - PRN number of the satellite within its system
+    /// * `codes` - Galileo E1 code table
+    ///
+    /// # Returns
+    /// The chips of the code (+1 or -1), `length()` of them, or `None` if the
+    /// ICD assigns no code to the PRN or the table lacks it
+    pub fn table_chips(
+        self, prn: usize, codes: &GalileoCodes,
+    ) -> Option<Vec<i8>> {
+        match self {
+            Self::GalileoE1b => codes.e1b(prn).map(<[i8]>::to_vec),
+            Self::GalileoE1c => codes.e1c(prn).map(<[i8]>::to_vec),
+            _ => self.chips(prn),
+        }
+    }
+}
@@ -0,0 +1,298 @@
+use constants::{GM_EARTH, MAX_SAT, OMEGA_EARTH, PI};
+
+use crate::{
+    datetime::{DateTime, GpsTime},
+    ephemeris::Ephemeris,
+    error::Error,
+    ionoutc::IonoUtc,
+    timeline::EphemerisTimeline,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Number of slots of the baseline constellation.
+const BASELINE_SLOTS: usize = 24;
+
+/// Planes A to E of the spares beyond the expanded slots, by index of their
+/// first slot in `SLOTS`, the planes without an expandable slot first.
+const SPARE_PLANES: [usize; 5] = [0, 8, 16, 4, 12];
+
+/// Expandable slots of the baseline constellation: index of the slot in
+/// `SLOTS`, and argument of latitude in degrees of its fore and aft slots at
+/// `SLOT_EPOCH`. The slots are expanded in this order.
+const EXPANDABLE_SLOTS: [(usize, f64, f64); 3] = [
+    (4, 94.916, 66.356),
+    (13, 282.676, 257.976),
+    (21, 0.456, 334.016),
+];
+
+/// Baseline 24-slot constellation of the GPS SPS Performance Standard: slot,
+/// right ascension of the ascending node and argument of latitude in degrees
+/// at `SLOT_EPOCH`.
+const SLOTS: [(&str, f64, f64); BASELINE_SLOTS] = [
+    ("A1", 272.847, 268.126),
+    ("A2", 272.847, 161.786),
+    ("A3", 272.847, 11.676),
+    ("A4", 272.847, 41.806),
+    ("B1", 332.847, 80.956),
+    ("B2", 332.847, 173.336),
+    ("B3", 332.847, 309.976),
+    ("B4", 332.847, 204.376),
+    ("C1", 32.847, 111.876),
+    ("C2", 32.847, 11.796),
+    ("C3", 32.847, 339.666),
+    ("C4", 32.847, 241.556),
+    ("D1", 92.847, 135.226),
+    ("D2", 92.847, 265.446),
+    ("D3", 92.847, 35.156),
+    ("D4", 92.847, 167.356),
+    ("E1", 152.847, 197.046),
+    ("E2", 152.847, 302.596),
+    ("E3", 152.847, 66.066),
+    ("E4", 152.847, 333.686),
+    ("F1", 212.847, 238.886),
+    ("F2", 212.847, 345.226),
+    ("F3", 212.847, 105.206),
+    ("F4", 212.847, 135.346),
+];
+
+/// Epoch of the slot table, 1 July 1993 00:00 UTC.
+const SLOT_EPOCH: DateTime = DateTime {
+    y: 1993,
+    m: 7,
+    d: 1,
+    hh: 0,
+    mm: 0,
+    sec: 0.0,
+};
+
+/// Greenwich hour angle at `SLOT_EPOCH` (18h 36m 14.4s) in degrees.
+const SLOT_EPOCH_HOUR_ANGLE: f64 = 279.06;
+
+/// Semi-major axis of the reference orbits in meters.
+const SEMI_MAJOR_AXIS: f64 = 26_559_700.0;
+
+/// Inclination of the reference orbits in degrees.
+const INCLINATION: f64 = 55.0;
+
+/// Nodal regression of the reference orbits caused by the Earth oblateness,
+/// in radians per second.
+const OMEGA_DOT: f64 = -7.83e-9;
+
+/// Interval between the reference times of consecutive ephemerides, in
+/// seconds.
+pub const EPHEMERIS_INTERVAL: f64 = 7200.0;
+
+/// GPS-UTC offset in seconds from the first day of a month on (year, month,
+/// offset).
+const LEAP_SECONDS: [(i32, i32, i32); 18] = [
+    (1981, 7, 1),
+    (1982, 7, 2),
+    (1983, 7, 3),
+    (1985, 7, 4),
+    (1988, 1, 5),
+    (1990, 1, 6),
+    (1991, 1, 7),
+    (1992, 7, 8),
+    (1993, 7, 9),
+    (1994, 7, 10),
+    (1996, 1, 11),
+    (1997, 7, 12),
+    (1999, 1, 13),
+    (2006, 1, 14),
+    (2009, 1, 15),
+    (2012, 7, 16),
+    (2015, 7, 17),
+    (2017, 1, 18),
+];
+
+/// Synthetic GPS constellation in the nominal reference orbits.
+///
+/// The first 24 satellites (PRN 1 to 24) occupy the baseline slots A1 to F4
+/// of the GPS SPS Performance Standard: circular orbits of 26 559.7 km
+/// semi-major axis inclined by 55 degrees in six planes. Satellites 25 to 27
+/// expand the slots B1, D2 and F2 in turn into their fore and aft slots of
+/// the expandable 24-slot constellation: the satellite of the baseline slot
+/// moves to the fore slot and the new one takes the aft slot. The standard
+/// defines no slots beyond these, so satellites 28 to 32 are spares placed
+/// in planes A, C, E, B and D, each in the middle of the widest gap of its
+/// plane.
+///
+/// The slots are propagated as Keplerian orbits with nodal regression from
+/// the 1993 epoch of the slot table to any date, and the resulting orbits are
+/// broadcast as ephemerides every two hours without harmonic corrections and
+/// with ideal satellite clocks. Consecutive ephemerides describe the same
+/// orbit, so the simulated ranges are continuous across ephemeris changes.
+#[derive(Debug, Clone)]
+pub struct NominalConstellation {
+    /// Right ascension of the ascending node and argument of latitude at the
+    /// slot epoch in radians, of each satellite (index PRN - 1)
+    slots: Vec<(f64, f64)>,
+}
+
+impl NominalConstellation {
+    /// Creates a nominal constellation.
+    ///
+    /// # Arguments
+    /// * `satellites` - Number of satellites, 24 for the baseline constellation
+    ///   up to `MAX_SAT` with spares (31 for a typical operational
+    ///   constellation)
+    ///
+    /// # Errors
+    /// * `Error::InvalidConstellationSize` - If `satellites` is not in 24..=32
+    pub fn new(satellites: usize) -> Result<Self, Error> {
+        if !(BASELINE_SLOTS..=MAX_SAT).contains(&satellites) {
+            return Err(Error::invalid_constellation_size(satellites));
+        }
+        let mut slots: Vec<(f64, f64)> = SLOTS
+            .iter()
+            .map(|&(_, raan, latitude)| {
+                (raan.to_radians(), latitude.to_radians())
+            })
+            .collect();
+        for &(slot, fore, aft) in
+            EXPANDABLE_SLOTS.iter().take(satellites - BASELINE_SLOTS)
+        {
+            let raan = slots[slot].0;
+            slots[slot].1 = fore.to_radians();
+            slots.push((raan, aft.to_radians()));
+        }
+        for &plane in SPARE_PLANES
+            .iter()
+            .take(satellites.saturating_sub(slots.len()))
+        {
+            let raan = slots[plane].0;
+            let mut latitudes: Vec<f64> = slots
+                .iter()
+                .filter(|(r, _)| (r - raan).abs() < 1e-9)
+                .map(|(_, u)| *u)
+                .collect();
+            latitudes.sort_by(f64::total_cmp);
+            // Widest gap, including the one across 360 degrees
+            let (start, gap) = latitudes
+                .iter()
+                .zip(latitudes.iter().cycle().skip(1))
+                .map(|(a, b)| (*a, (b - a).rem_euclid(2.0 * PI)))
+                .fold(
+                    (0.0, 0.0),
+                    |best, gap| if gap.1 > best.1 { gap } else { best },
+                );
+            slots.push((raan, (start + gap / 2.0).rem_euclid(2.0 * PI)));
+        }
+        Ok(Self { slots })
+    }
+
+    /// Synthesizes the ephemeris of a satellite.
+    ///
+    /// The issue of data counts the two-hour data sets, so that consecutive
+    /// ephemerides differ.
+    ///
+    /// # Arguments
+    /// * `prn` - PRN number of the satellite
+    /// * `toe` - Reference time of the ephemeris and clock
+    ///
+    /// # Returns
+    /// * `Some(Ephemeris)` - The ephemeris, valid for four hours
+    /// * `None` - If the constellation has no satellite with this PRN
+    pub fn ephemeris(&self, prn: usize, toe: &GpsTime) -> Option<Ephemeris> {
+        let &(raan, latitude) = self.slots.get(prn.checked_sub(1)?)?;
+        let elapsed = toe.diff_secs(&GpsTime::from(&SLOT_EPOCH));
+        let mean_motion = (GM_EARTH / SEMI_MAJOR_AXIS.powi(3)).sqrt();
+        // Longitude of the ascending node at the reference time, referred to
+        // the start of the week as broadcast
+        let node = raan + OMEGA_DOT * elapsed
+            - SLOT_EPOCH_HOUR_ANGLE.to_radians()
+            - OMEGA_EARTH * elapsed;
+        let iod = (toe.week * 84 + (toe.sec / EPHEMERIS_INTERVAL) as i32) % 256;
+        let mut eph = Ephemeris {
+            vflg: true,
+            t: DateTime::from(toe),
+            toc: toe.clone(),
+            toe: toe.clone(),
+            iodc: iod,
+            iode: iod,
+            sqrta: SEMI_MAJOR_AXIS.sqrt(),
+            m0: wrap_angle(latitude + mean_motion * elapsed),
+            omg0: wrap_angle(node + OMEGA_EARTH * toe.sec),
+            inc0: INCLINATION / 180.0 * PI,
+            omgdot: OMEGA_DOT,
+            codeL2: 1,
+            fit_interval: 4.0,
+            ..Ephemeris::default()
+        };
+        eph.A = SEMI_MAJOR_AXIS;
+        eph.n = mean_motion;
+        eph.sq1e2 = 1.0;
+        eph.omgkdot = eph.omgdot - OMEGA_EARTH;
+        Some(eph)
+    }
+
+    /// Synthesizes the ephemerides of all satellites over a time span.
+    ///
+    /// The reference times are the even two-hour boundaries from the one at
+    /// or before `start` to the first one after `end`.
+    ///
+    /// # Arguments
+    /// * `start` - Start of the span
+    /// * `end` - End of the span
+    pub fn timeline(
+        &self, start: &GpsTime, end: &GpsTime,
+    ) -> EphemerisTimeline {
+        let mut timeline = EphemerisTimeline::default();
+        let mut toe = GpsTime {
+            week: start.week,
+            sec: (start.sec / EPHEMERIS_INTERVAL).floor() * EPHEMERIS_INTERVAL,
+        };
+        loop {
+            for prn in 1..=self.slots.len() {
+                if let Some(eph) = self.ephemeris(prn, &toe) {
+                    timeline.insert(prn, eph);
+                }
+            }
+            if toe.diff_secs(end) > 0.0 {
+                break;
+            }
+            toe = toe.add_secs(EPHEMERIS_INTERVAL);
+        }
+        timeline
+    }
+
+    /// Synthesizes the ionospheric and UTC parameters at a time.
+    ///
+    /// The Klobuchar coefficients are those broadcast on 1 January 2022, a
+    /// day of moderate solar activity. UTC is offset from GPS time by the leap
+    /// seconds only, up to the last one introduced (18 s since 2017).
+    ///
+    /// # Arguments
+    /// * `time` - Time of the parameters
+    pub fn iono_utc(time: &GpsTime) -> IonoUtc {
+        let date = DateTime::from(time);
+        let dtls = LEAP_SECONDS
+            .iter()
+            .take_while(|&&(y, m, _)| (date.y, date.m) >= (y, m))
+            .last()
+            .map_or(0, |&(_, _, offset)| offset);
+        IonoUtc {
+            enable: true,
+            vflg: true,
+            alpha0: 0.1211e-07,
+            alpha1: -0.7451e-08,
+            alpha2: -0.5960e-07,
+            alpha3: 0.1192e-06,
+            beta0: 0.1167e+06,
+            beta1: -0.2458e+06,
+            beta2: -0.6554e+05,
+            beta3: 0.1114e+07,
+            dtls,
+            tot: (time.sec / 4096.0) as i32 * 4096,
+            week_number: time.week,
+            ..IonoUtc::default()
+        }
+    }
+}
+
+/// Wraps an angle in radians to [-pi, pi).
+pub fn wrap_angle(angle: f64) -> f64 {
+    (angle + PI).rem_euclid(2.0 * PI) - PI
+}
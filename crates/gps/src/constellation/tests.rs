@@ -0,0 +1,130 @@
+use constants::OMEGA_EARTH;
+use test_case::test_case;
+
+use super::{NominalConstellation, SLOT_EPOCH, SLOT_EPOCH_HOUR_ANGLE};
+use crate::{
+    Error,
+    datetime::{DateTime, GpsTime},
+    ephemeris::Ephemeris,
+};
+
+/// Returns the distance between the positions of two ephemerides.
+fn distance(a: &Ephemeris, b: &Ephemeris, time: &GpsTime) -> f64 {
+    let (pos_a, ..) = a.compute_satellite_state(time);
+    let (pos_b, ..) = b.compute_satellite_state(time);
+    pos_a
+        .iter()
+        .zip(pos_b)
+        .map(|(a, b)| (a - b).powi(2))
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// Returns the difference of two angles in degrees, wrapped to [-180, 180).
+fn angle_difference(a: f64, b: f64) -> f64 {
+    (a - b + 180.0).rem_euclid(360.0) - 180.0
+}
+
+#[test_case(24, 1, 272.847, 268.126; "A1")]
+#[test_case(24, 5, 332.847, 80.956; "B1")]
+#[test_case(24, 14, 92.847, 265.446; "D2")]
+#[test_case(24, 22, 212.847, 345.226; "F2")]
+#[test_case(24, 24, 212.847, 135.346; "F4")]
+#[test_case(25, 5, 332.847, 94.916; "B1F")]
+#[test_case(25, 25, 332.847, 66.356; "B1A")]
+#[test_case(26, 14, 92.847, 282.676; "D2F")]
+#[test_case(26, 26, 92.847, 257.976; "D2A")]
+#[test_case(27, 22, 212.847, 0.456; "F2F")]
+#[test_case(27, 27, 212.847, 334.016; "F2A")]
+#[test_case(32, 27, 212.847, 334.016; "F2A with spares")]
+fn slot_at_epoch(
+    satellites: usize, prn: usize, raan: f64, latitude: f64,
+) -> Result<(), Error> {
+    let epoch = GpsTime::from(&SLOT_EPOCH);
+    let eph = NominalConstellation::new(satellites)?
+        .ephemeris(prn, &epoch)
+        .ok_or_else(|| Error::msg("No nominal satellite"))?;
+    // Circular orbits: the mean anomaly is the argument of latitude
+    let u = (eph.m0 + eph.aop).to_degrees();
+    assert!(angle_difference(u, latitude).abs() < 1e-9);
+    // The broadcast node longitude refers to the start of the week
+    let node = (eph.omg0 - OMEGA_EARTH * epoch.sec).to_degrees()
+        + SLOT_EPOCH_HOUR_ANGLE;
+    assert!(angle_difference(node, raan).abs() < 1e-9);
+    Ok(())
+}
+
+#[test]
+fn spares_fill_distinct_slots() -> Result<(), Error> {
+    let epoch = GpsTime::from(&SLOT_EPOCH);
+    let constellation = NominalConstellation::new(32)?;
+    let positions = (1..=32)
+        .map(|prn| {
+            constellation
+                .ephemeris(prn, &epoch)
+                .map(|eph| eph.compute_satellite_state(&epoch).0)
+                .ok_or_else(|| Error::msg("No nominal satellite"))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    for (i, a) in positions.iter().enumerate() {
+        for b in &positions[i + 1..] {
+            let distance = a
+                .iter()
+                .zip(b)
+                .map(|(a, b)| (a - b).powi(2))
+                .sum::<f64>()
+                .sqrt();
+            assert!(distance > 1000e3, "satellites {distance} m apart");
+        }
+    }
+    assert!(constellation.ephemeris(33, &epoch).is_none());
+    assert!(NominalConstellation::new(23).is_err());
+    assert!(NominalConstellation::new(33).is_err());
+    Ok(())
+}
+
+#[test]
+fn propagation_across_week_rollover() -> Result<(), Error> {
+    let constellation = NominalConstellation::new(31)?;
+    // The GPS week 3072 rollover of November 2038
+    let start = GpsTime {
+        week: 3071,
+        sec: 597_900.0,
+    };
+    let timeline = constellation.timeline(&start, &start.add_secs(7500.0));
+    for prn in 1..=31 {
+        let before = timeline.select(prn, &start);
+        let after = timeline.select(prn, &start.add_secs(3600.0));
+        let (Some(before), Some(after)) = (before, after) else {
+            return Err(Error::msg("No nominal ephemeris"));
+        };
+        assert_eq!((before.toe.week, after.toe.week), (3071, 3072));
+        assert_ne!(before.iode, after.iode);
+        // Both data sets describe the same orbit on either side of the
+        // rollover
+        for time in [start.add_secs(1800.0), start.add_secs(7500.0)] {
+            assert!(distance(before, after, &time) < 1e-3);
+        }
+    }
+    Ok(())
+}
+
+#[test_case(1980, 1, 6, 0)]
+#[test_case(1981, 6, 30, 0)]
+#[test_case(1981, 7, 1, 1)]
+#[test_case(1999, 1, 1, 13)]
+#[test_case(2016, 12, 31, 17)]
+#[test_case(2017, 1, 1, 18)]
+#[test_case(2038, 11, 21, 18)]
+fn leap_seconds(y: i32, m: i32, d: i32, expected: i32) {
+    let date = DateTime {
+        y,
+        m,
+        d,
+        hh: 12,
+        mm: 0,
+        sec: 0.0,
+    };
+    let ionoutc = NominalConstellation::iono_utc(&GpsTime::from(&date));
+    assert_eq!(ionoutc.dtls, expected);
+}
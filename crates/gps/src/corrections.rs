@@ -0,0 +1,401 @@
+use std::{
+    fs::File,
+    io::{BufWriter, ErrorKind, Write},
+    net::{TcpListener, TcpStream},
+    path::PathBuf,
+};
+
+use constants::{MAX_SAT, PI, R2D};
+use geometry::{Ecef, Location};
+use rtcm::{
+    GpsEphemeris, MsmEpoch, MsmSatellite, MsmSignal, MsmType, StationArp,
+    message::GPS_L1CA_SIGNAL,
+};
+
+use crate::{
+    Error,
+    datetime::GpsTime,
+    ephemeris::Ephemeris,
+    generator::SatelliteSelection,
+    ionoutc::IonoUtc,
+    observation::NOMINAL_CN0,
+    precise::{PreciseOrbits, PreciseState},
+    propagation::compute_range,
+    table::ANT_PAT_DB,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Interval between station position (1005) messages in seconds.
+const ARP_INTERVAL: f64 = 10.0;
+
+/// Interval after which the ephemeris (1019) of a satellite is repeated, in
+/// seconds.
+const EPHEMERIS_INTERVAL: f64 = 30.0;
+
+/// Configuration of a virtual reference station.
+///
+/// The station observes the simulated sky from a fixed position with a
+/// perfect receiver: zero clock offset, no noise and carrier phases aligned
+/// with the pseudoranges.
+#[derive(Debug, Clone, Copy)]
+pub struct BaseStation {
+    /// Reference station ID (0-4095)
+    pub station_id: u16,
+    /// Antenna reference point in ECEF coordinates
+    pub position: Ecef,
+    /// Observation interval in seconds
+    pub interval: f64,
+    /// Multiple signal message type of the observations
+    pub msm: MsmType,
+}
+
+impl Default for BaseStation {
+    fn default() -> Self {
+        Self {
+            station_id: 0,
+            position: Ecef::default(),
+            interval: 1.0,
+            msm: MsmType::default(),
+        }
+    }
+}
+
+impl BaseStation {
+    /// Sets the antenna reference point from ECEF coordinates.
+    ///
+    /// # Arguments
+    /// * `xyz` - ECEF X, Y and Z in meters
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - Station with the position set
+    /// * `Err(Error)` - If not exactly three coordinates are given
+    ///
+    /// # Errors
+    /// * `Error::WrongPositions` - If not exactly three coordinates are given
+    pub fn with_ecef(mut self, xyz: &[f64]) -> Result<Self, Error> {
+        let [x, y, z] = xyz else {
+            return Err(Error::wrong_positions());
+        };
+        self.position = Ecef::new(*x, *y, *z);
+        Ok(self)
+    }
+
+    /// Sets the antenna reference point from geodetic coordinates.
+    ///
+    /// # Arguments
+    /// * `llh` - Latitude and longitude in degrees and height in meters
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - Station with the position set
+    /// * `Err(Error)` - If not exactly three coordinates are given
+    ///
+    /// # Errors
+    /// * `Error::WrongPositions` - If not exactly three coordinates are given
+    pub fn with_location(mut self, llh: &[f64]) -> Result<Self, Error> {
+        let [latitude, longitude, height] = llh else {
+            return Err(Error::wrong_positions());
+        };
+        self.position = Ecef::from(&Location::new(
+            latitude / R2D,
+            longitude / R2D,
+            *height,
+        ));
+        Ok(self)
+    }
+}
+
+/// Destination of an RTCM 3 stream.
+#[derive(Debug)]
+pub enum RtcmOutput {
+    /// Binary file
+    File(BufWriter<File>),
+    /// TCP server sending the stream to every connected client
+    Server(RtcmServer),
+}
+
+impl RtcmOutput {
+    /// Opens an RTCM output.
+    ///
+    /// Targets of the form `tcp://host:port` or `tcp:port` start a TCP
+    /// server, anything else is taken as a file path.
+    ///
+    /// # Arguments
+    /// * `target` - File path or TCP server address
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - The opened output
+    /// * `Err(Error)` - If the file cannot be created or the address bound
+    ///
+    /// # Errors
+    /// * Returns an error if the file cannot be created or the TCP address
+    ///   cannot be bound
+    pub fn open(target: &str) -> Result<Self, Error> {
+        if let Some(address) = target
+            .strip_prefix("tcp://")
+            .or_else(|| target.strip_prefix("tcp:"))
+        {
+            let address = if address.contains(':') {
+                address.to_string()
+            } else {
+                format!("0.0.0.0:{address}")
+            };
+            let listener = TcpListener::bind(&address)?;
+            listener.set_nonblocking(true)?;
+            eprintln!("RTCM server listening on {}", listener.local_addr()?);
+            return Ok(Self::Server(RtcmServer {
+                listener,
+                clients: Vec::new(),
+            }));
+        }
+        let file = File::create(PathBuf::from(target))?;
+        Ok(Self::File(BufWriter::new(file)))
+    }
+
+    /// Returns whether the output is a TCP server.
+    ///
+    /// Streams to a server are paced in real time.
+    pub fn is_server(&self) -> bool {
+        matches!(self, Self::Server(_))
+    }
+
+    /// Sends one framed message.
+    ///
+    /// # Arguments
+    /// * `frame` - Complete RTCM 3 frame
+    ///
+    /// # Errors
+    /// * Returns an error if the file cannot be written or the server fails to
+    ///   accept clients
+    pub fn send(&mut self, frame: &[u8]) -> Result<(), Error> {
+        match self {
+            Self::File(writer) => writer.write_all(frame)?,
+            Self::Server(server) => server.send(frame)?,
+        }
+        Ok(())
+    }
+
+    /// Flushes buffered messages.
+    ///
+    /// # Errors
+    /// * Returns an error if the file cannot be written
+    pub fn flush(&mut self) -> Result<(), Error> {
+        if let Self::File(writer) = self {
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// TCP server broadcasting an RTCM stream.
+#[derive(Debug)]
+pub struct RtcmServer {
+    /// Non-blocking listening socket
+    listener: TcpListener,
+    /// Connected clients
+    clients: Vec<TcpStream>,
+}
+
+impl RtcmServer {
+    /// Accepts pending clients and sends a frame to every client.
+    ///
+    /// Clients that cannot be written to are disconnected.
+    ///
+    /// # Arguments
+    /// * `frame` - Complete RTCM 3 frame
+    ///
+    /// # Errors
+    /// * Returns an error if accepting clients fails
+    fn send(&mut self, frame: &[u8]) -> std::io::Result<()> {
+        loop {
+            match self.listener.accept() {
+                Ok((stream, peer)) => {
+                    eprintln!("RTCM client connected: {peer}");
+                    stream.set_nodelay(true)?;
+                    self.clients.push(stream);
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) => return Err(e),
+            }
+        }
+        self.clients
+            .retain_mut(|client| client.write_all(frame).is_ok());
+        Ok(())
+    }
+}
+
+/// Encodes the observations of a virtual reference station epoch by epoch.
+pub(crate) struct RtcmEncoder {
+    /// Reference station configuration
+    base: BaseStation,
+    /// Start of continuous tracking of each satellite
+    lock_start: [Option<GpsTime>; MAX_SAT],
+    /// IODE and time of the last ephemeris message of each satellite
+    ephemeris_sent: [Option<(i32, GpsTime)>; MAX_SAT],
+    /// Time of the last station position message
+    arp_sent: Option<GpsTime>,
+}
+
+impl RtcmEncoder {
+    /// Creates an encoder for a reference station.
+    ///
+    /// # Arguments
+    /// * `base` - Reference station configuration
+    ///
+    /// # Returns
+    /// A new `RtcmEncoder`
+    pub fn new(base: BaseStation) -> Self {
+        Self {
+            base,
+            lock_start: std::array::from_fn(|_| None),
+            ephemeris_sent: std::array::from_fn(|_| None),
+            arp_sent: None,
+        }
+    }
+
+    /// Encodes the messages of one epoch.
+    ///
+    /// The station position is sent every 10 seconds and the ephemeris of a
+    /// satellite when it is first observed, when its IODE changes and every
+    /// 30 seconds. The MSM observations of all visible satellites follow.
+    ///
+    /// # Arguments
+    /// * `time` - GPS time of the epoch
+    /// * `ephemerides` - Current ephemeris of each satellite
+    /// * `precise_orbits` - Precise orbits for the observed ranges, if loaded
+    /// * `ionoutc` - Ionospheric parameters
+    /// * `elevation_mask` - Elevation mask in degrees
+    /// * `selection` - Satellites allowed to be observed
+    ///
+    /// # Returns
+    /// * `Ok(frames)` - The framed messages in sending order
+    /// * `Err(Error)` - If a message cannot be encoded
+    ///
+    /// # Errors
+    /// * Returns an error if a value does not fit its RTCM data field
+    pub fn encode_epoch(
+        &mut self, time: &GpsTime, ephemerides: &[Ephemeris],
+        precise_orbits: Option<&PreciseOrbits>, ionoutc: &IonoUtc,
+        elevation_mask: f64, selection: &SatelliteSelection,
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        let mut frames = Vec::new();
+        if self
+            .arp_sent
+            .as_ref()
+            .is_none_or(|sent| time.diff_secs(sent) >= ARP_INTERVAL - 1e-3)
+        {
+            let position = self.base.position;
+            frames.push(
+                StationArp {
+                    station_id: self.base.station_id,
+                    position: [position.x, position.y, position.z],
+                }
+                .encode()?,
+            );
+            self.arp_sent = Some(time.clone());
+        }
+        let mut satellites = Vec::new();
+        for (sv, eph) in ephemerides.iter().enumerate().take(MAX_SAT) {
+            let visible = selection.allows(sv + 1, eph.svhlth)
+                && matches!(
+                    eph.check_visibility(
+                        time,
+                        &self.base.position,
+                        elevation_mask
+                    ),
+                    Some((_, true))
+                );
+            if !visible {
+                self.lock_start[sv] = None;
+                continue;
+            }
+            if self.ephemeris_sent[sv].as_ref().is_none_or(|(iode, sent)| {
+                *iode != eph.iode
+                    || time.diff_secs(sent) >= EPHEMERIS_INTERVAL - 1e-3
+            }) {
+                frames.push(rtcm_ephemeris(sv + 1, eph).encode()?);
+                self.ephemeris_sent[sv] = Some((eph.iode, time.clone()));
+            }
+            let lock_time =
+                time.diff_secs(self.lock_start[sv].get_or_insert(time.clone()));
+            let rho = compute_range(
+                &PreciseState::new(precise_orbits, sv + 1, eph),
+                ionoutc,
+                time,
+                &self.base.position,
+            );
+            let boresight_angle_index =
+                ((90.0 - rho.azel.el * R2D) / 5.0) as usize;
+            let cnr = NOMINAL_CN0
+                + 20.0 * (20_200_000.0 / rho.distance).log10()
+                - ANT_PAT_DB[boresight_angle_index];
+            satellites.push(MsmSatellite {
+                id: (sv + 1) as u8,
+                signals: vec![MsmSignal {
+                    id: GPS_L1CA_SIGNAL,
+                    pseudorange: rho.range,
+                    phase_range: rho.range,
+                    phase_range_rate: rho.rate,
+                    cnr,
+                    lock_time_ms: (lock_time * 1000.0).round() as u32,
+                    half_cycle: false,
+                }],
+            });
+        }
+        frames.push(
+            MsmEpoch {
+                station_id: self.base.station_id,
+                tow_ms: (time.sec * 1000.0).round() as u32,
+                multiple_message: false,
+                msm: self.base.msm,
+                satellites,
+            }
+            .encode()?,
+        );
+        Ok(frames)
+    }
+}
+
+/// Converts a broadcast ephemeris into an RTCM 1019 message.
+///
+/// # Arguments
+/// * `prn` - Satellite PRN
+/// * `eph` - Broadcast ephemeris with angles in radians
+///
+/// # Returns
+/// The message with angles in semicircles
+fn rtcm_ephemeris(prn: usize, eph: &Ephemeris) -> GpsEphemeris {
+    GpsEphemeris {
+        prn: prn as u8,
+        week: eph.toe.week as u16,
+        ura: eph.ura as u8,
+        code_on_l2: eph.codeL2 as u8,
+        idot: eph.idot / PI,
+        iode: eph.iode as u8,
+        toc: eph.toc.sec,
+        af2: eph.af2,
+        af1: eph.af1,
+        af0: eph.af0,
+        iodc: eph.iodc as u16,
+        crs: eph.crs,
+        delta_n: eph.deltan / PI,
+        m0: eph.m0 / PI,
+        cuc: eph.cuc,
+        eccentricity: eph.ecc,
+        cus: eph.cus,
+        sqrt_a: eph.sqrta,
+        toe: eph.toe.sec,
+        cic: eph.cic,
+        omega0: eph.omg0 / PI,
+        cis: eph.cis,
+        i0: eph.inc0 / PI,
+        crc: eph.crc,
+        omega: eph.aop / PI,
+        omega_dot: eph.omgdot / PI,
+        tgd: eph.tgd,
+        health: eph.svhlth as u8,
+        l2p_data_flag: eph.l2p_flag != 0,
+        fit_interval: eph.fit_interval > 4.0,
+    }
+}
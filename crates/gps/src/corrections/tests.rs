@@ -0,0 +1,226 @@
+use std::path::PathBuf;
+
+use constants::SPEED_OF_LIGHT;
+use rtcm::{
+    MsmType,
+    bits::BitReader,
+    message::{
+        GPS_L1CA_SIGNAL, extended_lock_time_indicator, lock_time_indicator,
+        message_number,
+    },
+    parse_frame,
+};
+use test_case::test_case;
+
+use super::{BaseStation, RtcmEncoder};
+use crate::{
+    Error, generator::SignalGeneratorBuilder, precise::PreciseState,
+    propagation::compute_range,
+};
+
+/// Distance light travels in one millisecond (meters)
+const RANGE_MS: f64 = SPEED_OF_LIGHT * 1e-3;
+
+/// Station at 35.681298 N, 139.766247 E, 10 m
+const STATION: [f64; 3] = [35.681_298, 139.766_247, 10.0];
+
+/// Signal cell of a decoded MSM.
+struct Cell {
+    /// Satellite PRN
+    prn: u8,
+    /// Pseudorange in meters
+    pseudorange: f64,
+    /// Phase range in meters
+    phase_range: f64,
+    /// Phase range rate in meters per second (MSM7 only)
+    phase_range_rate: Option<f64>,
+    /// Lock time indicator (DF402 or DF407)
+    lock_time: u64,
+    /// Carrier-to-noise density ratio in dB-Hz
+    cnr: f64,
+}
+
+/// Reads a field that must be present.
+fn get(r: &mut BitReader, bits: usize) -> Result<u64, Error> {
+    r.get_u(bits).ok_or_else(|| Error::msg("Truncated message"))
+}
+
+/// Reads a signed field that must be present.
+fn get_i(r: &mut BitReader, bits: usize) -> Result<f64, Error> {
+    r.get_i(bits)
+        .map(|value| value as f64)
+        .ok_or_else(|| Error::msg("Truncated message"))
+}
+
+/// Decodes the GPS L1 C/A cells of an MSM4 or MSM7 payload.
+fn decode_msm(payload: &[u8], msm: MsmType) -> Result<Vec<Cell>, Error> {
+    let msm7 = msm == MsmType::Msm7;
+    let mut r = BitReader::new(payload);
+    assert_eq!(get(&mut r, 12)?, u64::from(msm.gps_message_number()));
+    assert_eq!(get(&mut r, 12)?, 7);
+    get(&mut r, 30 + 1 + 3 + 7 + 2 + 2 + 1 + 3)?;
+    let satellite_mask = get(&mut r, 64)?;
+    let prns = (1..=64u8)
+        .filter(|&id| satellite_mask >> (64 - u32::from(id)) & 1 == 1)
+        .collect::<Vec<_>>();
+    assert_eq!(get(&mut r, 32)?, 1 << (32 - u32::from(GPS_L1CA_SIGNAL)));
+    for _ in &prns {
+        assert_eq!(get(&mut r, 1)?, 1);
+    }
+    // Satellite data: rough range in whole and 2^-10 ms, rough rate
+    let mut rough = prns.iter().map(|_| [0.0; 2]).collect::<Vec<_>>();
+    for value in &mut rough {
+        value[0] = get(&mut r, 8)? as f64;
+    }
+    if msm7 {
+        get(&mut r, 4 * prns.len())?;
+    }
+    for value in &mut rough {
+        value[0] += get(&mut r, 10)? as f64 / 1024.0;
+    }
+    if msm7 {
+        for value in &mut rough {
+            value[1] = get_i(&mut r, 14)?;
+        }
+    }
+    // Signal data
+    let (pr_bits, pr_scale, cp_bits, cp_scale, lock_bits) = if msm7 {
+        (20, 2f64.powi(-29), 24, 2f64.powi(-31), 10)
+    } else {
+        (15, 2f64.powi(-24), 22, 2f64.powi(-29), 4)
+    };
+    let mut cells = prns
+        .iter()
+        .zip(&rough)
+        .map(|(&prn, &[range_ms, _])| {
+            let fine = get_i(&mut r, pr_bits)? * pr_scale;
+            Ok(Cell {
+                prn,
+                pseudorange: (range_ms + fine) * RANGE_MS,
+                phase_range: range_ms * RANGE_MS,
+                phase_range_rate: None,
+                lock_time: 0,
+                cnr: 0.0,
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    for cell in &mut cells {
+        cell.phase_range += get_i(&mut r, cp_bits)? * cp_scale * RANGE_MS;
+    }
+    for cell in &mut cells {
+        cell.lock_time = get(&mut r, lock_bits)?;
+    }
+    for _ in &cells {
+        assert_eq!(get(&mut r, 1)?, 0);
+    }
+    for cell in &mut cells {
+        cell.cnr = if msm7 {
+            get(&mut r, 10)? as f64 / 16.0
+        } else {
+            get(&mut r, 6)? as f64
+        };
+    }
+    if msm7 {
+        for (cell, [_, rate]) in cells.iter_mut().zip(rough) {
+            cell.phase_range_rate = Some(rate + get_i(&mut r, 15)? * 1e-4);
+        }
+    }
+    Ok(cells)
+}
+
+#[test_case(MsmType::Msm4, 2f64.powi(-24) * RANGE_MS; "msm4")]
+#[test_case(MsmType::Msm7, 2f64.powi(-29) * RANGE_MS; "msm7")]
+fn encode_epoch_round_trip(msm: MsmType, resolution: f64) -> Result<(), Error> {
+    let navigation = PathBuf::from(env!("CARGO_WORKSPACE_DIR"))
+        .join("resources/brdc0010.22n");
+    let generator = SignalGeneratorBuilder::default()
+        .navigation_file(Some(navigation))?
+        .location(Some(STATION.to_vec()))?
+        .time(Some("2022-01-01 00:00:00-00".to_string()))?
+        .duration(Some(10.0))
+        .data_format(Some(8))?
+        .build()?;
+    let base = BaseStation {
+        station_id: 7,
+        msm,
+        ..BaseStation::default()
+    }
+    .with_location(&STATION)?;
+    let mut encoder = RtcmEncoder::new(base);
+    for (epoch, elapsed) in [0.0, 1.0].into_iter().enumerate() {
+        let time = generator.receiver_gps_time.add_secs(elapsed);
+        let frames = encoder.encode_epoch(
+            &time,
+            &generator.ephemerides,
+            None,
+            &generator.ionoutc,
+            generator.elevation_mask,
+            &generator.satellite_selection,
+        )?;
+        let payloads = frames
+            .iter()
+            .map(|frame| parse_frame(frame).map(|(payload, _)| payload))
+            .collect::<Result<Vec<_>, _>>()?;
+        let numbers = payloads
+            .iter()
+            .map(|payload| message_number(payload).unwrap_or_default())
+            .collect::<Vec<_>>();
+        let (Some(msm_payload), Some(&last)) =
+            (payloads.last(), numbers.last())
+        else {
+            panic!("No messages");
+        };
+        assert_eq!(last, msm.gps_message_number());
+        let cells = decode_msm(msm_payload, msm)?;
+        assert!(cells.len() >= 4);
+        if epoch == 0 {
+            // Station position and the ephemeris of every satellite first
+            assert_eq!(numbers[0], 1005);
+            let mut r = BitReader::new(payloads[0]);
+            get(&mut r, 12)?;
+            assert_eq!(get(&mut r, 12)?, 7);
+            assert_eq!(numbers[1..numbers.len() - 1].len(), cells.len());
+            for (payload, cell) in payloads[1..].iter().zip(&cells) {
+                let mut r = BitReader::new(payload);
+                assert_eq!(get(&mut r, 12)?, 1019);
+                assert_eq!(get(&mut r, 6)?, u64::from(cell.prn));
+                get(&mut r, 10 + 4 + 2 + 14)?;
+                let eph = &generator.ephemerides[usize::from(cell.prn) - 1];
+                assert_eq!(get(&mut r, 8)?, eph.iode as u64);
+            }
+        } else {
+            // Only the observations until the repetition intervals elapse
+            assert_eq!(numbers.len(), 1);
+        }
+        let lock_ms = (elapsed * 1000.0) as u32;
+        for cell in &cells {
+            let prn = usize::from(cell.prn);
+            let eph = &generator.ephemerides[prn - 1];
+            let rho = compute_range(
+                &PreciseState::new(None, prn, eph),
+                &generator.ionoutc,
+                &time,
+                &base.position,
+            );
+            assert!(
+                (cell.pseudorange - rho.range).abs() <= resolution,
+                "PRN {prn}: {} {}",
+                cell.pseudorange,
+                rho.range
+            );
+            assert!((cell.phase_range - rho.range).abs() <= resolution);
+            if let Some(rate) = cell.phase_range_rate {
+                assert!((rate - rho.rate).abs() <= 1e-4);
+            }
+            let lock_time = match msm {
+                MsmType::Msm4 => u64::from(lock_time_indicator(lock_ms)),
+                MsmType::Msm7 => {
+                    u64::from(extended_lock_time_indicator(lock_ms))
+                }
+            };
+            assert_eq!(cell.lock_time, lock_time);
+            assert!(cell.cnr > 30.0 && cell.cnr < 60.0);
+        }
+    }
+    Ok(())
+}
@@ -1,7 +1,7 @@
 use constants::{
     SECONDS_IN_DAY, SECONDS_IN_HOUR, SECONDS_IN_MINUTE, SECONDS_IN_WEEK,
 };
-use geometry::Azel;
+use geometry::{Azel, Ecef};
 
 /// Represents time in the GPS time system.
 ///
@@ -165,7 +165,7 @@ impl From<&GpsTime> for DateTime {
         let d = ((f64::from(c) - 122.1) / 365.25) as i32;
         let e = 365 * d + d / 4;
         let f = (f64::from(c - e) / 30.6001) as i32;
-        let d = c - e - (30.6001 * f64::from(f)) as i32;
+        let day = c - e - (30.6001 * f64::from(f)) as i32;
         let m = f - 1 - 12 * (f / 14);
         let y = d - 4715 - (7 + m) / 10;
 
@@ -177,7 +177,7 @@ impl From<&GpsTime> for DateTime {
         Self {
             y,
             m,
-            d,
+            d: day,
             hh,
             mm,
             sec,
@@ -233,4 +233,11 @@ pub struct TimeRange {
 
     /// Ionospheric delay in meters
     pub iono_delay: f64,
+
+    /// Satellite position at transmission time, in the ECEF frame at
+    /// reception time (meters)
+    pub position: Ecef,
+
+    /// Satellite velocity in ECEF coordinates (meters per second)
+    pub velocity: Ecef,
 }
@@ -19,7 +19,7 @@ use crate::datetime::{DateTime, GpsTime};
 /// variables that are pre-computed to improve performance during position
 /// calculations.
 #[allow(non_snake_case)]
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct Ephemeris {
     /// Flag indicating whether this ephemeris data is valid
     pub vflg: bool,
@@ -98,12 +98,32 @@ pub struct Ephemeris {
     /// Group delay differential between L1 and L2 (seconds)
     pub tgd: f64,
 
+    /// Second group delay (seconds): BGD(E1,E5a) of Galileo ephemerides,
+    /// TGD2 of BeiDou ephemerides
+    pub tgd2: f64,
+
     /// Satellite health status
     pub svhlth: i32,
 
     /// Code on L2 channel
     pub codeL2: i32,
 
+    /// User range accuracy index (0-15)
+    pub ura: i32,
+
+    /// L2 P data flag (1 if the navigation data is off on the L2 P-code)
+    pub l2p_flag: i32,
+
+    /// Curve fit interval in hours (0 if not known, meaning 4 hours)
+    pub fit_interval: f64,
+
+    /// Transmission time of message (seconds of GPS week)
+    pub tom: f64,
+
+    /// Age of data offset of the navigation message correction table
+    /// (seconds, 0 if no table is used)
+    pub aodo: f64,
+
     /// --- Derived working variables ---
 
     /// Mean motion - average angular velocity (radians/second)
@@ -155,6 +175,27 @@ impl Ephemeris {
     #[inline]
     pub fn compute_satellite_state(
         &self, time: &GpsTime,
+    ) -> ([f64; 3], [f64; 3], [f64; 2]) {
+        self.compute_orbit_state(time, OMEGA_EARTH)
+    }
+
+    /// Computes satellite position, velocity, and clock correction with the
+    /// Earth rotation rate of a geodetic reference frame.
+    ///
+    /// GPS and Galileo orbits use the WGS-84 rate, see
+    /// `compute_satellite_state`. `omgkdot` must be derived with the same
+    /// rate, and `time` must be in the time scale of the ephemeris.
+    ///
+    /// # Arguments
+    /// * `time` - Time at which to compute the satellite state
+    /// * `omega_earth` - Earth rotation rate of the reference frame (rad/s)
+    ///
+    /// # Returns
+    /// Position, velocity and clock correction as returned by
+    /// `compute_satellite_state`
+    #[inline]
+    pub fn compute_orbit_state(
+        &self, time: &GpsTime, omega_earth: f64,
     ) -> ([f64; 3], [f64; 3], [f64; 2]) {
         // Time normalization function (handles GPS week rollover)
         let normalize_time = |current_time: f64, reference_time: f64| {
@@ -205,7 +246,7 @@ impl Ephemeris {
         let ypk = rk * suk;
         let xpkdot = rkdot * cuk - ypk * ukdot;
         let ypkdot = rkdot * suk + xpk * ukdot;
-        let ok = self.omg0 + tk * self.omgkdot - OMEGA_EARTH * self.toe.sec;
+        let ok = self.omg0 + tk * self.omgkdot - omega_earth * self.toe.sec;
         let (sok, cok) = ok.sin_cos();
         let pos = [
             xpk * cok - ypk * cik * sok,
@@ -284,4 +325,18 @@ impl Ephemeris {
 
         Some((azel, true)) // Visible
     }
+
+    /// Checks whether two ephemerides carry the same broadcast data set.
+    ///
+    /// Data sets are identified by their issue of data (IODE) and time of
+    /// ephemeris.
+    ///
+    /// # Arguments
+    /// * `other` - Ephemeris to compare with
+    ///
+    /// # Returns
+    /// * `true` - If both ephemerides belong to the same data set
+    pub fn same_data_set(&self, other: &Ephemeris) -> bool {
+        self.iode == other.iode && self.toe.diff_secs(&other.toe).abs() < 1.0
+    }
 }
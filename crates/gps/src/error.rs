@@ -12,10 +12,82 @@ pub enum Error {
     #[error("Invalid navigation file: {0}")]
     NavigationFile(String),
 
+    /// Error when a navigation file cannot be read or parsed
+    #[error("Cannot read navigation file {}: {source}", path.display())]
+    NavigationRead {
+        /// Path of the navigation file
+        path: std::path::PathBuf,
+        /// Underlying read or parse error
+        #[source]
+        source: Box<Error>,
+    },
+
+    /// Error when an SP3 orbit or RINEX clock file cannot be read or parsed
+    #[error("Cannot read precise product {}: {source}", path.display())]
+    PreciseRead {
+        /// Path of the orbit or clock file
+        path: std::path::PathBuf,
+        /// Underlying read or parse error
+        #[source]
+        source: Box<Error>,
+    },
+
+    /// Error when a YUMA or SEM almanac file cannot be read or parsed
+    #[error("Cannot read almanac file {}: {source}", path.display())]
+    AlmanacRead {
+        /// Path of the almanac file
+        path: std::path::PathBuf,
+        /// Underlying read or parse error
+        #[source]
+        source: Box<Error>,
+    },
+
+    /// Error when a spreading code table cannot be read or parsed
+    #[error("Cannot read code table {}: {source}", path.display())]
+    CodeRead {
+        /// Path of the code table
+        path: std::path::PathBuf,
+        /// Underlying read or parse error
+        #[source]
+        source: Box<Error>,
+    },
+
+    /// Error when a spreading code table has a malformed line or misses a
+    /// code
+    #[error("Invalid code table: {0}")]
+    InvalidCodeTable(String),
+
+    /// Error when an SBAS message file cannot be read or parsed
+    #[error("Cannot read SBAS message file {}: {source}", path.display())]
+    SbasMessageRead {
+        /// Path of the message file
+        path: std::path::PathBuf,
+        /// Underlying read or parse error
+        #[source]
+        source: Box<Error>,
+    },
+
+    /// Error when an SBAS message file has a malformed line
+    #[error("Invalid SBAS message file: {0}")]
+    InvalidSbasMessage(String),
+
+    /// Error when precise orbits use a time system other than GPS time
+    #[error("Unsupported time system of precise product: {0}")]
+    UnsupportedTimeSystem(String),
+
+    /// Error when precise orbits do not cover the scenario start time
+    #[error("Precise orbits do not cover the scenario start time")]
+    PreciseOrbitsOutOfRange,
+
     /// Error when no ephemeris data is available for a satellite
     #[error("No ephemeris available")]
     NoEphemeris,
 
+    /// Error when a satellite system is enabled but the navigation files
+    /// have no ephemeris of it
+    #[error("No {0} ephemeris available")]
+    NoSystemEphemeris(&'static str),
+
     /// Error when no current set of ephemerides is found for the simulation
     /// time
     #[error("No current set of ephemerides found")]
@@ -25,6 +97,24 @@ pub enum Error {
     #[error("Invalid sampling frequency")]
     InvalidSamplingFrequency,
 
+    /// Error when an invalid RF centre frequency is specified
+    #[error("Invalid RF centre frequency")]
+    InvalidCenterFrequency,
+
+    /// Error when an intermediate frequency is specified that the output
+    /// band cannot hold
+    #[error("Invalid intermediate frequency")]
+    InvalidIntermediateFrequency,
+
+    /// Error when a transmitter impairment is out of range
+    #[error("Invalid transmitter impairment: {0}")]
+    InvalidImpairment(String),
+
+    /// Error when an oversampling factor or receiver bandwidth is out of
+    /// range
+    #[error("Invalid front-end filter: {0}")]
+    InvalidFilter(String),
+
     /// Error when an invalid I/Q data format is specified
     #[error("Invalid I/Q data format")]
     InvalidDataFormat,
@@ -53,6 +143,28 @@ pub enum Error {
     #[error("Invalid delta leap second")]
     InvalidDeltaLeapSecond,
 
+    /// Error when a satellite PRN is out of range
+    #[error("Invalid satellite PRN: {0}")]
+    InvalidPrn(usize),
+
+    /// Error when a satellite health value does not fit in 6 bits
+    #[error("Invalid satellite health: {0}")]
+    InvalidHealth(i32),
+
+    /// Error when the maximum number of satellites is zero or exceeds the
+    /// number of channels
+    #[error("Invalid maximum number of satellites")]
+    InvalidMaxSatellites,
+
+    /// Error when a nominal constellation has fewer than 24 or more than
+    /// `MAX_SAT` satellites
+    #[error("Invalid nominal constellation size: {0}")]
+    InvalidConstellationSize(usize),
+
+    /// Error when a special message is too long or not printable ASCII
+    #[error("Invalid special message: {0:?}")]
+    InvalidSpecialMessage(String),
+
     /// Error when incorrect position data is provided
     #[error("Wrong positions")]
     WrongPositions,
@@ -77,6 +189,10 @@ pub enum Error {
     #[error("RINEX error: {0}")]
     Rinex(#[from] rinex::error::Error),
 
+    /// Error from the RTCM encoding module
+    #[error("RTCM error: {0}")]
+    Rtcm(#[from] rtcm::Error),
+
     /// Error when parsing time values
     #[error("Time parsing error: {0}")]
     TimeParseError(#[from] jiff::Error),
@@ -127,6 +243,30 @@ impl Error {
         Error::InvalidSamplingFrequency
     }
 
+    /// Create a new error for invalid RF centre frequency
+    #[inline]
+    pub fn invalid_center_frequency() -> Self {
+        Error::InvalidCenterFrequency
+    }
+
+    /// Create a new error for invalid intermediate frequency
+    #[inline]
+    pub fn invalid_intermediate_frequency() -> Self {
+        Error::InvalidIntermediateFrequency
+    }
+
+    /// Create a new error for an invalid transmitter impairment
+    #[inline]
+    pub fn invalid_impairment(message: impl Into<String>) -> Self {
+        Error::InvalidImpairment(message.into())
+    }
+
+    /// Create a new error for an invalid front-end filter
+    #[inline]
+    pub fn invalid_filter(message: impl Into<String>) -> Self {
+        Error::InvalidFilter(message.into())
+    }
+
     /// Create a new error for duplicate position setting
     #[inline]
     pub fn duplicate_position() -> Self {
@@ -163,6 +303,54 @@ impl Error {
         Error::InvalidDeltaLeapSecond
     }
 
+    /// Create a new error for an out of range satellite PRN
+    #[inline]
+    pub fn invalid_prn(prn: usize) -> Self {
+        Error::InvalidPrn(prn)
+    }
+
+    /// Create a new error for an invalid satellite health value
+    #[inline]
+    pub fn invalid_health(health: i32) -> Self {
+        Error::InvalidHealth(health)
+    }
+
+    /// Create a new error for an invalid maximum number of satellites
+    #[inline]
+    pub fn invalid_max_satellites() -> Self {
+        Error::InvalidMaxSatellites
+    }
+
+    /// Create a new error for an invalid nominal constellation size
+    #[inline]
+    pub fn invalid_constellation_size(satellites: usize) -> Self {
+        Error::InvalidConstellationSize(satellites)
+    }
+
+    /// Create a new error for an invalid special message
+    #[inline]
+    pub fn invalid_special_message(message: impl Into<String>) -> Self {
+        Error::InvalidSpecialMessage(message.into())
+    }
+
+    /// Create a new error for an invalid spreading code table
+    #[inline]
+    pub fn invalid_code_table(message: impl Into<String>) -> Self {
+        Error::InvalidCodeTable(message.into())
+    }
+
+    /// Create a new error for an invalid SBAS message file
+    #[inline]
+    pub fn invalid_sbas_message(message: impl Into<String>) -> Self {
+        Error::InvalidSbasMessage(message.into())
+    }
+
+    /// Create a new error for a satellite system without ephemerides
+    #[inline]
+    pub fn no_system_ephemeris(system: &'static str) -> Self {
+        Error::NoSystemEphemeris(system)
+    }
+
     /// Create a new error for wrong positions
     #[inline]
     pub fn wrong_positions() -> Self {
@@ -186,6 +374,18 @@ impl Error {
     pub fn no_current_ephemerides() -> Self {
         Error::NoCurrentEphemerides
     }
+
+    /// Create a new error for an unsupported time system of precise products
+    #[inline]
+    pub fn unsupported_time_system(time_system: impl Into<String>) -> Self {
+        Error::UnsupportedTimeSystem(time_system.into())
+    }
+
+    /// Create a new error for precise orbits not covering the start time
+    #[inline]
+    pub fn precise_orbits_out_of_range() -> Self {
+        Error::PreciseOrbitsOutOfRange
+    }
 }
 
 // We'll implement From for specific parsing errors as needed
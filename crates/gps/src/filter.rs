@@ -0,0 +1,260 @@
+use std::f64::consts::PI;
+
+#[cfg(test)]
+mod tests;
+
+/// Two-sided bandwidth of the satellite transmit filter in Hz
+pub const SATELLITE_BANDWIDTH: f64 = 24e6;
+
+/// Taps of the FIR filter per output sample; the filter delay is half of
+/// them in output samples
+const TAPS_PER_SAMPLE: usize = 32;
+
+/// Band-limiting filter between the oversampled composite signal and the
+/// output.
+///
+/// The filter is a low-pass FIR filter with a Blackman window, shifted to
+/// the output frequency of the centre frequency, followed by decimation to
+/// the output rate. Its outputs are only computed at the decimated rate.
+/// The filter delay is compensated by dropping the first outputs, so that
+/// the output samples stay aligned with the generated signal.
+#[derive(Debug, Clone)]
+pub struct FrontEndFilter {
+    /// Real parts of the taps, in the order of the history window
+    taps_re: Vec<f64>,
+    /// Imaginary parts of the taps, empty for a filter centred on 0 Hz
+    taps_im: Vec<f64>,
+    /// I samples of the history, stored twice for contiguous windows
+    history_i: Vec<f64>,
+    /// Q samples of the history, stored twice for contiguous windows
+    history_q: Vec<f64>,
+    /// Position of the oldest sample in the history
+    position: usize,
+    /// Number of input samples per output sample
+    decimation: usize,
+    /// Number of input samples pushed so far
+    count: usize,
+}
+
+impl FrontEndFilter {
+    /// Creates a filter for the given bandwidth and rates.
+    ///
+    /// # Arguments
+    /// * `bandwidth` - Two-sided bandwidth in Hz
+    /// * `center` - Centre of the passband in the output in Hz
+    /// * `input_frequency` - Sampling frequency of the input in Hz
+    /// * `decimation` - Number of input samples per output sample
+    pub fn new(
+        bandwidth: f64, center: f64, input_frequency: f64, decimation: usize,
+    ) -> Self {
+        Self::with_length(
+            bandwidth,
+            center,
+            input_frequency,
+            decimation,
+            TAPS_PER_SAMPLE * decimation + 1,
+        )
+    }
+
+    /// Creates a filter with the given number of taps.
+    ///
+    /// # Arguments
+    /// * `bandwidth` - Two-sided bandwidth in Hz
+    /// * `center` - Centre of the passband in the output in Hz
+    /// * `input_frequency` - Sampling frequency of the input in Hz
+    /// * `decimation` - Number of input samples per output sample
+    /// * `length` - Number of taps, odd for a delay of whole samples
+    fn with_length(
+        bandwidth: f64, center: f64, input_frequency: f64, decimation: usize,
+        length: usize,
+    ) -> Self {
+        let middle = (length / 2) as f64;
+        let cutoff = bandwidth / 2.0 / input_frequency;
+        let window = |n: f64| {
+            let x = 2.0 * PI * n / (length - 1) as f64;
+            0.42 - 0.5 * x.cos() + 0.08 * (2.0 * x).cos()
+        };
+        let taps = (0..length)
+            .map(|n| {
+                let t = n as f64 - middle;
+                let sinc = if t == 0.0 {
+                    2.0 * cutoff
+                } else {
+                    (2.0 * PI * cutoff * t).sin() / (PI * t)
+                };
+                sinc * window(n as f64)
+            })
+            .collect::<Vec<f64>>();
+        // Unity gain in the passband
+        let gain = taps.iter().sum::<f64>();
+        // The window holds the oldest sample first, so the taps run backwards
+        let (mut taps_re, mut taps_im) = (Vec::new(), Vec::new());
+        for (n, tap) in taps.iter().enumerate().rev() {
+            let (sin, cos) = (2.0 * PI * center / input_frequency
+                * (n as f64 - middle))
+                .sin_cos();
+            taps_re.push(tap / gain * cos);
+            taps_im.push(tap / gain * sin);
+        }
+        if center == 0.0 {
+            taps_im.clear();
+        }
+        Self {
+            taps_re,
+            taps_im,
+            history_i: vec![0.0; 2 * length],
+            history_q: vec![0.0; 2 * length],
+            position: 0,
+            decimation,
+            count: 0,
+        }
+    }
+
+    /// Pushes one input sample.
+    ///
+    /// # Arguments
+    /// * `i_acc` - Accumulated I sample
+    /// * `q_acc` - Accumulated Q sample
+    ///
+    /// # Returns
+    /// The filtered I and Q accumulators once per decimation, after the
+    /// filter delay, or `None`
+    pub fn push(&mut self, i_acc: i32, q_acc: i32) -> Option<(i32, i32)> {
+        let length = self.taps_re.len();
+        let (i, q) = (f64::from(i_acc), f64::from(q_acc));
+        self.history_i[self.position] = i;
+        self.history_i[self.position + length] = i;
+        self.history_q[self.position] = q;
+        self.history_q[self.position + length] = q;
+        self.position = (self.position + 1) % length;
+        let delay = length / 2;
+        let count = self.count;
+        self.count += 1;
+        if count < delay || !count.is_multiple_of(self.decimation) {
+            return None;
+        }
+        let window_i = &self.history_i[self.position..self.position + length];
+        let window_q = &self.history_q[self.position..self.position + length];
+        let mut i = dot(&self.taps_re, window_i);
+        let mut q = dot(&self.taps_re, window_q);
+        if !self.taps_im.is_empty() {
+            i -= dot(&self.taps_im, window_q);
+            q += dot(&self.taps_im, window_i);
+        }
+        Some((i.round() as i32, q.round() as i32))
+    }
+}
+
+/// Transmit filters of the satellites, ahead of the receiver filter.
+///
+/// The signals of each carrier are band-limited to `SATELLITE_BANDWIDTH`
+/// around their carrier in the output, at the generation rate, and then
+/// summed. All filters have the same length, so that the signals of the
+/// different carriers stay aligned. A filter passing the whole generated band
+/// leaves the signals unchanged but for its delay, so the builder only sets
+/// up the stage when the generated band is wider than the satellite filter.
+#[derive(Debug, Clone)]
+pub struct SatelliteFilter {
+    /// Index of the filter of each signal source
+    bands: Vec<usize>,
+    /// Filter of each distinct carrier
+    filters: Vec<FrontEndFilter>,
+    /// Sum of the I and Q samples of each carrier for the current sample
+    sums: Vec<(i32, i32)>,
+}
+
+impl SatelliteFilter {
+    /// Creates the transmit filters of the carriers of the signal sources.
+    ///
+    /// # Arguments
+    /// * `carriers` - Carrier frequency of each signal source in Hz
+    /// * `offset` - Frequency of a carrier in the output minus the carrier
+    ///   frequency in Hz, the intermediate minus the centre frequency
+    /// * `input_frequency` - Sampling frequency of the generated signals in Hz
+    pub fn new(carriers: &[f64], offset: f64, input_frequency: f64) -> Self {
+        // As many taps per satellite bandwidth as the receiver filter has
+        // per output sample
+        let length = TAPS_PER_SAMPLE
+            * (input_frequency / SATELLITE_BANDWIDTH).ceil() as usize
+            + 1;
+        let mut distinct: Vec<f64> = Vec::new();
+        let mut bands = Vec::with_capacity(carriers.len());
+        for &carrier in carriers {
+            let band = distinct
+                .iter()
+                .position(|&other| (other - carrier).abs() < 1.0)
+                .unwrap_or_else(|| {
+                    distinct.push(carrier);
+                    distinct.len() - 1
+                });
+            bands.push(band);
+        }
+        let filters = distinct
+            .iter()
+            .map(|&carrier| {
+                FrontEndFilter::with_length(
+                    SATELLITE_BANDWIDTH,
+                    carrier + offset,
+                    input_frequency,
+                    1,
+                    length,
+                )
+            })
+            .collect::<Vec<_>>();
+        Self {
+            bands,
+            sums: vec![(0, 0); filters.len()],
+            filters,
+        }
+    }
+
+    /// Adds the samples of a signal source to the sum of its carrier.
+    ///
+    /// # Arguments
+    /// * `source` - Index of the source in the carriers of `new`
+    /// * `i_acc` - Accumulated I sample of the source
+    /// * `q_acc` - Accumulated Q sample of the source
+    pub fn add(&mut self, source: usize, i_acc: i32, q_acc: i32) {
+        let sum = &mut self.sums[self.bands[source]];
+        sum.0 += i_acc;
+        sum.1 += q_acc;
+    }
+
+    /// Filters the sums of the current sample and starts the next one.
+    ///
+    /// # Returns
+    /// The sum of the filtered carriers, after the filter delay, or `None`
+    pub fn push(&mut self) -> Option<(i32, i32)> {
+        let mut output = Some((0, 0));
+        for (filter, sum) in self.filters.iter_mut().zip(&mut self.sums) {
+            let filtered = filter.push(sum.0, sum.1);
+            *sum = (0, 0);
+            output = output
+                .zip(filtered)
+                .map(|((i, q), (i_f, q_f))| (i + i_f, q + q_f));
+        }
+        output
+    }
+}
+
+/// Computes the dot product of two slices with four partial sums.
+///
+/// # Arguments
+/// * `a` - First slice
+/// * `b` - Second slice of the same length
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    let mut sums = [0.0; 4];
+    let chunks = a.chunks_exact(4).zip(b.chunks_exact(4));
+    for (a, b) in chunks {
+        for k in 0..4 {
+            sums[k] += a[k] * b[k];
+        }
+    }
+    let tail = a.len() / 4 * 4;
+    let rest = a[tail..]
+        .iter()
+        .zip(&b[tail..])
+        .map(|(a, b)| a * b)
+        .sum::<f64>();
+    sums.iter().sum::<f64>() + rest
+}
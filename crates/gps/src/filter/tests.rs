@@ -0,0 +1,176 @@
+use std::f64::consts::PI;
+
+use constants::{B1I_FREQ, CARR_FREQ};
+use test_case::test_case;
+
+use super::{FrontEndFilter, SATELLITE_BANDWIDTH, SatelliteFilter};
+
+/// Amplitude of the test signals in accumulator units.
+const AMPLITUDE: f64 = 1e6;
+
+/// Returns a complex tone of `AMPLITUDE` as accumulator samples.
+fn tone(
+    frequency: f64, sample_frequency: f64, count: usize,
+) -> Vec<(i32, i32)> {
+    (0..count)
+        .map(|n| {
+            let phase = 2.0 * PI * frequency / sample_frequency * n as f64;
+            let (sin, cos) = phase.sin_cos();
+            (
+                (AMPLITUDE * cos).round() as i32,
+                (AMPLITUDE * sin).round() as i32,
+            )
+        })
+        .collect()
+}
+
+/// Returns the mean magnitude of samples relative to `AMPLITUDE`.
+fn gain(samples: &[(i32, i32)]) -> f64 {
+    samples
+        .iter()
+        .map(|&(i, q)| f64::from(i).hypot(f64::from(q)))
+        .sum::<f64>()
+        / samples.len() as f64
+        / AMPLITUDE
+}
+
+/// Filters input samples, returning the outputs.
+fn filter(
+    filter: &mut FrontEndFilter, input: &[(i32, i32)],
+) -> Vec<(i32, i32)> {
+    input
+        .iter()
+        .filter_map(|&(i, q)| filter.push(i, q))
+        .collect()
+}
+
+// 2 MHz receiver filter at 16.368 MHz, decimated by 4
+#[test_case(0.0, 0.0, 1.0)]
+#[test_case(0.0, 0.3e6, 1.0)]
+#[test_case(0.0, -0.6e6, 1.0)]
+#[test_case(0.0, 1.5e6, 0.0)]
+#[test_case(0.0, -1.5e6, 0.0)]
+#[test_case(0.0, 3.0e6, 0.0)]
+#[test_case(0.0, 7.0e6, 0.0)]
+// Shifted to an intermediate frequency of 1 MHz
+#[test_case(1e6, 1.5e6, 1.0)]
+#[test_case(1e6, 0.4e6, 1.0)]
+#[test_case(1e6, -0.5e6, 0.0)]
+#[test_case(1e6, 2.5e6, 0.0)]
+fn passband_and_stopband_gain(center: f64, frequency: f64, expected: f64) {
+    let mut front_end = FrontEndFilter::new(2e6, center, 16.368e6, 4);
+    let input = tone(frequency, 16.368e6, 40_000);
+    let output = filter(&mut front_end, &input);
+    assert_eq!(output.len(), (input.len() - 64).div_ceil(4));
+    // Skip the outputs of the initial history
+    let gain = gain(&output[40..]);
+    assert!((gain - expected).abs() < 1e-3, "gain {gain}");
+}
+
+#[test]
+fn delay_aligned_with_unfiltered_samples() {
+    // Two tones within the passband, sampled at the output rate and
+    // generated four times faster
+    let signal = |time: f64| {
+        let first = (2.0 * PI * 0.2e6 * time).sin_cos();
+        let second = (-2.0 * PI * 0.45e6 * time + 1.0).sin_cos();
+        (
+            (AMPLITUDE * (first.1 + second.1) / 2.0).round() as i32,
+            (AMPLITUDE * (first.0 + second.0) / 2.0).round() as i32,
+        )
+    };
+    let input = (0..40_000)
+        .map(|n| signal(f64::from(n) / 16.368e6))
+        .collect::<Vec<_>>();
+    let mut front_end = FrontEndFilter::new(4.092e6, 0.0, 16.368e6, 4);
+    let output = filter(&mut front_end, &input);
+    // Output k is the sample at time k / 4.092 MHz, as without oversampling
+    for (k, &(i, q)) in output.iter().enumerate().skip(40) {
+        let (i_direct, q_direct) = signal(k as f64 / 4.092e6);
+        assert!(
+            (i - i_direct).abs() < 2000 && (q - q_direct).abs() < 2000,
+            "sample {k}: ({i}, {q}) against ({i_direct}, {q_direct})"
+        );
+    }
+}
+
+#[test_case(0, 0)]
+#[test_case(12, 3)]
+#[test_case(13, 3)]
+#[test_case(15, 4)]
+fn decimation_phase(impulse: usize, peak: usize) {
+    // The outputs are taken at the inputs 0, 4, 8, ...
+    let mut front_end = FrontEndFilter::new(2e6, 0.0, 16.368e6, 4);
+    let mut input = vec![(0, 0); 200];
+    input[impulse] = (1_000_000, 0);
+    let output = filter(&mut front_end, &input);
+    let index = output
+        .iter()
+        .enumerate()
+        .max_by_key(|&(_, &(i, _))| i)
+        .map(|(index, _)| index);
+    assert_eq!(index, Some(peak));
+    if impulse.is_multiple_of(4) {
+        // Linear phase: the response is symmetric around the impulse
+        for k in 1..=peak.min(8) {
+            assert_eq!(output[peak - k], output[peak + k]);
+        }
+    }
+}
+
+#[test]
+fn satellite_filter_follows_each_carrier() {
+    // GPS L1 C/A, Galileo E1 and BeiDou B1I generated at 65.472 MHz around
+    // the L1 centre frequency
+    let offset = -CARR_FREQ;
+    let input_frequency = 65.472e6;
+    let run = |source: usize, frequency: f64| {
+        let mut satellite = SatelliteFilter::new(
+            &[CARR_FREQ, CARR_FREQ, B1I_FREQ],
+            offset,
+            input_frequency,
+        );
+        let output = tone(frequency, input_frequency, 20_000)
+            .into_iter()
+            .filter_map(|(i, q)| {
+                satellite.add(source, i, q);
+                satellite.push()
+            })
+            .collect::<Vec<_>>();
+        gain(&output[200..])
+    };
+    // Within 12 MHz of L1 both L1 sources pass, beyond it they are stopped
+    for source in [0, 1] {
+        assert!((run(source, 6e6) - 1.0).abs() < 1e-3);
+        assert!((run(source, -8e6) - 1.0).abs() < 1e-3);
+        assert!(run(source, -20e6) < 1e-3);
+        assert!(run(source, 18e6) < 1e-3);
+    }
+    // B1I is filtered around its own carrier, 14.322 MHz below L1
+    let b1i = B1I_FREQ - CARR_FREQ;
+    assert!((run(2, -20e6) - 1.0).abs() < 1e-3);
+    assert!((run(2, b1i + 8e6) - 1.0).abs() < 1e-3);
+    assert!(run(2, 6e6) < 1e-3);
+}
+
+#[test]
+fn satellite_filter_spanning_generated_band_only_delays() {
+    // At a generation rate of the satellite bandwidth the filter passes the
+    // whole band, so the stage is not set up
+    let mut satellite =
+        SatelliteFilter::new(&[CARR_FREQ], -CARR_FREQ, SATELLITE_BANDWIDTH);
+    let input = (0..500)
+        .map(|n: i32| {
+            (n * 7919 % 20_011 - 10_000, n * 104_729 % 30_011 - 15_000)
+        })
+        .collect::<Vec<_>>();
+    let output = input
+        .iter()
+        .filter_map(|&(i, q)| {
+            satellite.add(0, i, q);
+            satellite.push()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(output[..], input[..output.len()]);
+    assert_eq!(input.len() - output.len(), 16);
+}
@@ -0,0 +1,16 @@
+//! Galileo E1 Open Service signal generation.
+//!
+//! This module simulates the Galileo E1-B and E1-C signals next to GPS L1 C/A
+//! in the same composite output at 1575.42 MHz. The memory codes are read from
+//! a code table, and the E1-B I/NAV message is built from the Galileo
+//! ephemerides of RINEX 3 or RINEX 4 navigation files.
+
+/// E1-B and E1-C memory code tables
+mod codes;
+/// I/NAV message pages of the E1-B component
+mod inav;
+/// Galileo satellite system driven by the signal generator
+mod system;
+
+pub use codes::GalileoCodes;
+pub use system::GalileoSystem;
@@ -0,0 +1,167 @@
+use std::path::Path;
+
+use constants::E1_CODE_LEN;
+
+use crate::Error;
+
+#[cfg(test)]
+mod tests;
+
+/// Number of Galileo satellites with E1 codes in the ICD tables.
+pub const GALILEO_CODES: usize = 50;
+
+/// E1-C secondary code CS25 (first bit first).
+const CS25: &str = "0011100000001010110110010";
+
+/// Galileo E1-B and E1-C primary memory codes.
+///
+/// The memory codes are not generated by shift registers but listed in
+/// annex C of the Galileo OS SIS ICD, 1023 hexadecimal digits per code. They
+/// are read from a text file holding one code per line as
+///
+/// ```text
+/// E1B 1 <1023 hexadecimal digits>
+/// E1C 1 <1023 hexadecimal digits>
+/// ```
+///
+/// where the columns are the component, the PRN and the code. Empty lines
+/// and lines starting with `#` are skipped. Chips of binary value 0 are
+/// transmitted as +1 and chips of value 1 as -1.
+#[derive(Clone, Default)]
+pub struct GalileoCodes {
+    /// E1-B codes (index PRN - 1)
+    e1b: Vec<Option<Vec<i8>>>,
+    /// E1-C codes (index PRN - 1)
+    e1c: Vec<Option<Vec<i8>>>,
+}
+
+impl GalileoCodes {
+    /// Reads the memory codes from a code table file.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the code table
+    ///
+    /// # Returns
+    /// * `Ok(GalileoCodes)` - The codes of the table
+    /// * `Err(Error)` - If the file cannot be read or parsed
+    ///
+    /// # Errors
+    /// * `Error::CodeRead` - If the file cannot be read or has a malformed
+    ///   line, with its path and the underlying error
+    pub fn read_file(path: &Path) -> Result<Self, Error> {
+        std::fs::read_to_string(path)
+            .map_err(Error::from)
+            .and_then(|text| Self::parse(&text))
+            .map_err(|e| Error::CodeRead {
+                path: path.to_path_buf(),
+                source: Box::new(e),
+            })
+    }
+
+    /// Parses the memory codes of a code table.
+    ///
+    /// # Arguments
+    /// * `text` - Content of the code table
+    ///
+    /// # Returns
+    /// * `Ok(GalileoCodes)` - The codes of the table
+    /// * `Err(Error)` - If a line is malformed
+    ///
+    /// # Errors
+    /// * `Error::InvalidCodeTable` - If a line does not have a known component,
+    ///   a PRN in 1-50 and 1023 hexadecimal digits
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let mut codes = Self {
+            e1b: vec![None; GALILEO_CODES],
+            e1c: vec![None; GALILEO_CODES],
+        };
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = |message: &str| {
+                Error::invalid_code_table(format!(
+                    "line {}: {message}",
+                    number + 1
+                ))
+            };
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let [component, prn, hex] = fields[..] else {
+                return Err(invalid("expected component, PRN and code"));
+            };
+            let table = match component {
+                "E1B" => &mut codes.e1b,
+                "E1C" => &mut codes.e1c,
+                _ => return Err(invalid("unknown component")),
+            };
+            let slot = prn
+                .parse::<usize>()
+                .ok()
+                .and_then(|prn| prn.checked_sub(1))
+                .and_then(|index| table.get_mut(index))
+                .ok_or_else(|| invalid("PRN out of range"))?;
+            *slot = Some(hex_chips(hex).ok_or_else(|| {
+                invalid("code is not 1023 hexadecimal digits")
+            })?);
+        }
+        Ok(codes)
+    }
+
+    /// Returns the E1-B and E1-C codes of a satellite.
+    ///
+    /// # Arguments
+    /// * `prn` - PRN number of the satellite
+    ///
+    /// # Returns
+    /// * `Some((e1b, e1c))` - The chips of both codes (+1 or -1)
+    /// * `None` - If the table lacks one of the codes
+    pub fn e1(&self, prn: usize) -> Option<(&[i8], &[i8])> {
+        Some((self.e1b(prn)?, self.e1c(prn)?))
+    }
+
+    /// Returns the E1-B code of a satellite, or `None` if the table lacks it.
+    pub fn e1b(&self, prn: usize) -> Option<&[i8]> {
+        self.e1b.get(prn.checked_sub(1)?)?.as_deref()
+    }
+
+    /// Returns the E1-C code of a satellite, or `None` if the table lacks it.
+    pub fn e1c(&self, prn: usize) -> Option<&[i8]> {
+        self.e1c.get(prn.checked_sub(1)?)?.as_deref()
+    }
+
+    /// Returns the PRNs for which the table has both E1 codes.
+    pub fn prns(&self) -> Vec<usize> {
+        (1..=GALILEO_CODES)
+            .filter(|&prn| self.e1(prn).is_some())
+            .collect()
+    }
+}
+
+/// Returns the E1-C secondary code CS25 as +1 or -1 chips.
+pub fn e1c_secondary() -> Vec<i8> {
+    CS25.bytes()
+        .map(|bit| if bit == b'0' { 1 } else { -1 })
+        .collect()
+}
+
+/// Converts a hexadecimal memory code into chips.
+///
+/// # Arguments
+/// * `hex` - 1023 hexadecimal digits, most significant bit first
+///
+/// # Returns
+/// The 4092 chips (+1 or -1), or `None` if the digits are malformed
+fn hex_chips(hex: &str) -> Option<Vec<i8>> {
+    if hex.len() * 4 != E1_CODE_LEN {
+        return None;
+    }
+    let mut chips = Vec::with_capacity(E1_CODE_LEN);
+    for digit in hex.chars() {
+        let value = digit.to_digit(16)?;
+        for bit in (0..4).rev() {
+            chips.push(if value >> bit & 1 == 0 { 1 } else { -1 });
+        }
+    }
+    Some(chips)
+}
@@ -0,0 +1,64 @@
+use constants::E1_CODE_LEN;
+
+use super::{GalileoCodes, e1c_secondary};
+use crate::Error;
+
+/// Returns a table line with a code of `E1_CODE_LEN / 4` hexadecimal digits
+/// starting with `head` and padded with zeros.
+fn table_line(component: &str, prn: usize, head: &str) -> String {
+    format!("{component} {prn} {head:0<1023}")
+}
+
+#[test]
+fn chips_follow_the_hexadecimal_digits() -> Result<(), Error> {
+    let text = [
+        "# Test table".to_string(),
+        String::new(),
+        table_line("E1B", 1, "F5D7"),
+        table_line("E1C", 1, "B39"),
+        table_line("E1B", 2, "1"),
+    ]
+    .join("\n");
+    let codes = GalileoCodes::parse(&text)?;
+    let (e1b, e1c) = codes.e1(1).ok_or_else(|| Error::msg("No PRN 1"))?;
+    assert_eq!(e1b.len(), E1_CODE_LEN);
+    // Binary 0 is transmitted as +1 and binary 1 as -1, first bit first
+    let binary = |chips: &[i8]| {
+        chips
+            .iter()
+            .map(|&chip| if chip > 0 { '0' } else { '1' })
+            .collect::<String>()
+    };
+    assert_eq!(binary(&e1b[..16]), "1111010111010111");
+    assert_eq!(binary(&e1c[..12]), "101100111001");
+    assert!(e1b[16..].iter().all(|&chip| chip == 1));
+    // PRN 2 lacks its E1-C code
+    assert!(codes.e1(2).is_none());
+    assert_eq!(codes.prns(), [1]);
+    Ok(())
+}
+
+#[test]
+fn malformed_tables() {
+    for text in [
+        table_line("E5A", 1, "F"),
+        table_line("E1B", 0, "F"),
+        table_line("E1B", 51, "F"),
+        "E1B 1 F5D7".to_string(),
+        table_line("E1B", 1, "G"),
+        "E1B 1".to_string(),
+    ] {
+        assert!(GalileoCodes::parse(&text).is_err(), "{text}");
+    }
+}
+
+#[test]
+fn secondary_code() {
+    // CS25 of the Galileo OS SIS ICD, 380AD90 hexadecimal
+    let cs25 = e1c_secondary();
+    assert_eq!(cs25.len(), 25);
+    let value = cs25
+        .iter()
+        .fold(0u32, |value, &chip| value << 1 | u32::from(chip < 0));
+    assert_eq!(value, 0x0380_ad90 >> 3);
+}
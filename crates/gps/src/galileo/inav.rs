@@ -0,0 +1,270 @@
+use constants::{GST_WEEK_OFFSET, PI};
+
+use crate::{datetime::GpsTime, ephemeris::Ephemeris, signal::Bits};
+
+#[cfg(test)]
+mod tests;
+
+/// Duration of an I/NAV page (even and odd part) in seconds.
+pub const PAGE_SECONDS: f64 = 2.0;
+
+/// Number of E1-B symbols of a page (250 symbols per second).
+pub const PAGE_SYMBOLS: usize = 500;
+
+/// Word types of the 15 pages of a nominal E1-B subframe.
+///
+/// Ephemeris, clock, ionospheric, GST and UTC words are sent once per
+/// 30-second subframe. Almanac pages are replaced by spare words, which carry
+/// the GST as well.
+const SUBFRAME_WORDS: [u8; 15] = [2, 4, 6, 0, 0, 0, 0, 0, 0, 0, 1, 3, 5, 0, 0];
+
+/// Synchronisation pattern at the start of every page part.
+const SYNC_PATTERN: [u8; 10] = [0, 1, 0, 1, 1, 0, 0, 0, 0, 0];
+
+/// Number of bits of a page part before encoding, tail bits included.
+const PART_BITS: usize = 120;
+
+/// Rows of the block interleaver of a page part.
+const INTERLEAVER_ROWS: usize = 8;
+
+/// Columns of the block interleaver of a page part.
+const INTERLEAVER_COLUMNS: usize = 30;
+
+/// Generator polynomial of the first convolutional code output (171 octal).
+const CONV_G1: u32 = 0o171;
+
+/// Generator polynomial of the second convolutional code output (133 octal).
+const CONV_G2: u32 = 0o133;
+
+/// CRC-24Q generator polynomial.
+const CRC24Q_POLY: u32 = 0x0186_4cfb;
+
+/// GST to UTC conversion parameters of word type 6.
+#[derive(Clone, Default)]
+pub struct GstUtc {
+    /// Constant term of the polynomial (seconds)
+    pub a0: f64,
+    /// First-order term of the polynomial (seconds/second)
+    pub a1: f64,
+    /// Leap second count before the leap second adjustment
+    pub dtls: i32,
+    /// Reference time of the UTC data (seconds of week)
+    pub tot: i32,
+    /// Reference week of the UTC data (GPS week)
+    pub wnot: i32,
+    /// Week of the leap second adjustment (GPS week)
+    pub wnlsf: i32,
+    /// Day of week of the leap second adjustment (1-7)
+    pub dn: i32,
+    /// Leap second count after the leap second adjustment
+    pub dtlsf: i32,
+}
+
+/// Content of the I/NAV message of a Galileo satellite on E1-B.
+///
+/// The ephemeris carries the Galileo values: `IODnav` as IODE, the SISA index
+/// as URA, the health and data validity bits of the RINEX health field as
+/// health, and the broadcast group delays BGD(E1,E5b) and BGD(E1,E5a) as
+/// TGD and second group delay.
+pub struct InavMessage<'a> {
+    /// Satellite PRN (SVID)
+    pub prn: usize,
+    /// Broadcast ephemeris of the satellite
+    pub eph: &'a Ephemeris,
+    /// Effective ionisation level coefficients ai0, ai1 and ai2
+    pub iono: [f64; 3],
+    /// GST to UTC conversion parameters
+    pub utc: &'a GstUtc,
+}
+
+impl InavMessage<'_> {
+    /// Generates the E1-B symbols of consecutive pages.
+    ///
+    /// Pages start at even seconds of GST, with the word types following the
+    /// subframe schedule that starts at every 30 seconds. The time of week of
+    /// the GST words is the start of their page.
+    ///
+    /// # Arguments
+    /// * `start` - Start of the first page (an even second of the week)
+    /// * `pages` - Number of pages
+    ///
+    /// # Returns
+    /// `PAGE_SYMBOLS` symbols per page, +1 for binary 0 and -1 for binary 1
+    pub fn symbols(&self, start: &GpsTime, pages: usize) -> Vec<i8> {
+        let first = (start.sec / PAGE_SECONDS).round() as usize;
+        let mut symbols = Vec::with_capacity(pages * PAGE_SYMBOLS);
+        for page in 0..pages {
+            let time = start.add_secs(page as f64 * PAGE_SECONDS);
+            let word_type =
+                SUBFRAME_WORDS[(first + page) % SUBFRAME_WORDS.len()];
+            let word = self.word(word_type, &time);
+            symbols.extend(
+                page_symbols(&word).iter().map(
+                    |&bit| {
+                        if bit == 0 { 1 } else { -1 }
+                    },
+                ),
+            );
+        }
+        symbols
+    }
+
+    /// Builds the 128 bits of an I/NAV word.
+    ///
+    /// # Arguments
+    /// * `word_type` - Word type (0 to 6)
+    /// * `time` - GST at the start of the page
+    fn word(&self, word_type: u8, time: &GpsTime) -> Vec<u8> {
+        let eph = self.eph;
+        let iodnav = eph.iode as u64 & 0x3ff;
+        let wn = (time.week - GST_WEEK_OFFSET) as u64;
+        let tow = time.sec.round() as u64;
+        let mut bits = Bits::default();
+        bits.push(u64::from(word_type), 6);
+        match word_type {
+            1 => {
+                bits.push(iodnav, 10);
+                bits.push((eph.toe.sec / 60.0).round() as u64, 14);
+                bits.push_scaled(eph.m0 / PI, 31, 32);
+                bits.push((eph.ecc * 2f64.powi(33)).round() as u64, 32);
+                bits.push((eph.sqrta * 2f64.powi(19)).round() as u64, 32);
+            }
+            2 => {
+                bits.push(iodnav, 10);
+                bits.push_scaled(eph.omg0 / PI, 31, 32);
+                bits.push_scaled(eph.inc0 / PI, 31, 32);
+                bits.push_scaled(eph.aop / PI, 31, 32);
+                bits.push_scaled(eph.idot / PI, 43, 14);
+            }
+            3 => {
+                bits.push(iodnav, 10);
+                bits.push_scaled(eph.omgdot / PI, 43, 24);
+                bits.push_scaled(eph.deltan / PI, 43, 16);
+                bits.push_scaled(eph.cuc, 29, 16);
+                bits.push_scaled(eph.cus, 29, 16);
+                bits.push_scaled(eph.crc, 5, 16);
+                bits.push_scaled(eph.crs, 5, 16);
+                bits.push(eph.ura as u64, 8);
+            }
+            4 => {
+                bits.push(iodnav, 10);
+                bits.push(self.prn as u64, 6);
+                bits.push_scaled(eph.cic, 29, 16);
+                bits.push_scaled(eph.cis, 29, 16);
+                bits.push((eph.toc.sec / 60.0).round() as u64, 14);
+                bits.push_scaled(eph.af0, 34, 31);
+                bits.push_scaled(eph.af1, 46, 21);
+                bits.push_scaled(eph.af2, 59, 6);
+            }
+            5 => {
+                let health = eph.svhlth as u64;
+                bits.push((self.iono[0] * 4.0).round() as u64, 11);
+                bits.push_scaled(self.iono[1], 8, 11);
+                bits.push_scaled(self.iono[2], 15, 14);
+                // No ionospheric disturbance flags
+                bits.zeros(5);
+                bits.push_scaled(eph.tgd2, 32, 10);
+                bits.push_scaled(eph.tgd, 32, 10);
+                bits.push(health >> 7 & 0x3, 2);
+                bits.push(health >> 1 & 0x3, 2);
+                bits.push(health >> 6 & 0x1, 1);
+                bits.push(health & 0x1, 1);
+                bits.push(wn, 12);
+                bits.push(tow, 20);
+            }
+            6 => {
+                let utc = self.utc;
+                let wnot = (utc.wnot - GST_WEEK_OFFSET) as u64;
+                let wnlsf = (utc.wnlsf - GST_WEEK_OFFSET) as u64;
+                bits.push_scaled(utc.a0, 30, 32);
+                bits.push_scaled(utc.a1, 50, 24);
+                bits.push(utc.dtls as u64, 8);
+                bits.push((utc.tot / 3600) as u64, 8);
+                bits.push(wnot, 8);
+                bits.push(wnlsf, 8);
+                bits.push(utc.dn as u64, 3);
+                bits.push(utc.dtlsf as u64, 8);
+                bits.push(tow, 20);
+            }
+            _ => {
+                // Spare word with valid GST ("10" time field)
+                bits.push(0b10, 2);
+                bits.zeros(88);
+                bits.push(wn, 12);
+                bits.push(tow, 20);
+            }
+        }
+        bits.pad(128);
+        bits.0
+    }
+}
+
+/// Builds the binary symbols of a nominal page from its word.
+///
+/// Both page parts start with the synchronisation pattern, followed by the
+/// convolutionally encoded and interleaved part. The CRC-24Q covers the even
+/// part and the odd part up to its reserved 1 field. The OSNMA, search and
+/// rescue and reserved fields are zero.
+///
+/// # Arguments
+/// * `word` - The 128 bits of the word
+///
+/// # Returns
+/// The `PAGE_SYMBOLS` symbols as binary values
+fn page_symbols(word: &[u8]) -> Vec<u8> {
+    // Even part: even/odd, page type, data (1/2), tail
+    let mut even = vec![0, 0];
+    even.extend_from_slice(&word[..112]);
+    // Odd part: even/odd, page type, data (2/2), reserved 1 (OSNMA), search
+    // and rescue, spare, CRC, reserved 2, tail
+    let mut odd = vec![1, 0];
+    odd.extend_from_slice(&word[112..]);
+    odd.resize(82, 0);
+    let crc = crc24q(even.iter().chain(&odd));
+    odd.extend((0..24).rev().map(|bit| (crc >> bit & 1) as u8));
+    even.resize(PART_BITS, 0);
+    odd.resize(PART_BITS, 0);
+    let mut symbols = Vec::with_capacity(PAGE_SYMBOLS);
+    for part in [even, odd] {
+        symbols.extend_from_slice(&SYNC_PATTERN);
+        symbols.extend(interleave(&encode(&part)));
+    }
+    symbols
+}
+
+/// Computes the CRC-24Q of a bit sequence.
+fn crc24q<'a>(bits: impl Iterator<Item = &'a u8>) -> u32 {
+    let mut crc = 0u32;
+    for &bit in bits {
+        crc ^= u32::from(bit) << 23;
+        crc <<= 1;
+        if crc & 0x0100_0000 != 0 {
+            crc ^= CRC24Q_POLY;
+        }
+    }
+    crc & 0x00ff_ffff
+}
+
+/// Encodes a page part with the rate 1/2, constraint length 7 convolutional
+/// code, whose second output is inverted.
+fn encode(bits: &[u8]) -> Vec<u8> {
+    let mut state = 0u32;
+    let mut symbols = Vec::with_capacity(bits.len() * 2);
+    for &bit in bits {
+        state = (state >> 1) | u32::from(bit) << 6;
+        symbols.push((state & CONV_G1).count_ones() as u8 & 1);
+        symbols.push(!(state & CONV_G2).count_ones() as u8 & 1);
+    }
+    symbols
+}
+
+/// Interleaves the symbols of a page part: written column by column into 8
+/// rows of 30 columns, read row by row.
+fn interleave(symbols: &[u8]) -> Vec<u8> {
+    (0..INTERLEAVER_ROWS)
+        .flat_map(|row| {
+            (0..INTERLEAVER_COLUMNS)
+                .map(move |column| symbols[column * INTERLEAVER_ROWS + row])
+        })
+        .collect()
+}
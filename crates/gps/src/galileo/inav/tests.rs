@@ -0,0 +1,120 @@
+use test_case::test_case;
+
+use super::{
+    CONV_G1, GstUtc, INTERLEAVER_COLUMNS, INTERLEAVER_ROWS, InavMessage,
+    PAGE_SYMBOLS, PART_BITS, SYNC_PATTERN, crc24q, encode, page_symbols,
+};
+use crate::{datetime::GpsTime, ephemeris::Ephemeris};
+
+/// Converts bytes into bits, most significant bit first.
+fn byte_bits(bytes: &[u8]) -> Vec<u8> {
+    bytes
+        .iter()
+        .flat_map(|byte| (0..8).rev().map(move |bit| byte >> bit & 1))
+        .collect()
+}
+
+/// Reverses the block interleaver of a page part.
+fn deinterleave(symbols: &[u8]) -> Vec<u8> {
+    let mut deinterleaved = vec![0; symbols.len()];
+    for row in 0..INTERLEAVER_ROWS {
+        for column in 0..INTERLEAVER_COLUMNS {
+            deinterleaved[column * INTERLEAVER_ROWS + row] =
+                symbols[row * INTERLEAVER_COLUMNS + column];
+        }
+    }
+    deinterleaved
+}
+
+/// Recovers the bits of an encoded page part from the first code output,
+/// the encoder starting from the zero state.
+fn decode(symbols: &[u8]) -> Vec<u8> {
+    let mut state = 0u32;
+    symbols
+        .chunks(2)
+        .map(|pair| {
+            let previous = (state >> 1) & (CONV_G1 & 0x3f);
+            let bit = pair[0] ^ (previous.count_ones() as u8 & 1);
+            state = (state >> 1) | u32::from(bit) << 6;
+            bit
+        })
+        .collect()
+}
+
+#[test_case(b"123456789", 0x00cd_e703; "check value")]
+#[test_case(b"", 0; "empty")]
+fn crc24q_check(data: &[u8], crc: u32) {
+    assert_eq!(crc24q(byte_bits(data).iter()), crc);
+}
+
+#[test]
+fn crc24q_residue_is_zero() {
+    let mut bits = byte_bits(b"Galileo I/NAV");
+    let crc = crc24q(bits.iter());
+    bits.extend((0..24).rev().map(|bit| (crc >> bit & 1) as u8));
+    assert_eq!(crc24q(bits.iter()), 0);
+}
+
+#[test]
+fn encoder_matches_decoder() {
+    let bits = byte_bits(b"tail bits flush the encoder");
+    let symbols = encode(&bits);
+    assert_eq!(symbols.len(), 2 * bits.len());
+    assert_eq!(decode(&symbols), bits);
+    // An all-zero input gives the inverted second output only
+    let zeros = encode(&[0; 6]);
+    assert_eq!(zeros, [0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1]);
+}
+
+#[test]
+fn page_parts() {
+    let eph = Ephemeris {
+        iode: 0x2a5,
+        toe: GpsTime {
+            week: 2190,
+            sec: 7200.0,
+        },
+        ecc: 0.01,
+        sqrta: 5440.6,
+        ..Ephemeris::default()
+    };
+    let message = InavMessage {
+        prn: 11,
+        eph: &eph,
+        iono: [0.0; 3],
+        utc: &GstUtc::default(),
+    };
+    let time = GpsTime {
+        week: 2190,
+        sec: 7220.0,
+    };
+    let word = message.word(1, &time);
+    let symbols = page_symbols(&word);
+    assert_eq!(symbols.len(), PAGE_SYMBOLS);
+    let parts = symbols
+        .chunks(PAGE_SYMBOLS / 2)
+        .map(|part| {
+            assert_eq!(part[..SYNC_PATTERN.len()], SYNC_PATTERN);
+            decode(&deinterleave(&part[SYNC_PATTERN.len()..]))
+        })
+        .collect::<Vec<_>>();
+    let [even, odd] = &parts[..] else {
+        panic!("a page has two parts");
+    };
+    assert_eq!(even.len(), PART_BITS);
+    // Even/odd and page type bits
+    assert_eq!(even[..2], [0, 0]);
+    assert_eq!(odd[..2], [1, 0]);
+    // Word type and data
+    assert_eq!(even[2..114], word[..112]);
+    assert_eq!(odd[2..18], word[112..]);
+    assert_eq!(even[2..8], [0, 0, 0, 0, 0, 1]);
+    // CRC-24Q of the even part and the odd part up to the CRC
+    let crc = odd[82..106]
+        .iter()
+        .fold(0, |crc, &bit| crc << 1 | u32::from(bit));
+    assert_eq!(crc24q(even[..114].iter().chain(&odd[..82])), crc);
+    // Reserved 2 and tail bits
+    assert!(odd[106..].iter().all(|&bit| bit == 0));
+    assert!(even[114..].iter().all(|&bit| bit == 0));
+}
@@ -0,0 +1,420 @@
+use constants::{CARR_FREQ, CODE_FREQ, E1_CODE_LEN, GM_GALILEO, OMEGA_EARTH};
+use rinex::navigation::{NavRecord, SatelliteSystem};
+
+use crate::{
+    Error,
+    datetime::{DateTime, GpsTime},
+    ephemeris::Ephemeris,
+    galileo::{
+        codes::{GALILEO_CODES, GalileoCodes, e1c_secondary},
+        inav::{GstUtc, InavMessage, PAGE_SECONDS},
+    },
+    generator::SatelliteSelection,
+    ionoutc::IonoUtc,
+    propagation::compute_range,
+    signal::{
+        SignalChannel, SignalComponent, SignalSystem, Subcarrier,
+        SystemContext, SystemNavigation, allocate_channels, band_offset,
+        is_visible, print_channel_status, signal_gain,
+    },
+    timeline::EphemerisTimeline,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Number of I/NAV pages of the symbol buffer of a channel.
+///
+/// The buffer starts with the page being transmitted at a frame boundary and
+/// reaches past the next boundary, where it is regenerated.
+const WINDOW_PAGES: usize = 17;
+
+/// Half the bandwidth of the BOC(1,1) main lobes around the carrier (Hz).
+const HALF_BANDWIDTH: f64 = 2.046e6;
+
+/// Share of the BOC(6,1) component in the CBOC modulation, sqrt(1/11).
+const CBOC_BOC6: f64 = 0.301_511_344_577_763_6;
+
+/// Galileo E1 Open Service signals on the GPS L1 frequency.
+///
+/// Each visible satellite transmits the E1-B data component with the I/NAV
+/// message and the E1-C pilot component with its secondary code, both on a
+/// BOC(1,1) subcarrier, or CBOC if enabled. Galileo System Time is simulated
+/// aligned to GPS time, so the GST week is the GPS week minus 1024 and the
+/// time of week is the same.
+///
+/// The satellite orbits and clocks follow the broadcast Galileo ephemerides,
+/// with the ranges delayed by the ionospheric model of the GPS message.
+pub struct GalileoSystem {
+    /// Broadcast ephemerides of all Galileo satellites
+    timeline: EphemerisTimeline,
+    /// Ephemeris in use of each satellite (index PRN - 1)
+    ephemerides: Vec<Option<Ephemeris>>,
+    /// E1-B and E1-C memory codes
+    codes: GalileoCodes,
+    /// E1-C secondary code
+    secondary: Vec<i8>,
+    /// Chip shape of the E1-B and E1-C components
+    subcarriers: (Subcarrier, Subcarrier),
+    /// Effective ionisation level coefficients of the navigation files
+    iono: [f64; 3],
+    /// GST to UTC conversion of the navigation files, if given
+    utc: Option<GstUtc>,
+    /// Satellites allowed to occupy a channel
+    selection: SatelliteSelection,
+    /// E1 carrier frequency minus the output centre frequency, or `None`
+    /// if E1 lies outside the output band
+    carrier_offset: Option<f64>,
+    /// Channels of the visible satellites
+    channels: Vec<SignalChannel>,
+}
+
+impl GalileoSystem {
+    /// Creates the Galileo system from the navigation data.
+    ///
+    /// Only I/NAV ephemerides are used, since F/NAV records describe the
+    /// E5a clock. Satellites without codes in the table are not simulated.
+    ///
+    /// # Arguments
+    /// * `navigation` - Navigation data of the systems other than GPS
+    /// * `codes` - E1-B and E1-C memory codes
+    /// * `cboc` - Whether to use the CBOC instead of the BOC(1,1) subcarrier
+    /// * `selection` - Satellites allowed to occupy a channel, without PRN
+    ///   lists
+    ///
+    /// # Returns
+    /// * `Ok(GalileoSystem)` - The system ready to be initialized
+    /// * `Err(Error)` - If there is no usable Galileo ephemeris
+    ///
+    /// # Errors
+    /// * `Error::NoSystemEphemeris` - If the navigation data holds no I/NAV
+    ///   ephemeris of a satellite with codes
+    /// * Returns an error if the time of clock of a record is out of range
+    pub fn new(
+        navigation: &SystemNavigation, codes: GalileoCodes, cboc: bool,
+        selection: SatelliteSelection,
+    ) -> Result<Self, Error> {
+        let mut timeline = EphemerisTimeline::new(GALILEO_CODES);
+        for record in navigation.ephemerides(SatelliteSystem::Galileo) {
+            let prn = record.satellite.prn;
+            if is_inav(record) && codes.e1(prn).is_some() {
+                let mut eph = convert_galileo_record(record)?;
+                eph.svhlth =
+                    selection
+                        .health_mode
+                        .broadcast_health(prn, eph.svhlth, &[]);
+                timeline.insert(prn, eph);
+            }
+        }
+        if timeline.is_empty() {
+            return Err(Error::no_system_ephemeris("Galileo"));
+        }
+        let iono = navigation
+            .ionospheric_correction("GAL")
+            .map_or([0.0; 3], |gal| {
+                [gal.values[0], gal.values[1], gal.values[2]]
+            });
+        let utc =
+            navigation
+                .time_system_correction("GAUT")
+                .map(|gaut| GstUtc {
+                    a0: gaut.a0,
+                    a1: gaut.a1,
+                    tot: gaut.time,
+                    wnot: gaut.week,
+                    ..GstUtc::default()
+                });
+        let subcarriers = if cboc {
+            (Subcarrier::Cboc(CBOC_BOC6), Subcarrier::Cboc(-CBOC_BOC6))
+        } else {
+            (Subcarrier::Boc(1), Subcarrier::Boc(1))
+        };
+        Ok(Self {
+            timeline,
+            ephemerides: vec![None; GALILEO_CODES],
+            codes,
+            secondary: e1c_secondary(),
+            subcarriers,
+            iono,
+            utc,
+            selection,
+            carrier_offset: None,
+            channels: Vec::new(),
+        })
+    }
+
+    /// Takes the ephemeris due at a time into use for every satellite.
+    fn select_ephemerides(&mut self, time: &GpsTime) {
+        for (sv, current) in self.ephemerides.iter_mut().enumerate() {
+            if let Some(eph) = self.timeline.select(sv + 1, time) {
+                *current = Some(eph.clone());
+            }
+        }
+    }
+
+    /// Releases the channels of satellites below the elevation mask and
+    /// allocates channels to the newly visible satellites.
+    fn allocate_channels(&mut self, context: &SystemContext) {
+        let Some(carrier_offset) = self.carrier_offset else {
+            return;
+        };
+        let (codes, subcarriers, secondary) =
+            (&self.codes, self.subcarriers, &self.secondary);
+        allocate_channels(
+            &mut self.channels,
+            &self.ephemerides,
+            1,
+            &self.selection,
+            |_, eph| is_visible(eph, context),
+            |prn, eph| {
+                let (e1b, e1c) = codes.e1(prn)?;
+                let components = vec![
+                    SignalComponent {
+                        code: e1b.to_vec(),
+                        secondary: Vec::new(),
+                        data: true,
+                        amplitude: std::f64::consts::FRAC_1_SQRT_2,
+                        subcarrier: subcarriers.0,
+                        quadrature: false,
+                    },
+                    SignalComponent {
+                        code: e1c.to_vec(),
+                        secondary: secondary.clone(),
+                        data: false,
+                        amplitude: -std::f64::consts::FRAC_1_SQRT_2,
+                        subcarrier: subcarriers.1,
+                        quadrature: false,
+                    },
+                ];
+                let mut channel = SignalChannel::new(
+                    prn,
+                    components,
+                    CODE_FREQ,
+                    CARR_FREQ,
+                    carrier_offset,
+                    E1_CODE_LEN as u64,
+                );
+                channel.start(compute_range(
+                    eph,
+                    context.ionoutc,
+                    context.time,
+                    context.location,
+                ));
+                Some(channel)
+            },
+        );
+    }
+
+    /// Regenerates the I/NAV symbols of all channels for the frame starting
+    /// at a time.
+    fn generate_messages(&mut self, context: &SystemContext) {
+        // First page of the buffer: the page transmitted a second before the
+        // current reception time
+        let mut start = context.time.add_secs(-1.0);
+        start.sec = (start.sec / PAGE_SECONDS).floor() * PAGE_SECONDS;
+        let utc = self.utc.clone().map_or_else(
+            || gst_utc(context.ionoutc),
+            |utc| GstUtc {
+                dtls: context.ionoutc.dtls,
+                wnlsf: context.ionoutc.wnlsf,
+                dn: context.ionoutc.day_number,
+                dtlsf: context.ionoutc.dtlsf,
+                ..utc
+            },
+        );
+        for channel in &mut self.channels {
+            let Some(eph) = &self.ephemerides[channel.prn - 1] else {
+                continue;
+            };
+            let message = InavMessage {
+                prn: channel.prn,
+                eph,
+                iono: self.iono,
+                utc: &utc,
+            };
+            channel.set_symbols(&start, message.symbols(&start, WINDOW_PAGES));
+        }
+    }
+}
+
+impl SignalSystem for GalileoSystem {
+    fn name(&self) -> &'static str {
+        "Galileo"
+    }
+
+    fn carrier_frequency(&self) -> f64 {
+        CARR_FREQ
+    }
+
+    fn initialize(&mut self, context: &SystemContext) {
+        self.carrier_offset = band_offset(context, CARR_FREQ, HALF_BANDWIDTH);
+        if self.carrier_offset.is_none() {
+            eprintln!(
+                "Warning: Galileo E1 lies outside the output band; Galileo \
+                 satellites are not simulated."
+            );
+        }
+        self.select_ephemerides(context.time);
+        self.allocate_channels(context);
+        self.generate_messages(context);
+    }
+
+    fn update_channels(&mut self, context: &SystemContext) {
+        for channel in &mut self.channels {
+            let Some(eph) = &self.ephemerides[channel.prn - 1] else {
+                continue;
+            };
+            let rho = compute_range(
+                eph,
+                context.ionoutc,
+                context.time,
+                context.location,
+            );
+            channel.update(&rho, context.step, context.sampling_period);
+            channel.gain =
+                signal_gain(&rho, context.fixed_gain, context.antenna_pattern);
+        }
+    }
+
+    fn update_navigation(&mut self, context: &SystemContext) {
+        self.select_ephemerides(context.time);
+        self.allocate_channels(context);
+        self.generate_messages(context);
+    }
+
+    fn next_sample(&mut self, sampling_period: f64) -> (i32, i32) {
+        self.channels
+            .iter_mut()
+            .fold((0, 0), |(i_acc, q_acc), channel| {
+                let (ip, qp) = channel.next_sample(sampling_period);
+                (i_acc + ip, q_acc + qp)
+            })
+    }
+
+    fn print_status(&self) {
+        for channel in &self.channels {
+            print_channel_status(
+                &format!("E{:02}", channel.prn),
+                channel.azel(),
+                channel.rho0(),
+                None,
+            );
+        }
+    }
+}
+
+/// Returns whether a Galileo record holds I/NAV (E1-B) data.
+fn is_inav(record: &NavRecord) -> bool {
+    let data_sources = record.orbit(5)[1] as u32;
+    record
+        .message
+        .as_deref()
+        .is_none_or(|message| message == "INAV")
+        && data_sources & 0b101 != 0
+}
+
+/// Converts a Galileo RINEX ephemeris record into the ephemeris used by the
+/// simulation, with its derived orbit constants.
+///
+/// The Galileo parameters are kept in the GPS fields: `IODnav` as IODE and
+/// IODC, the SISA index as URA, the health and data validity bits as health,
+/// BGD(E1,E5b) as TGD and BGD(E1,E5a) as second group delay. GST is taken as
+/// GPS time.
+///
+/// # Arguments
+/// * `record` - Galileo ephemeris record
+///
+/// # Returns
+/// * `Ok(Ephemeris)` - The converted ephemeris
+/// * `Err(Error)` - If the time of clock cannot be converted
+///
+/// # Errors
+/// * Returns an error if the time of clock is out of range
+pub fn convert_galileo_record(record: &NavRecord) -> Result<Ephemeris, Error> {
+    let utc_datetime = DateTime::from(record.epoch.in_tz("UTC")?);
+    let gps_time = GpsTime::from(&utc_datetime);
+    let [iodnav, crs, deltan, m0] = record.orbit(1);
+    let [cuc, ecc, cus, sqrta] = record.orbit(2);
+    let [toe, cic, omg0, cis] = record.orbit(3);
+    let [inc0, crc, aop, omgdot] = record.orbit(4);
+    let [idot, _, week, _] = record.orbit(5);
+    let [sisa, health, bgd_e5a, bgd_e5b] = record.orbit(6);
+    let mut eph = Ephemeris {
+        vflg: true,
+        t: utc_datetime,
+        toc: gps_time,
+        toe: GpsTime {
+            week: week as i32,
+            sec: toe,
+        },
+        iodc: iodnav as i32,
+        iode: iodnav as i32,
+        deltan,
+        cuc,
+        cus,
+        cic,
+        cis,
+        crc,
+        crs,
+        ecc,
+        sqrta,
+        m0,
+        omg0,
+        inc0,
+        aop,
+        omgdot,
+        idot,
+        af0: record.values[0],
+        af1: record.values[1],
+        af2: record.values[2],
+        tgd: bgd_e5b,
+        tgd2: bgd_e5a,
+        svhlth: health as i32,
+        ura: sisa_index(sisa),
+        ..Ephemeris::default()
+    };
+    eph.A = eph.sqrta * eph.sqrta;
+    eph.n = (GM_GALILEO / (eph.A * eph.A * eph.A)).sqrt() + eph.deltan;
+    eph.sq1e2 = (1.0 - eph.ecc * eph.ecc).sqrt();
+    eph.omgkdot = eph.omgdot - OMEGA_EARTH;
+    Ok(eph)
+}
+
+/// Encodes a signal-in-space accuracy as the SISA index of the Galileo OS
+/// SIS ICD.
+///
+/// # Arguments
+/// * `accuracy` - SISA in meters as written in RINEX files
+///
+/// # Returns
+/// The index of the smallest SISA value holding the accuracy, or 255 (no
+/// accuracy prediction available) for negative or unknown accuracies
+fn sisa_index(accuracy: f64) -> i32 {
+    let centimetres = (accuracy * 100.0).round();
+    if !(0.0..=600.0).contains(&centimetres) {
+        255
+    } else if centimetres < 50.0 {
+        centimetres as i32
+    } else if centimetres < 100.0 {
+        50 + ((centimetres - 50.0) / 2.0).ceil() as i32
+    } else if centimetres < 200.0 {
+        75 + ((centimetres - 100.0) / 4.0).ceil() as i32
+    } else {
+        100 + ((centimetres - 200.0) / 16.0).ceil() as i32
+    }
+}
+
+/// Returns the GST to UTC parameters of the GPS message, for navigation
+/// files without a GAUT correction.
+fn gst_utc(ionoutc: &IonoUtc) -> GstUtc {
+    GstUtc {
+        a0: ionoutc.A0,
+        a1: ionoutc.A1,
+        dtls: ionoutc.dtls,
+        tot: ionoutc.tot,
+        wnot: ionoutc.week_number,
+        wnlsf: ionoutc.wnlsf,
+        dn: ionoutc.day_number,
+        dtlsf: ionoutc.dtlsf,
+    }
+}
@@ -0,0 +1,42 @@
+use test_case::test_case;
+
+use super::CBOC_BOC6;
+use crate::signal::Subcarrier;
+
+/// BOC(1,1) share of the CBOC(6,1,1/11) modulation, sqrt(10/11).
+const CBOC_BOC1: f64 = 0.953_462_589_245_592_4;
+
+// The BOC(6,1) subcarrier changes sign every twelfth of a chip
+#[test_case(0.0, CBOC_BOC1 + CBOC_BOC6, CBOC_BOC1 - CBOC_BOC6)]
+#[test_case(0.05, CBOC_BOC1 + CBOC_BOC6, CBOC_BOC1 - CBOC_BOC6)]
+#[test_case(0.1, CBOC_BOC1 - CBOC_BOC6, CBOC_BOC1 + CBOC_BOC6)]
+#[test_case(0.45, CBOC_BOC1 - CBOC_BOC6, CBOC_BOC1 + CBOC_BOC6)]
+#[test_case(0.5, -CBOC_BOC1 + CBOC_BOC6, -CBOC_BOC1 - CBOC_BOC6)]
+#[test_case(0.6, -CBOC_BOC1 - CBOC_BOC6, -CBOC_BOC1 + CBOC_BOC6)]
+#[test_case(0.99, -CBOC_BOC1 - CBOC_BOC6, -CBOC_BOC1 + CBOC_BOC6)]
+fn cboc_levels(fraction: f64, e1b: f64, e1c: f64) {
+    let data = Subcarrier::Cboc(CBOC_BOC6).level(fraction);
+    let pilot = Subcarrier::Cboc(-CBOC_BOC6).level(fraction);
+    assert!((data - e1b).abs() < 1e-12, "{data} != {e1b}");
+    assert!((pilot - e1c).abs() < 1e-12, "{pilot} != {e1c}");
+}
+
+#[test]
+fn cboc_power() {
+    // 1/11 of the power in the BOC(6,1) component, no mean over a chip
+    assert!((CBOC_BOC6 * CBOC_BOC6 - 1.0 / 11.0).abs() < 1e-15);
+    let samples = 1200;
+    for boc6 in [CBOC_BOC6, -CBOC_BOC6] {
+        let levels = (0..samples)
+            .map(|i| {
+                Subcarrier::Cboc(boc6)
+                    .level((f64::from(i) + 0.5) / f64::from(samples))
+            })
+            .collect::<Vec<_>>();
+        let mean = levels.iter().sum::<f64>() / f64::from(samples);
+        let power = levels.iter().map(|level| level * level).sum::<f64>()
+            / f64::from(samples);
+        assert!(mean.abs() < 1e-12);
+        assert!((power - 1.0).abs() < 1e-12);
+    }
+}
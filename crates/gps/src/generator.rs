@@ -6,11 +6,20 @@
 
 /// Builder pattern implementation for signal generator configuration
 mod builder;
+/// Satellite visibility and DOP planning
+mod plan;
+/// Satellite selection and health handling
+mod selection;
 /// Core signal generation implementation
 mod signal_generator;
+#[cfg(test)]
+mod tests;
 /// Utility functions and types for signal generation
 mod utils;
 
 pub use builder::SignalGeneratorBuilder;
+pub use plan::{PlanEpoch, PlanFormat, PlannedSatellite, VisibilityPlan};
+pub use selection::{HealthMode, SatelliteSelection};
 pub use signal_generator::SignalGenerator;
 pub use utils::MotionMode;
+pub(crate) use utils::{set_derived_constants, ura_index};
@@ -1,34 +1,39 @@
 use std::path::PathBuf;
 
-use constants::{EPHEM_ARRAY_SIZE, MAX_CHAN, MAX_SAT, R2D, SECONDS_IN_HOUR};
+use constants::{CARR_FREQ, MAX_CHAN, MAX_SAT, R2D};
 use geometry::{Ecef, Location};
 use parsing::{read_nmea_gga, read_user_motion, read_user_motion_llh};
+use rinex::almanac::AlmanacRecord;
 
 use crate::{
     Error,
+    beidou::BeidouSystem,
+    cnav::{CnavSignal, CnavSystem},
+    constellation::NominalConstellation,
     datetime::{DateTime, GpsTime},
     ephemeris::Ephemeris,
+    filter::{FrontEndFilter, SATELLITE_BANDWIDTH, SatelliteFilter},
+    galileo::{GalileoCodes, GalileoSystem},
     generator::{
+        selection::{HealthMode, SatelliteSelection},
         signal_generator::SignalGenerator,
-        utils::{MotionMode, read_navigation_data},
+        utils::{
+            MotionMode, almanac_timeline, read_almanac_files, read_clock_files,
+            read_navigation_data, read_sp3_files,
+        },
     },
+    glonass::GlonassSystem,
+    impairment::{ImpairmentStage, Impairments},
     io::DataFormat,
     ionoutc::IonoUtc,
+    l1c::L1cSystem,
+    precise::PreciseOrbits,
+    qzss::QzssSystem,
+    sbas::{SbasMessages, SbasSystem},
+    signal::{SignalSystem, SystemNavigation},
+    superframe::SuperframePages,
+    truth::TruthFormat,
 };
-/// Type alias for ephemeris-related data used in the builder.
-///
-/// This tuple contains:
-/// - The number of valid ephemeris sets
-/// - Ionospheric and UTC parameters
-/// - A 2D array of ephemeris data organized by time set and satellite PRN
-///
-/// This is the same structure as the `Data` type in the utils module,
-/// but defined here for use within the builder.
-type EphemerisRelatedData = (
-    usize,
-    IonoUtc,
-    Box<[[Ephemeris; MAX_SAT]; EPHEM_ARRAY_SIZE]>,
-);
 /// Builder for creating and configuring a `SignalGenerator`.
 ///
 /// This struct implements the builder pattern for creating a `SignalGenerator`
@@ -59,8 +64,10 @@ type EphemerisRelatedData = (
 pub struct SignalGeneratorBuilder {
     /// Path to the output file for I/Q samples
     output_file: Option<PathBuf>,
-    /// Ephemeris data, ionospheric parameters, and UTC parameters
-    ephemerides_data: Option<EphemerisRelatedData>,
+    /// Paths to the RINEX navigation files, read when building
+    navigation_files: Option<Vec<PathBuf>>,
+    /// Whether malformed navigation file lines are skipped
+    lenient_navigation: Option<bool>,
     /// Leap second parameters [week, day, `delta_t`]
     leap: Option<Vec<i32>>,
     /// Receiver positions (static or dynamic)
@@ -85,6 +92,82 @@ pub struct SignalGeneratorBuilder {
     ionospheric_disable: Option<bool>,
     /// Whether to enable verbose output
     verbose: Option<bool>,
+    /// PRNs allowed to be simulated
+    include_prns: Option<Vec<usize>>,
+    /// PRNs that must not be simulated
+    exclude_prns: Option<Vec<usize>>,
+    /// Maximum number of simultaneously simulated satellites
+    max_satellites: Option<usize>,
+    /// Handling of unhealthy satellites
+    health_mode: Option<HealthMode>,
+    /// Elevation mask in degrees
+    elevation_mask: Option<f64>,
+    /// Path to the truth log file
+    truth_file: Option<PathBuf>,
+    /// Truth log output format
+    truth_format: Option<TruthFormat>,
+    /// Path to the RINEX observation file
+    observation_file: Option<PathBuf>,
+    /// RINEX observation interval in seconds
+    observation_interval: Option<f64>,
+    /// Whether carrier phases start aligned with the pseudoranges
+    phase_from_range: Option<bool>,
+    /// Per-PRN overrides of the broadcast health word
+    health_override: Option<Vec<(usize, i32)>>,
+    /// Precise orbits and clocks for the simulated ranges
+    precise_orbits: Option<PreciseOrbits>,
+    /// Almanacs used as ephemerides when no navigation file is set
+    almanac: Option<Vec<AlmanacRecord>>,
+    /// Nominal constellation used when neither navigation nor almanac files
+    /// are set
+    nominal_constellation: Option<NominalConstellation>,
+    /// Whether subframes 4 and 5 follow the full 25-page schedule
+    superframe_pages: Option<bool>,
+    /// Special message of subframe 4 page 17
+    special_message: Option<String>,
+    /// Whether subframes 1 and 2 broadcast zero URA index, L2 P data flag,
+    /// fit interval flag and age of data offset as gps-sdr-sim
+    compatible_subframes: Option<bool>,
+    /// Galileo E1-B and E1-C memory codes, enabling Galileo E1 signals
+    galileo_codes: Option<GalileoCodes>,
+    /// Whether Galileo E1 uses the CBOC instead of the BOC(1,1) subcarrier
+    galileo_cboc: Option<bool>,
+    /// Whether to add the BeiDou B1I signals
+    beidou: Option<bool>,
+    /// Whether to add the GLONASS L1OF signals
+    glonass: Option<bool>,
+    /// Whether to add the QZSS L1 C/A signals
+    qzss: Option<bool>,
+    /// Whether to add the SBAS L1 signals
+    sbas: Option<bool>,
+    /// Messages broadcast by the SBAS satellites
+    sbas_messages: Option<SbasMessages>,
+    /// Whether to add the GPS L2C signals
+    l2c: Option<bool>,
+    /// Whether to add the GPS L5 signals
+    l5: Option<bool>,
+    /// Whether to add the GPS L1C signals
+    l1c: Option<bool>,
+    /// RF centre frequency of the output in Hz
+    center_frequency: Option<f64>,
+    /// Frequency the centre frequency is shifted to in the output in Hz
+    intermediate_frequency: Option<f64>,
+    /// Whether to write real samples instead of I/Q samples
+    real_output: Option<bool>,
+    /// Transmitter I/Q gain imbalance in dB and phase imbalance in degrees
+    iq_imbalance: Option<(f64, f64)>,
+    /// Transmitter DC offset of the I and Q samples
+    dc_offset: Option<(f64, f64)>,
+    /// Transmitter LO frequency offset in ppm
+    frequency_offset: Option<f64>,
+    /// Transmitter LO phase noise mask as (offset in Hz, dBc/Hz) points
+    phase_noise: Option<Vec<(f64, f64)>>,
+    /// Whether to compensate the transmitter impairments instead
+    predistortion: Option<bool>,
+    /// Number of generated samples per output sample
+    oversampling: Option<usize>,
+    /// Two-sided receiver pre-correlation bandwidth in Hz
+    receiver_bandwidth: Option<f64>,
 }
 impl SignalGeneratorBuilder {
     /// Parses a datetime string into a timestamp.
@@ -103,42 +186,161 @@ impl SignalGeneratorBuilder {
         Ok(time)
     }
 
+    /// Returns the start time if set, or else the current time.
+    ///
+    /// # Errors
+    /// * Returns an error if the current time cannot be converted to UTC
+    fn start_or_now(start: Option<&GpsTime>) -> Result<GpsTime, Error> {
+        if let Some(start) = start {
+            return Ok(start.clone());
+        }
+        let now = jiff::Timestamp::now().in_tz("UTC")?;
+        Ok(GpsTime::from(&DateTime::from(now)))
+    }
+
+    /// Returns the duration of the scenario in seconds, from the duration if
+    /// set or else from the number of receiver positions.
+    fn scenario_duration(&self) -> f64 {
+        self.duration.unwrap_or_else(|| {
+            self.positions.as_ref().map_or(0.0, |p| p.len() as f64)
+                * self.sample_rate.unwrap_or(0.1)
+        })
+    }
+
     /// Sets the RINEX navigation file for GPS ephemerides.
     ///
     /// This file contains satellite orbit and clock parameters needed for the
-    /// simulation. The function reads and processes the navigation data,
-    /// extracting ephemeris sets and ionospheric/UTC parameters.
+    /// simulation. The file is read by `build`, which extracts the
+    /// ephemerides and ionospheric/UTC parameters with the parsing mode of
+    /// `lenient_navigation`.
     ///
     /// # Arguments
     /// * `navigation_file` - Optional path to a RINEX navigation file
     ///   (typically with .nav or .n extension)
     ///
     /// # Returns
-    /// * `Ok(Self)` - Builder with navigation data loaded
-    /// * `Err(Error)` - If the file cannot be read or contains no valid
-    ///   ephemeris data
+    /// * `Ok(Self)` - Builder with the navigation file set
+    /// * `Err(Error)` - If the file does not exist
     ///
     /// # Errors
-    /// * `Error::NoEphemeris` - If no valid ephemeris data was found in the
-    ///   file
-    /// * Other errors if the file cannot be read or parsed
+    /// * `Error::NavigationRead` - If the file does not exist, with the
+    ///   underlying error
     pub fn navigation_file(
-        mut self, navigation_file: Option<PathBuf>,
+        self, navigation_file: Option<PathBuf>,
     ) -> Result<Self, Error> {
-        // Read ephemeris
-        if let Some(file) = navigation_file {
-            let (count, iono_utc, ephemerides) = read_navigation_data(&file)
-                .map_err(|_| {
-                    Error::msg("ERROR: ephemeris file not found or error.")
+        self.navigation_files(navigation_file.map(|file| vec![file]))
+    }
+
+    /// Sets several RINEX navigation files for GPS ephemerides.
+    ///
+    /// The ephemerides of all files are merged per satellite and ordered by
+    /// time of clock, so that files of consecutive days cover a multi-day
+    /// simulation. Records present in several files are kept once. The
+    /// ionospheric and UTC parameters come from the first file providing
+    /// them.
+    ///
+    /// # Arguments
+    /// * `navigation_files` - Optional paths to RINEX navigation files
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - Builder with the navigation files set
+    /// * `Err(Error)` - If a file does not exist
+    ///
+    /// # Errors
+    /// * `Error::NavigationRead` - If a file does not exist, with its path and
+    ///   the underlying error
+    pub fn navigation_files(
+        mut self, navigation_files: Option<Vec<PathBuf>>,
+    ) -> Result<Self, Error> {
+        if let Some(files) = navigation_files {
+            for file in &files {
+                std::fs::metadata(file).map_err(|e| Error::NavigationRead {
+                    path: file.clone(),
+                    source: Box::new(e.into()),
                 })?;
-            if count == 0 {
-                return Err(Error::NoEphemeris);
             }
-            self.ephemerides_data = Some((count, iono_utc, ephemerides));
+            self.navigation_files = Some(files);
+        }
+        Ok(self)
+    }
+
+    /// Sets YUMA or SEM almanac files for almanac-based scenarios.
+    ///
+    /// When no navigation file is set, the almanacs replace the broadcast
+    /// ephemerides: each is propagated to the two-hour reference times of
+    /// the scenario and broadcast as ephemerides without harmonic corrections
+    /// whose clock polynomial is the propagated almanac clock. Almanac
+    /// orbits are approximate, kilometers off after some weeks, but
+    /// self-consistent, which suits long or future-date scenarios such as
+    /// planning a test months ahead. The 10-bit almanac weeks are resolved
+    /// around the start time, or the current date if none is set. No
+    /// ionospheric or UTC parameters are broadcast.
+    ///
+    /// # Arguments
+    /// * `files` - Optional paths to YUMA or SEM almanac files
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - Builder with the almanacs loaded
+    /// * `Err(Error)` - If a file cannot be read
+    ///
+    /// # Errors
+    /// * `Error::AlmanacRead` - If a file cannot be read or parsed, with its
+    ///   path and the underlying error
+    pub fn almanac_files(
+        mut self, files: Option<Vec<PathBuf>>,
+    ) -> Result<Self, Error> {
+        if let Some(files) = files {
+            self.almanac = Some(read_almanac_files(&files)?);
         }
         Ok(self)
     }
 
+    /// Sets a synthetic nominal constellation instead of navigation files.
+    ///
+    /// The ephemerides and ionospheric and UTC parameters are synthesized for
+    /// the start time, or the current date if none is set, so that scenarios
+    /// need no input file and can be placed at any date, e.g. across a
+    /// future week number rollover. Navigation and almanac files take
+    /// precedence over the nominal constellation.
+    ///
+    /// # Arguments
+    /// * `satellites` - Optional number of satellites: 24 for the baseline
+    ///   slots, 25 to 27 with expanded slots, up to 32 with spares
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - Builder with the nominal constellation set
+    /// * `Err(Error)` - If the number of satellites is invalid
+    ///
+    /// # Errors
+    /// * `Error::InvalidConstellationSize` - If `satellites` is not in 24..=32
+    pub fn nominal_constellation(
+        mut self, satellites: Option<usize>,
+    ) -> Result<Self, Error> {
+        if let Some(satellites) = satellites {
+            self.nominal_constellation =
+                Some(NominalConstellation::new(satellites)?);
+        }
+        Ok(self)
+    }
+
+    /// Sets whether malformed navigation file lines are skipped.
+    ///
+    /// In lenient mode, unknown header labels, malformed header lines and
+    /// malformed or truncated records are skipped with a warning giving their
+    /// line and column instead of failing the whole file. The mode applies
+    /// whether it is set before or after the navigation files.
+    ///
+    /// # Arguments
+    /// * `lenient` - Optional boolean flag to enable lenient parsing (default:
+    ///   false)
+    ///
+    /// # Returns
+    /// * `Self` - Builder with the parsing mode set
+    pub fn lenient_navigation(mut self, lenient: Option<bool>) -> Self {
+        self.lenient_navigation = lenient;
+        self
+    }
+
     /// Sets whether to override ephemeris time with the simulation start time.
     ///
     /// When enabled, this option adjusts the ephemeris data to match the
@@ -564,6 +766,707 @@ impl SignalGeneratorBuilder {
         Ok(self)
     }
 
+    /// Sets the truth log file for the simulated observables.
+    ///
+    /// The truth log receives one record per update step and active channel
+    /// with the receiver position, satellite position and velocity, ranges,
+    /// Doppler, code and carrier phase, delays, angles and applied gain.
+    ///
+    /// # Arguments
+    /// * `file` - Optional path to the truth log file
+    ///
+    /// # Returns
+    /// * `Self` - Builder with truth log file set
+    pub fn truth_file(mut self, file: Option<PathBuf>) -> Self {
+        self.truth_file = file;
+        self
+    }
+
+    /// Sets the format of the truth log.
+    ///
+    /// # Arguments
+    /// * `format` - Optional truth log format (default: CSV)
+    ///
+    /// # Returns
+    /// * `Self` - Builder with truth log format set
+    pub fn truth_format(mut self, format: Option<TruthFormat>) -> Self {
+        self.truth_format = format;
+        self
+    }
+
+    /// Sets the RINEX observation file for the simulated measurements.
+    ///
+    /// The file receives the ideal C1C, L1C, D1C and S1C observations of
+    /// every active channel in the RINEX 3 format.
+    ///
+    /// # Arguments
+    /// * `file` - Optional path to the RINEX observation file
+    ///
+    /// # Returns
+    /// * `Self` - Builder with observation file set
+    pub fn observation_file(mut self, file: Option<PathBuf>) -> Self {
+        self.observation_file = file;
+        self
+    }
+
+    /// Sets the interval of the RINEX observation file.
+    ///
+    /// # Arguments
+    /// * `interval` - Optional interval in seconds (default: 1.0)
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - Builder with observation interval set
+    /// * `Err(Error)` - If the interval is not a positive multiple of 0.1 s
+    ///
+    /// # Errors
+    /// * Returns an error if the interval is not a positive multiple of the 0.1
+    ///   s update step
+    pub fn observation_interval(
+        mut self, interval: Option<f64>,
+    ) -> Result<Self, Error> {
+        if let Some(interval) = interval {
+            let steps = interval * 10.0;
+            if steps < 1.0 - 1e-9 || (steps - steps.round()).abs() > 1e-6 {
+                return Err(Error::msg(
+                    "Observation interval must be a positive multiple of 0.1 s",
+                ));
+            }
+        }
+        self.observation_interval = interval;
+        Ok(self)
+    }
+
+    /// Controls whether carrier phases start aligned with the pseudoranges.
+    ///
+    /// By default every channel starts with the carrier phase of gps-sdr-sim.
+    /// When enabled, the carrier phase of a channel starts at the fractional
+    /// cycle of its pseudorange and then follows the range exactly, so that
+    /// the integer ambiguities match those of a virtual reference station
+    /// whose phase ranges equal its pseudoranges (see
+    /// `SignalGenerator::rtcm_stream`).
+    ///
+    /// # Arguments
+    /// * `enable` - Optional boolean flag (default: false)
+    ///
+    /// # Returns
+    /// * `Self` - Builder with phase alignment setting
+    pub fn phase_from_range(mut self, enable: Option<bool>) -> Self {
+        self.phase_from_range = enable;
+        self
+    }
+
+    /// Sets whether subframes 4 and 5 cycle through all 25 pages.
+    ///
+    /// By default every frame repeats subframe 4 page 18 and subframe 5 page
+    /// 25, as the original gps-sdr-sim does. With the page schedule the
+    /// frames carry the almanac of every satellite, derived from the current
+    /// ephemerides, and the health pages over each 12.5-minute superframe, so
+    /// that receivers can cold start from the simulated almanac.
+    ///
+    /// # Arguments
+    /// * `enable` - Optional boolean flag (default: false)
+    ///
+    /// # Returns
+    /// * `Self` - Builder with the page schedule setting
+    pub fn superframe_pages(mut self, enable: Option<bool>) -> Self {
+        self.superframe_pages = enable;
+        self
+    }
+
+    /// Sets the special message broadcast in subframe 4 page 17.
+    ///
+    /// A message enables the subframe 4 and 5 page schedule.
+    ///
+    /// # Arguments
+    /// * `message` - Optional text of up to 22 printable ASCII characters
+    ///
+    /// # Returns
+    /// * `Self` - Builder with the special message set
+    pub fn special_message(mut self, message: Option<String>) -> Self {
+        self.special_message = message;
+        self
+    }
+
+    /// Sets whether subframes 1 and 2 are encoded as by gps-sdr-sim.
+    ///
+    /// By default the subframes follow the navigation files: the accuracy
+    /// written in the files is encoded as URA index, and the L2 P data flag,
+    /// fit interval flag and age of data offset are passed through, so that
+    /// receivers weighting or rejecting satellites by URA or fit interval
+    /// behave as with the real signals. When enabled, all four fields are
+    /// broadcast as zero, as the original gps-sdr-sim does, and every
+    /// ephemeris is taken as valid for the 4 hours the zero fit interval flag
+    /// stands for. This is the only setting where the output differs from
+    /// the navigation files for the sake of byte-compatibility with
+    /// gps-sdr-sim.
+    ///
+    /// # Arguments
+    /// * `enable` - Optional boolean flag (default: false)
+    ///
+    /// # Returns
+    /// * `Self` - Builder with the subframe compatibility setting
+    pub fn compatible_subframes(mut self, enable: Option<bool>) -> Self {
+        self.compatible_subframes = enable;
+        self
+    }
+
+    /// Sets the Galileo E1 code table, enabling Galileo E1 Open Service
+    /// signals.
+    ///
+    /// The Galileo satellites of the navigation files with an I/NAV ephemeris
+    /// and codes in the table transmit their E1-B and E1-C signals in the
+    /// same output as GPS L1 C/A. The memory codes are not distributed with
+    /// the simulator; the table lists them as published in the Galileo OS SIS
+    /// ICD. Galileo satellites are not part of the truth log and RINEX
+    /// observation file, and their ephemerides are not shifted by the
+    /// ephemeris time override.
+    ///
+    /// # Arguments
+    /// * `file` - Optional path to the code table, see `GalileoCodes`
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - Builder with the codes loaded
+    /// * `Err(Error)` - If the code table cannot be read
+    ///
+    /// # Errors
+    /// * `Error::CodeRead` - If the code table cannot be read or parsed, with
+    ///   its path and the underlying error
+    pub fn galileo_codes(
+        mut self, file: Option<PathBuf>,
+    ) -> Result<Self, Error> {
+        if let Some(file) = file {
+            self.galileo_codes = Some(GalileoCodes::read_file(&file)?);
+        }
+        Ok(self)
+    }
+
+    /// Sets whether Galileo E1 uses the CBOC subcarrier.
+    ///
+    /// By default E1-B and E1-C are modulated on a BOC(1,1) subcarrier, which
+    /// receivers track with a BOC(1,1) replica at a small loss. CBOC adds the
+    /// BOC(6,1) component of the real signal, which needs a sampling
+    /// frequency of more than 14 MHz.
+    ///
+    /// # Arguments
+    /// * `enable` - Optional boolean flag (default: false)
+    ///
+    /// # Returns
+    /// * `Self` - Builder with the subcarrier setting
+    pub fn galileo_cboc(mut self, enable: Option<bool>) -> Self {
+        self.galileo_cboc = enable;
+        self
+    }
+
+    /// Sets whether to add the BeiDou B1I signals.
+    ///
+    /// The BeiDou satellites of the navigation files with PRN 1 to 63
+    /// transmit their B1I signals at 1561.098 MHz, with the D2 message on the
+    /// GEO satellites and the D1 message on the others. B1I lies 14.322 MHz
+    /// below GPS L1, so the satellites are only simulated if the sampling
+    /// frequency covers it around the centre frequency, see
+    /// `center_frequency`. BeiDou satellites are not part of the truth log
+    /// and RINEX observation file.
+    ///
+    /// # Arguments
+    /// * `enable` - Optional boolean flag (default: false)
+    ///
+    /// # Returns
+    /// * `Self` - Builder with the BeiDou setting
+    pub fn beidou(mut self, enable: Option<bool>) -> Self {
+        self.beidou = enable;
+        self
+    }
+
+    /// Sets whether to add the GLONASS L1OF signals.
+    ///
+    /// The GLONASS satellites of the navigation files in slots 1 to 24
+    /// transmit the ST code on their frequency channels between 1598.0625
+    /// and 1605.375 MHz. These lie 22.6 to 30 MHz above GPS L1, so only the
+    /// satellites whose channel the sampling frequency covers around the
+    /// centre frequency are simulated, see `center_frequency`. GLONASS
+    /// satellites are not part of the truth log and RINEX observation file.
+    ///
+    /// # Arguments
+    /// * `enable` - Optional boolean flag (default: false)
+    ///
+    /// # Returns
+    /// * `Self` - Builder with the GLONASS setting
+    pub fn glonass(mut self, enable: Option<bool>) -> Self {
+        self.glonass = enable;
+        self
+    }
+
+    /// Sets whether to add the QZSS L1 C/A signals.
+    ///
+    /// The QZSS satellites of the navigation files with PRN 193 to 202 (J01
+    /// to J10) transmit their L1 C/A codes with the LNAV message, as GPS
+    /// satellites do. QZSS satellites are not part of the truth log and RINEX
+    /// observation file, and are not affected by the satellite selection.
+    ///
+    /// # Arguments
+    /// * `enable` - Optional boolean flag (default: false)
+    ///
+    /// # Returns
+    /// * `Self` - Builder with the QZSS setting
+    pub fn qzss(mut self, enable: Option<bool>) -> Self {
+        self.qzss = enable;
+        self
+    }
+
+    /// Sets whether to add the SBAS L1 signals.
+    ///
+    /// The SBAS GEO satellites of the navigation files with PRN 120 to 158
+    /// (S20 to S58) transmit their L1 C/A codes with 250 bps messages. Unless
+    /// a message file is set, see `sbas_messages`, they broadcast message
+    /// type 0 (test mode). SBAS satellites are not part of the truth log and
+    /// RINEX observation file.
+    ///
+    /// # Arguments
+    /// * `enable` - Optional boolean flag (default: false)
+    ///
+    /// # Returns
+    /// * `Self` - Builder with the SBAS setting
+    pub fn sbas(mut self, enable: Option<bool>) -> Self {
+        self.sbas = enable;
+        self
+    }
+
+    /// Sets the SBAS message file, enabling the SBAS L1 signals.
+    ///
+    /// # Arguments
+    /// * `file` - Optional path to the message file, see `SbasMessages`
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - Builder with the messages loaded
+    /// * `Err(Error)` - If the message file cannot be read
+    ///
+    /// # Errors
+    /// * `Error::SbasMessageRead` - If the message file cannot be read or
+    ///   parsed, with its path and the underlying error
+    pub fn sbas_messages(
+        mut self, file: Option<PathBuf>,
+    ) -> Result<Self, Error> {
+        if let Some(file) = file {
+            self.sbas_messages = Some(SbasMessages::read_file(&file)?);
+        }
+        Ok(self)
+    }
+
+    /// Sets whether to add the GPS L2C signals.
+    ///
+    /// The GPS satellites of the navigation files transmit the CM code with
+    /// the CNAV message and the CL pilot code at 1227.60 MHz, 347.82 MHz
+    /// below L1, so the signals are only simulated if the sampling frequency
+    /// covers L2 around the centre frequency, see `center_frequency`. The
+    /// satellite selection applies as for L1 C/A. L2C channels are not part
+    /// of the truth log and RINEX observation file.
+    ///
+    /// # Arguments
+    /// * `enable` - Optional boolean flag (default: false)
+    ///
+    /// # Returns
+    /// * `Self` - Builder with the L2C setting
+    pub fn l2c(mut self, enable: Option<bool>) -> Self {
+        self.l2c = enable;
+        self
+    }
+
+    /// Sets whether to add the GPS L5 signals.
+    ///
+    /// The GPS satellites of the navigation files transmit the I5 code with
+    /// the CNAV message and the Q5 pilot code at 1176.45 MHz, so the signals
+    /// are only simulated if the sampling frequency covers their 20.46 MHz
+    /// main lobe around the centre frequency, see `center_frequency`. The
+    /// satellite selection applies as for L1 C/A. L5 channels are not part
+    /// of the truth log and RINEX observation file.
+    ///
+    /// # Arguments
+    /// * `enable` - Optional boolean flag (default: false)
+    ///
+    /// # Returns
+    /// * `Self` - Builder with the L5 setting
+    pub fn l5(mut self, enable: Option<bool>) -> Self {
+        self.l5 = enable;
+        self
+    }
+
+    /// Sets whether to add the GPS L1C signals.
+    ///
+    /// The GPS satellites of the navigation files transmit the L1CD
+    /// component with the CNAV-2 message and the L1CP pilot with the TMBOC
+    /// subcarrier on L1, next to the C/A code, so the signals are only
+    /// simulated if the sampling frequency covers their 4.092 MHz main lobes
+    /// around the centre frequency. The satellite selection applies as for
+    /// L1 C/A. L1C channels are not part of the truth log and RINEX
+    /// observation file.
+    ///
+    /// # Arguments
+    /// * `enable` - Optional boolean flag (default: false)
+    ///
+    /// # Returns
+    /// * `Self` - Builder with the L1C setting
+    pub fn l1c(mut self, enable: Option<bool>) -> Self {
+        self.l1c = enable;
+        self
+    }
+
+    /// Sets the RF centre frequency of the output.
+    ///
+    /// Every signal is generated at its carrier offset from this frequency.
+    /// The default is the GPS L1 frequency, 1575.42 MHz. Signals of other
+    /// systems outside the band of the sampling frequency around the centre
+    /// are left out with a warning.
+    ///
+    /// # Arguments
+    /// * `frequency` - Optional centre frequency in Hz
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - Builder with the centre frequency set
+    /// * `Err(Error)` - If the frequency is invalid
+    ///
+    /// # Errors
+    /// * `Error::InvalidCenterFrequency` - If the frequency is not positive
+    pub fn center_frequency(
+        mut self, frequency: Option<f64>,
+    ) -> Result<Self, Error> {
+        match frequency {
+            Some(freq) if freq.is_finite() && freq > 0.0 => {
+                self.center_frequency = Some(freq);
+            }
+            None => {}
+            _ => return Err(Error::invalid_center_frequency()),
+        }
+        Ok(self)
+    }
+
+    /// Sets the intermediate frequency of the output.
+    ///
+    /// The centre frequency appears at this frequency in the output instead
+    /// of at zero, shifting every signal by the same amount. A few hundred
+    /// kHz keep the carriers off the DC spike of I/Q transmitters, which
+    /// then have to be tuned to the centre frequency minus the intermediate
+    /// frequency. The default is 0 Hz. The signals are still
+    /// only simulated if they fit within the band of the sampling frequency,
+    /// see `center_frequency`.
+    ///
+    /// # Arguments
+    /// * `frequency` - Optional intermediate frequency in Hz, negative to shift
+    ///   the band down
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - Builder with the intermediate frequency set
+    /// * `Err(Error)` - If the frequency is invalid
+    ///
+    /// # Errors
+    /// * `Error::InvalidIntermediateFrequency` - If the frequency is not finite
+    pub fn intermediate_frequency(
+        mut self, frequency: Option<f64>,
+    ) -> Result<Self, Error> {
+        match frequency {
+            Some(freq) if freq.is_finite() => {
+                self.intermediate_frequency = Some(freq);
+            }
+            None => {}
+            _ => return Err(Error::invalid_intermediate_frequency()),
+        }
+        Ok(self)
+    }
+
+    /// Sets whether to write real samples instead of I/Q samples.
+    ///
+    /// Real output holds the in-phase component of the signal at the
+    /// intermediate frequency, one sample per sampling period, as front-ends
+    /// like the MAX2769 deliver it (e.g. at 4.092 MHz). The signals then
+    /// have to fit between 0 Hz and half the sampling frequency, so real
+    /// output needs an intermediate frequency, see `intermediate_frequency`.
+    ///
+    /// # Arguments
+    /// * `enable` - Optional boolean flag (default: false)
+    ///
+    /// # Returns
+    /// * `Self` - Builder with the real output setting
+    pub fn real_output(mut self, enable: Option<bool>) -> Self {
+        self.real_output = enable;
+        self
+    }
+
+    /// Sets the I/Q imbalance of the emulated transmitter.
+    ///
+    /// The Q branch of the quadrature modulator is amplified by the gain
+    /// imbalance relative to the I branch, and its LO is off quadrature by
+    /// the phase imbalance, leaking part of the Q signal into I.
+    ///
+    /// # Arguments
+    /// * `imbalance` - Optional gain imbalance in dB and phase imbalance in
+    ///   degrees
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - Builder with the I/Q imbalance set
+    /// * `Err(Error)` - If the imbalance is invalid
+    ///
+    /// # Errors
+    /// * `Error::InvalidImpairment` - If a value is not finite or the phase
+    ///   imbalance is not within +/-45 degrees
+    pub fn iq_imbalance(
+        mut self, imbalance: Option<(f64, f64)>,
+    ) -> Result<Self, Error> {
+        if let Some((gain, phase)) = imbalance
+            && !(gain.is_finite() && phase.is_finite() && phase.abs() <= 45.0)
+        {
+            return Err(Error::invalid_impairment(format!(
+                "I/Q imbalance {gain} dB, {phase} deg"
+            )));
+        }
+        self.iq_imbalance = imbalance;
+        Ok(self)
+    }
+
+    /// Sets the DC offset of the emulated transmitter.
+    ///
+    /// The offset is added to the I and Q samples before the LO, as the LO
+    /// leakage of a transmitter, so it appears at the centre frequency. It
+    /// is given in units of the 16-bit samples, 16 times the units of the
+    /// 8-bit samples.
+    ///
+    /// # Arguments
+    /// * `offset` - Optional DC offset of the I and Q samples
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - Builder with the DC offset set
+    /// * `Err(Error)` - If the offset is invalid
+    ///
+    /// # Errors
+    /// * `Error::InvalidImpairment` - If a value is not finite
+    pub fn dc_offset(
+        mut self, offset: Option<(f64, f64)>,
+    ) -> Result<Self, Error> {
+        if let Some((i, q)) = offset
+            && !(i.is_finite() && q.is_finite())
+        {
+            return Err(Error::invalid_impairment(format!(
+                "DC offset {i}, {q}"
+            )));
+        }
+        self.dc_offset = offset;
+        Ok(self)
+    }
+
+    /// Sets the LO frequency offset of the emulated transmitter.
+    ///
+    /// The whole output is rotated by the offset relative to the RF centre
+    /// frequency, as by a transmitter LO running fast or slow. The sampling
+    /// clock stays exact.
+    ///
+    /// # Arguments
+    /// * `ppm` - Optional frequency offset in parts per million
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - Builder with the frequency offset set
+    /// * `Err(Error)` - If the offset is invalid
+    ///
+    /// # Errors
+    /// * `Error::InvalidImpairment` - If the offset is not finite
+    pub fn frequency_offset(mut self, ppm: Option<f64>) -> Result<Self, Error> {
+        if let Some(ppm) = ppm
+            && !ppm.is_finite()
+        {
+            return Err(Error::invalid_impairment(format!(
+                "frequency offset {ppm} ppm"
+            )));
+        }
+        self.frequency_offset = ppm;
+        Ok(self)
+    }
+
+    /// Sets the LO phase noise of the emulated transmitter.
+    ///
+    /// The mask gives the single-sideband phase noise L(f) at offsets from
+    /// the carrier, interpolated linearly in log frequency. The phase noise
+    /// is synthesized between the first and last offset, up to half the
+    /// sampling frequency, from a fixed seed so that the output is
+    /// reproducible.
+    ///
+    /// # Arguments
+    /// * `mask` - Optional (offset in Hz, level in dBc/Hz) points in increasing
+    ///   offset
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - Builder with the phase noise mask set
+    /// * `Err(Error)` - If the mask is invalid
+    ///
+    /// # Errors
+    /// * `Error::InvalidImpairment` - If the mask has less than two points,
+    ///   offsets that are not positive and increasing, or levels that are not
+    ///   finite and negative
+    pub fn phase_noise(
+        mut self, mask: Option<Vec<(f64, f64)>>,
+    ) -> Result<Self, Error> {
+        if let Some(mask) = &mask {
+            let valid = mask.len() >= 2
+                && mask.first().is_some_and(|&(offset, _)| offset > 0.0)
+                && mask.windows(2).all(|pair| pair[0].0 < pair[1].0)
+                && mask.iter().all(|&(offset, level)| {
+                    offset.is_finite() && level.is_finite() && level < 0.0
+                });
+            if !valid {
+                return Err(Error::invalid_impairment(
+                    "phase noise mask needs two or more points of increasing \
+                     positive offset and negative level",
+                ));
+            }
+        }
+        self.phase_noise = mask;
+        Ok(self)
+    }
+
+    /// Sets whether to compensate the transmitter impairments instead of
+    /// emulating them.
+    ///
+    /// The samples are pre-distorted with the inverse of the I/Q imbalance,
+    /// DC offset and frequency offset, so that a transmitter with these
+    /// measured impairments radiates a clean signal. Random phase noise
+    /// cannot be compensated, so it must not be set.
+    ///
+    /// # Arguments
+    /// * `enable` - Optional boolean flag (default: false)
+    ///
+    /// # Returns
+    /// * `Self` - Builder with the pre-distortion setting
+    pub fn predistortion(mut self, enable: Option<bool>) -> Self {
+        self.predistortion = enable;
+        self
+    }
+
+    /// Sets the number of generated samples per output sample.
+    ///
+    /// The signals are generated at a multiple of the sampling frequency,
+    /// band-limited by the satellite transmit filter (24 MHz around each
+    /// carrier, when the generated band is wider) and the receiver
+    /// bandwidth, and decimated to the sampling frequency. The
+    /// rectangular chips then no longer alias into the output, at the cost
+    /// of generation time growing with the factor. The default of 1
+    /// generates at the sampling frequency without filter, unless a receiver
+    /// bandwidth is set.
+    ///
+    /// # Arguments
+    /// * `factor` - Optional oversampling factor (1 to 16)
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - Builder with the oversampling factor set
+    /// * `Err(Error)` - If the factor is invalid
+    ///
+    /// # Errors
+    /// * `Error::InvalidFilter` - If the factor is not within 1 to 16
+    pub fn oversampling(
+        mut self, factor: Option<usize>,
+    ) -> Result<Self, Error> {
+        match factor {
+            Some(factor) if (1..=16).contains(&factor) => {
+                self.oversampling = Some(factor);
+            }
+            None => {}
+            Some(factor) => {
+                return Err(Error::invalid_filter(format!(
+                    "oversampling factor {factor}"
+                )));
+            }
+        }
+        Ok(self)
+    }
+
+    /// Sets the pre-correlation bandwidth of the emulated receiver.
+    ///
+    /// The composite signal is low-pass filtered to this two-sided
+    /// bandwidth around the centre frequency, rounding the correlation
+    /// peaks as a receiver front-end does. The receiver filter is centred
+    /// on the centre frequency and follows the satellite transmit filters.
+    /// Without it, oversampled signals are filtered to the band of the
+    /// output.
+    ///
+    /// # Arguments
+    /// * `bandwidth` - Optional two-sided bandwidth in Hz
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - Builder with the receiver bandwidth set
+    /// * `Err(Error)` - If the bandwidth is invalid
+    ///
+    /// # Errors
+    /// * `Error::InvalidFilter` - If the bandwidth is not positive
+    pub fn receiver_bandwidth(
+        mut self, bandwidth: Option<f64>,
+    ) -> Result<Self, Error> {
+        match bandwidth {
+            Some(bandwidth) if bandwidth.is_finite() && bandwidth > 0.0 => {
+                self.receiver_bandwidth = Some(bandwidth);
+            }
+            None => {}
+            Some(bandwidth) => {
+                return Err(Error::invalid_filter(format!(
+                    "receiver bandwidth {bandwidth} Hz"
+                )));
+            }
+        }
+        Ok(self)
+    }
+
+    /// Sets SP3 precise orbit files for the simulated ranges.
+    ///
+    /// The ranges, Doppler and truth log then follow the interpolated precise
+    /// orbits and clocks, while the navigation message keeps broadcasting the
+    /// ephemerides of the navigation files. The difference is the broadcast
+    /// orbit and clock error a receiver sees. Satellites missing from the
+    /// precise orbits use their broadcast ephemeris.
+    ///
+    /// # Arguments
+    /// * `files` - Optional paths to SP3-c or SP3-d files, merged per satellite
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - Builder with the precise orbits loaded
+    /// * `Err(Error)` - If a file cannot be read
+    ///
+    /// # Errors
+    /// * `Error::PreciseRead` - If a file cannot be read or parsed or is not in
+    ///   GPS time, with its path and the underlying error
+    pub fn precise_orbit_files(
+        mut self, files: Option<Vec<PathBuf>>,
+    ) -> Result<Self, Error> {
+        if let Some(files) = files {
+            read_sp3_files(
+                self.precise_orbits.get_or_insert_default(),
+                &files,
+            )?;
+        }
+        Ok(self)
+    }
+
+    /// Sets RINEX clock files for the simulated ranges.
+    ///
+    /// The satellite clocks of these files replace those of the SP3 files,
+    /// usually at a finer interval. Only used together with
+    /// `precise_orbit_files`.
+    ///
+    /// # Arguments
+    /// * `files` - Optional paths to RINEX clock files
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - Builder with the precise clocks loaded
+    /// * `Err(Error)` - If a file cannot be read
+    ///
+    /// # Errors
+    /// * `Error::PreciseRead` - If a file cannot be read or parsed or is not in
+    ///   GPS time, with its path and the underlying error
+    pub fn precise_clock_files(
+        mut self, files: Option<Vec<PathBuf>>,
+    ) -> Result<Self, Error> {
+        if let Some(files) = files {
+            read_clock_files(
+                self.precise_orbits.get_or_insert_default(),
+                &files,
+            )?;
+        }
+        Ok(self)
+    }
+
     /// Sets the time step between simulation updates.
     ///
     /// This method specifies the time interval in seconds between position
@@ -581,6 +1484,166 @@ impl SignalGeneratorBuilder {
         self
     }
 
+    /// Restricts the simulation to the given satellites.
+    ///
+    /// Only the listed PRNs are allocated to channels, and only while they are
+    /// visible. This is useful for reduced-constellation tests such as
+    /// simulating exactly four satellites. The list names GPS satellites, of
+    /// L1 C/A, L1C, L2C and L5; the satellites of other systems are not
+    /// affected.
+    ///
+    /// # Arguments
+    /// * `prns` - Optional list of PRN numbers (1-32) to simulate
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - Builder with the include list set
+    /// * `Err(Error)` - If a PRN is out of range
+    ///
+    /// # Errors
+    /// * `Error::InvalidPrn` - If a PRN is not in the range 1-32
+    pub fn satellite_include(
+        mut self, prns: Option<Vec<usize>>,
+    ) -> Result<Self, Error> {
+        if let Some(prns) = &prns {
+            Self::check_prns(prns)?;
+        }
+        self.include_prns = prns;
+        Ok(self)
+    }
+
+    /// Excludes the given satellites from the simulation.
+    ///
+    /// Excluded PRNs are never allocated to a channel, even when they are
+    /// visible. The exclude list is applied after the include list. Like the
+    /// include list, it names GPS satellites only.
+    ///
+    /// # Arguments
+    /// * `prns` - Optional list of PRN numbers (1-32) to leave out
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - Builder with the exclude list set
+    /// * `Err(Error)` - If a PRN is out of range
+    ///
+    /// # Errors
+    /// * `Error::InvalidPrn` - If a PRN is not in the range 1-32
+    pub fn satellite_exclude(
+        mut self, prns: Option<Vec<usize>>,
+    ) -> Result<Self, Error> {
+        if let Some(prns) = &prns {
+            Self::check_prns(prns)?;
+        }
+        self.exclude_prns = prns;
+        Ok(self)
+    }
+
+    /// Limits the number of satellites simulated at the same time.
+    ///
+    /// Visible satellites are allocated in PRN order until the limit is
+    /// reached. A channel freed by a setting satellite can be taken by the
+    /// next visible one. The limit applies to each satellite system and
+    /// signal separately.
+    ///
+    /// # Arguments
+    /// * `max` - Optional maximum number of satellites (1-16)
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - Builder with the satellite limit set
+    /// * `Err(Error)` - If the limit is zero or exceeds the channel count
+    ///
+    /// # Errors
+    /// * `Error::InvalidMaxSatellites` - If the limit is not in the range 1-16
+    pub fn max_satellites(mut self, max: Option<usize>) -> Result<Self, Error> {
+        if max.is_some_and(|max| !(1..=MAX_CHAN).contains(&max)) {
+            return Err(Error::invalid_max_satellites());
+        }
+        self.max_satellites = max;
+        Ok(self)
+    }
+
+    /// Sets how satellites with a non-zero health word are handled.
+    ///
+    /// By default unhealthy satellites are simulated and broadcast their
+    /// health as read from the navigation file, like gps-sdr-sim does. The
+    /// mode applies to the satellites of all systems.
+    ///
+    /// # Arguments
+    /// * `mode` - Optional health handling mode (simulate, skip or force
+    ///   healthy)
+    ///
+    /// # Returns
+    /// * `Self` - Builder with health mode set
+    pub fn health_mode(mut self, mode: Option<HealthMode>) -> Self {
+        self.health_mode = mode;
+        self
+    }
+
+    /// Sets the elevation mask below which satellites are not simulated.
+    ///
+    /// # Arguments
+    /// * `mask` - Optional elevation mask in degrees (default: 0)
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - Builder with elevation mask set
+    /// * `Err(Error)` - If the mask is outside -90 to 90 degrees
+    ///
+    /// # Errors
+    /// * Returns an error if the mask is not in the range -90 to 90 degrees
+    pub fn elevation_mask(mut self, mask: Option<f64>) -> Result<Self, Error> {
+        if mask.is_some_and(|mask| !(-90.0..=90.0).contains(&mask)) {
+            return Err(Error::msg("Elevation mask must be within +/-90 deg"));
+        }
+        self.elevation_mask = mask;
+        Ok(self)
+    }
+
+    /// Overrides the broadcast health word of individual satellites.
+    ///
+    /// The override replaces the health read from the navigation file in
+    /// every ephemeris of the satellite. It is applied after
+    /// `HealthMode::ForceHealthy`, so a single satellite can still be
+    /// flagged unhealthy in that mode.
+    ///
+    /// # Arguments
+    /// * `overrides` - Optional list of (PRN, health) pairs, where health is
+    ///   the 6-bit value transmitted in subframe 1 (0 = healthy)
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - Builder with health overrides set
+    /// * `Err(Error)` - If a PRN or health value is out of range
+    ///
+    /// # Errors
+    /// * `Error::InvalidPrn` - If a PRN is not in the range 1-32
+    /// * `Error::InvalidHealth` - If a health value is not in the range 0-63
+    pub fn health_override(
+        mut self, overrides: Option<Vec<(usize, i32)>>,
+    ) -> Result<Self, Error> {
+        if let Some(overrides) = &overrides {
+            for &(prn, health) in overrides {
+                Self::check_prns(&[prn])?;
+                if !(0..=63).contains(&health) {
+                    return Err(Error::invalid_health(health));
+                }
+            }
+        }
+        self.health_override = overrides;
+        Ok(self)
+    }
+
+    /// Validates that all PRNs are in the range 1-32.
+    ///
+    /// # Arguments
+    /// * `prns` - PRN numbers to check
+    ///
+    /// # Returns
+    /// * `Ok(())` - If all PRNs are valid
+    /// * `Err(Error)` - The first invalid PRN
+    fn check_prns(prns: &[usize]) -> Result<(), Error> {
+        match prns.iter().find(|prn| !(1..=MAX_SAT).contains(prn)) {
+            Some(&prn) => Err(Error::invalid_prn(prn)),
+            None => Ok(()),
+        }
+    }
+
     /// Builds the `SignalGenerator` with the configured settings.
     ///
     /// This method finalizes the builder pattern, creating a `SignalGenerator`
@@ -594,7 +1657,12 @@ impl SignalGeneratorBuilder {
     /// * `Err(Error)` - If the configuration is invalid or incomplete
     ///
     /// # Errors
-    /// * `Error::navigation_not_set()` - If no navigation file was provided
+    /// * `Error::navigation_not_set()` - If neither a navigation file, an
+    ///   almanac file nor a nominal constellation was provided
+    /// * `Error::NavigationRead` - If a navigation file cannot be read or
+    ///   parsed, with its path, the underlying error and its line and column
+    /// * `Error::NoEphemeris` - If the navigation or almanac files have no GPS
+    ///   satellite
     /// * `Error::invalid_gps_day()` - If an invalid GPS day was specified
     /// * `Error::invalid_gps_week()` - If an invalid GPS week was specified
     /// * `Error::invalid_delta_leap_second()` - If an invalid leap second delta
@@ -602,15 +1670,56 @@ impl SignalGeneratorBuilder {
     /// * `Error::wrong_positions()` - If the positions vector is empty
     /// * `Error::invalid_duration()` - If a negative duration was specified
     /// * `Error::invalid_start_time()` - If the start time is outside the
-    ///   ephemeris range
+    ///   ephemeris range of the navigation files
     /// * `Error::no_current_ephemerides()` - If no valid ephemeris is available
     ///   for the start time
     /// * `Error::data_format_not_set()` - If no data format was specified
+    /// * `Error::invalid_intermediate_frequency()` - If the intermediate
+    ///   frequency lies outside the Nyquist band of the sampling frequency, or
+    ///   is not positive with real output
+    /// * `Error::invalid_impairment()` - If pre-distortion is enabled together
+    ///   with phase noise
+    /// * `Error::invalid_filter()` - If the receiver bandwidth exceeds the band
+    ///   of the output around the intermediate frequency
+    /// * `Error::precise_orbits_out_of_range()` - If precise orbits were given
+    ///   that do not cover the start time
+    /// * `Error::InvalidSpecialMessage` - If the special message is too long or
+    ///   not printable ASCII
+    /// * `Error::NoSystemEphemeris` - If Galileo codes were given or BeiDou was
+    ///   enabled but the navigation files have no usable ephemeris of the
+    ///   system
     #[allow(clippy::too_many_lines)]
     pub fn build(mut self) -> Result<SignalGenerator, Error> {
-        // ensure navigation data is read
-        let Some((count, mut ionoutc, mut ephemerides)) = self.ephemerides_data
-        else {
+        // read the navigation files, or build the navigation data from the
+        // almanacs or the nominal constellation
+        let from_almanac =
+            self.navigation_files.is_none() && self.almanac.is_some();
+        let (mut ionoutc, mut timeline, navigation) = if let Some(files) =
+            &self.navigation_files
+        {
+            let lenient = self.lenient_navigation.unwrap_or(false);
+            let data = read_navigation_data(files, lenient)?;
+            if data.1.is_empty() {
+                return Err(Error::NoEphemeris);
+            }
+            data
+        } else if let Some(records) = self.almanac.take() {
+            let start = Self::start_or_now(self.receiver_gps_time.as_ref())?;
+            let end = start.add_secs(self.scenario_duration());
+            let timeline = almanac_timeline(&records, &start, &end);
+            if timeline.is_empty() {
+                return Err(Error::NoEphemeris);
+            }
+            (IonoUtc::default(), timeline, SystemNavigation::default())
+        } else if let Some(constellation) = &self.nominal_constellation {
+            let start = Self::start_or_now(self.receiver_gps_time.as_ref())?;
+            let end = start.add_secs(self.scenario_duration());
+            (
+                NominalConstellation::iono_utc(&start),
+                constellation.timeline(&start, &end),
+                SystemNavigation::default(),
+            )
+        } else {
             return Err(Error::navigation_not_set());
         };
         // check and set defaults
@@ -670,26 +1779,69 @@ impl SignalGeneratorBuilder {
         };
         // frequency
         let sample_frequency = self.frequency.unwrap_or(2_600_000.0);
+        let intermediate_frequency = self.intermediate_frequency.unwrap_or(0.0);
+        let real_output = self.real_output.unwrap_or(false);
+        if 2.0 * intermediate_frequency.abs() >= sample_frequency
+            || (real_output && intermediate_frequency <= 0.0)
+        {
+            return Err(Error::invalid_intermediate_frequency());
+        }
+        let center_frequency = self.center_frequency.unwrap_or(CARR_FREQ);
+        let (gain_imbalance, phase_imbalance) =
+            self.iq_imbalance.unwrap_or_default();
+        let impairments = Impairments {
+            gain_imbalance,
+            phase_imbalance,
+            dc_offset: self.dc_offset.unwrap_or_default(),
+            frequency_offset: self.frequency_offset.unwrap_or(0.0),
+            phase_noise: self.phase_noise.take().unwrap_or_default(),
+            predistortion: self.predistortion.unwrap_or(false),
+        };
+        if impairments.predistortion && !impairments.phase_noise.is_empty() {
+            return Err(Error::invalid_impairment(
+                "phase noise cannot be pre-distorted",
+            ));
+        }
+        let oversampling = self.oversampling.unwrap_or(1);
+        let filter = if oversampling > 1 || self.receiver_bandwidth.is_some() {
+            // Widest band around the intermediate frequency that the output
+            // holds without aliasing
+            let band = if real_output {
+                2.0 * intermediate_frequency
+                    .min(sample_frequency / 2.0 - intermediate_frequency)
+            } else {
+                sample_frequency - 2.0 * intermediate_frequency.abs()
+            };
+            let bandwidth = self.receiver_bandwidth.unwrap_or(band);
+            if bandwidth > band {
+                return Err(Error::invalid_filter(format!(
+                    "receiver bandwidth {bandwidth} Hz exceeds the output \
+                     band of {band} Hz"
+                )));
+            }
+            Some(FrontEndFilter::new(
+                bandwidth,
+                intermediate_frequency,
+                sample_frequency * oversampling as f64,
+                oversampling,
+            ))
+        } else {
+            None
+        };
+        let impairments = (!impairments.is_none()).then(|| {
+            ImpairmentStage::new(
+                &impairments,
+                sample_frequency,
+                center_frequency,
+            )
+        });
         // is override time?
 
         let antenna_gains: [i32; MAX_CHAN] = [0; MAX_CHAN];
         let antenna_pattern: [f64; 37] = [0.; 37];
-        let mut gpstime_min = GpsTime::default();
-        let mut gpstime_max = GpsTime::default();
-        // get min time of ephemerides
-        for sv in 0..MAX_SAT {
-            if ephemerides[0][sv].vflg {
-                gpstime_min = ephemerides[0][sv].toc.clone();
-                break;
-            }
-        }
-        // get max time of ephemerides
-        for sv in 0..MAX_SAT {
-            if ephemerides[count - 1][sv].vflg {
-                gpstime_max = ephemerides[count - 1][sv].toc.clone();
-                break;
-            }
-        }
+        // get min and max time of clock of all ephemerides
+        let (gpstime_min, gpstime_max) =
+            timeline.time_span().unwrap_or_default();
         let time_override = self.time_override.unwrap_or(false);
         let receiver_gps_time = if let Some(gps_time_0) = self.receiver_gps_time
         {
@@ -709,10 +1861,10 @@ impl SignalGeneratorBuilder {
                 // time.
                 //
                 // Correct behavior (C-aligned):
-                // 1. Adjust ALL ephemeris sets by shifting their TOC/TOE.
-                // 2. Later in the code (see "Select the current set of
-                //    ephemerides"), STRICTLY select the ephemeris set where
-                //    |TOC - SimTime| < 2 hours.
+                // 1. Adjust ALL ephemerides by shifting their TOC/TOE.
+                // 2. Later in the code (see "Select the current ephemeris of
+                //    each satellite"), STRICTLY select ephemerides that are due
+                //    and within their fit interval.
                 //
                 // This ensures that even with a time override, we use the
                 // ephemeris parameters that are physically most
@@ -735,20 +1887,17 @@ impl SignalGeneratorBuilder {
                 ionoutc.tot = gtmp.sec as i32;
                 // Iono/UTC parameters may no longer valid
                 //ionoutc.vflg = FALSE;
-                for sv in 0..MAX_SAT {
-                    for i_eph in ephemerides.iter_mut().take(count) {
-                        if i_eph[sv].vflg {
-                            gtmp = i_eph[sv].toc.add_secs(dsec);
-                            let ttmp = DateTime::from(&gtmp);
-                            i_eph[sv].toc = gtmp;
-                            i_eph[sv].t = ttmp;
-                            gtmp = i_eph[sv].toe.add_secs(dsec);
-                            i_eph[sv].toe = gtmp;
-                        }
-                    }
+                for (_, eph) in timeline.iter_mut() {
+                    gtmp = eph.toc.add_secs(dsec);
+                    let ttmp = DateTime::from(&gtmp);
+                    eph.toc = gtmp;
+                    eph.t = ttmp;
+                    gtmp = eph.toe.add_secs(dsec);
+                    eph.toe = gtmp;
                 }
-            } else if gps_time_0.diff_secs(&gpstime_min) < 0.0
-                || gpstime_max.diff_secs(&gps_time_0) < 0.0f64
+            } else if !from_almanac
+                && (gps_time_0.diff_secs(&gpstime_min) < 0.0
+                    || gpstime_max.diff_secs(&gps_time_0) < 0.0f64)
             {
                 return Err(Error::invalid_start_time());
             }
@@ -756,34 +1905,29 @@ impl SignalGeneratorBuilder {
         } else {
             gpstime_min
         };
-        let mut valid_ephemerides_index = None;
-
-        // Select the current set of ephemerides
-        for (i, eph_item) in ephemerides.iter().enumerate().take(count) {
-            for e in eph_item.iter().take(MAX_SAT) {
-                if e.vflg {
-                    let dt = receiver_gps_time.diff_secs(&e.toc);
-                    if (-SECONDS_IN_HOUR..SECONDS_IN_HOUR).contains(&dt) {
-                        valid_ephemerides_index = Some(i);
-                        break;
-                    }
-                }
-            }
-            if valid_ephemerides_index.is_some() {
-                // ieph has been set
-                break;
-            }
-        }
+        // Select the current ephemeris of each satellite. Almanacs apply at
+        // any time, also before their reference time.
+        let select = |sv: usize| {
+            timeline
+                .select(sv + 1, &receiver_gps_time)
+                .or_else(|| {
+                    from_almanac.then(|| timeline.first(sv + 1)).flatten()
+                })
+                .cloned()
+                .unwrap_or_default()
+        };
+        let mut ephemerides: [Ephemeris; MAX_SAT] = std::array::from_fn(select);
 
         // If no valid ephemerides found and time_override is true, use the
-        // first set
-        if valid_ephemerides_index.is_none() && time_override && count > 0 {
-            valid_ephemerides_index = Some(0);
+        // first ephemeris of each satellite
+        if !ephemerides.iter().any(|eph| eph.vflg) {
+            if !time_override || timeline.is_empty() {
+                return Err(Error::no_current_ephemerides());
+            }
+            ephemerides = std::array::from_fn(|sv| {
+                timeline.first(sv + 1).cloned().unwrap_or_default()
+            });
         }
-
-        let Some(valid_ephemerides_index) = valid_ephemerides_index else {
-            return Err(Error::no_current_ephemerides());
-        };
         // Set ionospheric correction based on the disable flag
         // In gpssim.c, when -i flag is used, ionoutc.enable is set to FALSE
         // So when ionospheric_disable is true, ionoutc.enable should be false
@@ -791,23 +1935,152 @@ impl SignalGeneratorBuilder {
         let Some(data_format) = self.data_format else {
             return Err(Error::data_format_not_set());
         };
-
+        // Apply health policy and per-PRN overrides to all ephemerides, and
+        // clear the fields gps-sdr-sim broadcasts as zero in compatibility
+        // mode
+        let health_mode = self.health_mode.unwrap_or_default();
+        let compatible_subframes = self.compatible_subframes.unwrap_or(false);
+        let current = ephemerides
+            .iter_mut()
+            .enumerate()
+            .map(|(sv, eph)| (sv + 1, eph));
+        let health_override = self.health_override.unwrap_or_default();
+        for (prn, eph) in timeline.iter_mut().chain(current) {
+            eph.svhlth =
+                health_mode.broadcast_health(prn, eph.svhlth, &health_override);
+            if compatible_subframes {
+                eph.ura = 0;
+                eph.l2p_flag = 0;
+                eph.fit_interval = 0.0;
+                eph.aodo = 0.0;
+            }
+        }
+        // Precise orbits must cover the start of the scenario
+        let precise_orbits = self.precise_orbits.take();
+        if precise_orbits
+            .as_ref()
+            .is_some_and(|orbits| !orbits.covers(&receiver_gps_time))
+        {
+            return Err(Error::precise_orbits_out_of_range());
+        }
+        let superframe_pages = if self.superframe_pages.unwrap_or(false)
+            || self.special_message.is_some()
+        {
+            Some(SuperframePages::new(
+                &receiver_gps_time,
+                self.special_message.as_deref(),
+            )?)
+        } else {
+            None
+        };
+        let satellite_selection = SatelliteSelection {
+            include: self.include_prns,
+            exclude: self.exclude_prns.unwrap_or_default(),
+            max_satellites: self.max_satellites,
+            health_mode,
+        };
+        let mut systems: Vec<Box<dyn SignalSystem>> = Vec::new();
+        for (enable, signal) in
+            [(self.l2c, CnavSignal::L2c), (self.l5, CnavSignal::L5)]
+        {
+            if enable.unwrap_or(false) {
+                systems.push(Box::new(CnavSystem::new(
+                    signal,
+                    timeline.clone(),
+                    &navigation,
+                    satellite_selection.clone(),
+                )?));
+            }
+        }
+        if self.l1c.unwrap_or(false) {
+            systems.push(Box::new(L1cSystem::new(
+                timeline.clone(),
+                &navigation,
+                satellite_selection.clone(),
+            )?));
+        }
+        if let Some(codes) = self.galileo_codes {
+            systems.push(Box::new(GalileoSystem::new(
+                &navigation,
+                codes,
+                self.galileo_cboc.unwrap_or(false),
+                satellite_selection.without_prn_lists(),
+            )?));
+        }
+        if self.beidou.unwrap_or(false) {
+            systems.push(Box::new(BeidouSystem::new(
+                &navigation,
+                satellite_selection.without_prn_lists(),
+            )?));
+        }
+        if self.glonass.unwrap_or(false) {
+            systems.push(Box::new(GlonassSystem::new(
+                &navigation,
+                satellite_selection.without_prn_lists(),
+            )?));
+        }
+        if self.qzss.unwrap_or(false) {
+            systems.push(Box::new(QzssSystem::new(
+                &navigation,
+                satellite_selection.without_prn_lists(),
+            )?));
+        }
+        if self.sbas.unwrap_or(false) || self.sbas_messages.is_some() {
+            systems.push(Box::new(SbasSystem::new(
+                &navigation,
+                self.sbas_messages.unwrap_or_default(),
+                satellite_selection.without_prn_lists(),
+            )?));
+        }
+        // Satellite transmit filters, when the filtered signals are generated
+        // in a band wider than them
+        let generation_frequency = sample_frequency * oversampling as f64;
+        let satellite_filter = (filter.is_some()
+            && generation_frequency > SATELLITE_BANDWIDTH)
+            .then(|| {
+                let carriers = std::iter::once(CARR_FREQ)
+                    .chain(
+                        systems.iter().map(|system| system.carrier_frequency()),
+                    )
+                    .collect::<Vec<_>>();
+                SatelliteFilter::new(
+                    &carriers,
+                    intermediate_frequency - center_frequency,
+                    generation_frequency,
+                )
+            });
         let generator = SignalGenerator {
+            timeline,
             ephemerides,
-            valid_ephemerides_index,
             ionoutc,
+            superframe_pages,
             positions,
             simulation_step_count: user_motion_count,
             receiver_gps_time,
             antenna_gains,
             antenna_pattern,
             mode,
-            elevation_mask: 0.0, // Default elevation mask
+            elevation_mask: self.elevation_mask.unwrap_or(0.0),
             sample_frequency,
+            center_frequency,
+            intermediate_frequency,
+            real_output,
+            oversampling,
+            filter,
+            impairments,
             sample_rate,
             data_format,
             fixed_gain: self.path_loss,
             output_file: self.output_file,
+            satellite_selection,
+            truth_file: self.truth_file,
+            truth_format: self.truth_format.unwrap_or_default(),
+            observation_file: self.observation_file,
+            observation_interval: self.observation_interval.unwrap_or(1.0),
+            phase_from_range: self.phase_from_range.unwrap_or(false),
+            precise_orbits,
+            systems,
+            satellite_filter,
             verbose: false,
             ..Default::default()
         };
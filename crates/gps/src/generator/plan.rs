@@ -0,0 +1,302 @@
+use std::{fmt::Write as _, str::FromStr};
+
+use constants::{LAMBDA_L1_INV, R2D};
+use geometry::{Azel, Dop, Ecef, Location};
+
+use crate::Error;
+
+#[cfg(test)]
+mod tests;
+
+/// Output format of a visibility plan.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PlanFormat {
+    /// One comma-separated row per epoch and satellite
+    #[default]
+    Csv,
+    /// A JSON array with one object per epoch
+    Json,
+}
+
+impl FromStr for PlanFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            _ => Err(Error::msg(format!(
+                "Unknown plan format '{s}', expected csv or json"
+            ))),
+        }
+    }
+}
+
+/// A satellite visible at one epoch of a visibility plan.
+#[derive(Debug, Clone)]
+pub struct PlannedSatellite {
+    /// Satellite PRN number
+    pub prn: usize,
+    /// Azimuth in degrees (clockwise from north)
+    pub azimuth: f64,
+    /// Elevation in degrees
+    pub elevation: f64,
+    /// Geometric range in meters
+    pub range: f64,
+    /// L1 Doppler shift in Hz
+    pub doppler: f64,
+    /// Ionospheric delay in meters
+    pub iono_delay: f64,
+}
+
+/// Receiver position, visible satellites and DOP at one epoch.
+#[derive(Debug, Clone)]
+pub struct PlanEpoch {
+    /// GPS week number
+    pub week: i32,
+    /// GPS time of week in seconds
+    pub tow: f64,
+    /// Receiver position in ECEF coordinates
+    pub position: Ecef,
+    /// Visible satellites in PRN order
+    pub satellites: Vec<PlannedSatellite>,
+    /// Dilution of precision (`None` with fewer than four satellites)
+    pub dop: Option<Dop>,
+}
+
+impl PlanEpoch {
+    /// Creates an epoch and computes its DOP from the satellite directions.
+    ///
+    /// # Arguments
+    /// * `week` - GPS week number
+    /// * `tow` - GPS time of week in seconds
+    /// * `position` - Receiver position in ECEF coordinates
+    /// * `satellites` - Visible satellites
+    ///
+    /// # Returns
+    /// A new `PlanEpoch`
+    pub fn new(
+        week: i32, tow: f64, position: Ecef, satellites: Vec<PlannedSatellite>,
+    ) -> Self {
+        let azels: Vec<Azel> = satellites
+            .iter()
+            .map(|sat| Azel {
+                az: sat.azimuth / R2D,
+                el: sat.elevation / R2D,
+            })
+            .collect();
+        let dop = Dop::from_azel(&azels).ok();
+        Self {
+            week,
+            tow,
+            position,
+            satellites,
+            dop,
+        }
+    }
+}
+
+/// Satellite visibility and geometry over a scenario, without generating
+/// samples.
+///
+/// Created by `SignalGenerator::plan`. The plan can be rendered as CSV or
+/// JSON, and as an ASCII sky plot of the satellite tracks.
+#[derive(Debug, Clone, Default)]
+pub struct VisibilityPlan {
+    /// Planned epochs in time order
+    pub epochs: Vec<PlanEpoch>,
+}
+
+impl VisibilityPlan {
+    /// Renders the plan in the requested format.
+    ///
+    /// # Arguments
+    /// * `format` - CSV or JSON
+    ///
+    /// # Returns
+    /// The rendered plan as a string
+    pub fn render(&self, format: PlanFormat) -> String {
+        match format {
+            PlanFormat::Csv => self.to_csv(),
+            PlanFormat::Json => self.to_json(),
+        }
+    }
+
+    /// Renders the plan as CSV with one row per epoch and satellite.
+    ///
+    /// # Returns
+    /// The CSV text including a header line
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from(
+            "week,tow,prn,az_deg,el_deg,range_m,doppler_hz,iono_m,gdop,pdop,\
+             hdop,vdop,tdop,num_sats\n",
+        );
+        for epoch in &self.epochs {
+            let dop = epoch.dop.map_or_else(
+                || ",,,,".to_string(),
+                |d| {
+                    format!(
+                        "{:.3},{:.3},{:.3},{:.3},{:.3}",
+                        d.gdop, d.pdop, d.hdop, d.vdop, d.tdop
+                    )
+                },
+            );
+            for sat in &epoch.satellites {
+                let _ = writeln!(
+                    out,
+                    "{},{:.1},{},{:.2},{:.2},{:.3},{:.3},{:.3},{},{}",
+                    epoch.week,
+                    epoch.tow,
+                    sat.prn,
+                    sat.azimuth,
+                    sat.elevation,
+                    sat.range,
+                    sat.doppler,
+                    sat.iono_delay,
+                    dop,
+                    epoch.satellites.len()
+                );
+            }
+        }
+        out
+    }
+
+    /// Renders the plan as a JSON array with one object per epoch.
+    ///
+    /// # Returns
+    /// The JSON text
+    pub fn to_json(&self) -> String {
+        let epochs: Vec<String> = self
+            .epochs
+            .iter()
+            .map(|epoch| {
+                let dop = epoch.dop.map_or_else(
+                    || "null".to_string(),
+                    |d| {
+                        format!(
+                            r#"{{"gdop":{:.3},"pdop":{:.3},"hdop":{:.3},"vdop":{:.3},"tdop":{:.3}}}"#,
+                            d.gdop, d.pdop, d.hdop, d.vdop, d.tdop
+                        )
+                    },
+                );
+                let satellites: Vec<String> = epoch
+                    .satellites
+                    .iter()
+                    .map(|sat| {
+                        format!(
+                            r#"{{"prn":{},"az":{:.2},"el":{:.2},"range":{:.3},"doppler":{:.3},"iono":{:.3}}}"#,
+                            sat.prn,
+                            sat.azimuth,
+                            sat.elevation,
+                            sat.range,
+                            sat.doppler,
+                            sat.iono_delay
+                        )
+                    })
+                    .collect();
+                let position = format!(
+                    "[{:.3},{:.3},{:.3}]",
+                    epoch.position.x, epoch.position.y, epoch.position.z
+                );
+                format!(
+                    r#"  {{"week":{},"tow":{:.1},"position":{},"dop":{},"satellites":[{}]}}"#,
+                    epoch.week,
+                    epoch.tow,
+                    position,
+                    dop,
+                    satellites.join(",")
+                )
+            })
+            .collect();
+        format!("[\n{}\n]\n", epochs.join(",\n"))
+    }
+
+    /// Draws an ASCII sky plot of the satellite tracks.
+    ///
+    /// Zenith is at the centre, the horizon on the outer circle and north at
+    /// the top. Earlier positions of each satellite are drawn as `.` and its
+    /// position at the last epoch where it is visible is labelled with the
+    /// PRN.
+    ///
+    /// # Returns
+    /// The sky plot as a multi-line string
+    pub fn sky_plot(&self) -> String {
+        /// Plot radius in rows (columns are doubled for the aspect ratio)
+        const RADIUS: usize = 10;
+        let rows = 2 * RADIUS + 1;
+        let cols = 4 * RADIUS + 1;
+        let mut grid = vec![vec![' '; cols]; rows];
+        let cell = |az: f64, el: f64| {
+            let r = (90.0 - el.clamp(0.0, 90.0)) / 90.0 * RADIUS as f64;
+            let az = az / R2D;
+            let x = (RADIUS as f64 + r * az.sin()).round() as usize * 2;
+            let y = (RADIUS as f64 - r * az.cos()).round() as usize;
+            (y.min(rows - 1), x.min(cols - 1))
+        };
+        // Horizon circle and cardinal points
+        for deg in (0..360).step_by(6) {
+            let (y, x) = cell(f64::from(deg), 0.0);
+            grid[y][x] = '·';
+        }
+        grid[0][2 * RADIUS] = 'N';
+        grid[rows - 1][2 * RADIUS] = 'S';
+        grid[RADIUS][0] = 'W';
+        grid[RADIUS][cols - 1] = 'E';
+        grid[RADIUS][2 * RADIUS] = '+';
+        // Tracks
+        let mut last = std::collections::BTreeMap::new();
+        for epoch in &self.epochs {
+            for sat in &epoch.satellites {
+                let (y, x) = cell(sat.azimuth, sat.elevation);
+                grid[y][x] = '.';
+                last.insert(sat.prn, (y, x));
+            }
+        }
+        for (prn, (y, x)) in last {
+            let label = format!("{prn:02}");
+            for (k, ch) in label.chars().enumerate() {
+                if x + k < cols {
+                    grid[y][x + k] = ch;
+                }
+            }
+        }
+        let mut out = String::new();
+        if let Some(epoch) = self.epochs.first() {
+            let llh = Location::from(&epoch.position);
+            let _ = writeln!(
+                out,
+                "Sky plot at {:.6},{:.6},{:.1} (lat, lon, height)",
+                llh.latitude * R2D,
+                llh.longitude * R2D,
+                llh.height
+            );
+        }
+        for row in grid {
+            let line: String = row.into_iter().collect();
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Converts a computed range into a planned satellite entry.
+///
+/// # Arguments
+/// * `prn` - Satellite PRN number
+/// * `rho` - Range information from `compute_range`
+///
+/// # Returns
+/// The satellite with angles in degrees and Doppler in Hz
+pub(crate) fn planned_satellite(
+    prn: usize, rho: &crate::datetime::TimeRange,
+) -> PlannedSatellite {
+    PlannedSatellite {
+        prn,
+        azimuth: rho.azel.az * R2D,
+        elevation: rho.azel.el * R2D,
+        range: rho.distance,
+        doppler: -rho.rate * LAMBDA_L1_INV,
+        iono_delay: rho.iono_delay,
+    }
+}
@@ -0,0 +1,256 @@
+use std::path::PathBuf;
+
+use constants::R2D;
+use geometry::{Azel, Dop, Ecef, Location};
+
+use super::{PlanEpoch, PlannedSatellite, VisibilityPlan};
+use crate::{Error, generator::SignalGeneratorBuilder};
+
+/// Receiver at 35.681298 N, 139.766247 E, 10 m
+const RECEIVER: [f64; 3] = [35.681_298, 139.766_247, 10.0];
+
+/// GPS satellites above the horizon of the receiver on January 1, 2022 at
+/// 00:00:00.
+const VISIBLE: [usize; 11] = [5, 10, 12, 13, 14, 15, 18, 20, 23, 24, 28];
+
+/// Plans the first minute of 2022 at the receiver every 30 seconds.
+fn known_plan() -> Result<VisibilityPlan, Error> {
+    let navigation = PathBuf::from(env!("CARGO_WORKSPACE_DIR"))
+        .join("resources/brdc0010.22n");
+    let mut generator = SignalGeneratorBuilder::default()
+        .navigation_file(Some(navigation))?
+        .location(Some(RECEIVER.to_vec()))?
+        .time(Some("2022-01-01 00:00:00-00".to_string()))?
+        .duration(Some(60.0))
+        .data_format(Some(8))?
+        .build()?;
+    assert!(generator.plan(0.0).is_err());
+    assert!(generator.plan(f64::NAN).is_err());
+    let plan = generator.plan(30.0)?;
+    // The visible satellites are those above the horizon
+    let receiver = receiver();
+    let up = [
+        (RECEIVER[0] / R2D).cos() * (RECEIVER[1] / R2D).cos(),
+        (RECEIVER[0] / R2D).cos() * (RECEIVER[1] / R2D).sin(),
+        (RECEIVER[0] / R2D).sin(),
+    ];
+    for (sv, eph) in generator.ephemerides.iter().enumerate() {
+        let (position, ..) =
+            eph.compute_satellite_state(&generator.receiver_gps_time);
+        let los = [
+            position[0] - receiver.x,
+            position[1] - receiver.y,
+            position[2] - receiver.z,
+        ];
+        let height = (0..3).map(|i| los[i] * up[i]).sum::<f64>();
+        assert_eq!(eph.vflg && height > 0.0, VISIBLE.contains(&(sv + 1)));
+    }
+    Ok(plan)
+}
+
+/// Returns the position of the receiver.
+fn receiver() -> Ecef {
+    Ecef::from(&Location::new(
+        RECEIVER[0] / R2D,
+        RECEIVER[1] / R2D,
+        RECEIVER[2],
+    ))
+}
+
+/// Returns the DOP of satellite directions given in degrees.
+fn dop(directions: &[(f64, f64)]) -> Result<Dop, Error> {
+    let azels = directions
+        .iter()
+        .map(|&(az, el)| Azel {
+            az: az / R2D,
+            el: el / R2D,
+        })
+        .collect::<Vec<_>>();
+    Dop::from_azel(&azels).map_err(|e| Error::msg(e.to_string()))
+}
+
+/// Returns a satellite of a synthetic plan.
+fn satellite(prn: usize, azimuth: f64, elevation: f64) -> PlannedSatellite {
+    PlannedSatellite {
+        prn,
+        azimuth,
+        elevation,
+        range: 2.2e7,
+        doppler: 0.0,
+        iono_delay: 0.0,
+    }
+}
+
+#[test]
+fn plan_csv_of_known_epoch() -> Result<(), Error> {
+    let csv = known_plan()?.to_csv();
+    let mut lines = csv.lines();
+    let header = lines
+        .next()
+        .ok_or_else(|| Error::msg("No header"))?
+        .split(',')
+        .collect::<Vec<_>>();
+    assert_eq!(header, [
+        "week",
+        "tow",
+        "prn",
+        "az_deg",
+        "el_deg",
+        "range_m",
+        "doppler_hz",
+        "iono_m",
+        "gdop",
+        "pdop",
+        "hdop",
+        "vdop",
+        "tdop",
+        "num_sats"
+    ]);
+    let rows = lines
+        .map(|line| line.split(',').collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    assert!(rows.iter().all(|row| row.len() == header.len()));
+    let first = rows
+        .iter()
+        .filter(|row| row[0] == "2190" && row[1] == "518400.0")
+        .collect::<Vec<_>>();
+    assert_eq!(rows.len(), 2 * first.len());
+    let prns = first
+        .iter()
+        .map(|row| row[2].parse::<usize>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| Error::msg(e.to_string()))?;
+    assert_eq!(prns, VISIBLE);
+    let value = |field: &str| {
+        field.parse::<f64>().map_err(|e| Error::msg(e.to_string()))
+    };
+    let mut directions = Vec::new();
+    for row in &first {
+        let (az, el) = (value(row[3])?, value(row[4])?);
+        assert!((0.0..360.0).contains(&az) && el > 0.0 && el <= 90.0);
+        // Ranges of satellites above the horizon
+        assert!((2.0e7..2.6e7).contains(&value(row[5])?));
+        assert_eq!(row[13], "11");
+        directions.push((az, el));
+    }
+    // The DOP columns are those of the listed directions
+    let expected = dop(&directions)?;
+    let row = first[0];
+    for (column, expected) in [
+        expected.gdop,
+        expected.pdop,
+        expected.hdop,
+        expected.vdop,
+        expected.tdop,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        assert!((value(row[8 + column])? - expected).abs() < 2e-3);
+    }
+    Ok(())
+}
+
+#[test]
+fn plan_json_of_known_epoch() -> Result<(), Error> {
+    let plan = known_plan()?;
+    let json = plan.to_json();
+    let lines = json.lines().collect::<Vec<_>>();
+    assert_eq!(lines.first(), Some(&"["));
+    assert_eq!(lines.last(), Some(&"]"));
+    let epochs = &lines[1..lines.len() - 1];
+    assert_eq!(epochs.len(), 2);
+    // Values of a key in one epoch object
+    let values = |text: &str, key: &str| {
+        text.split(&format!("\"{key}\":"))
+            .skip(1)
+            .map(|rest| {
+                rest.split([',', '}', ']'])
+                    .next()
+                    .unwrap_or_default()
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+    };
+    for (text, tow) in epochs.iter().zip(["518400.0", "518430.0"]) {
+        assert!(
+            text.starts_with("  {")
+                && text.trim_end_matches(',').ends_with('}')
+        );
+        assert_eq!(values(text, "week"), ["2190"]);
+        assert_eq!(values(text, "tow"), [tow]);
+        let prns = values(text, "prn")
+            .iter()
+            .map(|prn| prn.parse::<usize>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| Error::msg(e.to_string()))?;
+        assert_eq!(prns, VISIBLE);
+        assert_eq!(values(text, "position").len(), 1);
+        assert_eq!(values(text, "az").len(), VISIBLE.len());
+        let gdop = values(text, "gdop");
+        let [gdop] = gdop.as_slice() else {
+            panic!("No GDOP in {text}");
+        };
+        let gdop =
+            gdop.parse::<f64>().map_err(|e| Error::msg(e.to_string()))?;
+        assert!(gdop > 1.0 && gdop < 3.0);
+    }
+    Ok(())
+}
+
+#[test]
+fn plan_without_dop() {
+    let plan = VisibilityPlan {
+        epochs: vec![PlanEpoch::new(2190, 1.5, receiver(), vec![
+            satellite(3, 10.0, 45.0),
+            satellite(7, 200.0, 30.0),
+        ])],
+    };
+    assert!(plan.epochs[0].dop.is_none());
+    let csv = plan.to_csv();
+    let rows = csv.lines().skip(1).collect::<Vec<_>>();
+    assert_eq!(rows.len(), 2);
+    assert!(rows[0].starts_with("2190,1.5,3,10.00,45.00,"));
+    assert!(rows[0].ends_with(",,,,,,2"));
+    assert!(plan.to_json().contains(r#""dop":null"#));
+}
+
+#[test]
+fn sky_plot_markers() {
+    let epoch = |satellites| PlanEpoch::new(2190, 0.0, receiver(), satellites);
+    let plan = VisibilityPlan {
+        epochs: vec![
+            epoch(vec![satellite(3, 180.0, 45.0)]),
+            epoch(vec![satellite(3, 0.0, 45.0), satellite(21, 90.0, 30.0)]),
+        ],
+    };
+    let plot = plan.sky_plot();
+    let lines = plot.lines().collect::<Vec<_>>();
+    // Title and 21 rows of at most 41 columns
+    assert_eq!(
+        lines[0],
+        "Sky plot at 35.681298,139.766247,10.0 (lat, lon, height)"
+    );
+    let rows = lines[1..]
+        .iter()
+        .map(|line| line.chars().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    assert_eq!(rows.len(), 21);
+    assert!(rows.iter().all(|row| row.len() <= 41));
+    let at = |y: usize, x: usize| rows[y].get(x).copied().unwrap_or(' ');
+    assert_eq!(
+        (at(0, 20), at(20, 20), at(10, 0), at(10, 40)),
+        ('N', 'S', 'W', 'E')
+    );
+    // Halfway to the horizon to the north, labelled at its last position,
+    // and earlier to the south
+    assert_eq!((at(5, 20), at(5, 21)), ('0', '3'));
+    assert_eq!(at(15, 20), '.');
+    // Two thirds to the horizon to the east, two columns per row
+    assert_eq!((at(10, 34), at(10, 35)), ('2', '1'));
+    assert_eq!(at(10, 20), '+');
+    // Without epochs only the frame is drawn
+    let empty = VisibilityPlan::default().sky_plot();
+    assert_eq!(empty.lines().count(), 21);
+    assert!(!empty.contains('.'));
+}
@@ -0,0 +1,137 @@
+use std::str::FromStr;
+
+use crate::Error;
+
+/// Defines how satellites with a non-zero broadcast health are handled.
+///
+/// The health word comes from the navigation file (or from a per-PRN
+/// override) and is transmitted in subframe 1 of every simulated satellite:
+/// - In `Simulate` mode, unhealthy satellites are generated like any other and
+///   broadcast their real health word
+/// - In `Skip` mode, unhealthy satellites never get a channel
+/// - In `ForceHealthy` mode, every satellite is generated and broadcast as
+///   healthy
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HealthMode {
+    /// Simulate unhealthy satellites with their broadcast health word
+    #[default]
+    Simulate,
+    /// Do not allocate channels to unhealthy satellites
+    Skip,
+    /// Simulate all satellites and broadcast them as healthy
+    ForceHealthy,
+}
+
+impl FromStr for HealthMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "simulate" => Ok(Self::Simulate),
+            "skip" => Ok(Self::Skip),
+            "healthy" | "force-healthy" | "force_healthy" => {
+                Ok(Self::ForceHealthy)
+            }
+            _ => Err(Error::msg(format!(
+                "Unknown health mode '{s}', expected simulate, skip or healthy"
+            ))),
+        }
+    }
+}
+
+impl HealthMode {
+    /// Computes the health word broadcast by a satellite.
+    ///
+    /// A per-PRN override takes precedence over the health mode, so that a
+    /// single satellite can be flagged unhealthy in `ForceHealthy` mode.
+    ///
+    /// # Arguments
+    /// * `prn` - Satellite PRN number (1-based)
+    /// * `health` - 6-bit health word from the navigation file
+    /// * `overrides` - List of (PRN, health) pairs
+    ///
+    /// # Returns
+    /// * The 6-bit health word transmitted in subframe 1
+    pub(crate) fn broadcast_health(
+        self, prn: usize, health: i32, overrides: &[(usize, i32)],
+    ) -> i32 {
+        let health = if self == Self::ForceHealthy {
+            0
+        } else {
+            health
+        };
+        overrides
+            .iter()
+            .rev()
+            .find(|&&(override_prn, _)| override_prn == prn)
+            .map_or(health, |&(_, health)| health)
+    }
+}
+
+/// Restricts which satellites are allowed to occupy a channel.
+///
+/// The selection is evaluated every time channels are (re)allocated, after
+/// the visibility check. Satellites that are filtered out are treated as if
+/// they were not visible, so an include list of four PRNs yields a reduced
+/// constellation of at most four satellites.
+#[derive(Debug, Clone, Default)]
+pub struct SatelliteSelection {
+    /// PRNs that may be simulated (`None` means all PRNs)
+    pub include: Option<Vec<usize>>,
+    /// PRNs that are never simulated
+    pub exclude: Vec<usize>,
+    /// Maximum number of simultaneously allocated channels
+    pub max_satellites: Option<usize>,
+    /// Handling of satellites with a non-zero health word
+    pub health_mode: HealthMode,
+}
+
+impl SatelliteSelection {
+    /// Checks whether a satellite passes the include/exclude lists and the
+    /// health policy.
+    ///
+    /// # Arguments
+    /// * `prn` - Satellite PRN number (1-based)
+    /// * `health` - Broadcast 6-bit health word of the satellite
+    ///
+    /// # Returns
+    /// * `true` if the satellite may be allocated to a channel
+    pub fn allows(&self, prn: usize, health: i32) -> bool {
+        if self
+            .include
+            .as_ref()
+            .is_some_and(|list| !list.contains(&prn))
+        {
+            return false;
+        }
+        if self.exclude.contains(&prn) {
+            return false;
+        }
+        !(self.health_mode == HealthMode::Skip && health != 0)
+    }
+
+    /// Checks whether another channel may be allocated.
+    ///
+    /// # Arguments
+    /// * `allocated` - Number of channels currently in use
+    ///
+    /// # Returns
+    /// * `true` if the maximum number of satellites has not been reached
+    pub fn has_capacity(&self, allocated: usize) -> bool {
+        self.max_satellites.is_none_or(|max| allocated < max)
+    }
+
+    /// Returns the selection of a system whose satellites are not numbered
+    /// by GPS PRNs, such as Galileo or GLONASS.
+    ///
+    /// The include and exclude lists name GPS PRNs and are dropped; the
+    /// satellite limit and the health policy apply to the channels of the
+    /// system.
+    pub fn without_prn_lists(&self) -> Self {
+        Self {
+            include: None,
+            exclude: Vec::new(),
+            ..self.clone()
+        }
+    }
+}
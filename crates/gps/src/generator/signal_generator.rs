@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, thread, time::Duration};
 
 use constants::*;
 use geometry::Ecef;
@@ -6,13 +6,26 @@ use geometry::Ecef;
 use crate::{
     Error,
     channel::Channel,
+    corrections::{BaseStation, RtcmEncoder, RtcmOutput},
     datetime::{DateTime, GpsTime},
     ephemeris::Ephemeris,
-    generator::utils::MotionMode,
+    filter::{FrontEndFilter, SatelliteFilter},
+    generator::{
+        plan::{PlanEpoch, VisibilityPlan, planned_satellite},
+        selection::SatelliteSelection,
+        utils::MotionMode,
+    },
+    impairment::ImpairmentStage,
     io::{DataFormat, IQWriter},
     ionoutc::IonoUtc,
+    observation::ObservationLogger,
+    precise::{PreciseOrbits, PreciseState},
     propagation::compute_range,
+    signal::{SignalSystem, SystemContext, band_offset, signal_gain},
+    superframe::SuperframePages,
     table::ANT_PAT_DB,
+    timeline::EphemerisTimeline,
+    truth::{TruthFormat, TruthRecord, TruthWriter},
 };
 /// Main class for GPS signal generation and simulation.
 ///
@@ -29,14 +42,17 @@ use crate::{
 /// 3. Call `initialize()` to set up the simulation
 /// 4. Call `run_simulation()` to generate the GPS signals
 pub struct SignalGenerator {
-    /// Satellite ephemeris data organized in hourly sets
-    pub ephemerides: Box<[[Ephemeris; MAX_SAT]; EPHEM_ARRAY_SIZE]>,
-    /// Index of the currently active ephemeris set
-    pub valid_ephemerides_index: usize,
+    /// Ephemerides of all satellites read from the navigation files
+    pub timeline: EphemerisTimeline,
+    /// Ephemeris currently broadcast by each satellite (index PRN - 1)
+    pub ephemerides: [Ephemeris; MAX_SAT],
     /// Array of satellite signal channels being tracked
     pub channels: [Channel; MAX_CHAN],
     /// Ionospheric and UTC parameters
     pub ionoutc: IonoUtc,
+    /// Subframe 4 and 5 page schedule, or `None` to repeat pages 18 and 25
+    /// in every frame
+    pub superframe_pages: Option<SuperframePages>,
     /// Tracking which satellites are allocated to which channels (-1 = not
     /// allocated)
     pub allocated_satellite: [i32; MAX_SAT],
@@ -52,10 +68,28 @@ pub struct SignalGenerator {
     pub antenna_pattern: [f64; 37],
     /// Simulation mode (static or dynamic position)
     pub mode: MotionMode,
-    /// Elevation mask angle in radians (satellites below this are not visible)
+    /// Elevation mask angle in degrees (satellites below this are not visible)
     pub elevation_mask: f64,
     /// Sampling frequency in Hz (typically 2.6MHz)
     pub sample_frequency: f64,
+    /// RF centre frequency of the output in Hz (GPS L1 by default)
+    pub center_frequency: f64,
+    /// Frequency the centre frequency is shifted to in the output in Hz (0
+    /// by default)
+    pub intermediate_frequency: f64,
+    /// Whether to write real samples instead of I/Q samples
+    pub real_output: bool,
+    /// Number of generated samples per output sample
+    pub oversampling: usize,
+    /// Transmit filters of the satellites at the generation rate, if any
+    pub satellite_filter: Option<SatelliteFilter>,
+    /// Band-limiting filter of the composite samples, if any
+    pub filter: Option<FrontEndFilter>,
+    /// Transmitter impairments applied to the composite samples, if any
+    pub impairments: Option<ImpairmentStage>,
+    /// Whether the GPS L1 carrier lies within the output band; the L1 C/A
+    /// channels add no samples otherwise
+    pub l1_in_band: bool,
     /// Time step between samples in seconds (typically 0.1s)
     pub sample_rate: f64,
     /// I/Q data format for output
@@ -68,6 +102,26 @@ pub struct SignalGenerator {
     pub output_file: Option<PathBuf>,
     /// I/Q sample writer
     pub writer: Option<IQWriter>,
+    /// Satellite include/exclude lists, channel limit and health policy
+    pub satellite_selection: SatelliteSelection,
+    /// Truth log file path
+    pub truth_file: Option<PathBuf>,
+    /// Truth log output format
+    pub truth_format: TruthFormat,
+    /// Truth log writer
+    pub truth_writer: Option<TruthWriter>,
+    /// RINEX observation file path
+    pub observation_file: Option<PathBuf>,
+    /// RINEX observation interval in seconds
+    pub observation_interval: f64,
+    /// RINEX observation logger
+    pub observation_logger: Option<ObservationLogger>,
+    /// Whether carrier phases start aligned with the pseudoranges
+    pub phase_from_range: bool,
+    /// Precise orbits and clocks for the simulated ranges, if loaded
+    pub precise_orbits: Option<PreciseOrbits>,
+    /// Satellite systems simulated next to GPS L1 C/A in the same output
+    pub systems: Vec<Box<dyn SignalSystem>>,
     /// Whether the generator has been initialized
     pub initialized: bool,
     /// Whether to show detailed channel status
@@ -76,10 +130,11 @@ pub struct SignalGenerator {
 impl Default for SignalGenerator {
     fn default() -> Self {
         Self {
-            ephemerides: Box::default(),
-            valid_ephemerides_index: usize::default(),
+            timeline: EphemerisTimeline::default(),
+            ephemerides: std::array::from_fn(|_| Ephemeris::default()),
             channels: std::array::from_fn(|_| Channel::default()),
             ionoutc: IonoUtc::default(),
+            superframe_pages: None,
             allocated_satellite: [0; MAX_SAT],
             positions: Vec::new(),
             simulation_step_count: usize::default(),
@@ -89,6 +144,14 @@ impl Default for SignalGenerator {
             mode: MotionMode::Static,
             elevation_mask: f64::default(),
             sample_frequency: 0.0,
+            center_frequency: CARR_FREQ,
+            intermediate_frequency: 0.0,
+            real_output: false,
+            oversampling: 1,
+            satellite_filter: None,
+            filter: None,
+            impairments: None,
+            l1_in_band: true,
             sample_rate: 0.0,
             data_format: DataFormat::Bits8,
             fixed_gain: None,
@@ -96,6 +159,16 @@ impl Default for SignalGenerator {
             // iq_buffer: Vec::new(),
             output_file: None,
             writer: None,
+            satellite_selection: SatelliteSelection::default(),
+            truth_file: None,
+            truth_format: TruthFormat::default(),
+            truth_writer: None,
+            observation_file: None,
+            observation_interval: 1.0,
+            observation_logger: None,
+            phase_from_range: false,
+            precise_orbits: None,
+            systems: Vec::new(),
             initialized: false,
             verbose: true,
         }
@@ -108,9 +181,13 @@ impl SignalGenerator {
     /// simulation:
     /// - Displays the simulation mode and initial position
     /// - Sets up the receiver time
+    /// - Builds the subframe 4 and 5 pages if the navigation message follows a
+    ///   page schedule
     /// - Allocates satellite channels based on visibility
     /// - Initializes the antenna gain pattern
+    /// - Allocates the channels of the other satellite systems
     /// - Sets up the I/Q sample buffer and writer
+    /// - Creates the truth log and RINEX observation file if requested
     ///
     /// This method must be called before `run_simulation()`.
     ///
@@ -119,8 +196,8 @@ impl SignalGenerator {
     /// * `Err(Error)` - If there's an error during initialization
     ///
     /// # Errors
-    /// * Returns an error if the output file cannot be opened or if there's an
-    ///   issue with the I/Q writer
+    /// * Returns an error if the output file, truth log or observation file
+    ///   cannot be opened or if there's an issue with the I/Q writer
     pub fn initialize(&mut self) -> Result<(), Error> {
         // Initialize channels
         match self.mode {
@@ -157,6 +234,9 @@ impl SignalGenerator {
             .for_each(|s| *s = -1);
         // Initial reception time
         self.receiver_gps_time = self.receiver_gps_time.add_secs(0.0);
+        if let Some(pages) = self.superframe_pages.as_mut() {
+            pages.update(&self.ephemerides, &self.ionoutc);
+        }
         // Allocate visible satellites
         self.allocate_channel(self.positions[0]);
         Self::print_channel_status(&self.channels);
@@ -168,6 +248,24 @@ impl SignalGenerator {
         for (i, item) in self.antenna_pattern.iter_mut().take(37).enumerate() {
             *item = 10.0f64.powf(-ANT_PAT_DB[i] / 20.0);
         }
+        let mut systems = std::mem::take(&mut self.systems);
+        let context = self.system_context(&self.positions[0]);
+        if band_offset(&context, CARR_FREQ, CODE_FREQ).is_none() {
+            eprintln!(
+                "Warning: GPS L1 C/A lies outside the output band around \
+                 {:.3} MHz.",
+                self.center_frequency / 1e6
+            );
+        }
+        // Low sampling frequencies cut the main lobe of L1 C/A, as in the
+        // original gpssim; only a carrier outside the band is left out
+        let l1_in_band = band_offset(&context, CARR_FREQ, 0.0).is_some();
+        for system in &mut systems {
+            system.initialize(&context);
+            system.print_status();
+        }
+        self.systems = systems;
+        self.l1_in_band = l1_in_band;
 
         self.iq_buffer_size =
             (self.sample_frequency * self.sample_rate).floor() as usize;
@@ -176,6 +274,25 @@ impl SignalGenerator {
                 file,
                 self.data_format,
                 self.iq_buffer_size,
+                self.real_output,
+            )?),
+            None => None,
+        };
+        self.truth_writer = match &self.truth_file {
+            Some(file) => Some(TruthWriter::new(
+                file,
+                self.truth_format,
+                self.ionoutc.dtls,
+            )?),
+            None => None,
+        };
+        self.observation_logger = match &self.observation_file {
+            Some(file) => Some(ObservationLogger::new(
+                file,
+                self.observation_interval,
+                &self.receiver_gps_time.add_secs(self.sample_rate),
+                &self.positions[0],
+                self.ionoutc.dtls,
             )?),
             None => None,
         };
@@ -188,7 +305,9 @@ impl SignalGenerator {
     ///
     /// This method determines which satellites are visible from the given
     /// position, allocates channels to visible satellites, and deallocates
-    /// channels for satellites that are no longer visible.
+    /// channels for satellites that are no longer visible. Satellites
+    /// rejected by the satellite selection are treated as not visible, and
+    /// no new channel is allocated once the satellite limit is reached.
     ///
     /// # Arguments
     /// * `xyz` - The current receiver position in ECEF coordinates
@@ -202,18 +321,20 @@ impl SignalGenerator {
         // let mut r_ref: f64 = 0.;
         // #[allow(unused_variables)]
         // let mut r_xyz: f64;
-        for (sv, eph) in self.ephemerides[self.valid_ephemerides_index]
-            .iter()
-            .enumerate()
-            .take(MAX_SAT)
-        {
-            if let Some((azel, true)) = eph.check_visibility(
-                &self.receiver_gps_time,
-                &xyz,
-                self.elevation_mask,
-            ) {
+        for (sv, eph) in self.ephemerides.iter().enumerate().take(MAX_SAT) {
+            if self.satellite_selection.allows(sv + 1, eph.svhlth)
+                && let Some((azel, true)) = eph.check_visibility(
+                    &self.receiver_gps_time,
+                    &xyz,
+                    self.elevation_mask,
+                )
+            {
                 visible_satellite_count += 1; // Number of visible satellites
-                if self.allocated_satellite[sv] == -1 {
+                let allocated_count =
+                    self.channels.iter().filter(|ch| ch.prn != 0).count();
+                if self.allocated_satellite[sv] == -1
+                    && self.satellite_selection.has_capacity(allocated_count)
+                {
                     // Visible but not allocated
                     //
                     // Allocated new satellite
@@ -226,11 +347,24 @@ impl SignalGenerator {
                             ichan.update_for_satellite(
                                 sv + 1,
                                 eph,
+                                &PreciseState::new(
+                                    self.precise_orbits.as_ref(),
+                                    sv + 1,
+                                    eph,
+                                ),
                                 &self.ionoutc,
                                 &self.receiver_gps_time,
                                 &xyz,
                                 azel,
+                                self.superframe_pages.as_ref(),
+                            );
+                            ichan.set_carrier_offset(
+                                CARR_FREQ - self.center_frequency
+                                    + self.intermediate_frequency,
                             );
+                            if self.phase_from_range {
+                                ichan.align_carrier_phase();
+                            }
                             break;
                         }
                         channel_index = i + 1;
@@ -255,7 +389,11 @@ impl SignalGenerator {
     /// output file.
     ///
     /// This method performs the following steps:
-    /// 1. Accumulates signal components from all active satellite channels
+    /// 1. Accumulates signal components from all active satellite channels,
+    ///    filters them by the satellite transmit filters and the receiver
+    ///    filter and decimates them when oversampling or a receiver bandwidth
+    ///    is configured, and applies the transmitter impairments when
+    ///    configured
     /// 2. Quantizes and stores the combined I/Q samples in the buffer
     /// 3. Writes the I/Q data to the output file
     ///
@@ -268,37 +406,83 @@ impl SignalGenerator {
     /// * Returns an error if writing to the output file fails
     #[inline]
     fn generate_and_write_samples(&mut self) -> Result<(), Error> {
-        let sampling_period = self.sample_frequency.recip();
+        let sampling_period = self.generation_period();
         let writer = self
             .writer
             .as_mut()
             .ok_or_else(|| Error::msg("IQWriter not initialized"))?;
         let buffer_size = writer.buffer_size;
         for isamp in 0..buffer_size {
-            let mut i_acc: i32 = 0;
-            let mut q_acc: i32 = 0;
-            // Step 1: Accumulate signal components from all channels
-            for i in 0..MAX_CHAN {
-                if self.channels[i].prn != 0 {
-                    let (ip, qp) = self.channels[i]
-                        .generate_iq_contribution(self.antenna_gains[i]);
-                    // Accumulate for all visible satellites
-                    // Add to total signal accumulation
-                    i_acc += ip;
-                    q_acc += qp;
-                    // Update code phase
-                    // Update code phase (C/A code sequence control)
-                    self.channels[i].update_navigation_bits(sampling_period);
+            let (mut i_acc, mut q_acc) = loop {
+                let mut i_acc: i32 = 0;
+                let mut q_acc: i32 = 0;
+                // Step 1: Accumulate signal components from all channels
+                for i in 0..MAX_CHAN {
+                    if self.channels[i].prn != 0 {
+                        if self.l1_in_band {
+                            let (ip, qp) = self.channels[i]
+                                .generate_iq_contribution(
+                                    self.antenna_gains[i],
+                                );
+                            // Accumulate for all visible satellites
+                            // Add to total signal accumulation
+                            i_acc += ip;
+                            q_acc += qp;
+                        }
+                        // Update code phase
+                        // Update code phase (C/A code sequence control)
+                        self.channels[i]
+                            .update_navigation_bits(sampling_period);
+                    }
+                }
+                // The satellite filter band-limits every carrier on its own,
+                // the GPS L1 C/A channels being its first source
+                let (i_acc, q_acc) = if let Some(satellite_filter) =
+                    self.satellite_filter.as_mut()
+                {
+                    satellite_filter.add(0, i_acc, q_acc);
+                    for (k, system) in self.systems.iter_mut().enumerate() {
+                        let (ip, qp) = system.next_sample(sampling_period);
+                        satellite_filter.add(k + 1, ip, qp);
+                    }
+                    match satellite_filter.push() {
+                        Some(sample) => sample,
+                        None => continue,
+                    }
+                } else {
+                    for system in &mut self.systems {
+                        let (ip, qp) = system.next_sample(sampling_period);
+                        i_acc += ip;
+                        q_acc += qp;
+                    }
+                    (i_acc, q_acc)
+                };
+                // Without filter every generated sample is an output sample
+                match self.filter.as_mut() {
+                    Some(filter) => {
+                        if let Some(sample) = filter.push(i_acc, q_acc) {
+                            break sample;
+                        }
+                    }
+                    None => break (i_acc, q_acc),
                 }
+            };
+
+            if let Some(impairments) = self.impairments.as_mut() {
+                (i_acc, q_acc) = impairments.apply(i_acc, q_acc);
             }
 
             // Step 2: Quantize and store I/Q samples
-            // Scaled by 2^7
-            // i_acc = (i_acc + 64) >> 7;
-            // q_acc = (q_acc + 64) >> 7;
-            // Store I/Q samples into buffer
-            writer.buffer[isamp * 2] = ((i_acc + 64) >> 7) as i16; // 8-bit quantization (with rounding)
-            writer.buffer[isamp * 2 + 1] = ((q_acc + 64) >> 7) as i16;
+            // Scaled by 2^7, with rounding, saturating instead of wrapping
+            // around when many strong signals or impairments overflow the
+            // 16-bit range
+            let quantize = |acc: i32| {
+                (acc.saturating_add(64) >> 7)
+                    .clamp(i32::from(i16::MIN), i32::from(i16::MAX))
+                    as i16
+            };
+            writer.buffer[isamp * 2] = quantize(i_acc);
+            writer.buffer[isamp * 2 + 1] = quantize(q_acc);
         }
 
         // Step 3: Write I/Q data to output file (handling different formats)
@@ -314,32 +498,50 @@ impl SignalGenerator {
     /// - Computes the current pseudorange (distance) to each satellite
     /// - Updates the code and carrier phase based on the pseudorange change
     /// - Calculates the signal gain based on path loss and antenna pattern
+    /// - Updates the channels of the other satellite systems
     ///
     /// The gain calculation depends on whether fixed gain mode is enabled:
     /// - If fixed gain is set, all satellites use the same constant gain
     /// - Otherwise, gain is calculated based on distance and elevation angle
     ///
+    /// When a truth log is open, one record per active channel is written
+    /// with the values that were just applied. The same records feed the
+    /// RINEX observation file.
+    ///
     /// # Arguments
     /// * `current_location` - The current receiver position in ECEF coordinates
-    fn update_channel_parameters(&mut self, current_location: Ecef) {
-        let ephemeris_set_index = self.valid_ephemerides_index;
-        let sampling_period = self.sample_frequency.recip();
+    ///
+    /// # Errors
+    /// * Returns an error if the truth log or observation file cannot be
+    ///   written
+    fn update_channel_parameters(
+        &mut self, current_location: Ecef,
+    ) -> Result<(), Error> {
+        let sampling_period = self.generation_period();
+        let mut truth_records = Vec::new();
         for i in 0..MAX_CHAN {
             // Only process channels with assigned satellites
             if self.channels[i].prn != 0 {
                 // Convert satellite PRN to array index
                 let sv = self.channels[i].prn - 1;
-                let eph = &self.ephemerides[ephemeris_set_index][sv];
+                let eph = &self.ephemerides[sv];
                 // Calculate current pseudorange (propagation delay)
                 // Refresh code phase and data bit counters
 
                 // Current pseudorange
-                let rho = compute_range(
-                    eph,
+                let mut rho = compute_range(
+                    &PreciseState::new(
+                        self.precise_orbits.as_ref(),
+                        sv + 1,
+                        eph,
+                    ),
                     &self.ionoutc,
                     &self.receiver_gps_time,
                     &current_location,
                 );
+                // Keep the range continuous after an ephemeris switch
+                rho.range +=
+                    self.channels[i].ephemeris_offset(&self.receiver_gps_time);
                 self.channels[i].update_state(
                     &rho,
                     self.sample_rate,
@@ -347,25 +549,77 @@ impl SignalGenerator {
                 );
 
                 // Calculate signal gain (considering path loss and antenna
-                // pattern) Signal gain
-                // Apply gain mode selection
-                let gain = if let Some(fixed_gain) = self.fixed_gain {
-                    // Fixed gain mode
-                    fixed_gain // hold the power level constant
-                } else {
-                    // With path loss compensation
-                    // Path loss
-                    let path_loss = 20_200_000.0 / rho.distance;
-                    // Receiver antenna gain
-                    let boresight_angle_index =
-                        ((90.0 - rho.azel.el * R2D) / 5.0) as usize; // covert elevation to boresight
-                    let ant_gain = self.antenna_pattern[boresight_angle_index];
-                    (path_loss * ant_gain * 128.0) as i32 // scaled by 2^7
-                };
+                // pattern)
+                let gain =
+                    signal_gain(&rho, self.fixed_gain, &self.antenna_pattern);
                 // Store gain for IQ generation phase
                 self.antenna_gains[i] = gain; // hold the power level constant
+                if self.truth_writer.is_some()
+                    || self.observation_logger.is_some()
+                {
+                    let channel = &self.channels[i];
+                    truth_records.push(TruthRecord {
+                        prn: channel.prn,
+                        satellite_position: rho.position,
+                        satellite_velocity: rho.velocity,
+                        range: rho.distance,
+                        pseudorange: rho.range,
+                        doppler: channel.carrier_frequency(),
+                        code_phase: channel.code_phase(),
+                        carrier_phase: channel.carrier_cycles(),
+                        iono_delay: rho.iono_delay,
+                        tropo_delay: 0.0,
+                        elevation: rho.azel.el * R2D,
+                        azimuth: rho.azel.az * R2D,
+                        gain,
+                    });
+                }
             }
         }
+        if let Some(writer) = self.truth_writer.as_mut() {
+            writer.write_step(
+                &self.receiver_gps_time,
+                &current_location,
+                &truth_records,
+            )?;
+        }
+        if let Some(logger) = self.observation_logger.as_mut() {
+            logger.record_step(&self.receiver_gps_time, &truth_records)?;
+        }
+        let mut systems = std::mem::take(&mut self.systems);
+        let context = self.system_context(&current_location);
+        for system in &mut systems {
+            system.update_channels(&context);
+        }
+        self.systems = systems;
+        Ok(())
+    }
+
+    /// Returns the period of the generated samples, shorter than the output
+    /// sampling period when oversampling.
+    fn generation_period(&self) -> f64 {
+        (self.sample_frequency * self.oversampling as f64).recip()
+    }
+
+    /// Returns the generator state shared with the other satellite systems.
+    ///
+    /// # Arguments
+    /// * `location` - The current receiver position in ECEF coordinates
+    fn system_context<'a>(&'a self, location: &'a Ecef) -> SystemContext<'a> {
+        SystemContext {
+            time: &self.receiver_gps_time,
+            location,
+            ionoutc: &self.ionoutc,
+            elevation_mask: self.elevation_mask,
+            step: self.sample_rate,
+            sampling_period: self.generation_period(),
+            sample_frequency: self.sample_frequency,
+            center_frequency: self.center_frequency,
+            intermediate_frequency: self.intermediate_frequency,
+            real_output: self.real_output,
+            fixed_gain: self.fixed_gain,
+            antenna_pattern: &self.antenna_pattern,
+        }
     }
 
     /// Handles periodic tasks that occur at regular intervals during
@@ -373,92 +627,264 @@ impl SignalGenerator {
     ///
     /// This method performs tasks that need to happen periodically (every 30
     /// seconds):
+    /// - Switches each satellite to a newer ephemeris once it is due, and
+    ///   regenerates the subframes of its channel and the almanac pages
     /// - Updates the navigation message for all active channels
-    /// - Refreshes the ephemeris data set if a newer one is available
-    /// - Updates the navigation subframes if the ephemeris set changed
     /// - Reallocates satellite channels based on current visibility
+    /// - Regenerates the navigation messages and channels of the other
+    ///   satellite systems
     ///
     /// These periodic updates ensure that the simulation accurately reflects
     /// the changing satellite positions and navigation data over time.
     ///
+    /// Ephemerides are switched only at frame boundaries, not at every 6
+    /// second subframe. Subframes 1 to 3 of a frame must carry the same
+    /// issue of data (IODC and IODE), which receivers compare before they use
+    /// an ephemeris, and the control segment also cuts over to a new data set
+    /// at the start of a frame. Switching within a frame would pair subframes
+    /// of two data sets. Waiting for the next boundary delays a switch by at
+    /// most 30 seconds, well within the validity of both ephemerides, and
+    /// keeps the output identical to gps-sdr-sim.
+    ///
     /// # Arguments
     /// * `current_location` - The current receiver position in ECEF coordinates
     fn handle_periodic_tasks(&mut self, current_location: Ecef) {
         let current_step_index =
             (self.receiver_gps_time.sec * 10.0 + 0.5) as i32;
         if current_step_index % 300 == 0 {
-            // Every 30 seconds
-            // 1. Update Nav Msg for active channels
-            for ichan in self.channels.iter_mut().take(MAX_CHAN) {
-                if ichan.prn != 0 {
-                    ichan.generate_nav_msg(&self.receiver_gps_time, false);
+            // Every 30 seconds, at a navigation message frame boundary
+            // 1. Take newer ephemerides into use, so that the frame generated
+            //    next carries their subframes 1 to 3
+            let time = self.receiver_gps_time.clone();
+            let switched = self.select_ephemerides(&time);
+            for (prn, previous) in &switched {
+                if let Some(channel) =
+                    self.channels.iter_mut().find(|ch| ch.prn == *prn)
+                {
+                    let eph = &self.ephemerides[prn - 1];
+                    let orbits = self.precise_orbits.as_ref();
+                    channel.switch_ephemeris(
+                        eph,
+                        &PreciseState::new(orbits, *prn, previous),
+                        &PreciseState::new(orbits, *prn, eph),
+                        &self.ionoutc,
+                        &time,
+                        &current_location,
+                    );
                 }
             }
-            // 2. Refresh ephemeris index and subframes if necessary
-            // Refresh ephemeris and subframes
-            // Quick and dirty fix. Need more elegant way.
-            let mut refreshed_eph = false;
-            let next_ephemeris_set_index = self.valid_ephemerides_index + 1;
-            // Check if next ephemeris set is valid and timely
-            if next_ephemeris_set_index < self.ephemerides.len()
-                && self.ephemerides[next_ephemeris_set_index]
-                    .iter()
-                    .take(MAX_SAT)
-                    .any(|eph| eph.vflg)
-            {
-                // Find the earliest ToC in the next set (or check a specific
-                // SV) Simplified check: Assume SV 0's ToC is
-                // representative
-                if self.ephemerides[next_ephemeris_set_index][0].vflg {
-                    let dt = self.ephemerides[next_ephemeris_set_index][0]
-                        .toc
-                        .diff_secs(&self.receiver_gps_time);
-                    // If the next ephemeris is close (e.g., within an hour),
-                    // switch to it
-                    if dt.abs() < SECONDS_IN_HOUR {
-                        // Use absolute diff
-                        self.valid_ephemerides_index = next_ephemeris_set_index;
-                        refreshed_eph = true;
-                        eprintln!(
-                            "\nSwitched to ephemeris set index \
-                             {next_ephemeris_set_index}"
-                        );
-                    }
+            if !switched.is_empty() {
+                if let Some(pages) = self.superframe_pages.as_mut() {
+                    pages.update(&self.ephemerides, &self.ionoutc);
                 }
+                let prns = switched
+                    .iter()
+                    .map(|(prn, _)| prn.to_string())
+                    .collect::<Vec<_>>();
+                eprintln!("\nSwitched ephemeris of PRN {}", prns.join(", "));
             }
-
-            // If ephemeris refreshed, update subframes for active channels
-            if refreshed_eph {
-                let current_ephemeris_set_index = self.valid_ephemerides_index;
-                for ichan in self
-                    .channels
-                    .iter_mut()
-                    .take(MAX_CHAN)
-                    .filter(|ch| ch.prn != 0)
-                {
-                    let sv = ichan.prn - 1;
-                    ichan.generate_navigation_subframes(
-                        &self.ephemerides[current_ephemeris_set_index][sv],
-                        &self.ionoutc,
+            // 2. Update Nav Msg for active channels
+            for ichan in self.channels.iter_mut().take(MAX_CHAN) {
+                if ichan.prn != 0 {
+                    ichan.generate_nav_msg(
+                        &self.receiver_gps_time,
+                        false,
+                        self.superframe_pages.as_ref(),
                     );
-                    // self.ephemerides[current_ephemeris_set_index][sv]
-                    //     .generate_navigation_subframes(
-                    //         &self.ionoutc,
-                    //         &mut ichan.sbf,
-                    //     );
-                    // Maybe need to regenerate nav message bits immediately?
-                    // ichan.generate_nav_msg(&self.receiver_gps_time, false);
-                    // // Already done above, maybe redundant
                 }
             }
             // Update channel allocation
             self.allocate_channel(current_location);
+            // 3. Regenerate the messages and channels of the other systems
+            let mut systems = std::mem::take(&mut self.systems);
+            let context = self.system_context(&current_location);
+            for system in &mut systems {
+                system.update_navigation(&context);
+            }
+            self.systems = systems;
 
             // Show details about simulated channels
             if self.verbose {
                 Self::print_channel_status(&self.channels);
+                for system in &self.systems {
+                    system.print_status();
+                }
+            }
+        }
+    }
+
+    /// Takes the ephemeris due at the given time into use for every
+    /// satellite.
+    ///
+    /// A satellite keeps its current ephemeris when the timeline has no valid
+    /// one for the time, or when the due one is the same data set.
+    ///
+    /// # Arguments
+    /// * `time` - Current GPS time
+    ///
+    /// # Returns
+    /// The PRN numbers of the switched satellites with their previous
+    /// ephemeris
+    fn select_ephemerides(
+        &mut self, time: &GpsTime,
+    ) -> Vec<(usize, Ephemeris)> {
+        let mut switched = Vec::new();
+        for (sv, current) in self.ephemerides.iter_mut().enumerate() {
+            if let Some(eph) = self.timeline.select(sv + 1, time)
+                && !(current.vflg && current.same_data_set(eph))
+            {
+                switched
+                    .push((sv + 1, std::mem::replace(current, eph.clone())));
+            }
+        }
+        switched
+    }
+
+    /// Computes satellite visibility and geometry over the scenario without
+    /// generating samples.
+    ///
+    /// The plan walks the same receiver trajectory and ephemerides as
+    /// `run_simulation`, and at every `interval` seconds lists the satellites
+    /// that are above the elevation mask and pass the satellite selection,
+    /// together with their azimuth, elevation, range, Doppler, ionospheric
+    /// delay and the DOP of the resulting geometry.
+    ///
+    /// The generator does not need to be initialized, and its state other
+    /// than the selected ephemerides is left untouched.
+    ///
+    /// # Arguments
+    /// * `interval` - Time between planned epochs in seconds (rounded to a
+    ///   multiple of the update step)
+    ///
+    /// # Returns
+    /// * `Ok(VisibilityPlan)` - The planned epochs
+    /// * `Err(Error)` - If the interval is not positive
+    ///
+    /// # Errors
+    /// * Returns an error if `interval` is not a positive number
+    pub fn plan(&mut self, interval: f64) -> Result<VisibilityPlan, Error> {
+        if interval.is_nan() || interval <= 0.0 {
+            return Err(Error::msg("Plan interval must be positive"));
+        }
+        let current = self.ephemerides.clone();
+        let num_steps = match self.mode {
+            MotionMode::Static => self.simulation_step_count.max(1),
+            MotionMode::Dynamic => self.simulation_step_count,
+        };
+        let step_interval =
+            ((interval / self.sample_rate).round() as usize).max(1);
+        let mut plan = VisibilityPlan::default();
+        for step_index in (0..num_steps).step_by(step_interval) {
+            let time = self
+                .receiver_gps_time
+                .add_secs(step_index as f64 * self.sample_rate);
+            self.select_ephemerides(&time);
+            let location = match self.mode {
+                MotionMode::Static => self.positions[0],
+                MotionMode::Dynamic => self
+                    .positions
+                    .get(step_index)
+                    .copied()
+                    .unwrap_or(self.positions[0]),
+            };
+            let mut satellites = Vec::new();
+            for (sv, eph) in self.ephemerides.iter().enumerate().take(MAX_SAT) {
+                if self.satellite_selection.allows(sv + 1, eph.svhlth)
+                    && let Some((_, true)) = eph.check_visibility(
+                        &time,
+                        &location,
+                        self.elevation_mask,
+                    )
+                {
+                    let rho = compute_range(
+                        &PreciseState::new(
+                            self.precise_orbits.as_ref(),
+                            sv + 1,
+                            eph,
+                        ),
+                        &self.ionoutc,
+                        &time,
+                        &location,
+                    );
+                    satellites.push(planned_satellite(sv + 1, &rho));
+                }
             }
+            plan.epochs.push(PlanEpoch::new(
+                time.week, time.sec, location, satellites,
+            ));
         }
+        self.ephemerides = current;
+        Ok(plan)
+    }
+
+    /// Streams the observations of a virtual reference station as RTCM 3.
+    ///
+    /// The station observes the same sky as the simulated receiver from a
+    /// fixed position over the scenario duration. Every epoch carries the
+    /// MSM observations of the visible satellites, preceded by the station
+    /// position (1005) every 10 seconds and the GPS ephemerides (1019) when
+    /// they are first used, change or are 30 seconds old. Streams to a TCP
+    /// server are paced in real time so that clients receive one epoch per
+    /// interval.
+    ///
+    /// Carrier phases of the base equal its pseudoranges. Generate the rover
+    /// signal with `phase_from_range` enabled so that both sides share the
+    /// same integer ambiguities.
+    ///
+    /// The generator does not need to be initialized, and its state other
+    /// than the selected ephemerides is left untouched.
+    ///
+    /// # Arguments
+    /// * `base` - Reference station configuration
+    /// * `output` - Destination of the stream
+    ///
+    /// # Returns
+    /// * `Ok(count)` - The number of epochs sent
+    /// * `Err(Error)` - If a message cannot be encoded or sent
+    ///
+    /// # Errors
+    /// * Returns an error if the interval is not positive
+    /// * Returns an error if an observation does not fit its RTCM data field
+    /// * Returns an error if the output cannot be written
+    pub fn rtcm_stream(
+        &mut self, base: &BaseStation, output: &mut RtcmOutput,
+    ) -> Result<usize, Error> {
+        if base.interval.is_nan() || base.interval <= 0.0 {
+            return Err(Error::msg("RTCM interval must be positive"));
+        }
+        let current = self.ephemerides.clone();
+        let num_steps = self.simulation_step_count.max(1);
+        let step_interval =
+            ((base.interval / self.sample_rate).round() as usize).max(1);
+        let epoch_duration =
+            Duration::from_secs_f64(step_interval as f64 * self.sample_rate);
+        let mut encoder = RtcmEncoder::new(*base);
+        let mut count = 0;
+        for step_index in (0..num_steps).step_by(step_interval) {
+            let started = std::time::Instant::now();
+            let time = self
+                .receiver_gps_time
+                .add_secs(step_index as f64 * self.sample_rate);
+            self.select_ephemerides(&time);
+            let frames = encoder.encode_epoch(
+                &time,
+                &self.ephemerides,
+                self.precise_orbits.as_ref(),
+                &self.ionoutc,
+                self.elevation_mask,
+                &self.satellite_selection,
+            )?;
+            for frame in &frames {
+                output.send(frame)?;
+            }
+            output.flush()?;
+            count += 1;
+            if output.is_server() {
+                thread::sleep(epoch_duration.saturating_sub(started.elapsed()));
+            }
+        }
+        self.ephemerides = current;
+        Ok(count)
     }
 
     /// Runs the GPS signal simulation and generates baseband I/Q samples.
@@ -516,7 +942,7 @@ impl SignalGenerator {
             };
             // Step 1: Update satellite parameters (pseudorange, phase, and
             // gain)
-            self.update_channel_parameters(current_location);
+            self.update_channel_parameters(current_location)?;
 
             // Step 2: Generate baseband I/Q sample data
             self.generate_and_write_samples()?;
@@ -534,6 +960,12 @@ impl SignalGenerator {
             );
         }
 
+        if let Some(writer) = self.truth_writer.as_mut() {
+            writer.flush()?;
+        }
+        if let Some(logger) = self.observation_logger.as_mut() {
+            logger.flush()?;
+        }
         eprintln!("\nDone!");
         eprintln!(
             "Process time = {:.1} [sec]",
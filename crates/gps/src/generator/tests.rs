@@ -0,0 +1,136 @@
+use rinex::almanac::AlmanacRecord;
+use test_case::test_case;
+
+use super::utils::{almanac_timeline, ura_index};
+use crate::{datetime::GpsTime, ephemeris::Ephemeris};
+
+/// Almanac of a satellite at the end of week 2190.
+const ALMANAC: AlmanacRecord = AlmanacRecord {
+    prn: 7,
+    health: 0,
+    eccentricity: 0.012,
+    toa: 589_824.0,
+    inclination: 0.958,
+    omega_dot: -8.1e-9,
+    sqrt_a: 5153.6,
+    omega0: 1.234,
+    perigee: -0.876,
+    mean_anomaly: 2.345,
+    af0: 1.2e-4,
+    af1: 3.6e-12,
+    week: 2190,
+};
+
+/// Returns the distance between the positions of two ephemerides and the
+/// difference of their clock corrections in meters.
+fn state_difference(
+    a: &Ephemeris, b: &Ephemeris, time: &GpsTime,
+) -> (f64, f64) {
+    let (pos_a, _, clock_a) = a.compute_satellite_state(time);
+    let (pos_b, _, clock_b) = b.compute_satellite_state(time);
+    let distance = pos_a
+        .iter()
+        .zip(pos_b)
+        .map(|(a, b)| (a - b).powi(2))
+        .sum::<f64>()
+        .sqrt();
+    (distance, (clock_a[0] - clock_b[0]).abs() * 299_792_458.0)
+}
+
+#[test]
+fn almanac_propagated_to_scenario() {
+    let toa = GpsTime {
+        week: 2190,
+        sec: ALMANAC.toa,
+    };
+    // Without propagation the ephemeris of the almanac is only valid within
+    // half a week of its reference time
+    let reference = almanac_timeline(&[ALMANAC], &toa, &toa);
+    let Some(reference) = reference.first(7) else {
+        panic!("No almanac ephemeris");
+    };
+    // A scenario two days later, in the next week
+    let start = toa.add_secs(2.0 * 86400.0);
+    let timeline =
+        almanac_timeline(&[ALMANAC], &start, &start.add_secs(3600.0));
+    for step in 0..=6 {
+        let time = start.add_secs(f64::from(step) * 600.0);
+        let Some(eph) = timeline.select(7, &time) else {
+            panic!("No ephemeris at {}", time.sec);
+        };
+        assert_eq!(eph.toe.week, 2191);
+        let (distance, clock) = state_difference(eph, reference, &time);
+        assert!(distance < 1e-3, "{distance} m apart");
+        assert!(clock < 1e-6, "clocks {clock} m apart");
+    }
+}
+
+#[test]
+fn almanac_months_after_reference_time() {
+    let toa = GpsTime {
+        week: 2190,
+        sec: ALMANAC.toa,
+    };
+    let start = toa.add_secs(150.0 * 86400.0 + 1234.5);
+    let end = start.add_secs(6.0 * 3600.0);
+    let timeline = almanac_timeline(&[ALMANAC], &start, &end);
+    let mut previous: Option<&Ephemeris> = None;
+    for step in 0..=72 {
+        let time = start.add_secs(f64::from(step) * 300.0);
+        let Some(eph) = timeline.select(7, &time) else {
+            panic!("No ephemeris at {}", time.sec);
+        };
+        // The broadcast time of week resolves to the simulated reference
+        // time, within the fit interval
+        assert!(eph.fit_interval.is_finite());
+        assert!(
+            time.diff_secs(&eph.toe).abs() <= eph.fit_interval * 1800.0,
+            "{} s from the reference time",
+            time.diff_secs(&eph.toe)
+        );
+        assert!(eph.toe.sec.rem_euclid(16.0) < 1e-9);
+        assert!(eph.toc.diff_secs(&eph.toe).abs() < 1e-9);
+        let broadcast = Ephemeris {
+            toe: GpsTime {
+                week: time.week,
+                sec: eph.toe.sec,
+            },
+            ..eph.clone()
+        };
+        let week = if time.diff_secs(&broadcast.toe) > 302_400.0 {
+            time.week + 1
+        } else if time.diff_secs(&broadcast.toe) < -302_400.0 {
+            time.week - 1
+        } else {
+            time.week
+        };
+        assert_eq!(week, eph.toe.week);
+        let (distance, _) = state_difference(eph, &broadcast, &time);
+        assert!(distance < 1e-3, "{distance} m apart");
+        // Consecutive data sets describe the same orbit and clock
+        if let Some(previous) = previous
+            && previous.iode != eph.iode
+        {
+            let (distance, clock) = state_difference(eph, previous, &time);
+            assert!(distance < 1e-3, "{distance} m apart at the switch");
+            assert!(clock < 1e-6, "clocks {clock} m apart at the switch");
+        }
+        previous = Some(eph);
+    }
+}
+
+#[test_case(0.0, 0)]
+#[test_case(2.4, 0)]
+#[test_case(2.41, 1)]
+#[test_case(3.4, 1)]
+#[test_case(4.85, 2)]
+#[test_case(4.86, 3)]
+#[test_case(13.65, 5)]
+#[test_case(24.0, 6)]
+#[test_case(3072.0, 13)]
+#[test_case(6144.0, 14)]
+#[test_case(6144.1, 15)]
+#[test_case(f64::NAN, 15)]
+fn ura_index_bounds(accuracy: f64, index: i32) {
+    assert_eq!(ura_index(accuracy), index);
+}
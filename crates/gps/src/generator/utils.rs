@@ -1,13 +1,18 @@
-use std::path::Path;
+use std::path::PathBuf;
 
 use constants::{
-    EPHEM_ARRAY_SIZE, GM_EARTH, MAX_SAT, OMEGA_EARTH, SECONDS_IN_HOUR,
+    GM_EARTH, MAX_SAT, OMEGA_EARTH, SECONDS_IN_HOUR, SECONDS_IN_WEEK,
 };
+use rinex::almanac::AlmanacRecord;
 
 use crate::{
+    constellation::{EPHEMERIS_INTERVAL, wrap_angle},
     datetime::{DateTime, GpsTime},
     ephemeris::Ephemeris,
     ionoutc::IonoUtc,
+    precise::PreciseOrbits,
+    signal::SystemNavigation,
+    timeline::EphemerisTimeline,
 };
 /// Defines the motion mode for the GPS signal simulation.
 ///
@@ -22,170 +27,369 @@ pub enum MotionMode {
     Dynamic,
     // UserControl - Future feature for real-time user-controlled motion
 }
-/// Type alias for the data returned by the `read_navigation_data` function.
-///
-/// This tuple contains:
-/// - The number of valid ephemeris sets
-/// - Ionospheric and UTC parameters
-/// - A 2D array of ephemeris data organized by time set and satellite PRN
-type Data = (
-    usize,
-    IonoUtc,
-    Box<[[Ephemeris; MAX_SAT]; EPHEM_ARRAY_SIZE]>,
-);
-
-/// Reads ionospheric/UTC parameters and ephemeris data from a RINEX navigation
-/// file.
-///
-/// This function parses a RINEX navigation file to extract:
+/// Reads ionospheric/UTC parameters and ephemeris data from RINEX navigation
+/// files.
+///
+/// This function parses one or more RINEX navigation files to extract:
 /// - Satellite ephemeris data (orbit and clock parameters)
 /// - Ionospheric correction parameters
 /// - UTC conversion parameters
 ///
-/// The ephemeris data is organized into hourly sets to allow for
-/// time-appropriate selection during simulation. Each set contains ephemeris
-/// data for all satellites that are valid within approximately the same time
-/// period.
+/// The ephemerides of all files are merged into a per-satellite timeline, so
+/// files of consecutive days can be combined. Records found in several files
+/// (same PRN, IODE and TOE) are kept once. The ionospheric and UTC parameters
+/// are taken from the first file providing a complete set of them. The
+/// records of the other satellite systems and all header corrections are
+/// collected for the systems simulated next to GPS.
+///
+/// In lenient mode, malformed header lines and records are skipped and a
+/// warning with their position is printed for each of them.
 ///
 /// # Arguments
-/// * `file` - Reference to a path pointing to a RINEX navigation file
+/// * `files` - Paths of the RINEX navigation files
+/// * `lenient` - Whether to skip malformed header lines and records
 ///
 /// # Returns
-/// * `Ok((count, ionoutc, ephemerides))` - A tuple containing:
-///   - `count`: The number of valid ephemeris sets
-///   - `ionoutc`: Ionospheric and UTC parameters
-///   - `ephemerides`: A 2D array of ephemeris data organized by time set and
-///     satellite PRN
-/// * `Err(Error)` - If the file cannot be read or parsed
+/// * `Ok((ionoutc, timeline, navigation))` - Ionospheric and UTC parameters,
+///   the GPS ephemerides of all satellites and the navigation data of the other
+///   systems
+/// * `Err(Error)` - If a file cannot be read or parsed
 ///
 /// # Errors
-/// * Returns an error if the file cannot be opened
-/// * Returns an error if the RINEX format is invalid
+/// * `Error::NavigationRead` - If a file cannot be opened or its RINEX format
+///   is invalid, with the path of the file and the underlying error
 pub fn read_navigation_data(
-    file: &dyn AsRef<Path>,
-) -> Result<Data, crate::Error> {
-    let rinex_data = rinex::Rinex::read_file(file)?;
-    let mut ephemeris_data: Box<[[Ephemeris; MAX_SAT]; EPHEM_ARRAY_SIZE]> =
-        std::array::from_fn(|_| std::array::from_fn(|_| Ephemeris::default()))
-            .into();
+    files: &[PathBuf], lenient: bool,
+) -> Result<(IonoUtc, EphemerisTimeline, SystemNavigation), crate::Error> {
     let mut iono_utc = IonoUtc::default();
-
-    iono_utc.read_from_rinex(&rinex_data);
-    let mut current_set_index = 0;
-    let mut current_set_start_time: Option<GpsTime> = None;
-
-    // Iterate through all ephemeris records in RINEX file
-    for rinex_record in &rinex_data.ephemerides {
-        // Calculate satellite index (0-based)
-        // PRN (Pseudo-Random Noise) code number, often 1-based in RINEX
-        let Some(sv) = rinex_record.prn.checked_sub(1) else {
-            // Log or handle PRN 0 case if necessary
-            eprintln!(
-                "Warning: Encountered RINEX record with PRN 0, skipping."
-            );
-            continue;
+    let mut timeline = EphemerisTimeline::default();
+    let mut navigation = SystemNavigation::default();
+    for file in files {
+        let located = |e: crate::Error| crate::Error::NavigationRead {
+            path: file.clone(),
+            source: Box::new(e),
         };
-        if sv >= MAX_SAT {
-            eprintln!(
-                "Warning: Skipping ephemeris for SV PRN {} (index {}) as it \
-                 exceeds MAX_SAT {}",
-                rinex_record.prn, sv, MAX_SAT
-            );
-            continue;
+        let rinex_data = if lenient {
+            rinex::Rinex::read_file_lenient(file)
+        } else {
+            rinex::Rinex::read_file(file)
         }
-
-        let utc_datetime =
-            DateTime::from(rinex_record.time_of_clock.in_tz("UTC")?);
-        let gps_time = GpsTime::from(&utc_datetime);
-
-        // --- Determine which time set this ephemeris belongs to ---
-        let mut update_set = false;
-        match current_set_start_time {
-            Some(ref start_time) => {
-                // If time difference exceeds 1 hour
-                if gps_time.diff_secs(start_time).abs() > SECONDS_IN_HOUR {
-                    update_set = true;
-                }
-            }
-            None => {
-                // First valid ephemeris record, set current set start time
-                current_set_start_time = Some(gps_time.clone());
-                // No need to update index since we start at index 0
+        .map_err(|e| located(e.into()))?;
+        for warning in &rinex_data.warnings {
+            eprintln!("Warning: {}: {warning}", file.display());
+        }
+        if !iono_utc.vflg {
+            iono_utc.read_from_rinex(&rinex_data);
+        }
+        navigation.extend(&rinex_data);
+        for rinex_record in &rinex_data.ephemerides {
+            if rinex_record.prn == 0 || rinex_record.prn > MAX_SAT {
+                eprintln!(
+                    "Warning: Skipping ephemeris for SV PRN {} as it is not \
+                     in 1..={MAX_SAT}",
+                    rinex_record.prn
+                );
+                continue;
             }
+            let eph = convert_ephemeris(rinex_record).map_err(located)?;
+            timeline.insert(rinex_record.prn, eph);
         }
+    }
+    Ok((iono_utc, timeline, navigation))
+}
 
-        if update_set {
-            // Move to next ephemeris set
-            current_set_index += 1;
-            // Check if new set index exceeds bounds
-            if current_set_index >= EPHEM_ARRAY_SIZE {
-                println!(
-                    "Warning: Reached maximum ephemeris sets \
-                     ({EPHEM_ARRAY_SIZE}). Stopping processing."
-                );
-                break; // Stop processing more records
+/// Reads SP3 precise orbit files into the precise orbits.
+///
+/// # Arguments
+/// * `orbits` - Precise orbits to add the positions and clocks to
+/// * `files` - Paths of the SP3 files
+///
+/// # Errors
+/// * `Error::PreciseRead` - If a file cannot be read or parsed or is not in GPS
+///   time, with the path of the file and the underlying error
+pub fn read_sp3_files(
+    orbits: &mut PreciseOrbits, files: &[PathBuf],
+) -> Result<(), crate::Error> {
+    for file in files {
+        rinex::Sp3::read_file(file)
+            .map_err(crate::Error::from)
+            .and_then(|sp3| orbits.insert_sp3(&sp3))
+            .map_err(|e| crate::Error::PreciseRead {
+                path: file.clone(),
+                source: Box::new(e),
+            })?;
+    }
+    Ok(())
+}
+
+/// Reads RINEX clock files into the precise orbits.
+///
+/// # Arguments
+/// * `orbits` - Precise orbits to add the satellite clocks to
+/// * `files` - Paths of the RINEX clock files
+///
+/// # Errors
+/// * `Error::PreciseRead` - If a file cannot be read or parsed or is not in GPS
+///   time, with the path of the file and the underlying error
+pub fn read_clock_files(
+    orbits: &mut PreciseOrbits, files: &[PathBuf],
+) -> Result<(), crate::Error> {
+    for file in files {
+        rinex::RinexClock::read_file(file)
+            .map_err(crate::Error::from)
+            .and_then(|clock| orbits.insert_clock(&clock))
+            .map_err(|e| crate::Error::PreciseRead {
+                path: file.clone(),
+                source: Box::new(e),
+            })?;
+    }
+    Ok(())
+}
+
+/// Reads YUMA or SEM almanac files.
+///
+/// # Arguments
+/// * `files` - Paths of the almanac files
+///
+/// # Returns
+/// * `Ok(records)` - The almanacs of all files in file order
+/// * `Err(Error)` - If a file cannot be read or parsed
+///
+/// # Errors
+/// * `Error::AlmanacRead` - If a file cannot be read or parsed, with the path
+///   of the file and the underlying error
+pub fn read_almanac_files(
+    files: &[PathBuf],
+) -> Result<Vec<AlmanacRecord>, crate::Error> {
+    let mut records = Vec::new();
+    for file in files {
+        let almanac = rinex::Almanac::read_file(file).map_err(|e| {
+            crate::Error::AlmanacRead {
+                path: file.clone(),
+                source: Box::new(e.into()),
             }
-            // Update the start time for the new ephemeris set
-            current_set_start_time = Some(gps_time.clone());
+        })?;
+        records.extend(almanac.records);
+    }
+    Ok(records)
+}
+
+/// Builds an ephemeris timeline from almanacs.
+///
+/// Each almanac is propagated to the even two-hour boundaries from the one
+/// at or before `start` to the first one after `end`, like the nominal
+/// constellation, and broadcast as ephemerides valid for four hours. The
+/// ephemerides have no harmonic corrections, mean motion correction or
+/// inclination rate, and their clock polynomial is the almanac clock
+/// propagated to the reference time. Since the navigation message only
+/// carries the time of week of the reference time, this keeps the broadcast
+/// orbits equal to the simulated ones however far the scenario is from the
+/// almanac epoch. Almanac weeks below 1024 are taken modulo 1024 and
+/// resolved to the full week nearest to the week of `start`.
+///
+/// # Arguments
+/// * `records` - Almanacs read from YUMA or SEM files
+/// * `start` - Start of the scenario
+/// * `end` - End of the scenario
+///
+/// # Returns
+/// The ephemerides of all satellites with a PRN in 1..=`MAX_SAT`
+pub fn almanac_timeline(
+    records: &[AlmanacRecord], start: &GpsTime, end: &GpsTime,
+) -> EphemerisTimeline {
+    let mut timeline = EphemerisTimeline::default();
+    for record in records {
+        if record.prn == 0 || record.prn > MAX_SAT {
+            eprintln!(
+                "Warning: Skipping almanac for SV PRN {} as it is not in \
+                 1..={MAX_SAT}",
+                record.prn
+            );
+            continue;
         }
-        // --- End set index logic ---
-
-        // Get a mutable reference to the target Ephemeris structure to populate
-        // current_set_index is guaranteed to be in bounds here
-        let eph = &mut ephemeris_data[current_set_index][sv];
-        eph.t = utc_datetime;
-        eph.toc = gps_time;
-        eph.af0 = rinex_record.sv_clock.bias;
-        eph.af1 = rinex_record.sv_clock.drift;
-        eph.af2 = rinex_record.sv_clock.drift_rate;
-
-        // orbit1
-        eph.iode = rinex_record.orbit1.iode as i32;
-        eph.crs = rinex_record.orbit1.crs;
-        eph.deltan = rinex_record.orbit1.delta_n;
-        eph.m0 = rinex_record.orbit1.m0;
-
-        // orbit2
-        eph.cuc = rinex_record.orbit2.cuc;
-        eph.ecc = rinex_record.orbit2.ecc;
-        eph.cus = rinex_record.orbit2.cus;
-        eph.sqrta = rinex_record.orbit2.sqrta;
-
-        // orbit3
-        eph.toe.sec = rinex_record.orbit3.toe;
-        eph.cic = rinex_record.orbit3.cic;
-        eph.omg0 = rinex_record.orbit3.omega;
-        eph.cis = rinex_record.orbit3.cis;
-
-        // orbit4
-        eph.inc0 = rinex_record.orbit4.i0;
-        eph.crc = rinex_record.orbit4.crc;
-        eph.aop = rinex_record.orbit4.omega;
-        eph.omgdot = rinex_record.orbit4.omega_dot;
-
-        // orbit5
-        eph.idot = rinex_record.orbit5.idot;
-        eph.codeL2 = rinex_record.orbit5.code_l2 as i32;
-        eph.toe.week = rinex_record.orbit5.week as i32;
-
-        // orbit6
-        eph.svhlth = rinex_record.orbit6.sv_health as i32;
-        if eph.svhlth > 0 && eph.svhlth < 32 {
-            eph.svhlth += 32;
+        let mut toe = GpsTime {
+            week: start.week,
+            sec: (start.sec / EPHEMERIS_INTERVAL).floor() * EPHEMERIS_INTERVAL,
+        };
+        loop {
+            timeline
+                .insert(record.prn, convert_almanac(record, start.week, &toe));
+            if toe.diff_secs(end) > 0.0 {
+                break;
+            }
+            toe = toe.add_secs(EPHEMERIS_INTERVAL);
         }
-        eph.tgd = rinex_record.orbit6.tgd;
-        eph.iodc = rinex_record.orbit6.iodc as i32;
-
-        // Set valid flag
-        eph.vflg = true;
-        eph.A = eph.sqrta * eph.sqrta;
-        eph.n = (GM_EARTH / (eph.A * eph.A * eph.A)).sqrt() + eph.deltan;
-        eph.sq1e2 = (1.0 - eph.ecc * eph.ecc).sqrt();
-        eph.omgkdot = eph.omgdot - OMEGA_EARTH;
     }
+    timeline
+}
+
+/// Converts a RINEX ephemeris record into the ephemeris used by the
+/// simulation, with its derived orbit constants.
+///
+/// The transmission time of the record is not kept: the handover words carry
+/// the simulated time of week instead.
+///
+/// # Arguments
+/// * `rinex_record` - GPS ephemeris read from a RINEX file
+///
+/// # Returns
+/// * `Ok(Ephemeris)` - The converted ephemeris
+/// * `Err(Error)` - If the time of clock cannot be converted
+///
+/// # Errors
+/// * Returns an error if the time of clock is out of range
+fn convert_ephemeris(
+    rinex_record: &rinex::ephemeris::Ephemeris,
+) -> Result<Ephemeris, crate::Error> {
+    let utc_datetime = DateTime::from(rinex_record.time_of_clock.in_tz("UTC")?);
+    let gps_time = GpsTime::from(&utc_datetime);
+    let mut eph = Ephemeris {
+        t: utc_datetime,
+        toc: gps_time,
+        ..Ephemeris::default()
+    };
+    eph.af0 = rinex_record.sv_clock.bias;
+    eph.af1 = rinex_record.sv_clock.drift;
+    eph.af2 = rinex_record.sv_clock.drift_rate;
+
+    // orbit1
+    eph.iode = rinex_record.orbit1.iode as i32;
+    eph.crs = rinex_record.orbit1.crs;
+    eph.deltan = rinex_record.orbit1.delta_n;
+    eph.m0 = rinex_record.orbit1.m0;
 
-    if current_set_start_time.is_some() {
-        current_set_index += 1;
+    // orbit2
+    eph.cuc = rinex_record.orbit2.cuc;
+    eph.ecc = rinex_record.orbit2.ecc;
+    eph.cus = rinex_record.orbit2.cus;
+    eph.sqrta = rinex_record.orbit2.sqrta;
+
+    // orbit3
+    eph.toe.sec = rinex_record.orbit3.toe;
+    eph.cic = rinex_record.orbit3.cic;
+    eph.omg0 = rinex_record.orbit3.omega;
+    eph.cis = rinex_record.orbit3.cis;
+
+    // orbit4
+    eph.inc0 = rinex_record.orbit4.i0;
+    eph.crc = rinex_record.orbit4.crc;
+    eph.aop = rinex_record.orbit4.omega;
+    eph.omgdot = rinex_record.orbit4.omega_dot;
+
+    // orbit5
+    eph.idot = rinex_record.orbit5.idot;
+    eph.codeL2 = rinex_record.orbit5.code_l2 as i32;
+    eph.toe.week = rinex_record.orbit5.week as i32;
+    eph.l2p_flag = rinex_record.orbit5.l2_pseudorange as i32;
+
+    // orbit6
+    eph.ura = ura_index(rinex_record.orbit6.sv_accuracy);
+    eph.svhlth = rinex_record.orbit6.sv_health as i32;
+    if eph.svhlth > 0 && eph.svhlth < 32 {
+        eph.svhlth += 32;
     }
-    Ok((current_set_index, iono_utc, ephemeris_data))
+    eph.tgd = rinex_record.orbit6.tgd;
+    eph.iodc = rinex_record.orbit6.iodc as i32;
+
+    // orbit7
+    eph.tom = rinex_record.orbit7.tom;
+    eph.fit_interval = rinex_record.orbit7.fit_interval;
+
+    // Set valid flag
+    eph.vflg = true;
+    set_derived_constants(&mut eph);
+    Ok(eph)
+}
+
+/// Encodes a user range accuracy as the URA index of IS-GPS-200.
+///
+/// # Arguments
+/// * `accuracy` - Satellite accuracy in meters as written in RINEX files
+///
+/// # Returns
+/// The smallest index whose upper bound holds the accuracy, or 15 when no
+/// accuracy prediction is available
+pub(crate) fn ura_index(accuracy: f64) -> i32 {
+    /// Upper bounds in meters of the URA indices 0 to 14.
+    const URA_BOUNDS: [f64; 15] = [
+        2.4, 3.4, 4.85, 6.85, 9.65, 13.65, 24.0, 48.0, 96.0, 192.0, 384.0,
+        768.0, 1536.0, 3072.0, 6144.0,
+    ];
+    URA_BOUNDS
+        .iter()
+        .position(|&bound| accuracy <= bound)
+        .map_or(15, |index| index as i32)
+}
+
+/// Converts an almanac into an ephemeris, see `almanac_timeline`.
+///
+/// The mean anomaly, the longitude of the ascending node at the weekly epoch
+/// and the clock bias are propagated from the almanac reference time to the
+/// reference time of the ephemeris.
+///
+/// # Arguments
+/// * `record` - Almanac of a satellite
+/// * `reference_week` - GPS week that the almanac week is resolved around
+/// * `toe` - Reference time of the ephemeris and clock
+fn convert_almanac(
+    record: &AlmanacRecord, reference_week: i32, toe: &GpsTime,
+) -> Ephemeris {
+    /// Number of weeks after which the 10-bit almanac week rolls over.
+    const WEEK_ROLLOVER: i32 = 1024;
+    let week = record.week as i32;
+    let week = if week < WEEK_ROLLOVER {
+        let offset = (week - reference_week).rem_euclid(WEEK_ROLLOVER);
+        if offset < WEEK_ROLLOVER / 2 {
+            reference_week + offset
+        } else {
+            reference_week + offset - WEEK_ROLLOVER
+        }
+    } else {
+        week
+    };
+    let toa = GpsTime {
+        week,
+        sec: record.toa,
+    };
+    let elapsed = toe.diff_secs(&toa);
+    let mean_motion = (GM_EARTH / record.sqrt_a.powi(6)).sqrt();
+    // The weekly epoch of the node longitude moves with the reference week
+    let weeks = f64::from(toe.week - toa.week);
+    // The issue of data counts the two-hour data sets, so that consecutive
+    // ephemerides differ
+    let iod = (toe.week * 84 + (toe.sec / EPHEMERIS_INTERVAL) as i32) % 256;
+    let mut eph = Ephemeris {
+        vflg: true,
+        t: DateTime::from(toe),
+        toc: toe.clone(),
+        toe: toe.clone(),
+        iodc: iod,
+        iode: iod,
+        ecc: record.eccentricity,
+        sqrta: record.sqrt_a,
+        m0: wrap_angle(record.mean_anomaly + mean_motion * elapsed),
+        omg0: wrap_angle(
+            record.omega0 + record.omega_dot * elapsed
+                - OMEGA_EARTH * SECONDS_IN_WEEK * weeks,
+        ),
+        inc0: record.inclination,
+        aop: record.perigee,
+        omgdot: record.omega_dot,
+        af0: record.af0 + record.af1 * elapsed,
+        af1: record.af1,
+        svhlth: record.health as i32,
+        fit_interval: 2.0 * EPHEMERIS_INTERVAL / SECONDS_IN_HOUR,
+        ..Ephemeris::default()
+    };
+    set_derived_constants(&mut eph);
+    eph
+}
+
+/// Sets the derived orbit constants of an ephemeris from its broadcast
+/// parameters.
+pub(crate) fn set_derived_constants(eph: &mut Ephemeris) {
+    eph.A = eph.sqrta * eph.sqrta;
+    eph.n = (GM_EARTH / (eph.A * eph.A * eph.A)).sqrt() + eph.deltan;
+    eph.sq1e2 = (1.0 - eph.ecc * eph.ecc).sqrt();
+    eph.omgkdot = eph.omgdot - OMEGA_EARTH;
 }
@@ -0,0 +1,19 @@
+//! GLONASS L1OF signal generation.
+//!
+//! This module simulates the FDMA signals of the GLONASS satellites in the
+//! L1 band, each on its own frequency channel around 1602 MHz, in the same
+//! composite output. The satellite orbits are integrated from the broadcast
+//! state vectors of the GLONASS ephemerides of RINEX 2 or mixed RINEX 3 and
+//! RINEX 4 navigation files, and the navigation message strings are built
+//! from them.
+
+/// ST ranging code and time mark
+mod codes;
+/// Navigation message strings
+mod gnav;
+/// Broadcast ephemerides and orbit integration
+mod orbit;
+/// GLONASS satellite system driven by the signal generator
+mod system;
+
+pub use system::GlonassSystem;
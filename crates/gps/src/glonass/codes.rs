@@ -0,0 +1,42 @@
+use constants::ST_CODE_LEN;
+
+#[cfg(test)]
+mod tests;
+
+/// Feedback stages of the ST code register, 1 + x^5 + x^9.
+const ST_TAPS: [usize; 2] = [5, 9];
+
+/// Register stage whose output is the ST code.
+const ST_OUTPUT_STAGE: usize = 7;
+
+/// Time mark at the end of every navigation message string, one bit per
+/// 10 ms (first bit first).
+const TIME_MARK: &str = "111110001101110101000010010110";
+
+/// Generates the ST ranging code, common to all GLONASS satellites.
+///
+/// The code is the output of the seventh stage of a 9-stage shift register
+/// starting with all ones. Chips of binary value 0 are transmitted as +1 and
+/// chips of value 1 as -1.
+///
+/// # Returns
+/// The 511 chips (+1 or -1)
+pub fn st_code() -> Vec<i8> {
+    let stage = |register: u16, stage: usize| register >> (stage - 1) & 1;
+    let mut register = 0x1ff;
+    let mut chips = Vec::with_capacity(ST_CODE_LEN);
+    for _ in 0..ST_CODE_LEN {
+        let bit = stage(register, ST_OUTPUT_STAGE);
+        chips.push(if bit == 0 { 1 } else { -1 });
+        let feedback = ST_TAPS
+            .iter()
+            .fold(0, |sum, &tap| sum ^ stage(register, tap));
+        register = (register << 1 | feedback) & 0x1ff;
+    }
+    chips
+}
+
+/// Returns the bits of the time mark.
+pub fn time_mark() -> impl Iterator<Item = u8> {
+    TIME_MARK.bytes().map(|bit| bit - b'0')
+}
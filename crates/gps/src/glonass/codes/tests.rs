@@ -0,0 +1,48 @@
+use constants::ST_CODE_LEN;
+
+use super::{TIME_MARK, st_code, time_mark};
+
+/// Returns the binary values of the ST code chips.
+fn st_bits() -> Vec<u8> {
+    st_code().iter().map(|&chip| u8::from(chip < 0)).collect()
+}
+
+#[test]
+fn st_code_sequence() {
+    let bits = st_bits();
+    assert_eq!(bits.len(), ST_CODE_LEN);
+    // Seven ones of the initial register, then the first feedback bits
+    assert_eq!(bits[..16], [1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 1, 1, 1, 1]);
+    // Feedback of stages 5 and 9, G(X) = 1 + X^5 + X^9
+    for n in 0..ST_CODE_LEN - 9 {
+        assert_eq!(bits[n + 9], bits[n + 4] ^ bits[n], "chip {n}");
+    }
+    // Maximal length sequence: 256 ones and 255 zeros
+    assert_eq!(bits.iter().map(|&bit| u32::from(bit)).sum::<u32>(), 256);
+}
+
+#[test]
+fn st_code_autocorrelation() {
+    let chips = st_code();
+    for shift in 1..ST_CODE_LEN {
+        let correlation: i32 = (0..ST_CODE_LEN)
+            .map(|i| {
+                i32::from(chips[i])
+                    * i32::from(chips[(i + shift) % ST_CODE_LEN])
+            })
+            .sum();
+        assert_eq!(correlation, -1, "shift {shift}");
+    }
+}
+
+#[test]
+fn time_mark_bits() {
+    let bits: Vec<u8> = time_mark().collect();
+    assert_eq!(bits.len(), 30);
+    assert_eq!(
+        bits.iter()
+            .map(|bit| char::from(b'0' + bit))
+            .collect::<String>(),
+        TIME_MARK
+    );
+}
@@ -0,0 +1,212 @@
+use constants::{SECONDS_IN_DAY, SECONDS_IN_HOUR, SECONDS_IN_MINUTE};
+
+use crate::{
+    datetime::GpsTime,
+    glonass::{codes::time_mark, orbit::GlonassEphemeris},
+    signal::Bits,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Duration of a navigation message string in seconds.
+pub const STRING_SECONDS: f64 = 2.0;
+
+/// Number of strings of a 30-second frame.
+const FRAME_STRINGS: u64 = 15;
+
+/// Number of bits of a string, from the idle bit 85 to the check bit 1.
+const STRING_BITS: usize = 85;
+
+/// Number of check bits of the Hamming code at the end of a string.
+const CHECK_BITS: usize = 8;
+
+/// Days from the GPS time origin to January 1, 1996, the start of the first
+/// four-year interval of GLONASS time.
+const DAYS_TO_1996: i64 = 5839;
+
+/// Number of days of a four-year interval.
+const DAYS_IN_INTERVAL: i64 = 1461;
+
+/// Content of the navigation message of a GLONASS satellite.
+///
+/// Strings 1 to 4 carry the immediate data of the satellite and string 5 the
+/// time scale data. The almanac strings 6 to 15 carry no almanac: their
+/// satellite number is zero.
+pub struct GnavMessage<'a> {
+    /// Slot number of the satellite
+    pub slot: usize,
+    /// Broadcast ephemeris of the satellite
+    pub eph: &'a GlonassEphemeris,
+    /// Correction of GLONASS time to UTC(SU), τc (seconds)
+    pub tau_c: f64,
+}
+
+impl GnavMessage<'_> {
+    /// Generates the symbols of consecutive strings.
+    ///
+    /// Frames start every 30 seconds from the start of the GLONASS day. Each
+    /// string holds the 85 data bits in relative code, each bit sent as two
+    /// 10 ms symbols of the meander sequence, followed by the 0.3 s time
+    /// mark.
+    ///
+    /// # Arguments
+    /// * `start` - GLONASS time of the start of the first string (an even
+    ///   second)
+    /// * `strings` - Number of strings
+    ///
+    /// # Returns
+    /// 200 symbols per string, +1 for binary 0 and -1 for binary 1
+    pub fn symbols(&self, start: &GpsTime, strings: usize) -> Vec<i8> {
+        let mut symbols = Vec::new();
+        for index in 0..strings {
+            let time = start.add_secs(index as f64 * STRING_SECONDS);
+            let string = (time.sec % SECONDS_IN_DAY / STRING_SECONDS).round()
+                as u64
+                % FRAME_STRINGS
+                + 1;
+            let frame_start =
+                time.add_secs(-((string - 1) as f64) * STRING_SECONDS);
+            let bits = with_check_bits(self.string(string, &frame_start));
+            let mut previous = 0;
+            for bit in bits {
+                let relative = bit ^ previous;
+                previous = relative;
+                symbols.extend([relative, relative ^ 1]);
+            }
+            symbols.extend(time_mark());
+        }
+        symbols
+            .into_iter()
+            .map(|bit| if bit == 0 { 1 } else { -1 })
+            .collect()
+    }
+
+    /// Builds the bits 85 to 9 of a string.
+    ///
+    /// # Arguments
+    /// * `string` - String number (1 to 15)
+    /// * `frame_start` - GLONASS time of the start of the frame
+    fn string(&self, string: u64, frame_start: &GpsTime) -> Bits {
+        let eph = self.eph;
+        let km = |v: f64| v / 1000.0;
+        let (day, interval) = day_numbers(frame_start);
+        let mut bits = Bits::default();
+        // Idle bit
+        bits.zeros(1);
+        bits.push(string, 4);
+        match string {
+            1 => {
+                let seconds = frame_start.sec % SECONDS_IN_DAY;
+                bits.zeros(2);
+                // P1: 30 minutes between adjacent tb
+                bits.push(0b01, 2);
+                bits.push((seconds / SECONDS_IN_HOUR) as u64, 5);
+                bits.push(
+                    (seconds % SECONDS_IN_HOUR / SECONDS_IN_MINUTE) as u64,
+                    6,
+                );
+                bits.push((seconds % SECONDS_IN_MINUTE / 30.0) as u64, 1);
+                push_signed(&mut bits, km(eph.velocity[0]), 20, 24);
+                push_signed(&mut bits, km(eph.acceleration[0]), 30, 5);
+                push_signed(&mut bits, km(eph.position[0]), 11, 27);
+            }
+            2 => {
+                let tb = eph.tb_index();
+                bits.push(if eph.health == 0 { 0 } else { 0b100 }, 3);
+                bits.push(tb & 1, 1);
+                bits.push(tb, 7);
+                bits.zeros(5);
+                push_signed(&mut bits, km(eph.velocity[1]), 20, 24);
+                push_signed(&mut bits, km(eph.acceleration[1]), 30, 5);
+                push_signed(&mut bits, km(eph.position[1]), 11, 27);
+            }
+            3 => {
+                bits.push(1, 1);
+                push_signed(&mut bits, eph.frequency_bias, 40, 11);
+                bits.zeros(1);
+                // P: τc and τGPS uplinked from the control segment
+                bits.zeros(2);
+                bits.push(u64::from(eph.health != 0), 1);
+                push_signed(&mut bits, km(eph.velocity[2]), 20, 24);
+                push_signed(&mut bits, km(eph.acceleration[2]), 30, 5);
+                push_signed(&mut bits, km(eph.position[2]), 11, 27);
+            }
+            4 => {
+                push_signed(&mut bits, -eph.clock_bias, 30, 22);
+                // Δτn, unknown
+                bits.zeros(5);
+                bits.push(eph.age as u64, 5);
+                bits.zeros(14);
+                // P4, FT and spare bits
+                bits.zeros(8);
+                bits.push(day, 11);
+                bits.push(self.slot as u64, 5);
+                // M: GLONASS-M satellite
+                bits.push(0b01, 2);
+            }
+            5 => {
+                bits.push(day, 11);
+                push_signed(&mut bits, self.tau_c, 31, 32);
+                bits.zeros(1);
+                bits.push(interval, 5);
+                // τGPS: GLONASS time is aligned with GPS time
+                bits.zeros(22);
+                bits.push(u64::from(eph.health != 0), 1);
+            }
+            _ => {}
+        }
+        bits.pad(STRING_BITS - CHECK_BITS);
+        bits
+    }
+}
+
+/// Appends a value scaled by 2^scale, rounded, as sign and magnitude.
+fn push_signed(bits: &mut Bits, value: f64, scale: i32, len: u32) {
+    let magnitude = (value.abs() * 2f64.powi(scale)).round() as u64;
+    bits.push(u64::from(value < 0.0 && magnitude != 0), 1);
+    bits.push(magnitude, len - 1);
+}
+
+/// Appends the check bits of the Hamming code to the bits 85 to 9 of a
+/// string.
+///
+/// Check bits 1 to 7 are the parities of the data bits at the Hamming code
+/// positions with the corresponding bit set, the data bits 9 to 85 taking the
+/// positions from 3 on that are not powers of two. Check bit 8 is the parity
+/// of all other bits.
+fn with_check_bits(bits: Bits) -> Vec<u8> {
+    let mut bits = bits.0;
+    let mut checks = [0u8; CHECK_BITS];
+    let mut position = 2usize;
+    // Data bits from bit 9 up to bit 85
+    for &bit in bits.iter().rev() {
+        position += 1;
+        if position.is_power_of_two() {
+            position += 1;
+        }
+        for (k, check) in checks.iter_mut().enumerate().take(CHECK_BITS - 1) {
+            if position >> k & 1 == 1 {
+                *check ^= bit;
+            }
+        }
+        checks[CHECK_BITS - 1] ^= bit;
+    }
+    checks[CHECK_BITS - 1] ^= checks[..CHECK_BITS - 1]
+        .iter()
+        .fold(0, |sum, &check| sum ^ check);
+    // Bit 8 is sent first, bit 1 last
+    bits.extend(checks.iter().rev());
+    bits
+}
+
+/// Returns the day number within the four-year interval and the number of
+/// the four-year interval since 1996 of a GLONASS time.
+fn day_numbers(time: &GpsTime) -> (u64, u64) {
+    let days = i64::from(time.week) * 7 + (time.sec / SECONDS_IN_DAY) as i64
+        - DAYS_TO_1996;
+    (
+        (days % DAYS_IN_INTERVAL + 1) as u64,
+        (days / DAYS_IN_INTERVAL + 1) as u64,
+    )
+}
@@ -0,0 +1,158 @@
+use super::{
+    CHECK_BITS, FRAME_STRINGS, GnavMessage, STRING_BITS, with_check_bits,
+};
+use crate::{
+    datetime::GpsTime,
+    glonass::{codes::time_mark, orbit::GlonassEphemeris},
+    signal::Bits,
+};
+
+/// Data bits 9 to 85 covered by the check bit β1 (ICD C1).
+const C1_BITS: [usize; 41] = [
+    9, 10, 12, 13, 15, 17, 19, 20, 22, 24, 26, 28, 30, 32, 34, 35, 37, 39, 41,
+    43, 45, 47, 49, 51, 53, 55, 57, 59, 61, 63, 65, 66, 68, 70, 72, 74, 76, 78,
+    80, 82, 84,
+];
+
+/// Data bits 9 to 85 covered by the check bit β2 (ICD C2).
+const C2_BITS: [usize; 41] = [
+    9, 11, 12, 14, 15, 18, 19, 21, 22, 25, 26, 29, 30, 33, 34, 36, 37, 40, 41,
+    44, 45, 48, 49, 52, 53, 56, 57, 60, 61, 64, 65, 67, 68, 71, 72, 75, 76, 79,
+    80, 83, 84,
+];
+
+/// Returns pseudo-random data bits 85 to 9 of a string.
+fn data_bits(seed: u32) -> Vec<u8> {
+    let mut state = seed;
+    (0..STRING_BITS - CHECK_BITS)
+        .map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (state >> 16 & 1) as u8
+        })
+        .collect()
+}
+
+/// Returns bit `number` (85 to 1) of a string sent from bit 85 to bit 1.
+fn bit(string: &[u8], number: usize) -> u8 {
+    string[STRING_BITS - number]
+}
+
+/// Computes the check sums C1, C2 and CΣ of a received string.
+fn check_sums(string: &[u8]) -> [u8; 3] {
+    let parity = |bits: &[usize]| {
+        bits.iter()
+            .fold(0, |sum, &number| sum ^ bit(string, number))
+    };
+    [
+        bit(string, 1) ^ parity(&C1_BITS),
+        bit(string, 2) ^ parity(&C2_BITS),
+        string.iter().fold(0, |sum, &bit| sum ^ bit),
+    ]
+}
+
+#[test]
+fn hamming_check_bits() {
+    for seed in 0..32 {
+        let data = data_bits(seed);
+        let string = with_check_bits(Bits(data.clone()));
+        assert_eq!(string.len(), STRING_BITS);
+        assert_eq!(string[..STRING_BITS - CHECK_BITS], data);
+        assert_eq!(check_sums(&string), [0; 3], "seed {seed}");
+    }
+}
+
+#[test]
+fn hamming_detects_single_errors() {
+    let data = data_bits(7);
+    let reference = with_check_bits(Bits(data.clone()));
+    for number in 9..=STRING_BITS {
+        let mut corrupted = data.clone();
+        corrupted[STRING_BITS - number] ^= 1;
+        let string = with_check_bits(Bits(corrupted));
+        // The check bits of the original string no longer match
+        let mut received = string.clone();
+        received[STRING_BITS - CHECK_BITS..]
+            .copy_from_slice(&reference[STRING_BITS - CHECK_BITS..]);
+        assert_ne!(check_sums(&received)[2], 0, "bit {number}");
+        // Each data bit has its own set of check bits
+        let flipped: Vec<usize> = (1..=CHECK_BITS)
+            .filter(|&check| bit(&string, check) != bit(&reference, check))
+            .collect();
+        assert!(flipped.len() >= 3, "bit {number}: {flipped:?}");
+        assert_eq!(
+            flipped.contains(&1),
+            C1_BITS.contains(&number),
+            "bit {number}"
+        );
+        assert_eq!(
+            flipped.contains(&2),
+            C2_BITS.contains(&number),
+            "bit {number}"
+        );
+    }
+}
+
+#[test]
+fn string_symbols() {
+    let eph = GlonassEphemeris {
+        tb: GpsTime {
+            week: 2190,
+            sec: 173_700.0,
+        },
+        position: [7_003_008.789, -12_206_626.953, 21_280_765.625],
+        velocity: [783.5417, 2804.2530, 1352.5150],
+        acceleration: [0.0, 1.7e-6, -5.41e-6],
+        clock_bias: -2.5e-5,
+        frequency_bias: 1.8e-12,
+        channel: -2,
+        health: 0,
+        age: 0,
+    };
+    let message = GnavMessage {
+        slot: 5,
+        eph: &eph,
+        tau_c: 1.2e-8,
+    };
+    // Start of a day, the first string of a frame
+    let start = GpsTime {
+        week: 2190,
+        sec: 172_800.0,
+    };
+    let strings = FRAME_STRINGS as usize + 2;
+    let symbols = message.symbols(&start, strings);
+    assert_eq!(symbols.len(), 200 * strings);
+    let mark: Vec<u8> = time_mark().collect();
+    for (index, string) in symbols.chunks(200).enumerate() {
+        let binary: Vec<u8> =
+            string.iter().map(|&symbol| u8::from(symbol < 0)).collect();
+        let (data, time) = binary.split_at(2 * STRING_BITS);
+        assert_eq!(time, mark);
+        // Meander: each bit is sent as the bit and its complement
+        let relative: Vec<u8> = data
+            .chunks(2)
+            .map(|pair| {
+                assert_eq!(pair[0] ^ pair[1], 1);
+                pair[0]
+            })
+            .collect();
+        // Relative code: each bit is the sum of two relative bits
+        let mut previous = 0;
+        let bits: Vec<u8> = relative
+            .iter()
+            .map(|&bit| {
+                let absolute = bit ^ previous;
+                previous = bit;
+                absolute
+            })
+            .collect();
+        assert_eq!(check_sums(&bits), [0; 3], "string {index}");
+        let number = index as u64 % FRAME_STRINGS + 1;
+        let frame_start =
+            start.add_secs((index as u64 - (number - 1)) as f64 * 2.0);
+        assert_eq!(bits, with_check_bits(message.string(number, &frame_start)));
+        // Idle bit and string number m
+        assert_eq!(bits[0], 0);
+        let m = bits[1..5].iter().fold(0, |m, &bit| m << 1 | u64::from(bit));
+        assert_eq!(m, number);
+    }
+}
@@ -0,0 +1,169 @@
+use constants::{
+    GLONASS_UTC_OFFSET, GM_PZ90, OMEGA_EARTH_PZ90, PZ90_J2, PZ90_RADIUS,
+    SECONDS_IN_DAY,
+};
+use rinex::navigation::NavRecord;
+
+use crate::{
+    Error,
+    datetime::{DateTime, GpsTime},
+    propagation::SatelliteState,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Longest integration step of the equations of motion in seconds.
+const INTEGRATION_STEP: f64 = 30.0;
+
+/// Broadcast ephemeris of a GLONASS satellite.
+///
+/// The satellite state is given as position, velocity and lunisolar
+/// acceleration in the PZ-90 frame at the reference time `tb`, together with
+/// the clock offset and relative frequency offset of the satellite.
+#[derive(Clone)]
+pub struct GlonassEphemeris {
+    /// Reference time of the ephemeris in UTC(SU), as week and seconds
+    pub tb: GpsTime,
+    /// Position at the reference time (meters)
+    pub position: [f64; 3],
+    /// Velocity at the reference time (meters/second)
+    pub velocity: [f64; 3],
+    /// Lunisolar acceleration (meters/second²)
+    pub acceleration: [f64; 3],
+    /// Satellite clock offset from GLONASS time, -τn (seconds)
+    pub clock_bias: f64,
+    /// Relative frequency offset of the satellite clock, +γn
+    pub frequency_bias: f64,
+    /// Frequency channel number (-7 to +6)
+    pub channel: i32,
+    /// Health flag (0 for healthy)
+    pub health: i32,
+    /// Age of the ephemeris data in days
+    pub age: i32,
+}
+
+impl GlonassEphemeris {
+    /// Converts a GLONASS RINEX ephemeris record.
+    ///
+    /// # Arguments
+    /// * `record` - GLONASS ephemeris record
+    ///
+    /// # Returns
+    /// * `Ok(GlonassEphemeris)` - The ephemeris in meters and seconds
+    /// * `Err(Error)` - If the epoch of the record cannot be converted
+    ///
+    /// # Errors
+    /// * Returns an error if the epoch is out of range
+    pub fn from_record(record: &NavRecord) -> Result<Self, Error> {
+        let datetime = DateTime::from(record.epoch.in_tz("UTC")?);
+        let [x, vx, ax, health] = record.orbit(1);
+        let [y, vy, ay, channel] = record.orbit(2);
+        let [z, vz, az, age] = record.orbit(3);
+        let km = |v: [f64; 3]| v.map(|value| value * 1000.0);
+        Ok(Self {
+            tb: GpsTime::from(&datetime),
+            position: km([x, y, z]),
+            velocity: km([vx, vy, vz]),
+            acceleration: km([ax, ay, az]),
+            clock_bias: record.values[0],
+            frequency_bias: record.values[1],
+            channel: channel as i32,
+            health: health as i32,
+            age: age as i32,
+        })
+    }
+
+    /// Returns the index of the reference time within the GLONASS day, in
+    /// 15-minute intervals.
+    pub fn tb_index(&self) -> u64 {
+        let seconds = (self.tb.sec + GLONASS_UTC_OFFSET) % SECONDS_IN_DAY;
+        (seconds / 900.0).round() as u64
+    }
+
+    /// Integrates the equations of motion from the reference time.
+    ///
+    /// The fourth-order Runge-Kutta method integrates the central gravity
+    /// field with the J2 term, the centrifugal and Coriolis accelerations of
+    /// the rotating PZ-90 frame and the constant lunisolar acceleration.
+    ///
+    /// # Arguments
+    /// * `dt` - Time from the reference time in seconds
+    ///
+    /// # Returns
+    /// Position and velocity in the PZ-90 frame
+    pub fn propagate(&self, dt: f64) -> ([f64; 3], [f64; 3]) {
+        let steps = (dt.abs() / INTEGRATION_STEP).ceil().max(1.0);
+        let h = dt / steps;
+        let mut state = [
+            self.position[0],
+            self.position[1],
+            self.position[2],
+            self.velocity[0],
+            self.velocity[1],
+            self.velocity[2],
+        ];
+        let add = |a: &[f64; 6], b: &[f64; 6], scale: f64| {
+            std::array::from_fn(|i| a[i] + b[i] * scale)
+        };
+        for _ in 0..steps as usize {
+            let k1 = self.derivative(&state);
+            let k2 = self.derivative(&add(&state, &k1, h / 2.0));
+            let k3 = self.derivative(&add(&state, &k2, h / 2.0));
+            let k4 = self.derivative(&add(&state, &k3, h));
+            state = std::array::from_fn(|i| {
+                state[i] + h / 6.0 * (k1[i] + 2.0 * k2[i] + 2.0 * k3[i] + k4[i])
+            });
+        }
+        ([state[0], state[1], state[2]], [
+            state[3], state[4], state[5],
+        ])
+    }
+
+    /// Returns the time derivative of a position and velocity state.
+    fn derivative(&self, state: &[f64; 6]) -> [f64; 6] {
+        let [x, y, z, vx, vy, vz] = *state;
+        let r2 = x * x + y * y + z * z;
+        let r = r2.sqrt();
+        let mu = GM_PZ90 / (r2 * r);
+        let j2 =
+            1.5 * PZ90_J2 * GM_PZ90 * PZ90_RADIUS * PZ90_RADIUS / (r2 * r2 * r);
+        let z2 = 5.0 * z * z / r2;
+        let w2 = OMEGA_EARTH_PZ90 * OMEGA_EARTH_PZ90;
+        [
+            vx,
+            vy,
+            vz,
+            -mu * x - j2 * x * (1.0 - z2)
+                + w2 * x
+                + 2.0 * OMEGA_EARTH_PZ90 * vy
+                + self.acceleration[0],
+            -mu * y - j2 * y * (1.0 - z2) + w2 * y
+                - 2.0 * OMEGA_EARTH_PZ90 * vx
+                + self.acceleration[1],
+            -mu * z - j2 * z * (3.0 - z2) + self.acceleration[2],
+        ]
+    }
+}
+
+/// Orbit of a GLONASS satellite computed from its broadcast ephemeris.
+pub struct GlonassOrbit<'a> {
+    /// Broadcast ephemeris with the reference time in UTC(SU)
+    pub eph: &'a GlonassEphemeris,
+    /// Leap seconds between GPS time and UTC
+    pub leap_seconds: i32,
+}
+
+impl SatelliteState for GlonassOrbit<'_> {
+    fn satellite_state(
+        &self, time: &GpsTime,
+    ) -> ([f64; 3], [f64; 3], [f64; 2]) {
+        let dt = time.diff_secs(&self.eph.tb) - f64::from(self.leap_seconds);
+        let (pos, vel) = self.eph.propagate(dt);
+        let clk = [
+            self.eph.clock_bias + self.eph.frequency_bias * dt,
+            self.eph.frequency_bias,
+        ];
+        (pos, vel, clk)
+    }
+}
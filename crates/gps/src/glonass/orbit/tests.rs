@@ -0,0 +1,59 @@
+use super::GlonassEphemeris;
+use crate::datetime::GpsTime;
+
+/// Creates the ephemeris of the orbit example of the GLONASS ICD, with
+/// reference time 11700 s and the given lunisolar acceleration.
+fn icd_ephemeris(acceleration: [f64; 3]) -> GlonassEphemeris {
+    GlonassEphemeris {
+        tb: GpsTime {
+            week: 0,
+            sec: 11700.0,
+        },
+        position: [7_003_008.789, -12_206_626.953, 21_280_765.625],
+        velocity: [783.5417, 2804.2530, 1352.5150],
+        acceleration,
+        clock_bias: 0.0,
+        frequency_bias: 0.0,
+        channel: 0,
+        health: 0,
+        age: 0,
+    }
+}
+
+#[test]
+fn propagate_reference_state() {
+    // PZ-90 state at 12300 s without the lunisolar acceleration
+    let position = [7_523_174.853, -10_506_962.176, 21_999_239.866];
+    let velocity = [950.126, 2855.687, 1040.679];
+    let (pos, vel) = icd_ephemeris([0.0; 3]).propagate(600.0);
+    for i in 0..3 {
+        assert!((pos[i] - position[i]).abs() < 0.02, "{pos:?}");
+        assert!((vel[i] - velocity[i]).abs() < 5e-3, "{vel:?}");
+    }
+    // Propagating back returns to the reference state
+    let eph = icd_ephemeris([0.0; 3]);
+    let back = GlonassEphemeris {
+        position: pos,
+        velocity: vel,
+        ..icd_ephemeris([0.0; 3])
+    };
+    let (pos, vel) = back.propagate(-600.0);
+    for i in 0..3 {
+        assert!((pos[i] - eph.position[i]).abs() < 1e-3, "{pos:?}");
+        assert!((vel[i] - eph.velocity[i]).abs() < 1e-6, "{vel:?}");
+    }
+}
+
+#[test]
+fn propagate_lunisolar_acceleration() {
+    let acceleration = [0.0, 1.7e-6, -5.41e-6];
+    let dt = 600.0;
+    let (pos0, vel0) = icd_ephemeris([0.0; 3]).propagate(dt);
+    let (pos, vel) = icd_ephemeris(acceleration).propagate(dt);
+    for i in 0..3 {
+        let offset = 0.5 * acceleration[i] * dt * dt;
+        assert!((pos[i] - pos0[i] - offset).abs() < 0.02, "{pos:?}");
+        let offset = acceleration[i] * dt;
+        assert!((vel[i] - vel0[i] - offset).abs() < 1e-4, "{vel:?}");
+    }
+}
@@ -0,0 +1,314 @@
+use constants::{
+    CARR_FREQ, G1_CHANNEL_SPACING, G1_FREQ, GLONASS_UTC_OFFSET, R2D,
+    SECONDS_IN_HOUR, ST_CODE_FREQ, ST_CODE_LEN,
+};
+use rinex::navigation::SatelliteSystem;
+
+use crate::{
+    Error,
+    datetime::{GpsTime, TimeRange},
+    generator::SatelliteSelection,
+    glonass::{
+        codes::st_code,
+        gnav::{GnavMessage, STRING_SECONDS},
+        orbit::{GlonassEphemeris, GlonassOrbit},
+    },
+    propagation::compute_range,
+    signal::{
+        SignalChannel, SignalComponent, SignalSystem, Subcarrier,
+        SystemContext, SystemEphemeris, SystemNavigation, allocate_channels,
+        band_offset, print_channel_status, signal_gain,
+    },
+};
+
+/// Number of GLONASS orbital slots.
+const SLOTS: usize = 24;
+
+/// Number of strings of the symbol buffer of a channel.
+///
+/// The buffer starts with the string being transmitted at a frame boundary
+/// and reaches past the next boundary, where it is regenerated.
+const WINDOW_STRINGS: usize = 18;
+
+/// Number of ST code chips per navigation symbol (10 ms).
+const CHIPS_PER_SYMBOL: u64 = 10 * ST_CODE_LEN as u64;
+
+/// Half the bandwidth of the L1OF main lobe around the carrier (Hz).
+const HALF_BANDWIDTH: f64 = ST_CODE_FREQ;
+
+/// Longest time from the reference time for which an ephemeris is used, in
+/// seconds (half an hour).
+const MAX_EPHEMERIS_AGE: f64 = SECONDS_IN_HOUR / 2.0;
+
+/// GLONASS L1OF signals.
+///
+/// Each visible GLONASS satellite transmits the ST code on the carrier of
+/// its frequency channel k, 1602 MHz + k · 562.5 kHz, with the navigation
+/// message strings at 50 bps. GLONASS time is simulated as UTC(SU) plus
+/// three hours, with UTC following GPS time by the leap seconds of the GPS
+/// message.
+///
+/// The satellite orbits are integrated from the broadcast PZ-90 state
+/// vectors of the GLONASS ephemerides, the ephemeris closest in time being
+/// used for each satellite. The ranges are delayed by the Klobuchar model of
+/// the GPS message, scaled to the carrier frequency of each satellite.
+/// Satellites whose frequency channel lies outside the output band are not
+/// simulated.
+pub struct GlonassSystem {
+    /// Broadcast ephemerides of each slot (index slot - 1), sorted by
+    /// reference time
+    ephemerides: Vec<Vec<GlonassEphemeris>>,
+    /// Ephemeris in use of each slot
+    current: Vec<Option<GlonassEphemeris>>,
+    /// ST code of all satellites
+    code: Vec<i8>,
+    /// Correction of GLONASS time to UTC(SU) of the navigation files, τc
+    tau_c: f64,
+    /// Leap seconds between GPS time and UTC
+    leap_seconds: i32,
+    /// Satellites allowed to occupy a channel
+    selection: SatelliteSelection,
+    /// Channels of the visible satellites
+    channels: Vec<SignalChannel>,
+}
+
+impl GlonassSystem {
+    /// Creates the GLONASS system from the navigation data.
+    ///
+    /// # Arguments
+    /// * `navigation` - Navigation data of the systems other than GPS
+    /// * `selection` - Satellites allowed to occupy a channel, without PRN
+    ///   lists
+    ///
+    /// # Returns
+    /// * `Ok(GlonassSystem)` - The system ready to be initialized
+    /// * `Err(Error)` - If there is no usable GLONASS ephemeris
+    ///
+    /// # Errors
+    /// * `Error::NoSystemEphemeris` - If the navigation data holds no ephemeris
+    ///   of a satellite in slot 1 to 24
+    /// * Returns an error if the epoch of a record is out of range
+    pub fn new(
+        navigation: &SystemNavigation, selection: SatelliteSelection,
+    ) -> Result<Self, Error> {
+        let mut ephemerides = vec![Vec::new(); SLOTS];
+        for record in navigation.ephemerides(SatelliteSystem::Glonass) {
+            let Some(slot) = record
+                .satellite
+                .prn
+                .checked_sub(1)
+                .and_then(|sv| ephemerides.get_mut(sv))
+            else {
+                continue;
+            };
+            let mut eph = GlonassEphemeris::from_record(record)?;
+            eph.health = selection.health_mode.broadcast_health(
+                record.satellite.prn,
+                eph.health,
+                &[],
+            );
+            if slot.iter().all(|other: &GlonassEphemeris| {
+                other.tb.diff_secs(&eph.tb) != 0.0
+            }) {
+                slot.push(eph);
+            }
+        }
+        if ephemerides.iter().all(Vec::is_empty) {
+            return Err(Error::no_system_ephemeris("GLONASS"));
+        }
+        for slot in &mut ephemerides {
+            slot.sort_by(|a, b| a.tb.diff_secs(&b.tb).total_cmp(&0.0));
+        }
+        // GLUT holds -τc
+        let tau_c = navigation
+            .time_system_correction("GLUT")
+            .map_or(0.0, |correction| -correction.a0);
+        Ok(Self {
+            ephemerides,
+            current: vec![None; SLOTS],
+            code: st_code(),
+            tau_c,
+            leap_seconds: 0,
+            selection,
+            channels: Vec::new(),
+        })
+    }
+
+    /// Takes the ephemeris closest to a time into use for every satellite.
+    fn select_ephemerides(&mut self, time: &GpsTime) {
+        let utc = time.add_secs(-f64::from(self.leap_seconds));
+        for (slot, current) in self.ephemerides.iter().zip(&mut self.current) {
+            *current = slot
+                .iter()
+                .map(|eph| (utc.diff_secs(&eph.tb).abs(), eph))
+                .filter(|(age, _)| *age <= MAX_EPHEMERIS_AGE)
+                .min_by(|a, b| a.0.total_cmp(&b.0))
+                .map(|(_, eph)| eph.clone());
+        }
+    }
+
+    /// Releases the channels of satellites below the elevation mask and
+    /// allocates channels to the newly visible satellites.
+    fn allocate_channels(&mut self, context: &SystemContext) {
+        let (leap_seconds, code) = (self.leap_seconds, &self.code);
+        allocate_channels(
+            &mut self.channels,
+            &self.current,
+            1,
+            &self.selection,
+            |_, eph| {
+                l1of_range(eph, leap_seconds, context).azel.el * R2D
+                    > context.elevation_mask
+            },
+            |slot, eph| {
+                let carrier = carrier_frequency(eph);
+                let carrier_offset =
+                    band_offset(context, carrier, HALF_BANDWIDTH)?;
+                let component = SignalComponent {
+                    code: code.clone(),
+                    secondary: Vec::new(),
+                    data: true,
+                    amplitude: 1.0,
+                    subcarrier: Subcarrier::Bpsk,
+                    quadrature: false,
+                };
+                let mut channel = SignalChannel::new(
+                    slot,
+                    vec![component],
+                    ST_CODE_FREQ,
+                    carrier,
+                    carrier_offset,
+                    CHIPS_PER_SYMBOL,
+                );
+                channel.start(l1of_range(eph, leap_seconds, context));
+                Some(channel)
+            },
+        );
+    }
+
+    /// Regenerates the navigation message symbols of all channels for the
+    /// frame starting at a time.
+    fn generate_messages(&mut self, context: &SystemContext) {
+        // The buffers start with the string transmitted a second before the
+        // current reception time
+        let time = glonass_time(context.time, self.leap_seconds).add_secs(-1.0);
+        let start = GpsTime {
+            week: time.week,
+            sec: (time.sec / STRING_SECONDS).floor() * STRING_SECONDS,
+        };
+        for channel in &mut self.channels {
+            let Some(eph) = &self.current[channel.prn - 1] else {
+                continue;
+            };
+            let message = GnavMessage {
+                slot: channel.prn,
+                eph,
+                tau_c: self.tau_c,
+            };
+            channel.set_symbols(
+                &gps_time(&start, self.leap_seconds),
+                message.symbols(&start, WINDOW_STRINGS),
+            );
+        }
+    }
+}
+
+impl SystemEphemeris for GlonassEphemeris {
+    fn health(&self) -> i32 {
+        self.health
+    }
+}
+
+impl SignalSystem for GlonassSystem {
+    fn name(&self) -> &'static str {
+        "GLONASS"
+    }
+
+    fn carrier_frequency(&self) -> f64 {
+        G1_FREQ
+    }
+
+    fn initialize(&mut self, context: &SystemContext) {
+        self.leap_seconds = context.ionoutc.dtls;
+        if band_offset(context, G1_FREQ, HALF_BANDWIDTH).is_none() {
+            eprintln!(
+                "Warning: GLONASS L1OF lies outside the output band; only \
+                 satellites on frequency channels within the band are \
+                 simulated."
+            );
+        }
+        self.select_ephemerides(context.time);
+        self.allocate_channels(context);
+        self.generate_messages(context);
+    }
+
+    fn update_channels(&mut self, context: &SystemContext) {
+        for channel in &mut self.channels {
+            let Some(eph) = &self.current[channel.prn - 1] else {
+                continue;
+            };
+            let rho = l1of_range(eph, self.leap_seconds, context);
+            channel.update(&rho, context.step, context.sampling_period);
+            channel.gain =
+                signal_gain(&rho, context.fixed_gain, context.antenna_pattern);
+        }
+    }
+
+    fn update_navigation(&mut self, context: &SystemContext) {
+        self.select_ephemerides(context.time);
+        self.allocate_channels(context);
+        self.generate_messages(context);
+    }
+
+    fn next_sample(&mut self, sampling_period: f64) -> (i32, i32) {
+        self.channels
+            .iter_mut()
+            .fold((0, 0), |(i_acc, q_acc), channel| {
+                let (ip, qp) = channel.next_sample(sampling_period);
+                (i_acc + ip, q_acc + qp)
+            })
+    }
+
+    fn print_status(&self) {
+        for channel in &self.channels {
+            print_channel_status(
+                &format!("R{:02}", channel.prn),
+                channel.azel(),
+                channel.rho0(),
+                None,
+            );
+        }
+    }
+}
+
+/// Returns the carrier frequency of the frequency channel of a satellite.
+fn carrier_frequency(eph: &GlonassEphemeris) -> f64 {
+    G1_FREQ + f64::from(eph.channel) * G1_CHANNEL_SPACING
+}
+
+/// Computes the L1OF range of a satellite at the current receiver time.
+///
+/// The ionospheric delay of the GPS Klobuchar model is scaled from L1 to the
+/// carrier frequency of the satellite.
+fn l1of_range(
+    eph: &GlonassEphemeris, leap_seconds: i32, context: &SystemContext,
+) -> TimeRange {
+    let orbit = GlonassOrbit { eph, leap_seconds };
+    let mut rho =
+        compute_range(&orbit, context.ionoutc, context.time, context.location);
+    let ratio = CARR_FREQ / carrier_frequency(eph);
+    let iono_delay = rho.iono_delay * ratio * ratio;
+    rho.range += iono_delay - rho.iono_delay;
+    rho.iono_delay = iono_delay;
+    rho
+}
+
+/// Converts a GPS time into GLONASS time.
+fn glonass_time(time: &GpsTime, leap_seconds: i32) -> GpsTime {
+    time.add_secs(GLONASS_UTC_OFFSET - f64::from(leap_seconds))
+}
+
+/// Converts a GLONASS time into GPS time.
+fn gps_time(time: &GpsTime, leap_seconds: i32) -> GpsTime {
+    time.add_secs(f64::from(leap_seconds) - GLONASS_UTC_OFFSET)
+}
@@ -10,8 +10,18 @@
 //! configuring all aspects of the simulation before generating the signal with
 //! `SignalGenerator`.
 
+/// BeiDou B1I signal generation
+mod beidou;
 /// GPS channel simulation and signal generation
 mod channel;
+/// GPS L2C and L5 signal generation with CNAV messages
+mod cnav;
+/// Ranging codes of the simulated signals by system and PRN
+pub mod codes;
+/// Synthetic nominal GPS constellation
+mod constellation;
+/// RTCM 3 correction stream of a virtual reference station
+mod corrections;
 /// GPS time system representation and utilities
 mod datetime;
 /// Signal propagation delay calculations
@@ -20,17 +30,63 @@ mod delay;
 mod ephemeris;
 /// Error types for GPS signal generation
 mod error;
+/// Satellite transmit and front-end filtering of the oversampled signals
+mod filter;
+/// Galileo E1 Open Service signal generation
+mod galileo;
 /// Main signal generator implementation
 mod generator;
+/// GLONASS L1OF signal generation
+mod glonass;
+/// Transmitter impairments applied to the composite samples
+mod impairment;
 /// I/Q data format handling and file I/O
 mod io;
 /// Ionospheric and UTC parameter handling
 mod ionoutc;
+/// GPS L1C signal generation with CNAV-2 messages
+mod l1c;
+/// RINEX observation export of simulated measurements
+mod observation;
+/// Precise orbit and clock interpolation from SP3 and RINEX clock files
+mod precise;
 /// Satellite position and velocity propagation
 mod propagation;
+/// QZSS L1 C/A signal generation
+mod qzss;
+/// SBAS L1 signal generation
+mod sbas;
+/// Signal channels and navigation data of satellite systems other than GPS
+mod signal;
+/// Subframe 4 and 5 page schedule of the navigation message
+mod superframe;
 /// Lookup tables for signal generation
 mod table;
+/// Per-satellite timeline of broadcast ephemerides
+mod timeline;
+/// Truth log of simulated observables
+mod truth;
 
+pub use beidou::BeidouSystem;
+pub use cnav::{CnavSignal, CnavSystem};
+pub use constellation::NominalConstellation;
+pub use corrections::{BaseStation, RtcmOutput, RtcmServer};
 pub use error::Error;
-pub use generator::{MotionMode, SignalGenerator, SignalGeneratorBuilder};
+pub use galileo::{GalileoCodes, GalileoSystem};
+pub use generator::{
+    HealthMode, MotionMode, PlanEpoch, PlanFormat, PlannedSatellite,
+    SatelliteSelection, SignalGenerator, SignalGeneratorBuilder,
+    VisibilityPlan,
+};
+pub use glonass::GlonassSystem;
+pub use impairment::Impairments;
 pub use io::DataFormat;
+pub use l1c::L1cSystem;
+pub use observation::ObservationLogger;
+pub use precise::PreciseOrbits;
+pub use qzss::QzssSystem;
+pub use rtcm::MsmType;
+pub use sbas::{SbasMessages, SbasSystem};
+pub use signal::{SignalSystem, SystemContext, SystemNavigation};
+pub use superframe::SuperframePages;
+pub use truth::{TruthFormat, TruthRecord, TruthWriter};
@@ -0,0 +1,266 @@
+use std::f64::consts::PI;
+
+#[cfg(test)]
+mod tests;
+
+/// Number of tones synthesizing the phase noise, a multiple of 4
+const PHASE_NOISE_TONES: usize = 64;
+
+/// Seed of the random tone phases, fixed for reproducible output
+const PHASE_NOISE_SEED: u64 = 0x5DEE_CE66_D1CE_4E5B;
+
+/// Imperfections of an SDR transmitter chain.
+///
+/// The impairments follow the path of the samples through a quadrature
+/// modulator: the I/Q gain and phase imbalance of the two branches, the DC
+/// offset of the DACs (LO leakage), and the frequency offset and phase
+/// noise of the LO. The default has no impairment.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Impairments {
+    /// Gain of the Q branch relative to the I branch in dB
+    pub gain_imbalance: f64,
+    /// Phase error of the Q branch LO in degrees
+    pub phase_imbalance: f64,
+    /// DC offset of the I and Q samples, in units of the 16-bit output
+    pub dc_offset: (f64, f64),
+    /// LO frequency offset in ppm of the RF centre frequency
+    pub frequency_offset: f64,
+    /// Single-sideband LO phase noise mask as (offset in Hz, level in
+    /// dBc/Hz) points in increasing offset, interpolated in log frequency
+    pub phase_noise: Vec<(f64, f64)>,
+    /// Whether to apply the inverse of the imbalance, DC offset and
+    /// frequency offset, compensating a transmitter with these impairments
+    pub predistortion: bool,
+}
+
+impl Impairments {
+    /// Checks whether the impairments leave the samples unchanged.
+    pub fn is_none(&self) -> bool {
+        self.gain_imbalance == 0.0
+            && self.phase_imbalance == 0.0
+            && self.dc_offset == (0.0, 0.0)
+            && self.frequency_offset == 0.0
+            && self.phase_noise.is_empty()
+    }
+}
+
+/// Tones of the synthesized phase noise.
+///
+/// Each tone is a rotating unit phasor; the tones are kept in separate
+/// arrays so that their update vectorizes.
+#[derive(Debug, Clone, Default)]
+struct PhaseNoise {
+    /// Peak phase deviations in radians
+    amplitudes: Vec<f64>,
+    /// Cosines of the current phasors
+    cos: Vec<f64>,
+    /// Sines of the current phasors
+    sin: Vec<f64>,
+    /// Cosines of the phasor rotations per sample
+    step_cos: Vec<f64>,
+    /// Sines of the phasor rotations per sample
+    step_sin: Vec<f64>,
+}
+
+impl PhaseNoise {
+    /// Returns the current phase noise and advances the tones by one
+    /// sample.
+    ///
+    /// # Returns
+    /// The phase noise in radians
+    fn next(&mut self) -> f64 {
+        // Four partial sums break the dependency chain of the additions
+        let mut sums = [0.0; 4];
+        for (amplitudes, cos) in self
+            .amplitudes
+            .chunks_exact(4)
+            .zip(self.cos.chunks_exact(4))
+        {
+            for k in 0..4 {
+                sums[k] += amplitudes[k] * cos[k];
+            }
+        }
+        for (((cos, sin), step_cos), step_sin) in self
+            .cos
+            .iter_mut()
+            .zip(self.sin.iter_mut())
+            .zip(&self.step_cos)
+            .zip(&self.step_sin)
+        {
+            let c = *cos * step_cos - *sin * step_sin;
+            let s = *cos * step_sin + *sin * step_cos;
+            // Keep the phasor on the unit circle against rounding
+            let norm = 1.5 - 0.5 * (c * c + s * s);
+            *cos = c * norm;
+            *sin = s * norm;
+        }
+        sums.iter().sum()
+    }
+}
+
+/// Applies the transmitter impairments to the composite samples.
+///
+/// The phase noise is a sum of tones at logarithmically spaced offsets with
+/// random phases, each carrying the power of the mask over its share of
+/// the spectrum. The tone phases come from a fixed seed, so the output is
+/// reproducible.
+#[derive(Debug, Clone)]
+pub struct ImpairmentStage {
+    /// Gain of the Q branch relative to the I branch
+    gain: f64,
+    /// Sine and cosine of the Q branch phase error
+    phase: (f64, f64),
+    /// DC offset of the I and Q accumulators
+    dc_offset: (f64, f64),
+    /// LO frequency offset in cycles per sample
+    frequency_step: f64,
+    /// LO phase from the frequency offset in cycles
+    frequency_phase: f64,
+    /// Tones of the LO phase noise
+    phase_noise: PhaseNoise,
+    /// Whether to apply the inverse impairments
+    predistortion: bool,
+}
+
+impl ImpairmentStage {
+    /// Creates the impairment stage of a generator.
+    ///
+    /// # Arguments
+    /// * `impairments` - Transmitter impairments
+    /// * `sample_frequency` - Sampling frequency in Hz
+    /// * `center_frequency` - RF centre frequency of the output in Hz
+    pub fn new(
+        impairments: &Impairments, sample_frequency: f64, center_frequency: f64,
+    ) -> Self {
+        let phase = impairments.phase_imbalance.to_radians();
+        Self {
+            gain: 10.0f64.powf(impairments.gain_imbalance / 20.0),
+            phase: phase.sin_cos(),
+            // The accumulators are scaled by 2^7 against the output
+            dc_offset: (
+                impairments.dc_offset.0 * 128.0,
+                impairments.dc_offset.1 * 128.0,
+            ),
+            frequency_step: impairments.frequency_offset
+                * 1e-6
+                * center_frequency
+                / sample_frequency,
+            frequency_phase: 0.0,
+            phase_noise: phase_noise_tones(
+                &impairments.phase_noise,
+                sample_frequency,
+            ),
+            predistortion: impairments.predistortion,
+        }
+    }
+
+    /// Applies the impairments to one sample and advances the LO.
+    ///
+    /// # Arguments
+    /// * `i_acc` - Accumulated I sample
+    /// * `q_acc` - Accumulated Q sample
+    ///
+    /// # Returns
+    /// The impaired I and Q accumulators
+    pub fn apply(&mut self, i_acc: i32, q_acc: i32) -> (i32, i32) {
+        let (sin_imb, cos_imb) = self.phase;
+        let lo_phase =
+            2.0 * PI * self.frequency_phase + self.phase_noise.next();
+        self.frequency_phase =
+            (self.frequency_phase + self.frequency_step).fract();
+        let (i, q) = (f64::from(i_acc), f64::from(q_acc));
+        let (i, q) = if self.predistortion {
+            // Undo the LO rotation, DC offset and imbalance of the
+            // transmitter in reverse order
+            let (sin_lo, cos_lo) = (-lo_phase).sin_cos();
+            let i_rot = i * cos_lo - q * sin_lo - self.dc_offset.0;
+            let q_rot = i * sin_lo + q * cos_lo - self.dc_offset.1;
+            (
+                i_rot + q_rot * sin_imb / cos_imb,
+                q_rot / (self.gain * cos_imb),
+            )
+        } else {
+            // Imbalanced quadrature branches, LO leakage, then the LO
+            let i_imb = i - self.gain * q * sin_imb + self.dc_offset.0;
+            let q_imb = self.gain * q * cos_imb + self.dc_offset.1;
+            let (sin_lo, cos_lo) = lo_phase.sin_cos();
+            (
+                i_imb * cos_lo - q_imb * sin_lo,
+                i_imb * sin_lo + q_imb * cos_lo,
+            )
+        };
+        (i.round() as i32, q.round() as i32)
+    }
+}
+
+/// Synthesizes the tones of a phase noise mask.
+///
+/// # Arguments
+/// * `mask` - Single-sideband phase noise as (offset in Hz, dBc/Hz) points
+/// * `sample_frequency` - Sampling frequency in Hz
+///
+/// # Returns
+/// Tones between the first and last offset of the mask, up to half the
+/// sampling frequency, or none for a mask of less than two points
+fn phase_noise_tones(mask: &[(f64, f64)], sample_frequency: f64) -> PhaseNoise {
+    let mut tones = PhaseNoise::default();
+    let (Some(&(first, _)), Some(&(last, _))) = (mask.first(), mask.last())
+    else {
+        return tones;
+    };
+    let last = last.min(sample_frequency / 2.0);
+    if mask.len() < 2 || last <= first {
+        return tones;
+    }
+    let ratio = (last / first).powf(1.0 / PHASE_NOISE_TONES as f64);
+    let mut state = PHASE_NOISE_SEED;
+    for k in 0..PHASE_NOISE_TONES {
+        // Each tone stands for the band of one ratio around it
+        let frequency = first * ratio.powf(k as f64 + 0.5);
+        let bandwidth = frequency * (ratio.sqrt() - ratio.sqrt().recip());
+        let level = 10.0f64.powf(mask_level(mask, frequency) / 10.0);
+        // L(f) is half the phase spectral density, and a tone of
+        // amplitude A carries a variance of A^2 / 2
+        let amplitude = 2.0 * (level * bandwidth).sqrt();
+        let (sin, cos) = (2.0 * PI * next_uniform(&mut state)).sin_cos();
+        let (step_sin, step_cos) =
+            (2.0 * PI * frequency / sample_frequency).sin_cos();
+        tones.amplitudes.push(amplitude);
+        tones.cos.push(cos);
+        tones.sin.push(sin);
+        tones.step_cos.push(step_cos);
+        tones.step_sin.push(step_sin);
+    }
+    tones
+}
+
+/// Interpolates a phase noise mask linearly in log frequency.
+///
+/// # Arguments
+/// * `mask` - Single-sideband phase noise as (offset in Hz, dBc/Hz) points
+/// * `frequency` - Offset frequency in Hz within the mask
+///
+/// # Returns
+/// The phase noise level in dBc/Hz
+fn mask_level(mask: &[(f64, f64)], frequency: f64) -> f64 {
+    let index = mask
+        .windows(2)
+        .position(|pair| frequency <= pair[1].0)
+        .unwrap_or(mask.len() - 2);
+    let ((f0, l0), (f1, l1)) = (mask[index], mask[index + 1]);
+    let t = (frequency / f0).log10() / (f1 / f0).log10();
+    l0 + t * (l1 - l0)
+}
+
+/// Draws a uniform number in [0, 1) from a splitmix64 generator.
+///
+/// # Arguments
+/// * `state` - Generator state, advanced by the draw
+fn next_uniform(state: &mut u64) -> f64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+    (z >> 11) as f64 / (1u64 << 53) as f64
+}
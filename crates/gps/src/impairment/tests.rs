@@ -0,0 +1,137 @@
+use super::{ImpairmentStage, Impairments};
+
+/// Sampling frequency of the test stages in Hz.
+const SAMPLE_FREQUENCY: f64 = 1e6;
+
+/// Returns the impairment stage of a transmitter at GPS L1.
+fn stage(impairments: &Impairments) -> ImpairmentStage {
+    ImpairmentStage::new(impairments, SAMPLE_FREQUENCY, 1575.42e6)
+}
+
+/// Returns a sequence of accumulator samples on a circle with some DC.
+fn samples(count: usize) -> Vec<(i32, i32)> {
+    (0..count)
+        .map(|k| {
+            let (sin, cos) = (0.013 * k as f64).sin_cos();
+            ((20_000.0 * cos) as i32 + 300, (20_000.0 * sin) as i32 - 700)
+        })
+        .collect()
+}
+
+#[test]
+fn no_impairments_leave_samples_unchanged() {
+    let impairments = Impairments::default();
+    assert!(impairments.is_none());
+    for predistortion in [false, true] {
+        let mut stage = stage(&Impairments {
+            predistortion,
+            ..Impairments::default()
+        });
+        let extremes = [(i32::MAX, i32::MIN), (0, 0), (-1, 1)];
+        for (i, q) in samples(1000).into_iter().chain(extremes) {
+            assert_eq!(stage.apply(i, q), (i, q));
+        }
+    }
+}
+
+#[test]
+fn imbalance_and_dc_offset() {
+    // Q branch gain of 2 with a 30 degree phase error, DC of 1 and -2
+    // output units (128 and -256 in the accumulators)
+    let mut stage = stage(&Impairments {
+        gain_imbalance: 20.0 * 2.0f64.log10(),
+        phase_imbalance: 30.0,
+        dc_offset: (1.0, -2.0),
+        ..Impairments::default()
+    });
+    // I = 1000 - 2 * 1000 * sin 30 + 128, Q = 2 * 1000 * cos 30 - 256
+    assert_eq!(stage.apply(1000, 1000), (128, 1476));
+    // I passes unchanged but for the offset, Q leaks into I
+    assert_eq!(stage.apply(1000, 0), (1128, -256));
+    assert_eq!(stage.apply(0, 1000), (-872, 1476));
+    assert_eq!(stage.apply(0, 0), (128, -256));
+}
+
+#[test]
+fn frequency_offset_rotates_samples() {
+    // 1 ppm at L1 is 1575.42 Hz, a quarter turn after 158.7 us
+    let mut stage = stage(&Impairments {
+        frequency_offset: 1.0,
+        ..Impairments::default()
+    });
+    let step = 2.0 * std::f64::consts::PI * 1575.42 / SAMPLE_FREQUENCY;
+    for k in 0..1000 {
+        let (sin, cos) = (step * f64::from(k)).sin_cos();
+        let expected = ((1e5 * cos).round() as i32, (1e5 * sin).round() as i32);
+        let (i, q) = stage.apply(100_000, 0);
+        assert!((i - expected.0).abs() <= 1 && (q - expected.1).abs() <= 1);
+    }
+}
+
+#[test]
+fn predistortion_inverts_impairments() {
+    let impairments = Impairments {
+        gain_imbalance: -1.5,
+        phase_imbalance: 4.0,
+        dc_offset: (12.5, -7.25),
+        frequency_offset: 2.5,
+        phase_noise: vec![(1e3, -70.0), (1e5, -100.0)],
+        predistortion: false,
+    };
+    let mut transmitter = stage(&impairments);
+    let mut predistortion = stage(&Impairments {
+        predistortion: true,
+        ..impairments
+    });
+    // Both stages draw the same LO phases sample by sample, so that the
+    // predistortion undoes the impairments up to the rounding of the
+    // impaired samples, scaled by the inverse Q gain
+    for (i, q) in samples(5000) {
+        let (i_tx, q_tx) = transmitter.apply(i, q);
+        let (i_rx, q_rx) = predistortion.apply(i_tx, q_tx);
+        assert!((i_rx - i).abs() <= 2, "I {i} -> {i_rx}");
+        assert!((q_rx - q).abs() <= 2, "Q {q} -> {q_rx}");
+    }
+}
+
+#[test]
+fn phase_noise_follows_mask() {
+    let mask = vec![(1e3, -80.0), (1e4, -95.0), (1e5, -105.0), (1e6, -110.0)];
+    // Integrated mask up to half the sampling frequency, 2 L(f) df with
+    // L(f) linear in dB against log frequency
+    let steps = 100_000;
+    let (first, last) = (1e3f64, SAMPLE_FREQUENCY / 2.0);
+    let mut variance = 0.0;
+    for k in 0..steps {
+        let f0 = first * (last / first).powf(f64::from(k) / f64::from(steps));
+        let f1 =
+            first * (last / first).powf(f64::from(k + 1) / f64::from(steps));
+        let f = (f0 * f1).sqrt();
+        let (low, high) = mask
+            .windows(2)
+            .map(|pair| (pair[0], pair[1]))
+            .find(|&(_, (f_high, _))| f <= f_high)
+            .unwrap_or((mask[2], mask[3]));
+        let t = (f / low.0).log10() / (high.0 / low.0).log10();
+        let level = low.1 + t * (high.1 - low.1);
+        variance += 2.0 * 10.0f64.powf(level / 10.0) * (f1 - f0);
+    }
+    // The phase of a constant sample over one second, a thousand periods
+    // of the lowest offset
+    let mut stage = stage(&Impairments {
+        phase_noise: mask,
+        ..Impairments::default()
+    });
+    let mut sum_squares = 0.0;
+    let count = 1_000_000;
+    for _ in 0..count {
+        let (i, q) = stage.apply(1_000_000_000, 0);
+        sum_squares += f64::from(q).atan2(f64::from(i)).powi(2);
+    }
+    let rms = (sum_squares / f64::from(count)).sqrt();
+    let expected = variance.sqrt();
+    assert!(
+        (rms / expected - 1.0).abs() < 0.01,
+        "{rms} rad RMS against {expected} rad of the mask"
+    );
+}
@@ -29,7 +29,8 @@ pub enum DataFormat {
 ///
 /// This structure manages the buffering and formatting of I/Q samples
 /// for writing to a binary file. It supports different bit depths (1, 8, or 16
-/// bits) and handles the necessary conversions and optimizations.
+/// bits) and handles the necessary conversions and optimizations. For real
+/// output only the I samples are written.
 #[derive(Debug)]
 pub struct IQWriter {
     /// Buffered file writer for efficient I/O
@@ -43,6 +44,12 @@ pub struct IQWriter {
 
     /// Size of the I/Q buffer in samples
     pub buffer_size: usize,
+
+    /// Whether to write the I samples only, as real samples
+    real: bool,
+
+    /// Buffer for the I samples of real output, reused between writes
+    real_buffer: Vec<i16>,
 }
 impl IQWriter {
     /// Creates a new I/Q sample writer.
@@ -55,6 +62,7 @@ impl IQWriter {
     /// * `path` - Path to the output file
     /// * `format` - Format specification for the output data (1, 8, or 16 bits)
     /// * `buffer_size` - Size of the I/Q buffer in samples
+    /// * `real` - Whether to write the I samples only, as real samples
     ///
     /// # Returns
     /// * `Ok(Self)` - A new `IQWriter` instance
@@ -63,17 +71,21 @@ impl IQWriter {
     /// # Errors
     /// * Returns an error if the output file cannot be created
     pub fn new(
-        path: &PathBuf, format: DataFormat, buffer_size: usize,
+        path: &PathBuf, format: DataFormat, buffer_size: usize, real: bool,
     ) -> Result<Self, Error> {
         let file = File::create(path)?;
         let mut writer = BufWriter::new(file);
         // Allocate buffer for I/Q samples (2 values per sample: I and Q)
         let buffer = vec![0; 2 * buffer_size];
+        let real_buffer =
+            Vec::with_capacity(if real { buffer_size } else { 0 });
         Ok(Self {
             writer,
             format,
             buffer,
             buffer_size,
+            real,
+            real_buffer,
         })
     }
 
@@ -87,6 +99,8 @@ impl IQWriter {
     /// - For 8-bit format: Converts 16-bit samples to 8-bit
     /// - For 16-bit format: Writes samples directly
     ///
+    /// For real output, the Q samples are dropped before the conversion.
+    ///
     /// # Returns
     /// * `Ok(())` - If the samples were successfully written
     /// * `Err(Error)` - If there was an error writing to the file
@@ -95,11 +109,19 @@ impl IQWriter {
     /// * Returns an error if writing to the output file fails
     #[inline]
     pub fn write_samples(&mut self) -> Result<(), Error> {
+        let samples = if self.real {
+            self.real_buffer.clear();
+            self.real_buffer
+                .extend(self.buffer.iter().step_by(2).copied());
+            &self.real_buffer
+        } else {
+            &self.buffer
+        };
         match self.format {
             DataFormat::Bits1 => {
                 // For 1-bit format, pack 8 samples into each byte
-                let mut iq8_buff = vec![0; self.buffer_size / 4];
-                for isamp in 0..2 * self.buffer_size {
+                let mut iq8_buff = vec![0; samples.len().div_ceil(8)];
+                for isamp in 0..samples.len() {
                     if isamp % 8 == 0 {
                         iq8_buff[isamp / 8] = 0;
                     }
@@ -107,7 +129,7 @@ impl IQWriter {
 
                     // Set the appropriate bit based on sample sign
                     *curr_bit = (i32::from(*curr_bit)
-                        | i32::from(i32::from(self.buffer[isamp]) > 0)
+                        | i32::from(i32::from(samples[isamp]) > 0)
                             << (7 - isamp as i32 % 8))
                         as i8;
                 }
@@ -122,16 +144,16 @@ impl IQWriter {
                 unsafe {
                     self.writer.write_all(std::slice::from_raw_parts(
                         iq8_buff.as_ptr().cast::<u8>(),
-                        self.buffer_size / 4,
+                        iq8_buff.len(),
                     ))?;
                 }
             }
             DataFormat::Bits8 => {
                 // For 8-bit format, convert 16-bit samples to 8-bit
-                let mut iq8_buff = vec![0; 2 * self.buffer_size];
+                let mut iq8_buff = vec![0; samples.len()];
                 for (isamp, buff) in iq8_buff.iter_mut().enumerate() {
                     // Convert 16-bit to 8-bit by right-shifting 4 bits
-                    *buff = (i32::from(self.buffer[isamp]) >> 4) as i8;
+                    *buff = (i32::from(samples[isamp]) >> 4) as i8;
                     // 12-bit bladeRF -> 8-bit HackRF
                     //iq8_buff[isamp] = iq_buff[isamp] >> 8; // for PocketSDR
                 }
@@ -146,22 +168,22 @@ impl IQWriter {
                 unsafe {
                     self.writer.write_all(std::slice::from_raw_parts(
                         iq8_buff.as_ptr().cast::<u8>(),
-                        2 * self.buffer_size,
+                        iq8_buff.len(),
                     ))?;
                 }
             }
             DataFormat::Bits16 => {
                 // For 16-bit format, write samples directly
-                // SAFETY: We're creating a byte slice from the internal i16
-                // buffer. The buffer is allocated and
+                // SAFETY: We're creating a byte slice from the i16 samples of
+                // the internal buffer. The buffer is allocated and
                 // initialized before this function is called,
                 // and we're only reading the raw bytes to write them to a file.
                 // The slice lifetime is limited to this function call and
                 // doesn't outlive the buffer.
                 let byte_slice = unsafe {
                     std::slice::from_raw_parts(
-                        self.buffer.as_ptr().cast::<u8>(),
-                        2 * self.buffer_size * 2, // 2 bytes per sample
+                        samples.as_ptr().cast::<u8>(),
+                        samples.len() * 2, // 2 bytes per sample
                     )
                 };
                 self.writer.write_all(byte_slice)?;
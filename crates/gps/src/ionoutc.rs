@@ -11,7 +11,7 @@
 /// caused by the ionosphere, which varies with time of day, receiver location,
 /// and satellite elevation angle.
 #[allow(non_snake_case)]
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct IonoUtc {
     /// Flag to enable/disable ionospheric corrections
     pub enable: bool,
@@ -87,30 +87,44 @@ impl IonoUtc {
     /// Populates ionospheric and UTC parameters from a RINEX navigation file.
     ///
     /// This method extracts the ionospheric model parameters (alpha, beta) and
-    /// UTC conversion parameters from a parsed RINEX navigation file. It also
-    /// sets the validity flag based on the reference time.
+    /// UTC conversion parameters from a parsed RINEX navigation file. As in
+    /// gps-sdr-sim, the parameters are only valid when the file provides all
+    /// of them and the reference time is a multiple of 4096 seconds.
     ///
     /// # Arguments
     /// * `rinex` - A reference to a parsed RINEX navigation file
     pub fn read_from_rinex(&mut self, rinex: &rinex::Rinex) {
         // Extract ionospheric model parameters (Klobuchar model)
-        self.alpha0 = rinex.ion_alpha[0];
-        self.alpha1 = rinex.ion_alpha[1];
-        self.alpha2 = rinex.ion_alpha[2];
-        self.alpha3 = rinex.ion_alpha[3];
-        self.beta0 = rinex.ion_beta[0];
-        self.beta1 = rinex.ion_beta[1];
-        self.beta2 = rinex.ion_beta[2];
-        self.beta3 = rinex.ion_beta[3];
+        if let Some(ion_alpha) = rinex.ion_alpha {
+            self.alpha0 = ion_alpha[0];
+            self.alpha1 = ion_alpha[1];
+            self.alpha2 = ion_alpha[2];
+            self.alpha3 = ion_alpha[3];
+        }
+        if let Some(ion_beta) = rinex.ion_beta {
+            self.beta0 = ion_beta[0];
+            self.beta1 = ion_beta[1];
+            self.beta2 = ion_beta[2];
+            self.beta3 = ion_beta[3];
+        }
 
         // Extract UTC parameters
-        self.A0 = rinex.delta_utc.a0;
-        self.A1 = rinex.delta_utc.a1;
-        self.tot = rinex.delta_utc.time;
-        self.week_number = rinex.delta_utc.week;
-        self.dtls = rinex.leap_seconds;
-
-        // Set validity flag (tot should be a multiple of 4096 seconds)
-        self.vflg = self.tot % 4096 == 0;
+        if let Some(delta_utc) = &rinex.delta_utc {
+            self.A0 = delta_utc.a0;
+            self.A1 = delta_utc.a1;
+            self.tot = delta_utc.time;
+            self.week_number = delta_utc.week;
+        }
+        if let Some(leap_seconds) = rinex.leap_seconds {
+            self.dtls = leap_seconds;
+        }
+
+        // Set validity flag (all parameters read and tot a multiple of 4096
+        // seconds)
+        self.vflg = rinex.ion_alpha.is_some()
+            && rinex.ion_beta.is_some()
+            && rinex.delta_utc.is_some()
+            && rinex.leap_seconds.is_some()
+            && self.tot % 4096 == 0;
     }
 }
@@ -0,0 +1,17 @@
+//! GPS L1C signal generation.
+//!
+//! This module simulates the modernized civil signal of the GPS satellites
+//! on L1 next to the C/A code in the same composite output. The L1CD and
+//! L1CP ranging codes are Weil codes, the pilot carries the L1CO overlay
+//! code, and the CNAV-2 frames are built from the GPS ephemerides with the
+//! inter-signal corrections of RINEX 4 CNAV-2 records.
+
+/// L1CD/L1CP Weil codes and the L1CO overlay codes
+mod codes;
+/// CNAV-2 frames of the L1CD component
+mod message;
+/// L1C signals driven by the signal generator
+mod system;
+
+pub(crate) use codes::l1c_codes;
+pub use system::L1cSystem;
@@ -0,0 +1,167 @@
+use constants::L1C_CODE_LEN;
+
+/// Number of PRNs with L1C codes in IS-GPS-800.
+pub const L1C_CODES: usize = 63;
+
+/// Length of the Legendre sequence and of the Weil codes (prime).
+const WEIL_LEN: usize = 10223;
+
+/// Chips inserted into the Weil code to extend it to the ranging code length.
+const EXPANSION: [u8; 7] = [0, 1, 1, 0, 1, 0, 0];
+
+/// Weil indices of the L1CD codes of PRN 1 to 63.
+const L1CD_WEIL_INDICES: [usize; L1C_CODES] = [
+    5097, 5110, 5079, 4403, 4121, 5043, 5042, 5104, 4940, 5035, 4372, 5064,
+    5084, 5048, 4950, 5019, 5076, 3736, 4993, 5060, 5061, 5096, 4983, 4783,
+    4991, 4815, 4443, 4769, 4879, 4894, 4985, 5056, 4921, 5036, 4812, 4838,
+    4855, 4904, 4753, 4483, 4942, 4813, 4957, 4618, 4669, 4969, 5031, 5038,
+    4740, 4073, 4843, 4979, 4867, 4964, 5025, 4579, 4390, 4763, 4612, 4784,
+    3716, 4703, 4851,
+];
+
+/// Insertion indices of the expansion sequence of the L1CD codes of PRN 1 to
+/// 63.
+const L1CD_INSERTION_INDICES: [usize; L1C_CODES] = [
+    181, 359, 72, 1110, 1480, 5034, 4622, 1, 4547, 826, 6284, 4195, 368, 1,
+    4796, 523, 151, 713, 9850, 5734, 34, 6142, 190, 644, 467, 5384, 801, 594,
+    4450, 9437, 4307, 5906, 378, 9448, 9432, 5849, 5547, 9546, 9132, 403, 3766,
+    3, 684, 9711, 333, 6124, 10216, 4251, 9893, 9884, 4627, 4449, 9798, 985,
+    4272, 126, 10024, 434, 1029, 561, 289, 638, 4353,
+];
+
+/// Weil indices of the L1CP codes of PRN 1 to 63.
+const L1CP_WEIL_INDICES: [usize; L1C_CODES] = [
+    5111, 5109, 5108, 5106, 5103, 5101, 5100, 5098, 5095, 5094, 5093, 5091,
+    5090, 5081, 5080, 5069, 5068, 5054, 5044, 5027, 5026, 5014, 5004, 4980,
+    4915, 4909, 4893, 4885, 4832, 4824, 4591, 3706, 5092, 4986, 4965, 4920,
+    4917, 4858, 4847, 4790, 4770, 4318, 4126, 3961, 3790, 4911, 4881, 4827,
+    4795, 4789, 4725, 4675, 4539, 4535, 4458, 4197, 4096, 3484, 3481, 3393,
+    3175, 2360, 1852,
+];
+
+/// Insertion indices of the expansion sequence of the L1CP codes of PRN 1 to
+/// 63.
+const L1CP_INSERTION_INDICES: [usize; L1C_CODES] = [
+    412, 161, 1, 303, 207, 4971, 4496, 5, 4557, 485, 253, 4676, 1, 66, 4485,
+    282, 193, 5211, 729, 4848, 982, 5955, 9805, 670, 464, 29, 429, 394, 616,
+    9457, 4429, 4771, 365, 9705, 9489, 4193, 9947, 824, 864, 347, 677, 6544,
+    6312, 9804, 278, 9461, 444, 4839, 4144, 9875, 197, 1156, 4674, 10035, 4504,
+    5, 9937, 430, 5, 355, 909, 1622, 6284,
+];
+
+/// Length of the L1CO overlay code in bits (one per L1CP code period, 18 s).
+const OVERLAY_LEN: usize = 1800;
+
+/// Number of stages of the overlay code shift register.
+const OVERLAY_STAGES: usize = 11;
+
+/// Polynomials of the overlay code generator of PRN 1 to 63, with the
+/// coefficient of x^11 as the most significant bit.
+const OVERLAY_POLYNOMIALS: [u16; L1C_CODES] = [
+    0o5111, 0o5421, 0o5501, 0o5403, 0o6417, 0o6141, 0o6351, 0o6501, 0o6205,
+    0o6235, 0o7751, 0o6623, 0o6733, 0o7627, 0o5667, 0o5051, 0o7665, 0o6325,
+    0o4365, 0o4745, 0o7633, 0o6747, 0o4475, 0o4225, 0o7063, 0o4423, 0o6651,
+    0o4161, 0o7237, 0o4473, 0o5477, 0o6163, 0o7223, 0o6323, 0o7125, 0o7035,
+    0o4341, 0o4353, 0o4107, 0o5735, 0o6741, 0o7071, 0o4563, 0o5755, 0o6127,
+    0o4671, 0o4511, 0o4533, 0o5357, 0o5607, 0o6673, 0o6153, 0o7565, 0o7107,
+    0o6211, 0o4321, 0o7201, 0o4451, 0o5411, 0o5141, 0o7041, 0o6637, 0o4577,
+];
+
+/// Initial conditions of the overlay code generator of PRN 1 to 63, the
+/// first 11 bits of the code with the first bit as the most significant bit.
+const OVERLAY_INITIAL_CONDITIONS: [u16; L1C_CODES] = [
+    0o3266, 0o2040, 0o1527, 0o3307, 0o3756, 0o3026, 0o0562, 0o0420, 0o3415,
+    0o0337, 0o0265, 0o1230, 0o2204, 0o1440, 0o2412, 0o3516, 0o2761, 0o3750,
+    0o2701, 0o1206, 0o1544, 0o1774, 0o0546, 0o2213, 0o3707, 0o2051, 0o3650,
+    0o1777, 0o3203, 0o1762, 0o2100, 0o0571, 0o3710, 0o3535, 0o3110, 0o1426,
+    0o0255, 0o0321, 0o3124, 0o0572, 0o1736, 0o3305, 0o0020, 0o0551, 0o2047,
+    0o0044, 0o1254, 0o2254, 0o1430, 0o0025, 0o3752, 0o3745, 0o1205, 0o3231,
+    0o3333, 0o2122, 0o1015, 0o3402, 0o1342, 0o3640, 0o1006, 0o2010, 0o1754,
+];
+
+/// Generates the L1CD and L1CP ranging codes of a satellite.
+///
+/// Each code is a Weil code, the sum of the Legendre sequence of length
+/// 10223 and the same sequence shifted by the Weil index of the satellite,
+/// with the 7-chip expansion sequence 0110100 inserted before the chip at the
+/// insertion index. Chips of binary value 0 are transmitted as +1 and chips
+/// of value 1 as -1.
+///
+/// # Arguments
+/// * `prn` - PRN number of the satellite (1 to 63)
+///
+/// # Returns
+/// The 10230 L1CD and L1CP chips (+1 or -1), or `None` for other PRNs
+pub fn l1c_codes(prn: usize) -> Option<(Vec<i8>, Vec<i8>)> {
+    let sv = prn.checked_sub(1)?;
+    let legendre = legendre_sequence();
+    Some((
+        weil_code(
+            &legendre,
+            *L1CD_WEIL_INDICES.get(sv)?,
+            *L1CD_INSERTION_INDICES.get(sv)?,
+        ),
+        weil_code(
+            &legendre,
+            *L1CP_WEIL_INDICES.get(sv)?,
+            *L1CP_INSERTION_INDICES.get(sv)?,
+        ),
+    ))
+}
+
+/// Returns the Legendre sequence of length 10223: 1 at the non-zero
+/// quadratic residues, 0 elsewhere.
+fn legendre_sequence() -> Vec<u8> {
+    let mut legendre = vec![0; WEIL_LEN];
+    for x in 1..WEIL_LEN {
+        legendre[x * x % WEIL_LEN] = 1;
+    }
+    legendre
+}
+
+/// Builds a ranging code from the Legendre sequence, a Weil index and an
+/// insertion index.
+fn weil_code(legendre: &[u8], weil_index: usize, insertion: usize) -> Vec<i8> {
+    let weil = |t: usize| legendre[t] ^ legendre[(t + weil_index) % WEIL_LEN];
+    let mut bits: Vec<u8> = (0..insertion - 1).map(weil).collect();
+    bits.extend(EXPANSION);
+    bits.extend((insertion - 1..WEIL_LEN).map(weil));
+    debug_assert_eq!(bits.len(), L1C_CODE_LEN);
+    bits.into_iter()
+        .map(|bit| if bit == 0 { 1 } else { -1 })
+        .collect()
+}
+
+/// Generates the L1CO overlay code of a satellite, the secondary code of the
+/// L1CP component.
+///
+/// The code is the first 1800 bits of an 11-stage maximal length sequence,
+/// starting with the initial conditions of IS-GPS-800, each bit following
+/// from the 11 bits before by the recursion of the polynomial. Bits of
+/// binary value 0 are transmitted as +1 and bits of value 1 as -1.
+///
+/// # Arguments
+/// * `prn` - PRN number of the satellite (1 to 63)
+///
+/// # Returns
+/// The 1800 overlay bits (+1 or -1), or `None` for other PRNs
+pub fn overlay_code(prn: usize) -> Option<Vec<i8>> {
+    let sv = prn.checked_sub(1)?;
+    let polynomial = *OVERLAY_POLYNOMIALS.get(sv)?;
+    let initial = *OVERLAY_INITIAL_CONDITIONS.get(sv)?;
+    let mut bits: Vec<u16> = (0..OVERLAY_STAGES)
+        .rev()
+        .map(|bit| initial >> bit & 1)
+        .collect();
+    while bits.len() < OVERLAY_LEN {
+        let start = bits.len() - OVERLAY_STAGES;
+        let feedback = (0..OVERLAY_STAGES)
+            .fold(0, |sum, k| sum ^ (polynomial >> k & bits[start + k]));
+        bits.push(feedback & 1);
+    }
+    Some(
+        bits.into_iter()
+            .map(|bit| if bit == 0 { 1 } else { -1 })
+            .collect(),
+    )
+}
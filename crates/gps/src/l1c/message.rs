@@ -0,0 +1,241 @@
+use constants::{PI, SECONDS_IN_WEEK};
+use rtcm::crc::crc24q_bits;
+
+use crate::{
+    datetime::GpsTime, ephemeris::Ephemeris, ionoutc::IonoUtc, signal::Bits,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Duration of a frame in seconds.
+pub const FRAME_SECONDS: f64 = 18.0;
+
+/// Number of bits of subframe 2 before the CRC.
+const SUBFRAME2_BITS: usize = 576;
+
+/// Number of bits of subframe 3 before the CRC.
+const SUBFRAME3_BITS: usize = 250;
+
+/// Number of rows of the block interleaver of subframes 2 and 3.
+const INTERLEAVER_ROWS: usize = 38;
+
+/// Number of columns of the block interleaver of subframes 2 and 3.
+const INTERLEAVER_COLUMNS: usize = 46;
+
+/// Feedback stages of the BCH(51,8) encoder of the TOI count,
+/// 1 + x^3 + x^4 + x^5 + x^6 + x^7 + x^8.
+const BCH_FEEDBACK: u8 = 0b1001_1111;
+
+/// Number of BCH symbols of the 8 least significant bits of the TOI count.
+const BCH_SYMBOLS: usize = 51;
+
+/// Duration of an interval of the time of week in seconds (two hours).
+const INTERVAL_SECONDS: f64 = 7200.0;
+
+/// Reference semi-major axis of the CNAV-2 ephemeris (meters).
+const A_REF: f64 = 26_559_710.0;
+
+/// Reference rate of right ascension of the CNAV-2 ephemeris
+/// (semi-circles/second).
+const OMEGA_DOT_REF: f64 = -2.6e-9;
+
+/// Unit of the CNAV-2 times of ephemeris, clock and prediction in seconds.
+const TIME_UNIT: f64 = 300.0;
+
+/// Inter-signal corrections of subframes 2 and 3 (seconds).
+///
+/// The clock correction of a signal is the one of the LNAV message, TGD
+/// included, plus the correction of the signal.
+#[derive(Clone, Copy, Default)]
+pub struct InterSignalCorrections {
+    /// Correction of L1 C/A
+    pub l1ca: f64,
+    /// Correction of L2C
+    pub l2c: f64,
+    /// Correction of L5 I5
+    pub l5i5: f64,
+    /// Correction of L5 Q5
+    pub l5q5: f64,
+    /// Correction of L1CD
+    pub l1cd: f64,
+    /// Correction of L1CP
+    pub l1cp: f64,
+}
+
+/// Content of the CNAV-2 message of a GPS satellite on L1CD.
+///
+/// Every 18 s frame carries the time of interval in subframe 1, the
+/// ephemeris and clock of the LNAV ephemeris in the CNAV-2 representation in
+/// subframe 2, and the UTC and ionospheric parameters in page 1 of
+/// subframe 3. The rates of the semi-major axis and the mean motion
+/// difference are zero and the times of ephemeris and clock are rounded to
+/// 300 seconds.
+///
+/// The LDPC parity symbols of subframes 2 and 3 are sent as zeros, since the
+/// parity-check matrices of IS-GPS-800 are not part of the simulator; the
+/// information symbols, the BCH code of subframe 1 and the interleaving
+/// follow the specification.
+pub struct Cnav2Message<'a> {
+    /// Satellite PRN
+    pub prn: usize,
+    /// Broadcast ephemeris of the satellite
+    pub eph: &'a Ephemeris,
+    /// Inter-signal corrections of the satellite
+    pub isc: InterSignalCorrections,
+    /// Ionospheric and UTC parameters
+    pub ionoutc: &'a IonoUtc,
+}
+
+impl Cnav2Message<'_> {
+    /// Generates the symbols of consecutive frames.
+    ///
+    /// # Arguments
+    /// * `start` - Start of the first frame, a multiple of 18 seconds from the
+    ///   start of the week
+    /// * `frames` - Number of frames
+    ///
+    /// # Returns
+    /// 1800 symbols per frame, +1 for binary 0 and -1 for binary 1
+    pub fn symbols(&self, start: &GpsTime, frames: usize) -> Vec<i8> {
+        (0..frames)
+            .flat_map(|index| {
+                self.frame(&start.add_secs(index as f64 * FRAME_SECONDS))
+            })
+            .map(|bit| if bit == 0 { 1 } else { -1 })
+            .collect()
+    }
+
+    /// Builds the 1800 symbols of the frame transmitted from a time on.
+    ///
+    /// # Arguments
+    /// * `time` - GPS time at the start of the frame
+    fn frame(&self, time: &GpsTime) -> Vec<u8> {
+        // Time of interval and interval of the week at the start of the next
+        // frame
+        let next = (time.sec + FRAME_SECONDS) % SECONDS_IN_WEEK;
+        let toi = ((next % INTERVAL_SECONDS) / FRAME_SECONDS).round() as u64;
+        let itow = (next / INTERVAL_SECONDS).floor() as u64;
+        let mut symbols = toi_symbols(toi);
+        let mut subframes = Bits::default();
+        subframes.0.extend(encode(self.subframe2(time, itow)));
+        subframes.0.extend(encode(self.subframe3()));
+        // Written row by row and read column by column
+        for column in 0..INTERLEAVER_COLUMNS {
+            for row in 0..INTERLEAVER_ROWS {
+                symbols.push(subframes.0[row * INTERLEAVER_COLUMNS + column]);
+            }
+        }
+        symbols
+    }
+
+    /// Builds the ephemeris and clock bits of subframe 2.
+    fn subframe2(&self, time: &GpsTime, itow: u64) -> Bits {
+        let eph = self.eph;
+        let toe = (eph.toe.sec / TIME_UNIT).round() as u64;
+        let mut bits = Bits::default();
+        bits.push(time.week as u64, 13);
+        bits.push(itow, 8);
+        bits.push(toe, 11);
+        bits.push(u64::from(eph.svhlth != 0), 1);
+        bits.push(eph.ura as u64, 5);
+        bits.push(toe, 11);
+        bits.push_scaled(eph.A - A_REF, 9, 26);
+        // Rate of change of the semi-major axis
+        bits.zeros(25);
+        bits.push_scaled(eph.deltan / PI, 44, 17);
+        // Rate of change of the mean motion difference
+        bits.zeros(23);
+        bits.push_scaled(eph.m0 / PI, 32, 33);
+        bits.push((eph.ecc * 2f64.powi(34)).round() as u64, 33);
+        bits.push_scaled(eph.aop / PI, 32, 33);
+        bits.push_scaled(eph.omg0 / PI, 32, 33);
+        bits.push_scaled(eph.inc0 / PI, 32, 33);
+        bits.push_scaled(eph.omgdot / PI - OMEGA_DOT_REF, 44, 17);
+        bits.push_scaled(eph.idot / PI, 44, 15);
+        bits.push_scaled(eph.cis, 30, 16);
+        bits.push_scaled(eph.cic, 30, 16);
+        bits.push_scaled(eph.crs, 8, 24);
+        bits.push_scaled(eph.crc, 8, 24);
+        bits.push_scaled(eph.cus, 30, 21);
+        bits.push_scaled(eph.cuc, 30, 21);
+        // URA_NED0, URA_NED1 and URA_NED2
+        bits.zeros(11);
+        bits.push_scaled(eph.af0, 35, 26);
+        bits.push_scaled(eph.af1, 48, 20);
+        bits.push_scaled(eph.af2, 60, 10);
+        bits.push_scaled(eph.tgd, 35, 13);
+        bits.push_scaled(self.isc.l1cp, 35, 13);
+        bits.push_scaled(self.isc.l1cd, 35, 13);
+        // Integrity status flag
+        bits.zeros(1);
+        bits.push(time.week as u64, 8);
+        bits.pad(SUBFRAME2_BITS);
+        bits
+    }
+
+    /// Builds the bits of page 1 of subframe 3 with the UTC and ionospheric
+    /// parameters.
+    fn subframe3(&self) -> Bits {
+        let ionoutc = self.ionoutc;
+        let isc = &self.isc;
+        let mut bits = Bits::default();
+        bits.push(self.prn as u64, 8);
+        bits.push(1, 6);
+        bits.push_scaled(ionoutc.A0, 35, 16);
+        bits.push_scaled(ionoutc.A1, 51, 13);
+        // Second-order term
+        bits.zeros(7);
+        bits.push(ionoutc.dtls as u64, 8);
+        bits.push((ionoutc.tot >> 4) as u64, 16);
+        bits.push(ionoutc.week_number as u64, 13);
+        bits.push(ionoutc.wnlsf as u64, 13);
+        bits.push(ionoutc.day_number as u64, 4);
+        bits.push(ionoutc.dtlsf as u64, 8);
+        bits.push_scaled(ionoutc.alpha0, 30, 8);
+        bits.push_scaled(ionoutc.alpha1, 27, 8);
+        bits.push_scaled(ionoutc.alpha2, 24, 8);
+        bits.push_scaled(ionoutc.alpha3, 24, 8);
+        bits.push_scaled(ionoutc.beta0, -11, 8);
+        bits.push_scaled(ionoutc.beta1, -14, 8);
+        bits.push_scaled(ionoutc.beta2, -16, 8);
+        bits.push_scaled(ionoutc.beta3, -16, 8);
+        bits.push_scaled(isc.l1ca, 35, 13);
+        bits.push_scaled(isc.l2c, 35, 13);
+        bits.push_scaled(isc.l5i5, 35, 13);
+        bits.push_scaled(isc.l5q5, 35, 13);
+        bits.pad(SUBFRAME3_BITS);
+        bits
+    }
+}
+
+/// Encodes the TOI count into the 52 symbols of subframe 1.
+///
+/// The 8 least significant bits are encoded by the BCH(51,8) code, and the
+/// most significant bit is added to each of the 51 symbols and sent first.
+fn toi_symbols(toi: u64) -> Vec<u8> {
+    let msb = (toi >> 8 & 1) as u8;
+    let mut register = toi as u8;
+    let mut symbols = Vec::with_capacity(BCH_SYMBOLS + 1);
+    symbols.push(msb);
+    for _ in 0..BCH_SYMBOLS {
+        symbols.push(register >> 7 ^ msb);
+        let feedback = (register & BCH_FEEDBACK).count_ones() as u8 & 1;
+        register = register << 1 | feedback;
+    }
+    symbols
+}
+
+/// Appends the CRC-24Q and the parity symbols to the bits of subframe 2 or 3.
+fn encode(mut bits: Bits) -> Vec<u8> {
+    let len = bits.0.len();
+    // CRC-24Q over the bits packed MSB first
+    let mut bytes = vec![0u8; len.div_ceil(8)];
+    for (i, &bit) in bits.0.iter().enumerate() {
+        bytes[i / 8] |= bit << (7 - i % 8);
+    }
+    bits.push(u64::from(crc24q_bits(&bytes, len)), 24);
+    // Rate 1/2: as many parity symbols as information bits
+    bits.zeros(len + 24);
+    bits.0
+}
@@ -0,0 +1,177 @@
+use rtcm::crc::crc24q_bits;
+use test_case::test_case;
+
+use super::{
+    BCH_SYMBOLS, Cnav2Message, INTERLEAVER_COLUMNS, INTERLEAVER_ROWS,
+    InterSignalCorrections, SUBFRAME2_BITS, SUBFRAME3_BITS, encode,
+    toi_symbols,
+};
+use crate::{
+    datetime::GpsTime, ephemeris::Ephemeris, ionoutc::IonoUtc, signal::Bits,
+};
+
+/// Number of symbols of subframe 1.
+const SUBFRAME1_SYMBOLS: usize = BCH_SYMBOLS + 1;
+
+/// Number of symbols of subframe 2 (1200) and subframe 3 (548) after
+/// encoding.
+const SUBFRAME_SYMBOLS: [usize; 2] =
+    [2 * (SUBFRAME2_BITS + 24), 2 * (SUBFRAME3_BITS + 24)];
+
+/// Packs bits into bytes, most significant bit first.
+fn pack(bits: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![0; bits.len().div_ceil(8)];
+    for (i, &bit) in bits.iter().enumerate() {
+        bytes[i / 8] |= bit << (7 - i % 8);
+    }
+    bytes
+}
+
+/// Returns the unsigned value of `len` bits from `first` on.
+fn field(bits: &[u8], first: usize, len: usize) -> u64 {
+    bits[first..first + len]
+        .iter()
+        .fold(0, |value, &bit| value << 1 | u64::from(bit))
+}
+
+/// Builds the frame of PRN 9 starting at a time of week.
+fn frame(sec: f64) -> Vec<u8> {
+    let eph = Ephemeris {
+        toe: GpsTime {
+            week: 2190,
+            sec: 345_600.0,
+        },
+        ecc: 0.01,
+        ura: 2,
+        ..Ephemeris::default()
+    };
+    let ionoutc = IonoUtc {
+        dtls: 18,
+        week_number: 2190,
+        ..IonoUtc::default()
+    };
+    let message = Cnav2Message {
+        prn: 9,
+        eph: &eph,
+        isc: InterSignalCorrections::default(),
+        ionoutc: &ionoutc,
+    };
+    message.frame(&GpsTime { week: 2190, sec })
+}
+
+/// Reverses the block interleaver of subframes 2 and 3.
+fn deinterleave(symbols: &[u8]) -> Vec<u8> {
+    let mut subframes = vec![0; symbols.len()];
+    for (index, &symbol) in symbols.iter().enumerate() {
+        let (column, row) =
+            (index / INTERLEAVER_ROWS, index % INTERLEAVER_ROWS);
+        subframes[row * INTERLEAVER_COLUMNS + column] = symbol;
+    }
+    subframes
+}
+
+#[test]
+fn toi_bch_code() {
+    let codewords: Vec<Vec<u8>> = (0..256).map(toi_symbols).collect();
+    for (toi, codeword) in codewords.iter().enumerate() {
+        assert_eq!(codeword.len(), SUBFRAME1_SYMBOLS);
+        assert_eq!(codeword[0], 0);
+        // The register starts with the 8 bits of the TOI count, sent first
+        assert_eq!(field(codeword, 1, 8), toi as u64);
+        // Feedback 1 + x^3 + x^4 + x^5 + x^6 + x^7 + x^8
+        let symbols = &codeword[1..];
+        for n in 0..BCH_SYMBOLS - 8 {
+            let feedback = [0, 3, 4, 5, 6, 7]
+                .iter()
+                .fold(0, |sum, &k| sum ^ symbols[n + k]);
+            assert_eq!(symbols[n + 8], feedback, "TOI {toi}, symbol {n}");
+        }
+    }
+    // Linear code with minimum distance 19
+    let weight = |codeword: &[u8]| {
+        codeword
+            .iter()
+            .map(|&symbol| u32::from(symbol))
+            .sum::<u32>()
+    };
+    assert_eq!(codewords[1..].iter().map(|c| weight(c)).min(), Some(19));
+    for (a, b) in [(3, 200), (17, 94), (255, 1)] {
+        let sum: Vec<u8> = codewords[a]
+            .iter()
+            .zip(&codewords[b])
+            .map(|(x, y)| x ^ y)
+            .collect();
+        assert_eq!(sum, codewords[a ^ b]);
+    }
+}
+
+#[test_case(0x000; "zero")]
+#[test_case(0x0a5; "low")]
+#[test_case(0x1a5; "msb")]
+#[test_case(0x18f; "highest count")]
+fn toi_msb(toi: u64) {
+    let symbols = toi_symbols(toi);
+    let low = toi_symbols(toi & 0xff);
+    let msb = (toi >> 8) as u8;
+    assert_eq!(symbols[0], msb);
+    for (symbol, low) in symbols[1..].iter().zip(&low[1..]) {
+        assert_eq!(*symbol, low ^ msb);
+    }
+}
+
+#[test]
+fn frame_toi() {
+    // The TOI count is the one of the next frame: 3600 s into the interval
+    for (sec, toi) in [(345_582.0, 0), (349_182.0, 200), (352_782.0, 0)] {
+        let frame = frame(sec);
+        assert_eq!(frame.len(), 1800);
+        assert_eq!(frame[..SUBFRAME1_SYMBOLS], toi_symbols(toi));
+    }
+}
+
+#[test]
+fn frame_interleaving() {
+    let frame = frame(345_582.0);
+    let interleaved = &frame[SUBFRAME1_SYMBOLS..];
+    assert_eq!(interleaved.len(), INTERLEAVER_ROWS * INTERLEAVER_COLUMNS);
+    assert_eq!(interleaved.len(), SUBFRAME_SYMBOLS.iter().sum::<usize>());
+    let subframes = deinterleave(interleaved);
+    let (subframe2, subframe3) = subframes.split_at(SUBFRAME_SYMBOLS[0]);
+    // Subframe 2: WN, ITOW, top, health, URA, toe
+    assert_eq!(field(subframe2, 0, 13), 2190);
+    assert_eq!(field(subframe2, 13, 8), 48);
+    assert_eq!(field(subframe2, 21, 11), 1152);
+    assert_eq!(field(subframe2, 33, 5), 2);
+    assert_eq!(field(subframe2, 38, 11), 1152);
+    // Subframe 3: PRN and page number
+    assert_eq!(field(subframe3, 0, 8), 9);
+    assert_eq!(field(subframe3, 8, 6), 1);
+    for (subframe, bits) in
+        [(subframe2, SUBFRAME2_BITS), (subframe3, SUBFRAME3_BITS)]
+    {
+        // The CRC-24Q covers the bits of the subframe
+        let information = &subframe[..bits + 24];
+        assert_eq!(crc24q_bits(&pack(information), bits + 24), 0);
+    }
+    // The first column holds the first symbol of each row
+    for row in 0..INTERLEAVER_ROWS {
+        assert_eq!(interleaved[row], subframes[row * INTERLEAVER_COLUMNS]);
+    }
+}
+
+#[test]
+fn ldpc_parity_symbols() {
+    // The parity-check matrices of IS-GPS-800 are not part of the
+    // simulator: the parity symbols follow the information symbols as zeros
+    for (bits, symbols) in [SUBFRAME2_BITS, SUBFRAME3_BITS]
+        .into_iter()
+        .zip(SUBFRAME_SYMBOLS)
+    {
+        let information: Vec<u8> =
+            (0..bits).map(|i| u8::from(i % 3 == 0)).collect();
+        let encoded = encode(Bits(information.clone()));
+        assert_eq!(encoded.len(), symbols);
+        assert_eq!(encoded[..bits], information);
+        assert!(encoded[bits + 24..].iter().all(|&symbol| symbol == 0));
+    }
+}
@@ -0,0 +1,363 @@
+use constants::{
+    CARR_FREQ, CODE_FREQ, L1C_CODE_LEN, L2_FREQ, L5_FREQ, SPEED_OF_LIGHT,
+};
+use rinex::navigation::SatelliteSystem;
+
+use crate::{
+    Error,
+    datetime::{DateTime, GpsTime, TimeRange},
+    ephemeris::Ephemeris,
+    generator::SatelliteSelection,
+    l1c::{
+        codes::{l1c_codes, overlay_code},
+        message::{Cnav2Message, FRAME_SECONDS, InterSignalCorrections},
+    },
+    propagation::compute_range,
+    signal::{
+        SignalChannel, SignalComponent, SignalSystem, Subcarrier,
+        SystemContext, SystemNavigation, allocate_channels, band_offset,
+        is_visible, print_channel_status, signal_gain,
+    },
+    timeline::EphemerisTimeline,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Number of GPS satellites simulated on L1C.
+const L1C_SATELLITES: usize = 32;
+
+/// Number of frames of the symbol buffer of a channel.
+///
+/// The buffer starts with the frame being transmitted at a frame boundary
+/// and reaches past the next boundary, where it is regenerated.
+const WINDOW_FRAMES: usize = 4;
+
+/// Half the bandwidth of the BOC(1,1) main lobes around the carrier (Hz).
+const HALF_BANDWIDTH: f64 = 2.046e6;
+
+/// Number of L1CP chips of a TMBOC pattern.
+const TMBOC_PATTERN: usize = 33;
+
+/// Chips of every TMBOC pattern transmitted with the BOC(6,1) subcarrier.
+const TMBOC_BOC6_CHIPS: [usize; 4] = [0, 4, 6, 29];
+
+/// Amplitude of the L1CD component, a quarter of the L1C power.
+const L1CD_AMPLITUDE: f64 = 0.5;
+
+/// Amplitude of the L1CP component, three quarters of the L1C power.
+const L1CP_AMPLITUDE: f64 = 0.866_025_403_784_438_6;
+
+/// GPS L1C signals with the CNAV-2 message on the L1 frequency.
+///
+/// Each visible GPS satellite allowed by the satellite selection transmits
+/// the L1CD data component with the CNAV-2 message at 100 symbols per second
+/// on a BOC(1,1) subcarrier, and the L1CP pilot component with the L1CO
+/// overlay code on the TMBOC subcarrier, BOC(6,1) for 4 of every 33 chips and
+/// BOC(1,1) otherwise. Both components are in phase with the C/A code
+/// carrier. All satellites with an ephemeris are simulated, whether or not
+/// their block broadcasts the signal.
+///
+/// The ranges follow the broadcast ephemerides, with the Klobuchar delay of
+/// L1 and the group delay of L1CP: TGD plus the inter-signal correction of
+/// the RINEX 4 CNAV-2 record closest in time, or TGD alone without CNAV-2
+/// records.
+pub struct L1cSystem {
+    /// Broadcast ephemerides of all GPS satellites
+    timeline: EphemerisTimeline,
+    /// Ephemeris in use of each satellite (index PRN - 1)
+    ephemerides: Vec<Option<Ephemeris>>,
+    /// Inter-signal corrections of the CNAV-2 records of each satellite
+    /// (index PRN - 1), with their reference times
+    corrections: Vec<Vec<(GpsTime, InterSignalCorrections)>>,
+    /// Satellites allowed to occupy a channel
+    selection: SatelliteSelection,
+    /// L1 carrier frequency minus the output centre frequency, or `None` if
+    /// L1C lies outside the output band
+    carrier_offset: Option<f64>,
+    /// Channels of the visible satellites
+    channels: Vec<SignalChannel>,
+}
+
+impl L1cSystem {
+    /// Creates the L1C system from the GPS ephemerides.
+    ///
+    /// # Arguments
+    /// * `timeline` - Broadcast ephemerides of the GPS satellites
+    /// * `navigation` - Navigation data with the GPS CNAV-2 records, if any
+    /// * `selection` - Satellites allowed to occupy a channel
+    ///
+    /// # Returns
+    /// * `Ok(L1cSystem)` - The system ready to be initialized
+    /// * `Err(Error)` - If a CNAV-2 record cannot be converted
+    ///
+    /// # Errors
+    /// * Returns an error if the time of clock of a CNAV-2 record is out of
+    ///   range
+    pub fn new(
+        timeline: EphemerisTimeline, navigation: &SystemNavigation,
+        selection: SatelliteSelection,
+    ) -> Result<Self, Error> {
+        let mut corrections = vec![Vec::new(); L1C_SATELLITES];
+        for record in navigation
+            .ephemerides(SatelliteSystem::Gps)
+            .filter(|record| record.message.as_deref() == Some("CNV2"))
+        {
+            let Some(satellite) = record
+                .satellite
+                .prn
+                .checked_sub(1)
+                .and_then(|sv| corrections.get_mut(sv))
+            else {
+                continue;
+            };
+            let datetime = DateTime::from(record.epoch.in_tz("UTC")?);
+            let [l1ca, l2c, l5i5, l5q5] = record.orbit(7);
+            let [l1cd, l1cp, _, _] = record.orbit(8);
+            satellite.push((
+                GpsTime::from(&datetime),
+                InterSignalCorrections {
+                    l1ca,
+                    l2c,
+                    l5i5,
+                    l5q5,
+                    l1cd,
+                    l1cp,
+                },
+            ));
+        }
+        Ok(Self {
+            timeline,
+            ephemerides: vec![None; L1C_SATELLITES],
+            corrections,
+            selection,
+            carrier_offset: None,
+            channels: Vec::new(),
+        })
+    }
+
+    /// Takes the ephemeris due at a time into use for every satellite.
+    fn select_ephemerides(&mut self, time: &GpsTime) {
+        for (sv, current) in self.ephemerides.iter_mut().enumerate() {
+            if let Some(eph) = self.timeline.select(sv + 1, time) {
+                *current = Some(eph.clone());
+            }
+        }
+    }
+
+    /// Releases the channels of satellites below the elevation mask and
+    /// allocates channels to the newly visible satellites.
+    fn allocate_channels(&mut self, context: &SystemContext) {
+        let Some(carrier_offset) = self.carrier_offset else {
+            return;
+        };
+        let corrections = &self.corrections;
+        allocate_channels(
+            &mut self.channels,
+            &self.ephemerides,
+            1,
+            &self.selection,
+            |_, eph| is_visible(eph, context),
+            |prn, eph| {
+                let mut channel = SignalChannel::new(
+                    prn,
+                    components(prn)?,
+                    CODE_FREQ,
+                    CARR_FREQ,
+                    carrier_offset,
+                    L1C_CODE_LEN as u64,
+                );
+                let isc = closest_corrections(
+                    &corrections[prn - 1],
+                    eph,
+                    context.time,
+                );
+                channel.start(l1c_range(eph, &isc, context));
+                Some(channel)
+            },
+        );
+    }
+
+    /// Regenerates the message symbols of all channels for the frame
+    /// starting at a time.
+    fn generate_messages(&mut self, context: &SystemContext) {
+        // The buffers start with the frame transmitted a second before the
+        // current reception time
+        let mut start = context.time.add_secs(-1.0);
+        start.sec = (start.sec / FRAME_SECONDS).floor() * FRAME_SECONDS;
+        for channel in &mut self.channels {
+            let Some(eph) = &self.ephemerides[channel.prn - 1] else {
+                continue;
+            };
+            let message = Cnav2Message {
+                prn: channel.prn,
+                eph,
+                isc: closest_corrections(
+                    &self.corrections[channel.prn - 1],
+                    eph,
+                    &start,
+                ),
+                ionoutc: context.ionoutc,
+            };
+            channel.set_symbols(&start, message.symbols(&start, WINDOW_FRAMES));
+        }
+    }
+}
+
+impl SignalSystem for L1cSystem {
+    fn name(&self) -> &'static str {
+        "GPS L1C"
+    }
+
+    fn carrier_frequency(&self) -> f64 {
+        CARR_FREQ
+    }
+
+    fn initialize(&mut self, context: &SystemContext) {
+        self.carrier_offset = band_offset(context, CARR_FREQ, HALF_BANDWIDTH);
+        if self.carrier_offset.is_none() {
+            eprintln!(
+                "Warning: GPS L1C lies outside the output band; GPS L1C \
+                 signals are not simulated."
+            );
+        }
+        self.select_ephemerides(context.time);
+        self.allocate_channels(context);
+        self.generate_messages(context);
+    }
+
+    fn update_channels(&mut self, context: &SystemContext) {
+        for channel in &mut self.channels {
+            let Some(eph) = &self.ephemerides[channel.prn - 1] else {
+                continue;
+            };
+            let isc = closest_corrections(
+                &self.corrections[channel.prn - 1],
+                eph,
+                context.time,
+            );
+            let rho = l1c_range(eph, &isc, context);
+            channel.update(&rho, context.step, context.sampling_period);
+            channel.gain =
+                signal_gain(&rho, context.fixed_gain, context.antenna_pattern);
+        }
+    }
+
+    fn update_navigation(&mut self, context: &SystemContext) {
+        self.select_ephemerides(context.time);
+        self.allocate_channels(context);
+        self.generate_messages(context);
+    }
+
+    fn next_sample(&mut self, sampling_period: f64) -> (i32, i32) {
+        self.channels
+            .iter_mut()
+            .fold((0, 0), |(i_acc, q_acc), channel| {
+                let (ip, qp) = channel.next_sample(sampling_period);
+                (i_acc + ip, q_acc + qp)
+            })
+    }
+
+    fn print_status(&self) {
+        for channel in &self.channels {
+            print_channel_status(
+                &format!("G{:02}", channel.prn),
+                channel.azel(),
+                channel.rho0(),
+                Some("L1C"),
+            );
+        }
+    }
+}
+
+/// Returns the L1CD data component and the L1CP pilot component of a
+/// satellite's signal.
+///
+/// The TMBOC pilot is split into a BOC(1,1) and a BOC(6,1) component, each
+/// with the L1CP chips of its subcarrier and zeros in the chips of the
+/// other one.
+fn components(prn: usize) -> Option<Vec<SignalComponent>> {
+    let (l1cd, l1cp) = l1c_codes(prn)?;
+    let overlay = overlay_code(prn)?;
+    let is_boc6 =
+        |chip: usize| TMBOC_BOC6_CHIPS.contains(&(chip % TMBOC_PATTERN));
+    let pilot = |boc6: bool| {
+        l1cp.iter()
+            .enumerate()
+            .map(|(chip, &value)| if is_boc6(chip) == boc6 { value } else { 0 })
+            .collect()
+    };
+    let component =
+        |code, secondary, data, amplitude, subcarrier| SignalComponent {
+            code,
+            secondary,
+            data,
+            amplitude,
+            subcarrier,
+            quadrature: false,
+        };
+    Some(vec![
+        component(l1cd, Vec::new(), true, L1CD_AMPLITUDE, Subcarrier::Boc(1)),
+        component(
+            pilot(false),
+            overlay.clone(),
+            false,
+            L1CP_AMPLITUDE,
+            Subcarrier::Boc(1),
+        ),
+        component(
+            pilot(true),
+            overlay,
+            false,
+            L1CP_AMPLITUDE,
+            Subcarrier::Boc(6),
+        ),
+    ])
+}
+
+/// Returns the inter-signal corrections of the CNAV-2 record closest to a
+/// time, or the corrections derived from TGD without CNAV-2 records.
+///
+/// # Arguments
+/// * `corrections` - Corrections of the CNAV-2 records of the satellite
+/// * `eph` - Ephemeris in use
+/// * `time` - Current GPS time
+fn closest_corrections(
+    corrections: &[(GpsTime, InterSignalCorrections)], eph: &Ephemeris,
+    time: &GpsTime,
+) -> InterSignalCorrections {
+    corrections
+        .iter()
+        .min_by(|a, b| {
+            time.diff_secs(&a.0)
+                .abs()
+                .total_cmp(&time.diff_secs(&b.0).abs())
+        })
+        .map_or_else(
+            || {
+                let p_code = |freq: f64| {
+                    eph.tgd * (1.0 - (CARR_FREQ / freq) * (CARR_FREQ / freq))
+                };
+                InterSignalCorrections {
+                    l2c: p_code(L2_FREQ),
+                    l5i5: p_code(L5_FREQ),
+                    l5q5: p_code(L5_FREQ),
+                    ..InterSignalCorrections::default()
+                }
+            },
+            |(_, isc)| *isc,
+        )
+}
+
+/// Computes the L1C range of a satellite at the current receiver time.
+///
+/// The inter-signal correction of L1CP is added to the clock correction,
+/// which includes TGD.
+fn l1c_range(
+    eph: &Ephemeris, isc: &InterSignalCorrections, context: &SystemContext,
+) -> TimeRange {
+    let mut rho =
+        compute_range(eph, context.ionoutc, context.time, context.location);
+    rho.range -= SPEED_OF_LIGHT * isc.l1cp;
+    rho
+}
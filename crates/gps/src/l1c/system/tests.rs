@@ -0,0 +1,53 @@
+use super::{
+    L1CD_AMPLITUDE, L1CP_AMPLITUDE, TMBOC_BOC6_CHIPS, TMBOC_PATTERN, components,
+};
+use crate::{Error, l1c::codes::l1c_codes, signal::Subcarrier};
+
+#[test]
+fn tmboc_pilot_pattern() -> Result<(), Error> {
+    let components = components(1).ok_or_else(|| Error::msg("no PRN 1"))?;
+    let (_, l1cp) = l1c_codes(1).ok_or_else(|| Error::msg("no PRN 1"))?;
+    let [_, boc1, boc6] = &components[..] else {
+        panic!("expected three components");
+    };
+    assert!(matches!(boc1.subcarrier, Subcarrier::Boc(1)));
+    assert!(matches!(boc6.subcarrier, Subcarrier::Boc(6)));
+    // BOC(6,1) in chips 0, 4, 6 and 29 of every 33 chips (IS-GPS-800)
+    assert_eq!(TMBOC_PATTERN, 33);
+    assert_eq!(TMBOC_BOC6_CHIPS, [0, 4, 6, 29]);
+    let mut boc6_chips = 0;
+    for (chip, &value) in l1cp.iter().enumerate() {
+        let boc6_chip = [0, 4, 6, 29].contains(&(chip % 33));
+        if boc6_chip {
+            boc6_chips += 1;
+            assert_eq!((boc1.code[chip], boc6.code[chip]), (0, value));
+        } else {
+            assert_eq!((boc1.code[chip], boc6.code[chip]), (value, 0));
+        }
+    }
+    // 10230 chips hold 310 whole patterns
+    assert_eq!(boc6_chips, 310 * 4);
+    assert!(boc1.secondary == boc6.secondary && !boc1.secondary.is_empty());
+    assert!(!boc1.data && !boc6.data);
+    Ok(())
+}
+
+#[test]
+fn tmboc_power() -> Result<(), Error> {
+    let components = components(5).ok_or_else(|| Error::msg("no PRN 5"))?;
+    let power = |chips: &[i8], amplitude: f64| {
+        let energy: f64 =
+            chips.iter().map(|&chip| f64::from(chip).powi(2)).sum();
+        amplitude * amplitude * energy / chips.len() as f64
+    };
+    let data = power(&components[0].code, L1CD_AMPLITUDE);
+    let boc1 = power(&components[1].code, L1CP_AMPLITUDE);
+    let boc6 = power(&components[2].code, L1CP_AMPLITUDE);
+    // L1CD a quarter, L1CP three quarters of the power, and BOC(6,1) 1/11 of
+    // the total as in TMBOC(6,1,4/33)
+    assert!((data - 0.25).abs() < 1e-12);
+    assert!((boc1 + boc6 - 0.75).abs() < 1e-12);
+    assert!((boc6 - 0.75 * 4.0 / 33.0).abs() < 1e-12);
+    assert!((boc6 - 1.0 / 11.0).abs() < 1e-12);
+    Ok(())
+}
@@ -0,0 +1,186 @@
+use std::{fs::File, io::BufWriter, path::PathBuf};
+
+use constants::{LAMBDA_L1_INV, MAX_SAT};
+use geometry::Ecef;
+use rinex::observation::{
+    ObservationEpoch, ObservationHeader, ObservationTime, ObservationWriter,
+    SatelliteObservation,
+};
+
+use crate::{
+    Error,
+    datetime::{DateTime, GpsTime},
+    truth::TruthRecord,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Observation types written for every GPS satellite.
+const OBSERVATION_TYPES: [&str; 4] = ["C1C", "L1C", "D1C", "S1C"];
+
+/// Nominal C/N0 in dB-Hz of a signal with unity gain (128 after scaling).
+pub(crate) const NOMINAL_CN0: f64 = 45.0;
+
+/// Tolerance in seconds when matching update steps to observation epochs.
+const EPOCH_TOLERANCE: f64 = 1e-3;
+
+/// Writes the ideal measurements of a simulation as a RINEX 3 observation
+/// file.
+///
+/// The measurements are those of a perfect receiver with a zero clock
+/// offset tracking the generated signal:
+/// - C1C: pseudorange including satellite clock and ionospheric delay
+/// - L1C: accumulated carrier phase of the generated signal, with an integer
+///   ambiguity chosen so that it starts close to C1C
+/// - D1C: carrier Doppler of the generated signal
+/// - S1C: C/N0 derived from the applied gain, 45 dB-Hz at unity gain
+#[derive(Debug)]
+pub struct ObservationLogger {
+    /// RINEX observation writer
+    writer: ObservationWriter<BufWriter<File>>,
+    /// Observation interval in seconds
+    interval: f64,
+    /// Integer carrier phase ambiguity of each tracked satellite
+    ambiguities: [Option<f64>; MAX_SAT],
+}
+
+impl ObservationLogger {
+    /// Creates the observation file and writes its header.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the RINEX observation file
+    /// * `interval` - Observation interval in seconds
+    /// * `start` - First update step of the simulation
+    /// * `position` - Approximate receiver position for the header
+    /// * `leap_seconds` - GPS-UTC offset in seconds
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - A new `ObservationLogger`
+    /// * `Err(Error)` - If the file cannot be created
+    ///
+    /// # Errors
+    /// * Returns an error if the file cannot be created or written
+    pub fn new(
+        path: &PathBuf, interval: f64, start: &GpsTime, position: &Ecef,
+        leap_seconds: i32,
+    ) -> Result<Self, Error> {
+        let first_tow =
+            ((start.sec - EPOCH_TOLERANCE) / interval).ceil() * interval;
+        let first_epoch = start.add_secs(first_tow - start.sec);
+        let header = ObservationHeader {
+            program: "gpssim".to_string(),
+            marker_name: "GPSSIM".to_string(),
+            receiver_type: "SIMULATED".to_string(),
+            approx_position: [position.x, position.y, position.z],
+            interval,
+            first_epoch: observation_time(&first_epoch),
+            leap_seconds: Some(leap_seconds),
+            observation_types: vec![(
+                'G',
+                OBSERVATION_TYPES.iter().map(ToString::to_string).collect(),
+            )],
+            comments: vec![
+                "Ideal measurements of the simulated signal".to_string(),
+            ],
+            ..Default::default()
+        };
+        let writer = ObservationWriter::new(
+            BufWriter::new(File::create(path)?),
+            &header,
+        )?;
+        Ok(Self {
+            writer,
+            interval,
+            ambiguities: [None; MAX_SAT],
+        })
+    }
+
+    /// Records the channel states of one update step.
+    ///
+    /// Satellites missing from an update step lose their carrier phase
+    /// ambiguity. An epoch is written when the step falls on a multiple of
+    /// the observation interval.
+    ///
+    /// # Arguments
+    /// * `time` - GPS time of the update step
+    /// * `records` - One record per active channel
+    ///
+    /// # Returns
+    /// * `Ok(())` - If the step was recorded
+    /// * `Err(Error)` - If writing fails
+    ///
+    /// # Errors
+    /// * Returns an error if the file cannot be written
+    pub fn record_step(
+        &mut self, time: &GpsTime, records: &[TruthRecord],
+    ) -> Result<(), Error> {
+        for (sv, ambiguity) in self.ambiguities.iter_mut().enumerate() {
+            if !records.iter().any(|r| r.prn == sv + 1) {
+                *ambiguity = None;
+            }
+        }
+        let mut satellites = Vec::with_capacity(records.len());
+        for record in records {
+            let ambiguity = *self.ambiguities[record.prn - 1].get_or_insert(
+                (record.pseudorange * LAMBDA_L1_INV + record.carrier_phase)
+                    .round(),
+            );
+            let cn0 = if record.gain > 0 {
+                NOMINAL_CN0 + 20.0 * (f64::from(record.gain) / 128.0).log10()
+            } else {
+                0.0
+            };
+            satellites.push(SatelliteObservation {
+                system: 'G',
+                prn: record.prn,
+                values: vec![
+                    Some(record.pseudorange),
+                    Some(ambiguity - record.carrier_phase),
+                    Some(record.doppler),
+                    Some(cn0),
+                ],
+            });
+        }
+        let offset =
+            time.sec - (time.sec / self.interval).round() * self.interval;
+        if offset.abs() > EPOCH_TOLERANCE {
+            return Ok(());
+        }
+        satellites.sort_by_key(|sat| sat.prn);
+        self.writer.write_epoch(&ObservationEpoch {
+            time: observation_time(&time.add_secs(-offset)),
+            flag: 0,
+            satellites,
+        })?;
+        Ok(())
+    }
+
+    /// Flushes buffered epochs to the file.
+    ///
+    /// # Errors
+    /// * Returns an error if the file cannot be written
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Converts GPS time to the calendar epoch time of a RINEX file.
+///
+/// # Arguments
+/// * `time` - GPS time
+///
+/// # Returns
+/// The calendar date and time in the GPS time system
+fn observation_time(time: &GpsTime) -> ObservationTime {
+    let t = DateTime::from(time);
+    ObservationTime {
+        year: t.y,
+        month: t.m,
+        day: t.d,
+        hour: t.hh,
+        minute: t.mm,
+        second: t.sec,
+    }
+}
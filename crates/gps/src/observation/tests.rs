@@ -0,0 +1,181 @@
+use constants::LAMBDA_L1;
+use geometry::Ecef;
+
+use super::ObservationLogger;
+use crate::{Error, datetime::GpsTime, truth::TruthRecord};
+
+/// Thursday, December 30, 2021, 00:00:00 GPS time.
+const START: GpsTime = GpsTime {
+    week: 2190,
+    sec: 345_600.0,
+};
+
+/// Records update steps and returns the lines of the observation file.
+///
+/// # Arguments
+/// * `interval` - Observation interval in seconds
+/// * `steps` - Seconds since `START` and channel records of each step
+/// * `name` - Distinct part of the file name
+fn write_observations(
+    interval: f64, steps: &[(f64, Vec<TruthRecord>)], name: &str,
+) -> Result<Vec<String>, Error> {
+    let path = std::env::temp_dir()
+        .join(format!("observation-{}-{name}.obs", std::process::id()));
+    let (first, _) = steps.first().ok_or_else(|| Error::msg("No steps"))?;
+    let position = Ecef::new(-3_961_904.9, 3_348_993.8, 3_698_211.7);
+    let mut logger = ObservationLogger::new(
+        &path,
+        interval,
+        &START.add_secs(*first),
+        &position,
+        18,
+    )?;
+    for (elapsed, records) in steps {
+        logger.record_step(&START.add_secs(*elapsed), records)?;
+    }
+    logger.flush()?;
+    drop(logger);
+    let text = std::fs::read_to_string(&path)?;
+    std::fs::remove_file(&path)?;
+    Ok(text.lines().map(str::to_owned).collect())
+}
+
+/// Satellite ID and observation values of an epoch.
+type Satellites = Vec<(String, Vec<f64>)>;
+
+/// Returns the epoch lines and the values of each of their satellites.
+fn epochs(lines: &[String]) -> Vec<(String, Satellites)> {
+    let body = lines
+        .iter()
+        .skip_while(|line| !line.contains("END OF HEADER"))
+        .skip(1);
+    let mut epochs: Vec<(String, Satellites)> = Vec::new();
+    for line in body {
+        if line.starts_with('>') {
+            epochs.push((line.clone(), Vec::new()));
+        } else if let Some((_, satellites)) = epochs.last_mut() {
+            let values = line.as_bytes()[3..]
+                .chunks(16)
+                .filter_map(|field| std::str::from_utf8(field).ok())
+                .filter_map(|field| field.trim().parse().ok())
+                .collect();
+            satellites.push((line[..3].to_string(), values));
+        }
+    }
+    epochs
+}
+
+/// Returns the record of a channel.
+fn channel(
+    prn: usize, pseudorange: f64, carrier_phase: f64, doppler: f64, gain: i32,
+) -> TruthRecord {
+    TruthRecord {
+        prn,
+        pseudorange,
+        doppler,
+        carrier_phase,
+        gain,
+        ..TruthRecord::default()
+    }
+}
+
+#[test]
+fn epochs_aligned_to_interval() -> Result<(), Error> {
+    // Steps of 0.1 s from 0.3 s, accumulating rounding errors
+    let steps = (0..25)
+        .map(|i| {
+            let elapsed = 0.3 + f64::from(i) * 0.1;
+            (elapsed, vec![channel(5, 2.1e7, 0.0, 0.0, 128)])
+        })
+        .collect::<Vec<_>>();
+    let lines = write_observations(1.0, &steps, "aligned")?;
+    let first = lines
+        .iter()
+        .find(|line| line.contains("TIME OF FIRST OBS"))
+        .ok_or_else(|| Error::msg("No first epoch"))?;
+    assert!(first.starts_with("  2021    12    30     0     0    1.0000000"));
+    let epochs = epochs(&lines);
+    let times = epochs
+        .iter()
+        .map(|(line, _)| line.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(times, [
+        "> 2021 12 30 00 00  1.0000000  0  1",
+        "> 2021 12 30 00 00  2.0000000  0  1"
+    ]);
+    Ok(())
+}
+
+#[test]
+fn measurements_follow_channel_state() -> Result<(), Error> {
+    let (range, phase) = (21_234_567.891, 1_234.75);
+    let steps = [
+        (0.0, vec![
+            channel(5, range, phase, -1_500.25, 128),
+            channel(12, 2.2e7, -30.5, 250.0, 256),
+            channel(2, 2.4e7, 0.0, 0.0, 0),
+        ]),
+        (1.0, vec![
+            channel(5, range - 285.0, phase + 1_500.25, -1_500.25, 64),
+            channel(12, 2.2e7 + 47.6, -280.5, 250.0, 256),
+            channel(2, 2.4e7, 0.0, 0.0, 0),
+        ]),
+    ];
+    let lines = write_observations(1.0, &steps, "state")?;
+    let epochs = epochs(&lines);
+    assert_eq!(epochs.len(), 2);
+    let [(_, first), (_, second)] = epochs.as_slice() else {
+        panic!("Not two epochs");
+    };
+    // Satellites in PRN order
+    let prns = first.iter().map(|(id, _)| id.as_str()).collect::<Vec<_>>();
+    assert_eq!(prns, ["G02", "G05", "G12"]);
+    let values = |epoch: &[(String, Vec<f64>)], prn: &str| {
+        epoch
+            .iter()
+            .find(|(id, _)| id == prn)
+            .map(|(_, values)| values.clone())
+            .unwrap_or_default()
+    };
+    let (g05, g05_next) = (values(first, "G05"), values(second, "G05"));
+    assert_eq!(g05.len(), 4);
+    // C1C is the pseudorange and D1C the carrier Doppler
+    assert!((g05[0] - range).abs() < 1e-3);
+    assert!((g05[2] + 1_500.25).abs() < 1e-3);
+    // L1C starts within half a cycle of C1C, an integer number of cycles
+    // from the negated carrier phase of the signal
+    assert!((g05[1] - range / LAMBDA_L1).abs() <= 0.5);
+    assert!(((g05[1] + phase) - (g05[1] + phase).round()).abs() < 1e-3);
+    // With the same ambiguity, L1C decreases as the carrier phase advances
+    assert!((g05_next[1] - (g05[1] - 1_500.25)).abs() < 1e-3);
+    // S1C is 45 dB-Hz at unity gain, 6 dB per doubling, 0 without signal
+    assert!((g05[3] - 45.0).abs() < 1e-3);
+    assert!((g05_next[3] - 38.979).abs() < 1e-3);
+    assert!((values(first, "G12")[3] - 51.021).abs() < 1e-3);
+    assert!(values(first, "G02")[3].abs() < 1e-3);
+    Ok(())
+}
+
+#[test]
+fn ambiguity_reset_after_loss() -> Result<(), Error> {
+    let range = 2.1e7;
+    let steps = [
+        (0.0, vec![channel(7, range, 0.0, 0.0, 128)]),
+        (1.0, vec![channel(7, range, 100.0, 0.0, 128)]),
+        // Lost for one step, the carrier phase restarts
+        (1.1, Vec::new()),
+        (2.0, vec![channel(7, range, 5_000.3, 0.0, 128)]),
+    ];
+    let lines = write_observations(1.0, &steps, "reset")?;
+    let phases = epochs(&lines)
+        .iter()
+        .map(|(_, satellites)| {
+            satellites.first().map_or(f64::NAN, |(_, values)| values[1])
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(phases.len(), 3);
+    assert!((phases[1] - (phases[0] - 100.0)).abs() < 1e-3);
+    // A new ambiguity aligns L1C with C1C again
+    assert!((phases[2] - range / LAMBDA_L1).abs() <= 0.5);
+    Ok(())
+}
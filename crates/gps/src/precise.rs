@@ -0,0 +1,326 @@
+use constants::{MAX_SAT, SPEED_OF_LIGHT};
+use rinex::{
+    RinexClock, Sp3, navigation::SatelliteSystem, utc::gps_week_seconds,
+};
+
+use crate::{
+    datetime::GpsTime, ephemeris::Ephemeris, error::Error,
+    propagation::SatelliteState,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Number of SP3 samples of the Lagrange interpolation of positions (degree
+/// 9).
+const ORBIT_POINTS: usize = 10;
+
+/// Half of the time step used to differentiate interpolated positions, in
+/// seconds.
+const VELOCITY_STEP: f64 = 0.5;
+
+/// Samples closer in time than this are the same epoch, in seconds.
+const SAME_EPOCH: f64 = 1e-3;
+
+/// Precise satellite orbits and clocks read from SP3 and RINEX clock files.
+///
+/// Positions are interpolated with a Lagrange polynomial through the nearest
+/// `ORBIT_POINTS` samples, evaluated with Neville's algorithm. Velocities are
+/// central differences of the interpolated positions. Clock offsets, which
+/// behave like random walks rather than smooth functions, are interpolated
+/// linearly between the neighbouring samples. Clocks from RINEX clock files
+/// replace the SP3 clocks of a satellite.
+///
+/// SP3 positions refer to the satellite center of mass and are used as they
+/// are. SP3 clocks refer to the ionosphere-free signal combination; see
+/// `PreciseState` for the L1 C/A clock.
+#[derive(Clone)]
+pub struct PreciseOrbits {
+    /// Positions in meters of each satellite (index PRN - 1), sorted by time
+    orbits: Vec<Vec<(GpsTime, [f64; 3])>>,
+    /// Clock offsets in seconds from SP3 files, sorted by time
+    sp3_clocks: Vec<Vec<(GpsTime, f64)>>,
+    /// Clock offsets in seconds from RINEX clock files, sorted by time
+    clocks: Vec<Vec<(GpsTime, f64)>>,
+}
+
+impl Default for PreciseOrbits {
+    fn default() -> Self {
+        Self {
+            orbits: vec![Vec::new(); MAX_SAT],
+            sp3_clocks: vec![Vec::new(); MAX_SAT],
+            clocks: vec![Vec::new(); MAX_SAT],
+        }
+    }
+}
+
+impl PreciseOrbits {
+    /// Adds the GPS positions and clocks of an SP3 file.
+    ///
+    /// Epochs already present, e.g. at the boundary of daily files, are kept
+    /// once.
+    ///
+    /// # Arguments
+    /// * `sp3` - Parsed SP3 file
+    ///
+    /// # Errors
+    /// * `Error::UnsupportedTimeSystem` - If the epochs are not in GPS time
+    pub fn insert_sp3(&mut self, sp3: &Sp3) -> Result<(), Error> {
+        check_time_system(&sp3.time_system)?;
+        for epoch in &sp3.epochs {
+            let (week, sec) = gps_week_seconds(&epoch.time);
+            let time = GpsTime { week, sec };
+            for record in &epoch.positions {
+                let Some(sv) =
+                    gps_index(record.satellite.system, record.satellite.prn)
+                else {
+                    continue;
+                };
+                let position = record.position.map(|km| km * 1000.0);
+                insert_sorted(&mut self.orbits[sv], &time, position);
+                if let Some(clock) = record.clock {
+                    insert_sorted(
+                        &mut self.sp3_clocks[sv],
+                        &time,
+                        clock * 1e-6,
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds the GPS satellite clocks of a RINEX clock file.
+    ///
+    /// # Arguments
+    /// * `clock` - Parsed RINEX clock file
+    ///
+    /// # Errors
+    /// * `Error::UnsupportedTimeSystem` - If the epochs are not in GPS time
+    pub fn insert_clock(&mut self, clock: &RinexClock) -> Result<(), Error> {
+        check_time_system(&clock.time_system)?;
+        for record in &clock.records {
+            let Some(sv) =
+                gps_index(record.satellite.system, record.satellite.prn)
+            else {
+                continue;
+            };
+            let (week, sec) = gps_week_seconds(&record.epoch);
+            insert_sorted(
+                &mut self.clocks[sv],
+                &GpsTime { week, sec },
+                record.bias,
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns whether any satellite has orbit samples around a time.
+    ///
+    /// # Arguments
+    /// * `time` - GPS time to check
+    pub fn covers(&self, time: &GpsTime) -> bool {
+        self.orbits.iter().any(|samples| {
+            matches!(
+                (samples.first(), samples.last()),
+                (Some((first, _)), Some((last, _)))
+                    if time.diff_secs(first) >= 0.0 && last.diff_secs(time) >= 0.0
+            )
+        })
+    }
+
+    /// Interpolates the state of a satellite.
+    ///
+    /// The clock offset includes the relativistic correction for the orbit
+    /// eccentricity, -2 r·v / c², which precise clocks leave out and
+    /// broadcast clocks include.
+    ///
+    /// # Arguments
+    /// * `prn` - PRN number of the satellite
+    /// * `time` - GPS time of the state
+    ///
+    /// # Returns
+    /// * `Some((position, velocity, clock))` - In the units of
+    ///   `Ephemeris::compute_satellite_state`
+    /// * `None` - If the orbit or clock samples do not cover the time
+    pub fn satellite_state(
+        &self, prn: usize, time: &GpsTime,
+    ) -> Option<([f64; 3], [f64; 3], [f64; 2])> {
+        let sv = prn.checked_sub(1).filter(|&sv| sv < MAX_SAT)?;
+        let position = interpolate_orbit(&self.orbits[sv], time, 0.0)?;
+        let before = interpolate_orbit(&self.orbits[sv], time, -VELOCITY_STEP)?;
+        let after = interpolate_orbit(&self.orbits[sv], time, VELOCITY_STEP)?;
+        let velocity: [f64; 3] = std::array::from_fn(|i| {
+            (after[i] - before[i]) / (2.0 * VELOCITY_STEP)
+        });
+        let clocks = if self.clocks[sv].is_empty() {
+            &self.sp3_clocks[sv]
+        } else {
+            &self.clocks[sv]
+        };
+        let [bias, drift] = interpolate_clock(clocks, time)?;
+        let radial: f64 = (0..3).map(|i| position[i] * velocity[i]).sum();
+        let relativistic = -2.0 * radial / (SPEED_OF_LIGHT * SPEED_OF_LIGHT);
+        Some((position, velocity, [bias + relativistic, drift]))
+    }
+}
+
+/// Satellite state from precise orbits, falling back to the broadcast
+/// ephemeris for satellites or times the precise orbits do not cover.
+///
+/// The broadcast group delay (TGD) is subtracted from the precise clock, as
+/// from the broadcast clock, to give the clock of the L1 C/A signal.
+pub struct PreciseState<'a> {
+    /// Precise orbits, if loaded
+    orbits: Option<&'a PreciseOrbits>,
+    /// PRN number of the satellite
+    prn: usize,
+    /// Broadcast ephemeris of the satellite
+    broadcast: &'a Ephemeris,
+}
+
+impl<'a> PreciseState<'a> {
+    /// Creates the state source of a satellite.
+    ///
+    /// # Arguments
+    /// * `orbits` - Precise orbits, or `None` to use the broadcast ephemeris
+    /// * `prn` - PRN number of the satellite
+    /// * `broadcast` - Broadcast ephemeris of the satellite
+    pub fn new(
+        orbits: Option<&'a PreciseOrbits>, prn: usize, broadcast: &'a Ephemeris,
+    ) -> Self {
+        Self {
+            orbits,
+            prn,
+            broadcast,
+        }
+    }
+}
+
+impl SatelliteState for PreciseState<'_> {
+    fn satellite_state(
+        &self, time: &GpsTime,
+    ) -> ([f64; 3], [f64; 3], [f64; 2]) {
+        self.orbits
+            .and_then(|orbits| orbits.satellite_state(self.prn, time))
+            .map_or_else(
+                || self.broadcast.compute_satellite_state(time),
+                |(pos, vel, [bias, drift])| {
+                    (pos, vel, [bias - self.broadcast.tgd, drift])
+                },
+            )
+    }
+}
+
+/// Checks that precise products are given in GPS time.
+///
+/// # Errors
+/// * `Error::UnsupportedTimeSystem` - If the time system is not GPS
+fn check_time_system(time_system: &str) -> Result<(), Error> {
+    if time_system == "GPS" {
+        Ok(())
+    } else {
+        Err(Error::unsupported_time_system(time_system))
+    }
+}
+
+/// Returns the array index of a GPS satellite.
+///
+/// # Returns
+/// * `Some(index)` - PRN - 1 for GPS satellites
+/// * `None` - For other systems or PRNs out of range
+fn gps_index(system: SatelliteSystem, prn: usize) -> Option<usize> {
+    (system == SatelliteSystem::Gps && (1..=MAX_SAT).contains(&prn))
+        .then(|| prn - 1)
+}
+
+/// Inserts a sample into a list sorted by time, keeping the first sample of
+/// each epoch.
+fn insert_sorted<T>(samples: &mut Vec<(GpsTime, T)>, time: &GpsTime, value: T) {
+    let index =
+        samples.partition_point(|(t, _)| t.diff_secs(time) < -SAME_EPOCH);
+    if samples
+        .get(index)
+        .is_none_or(|(t, _)| t.diff_secs(time) > SAME_EPOCH)
+    {
+        samples.insert(index, (time.clone(), value));
+    }
+}
+
+/// Interpolates a position at an offset from a time.
+///
+/// The samples around the time are taken so that the time lies in the middle
+/// interval where possible. No position is returned outside the samples or
+/// across a gap of more than twice the sampling interval.
+///
+/// # Arguments
+/// * `samples` - Positions sorted by time
+/// * `time` - GPS time
+/// * `offset` - Offset from `time` in seconds
+fn interpolate_orbit(
+    samples: &[(GpsTime, [f64; 3])], time: &GpsTime, offset: f64,
+) -> Option<[f64; 3]> {
+    let relative = |t: &GpsTime| t.diff_secs(time) - offset;
+    let after = samples.partition_point(|(t, _)| relative(t) <= 0.0);
+    let (last, _) = samples.last()?;
+    if after == 0 || relative(last) < -SAME_EPOCH {
+        return None;
+    }
+    let start = after
+        .saturating_sub(ORBIT_POINTS / 2)
+        .min(samples.len().saturating_sub(ORBIT_POINTS));
+    let window = &samples[start..(start + ORBIT_POINTS).min(samples.len())];
+    let times: Vec<f64> = window.iter().map(|(t, _)| relative(t)).collect();
+    let spacing = times
+        .windows(2)
+        .map(|pair| pair[1] - pair[0])
+        .fold(f64::INFINITY, f64::min);
+    if let Some((next, _)) = samples.get(after)
+        && next.diff_secs(&samples[after - 1].0) > 2.0 * spacing
+    {
+        return None;
+    }
+    Some(std::array::from_fn(|axis| {
+        let values: Vec<f64> = window.iter().map(|(_, p)| p[axis]).collect();
+        neville(&times, &values)
+    }))
+}
+
+/// Evaluates the polynomial through a set of points at zero with Neville's
+/// algorithm.
+///
+/// # Arguments
+/// * `times` - Abscissae of the points, relative to the evaluation time
+/// * `values` - Values at the points
+fn neville(times: &[f64], values: &[f64]) -> f64 {
+    let mut p = values.to_vec();
+    for level in 1..p.len() {
+        for i in 0..p.len() - level {
+            let (t0, t1) = (times[i], times[i + level]);
+            p[i] = (t1 * p[i] - t0 * p[i + 1]) / (t1 - t0);
+        }
+    }
+    p[0]
+}
+
+/// Interpolates a clock offset and drift linearly.
+///
+/// # Arguments
+/// * `samples` - Clock offsets sorted by time
+/// * `time` - GPS time
+///
+/// # Returns
+/// * `Some([bias, drift])` - Offset in seconds and drift in seconds per second
+/// * `None` - If the samples do not cover the time
+fn interpolate_clock(
+    samples: &[(GpsTime, f64)], time: &GpsTime,
+) -> Option<[f64; 2]> {
+    let after = samples.partition_point(|(t, _)| t.diff_secs(time) <= 0.0);
+    let (t0, c0) = samples.get(after.checked_sub(1)?)?;
+    let Some((t1, c1)) = samples.get(after) else {
+        // Exactly at the last sample
+        return (time.diff_secs(t0).abs() < SAME_EPOCH).then_some([*c0, 0.0]);
+    };
+    let drift = (c1 - c0) / t1.diff_secs(t0);
+    Some([c0 + drift * time.diff_secs(t0), drift])
+}
@@ -0,0 +1,238 @@
+use constants::SPEED_OF_LIGHT;
+use test_case::test_case;
+
+use super::{
+    ORBIT_POINTS, PreciseOrbits, PreciseState, interpolate_clock,
+    interpolate_orbit, neville,
+};
+use crate::{
+    datetime::GpsTime, ephemeris::Ephemeris, propagation::SatelliteState,
+};
+
+/// Interval of the orbit samples in seconds, as in SP3 files.
+const INTERVAL: f64 = 900.0;
+
+/// Number of orbit samples of the tests.
+const SAMPLES: usize = 16;
+
+/// Time of the first sample.
+fn start() -> GpsTime {
+    GpsTime {
+        week: 2190,
+        sec: 518_400.0,
+    }
+}
+
+/// Evaluates a polynomial of degree 9 on each axis, in hours from the start.
+fn polynomial(time: &GpsTime) -> [f64; 3] {
+    let hours = time.diff_secs(&start()) / 3600.0;
+    std::array::from_fn(|axis| {
+        (0..ORBIT_POINTS).rev().fold(0.0, |sum, power| {
+            let coefficient = 1e7 / (power + axis + 1) as f64;
+            sum * hours
+                + if power.is_multiple_of(2) {
+                    coefficient
+                } else {
+                    -coefficient
+                }
+        })
+    })
+}
+
+/// Returns orbit samples of a function every `INTERVAL` seconds.
+fn samples(
+    position: impl Fn(&GpsTime) -> [f64; 3],
+) -> Vec<(GpsTime, [f64; 3])> {
+    (0..SAMPLES)
+        .map(|i| {
+            let time = start().add_secs(i as f64 * INTERVAL);
+            let value = position(&time);
+            (time, value)
+        })
+        .collect()
+}
+
+/// Returns the largest difference between two vectors.
+fn difference(a: [f64; 3], b: [f64; 3]) -> f64 {
+    (0..3).map(|i| (a[i] - b[i]).abs()).fold(0.0, f64::max)
+}
+
+#[test]
+fn neville_exact_polynomial() {
+    let times = [-4.5, -3.5, -2.5, -1.5, -0.5, 0.5, 1.5, 2.5, 3.5, 4.5];
+    let values = times.map(|t: f64| 3.0 - 2.0 * t + 0.5 * t.powi(9));
+    assert!((neville(&times, &values) - 3.0).abs() < 1e-9);
+    // A single point is a constant
+    assert!((neville(&[2.0], &[7.0]) - 7.0).abs() < 1e-12);
+}
+
+// In the middle, and near both ends of the samples where the window of
+// ORBIT_POINTS samples is clamped to the first or last ones
+#[test_case(7.0 * INTERVAL + 123.0, 0.0; "middle")]
+#[test_case(7.0 * INTERVAL, 0.0; "at a sample")]
+#[test_case(200.0, 0.0; "first interval")]
+#[test_case(0.0, 0.0; "first sample")]
+#[test_case(14.0 * INTERVAL + 700.0, 0.0; "last interval")]
+#[test_case(15.0 * INTERVAL, 0.0; "last sample")]
+#[test_case(100.0, 0.5; "offset")]
+#[test_case(15.0 * INTERVAL + 0.5, -0.5; "negative offset")]
+fn orbit_exact_fit(elapsed: f64, offset: f64) -> Result<(), crate::Error> {
+    let samples = samples(polynomial);
+    let time = start().add_secs(elapsed);
+    let position = interpolate_orbit(&samples, &time, offset)
+        .ok_or_else(|| crate::Error::msg("No interpolated position"))?;
+    let expected = polynomial(&time.add_secs(offset));
+    assert!(difference(position, expected) < 1e-3, "{position:?}");
+    Ok(())
+}
+
+#[test_case(-1.0, 0.0; "before the first sample")]
+#[test_case(15.0 * INTERVAL + 1.0, 0.0; "after the last sample")]
+#[test_case(0.0, -1.0; "offset before the first sample")]
+#[test_case(15.0 * INTERVAL, 1.0; "offset after the last sample")]
+fn orbit_outside_samples(elapsed: f64, offset: f64) {
+    let samples = samples(polynomial);
+    let time = start().add_secs(elapsed);
+    assert!(interpolate_orbit(&samples, &time, offset).is_none());
+    assert!(interpolate_orbit(&[], &time, offset).is_none());
+}
+
+#[test]
+fn orbit_gap() {
+    // Samples 7 and 8 missing: 45 minutes between samples 6 and 9
+    let mut samples = samples(polynomial);
+    samples.drain(7..9);
+    let inside = |elapsed: f64| {
+        interpolate_orbit(&samples, &start().add_secs(elapsed), 0.0)
+    };
+    assert!(inside(6.0 * INTERVAL + 1.0).is_none());
+    assert!(inside(8.5 * INTERVAL).is_none());
+    assert!(inside(6.0 * INTERVAL - 1.0).is_some());
+    assert!(inside(9.0 * INTERVAL + 1.0).is_some());
+}
+
+#[test]
+fn clock_linear_interpolation() -> Result<(), crate::Error> {
+    let samples = [(0.0, 1e-4), (30.0, 1.3e-4), (60.0, 1.2e-4)]
+        .map(|(elapsed, bias)| (start().add_secs(elapsed), bias));
+    let at =
+        |elapsed: f64| interpolate_clock(&samples, &start().add_secs(elapsed));
+    let [bias, drift] =
+        at(45.0).ok_or_else(|| crate::Error::msg("No clock at 45 s"))?;
+    assert!((bias - 1.25e-4).abs() < 1e-15);
+    assert!((drift + 1e-5 / 30.0).abs() < 1e-18);
+    let [bias, drift] =
+        at(10.0).ok_or_else(|| crate::Error::msg("No clock at 10 s"))?;
+    assert!((bias - 1.1e-4).abs() < 1e-15);
+    assert!((drift - 1e-6).abs() < 1e-18);
+    // The last sample has no drift
+    let [bias, drift] =
+        at(60.0).ok_or_else(|| crate::Error::msg("No clock at 60 s"))?;
+    assert!((bias - 1.2e-4).abs() < 1e-15 && drift.abs() < 1e-18);
+    assert!(at(-1.0).is_none());
+    assert!(at(61.0).is_none());
+    Ok(())
+}
+
+/// Position of a satellite moving on a parabola, whose velocity the central
+/// differences give exactly.
+fn parabola(time: &GpsTime) -> [f64; 3] {
+    let t = time.diff_secs(&start());
+    [
+        2.0e7 + 1000.0 * t + 0.01 * t * t,
+        -1.5e7 - 2500.0 * t,
+        1.0e7 + 3000.0 * t - 0.02 * t * t,
+    ]
+}
+
+/// Returns precise orbits of PRN 5 on the parabola, with SP3 clocks of 10
+/// microseconds and, if `clk` is set, RINEX clocks of 20 microseconds.
+fn precise_orbits(clk: bool) -> PreciseOrbits {
+    let mut orbits = PreciseOrbits::default();
+    orbits.orbits[4] = samples(parabola);
+    orbits.sp3_clocks[4] = (0..SAMPLES)
+        .map(|i| (start().add_secs(i as f64 * INTERVAL), 1e-5))
+        .collect();
+    if clk {
+        orbits.clocks[4] = (0..=SAMPLES * 30)
+            .map(|i| (start().add_secs(i as f64 * 30.0), 2e-5))
+            .collect();
+    }
+    orbits
+}
+
+#[test_case(false, 1e-5; "sp3 clock")]
+#[test_case(true, 2e-5; "rinex clock")]
+fn satellite_state(clk: bool, clock: f64) -> Result<(), crate::Error> {
+    let orbits = precise_orbits(clk);
+    let time = start().add_secs(3000.0);
+    let (position, velocity, [bias, drift]) = orbits
+        .satellite_state(5, &time)
+        .ok_or_else(|| crate::Error::msg("No precise state"))?;
+    assert!(difference(position, parabola(&time)) < 1e-3);
+    let expected = [1000.0 + 0.02 * 3000.0, -2500.0, 3000.0 - 0.04 * 3000.0];
+    assert!(difference(velocity, expected) < 1e-6, "{velocity:?}");
+    // The relativistic correction is added to the precise clock
+    let radial: f64 = (0..3).map(|i| position[i] * velocity[i]).sum();
+    let relativistic = -2.0 * radial / (SPEED_OF_LIGHT * SPEED_OF_LIGHT);
+    assert!(relativistic.abs() > 1e-9);
+    assert!((bias - clock - relativistic).abs() < 1e-15);
+    assert!(drift.abs() < 1e-18);
+    // Other satellites, times outside the orbits or PRNs out of range
+    assert!(orbits.satellite_state(6, &time).is_none());
+    assert!(orbits.satellite_state(5, &start().add_secs(-1.0)).is_none());
+    assert!(orbits.satellite_state(0, &time).is_none());
+    assert!(orbits.satellite_state(33, &time).is_none());
+    Ok(())
+}
+
+/// Returns a broadcast ephemeris of a circular orbit with a group delay.
+fn broadcast() -> Ephemeris {
+    let radius: f64 = 26_560e3;
+    Ephemeris {
+        vflg: true,
+        toc: start(),
+        toe: start(),
+        sqrta: radius.sqrt(),
+        A: radius,
+        n: (3.986_005e14 / radius.powi(3)).sqrt(),
+        sq1e2: 1.0,
+        af0: 3e-5,
+        tgd: 5e-9,
+        ..Ephemeris::default()
+    }
+}
+
+#[test]
+fn precise_state_subtracts_group_delay() {
+    let orbits = precise_orbits(true);
+    let eph = broadcast();
+    let time = start().add_secs(3000.0);
+    let precise = orbits.satellite_state(5, &time);
+    let state =
+        PreciseState::new(Some(&orbits), 5, &eph).satellite_state(&time);
+    let Some((position, _, [bias, _])) = precise else {
+        panic!("No precise state");
+    };
+    assert!(difference(state.0, position) < 1e-9);
+    assert!((state.2[0] - (bias - 5e-9)).abs() < 1e-18);
+}
+
+#[test_case(false, 5, 3000.0; "no precise orbits")]
+#[test_case(true, 6, 3000.0; "other satellite")]
+#[test_case(true, 5, -60.0; "before the orbits")]
+#[test_case(true, 5, 16.0 * INTERVAL; "after the orbits")]
+fn precise_state_falls_back_to_broadcast(
+    loaded: bool, prn: usize, elapsed: f64,
+) {
+    let orbits = precise_orbits(false);
+    let eph = broadcast();
+    let time = start().add_secs(elapsed);
+    let state = PreciseState::new(loaded.then_some(&orbits), prn, &eph)
+        .satellite_state(&time);
+    let (position, velocity, clock) = eph.compute_satellite_state(&time);
+    assert!(difference(state.0, position) < 1e-9);
+    assert!(difference(state.1, velocity) < 1e-12);
+    assert!((state.2[0] - clock[0]).abs() < 1e-18);
+    assert!((state.2[1] - clock[1]).abs() < 1e-21);
+}
@@ -8,6 +8,33 @@ use crate::{
     ionoutc::IonoUtc,
 };
 
+/// Source of the satellite position, velocity and clock used to compute
+/// ranges.
+///
+/// The broadcast ephemeris is the default source. Precise orbits can stand in
+/// for it so that the simulated ranges follow the true orbit while the
+/// navigation message still carries the broadcast one.
+pub trait SatelliteState {
+    /// Computes the satellite state at a time.
+    ///
+    /// # Arguments
+    /// * `time` - GPS time of the state
+    ///
+    /// # Returns
+    /// Position, velocity and clock correction as returned by
+    /// `Ephemeris::compute_satellite_state`
+    fn satellite_state(&self, time: &GpsTime)
+    -> ([f64; 3], [f64; 3], [f64; 2]);
+}
+
+impl SatelliteState for Ephemeris {
+    fn satellite_state(
+        &self, time: &GpsTime,
+    ) -> ([f64; 3], [f64; 3], [f64; 2]) {
+        self.compute_satellite_state(time)
+    }
+}
+
 /// Computes the range between a satellite and the receiver.
 ///
 /// This function calculates the pseudorange, geometric distance, range rate,
@@ -31,7 +58,7 @@ use crate::{
 /// 9. Add ionospheric delay
 ///
 /// # Arguments
-/// * `eph` - Ephemeris data of the satellite
+/// * `state` - Source of the satellite state, usually its ephemeris
 /// * `ionoutc` - Ionospheric and UTC parameters
 /// * `time` - GPS time at the moment of signal reception
 /// * `xyz` - Position of the receiver in ECEF coordinates
@@ -39,11 +66,11 @@ use crate::{
 /// # Returns
 /// A `TimeRange` structure containing the computed range information
 pub fn compute_range(
-    eph: &Ephemeris, ionoutc: &IonoUtc, time: &GpsTime, xyz: &Ecef,
+    state: &impl SatelliteState, ionoutc: &IonoUtc, time: &GpsTime, xyz: &Ecef,
 ) -> TimeRange {
     let mut rho = TimeRange::default();
     // SV position at time of the pseudorange observation.
-    let (mut pos, vel, clk) = eph.compute_satellite_state(time);
+    let (mut pos, vel, clk) = state.satellite_state(time);
     // Receiver to satellite vector and light-time.
     let los = Ecef::from(&pos) - xyz;
 
@@ -61,10 +88,12 @@ pub fn compute_range(
     // sub_vect(&mut los, &pos, xyz);
     let range = los.norm();
     rho.distance = range;
+    rho.position = Ecef::from(&pos);
     // Pseudorange.
     rho.range = range - SPEED_OF_LIGHT * clk[0];
     // Relative velocity of SV and receiver.
     let vel = Ecef::from(&vel);
+    rho.velocity = vel;
     let rate = vel.dot_prod(&los) / range;
     // Pseudorange rate.
     rho.rate = rate; // - SPEED_OF_LIGHT*clk[1];
@@ -0,0 +1,350 @@
+use constants::{CARR_FREQ, CODE_FREQ};
+use rinex::navigation::{NavRecord, SatelliteSystem};
+
+use crate::{
+    Error,
+    channel::Channel,
+    datetime::{DateTime, GpsTime},
+    ephemeris::Ephemeris,
+    generator::{SatelliteSelection, set_derived_constants, ura_index},
+    ionoutc::IonoUtc,
+    propagation::compute_range,
+    signal::{
+        SignalSystem, SystemChannel, SystemContext, SystemNavigation,
+        allocate_channels, band_offset, is_visible, print_channel_status,
+        signal_gain,
+    },
+    timeline::EphemerisTimeline,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Number of QZSS satellites with L1 C/A codes, PRN 193 to 202.
+const QZSS_SATELLITES: usize = 10;
+
+/// Offset of the QZSS PRN numbers from the satellite numbers of RINEX files
+/// (J01 is PRN 193).
+const PRN_OFFSET: usize = 192;
+
+/// QZSS L1 C/A signals.
+///
+/// The QZSS satellites with PRN 193 to 202 transmit the same L1 C/A signal
+/// and LNAV message as GPS satellites, with their own C/A codes. QZSS time is
+/// aligned with GPS time, and the orbits use the GPS constants, so the
+/// satellites are simulated by GPS channels from their RINEX 3 or RINEX 4
+/// ephemerides. The navigation message broadcasts the QZSS Klobuchar
+/// parameters of the navigation files if given, while the ranges are delayed
+/// by the GPS model.
+pub struct QzssSystem {
+    /// Broadcast ephemerides of all QZSS satellites (index PRN - 193)
+    timeline: EphemerisTimeline,
+    /// Ephemeris in use of each satellite (index PRN - 193)
+    ephemerides: Vec<Option<Ephemeris>>,
+    /// Klobuchar alpha parameters of the navigation files, if given
+    alpha: Option<[f64; 4]>,
+    /// Klobuchar beta parameters of the navigation files, if given
+    beta: Option<[f64; 4]>,
+    /// Satellites allowed to occupy a channel
+    selection: SatelliteSelection,
+    /// L1 carrier frequency minus the output centre frequency, or `None` if
+    /// L1 lies outside the output band
+    carrier_offset: Option<f64>,
+    /// Channels of the visible satellites with their signal gains
+    channels: Vec<(Channel, i32)>,
+}
+
+impl QzssSystem {
+    /// Creates the QZSS system from the navigation data.
+    ///
+    /// # Arguments
+    /// * `navigation` - Navigation data of the systems other than GPS
+    /// * `selection` - Satellites allowed to occupy a channel, without PRN
+    ///   lists
+    ///
+    /// # Returns
+    /// * `Ok(QzssSystem)` - The system ready to be initialized
+    /// * `Err(Error)` - If there is no usable QZSS ephemeris
+    ///
+    /// # Errors
+    /// * `Error::NoSystemEphemeris` - If the navigation data holds no ephemeris
+    ///   of a satellite with PRN 193 to 202
+    /// * Returns an error if the time of clock of a record is out of range
+    pub fn new(
+        navigation: &SystemNavigation, selection: SatelliteSelection,
+    ) -> Result<Self, Error> {
+        let mut timeline = EphemerisTimeline::new(QZSS_SATELLITES);
+        for record in navigation.ephemerides(SatelliteSystem::Qzss) {
+            let sv = record.satellite.prn;
+            if sv <= QZSS_SATELLITES {
+                let mut eph = convert_qzss_record(record)?;
+                eph.svhlth = selection.health_mode.broadcast_health(
+                    sv + PRN_OFFSET,
+                    eph.svhlth,
+                    &[],
+                );
+                timeline.insert(sv, eph);
+            }
+        }
+        if timeline.is_empty() {
+            return Err(Error::no_system_ephemeris("QZSS"));
+        }
+        let klobuchar = |kind: &str| {
+            navigation
+                .ionospheric_correction(kind)
+                .map(|correction| correction.values)
+        };
+        Ok(Self {
+            timeline,
+            ephemerides: vec![None; QZSS_SATELLITES],
+            alpha: klobuchar("QZSA"),
+            beta: klobuchar("QZSB"),
+            selection,
+            carrier_offset: None,
+            channels: Vec::new(),
+        })
+    }
+
+    /// Returns the ionospheric and UTC parameters of the navigation message,
+    /// with the QZSS Klobuchar parameters if given.
+    fn broadcast_ionoutc(&self, ionoutc: &IonoUtc) -> IonoUtc {
+        let mut broadcast = ionoutc.clone();
+        if let Some(alpha) = self.alpha {
+            [
+                broadcast.alpha0,
+                broadcast.alpha1,
+                broadcast.alpha2,
+                broadcast.alpha3,
+            ] = alpha;
+        }
+        if let Some(beta) = self.beta {
+            [
+                broadcast.beta0,
+                broadcast.beta1,
+                broadcast.beta2,
+                broadcast.beta3,
+            ] = beta;
+        }
+        broadcast
+    }
+
+    /// Takes the ephemeris due at a time into use for every satellite.
+    ///
+    /// The channels of satellites whose ephemeris changes switch to the new
+    /// one, as GPS channels do.
+    fn select_ephemerides(&mut self, context: &SystemContext) {
+        let ionoutc = self.broadcast_ionoutc(context.ionoutc);
+        for (sv, current) in self.ephemerides.iter_mut().enumerate() {
+            let Some(eph) = self.timeline.select(sv + 1, context.time) else {
+                continue;
+            };
+            let previous = current.replace(eph.clone());
+            let Some(previous) = previous else {
+                continue;
+            };
+            if previous.same_data_set(eph) {
+                continue;
+            }
+            let prn = sv + 1 + PRN_OFFSET;
+            if let Some((channel, _)) = self
+                .channels
+                .iter_mut()
+                .find(|(channel, _)| channel.prn == prn)
+            {
+                channel.switch_ephemeris(
+                    eph,
+                    &previous,
+                    eph,
+                    &ionoutc,
+                    context.time,
+                    context.location,
+                );
+            }
+        }
+    }
+
+    /// Releases the channels of satellites below the elevation mask and
+    /// allocates channels to the newly visible satellites.
+    fn allocate_channels(&mut self, context: &SystemContext) {
+        let Some(carrier_offset) = self.carrier_offset else {
+            return;
+        };
+        let ionoutc = self.broadcast_ionoutc(context.ionoutc);
+        allocate_channels(
+            &mut self.channels,
+            &self.ephemerides,
+            PRN_OFFSET + 1,
+            &self.selection,
+            |_, eph| is_visible(eph, context),
+            |prn, eph| {
+                let (azel, _) = eph.check_visibility(
+                    context.time,
+                    context.location,
+                    context.elevation_mask,
+                )?;
+                let mut channel = Channel::default();
+                channel.update_for_satellite(
+                    prn,
+                    eph,
+                    eph,
+                    &ionoutc,
+                    context.time,
+                    context.location,
+                    azel,
+                    None,
+                );
+                channel.set_carrier_offset(carrier_offset);
+                Some((channel, 0))
+            },
+        );
+    }
+}
+
+impl SystemChannel for (Channel, i32) {
+    fn number(&self) -> usize {
+        self.0.prn
+    }
+}
+
+impl SignalSystem for QzssSystem {
+    fn name(&self) -> &'static str {
+        "QZSS"
+    }
+
+    fn carrier_frequency(&self) -> f64 {
+        CARR_FREQ
+    }
+
+    fn initialize(&mut self, context: &SystemContext) {
+        self.carrier_offset = band_offset(context, CARR_FREQ, CODE_FREQ);
+        if self.carrier_offset.is_none() {
+            eprintln!(
+                "Warning: QZSS L1 C/A lies outside the output band; QZSS \
+                 satellites are not simulated."
+            );
+        }
+        self.select_ephemerides(context);
+        self.allocate_channels(context);
+    }
+
+    fn update_channels(&mut self, context: &SystemContext) {
+        for (channel, gain) in &mut self.channels {
+            let Some(eph) = &self.ephemerides[channel.prn - PRN_OFFSET - 1]
+            else {
+                continue;
+            };
+            let mut rho = compute_range(
+                eph,
+                context.ionoutc,
+                context.time,
+                context.location,
+            );
+            // Keep the range continuous after an ephemeris switch
+            rho.range += channel.ephemeris_offset(context.time);
+            channel.update_state(&rho, context.step, context.sampling_period);
+            *gain =
+                signal_gain(&rho, context.fixed_gain, context.antenna_pattern);
+        }
+    }
+
+    fn update_navigation(&mut self, context: &SystemContext) {
+        self.select_ephemerides(context);
+        for (channel, _) in &mut self.channels {
+            channel.generate_nav_msg(context.time, false, None);
+        }
+        self.allocate_channels(context);
+    }
+
+    fn next_sample(&mut self, sampling_period: f64) -> (i32, i32) {
+        self.channels.iter_mut().fold(
+            (0, 0),
+            |(i_acc, q_acc), (channel, gain)| {
+                let (ip, qp) = channel.generate_iq_contribution(*gain);
+                channel.update_navigation_bits(sampling_period);
+                (i_acc + ip, q_acc + qp)
+            },
+        )
+    }
+
+    fn print_status(&self) {
+        for (channel, _) in &self.channels {
+            print_channel_status(
+                &format!("J{:02}", channel.prn - PRN_OFFSET),
+                channel.azel(),
+                channel.rho0(),
+                None,
+            );
+        }
+    }
+}
+
+/// Converts a QZSS RINEX ephemeris record into the ephemeris used by the
+/// simulation, with its derived orbit constants.
+///
+/// The record has the layout of a GPS LNAV record, except that the last
+/// field is the fit interval flag: 0 for a fit interval of two hours,
+/// otherwise more than two hours, taken as four.
+///
+/// # Arguments
+/// * `record` - QZSS ephemeris record
+///
+/// # Returns
+/// * `Ok(Ephemeris)` - The converted ephemeris
+/// * `Err(Error)` - If the time of clock cannot be converted
+///
+/// # Errors
+/// * Returns an error if the time of clock is out of range
+fn convert_qzss_record(record: &NavRecord) -> Result<Ephemeris, Error> {
+    let datetime = DateTime::from(record.epoch.in_tz("UTC")?);
+    let toc = GpsTime::from(&datetime);
+    let [iode, crs, deltan, m0] = record.orbit(1);
+    let [cuc, ecc, cus, sqrta] = record.orbit(2);
+    let [toe, cic, omg0, cis] = record.orbit(3);
+    let [inc0, crc, aop, omgdot] = record.orbit(4);
+    let [idot, code_l2, week, l2p_flag] = record.orbit(5);
+    let [accuracy, health, tgd, iodc] = record.orbit(6);
+    let [tom, fit_flag, _, _] = record.orbit(7);
+    let mut svhlth = health as i32;
+    if svhlth > 0 && svhlth < 32 {
+        svhlth += 32;
+    }
+    let mut eph = Ephemeris {
+        vflg: true,
+        t: datetime,
+        toc,
+        toe: GpsTime {
+            week: week as i32,
+            sec: toe,
+        },
+        iodc: iodc as i32,
+        iode: iode as i32,
+        deltan,
+        cuc,
+        cus,
+        cic,
+        cis,
+        crc,
+        crs,
+        ecc,
+        sqrta,
+        m0,
+        omg0,
+        inc0,
+        aop,
+        omgdot,
+        idot,
+        af0: record.values[0],
+        af1: record.values[1],
+        af2: record.values[2],
+        tgd,
+        svhlth,
+        codeL2: code_l2 as i32,
+        l2p_flag: l2p_flag as i32,
+        ura: ura_index(accuracy),
+        fit_interval: if fit_flag == 0.0 { 2.0 } else { 4.0 },
+        tom,
+        ..Ephemeris::default()
+    };
+    set_derived_constants(&mut eph);
+    Ok(eph)
+}
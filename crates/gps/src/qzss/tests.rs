@@ -0,0 +1,154 @@
+use jiff::civil::date;
+use rinex::navigation::{
+    NavRecord, NavRecordKind, SatelliteId, SatelliteSystem,
+};
+use test_case::test_case;
+
+use super::{PRN_OFFSET, QZSS_SATELLITES, QzssSystem};
+use crate::{
+    Error,
+    channel::ca_code,
+    datetime::GpsTime,
+    generator::{HealthMode, SatelliteSelection},
+    signal::SystemNavigation,
+};
+
+/// G2 delays of the C/A codes of QZSS PRN 193 to 202 (IS-QZSS-PNT).
+const QZSS_DELAYS: [usize; 10] =
+    [339, 208, 711, 189, 263, 537, 663, 942, 173, 900];
+
+/// Generates a C/A code from the G1 and G2 registers, with G2 delayed by
+/// `delay` chips (binary chips).
+fn reference_ca_code(delay: usize) -> Vec<u8> {
+    let run = |taps: &[usize]| {
+        let mut register = [1u8; 10];
+        (0..1023)
+            .map(|_| {
+                let output = register[9];
+                let feedback =
+                    taps.iter().fold(0, |sum, &tap| sum ^ register[tap - 1]);
+                register.rotate_right(1);
+                register[0] = feedback;
+                output
+            })
+            .collect::<Vec<_>>()
+    };
+    // G1 = 1 + X^3 + X^10, G2 = 1 + X^2 + X^3 + X^6 + X^8 + X^9 + X^10
+    let g1 = run(&[3, 10]);
+    let g2 = run(&[2, 3, 6, 8, 9, 10]);
+    (0..1023)
+        .map(|i| g1[i] ^ g2[(i + 1023 - delay) % 1023])
+        .collect()
+}
+
+/// Returns the binary chips of the C/A code of a PRN.
+fn binary_ca_code(prn: usize) -> Option<Vec<u8>> {
+    ca_code(prn)
+        .map(|code| code.iter().map(|&chip| u8::from(chip < 0)).collect())
+}
+
+/// Creates a QZSS LNAV record of satellite `Jnn` with time of clock and
+/// ephemeris at the start of GPS week 2190 plus one hour.
+fn qzss_record(satellite: usize, health: f64) -> NavRecord {
+    let mut values = vec![0.0; 31];
+    // IODE, sqrt(A), toe, inclination, week, health, fit interval flag
+    values[3] = 17.0;
+    values[10] = 6493.0;
+    values[11] = 3600.0;
+    values[15] = 0.7;
+    values[21] = 2190.0;
+    values[24] = health;
+    values[28] = 1.0;
+    NavRecord {
+        kind: NavRecordKind::Ephemeris,
+        satellite: SatelliteId {
+            system: SatelliteSystem::Qzss,
+            prn: satellite,
+        },
+        message: None,
+        epoch: date(2021, 12, 26).at(1, 0, 0, 0),
+        label: None,
+        values,
+    }
+}
+
+#[test]
+fn gps_ca_code_reference() -> Result<(), Error> {
+    // First 10 chips of PRN 1 and 2 in octal (IS-GPS-200 table 3-Ia)
+    for (prn, delay, octal) in [(1, 5, 0o1440), (2, 6, 0o1620)] {
+        let code = binary_ca_code(prn).ok_or_else(|| Error::msg("no code"))?;
+        assert_eq!(code, reference_ca_code(delay));
+        let first = code[..10]
+            .iter()
+            .fold(0, |sum, &chip| sum << 1 | u32::from(chip));
+        assert_eq!(first, octal);
+    }
+    Ok(())
+}
+
+#[test]
+fn qzss_prn_mapping() -> Result<(), Error> {
+    for (sv, &delay) in QZSS_DELAYS.iter().enumerate() {
+        // J01 is PRN 193
+        let prn = sv + 1 + PRN_OFFSET;
+        let code = binary_ca_code(prn).ok_or_else(|| Error::msg("no code"))?;
+        assert_eq!(code, reference_ca_code(delay), "J{:02}", sv + 1);
+    }
+    assert_eq!(PRN_OFFSET + QZSS_SATELLITES, 202);
+    // PRN 192 and 203 have C/A codes of their own
+    let first = PRN_OFFSET + 1;
+    let last = PRN_OFFSET + QZSS_SATELLITES;
+    assert!(
+        ca_code(first - 1).is_some_and(|code| Some(code) != ca_code(first))
+    );
+    assert!(ca_code(last + 1).is_some_and(|code| Some(code) != ca_code(last)));
+    Ok(())
+}
+
+#[test_case(HealthMode::Simulate, 1.0, 33; "unhealthy")]
+#[test_case(HealthMode::Simulate, 0.0, 0; "healthy")]
+#[test_case(HealthMode::ForceHealthy, 1.0, 0; "forced healthy")]
+fn qzss_ephemerides(
+    health_mode: HealthMode, health: f64, svhlth: i32,
+) -> Result<(), Error> {
+    let navigation = SystemNavigation {
+        records: vec![
+            qzss_record(3, health),
+            qzss_record(QZSS_SATELLITES + 1, health),
+        ],
+        ..SystemNavigation::default()
+    };
+    let selection = SatelliteSelection {
+        health_mode,
+        ..SatelliteSelection::default()
+    };
+    let system = QzssSystem::new(&navigation, selection)?;
+    let time = GpsTime {
+        week: 2190,
+        sec: 3600.0,
+    };
+    // J03 takes the ephemeris slot of PRN 195
+    let eph = system
+        .timeline
+        .select(3, &time)
+        .ok_or_else(|| Error::msg("no J03 ephemeris"))?;
+    assert_eq!(eph.iode, 17);
+    assert_eq!(eph.svhlth, svhlth);
+    assert!((eph.fit_interval - 4.0).abs() < 1e-9);
+    for sv in (1..=QZSS_SATELLITES).filter(|&sv| sv != 3) {
+        assert!(system.timeline.select(sv, &time).is_none());
+    }
+    Ok(())
+}
+
+#[test]
+fn qzss_without_ephemerides() {
+    let navigation = SystemNavigation {
+        records: vec![qzss_record(QZSS_SATELLITES + 1, 0.0)],
+        ..SystemNavigation::default()
+    };
+    assert!(matches!(
+        QzssSystem::new(&navigation, SatelliteSelection::default()),
+        Err(Error::NoSystemEphemeris("QZSS"))
+    ));
+}
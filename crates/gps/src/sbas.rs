@@ -0,0 +1,18 @@
+//! SBAS L1 signal generation.
+//!
+//! This module simulates the L1 signals of SBAS GEO satellites such as those
+//! of WAAS and EGNOS in the same composite output as GPS L1 C/A. The
+//! satellites transmit C/A codes on the L1 carrier, with 250 bps messages
+//! protected by a CRC and a rate 1/2 convolutional code. The GEO orbits come
+//! from the SBAS ephemerides of RINEX 3 or RINEX 4 navigation files, and the
+//! messages from a message file or the test mode message type 0.
+
+/// SBAS message file and FEC encoding
+mod message;
+/// GEO orbits from the broadcast state vectors
+mod orbit;
+/// SBAS satellite system driven by the signal generator
+mod system;
+
+pub use message::SbasMessages;
+pub use system::SbasSystem;
@@ -0,0 +1,234 @@
+use std::{collections::BTreeMap, path::Path};
+
+use constants::SECONDS_IN_WEEK;
+use rtcm::crc::crc24q_bits;
+
+use crate::{Error, datetime::GpsTime, signal::Bits};
+
+#[cfg(test)]
+mod tests;
+
+/// Number of data bits of a message, between the message type and the CRC.
+pub const DATA_BITS: usize = 212;
+
+/// Number of hexadecimal digits of the data field of a message file line.
+const DATA_DIGITS: usize = DATA_BITS / 4;
+
+/// Number of bits of a message before the CRC: preamble, message type and
+/// data.
+const PAYLOAD_BITS: usize = 8 + 6 + DATA_BITS;
+
+/// Number of FEC symbols of a message (250 bits at rate 1/2).
+const MESSAGE_SYMBOLS: usize = 500;
+
+/// Parts of the 24-bit preamble, sent in consecutive messages.
+const PREAMBLES: [u64; 3] = [0x53, 0x9a, 0xc6];
+
+/// Highest message type.
+const MAX_MESSAGE_TYPE: u8 = 63;
+
+/// Message type 0: do not use for safety applications (test mode).
+const TEST_MODE: u8 = 0;
+
+/// Generator polynomial G1 of the convolutional code (octal 171).
+const CONV_G1: u32 = 0o171;
+
+/// Generator polynomial G2 of the convolutional code (octal 133).
+const CONV_G2: u32 = 0o133;
+
+/// Content of a message of the message file.
+#[derive(Clone)]
+struct SbasMessage {
+    /// Message type (0 to 63)
+    message_type: u8,
+    /// The 212 data bits
+    data: Vec<u8>,
+}
+
+/// SBAS messages broadcast by the GEO satellites.
+///
+/// Every second, each SBAS satellite broadcasts a 250-bit message: the
+/// rotating 24-bit preamble, the message type, 212 data bits and the
+/// CRC-24Q parity. The messages are read from a text file holding one
+/// message per line as
+///
+/// ```text
+/// 120 2318 345600 2 <53 hexadecimal digits>
+/// ```
+///
+/// where the columns are the PRN (120 to 158), the GPS week and time of week
+/// in seconds at which the transmission of the message starts, the message
+/// type and the data bits. Empty lines and lines starting with `#` are
+/// skipped. The preamble and the CRC are added by the simulator. In the
+/// seconds for which the file has no message of a satellite, it broadcasts
+/// message type 0 (test mode) with zero data, which is all it broadcasts
+/// without a message file.
+#[derive(Clone, Default)]
+pub struct SbasMessages {
+    /// Messages by PRN, GPS week and time of week
+    messages: BTreeMap<(usize, i32, u32), SbasMessage>,
+}
+
+impl SbasMessages {
+    /// Reads the messages of a message file.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the message file
+    ///
+    /// # Returns
+    /// * `Ok(SbasMessages)` - The messages of the file
+    /// * `Err(Error)` - If the file cannot be read or parsed
+    ///
+    /// # Errors
+    /// * `Error::SbasMessageRead` - If the file cannot be read or has a
+    ///   malformed line, with its path and the underlying error
+    pub fn read_file(path: &Path) -> Result<Self, Error> {
+        std::fs::read_to_string(path)
+            .map_err(Error::from)
+            .and_then(|text| Self::parse(&text))
+            .map_err(|e| Error::SbasMessageRead {
+                path: path.to_path_buf(),
+                source: Box::new(e),
+            })
+    }
+
+    /// Parses the messages of a message file.
+    ///
+    /// # Arguments
+    /// * `text` - Content of the message file
+    ///
+    /// # Returns
+    /// * `Ok(SbasMessages)` - The messages of the file
+    /// * `Err(Error)` - If a line is malformed
+    ///
+    /// # Errors
+    /// * `Error::InvalidSbasMessage` - If a line does not have a PRN in
+    ///   120-158, a week, a whole time of week, a message type in 0-63 and 53
+    ///   hexadecimal digits
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let mut messages = BTreeMap::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = |message: &str| {
+                Error::invalid_sbas_message(format!(
+                    "line {}: {message}",
+                    number + 1
+                ))
+            };
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let [prn, week, tow, message_type, hex] = fields[..] else {
+                return Err(invalid(
+                    "expected PRN, week, time of week, message type and data",
+                ));
+            };
+            let prn = prn
+                .parse::<usize>()
+                .ok()
+                .filter(|prn| (120..=158).contains(prn))
+                .ok_or_else(|| invalid("PRN out of range"))?;
+            let week = week
+                .parse::<i32>()
+                .ok()
+                .filter(|week| *week >= 0)
+                .ok_or_else(|| invalid("invalid week"))?;
+            let tow = tow
+                .parse::<u32>()
+                .ok()
+                .filter(|tow| f64::from(*tow) < SECONDS_IN_WEEK)
+                .ok_or_else(|| invalid("invalid time of week"))?;
+            let message_type = message_type
+                .parse::<u8>()
+                .ok()
+                .filter(|message_type| *message_type <= MAX_MESSAGE_TYPE)
+                .ok_or_else(|| invalid("message type out of range"))?;
+            let data = hex_bits(hex)
+                .ok_or_else(|| invalid("data is not 53 hexadecimal digits"))?;
+            messages
+                .insert((prn, week, tow), SbasMessage { message_type, data });
+        }
+        Ok(Self { messages })
+    }
+
+    /// Generates the FEC symbols of the messages of consecutive seconds.
+    ///
+    /// The messages are encoded continuously by the rate 1/2, constraint
+    /// length 7 convolutional code, starting with the message of the second
+    /// before, so that the first symbols are those a satellite transmitting
+    /// without interruption sends.
+    ///
+    /// # Arguments
+    /// * `prn` - PRN number of the satellite
+    /// * `start` - GPS time of the start of the first message (a whole second)
+    /// * `seconds` - Number of messages
+    ///
+    /// # Returns
+    /// 500 symbols per message, +1 for binary 0 and -1 for binary 1
+    pub fn symbols(
+        &self, prn: usize, start: &GpsTime, seconds: usize,
+    ) -> Vec<i8> {
+        let mut state = 0u32;
+        let mut symbols = Vec::with_capacity((seconds + 1) * MESSAGE_SYMBOLS);
+        for index in 0..=seconds {
+            let time = start.add_secs(index as f64 - 1.0);
+            for bit in self.message_bits(prn, &time) {
+                state = (state >> 1) | u32::from(bit) << 6;
+                symbols.push((state & CONV_G1).count_ones() & 1);
+                symbols.push((state & CONV_G2).count_ones() & 1);
+            }
+        }
+        symbols
+            .into_iter()
+            .skip(MESSAGE_SYMBOLS)
+            .map(|symbol| if symbol == 0 { 1 } else { -1 })
+            .collect()
+    }
+
+    /// Builds the 250 bits of the message a satellite transmits from a time
+    /// on.
+    ///
+    /// The preamble parts follow each other every second from the start of
+    /// the GPS week numbering.
+    fn message_bits(&self, prn: usize, time: &GpsTime) -> Vec<u8> {
+        let tow = time.sec.round() as u32;
+        let seconds =
+            i64::from(time.week) * SECONDS_IN_WEEK as i64 + i64::from(tow);
+        let mut bits = Bits::default();
+        bits.push(PREAMBLES[seconds.rem_euclid(3) as usize], 8);
+        if let Some(message) = self.messages.get(&(prn, time.week, tow)) {
+            bits.push(u64::from(message.message_type), 6);
+            bits.0.extend_from_slice(&message.data);
+        } else {
+            bits.push(u64::from(TEST_MODE), 6);
+            bits.zeros(DATA_BITS);
+        }
+        // CRC-24Q over the bits packed MSB first
+        let mut bytes = vec![0u8; PAYLOAD_BITS.div_ceil(8)];
+        for (i, &bit) in bits.0.iter().enumerate() {
+            bytes[i / 8] |= bit << (7 - i % 8);
+        }
+        bits.push(u64::from(crc24q_bits(&bytes, PAYLOAD_BITS)), 24);
+        bits.0
+    }
+}
+
+/// Converts the hexadecimal data field of a message into bits.
+///
+/// # Arguments
+/// * `hex` - 53 hexadecimal digits, most significant bit first
+///
+/// # Returns
+/// The 212 data bits, or `None` if the digits are malformed
+fn hex_bits(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() != DATA_DIGITS {
+        return None;
+    }
+    let mut bits = Vec::with_capacity(DATA_BITS);
+    for digit in hex.chars() {
+        let value = digit.to_digit(16)?;
+        bits.extend((0..4).rev().map(|bit| (value >> bit & 1) as u8));
+    }
+    Some(bits)
+}
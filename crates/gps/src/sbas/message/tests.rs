@@ -0,0 +1,133 @@
+use rtcm::crc::crc24q_bits;
+
+use super::{DATA_BITS, MESSAGE_SYMBOLS, PREAMBLES, SbasMessages};
+use crate::{Error, datetime::GpsTime};
+
+/// Message file with messages of PRN 131 at 345600 and 345601 s of week
+/// 2190.
+const MESSAGES: &str = "\
+# PRN week tow type data
+131 2190 345600 2 C00A0000F00000000000000000000000000000000000000000123
+131 2190 345601 63 FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF
+";
+
+/// Taps of G1 (octal 171) on the current and the six previous bits.
+const G1_TAPS: [u8; 7] = [1, 1, 1, 1, 0, 0, 1];
+
+/// Taps of G2 (octal 133) on the current and the six previous bits.
+const G2_TAPS: [u8; 7] = [1, 0, 1, 1, 0, 1, 1];
+
+/// Encodes bits with the rate 1/2 convolutional code from the zero state,
+/// the G1 symbol first.
+fn convolve(bits: &[u8]) -> Vec<u8> {
+    let tap = |taps: &[u8; 7], n: usize| {
+        (0..7)
+            .filter(|&k| n >= k)
+            .fold(0, |sum, k| sum ^ (taps[k] & bits[n - k]))
+    };
+    (0..bits.len())
+        .flat_map(|n| [tap(&G1_TAPS, n), tap(&G2_TAPS, n)])
+        .collect()
+}
+
+/// Packs bits into bytes, most significant bit first.
+fn pack(bits: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![0; bits.len().div_ceil(8)];
+    for (i, &bit) in bits.iter().enumerate() {
+        bytes[i / 8] |= bit << (7 - i % 8);
+    }
+    bytes
+}
+
+/// Returns the value of `len` bits from `first` on.
+fn field(bits: &[u8], first: usize, len: usize) -> u64 {
+    bits[first..first + len]
+        .iter()
+        .fold(0, |value, &bit| value << 1 | u64::from(bit))
+}
+
+#[test]
+fn message_fields() -> Result<(), Error> {
+    let messages = SbasMessages::parse(MESSAGES)?;
+    let time = GpsTime {
+        week: 2190,
+        sec: 345_600.0,
+    };
+    let bits = messages.message_bits(131, &time);
+    assert_eq!(bits.len(), 250);
+    assert_eq!(field(&bits, 8, 6), 2);
+    assert_eq!(field(&bits, 14, 8), 0xc0);
+    assert_eq!(field(&bits, 14 + DATA_BITS - 12, 12), 0x123);
+    // The parity of the whole message is zero
+    assert_eq!(crc24q_bits(&pack(&bits), bits.len()), 0);
+    // Other satellites and seconds broadcast message type 0 with zero data
+    for (prn, sec) in [(120, 345_600.0), (131, 345_602.0)] {
+        let bits = messages.message_bits(prn, &GpsTime { week: 2190, sec });
+        assert_eq!(field(&bits, 8, 6 + DATA_BITS - 64), 0);
+        assert_eq!(crc24q_bits(&pack(&bits), bits.len()), 0);
+    }
+    Ok(())
+}
+
+#[test]
+fn preamble_sequence() {
+    let messages = SbasMessages::default();
+    // A week holds a whole number of preamble cycles, so that the cycle
+    // continues across the week rollover
+    let times = (604_795..604_800)
+        .map(|sec| GpsTime {
+            week: 2189,
+            sec: f64::from(sec),
+        })
+        .chain((0..5).map(|sec| GpsTime {
+            week: 2190,
+            sec: f64::from(sec),
+        }));
+    let preambles: Vec<u64> = times
+        .map(|time| field(&messages.message_bits(120, &time), 0, 8))
+        .collect();
+    assert_eq!(preambles, [
+        0x9a, 0xc6, 0x53, 0x9a, 0xc6, 0x53, 0x9a, 0xc6, 0x53, 0x9a
+    ]);
+    assert_eq!(PREAMBLES, [0x53, 0x9a, 0xc6]);
+}
+
+#[test]
+fn convolutional_encoding() -> Result<(), Error> {
+    let messages = SbasMessages::parse(MESSAGES)?;
+    let start = GpsTime {
+        week: 2190,
+        sec: 345_600.0,
+    };
+    let symbols = messages.symbols(131, &start, 3);
+    assert_eq!(symbols.len(), 3 * MESSAGE_SYMBOLS);
+    // The encoder runs through the message of the second before
+    let bits: Vec<u8> = (-1..3)
+        .flat_map(|second| {
+            messages.message_bits(131, &start.add_secs(f64::from(second)))
+        })
+        .collect();
+    let expected: Vec<i8> = convolve(&bits)[MESSAGE_SYMBOLS..]
+        .iter()
+        .map(|&symbol| if symbol == 0 { 1 } else { -1 })
+        .collect();
+    assert_eq!(symbols, expected);
+    Ok(())
+}
+
+#[test]
+fn convolutional_impulse_response() {
+    let mut bits = vec![0; 8];
+    bits[0] = 1;
+    let symbols = convolve(&bits);
+    let g1: Vec<u8> = symbols.iter().step_by(2).copied().collect();
+    let g2: Vec<u8> = symbols.iter().skip(1).step_by(2).copied().collect();
+    // The generator polynomials in octal, first tap first
+    let octal = |taps: &[u8]| {
+        taps[..7]
+            .iter()
+            .fold(0, |sum, &tap| sum << 1 | u32::from(tap))
+    };
+    assert_eq!(octal(&g1), super::CONV_G1);
+    assert_eq!(octal(&g2), super::CONV_G2);
+}
@@ -0,0 +1,78 @@
+use rinex::navigation::NavRecord;
+
+use crate::{
+    Error,
+    datetime::{DateTime, GpsTime},
+    propagation::SatelliteState,
+};
+
+/// Broadcast ephemeris of an SBAS GEO satellite.
+///
+/// The satellite state is given as position, velocity and acceleration in
+/// the WGS84 frame at the reference time `t0`, together with the clock
+/// offset and drift of the satellite, as in message type 9.
+#[derive(Clone)]
+pub struct SbasEphemeris {
+    /// Reference time of the ephemeris in GPS time
+    pub t0: GpsTime,
+    /// Position at the reference time (meters)
+    pub position: [f64; 3],
+    /// Velocity at the reference time (meters/second)
+    pub velocity: [f64; 3],
+    /// Acceleration (meters/second²)
+    pub acceleration: [f64; 3],
+    /// Satellite clock offset from GPS time (seconds)
+    pub clock_bias: f64,
+    /// Satellite clock drift (seconds/second)
+    pub clock_drift: f64,
+    /// Health of the satellite (0 for healthy)
+    pub health: i32,
+}
+
+impl SbasEphemeris {
+    /// Converts an SBAS RINEX ephemeris record.
+    ///
+    /// # Arguments
+    /// * `record` - SBAS ephemeris record
+    ///
+    /// # Returns
+    /// * `Ok(SbasEphemeris)` - The ephemeris in meters and seconds
+    /// * `Err(Error)` - If the epoch of the record cannot be converted
+    ///
+    /// # Errors
+    /// * Returns an error if the epoch is out of range
+    pub fn from_record(record: &NavRecord) -> Result<Self, Error> {
+        let datetime = DateTime::from(record.epoch.in_tz("UTC")?);
+        let [x, vx, ax, health] = record.orbit(1);
+        let [y, vy, ay, _ura] = record.orbit(2);
+        let [z, vz, az, _iodn] = record.orbit(3);
+        let km = |v: [f64; 3]| v.map(|value| value * 1000.0);
+        Ok(Self {
+            t0: GpsTime::from(&datetime),
+            position: km([x, y, z]),
+            velocity: km([vx, vy, vz]),
+            acceleration: km([ax, ay, az]),
+            clock_bias: record.values[0],
+            clock_drift: record.values[1],
+            health: health as i32,
+        })
+    }
+}
+
+impl SatelliteState for SbasEphemeris {
+    fn satellite_state(
+        &self, time: &GpsTime,
+    ) -> ([f64; 3], [f64; 3], [f64; 2]) {
+        let dt = time.diff_secs(&self.t0);
+        let pos = std::array::from_fn(|i| {
+            self.position[i]
+                + self.velocity[i] * dt
+                + 0.5 * self.acceleration[i] * dt * dt
+        });
+        let vel = std::array::from_fn(|i| {
+            self.velocity[i] + self.acceleration[i] * dt
+        });
+        let clk = [self.clock_bias + self.clock_drift * dt, self.clock_drift];
+        (pos, vel, clk)
+    }
+}
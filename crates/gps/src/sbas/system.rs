@@ -0,0 +1,268 @@
+use constants::{CA_SEQ_LEN, CARR_FREQ, CODE_FREQ, R2D, SECONDS_IN_HOUR};
+use rinex::navigation::SatelliteSystem;
+
+use crate::{
+    Error,
+    channel::ca_code,
+    datetime::{GpsTime, TimeRange},
+    generator::SatelliteSelection,
+    propagation::compute_range,
+    sbas::{message::SbasMessages, orbit::SbasEphemeris},
+    signal::{
+        SignalChannel, SignalComponent, SignalSystem, Subcarrier,
+        SystemContext, SystemEphemeris, SystemNavigation, allocate_channels,
+        band_offset, print_channel_status, signal_gain,
+    },
+};
+
+/// Lowest PRN number of the SBAS satellites.
+const FIRST_PRN: usize = 120;
+
+/// Number of SBAS PRN numbers, 120 to 158.
+const SBAS_SATELLITES: usize = 39;
+
+/// Offset of the SBAS PRN numbers from the satellite numbers of RINEX files
+/// (S20 is PRN 120).
+const PRN_OFFSET: usize = 100;
+
+/// Number of messages of the symbol buffer of a channel.
+///
+/// The buffer starts with the message being transmitted at a frame boundary
+/// and reaches past the next boundary, where it is regenerated.
+const WINDOW_MESSAGES: usize = 32;
+
+/// Number of C/A code chips per FEC symbol (2 ms at 500 symbols per second).
+const CHIPS_PER_SYMBOL: u64 = 2 * CA_SEQ_LEN as u64;
+
+/// Longest time from the reference time for which an ephemeris is used, in
+/// seconds.
+const MAX_EPHEMERIS_AGE: f64 = SECONDS_IN_HOUR;
+
+/// SBAS L1 signals of GEO satellites.
+///
+/// Each visible SBAS satellite with PRN 120 to 158 transmits its C/A code
+/// on the GPS L1 carrier, with 250-bit messages every second at 250 bps,
+/// encoded by the rate 1/2 convolutional code into 500 symbols per second.
+/// The messages come from a message file or are test mode messages of type
+/// 0, see `SbasMessages`; SBAS network time is simulated as GPS time.
+///
+/// The satellite orbits follow the GEO state vectors of the SBAS ephemerides
+/// of RINEX 3 or RINEX 4 navigation files, the ephemeris closest in time
+/// being used for each satellite. The ranges are delayed by the Klobuchar
+/// model of the GPS message.
+pub struct SbasSystem {
+    /// Broadcast ephemerides of each satellite (index PRN - 120), sorted by
+    /// reference time
+    ephemerides: Vec<Vec<SbasEphemeris>>,
+    /// Ephemeris in use of each satellite (index PRN - 120)
+    current: Vec<Option<SbasEphemeris>>,
+    /// Messages broadcast by the satellites
+    messages: SbasMessages,
+    /// Satellites allowed to occupy a channel
+    selection: SatelliteSelection,
+    /// L1 carrier frequency minus the output centre frequency, or `None` if
+    /// L1 lies outside the output band
+    carrier_offset: Option<f64>,
+    /// Channels of the visible satellites
+    channels: Vec<SignalChannel>,
+}
+
+impl SbasSystem {
+    /// Creates the SBAS system from the navigation data.
+    ///
+    /// # Arguments
+    /// * `navigation` - Navigation data of the systems other than GPS
+    /// * `messages` - Messages broadcast by the satellites
+    /// * `selection` - Satellites allowed to occupy a channel, without PRN
+    ///   lists
+    ///
+    /// # Returns
+    /// * `Ok(SbasSystem)` - The system ready to be initialized
+    /// * `Err(Error)` - If there is no usable SBAS ephemeris
+    ///
+    /// # Errors
+    /// * `Error::NoSystemEphemeris` - If the navigation data holds no ephemeris
+    ///   of a satellite with PRN 120 to 158
+    /// * Returns an error if the epoch of a record is out of range
+    pub fn new(
+        navigation: &SystemNavigation, messages: SbasMessages,
+        selection: SatelliteSelection,
+    ) -> Result<Self, Error> {
+        let mut ephemerides = vec![Vec::new(); SBAS_SATELLITES];
+        for record in navigation.ephemerides(SatelliteSystem::Sbas) {
+            let Some(satellite) = (record.satellite.prn + PRN_OFFSET)
+                .checked_sub(FIRST_PRN)
+                .and_then(|sv| ephemerides.get_mut(sv))
+            else {
+                continue;
+            };
+            let mut eph = SbasEphemeris::from_record(record)?;
+            eph.health = selection.health_mode.broadcast_health(
+                record.satellite.prn + PRN_OFFSET,
+                eph.health,
+                &[],
+            );
+            if satellite
+                .iter()
+                .all(|other: &SbasEphemeris| other.t0.diff_secs(&eph.t0) != 0.0)
+            {
+                satellite.push(eph);
+            }
+        }
+        if ephemerides.iter().all(Vec::is_empty) {
+            return Err(Error::no_system_ephemeris("SBAS"));
+        }
+        for satellite in &mut ephemerides {
+            satellite.sort_by(|a, b| a.t0.diff_secs(&b.t0).total_cmp(&0.0));
+        }
+        Ok(Self {
+            ephemerides,
+            current: vec![None; SBAS_SATELLITES],
+            messages,
+            selection,
+            carrier_offset: None,
+            channels: Vec::new(),
+        })
+    }
+
+    /// Takes the ephemeris closest to a time into use for every satellite.
+    fn select_ephemerides(&mut self, time: &GpsTime) {
+        for (satellite, current) in
+            self.ephemerides.iter().zip(&mut self.current)
+        {
+            *current = satellite
+                .iter()
+                .map(|eph| (time.diff_secs(&eph.t0).abs(), eph))
+                .filter(|(age, _)| *age <= MAX_EPHEMERIS_AGE)
+                .min_by(|a, b| a.0.total_cmp(&b.0))
+                .map(|(_, eph)| eph.clone());
+        }
+    }
+
+    /// Releases the channels of satellites below the elevation mask and
+    /// allocates channels to the newly visible satellites.
+    fn allocate_channels(&mut self, context: &SystemContext) {
+        let Some(carrier_offset) = self.carrier_offset else {
+            return;
+        };
+        allocate_channels(
+            &mut self.channels,
+            &self.current,
+            FIRST_PRN,
+            &self.selection,
+            |_, eph| {
+                l1_range(eph, context).azel.el * R2D > context.elevation_mask
+            },
+            |prn, eph| {
+                let code = ca_code(prn)?;
+                let component = SignalComponent {
+                    code,
+                    secondary: Vec::new(),
+                    data: true,
+                    amplitude: 1.0,
+                    subcarrier: Subcarrier::Bpsk,
+                    quadrature: false,
+                };
+                let mut channel = SignalChannel::new(
+                    prn,
+                    vec![component],
+                    CODE_FREQ,
+                    CARR_FREQ,
+                    carrier_offset,
+                    CHIPS_PER_SYMBOL,
+                );
+                channel.start(l1_range(eph, context));
+                Some(channel)
+            },
+        );
+    }
+
+    /// Regenerates the message symbols of all channels for the frame
+    /// starting at a time.
+    fn generate_messages(&mut self, context: &SystemContext) {
+        // The buffers start with the message transmitted a second before the
+        // current reception time
+        let time = context.time.add_secs(-1.0);
+        let start = GpsTime {
+            week: time.week,
+            sec: time.sec.floor(),
+        };
+        for channel in &mut self.channels {
+            let symbols =
+                self.messages.symbols(channel.prn, &start, WINDOW_MESSAGES);
+            channel.set_symbols(&start, symbols);
+        }
+    }
+}
+
+impl SystemEphemeris for SbasEphemeris {
+    fn health(&self) -> i32 {
+        self.health
+    }
+}
+
+impl SignalSystem for SbasSystem {
+    fn name(&self) -> &'static str {
+        "SBAS"
+    }
+
+    fn carrier_frequency(&self) -> f64 {
+        CARR_FREQ
+    }
+
+    fn initialize(&mut self, context: &SystemContext) {
+        self.carrier_offset = band_offset(context, CARR_FREQ, CODE_FREQ);
+        if self.carrier_offset.is_none() {
+            eprintln!(
+                "Warning: SBAS L1 lies outside the output band; SBAS \
+                 satellites are not simulated."
+            );
+        }
+        self.select_ephemerides(context.time);
+        self.allocate_channels(context);
+        self.generate_messages(context);
+    }
+
+    fn update_channels(&mut self, context: &SystemContext) {
+        for channel in &mut self.channels {
+            let Some(eph) = &self.current[channel.prn - FIRST_PRN] else {
+                continue;
+            };
+            let rho = l1_range(eph, context);
+            channel.update(&rho, context.step, context.sampling_period);
+            channel.gain =
+                signal_gain(&rho, context.fixed_gain, context.antenna_pattern);
+        }
+    }
+
+    fn update_navigation(&mut self, context: &SystemContext) {
+        self.select_ephemerides(context.time);
+        self.allocate_channels(context);
+        self.generate_messages(context);
+    }
+
+    fn next_sample(&mut self, sampling_period: f64) -> (i32, i32) {
+        self.channels
+            .iter_mut()
+            .fold((0, 0), |(i_acc, q_acc), channel| {
+                let (ip, qp) = channel.next_sample(sampling_period);
+                (i_acc + ip, q_acc + qp)
+            })
+    }
+
+    fn print_status(&self) {
+        for channel in &self.channels {
+            print_channel_status(
+                &format!("S{:02}", channel.prn - PRN_OFFSET),
+                channel.azel(),
+                channel.rho0(),
+                None,
+            );
+        }
+    }
+}
+
+/// Computes the L1 range of a satellite at the current receiver time.
+fn l1_range(eph: &SbasEphemeris, context: &SystemContext) -> TimeRange {
+    compute_range(eph, context.ionoutc, context.time, context.location)
+}
@@ -0,0 +1,634 @@
+use constants::{MAX_CHAN, R2D, SPEED_OF_LIGHT, SPEED_OF_LIGHT_INV};
+use geometry::{Azel, Ecef};
+use rinex::navigation::{
+    IonosphericCorrection, NavRecord, NavRecordKind, SatelliteSystem,
+    TimeSystemCorrection,
+};
+
+use crate::{
+    datetime::{GpsTime, TimeRange},
+    ephemeris::Ephemeris,
+    generator::SatelliteSelection,
+    ionoutc::IonoUtc,
+    table::{COS_TABLE512, SIN_TABLE512},
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Navigation data of the satellite systems other than GPS L1 C/A.
+///
+/// GPS LNAV records become the ephemeris timeline of the generator; the
+/// records of the other systems and the GPS CNAV records are kept as read,
+/// together with the header corrections they need, and converted by the
+/// system that simulates them.
+#[derive(Clone, Default)]
+pub struct SystemNavigation {
+    /// Ephemeris records of the other systems and GPS CNAV records in file
+    /// order
+    pub records: Vec<NavRecord>,
+    /// Ionospheric corrections of the file headers
+    pub ionospheric_corrections: Vec<IonosphericCorrection>,
+    /// Time system corrections of the file headers
+    pub time_system_corrections: Vec<TimeSystemCorrection>,
+}
+
+impl SystemNavigation {
+    /// Adds the records and header corrections of a navigation file.
+    ///
+    /// # Arguments
+    /// * `rinex` - Parsed navigation file
+    pub fn extend(&mut self, rinex: &rinex::Rinex) {
+        self.records.extend(
+            rinex
+                .records
+                .iter()
+                .filter(|record| {
+                    record.kind == NavRecordKind::Ephemeris
+                        && !record.is_gps_lnav()
+                })
+                .cloned(),
+        );
+        self.ionospheric_corrections
+            .extend(rinex.ionospheric_corrections.iter().cloned());
+        self.time_system_corrections
+            .extend(rinex.time_system_corrections.iter().cloned());
+    }
+
+    /// Returns the ephemeris records of a satellite system.
+    ///
+    /// # Arguments
+    /// * `system` - Satellite system of the records
+    pub fn ephemerides(
+        &self, system: SatelliteSystem,
+    ) -> impl Iterator<Item = &NavRecord> {
+        self.records
+            .iter()
+            .filter(move |record| record.satellite.system == system)
+    }
+
+    /// Returns the first ionospheric correction of a type.
+    ///
+    /// # Arguments
+    /// * `kind` - Correction type such as GAL or BDSA
+    pub fn ionospheric_correction(
+        &self, kind: &str,
+    ) -> Option<&IonosphericCorrection> {
+        self.ionospheric_corrections
+            .iter()
+            .find(|correction| correction.kind == kind)
+    }
+
+    /// Returns the first time system correction of a type.
+    ///
+    /// # Arguments
+    /// * `kind` - Correction type such as GAUT or BDUT
+    pub fn time_system_correction(
+        &self, kind: &str,
+    ) -> Option<&TimeSystemCorrection> {
+        self.time_system_corrections
+            .iter()
+            .find(|correction| correction.kind == kind)
+    }
+}
+
+/// Chip shape of a signal component.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Subcarrier {
+    /// Rectangular chips without subcarrier (BPSK)
+    Bpsk,
+    /// Sine-phased BOC(n,1) subcarrier with 2n half periods per chip
+    Boc(u32),
+    /// CBOC: BOC(1,1) plus a BOC(6,1) share of the given signed amplitude,
+    /// the BOC(1,1) amplitude completing the unit power
+    Cboc(f64),
+}
+
+impl Subcarrier {
+    /// Returns the subcarrier level at a position within a chip.
+    ///
+    /// # Arguments
+    /// * `fraction` - Position within the chip (0.0 to 1.0)
+    pub fn level(self, fraction: f64) -> f64 {
+        let boc = |n: u32| {
+            if ((fraction * f64::from(2 * n)) as u32).is_multiple_of(2) {
+                1.0
+            } else {
+                -1.0
+            }
+        };
+        match self {
+            Self::Bpsk => 1.0,
+            Self::Boc(n) => boc(n),
+            Self::Cboc(boc6) => {
+                (1.0 - boc6 * boc6).sqrt() * boc(1) + boc6 * boc(6)
+            }
+        }
+    }
+}
+
+/// A spread-spectrum component of a signal, such as a data or pilot channel.
+#[derive(Clone)]
+pub struct SignalComponent {
+    /// Primary code chips (+1 or -1)
+    pub code: Vec<i8>,
+    /// Secondary code bits (+1 or -1) applied per primary code period,
+    /// aligned to the start of the week (empty for none)
+    pub secondary: Vec<i8>,
+    /// Whether the component carries the navigation symbols
+    pub data: bool,
+    /// Signed amplitude of the component relative to the signal
+    pub amplitude: f64,
+    /// Chip shape
+    pub subcarrier: Subcarrier,
+    /// Whether the component is modulated on the quadrature carrier instead
+    /// of the in-phase carrier
+    pub quadrature: bool,
+}
+
+/// A satellite channel of a signal other than GPS L1 C/A.
+///
+/// The signal is the sum of its components, spread at a common chip rate and
+/// modulated on the in-phase or quadrature carrier at a fixed offset from the
+/// output centre frequency. Data components carry one navigation symbol per
+/// `chips_per_symbol` chips from a symbol buffer starting at a known system
+/// time, so that the position in the code, the secondary code and the
+/// navigation message all follow from the time of transmission.
+///
+/// As for GPS channels, the code phase is set from the pseudorange at the
+/// start of every update step, and the code and carrier frequencies follow
+/// the range rate over the step.
+pub struct SignalChannel {
+    /// Satellite number within its system
+    pub prn: usize,
+    /// Signal components
+    components: Vec<SignalComponent>,
+    /// Nominal chip rate (Hz)
+    chip_rate: f64,
+    /// Carrier wavelength (meters)
+    wavelength: f64,
+    /// Carrier frequency minus the output centre frequency (Hz)
+    carrier_offset: f64,
+    /// Number of chips per navigation symbol
+    chips_per_symbol: u64,
+    /// Navigation symbols (+1 or -1)
+    symbols: Vec<i8>,
+    /// System time of the start of the first symbol
+    symbol_start: GpsTime,
+    /// Code periods from the start of the week to `symbol_start`, per
+    /// component
+    period_offsets: Vec<u64>,
+    /// Chips transmitted since `symbol_start` at the current sample
+    chip_count: f64,
+    /// Code frequency with Doppler (Hz)
+    code_frequency: f64,
+    /// Carrier frequency with Doppler and offset (Hz)
+    carrier_frequency: f64,
+    /// Carrier phase accumulator (fixed-point representation)
+    carrier_phase: u32,
+    /// Carrier phase step per sample (fixed-point representation)
+    carrier_phase_step: i32,
+    /// Previous pseudorange
+    rho0: TimeRange,
+    /// Signal gain (scaled by 2^7)
+    pub gain: i32,
+}
+
+impl SignalChannel {
+    /// Creates a channel without navigation symbols.
+    ///
+    /// # Arguments
+    /// * `prn` - Satellite number within its system
+    /// * `components` - Signal components
+    /// * `chip_rate` - Nominal chip rate in Hz
+    /// * `carrier_frequency` - Nominal carrier frequency in Hz
+    /// * `carrier_offset` - Carrier frequency in the output in Hz, see
+    ///   `band_offset`
+    /// * `chips_per_symbol` - Number of chips per navigation symbol
+    pub fn new(
+        prn: usize, components: Vec<SignalComponent>, chip_rate: f64,
+        carrier_frequency: f64, carrier_offset: f64, chips_per_symbol: u64,
+    ) -> Self {
+        Self {
+            prn,
+            period_offsets: vec![0; components.len()],
+            components,
+            chip_rate,
+            wavelength: SPEED_OF_LIGHT / carrier_frequency,
+            carrier_offset,
+            chips_per_symbol,
+            symbols: Vec::new(),
+            symbol_start: GpsTime::default(),
+            chip_count: 0.0,
+            code_frequency: chip_rate,
+            carrier_frequency: carrier_offset,
+            carrier_phase: 0,
+            carrier_phase_step: 0,
+            rho0: TimeRange::default(),
+            gain: 0,
+        }
+    }
+
+    /// Returns the last pseudorange of the channel.
+    pub fn rho0(&self) -> &TimeRange {
+        &self.rho0
+    }
+
+    /// Returns the satellite's azimuth and elevation.
+    pub fn azel(&self) -> &Azel {
+        &self.rho0.azel
+    }
+
+    /// Sets the navigation symbols transmitted from a system time on.
+    ///
+    /// Symbols that the previous buffer already holds for the same times are
+    /// kept, so that the symbol being transmitted while the message is
+    /// regenerated does not change.
+    ///
+    /// # Arguments
+    /// * `start` - System time of the start of the first symbol
+    /// * `symbols` - Navigation symbols (+1 or -1)
+    pub fn set_symbols(&mut self, start: &GpsTime, mut symbols: Vec<i8>) {
+        let symbol_duration = self.chips_per_symbol as f64 / self.chip_rate;
+        let offset =
+            (start.diff_secs(&self.symbol_start) / symbol_duration).round();
+        if offset >= 0.0 && (offset as usize) < self.symbols.len() {
+            let kept = &self.symbols[offset as usize..];
+            let count = kept.len().min(symbols.len());
+            symbols[..count].copy_from_slice(&kept[..count]);
+        }
+        self.symbols = symbols;
+        self.symbol_start = start.clone();
+        self.period_offsets = self
+            .components
+            .iter()
+            .map(|component| {
+                (start.sec * self.chip_rate / component.code.len() as f64)
+                    .round() as u64
+            })
+            .collect();
+    }
+
+    /// Initializes the channel state from the pseudorange at the scenario
+    /// time.
+    ///
+    /// # Arguments
+    /// * `rho` - Pseudorange at the current receiver time
+    pub fn start(&mut self, rho: TimeRange) {
+        self.rho0 = rho;
+        self.carrier_phase = 0;
+        self.carrier_frequency = self.carrier_offset;
+    }
+
+    /// Updates the code phase and frequencies from a new pseudorange.
+    ///
+    /// # Arguments
+    /// * `rho1` - Pseudorange at the end of the step
+    /// * `dt` - Duration of the step in seconds
+    /// * `sampling_period` - Receiver sampling period in seconds
+    pub fn update(&mut self, rho1: &TimeRange, dt: f64, sampling_period: f64) {
+        let rate = (rho1.range - self.rho0.range) / dt;
+        self.carrier_frequency = self.carrier_offset - rate / self.wavelength;
+        self.code_frequency =
+            self.chip_rate * (1.0 - rate * SPEED_OF_LIGHT_INV);
+        // Chips transmitted since the symbol start at the start of the step
+        let transmit_time = self.rho0.time.diff_secs(&self.symbol_start)
+            - self.rho0.range * SPEED_OF_LIGHT_INV;
+        self.chip_count = (transmit_time * self.chip_rate).max(0.0);
+        self.carrier_phase_step = (512.0
+            * 65536.0
+            * self.carrier_frequency
+            * sampling_period)
+            .round() as i32;
+        self.rho0 = rho1.clone();
+    }
+
+    /// Returns the I/Q contribution of the current sample and advances the
+    /// channel by one sample.
+    ///
+    /// # Arguments
+    /// * `sampling_period` - Receiver sampling period in seconds
+    ///
+    /// # Returns
+    /// A tuple `(ip, qp)` of the I and Q components
+    pub fn next_sample(&mut self, sampling_period: f64) -> (i32, i32) {
+        let chip = self.chip_count.floor();
+        let fraction = self.chip_count - chip;
+        let chip = chip as u64;
+        let symbol = self
+            .symbols
+            .get((chip / self.chips_per_symbol) as usize)
+            .copied()
+            .unwrap_or(1);
+        let (mut i_level, mut q_level) = (0.0, 0.0);
+        for (component, &period_offset) in
+            self.components.iter().zip(&self.period_offsets)
+        {
+            let length = component.code.len() as u64;
+            let mut value = component.amplitude
+                * f64::from(component.code[(chip % length) as usize])
+                * component.subcarrier.level(fraction);
+            if !component.secondary.is_empty() {
+                let period = period_offset + chip / length;
+                value *= f64::from(
+                    component.secondary
+                        [(period % component.secondary.len() as u64) as usize],
+                );
+            }
+            if component.data {
+                value *= f64::from(symbol);
+            }
+            if component.quadrature {
+                q_level += value;
+            } else {
+                i_level += value;
+            }
+        }
+        let i_table = (self.carrier_phase >> 16 & 0x1ff) as usize;
+        let (cos, sin) = (COS_TABLE512[i_table], SIN_TABLE512[i_table]);
+        let i_gain = (i_level * f64::from(self.gain)).round() as i32;
+        self.chip_count += self.code_frequency * sampling_period;
+        self.carrier_phase = self
+            .carrier_phase
+            .wrapping_add(self.carrier_phase_step as u32);
+        if q_level == 0.0 {
+            return (i_gain * cos, i_gain * sin);
+        }
+        // The quadrature carrier leads the in-phase carrier by 90 degrees
+        let q_gain = (q_level * f64::from(self.gain)).round() as i32;
+        (i_gain * cos - q_gain * sin, i_gain * sin + q_gain * cos)
+    }
+}
+
+/// Bit sequence built field by field, most significant bit first.
+#[derive(Default)]
+pub struct Bits(pub Vec<u8>);
+
+impl Bits {
+    /// Appends the low bits of an unsigned or two's complement value.
+    pub fn push(&mut self, value: u64, len: u32) {
+        self.0
+            .extend((0..len).rev().map(|bit| (value >> bit & 1) as u8));
+    }
+
+    /// Appends a value scaled by 2^scale, rounded, in two's complement.
+    pub fn push_scaled(&mut self, value: f64, scale: i32, len: u32) {
+        self.push((value * 2f64.powi(scale)).round() as i64 as u64, len);
+    }
+
+    /// Appends zero bits.
+    pub fn zeros(&mut self, len: usize) {
+        self.0.resize(self.0.len() + len, 0);
+    }
+
+    /// Appends zero bits up to a length.
+    pub fn pad(&mut self, len: usize) {
+        self.0.resize(len, 0);
+    }
+}
+
+/// State of the generator shared with the other satellite systems at a
+/// simulation step.
+pub struct SystemContext<'a> {
+    /// Current GPS time at the receiver
+    pub time: &'a GpsTime,
+    /// Current receiver position
+    pub location: &'a Ecef,
+    /// Ionospheric and UTC parameters
+    pub ionoutc: &'a IonoUtc,
+    /// Elevation mask in degrees
+    pub elevation_mask: f64,
+    /// Time step between channel updates in seconds
+    pub step: f64,
+    /// Period of the generated samples in seconds, shorter than the output
+    /// sampling period when oversampling
+    pub sampling_period: f64,
+    /// Receiver sampling frequency of the output in Hz
+    pub sample_frequency: f64,
+    /// RF centre frequency of the output in Hz
+    pub center_frequency: f64,
+    /// Frequency the centre frequency is shifted to in the output in Hz
+    pub intermediate_frequency: f64,
+    /// Whether the output holds real samples instead of I/Q samples
+    pub real_output: bool,
+    /// Fixed gain, if path loss is disabled
+    pub fixed_gain: Option<i32>,
+    /// Receiver antenna gain pattern (by boresight angle)
+    pub antenna_pattern: &'a [f64; 37],
+}
+
+/// A satellite system simulated next to GPS L1 C/A.
+///
+/// The generator drives every system through the same steps as its GPS
+/// channels, and adds their samples to the composite output.
+pub trait SignalSystem {
+    /// Returns the name of the system for status output.
+    fn name(&self) -> &'static str;
+
+    /// Returns the nominal carrier frequency of the system in Hz, the
+    /// centre of the frequency channels for FDMA signals.
+    fn carrier_frequency(&self) -> f64;
+
+    /// Selects the ephemerides and allocates the channels of the visible
+    /// satellites at the scenario start.
+    ///
+    /// # Arguments
+    /// * `context` - Generator state at the start time
+    fn initialize(&mut self, context: &SystemContext);
+
+    /// Updates pseudorange, Doppler and gain of all channels for a step.
+    ///
+    /// # Arguments
+    /// * `context` - Generator state at the end of the step
+    fn update_channels(&mut self, context: &SystemContext);
+
+    /// Switches ephemerides, regenerates the navigation messages and
+    /// reallocates channels at a 30-second frame boundary.
+    ///
+    /// # Arguments
+    /// * `context` - Generator state at the frame boundary
+    fn update_navigation(&mut self, context: &SystemContext);
+
+    /// Returns the sum of the I/Q contributions of all channels and advances
+    /// them by one sample.
+    ///
+    /// # Arguments
+    /// * `sampling_period` - Receiver sampling period in seconds
+    fn next_sample(&mut self, sampling_period: f64) -> (i32, i32);
+
+    /// Prints the status of the active channels.
+    fn print_status(&self);
+}
+
+/// Ephemeris of a satellite of a system simulated next to GPS L1 C/A.
+pub trait SystemEphemeris {
+    /// Returns the broadcast health of the satellite, 0 if healthy.
+    fn health(&self) -> i32;
+}
+
+impl SystemEphemeris for Ephemeris {
+    fn health(&self) -> i32 {
+        self.svhlth
+    }
+}
+
+/// A channel of a system simulated next to GPS L1 C/A.
+pub trait SystemChannel {
+    /// Returns the number of the satellite of the channel.
+    fn number(&self) -> usize;
+}
+
+impl SystemChannel for SignalChannel {
+    fn number(&self) -> usize {
+        self.prn
+    }
+}
+
+/// Releases the channels of satellites that are no longer visible or allowed
+/// by the selection, and allocates channels to the newly visible satellites.
+///
+/// Satellites are allocated in order of their number while the system has
+/// fewer than `MAX_CHAN` channels and the selection has capacity. The
+/// channels stay sorted by satellite number.
+///
+/// # Arguments
+/// * `channels` - Channels of the system
+/// * `ephemerides` - Ephemeris in use of each satellite, starting with the
+///   satellite numbered `first`
+/// * `first` - Number of the first satellite
+/// * `selection` - Satellites allowed to occupy a channel
+/// * `visible` - Returns whether a satellite is above the elevation mask
+/// * `open` - Creates the channel of a newly visible satellite, or returns
+///   `None` if the satellite cannot be simulated
+pub fn allocate_channels<C: SystemChannel, E: SystemEphemeris>(
+    channels: &mut Vec<C>, ephemerides: &[Option<E>], first: usize,
+    selection: &SatelliteSelection, visible: impl Fn(usize, &E) -> bool,
+    mut open: impl FnMut(usize, &E) -> Option<C>,
+) {
+    let allowed = |number: usize, eph: &E| {
+        selection.allows(number, eph.health()) && visible(number, eph)
+    };
+    channels.retain(|channel| {
+        let number = channel.number();
+        number
+            .checked_sub(first)
+            .and_then(|index| ephemerides.get(index))
+            .and_then(Option::as_ref)
+            .is_some_and(|eph| allowed(number, eph))
+    });
+    for (index, eph) in ephemerides.iter().enumerate() {
+        if channels.len() >= MAX_CHAN || !selection.has_capacity(channels.len())
+        {
+            break;
+        }
+        let number = index + first;
+        let Some(eph) = eph else {
+            continue;
+        };
+        if channels.iter().any(|channel| channel.number() == number)
+            || !allowed(number, eph)
+        {
+            continue;
+        }
+        if let Some(channel) = open(number, eph) {
+            channels.push(channel);
+        }
+    }
+    channels.sort_by_key(SystemChannel::number);
+}
+
+/// Checks whether a satellite with a broadcast ephemeris of the GPS kind is
+/// above the elevation mask at the current receiver time.
+///
+/// # Arguments
+/// * `eph` - Ephemeris of the satellite
+/// * `context` - Generator state with the receiver time and position
+pub fn is_visible(eph: &Ephemeris, context: &SystemContext) -> bool {
+    matches!(
+        eph.check_visibility(
+            context.time,
+            context.location,
+            context.elevation_mask
+        ),
+        Some((_, true))
+    )
+}
+
+/// Prints the status line of a channel.
+///
+/// # Arguments
+/// * `satellite` - Satellite as written in RINEX files, e.g. `E11`
+/// * `azel` - Azimuth and elevation of the satellite
+/// * `rho` - Last pseudorange of the channel
+/// * `signal` - Name of the signal, if the system has several
+pub fn print_channel_status(
+    satellite: &str, azel: &Azel, rho: &TimeRange, signal: Option<&str>,
+) {
+    let status = format!(
+        "{satellite} {:6.1} {:5.1} {:11.1} {:5.1}",
+        azel.az * R2D,
+        azel.el * R2D,
+        rho.distance,
+        rho.iono_delay,
+    );
+    match signal {
+        Some(signal) => eprintln!("{status} {signal}"),
+        None => eprintln!("{status}"),
+    }
+}
+
+/// Computes the signal gain of a satellite from its path loss and the
+/// receiver antenna pattern.
+///
+/// # Arguments
+/// * `rho` - Range to the satellite
+/// * `fixed_gain` - Fixed gain, if path loss is disabled
+/// * `antenna_pattern` - Receiver antenna gain pattern (by boresight angle)
+///
+/// # Returns
+/// The gain scaled by 2^7
+pub fn signal_gain(
+    rho: &TimeRange, fixed_gain: Option<i32>, antenna_pattern: &[f64; 37],
+) -> i32 {
+    if let Some(fixed_gain) = fixed_gain {
+        // Fixed gain mode
+        fixed_gain // hold the power level constant
+    } else {
+        // With path loss compensation
+        // Path loss
+        let path_loss = 20_200_000.0 / rho.distance;
+        // Receiver antenna gain
+        let boresight_angle_index = ((90.0 - rho.azel.el * R2D) / 5.0) as usize; // covert elevation to boresight
+        let ant_gain = antenna_pattern[boresight_angle_index];
+        (path_loss * ant_gain * 128.0) as i32 // scaled by 2^7
+    }
+}
+
+/// Checks whether a signal fits within the output band.
+///
+/// The output spans the sampling frequency around the RF centre frequency,
+/// which appears at the intermediate frequency. A signal fits if its carrier
+/// and the main lobe of its spectrum up to `half_bandwidth` lie within the
+/// Nyquist band, which only covers positive frequencies for real output.
+///
+/// # Arguments
+/// * `context` - Generator state with the centre and sampling frequencies
+/// * `carrier` - Carrier frequency of the signal in Hz
+/// * `half_bandwidth` - Half the main lobe bandwidth of the signal in Hz
+///
+/// # Returns
+/// The carrier frequency in the output in Hz, or `None` if the signal does
+/// not fit
+pub fn band_offset(
+    context: &SystemContext, carrier: f64, half_bandwidth: f64,
+) -> Option<f64> {
+    let offset =
+        carrier - context.center_frequency + context.intermediate_frequency;
+    let fits = if context.real_output {
+        offset >= half_bandwidth
+            && 2.0 * (offset + half_bandwidth) <= context.sample_frequency
+    } else {
+        2.0 * (offset.abs() + half_bandwidth) <= context.sample_frequency
+    };
+    fits.then_some(offset)
+}
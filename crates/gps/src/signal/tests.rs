@@ -0,0 +1,146 @@
+use constants::{B1I_FREQ, CARR_FREQ, CODE_FREQ, MAX_CHAN};
+use geometry::Ecef;
+use test_case::test_case;
+
+use super::{
+    SystemChannel, SystemContext, SystemEphemeris, allocate_channels,
+    band_offset,
+};
+use crate::{
+    datetime::GpsTime,
+    generator::{HealthMode, SatelliteSelection},
+    ionoutc::IonoUtc,
+};
+
+/// Health word of a test satellite.
+struct Health(i32);
+
+impl SystemEphemeris for Health {
+    fn health(&self) -> i32 {
+        self.0
+    }
+}
+
+/// Channel of a test satellite.
+struct Number(usize);
+
+impl SystemChannel for Number {
+    fn number(&self) -> usize {
+        self.0
+    }
+}
+
+/// Returns the satellite numbers of the channels.
+fn numbers(channels: &[Number]) -> Vec<usize> {
+    channels.iter().map(SystemChannel::number).collect()
+}
+
+/// Returns healthy ephemerides of `count` satellites.
+fn healthy(count: usize) -> Vec<Option<Health>> {
+    (0..count).map(|_| Some(Health(0))).collect()
+}
+
+#[test]
+fn channels_are_capped() {
+    let ephemerides = healthy(30);
+    let mut channels = Vec::new();
+    allocate_channels(
+        &mut channels,
+        &ephemerides,
+        1,
+        &SatelliteSelection::default(),
+        |_, _| true,
+        |number, _| Some(Number(number)),
+    );
+    assert_eq!(numbers(&channels), (1..=MAX_CHAN).collect::<Vec<_>>());
+}
+
+#[test]
+fn channels_follow_limit_and_visibility() {
+    let ephemerides = healthy(10);
+    let selection = SatelliteSelection {
+        max_satellites: Some(3),
+        ..SatelliteSelection::default()
+    };
+    let mut channels = vec![Number(127), Number(122)];
+    // Satellite 122 has set, 123 cannot be simulated
+    allocate_channels(
+        &mut channels,
+        &ephemerides,
+        120,
+        &selection,
+        |number, _| number != 122,
+        |number, _| (number != 123).then_some(Number(number)),
+    );
+    assert_eq!(numbers(&channels), [120, 121, 127]);
+    // Satellites beyond the ephemerides are released
+    channels.push(Number(200));
+    allocate_channels(
+        &mut channels,
+        &ephemerides,
+        120,
+        &selection,
+        |_, _| true,
+        |number, _| Some(Number(number)),
+    );
+    assert_eq!(numbers(&channels), [120, 121, 127]);
+}
+
+#[test]
+fn unhealthy_satellites_follow_health_mode() {
+    let ephemerides = vec![Some(Health(0)), None, Some(Health(1))];
+    for (health_mode, expected) in [
+        (HealthMode::Simulate, vec![1, 3]),
+        (HealthMode::Skip, vec![1]),
+        (HealthMode::ForceHealthy, vec![1, 3]),
+    ] {
+        let selection = SatelliteSelection {
+            health_mode,
+            ..SatelliteSelection::default()
+        };
+        let mut channels = vec![Number(3)];
+        allocate_channels(
+            &mut channels,
+            &ephemerides,
+            1,
+            &selection,
+            |_, _| true,
+            |number, _| Some(Number(number)),
+        );
+        assert_eq!(numbers(&channels), expected);
+    }
+}
+
+// Complex output spans -fs/2 to fs/2 around the intermediate frequency
+#[test_case(CARR_FREQ, 2.6e6, 0.0, false, Some(0.0))]
+#[test_case(CARR_FREQ, 2.6e6, 0.5e6, false, None)]
+#[test_case(CARR_FREQ, 4.092e6, 1.023e6, false, Some(1.023e6))]
+#[test_case(CARR_FREQ, 4.092e6, -1.023e6, false, Some(-1.023e6))]
+#[test_case(B1I_FREQ, 4.092e6, 0.0, false, None)]
+#[test_case(B1I_FREQ, 40e6, 0.0, false, Some(B1I_FREQ - CARR_FREQ))]
+// Real output only spans 0 to fs/2
+#[test_case(CARR_FREQ, 4.092e6, 1.023e6, true, Some(1.023e6))]
+#[test_case(CARR_FREQ, 4.092e6, 1.0e6, true, None)]
+#[test_case(CARR_FREQ, 4.092e6, 1.1e6, true, None)]
+#[test_case(CARR_FREQ, 4.092e6, -1.023e6, true, None)]
+#[test_case(B1I_FREQ, 40e6, 16e6, true, Some(B1I_FREQ - CARR_FREQ + 16e6))]
+fn band_offset_in_output(
+    carrier: f64, sample_frequency: f64, intermediate_frequency: f64,
+    real_output: bool, expected: Option<f64>,
+) {
+    let context = SystemContext {
+        time: &GpsTime::default(),
+        location: &Ecef::default(),
+        ionoutc: &IonoUtc::default(),
+        elevation_mask: 0.0,
+        step: 0.1,
+        sampling_period: sample_frequency.recip(),
+        sample_frequency,
+        center_frequency: CARR_FREQ,
+        intermediate_frequency,
+        real_output,
+        fixed_gain: None,
+        antenna_pattern: &[0.0; 37],
+    };
+    assert_eq!(band_offset(&context, carrier, CODE_FREQ), expected);
+}
@@ -0,0 +1,407 @@
+use constants::{
+    MAX_SAT, N_DWRD_SBF, OMEGA_EARTH, PI, POW2_M11, POW2_M19, POW2_M20,
+    POW2_M21, POW2_M23, POW2_M24, POW2_M27, POW2_M30, POW2_M38, POW2_M50,
+};
+
+use crate::{
+    datetime::GpsTime, ephemeris::Ephemeris, error::Error, ionoutc::IonoUtc,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Number of pages of subframes 4 and 5 in a superframe.
+const PAGES: usize = 25;
+
+/// Length of a navigation message frame in seconds.
+const FRAME_LENGTH: f64 = 30.0;
+
+/// Interval of the almanac reference times in seconds.
+const TOA_INTERVAL: f64 = 4096.0;
+
+/// Maximum number of characters of the special message.
+const SPECIAL_MESSAGE_LENGTH: usize = 22;
+
+/// Reference inclination that almanac inclinations are relative to, in
+/// semicircles.
+const REFERENCE_INCLINATION: f64 = 0.30;
+
+/// Telemetry word with the preamble, common to all subframes.
+const TLM_WORD: u32 = 0x008b_0000 << 6;
+
+/// Data ID of the GPS navigation message.
+const DATA_ID: u32 = 1;
+
+/// Alternating ones and zeros filling the data bits of dummy almanac pages.
+const ALTERNATING: u32 = 0x00aa_aaaa;
+
+/// Signal configuration of a satellite in subframe 4 page 25: A-S off,
+/// Block II and later signal capabilities.
+const CONFIGURATION: u32 = 0b0001;
+
+/// Six-bit health of a satellite that is not in the almanac.
+const NOT_AVAILABLE: u32 = 0x3f;
+
+/// SV ID of each page of subframe 4 (index page - 1). IDs 25 to 32 are
+/// almanac pages of that PRN, the others identify the page contents.
+const SUBFRAME4_SV_IDS: [u32; PAGES] = [
+    57, 25, 26, 27, 28, 57, 29, 30, 31, 32, 57, 62, 52, 53, 54, 57, 55, 56, 58,
+    59, 57, 60, 61, 62, 63,
+];
+
+/// SV ID of subframe 4 page 17, the special message.
+const SPECIAL_MESSAGE_SV_ID: u32 = 55;
+
+/// SV ID of subframe 4 page 18, the ionospheric and UTC parameters.
+const IONO_UTC_SV_ID: u32 = 56;
+
+/// SV ID of reserved subframe 4 pages.
+const RESERVED_SV_ID: u32 = 57;
+
+/// SV ID of subframe 4 page 25, the configurations and the health of
+/// satellites 25 to 32.
+const SUBFRAME4_HEALTH_SV_ID: u32 = 63;
+
+/// SV ID of subframe 5 page 25, the almanac reference time and the health of
+/// satellites 1 to 24.
+const SUBFRAME5_HEALTH_SV_ID: u32 = 51;
+
+/// Pages of navigation message subframes 4 and 5 over a 12.5-minute
+/// superframe.
+///
+/// Subframes 4 and 5 carry a different page in each 30-second frame and
+/// repeat after 25 frames, counted from the start of the GPS week:
+/// - Subframe 5 pages 1 to 24 and subframe 4 pages 2 to 5 and 7 to 10 hold the
+///   almanacs of PRN 1 to 32, or dummy pages for satellites without one
+/// - Subframe 5 page 25 holds the almanac reference time and the health of
+///   satellites 1 to 24
+/// - Subframe 4 page 25 holds the signal configurations of all satellites and
+///   the health of satellites 25 to 32
+/// - Subframe 4 page 18 holds the ionospheric and UTC parameters
+/// - Subframe 4 page 17 holds the special message, if one is set
+/// - The other subframe 4 pages are reserved and zero-filled
+///
+/// The almanacs are derived from the current ephemerides, propagated to a
+/// common reference time at or before the start of the scenario.
+#[derive(Clone)]
+pub struct SuperframePages {
+    /// Reference time of the almanac
+    toa: GpsTime,
+    /// Special message padded with blanks, if any
+    message: Option<[u8; SPECIAL_MESSAGE_LENGTH]>,
+    /// Subframe 4 and 5 words of each page (index page - 1)
+    pages: [[[u32; N_DWRD_SBF]; 2]; PAGES],
+}
+
+impl SuperframePages {
+    /// Creates the pages of a scenario, with empty almanacs until `update`
+    /// is called.
+    ///
+    /// # Arguments
+    /// * `start` - Start time of the scenario; the almanac reference time is
+    ///   the multiple of 4096 seconds of week at or before it
+    /// * `message` - Special message of up to 22 printable ASCII characters
+    ///
+    /// # Errors
+    /// * `Error::InvalidSpecialMessage` - If the message is too long or has
+    ///   characters other than printable ASCII
+    pub fn new(start: &GpsTime, message: Option<&str>) -> Result<Self, Error> {
+        let message = message
+            .map(|text| {
+                if text.len() > SPECIAL_MESSAGE_LENGTH
+                    || !text.bytes().all(|c| c == b' ' || c.is_ascii_graphic())
+                {
+                    return Err(Error::invalid_special_message(text));
+                }
+                let mut padded = [b' '; SPECIAL_MESSAGE_LENGTH];
+                padded[..text.len()].copy_from_slice(text.as_bytes());
+                Ok(padded)
+            })
+            .transpose()?;
+        Ok(Self {
+            toa: GpsTime {
+                week: start.week,
+                sec: (start.sec / TOA_INTERVAL).floor() * TOA_INTERVAL,
+            },
+            message,
+            pages: [[[0; N_DWRD_SBF]; 2]; PAGES],
+        })
+    }
+
+    /// Rebuilds all pages from the current ephemerides.
+    ///
+    /// # Arguments
+    /// * `ephemerides` - Current ephemeris of each satellite (index PRN - 1);
+    ///   satellites without a valid one get dummy almanac pages
+    /// * `ionoutc` - Ionospheric and UTC parameters for page 18
+    pub fn update(&mut self, ephemerides: &[Ephemeris], ionoutc: &IonoUtc) {
+        let ephemeris =
+            |prn: usize| ephemerides.get(prn - 1).filter(|eph| eph.vflg);
+        let almanac = |prn: usize, subframe: u32| match ephemeris(prn) {
+            Some(eph) => almanac_page(subframe, prn as u32, eph, &self.toa),
+            None => dummy_page(subframe),
+        };
+        let health: [u32; MAX_SAT] = std::array::from_fn(|sv| {
+            ephemeris(sv + 1)
+                .map_or(NOT_AVAILABLE, |eph| eph.svhlth as u32 & 0x3f)
+        });
+        let configurations: [u32; MAX_SAT] = std::array::from_fn(|sv| {
+            if ephemeris(sv + 1).is_some() {
+                CONFIGURATION
+            } else {
+                0
+            }
+        });
+        for (index, page) in self.pages.iter_mut().enumerate() {
+            let sv_id = SUBFRAME4_SV_IDS[index];
+            page[0] = match sv_id {
+                1..=32 => almanac(sv_id as usize, 4),
+                SPECIAL_MESSAGE_SV_ID => match &self.message {
+                    Some(message) => special_message_page(message),
+                    None => reserved_page(4, sv_id),
+                },
+                IONO_UTC_SV_ID if ionoutc.vflg => iono_utc_page(ionoutc),
+                IONO_UTC_SV_ID => reserved_page(4, RESERVED_SV_ID),
+                SUBFRAME4_HEALTH_SV_ID => {
+                    subframe4_health_page(&configurations, &health)
+                }
+                _ => reserved_page(4, sv_id),
+            };
+            page[1] = if index < PAGES - 1 {
+                almanac(index + 1, 5)
+            } else {
+                subframe5_health_page(&self.toa, &health)
+            };
+        }
+    }
+
+    /// Returns the subframe 4 and 5 words of the frame starting at a time.
+    ///
+    /// # Arguments
+    /// * `time` - Start time of the frame, a multiple of 30 seconds of week
+    pub fn frame(&self, time: &GpsTime) -> &[[u32; N_DWRD_SBF]; 2] {
+        let frame = (time.sec / FRAME_LENGTH + 0.5) as usize;
+        &self.pages[frame % PAGES]
+    }
+}
+
+/// Builds subframe 4 page 18 with the ionospheric and UTC parameters.
+///
+/// # Arguments
+/// * `ionoutc` - Ionospheric and UTC parameters
+pub(crate) fn iono_utc_page(ionoutc: &IonoUtc) -> [u32; N_DWRD_SBF] {
+    let wnlsf;
+    let dtlsf;
+    let dn;
+    let alpha0 = (ionoutc.alpha0 / POW2_M30).round() as i32;
+    let alpha1 = (ionoutc.alpha1 / POW2_M27).round() as i32;
+    let alpha2 = (ionoutc.alpha2 / POW2_M24).round() as i32;
+    let alpha3 = (ionoutc.alpha3 / POW2_M24).round() as i32;
+    let beta0 = (ionoutc.beta0 / 2048.0).round() as i32;
+    let beta1 = (ionoutc.beta1 / 16384.0).round() as i32;
+    let beta2 = (ionoutc.beta2 / 65536.0).round() as i32;
+    let beta3 = (ionoutc.beta3 / 65536.0).round() as i32;
+
+    #[allow(non_snake_case)]
+    let A0 = (ionoutc.A0 / POW2_M30).round() as i32;
+
+    #[allow(non_snake_case)]
+    let A1 = (ionoutc.A1 / POW2_M50).round() as i32;
+    let dtls = ionoutc.dtls;
+    let tot = (ionoutc.tot / 4096) as u32;
+    let week_number = (ionoutc.week_number % 256) as u32;
+    // 2016/12/31 (Sat) -> WNlsf = 1929, DN = 7 (http://navigationservices.agi.com/GNSSWeb/)
+    // Days are counted from 1 to 7 (Sunday is 1).
+    if ionoutc.leapen == 1 {
+        wnlsf = (ionoutc.wnlsf % 256) as u32;
+        dn = ionoutc.day_number as u32;
+        dtlsf = ionoutc.dtlsf as u32;
+    } else {
+        wnlsf = (1929 % 256) as u32;
+        dn = 7;
+        dtlsf = 18;
+    }
+    [
+        TLM_WORD,
+        0x4 << 8,
+        DATA_ID << 28
+            | IONO_UTC_SV_ID << 22
+            | (alpha0 as u32 & 0xff) << 14
+            | (alpha1 as u32 & 0xff) << 6,
+        (alpha2 as u32 & 0xff) << 22
+            | (alpha3 as u32 & 0xff) << 14
+            | (beta0 as u32 & 0xff) << 6,
+        (beta1 as u32 & 0xff) << 22
+            | (beta2 as u32 & 0xff) << 14
+            | (beta3 as u32 & 0xff) << 6,
+        (A1 as u32 & 0x00ff_ffff) << 6,
+        ((A0 >> 8) as u32 & 0x00ff_ffff) << 6,
+        (A0 as u32 & 0xff) << 22
+            | (tot & 0xff) << 14
+            | (week_number & 0xff) << 6,
+        (dtls as u32 & 0xff) << 22 | (wnlsf & 0xff) << 14 | (dn & 0xff) << 6,
+        (dtlsf & 0xff) << 22,
+    ]
+}
+
+/// Builds a reserved page with only the data and SV IDs set.
+///
+/// # Arguments
+/// * `subframe` - Subframe ID (4 or 5)
+/// * `sv_id` - SV ID of the page
+pub(crate) fn reserved_page(subframe: u32, sv_id: u32) -> [u32; N_DWRD_SBF] {
+    let mut words = [0; N_DWRD_SBF];
+    words[0] = TLM_WORD;
+    words[1] = subframe << 8;
+    words[2] = DATA_ID << 28 | sv_id << 22;
+    words
+}
+
+/// Builds the almanac page of a satellite from its ephemeris.
+///
+/// The orbit and clock are propagated from the reference times of the
+/// ephemeris to the almanac reference time, so that a receiver evaluating
+/// the almanac gets the ephemeris orbit without its harmonic corrections.
+///
+/// # Arguments
+/// * `subframe` - Subframe ID (4 or 5)
+/// * `prn` - PRN number of the satellite
+/// * `eph` - Ephemeris of the satellite
+/// * `toa` - Almanac reference time
+fn almanac_page(
+    subframe: u32, prn: u32, eph: &Ephemeris, toa: &GpsTime,
+) -> [u32; N_DWRD_SBF] {
+    let dt = toa.diff_secs(&eph.toe);
+    let clock_dt = toa.diff_secs(&eph.toc);
+    // The node longitude is referred to the start of the week of the
+    // reference time, which differs from that of the ephemeris across a week
+    // boundary
+    let node = eph.omg0 + eph.omgdot * dt
+        - OMEGA_EARTH * (dt - (toa.sec - eph.toe.sec));
+    let ecc = (eph.ecc / POW2_M21).round() as u32;
+    let toa_field = (toa.sec / TOA_INTERVAL) as u32;
+    let delta_i = (((eph.inc0 + eph.idot * dt) / PI - REFERENCE_INCLINATION)
+        / POW2_M19)
+        .round() as i32;
+    let omgdot = (eph.omgdot / PI / POW2_M38).round() as i32;
+    let health = almanac_health(eph.svhlth);
+    let sqrta = (eph.sqrta / POW2_M11).round() as u32;
+    let omg0 = semicircles(node);
+    let aop = semicircles(eph.aop);
+    let m0 = semicircles(eph.m0 + eph.n * dt);
+    let af0 = ((eph.af0 + eph.af1 * clock_dt + eph.af2 * clock_dt * clock_dt)
+        / POW2_M20)
+        .round() as i32;
+    let af1 = ((eph.af1 + 2.0 * eph.af2 * clock_dt) / POW2_M38).round() as i32;
+    [
+        TLM_WORD,
+        subframe << 8,
+        DATA_ID << 28 | (prn & 0x3f) << 22 | (ecc & 0xffff) << 6,
+        (toa_field & 0xff) << 22 | (delta_i as u32 & 0xffff) << 6,
+        (omgdot as u32 & 0xffff) << 14 | (health & 0xff) << 6,
+        (sqrta & 0x00ff_ffff) << 6,
+        (omg0 as u32 & 0x00ff_ffff) << 6,
+        (aop as u32 & 0x00ff_ffff) << 6,
+        (m0 as u32 & 0x00ff_ffff) << 6,
+        ((af0 >> 3) as u32 & 0xff) << 22
+            | (af1 as u32 & 0x7ff) << 11
+            | (af0 as u32 & 0x7) << 8,
+    ]
+}
+
+/// Builds the almanac page of a satellite without an almanac: SV ID 0 and
+/// alternating ones and zeros in the data bits.
+///
+/// # Arguments
+/// * `subframe` - Subframe ID (4 or 5)
+fn dummy_page(subframe: u32) -> [u32; N_DWRD_SBF] {
+    let mut words = [ALTERNATING << 6; N_DWRD_SBF];
+    words[0] = TLM_WORD;
+    words[1] = subframe << 8;
+    words[2] = DATA_ID << 28 | (ALTERNATING & 0xffff) << 6;
+    words[9] = (ALTERNATING >> 2) << 8;
+    words
+}
+
+/// Builds subframe 4 page 17 with the special message.
+///
+/// # Arguments
+/// * `message` - The 22 characters of the message
+fn special_message_page(
+    message: &[u8; SPECIAL_MESSAGE_LENGTH],
+) -> [u32; N_DWRD_SBF] {
+    let c = |i: usize| u32::from(message[i]);
+    let mut words = reserved_page(4, SPECIAL_MESSAGE_SV_ID);
+    words[2] |= c(0) << 14 | c(1) << 6;
+    for (word, chars) in words[3..9].iter_mut().zip((2..20).step_by(3)) {
+        *word = c(chars) << 22 | c(chars + 1) << 14 | c(chars + 2) << 6;
+    }
+    words[9] = c(20) << 22 | c(21) << 14;
+    words
+}
+
+/// Builds subframe 4 page 25 with the signal configurations of all
+/// satellites and the health of satellites 25 to 32.
+///
+/// # Arguments
+/// * `configurations` - Four-bit configuration of each satellite
+/// * `health` - Six-bit health of each satellite
+fn subframe4_health_page(
+    configurations: &[u32; MAX_SAT], health: &[u32; MAX_SAT],
+) -> [u32; N_DWRD_SBF] {
+    let mut words = reserved_page(4, SUBFRAME4_HEALTH_SV_ID);
+    // Configurations of satellites 1 to 4 in word 3, then six per word
+    for (i, configuration) in configurations.iter().enumerate() {
+        let (word, slot) = if i < 4 {
+            (2, i + 2)
+        } else {
+            (3 + (i - 4) / 6, (i - 4) % 6)
+        };
+        words[word] |= (configuration & 0xf) << (26 - 4 * slot);
+    }
+    words[7] |= (health[24] & 0x3f) << 6;
+    for (i, sv_health) in health[25..].iter().enumerate() {
+        words[8 + i / 4] |= (sv_health & 0x3f) << (24 - 6 * (i % 4));
+    }
+    words
+}
+
+/// Builds subframe 5 page 25 with the almanac reference time and the health
+/// of satellites 1 to 24.
+///
+/// # Arguments
+/// * `toa` - Almanac reference time
+/// * `health` - Six-bit health of each satellite
+fn subframe5_health_page(
+    toa: &GpsTime, health: &[u32; MAX_SAT],
+) -> [u32; N_DWRD_SBF] {
+    let toa_field = (toa.sec / TOA_INTERVAL) as u32;
+    let wna = (toa.week % 256) as u32;
+    let mut words = reserved_page(5, SUBFRAME5_HEALTH_SV_ID);
+    words[2] |= (toa_field & 0xff) << 14 | (wna & 0xff) << 6;
+    for (i, sv_health) in health[..24].iter().enumerate() {
+        words[3 + i / 4] |= (sv_health & 0x3f) << (24 - 6 * (i % 4));
+    }
+    words
+}
+
+/// Converts the eight-bit almanac health from the six-bit ephemeris health:
+/// all navigation data are flagged bad when the summary bit is set, and the
+/// signal health is kept.
+///
+/// # Arguments
+/// * `svhlth` - Six-bit health of the ephemeris
+fn almanac_health(svhlth: i32) -> u32 {
+    let svhlth = svhlth as u32;
+    let data_health = if svhlth & 0x20 != 0 { 0b111 } else { 0 };
+    data_health << 5 | svhlth & 0x1f
+}
+
+/// Converts an angle in radians to 24-bit semicircles at scale 2^-23, wrapped
+/// to [-1, 1) semicircles.
+///
+/// # Arguments
+/// * `angle` - Angle in radians
+fn semicircles(angle: f64) -> i32 {
+    (((angle / PI + 1.0).rem_euclid(2.0) - 1.0) / POW2_M23).round() as i32
+}
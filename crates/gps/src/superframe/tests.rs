@@ -0,0 +1,107 @@
+use constants::N_DWRD_SBF;
+
+use super::{
+    IONO_UTC_SV_ID, PAGES, RESERVED_SV_ID, SUBFRAME4_SV_IDS,
+    SUBFRAME5_HEALTH_SV_ID, SuperframePages,
+};
+use crate::{
+    Error, NominalConstellation, datetime::GpsTime, ephemeris::Ephemeris,
+};
+
+/// Returns the subframe ID of the handover word and the SV ID of a page.
+fn page_ids(words: &[u32; N_DWRD_SBF]) -> (u32, u32) {
+    (words[1] >> 8 & 0x7, words[2] >> 22 & 0x3f)
+}
+
+/// Builds the pages of a scenario with the nominal ephemerides of PRN 1 to
+/// 32, leaving out `missing`.
+fn pages(
+    start: &GpsTime, missing: usize, iono_utc: bool,
+) -> Result<SuperframePages, Error> {
+    let constellation = NominalConstellation::new(32)?;
+    let ephemerides = (1..=32)
+        .map(|prn| {
+            (prn != missing)
+                .then(|| constellation.ephemeris(prn, start))
+                .flatten()
+                .unwrap_or_default()
+        })
+        .collect::<Vec<Ephemeris>>();
+    let mut ionoutc = NominalConstellation::iono_utc(start);
+    ionoutc.vflg = iono_utc;
+    let mut pages = SuperframePages::new(start, Some("SIMULATED"))?;
+    pages.update(&ephemerides, &ionoutc);
+    Ok(pages)
+}
+
+#[test]
+fn page_schedule() -> Result<(), Error> {
+    let start = GpsTime {
+        week: 2190,
+        sec: 86250.0,
+    };
+    let pages = pages(&start, 0, true)?;
+    // Two superframes from the start of a superframe of the week
+    for frame in 0..2 * PAGES {
+        let time = start.add_secs(30.0 * frame as f64);
+        let [subframe4, subframe5] = pages.frame(&time);
+        let page = frame % PAGES;
+        assert_eq!(page_ids(subframe4), (4, SUBFRAME4_SV_IDS[page]));
+        let sv_id = if page < 24 {
+            page as u32 + 1
+        } else {
+            SUBFRAME5_HEALTH_SV_ID
+        };
+        assert_eq!(page_ids(subframe5), (5, sv_id));
+    }
+    // Almanacs of PRN 25 to 32 in subframe 4 pages 2 to 5 and 7 to 10
+    let almanac_pages = (1..=PAGES)
+        .filter(|&page| (25..=32).contains(&SUBFRAME4_SV_IDS[page - 1]))
+        .collect::<Vec<_>>();
+    assert_eq!(almanac_pages, [2, 3, 4, 5, 7, 8, 9, 10]);
+    Ok(())
+}
+
+#[test]
+fn page_schedule_follows_week() -> Result<(), Error> {
+    // 86250 s is a multiple of the 750 s superframe, 86280 s is not
+    let aligned = GpsTime {
+        week: 2190,
+        sec: 86250.0,
+    };
+    let offset = GpsTime {
+        week: 2190,
+        sec: 86280.0,
+    };
+    let pages = pages(&aligned, 0, true)?;
+    assert_eq!(page_ids(&pages.frame(&aligned)[0]).1, SUBFRAME4_SV_IDS[0]);
+    assert_eq!(page_ids(&pages.frame(&offset)[0]).1, SUBFRAME4_SV_IDS[1]);
+    assert_eq!(page_ids(&pages.frame(&offset)[1]).1, 2);
+    // Page 18 of the frame at 17 * 30 s into the superframe
+    let page18 = aligned.add_secs(17.0 * 30.0);
+    assert_eq!(page_ids(&pages.frame(&page18)[0]).1, IONO_UTC_SV_ID);
+    Ok(())
+}
+
+#[test]
+fn missing_data_pages() -> Result<(), Error> {
+    let start = GpsTime {
+        week: 2190,
+        sec: 86250.0,
+    };
+    // No almanac of PRN 3 (subframe 5 page 3) and PRN 28 (subframe 4 page
+    // 5), and no ionospheric and UTC parameters
+    let pages_without_3 = pages(&start, 3, false)?;
+    let pages_without_28 = pages(&start, 28, false)?;
+    let frame = |pages: &SuperframePages, page: usize| {
+        *pages.frame(&start.add_secs(30.0 * (page - 1) as f64))
+    };
+    assert_eq!(page_ids(&frame(&pages_without_3, 3)[1]).1, 0);
+    assert_eq!(page_ids(&frame(&pages_without_3, 5)[0]).1, 28);
+    assert_eq!(page_ids(&frame(&pages_without_28, 5)[0]).1, 0);
+    assert_eq!(page_ids(&frame(&pages_without_28, 3)[1]).1, 3);
+    assert_eq!(page_ids(&frame(&pages_without_3, 18)[0]).1, RESERVED_SV_ID);
+    // The special message page
+    assert_eq!(page_ids(&frame(&pages_without_3, 17)[0]).1, 55);
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use constants::{MAX_SAT, SECONDS_IN_HOUR};
+
+use crate::{datetime::GpsTime, ephemeris::Ephemeris};
+
+#[cfg(test)]
+mod tests;
+
+/// Curve fit interval of ephemerides that do not state one, in hours.
+const DEFAULT_FIT_INTERVAL: f64 = 4.0;
+
+/// Broadcast ephemerides of all satellites, ordered by time of clock.
+///
+/// Each satellite keeps its own growable list of ephemerides, so navigation
+/// data from several files spanning any number of days can be merged. A
+/// record already present with the same IODE and TOE is not added twice.
+#[derive(Clone)]
+pub struct EphemerisTimeline {
+    /// Ephemerides of each satellite (index PRN - 1), sorted by time of clock
+    satellites: Vec<Vec<Ephemeris>>,
+}
+
+impl Default for EphemerisTimeline {
+    fn default() -> Self {
+        Self::new(MAX_SAT)
+    }
+}
+
+impl EphemerisTimeline {
+    /// Creates an empty timeline for a number of satellites.
+    ///
+    /// # Arguments
+    /// * `satellites` - Highest PRN number of the constellation
+    pub fn new(satellites: usize) -> Self {
+        Self {
+            satellites: vec![Vec::new(); satellites],
+        }
+    }
+
+    /// Adds an ephemeris to the timeline of a satellite.
+    ///
+    /// The ephemeris is placed after all ephemerides with an earlier or equal
+    /// time of clock. Duplicates of an ephemeris already in the timeline,
+    /// identified by IODE and TOE, are dropped.
+    ///
+    /// # Arguments
+    /// * `prn` - PRN number of the satellite (1 to `MAX_SAT`)
+    /// * `ephemeris` - Ephemeris to add
+    ///
+    /// # Returns
+    /// * `true` - If the ephemeris was added
+    /// * `false` - If the PRN is out of range or the ephemeris is a duplicate
+    pub fn insert(&mut self, prn: usize, ephemeris: Ephemeris) -> bool {
+        let Some(timeline) = prn
+            .checked_sub(1)
+            .and_then(|sv| self.satellites.get_mut(sv))
+        else {
+            return false;
+        };
+        if timeline.iter().any(|eph| eph.same_data_set(&ephemeris)) {
+            return false;
+        }
+        let index = timeline
+            .partition_point(|eph| eph.toc.diff_secs(&ephemeris.toc) <= 0.0);
+        timeline.insert(index, ephemeris);
+        true
+    }
+
+    /// Returns whether the timeline holds no ephemeris.
+    pub fn is_empty(&self) -> bool {
+        self.satellites.iter().all(Vec::is_empty)
+    }
+
+    /// Selects the ephemeris of a satellite to broadcast at a given time.
+    ///
+    /// As in gps-sdr-sim, an ephemeris is taken into use an hour before its
+    /// time of clock. The latest such ephemeris is selected if the time lies
+    /// within its curve fit interval around the time of ephemeris.
+    ///
+    /// # Arguments
+    /// * `prn` - PRN number of the satellite
+    /// * `time` - GPS time of the selection
+    ///
+    /// # Returns
+    /// * `Some(ephemeris)` - The ephemeris to use
+    /// * `None` - If no ephemeris of the satellite is valid at that time
+    pub fn select(&self, prn: usize, time: &GpsTime) -> Option<&Ephemeris> {
+        let timeline =
+            prn.checked_sub(1).and_then(|sv| self.satellites.get(sv))?;
+        let due = timeline
+            .partition_point(|eph| eph.toc.diff_secs(time) < SECONDS_IN_HOUR);
+        let eph = timeline.get(due.checked_sub(1)?)?;
+        let fit_interval = if eph.fit_interval > 0.0 {
+            eph.fit_interval
+        } else {
+            DEFAULT_FIT_INTERVAL
+        };
+        (time.diff_secs(&eph.toe).abs() <= fit_interval * SECONDS_IN_HOUR / 2.0)
+            .then_some(eph)
+    }
+
+    /// Returns the earliest ephemeris of a satellite.
+    ///
+    /// # Arguments
+    /// * `prn` - PRN number of the satellite
+    pub fn first(&self, prn: usize) -> Option<&Ephemeris> {
+        prn.checked_sub(1)
+            .and_then(|sv| self.satellites.get(sv))
+            .and_then(|timeline| timeline.first())
+    }
+
+    /// Returns the earliest and latest time of clock of all ephemerides.
+    ///
+    /// # Returns
+    /// * `Some((first, last))` - The time span of the timeline
+    /// * `None` - If the timeline is empty
+    pub fn time_span(&self) -> Option<(GpsTime, GpsTime)> {
+        let first = self
+            .satellites
+            .iter()
+            .filter_map(|timeline| timeline.first())
+            .min_by(|a, b| a.toc.diff_secs(&b.toc).total_cmp(&0.0))?;
+        let last = self
+            .satellites
+            .iter()
+            .filter_map(|timeline| timeline.last())
+            .max_by(|a, b| a.toc.diff_secs(&b.toc).total_cmp(&0.0))?;
+        Some((first.toc.clone(), last.toc.clone()))
+    }
+
+    /// Returns all ephemerides with the PRN number of their satellite, for
+    /// modification.
+    pub fn iter_mut(
+        &mut self,
+    ) -> impl Iterator<Item = (usize, &mut Ephemeris)> {
+        self.satellites
+            .iter_mut()
+            .enumerate()
+            .flat_map(|(sv, timeline)| {
+                timeline.iter_mut().map(move |eph| (sv + 1, eph))
+            })
+    }
+}
@@ -0,0 +1,89 @@
+use super::EphemerisTimeline;
+use crate::{datetime::GpsTime, ephemeris::Ephemeris};
+
+/// Creates an ephemeris with time of clock and ephemeris `sec` in week 2190.
+fn ephemeris(sec: f64, iode: i32) -> Ephemeris {
+    let time = GpsTime { week: 2190, sec };
+    Ephemeris {
+        vflg: true,
+        toc: time.clone(),
+        toe: time,
+        iode,
+        fit_interval: 4.0,
+        ..Ephemeris::default()
+    }
+}
+
+/// Returns the IODEs of a satellite in timeline order.
+fn iodes(timeline: &mut EphemerisTimeline, prn: usize) -> Vec<i32> {
+    timeline
+        .iter_mut()
+        .filter(|(sv, _)| *sv == prn)
+        .map(|(_, eph)| eph.iode)
+        .collect()
+}
+
+#[test]
+fn timeline_drops_duplicates() {
+    let mut timeline = EphemerisTimeline::default();
+    assert!(timeline.insert(3, ephemeris(7200.0, 10)));
+    assert!(timeline.insert(3, ephemeris(14400.0, 11)));
+    // The same data set from an overlapping file of the next day
+    assert!(!timeline.insert(3, ephemeris(7200.0, 10)));
+    assert!(!timeline.insert(3, ephemeris(7200.4, 10)));
+    // A new upload with the same reference time is a different data set
+    assert!(timeline.insert(3, ephemeris(7200.0, 74)));
+    // The same data set of another satellite
+    assert!(timeline.insert(4, ephemeris(7200.0, 10)));
+    assert_eq!(iodes(&mut timeline, 3), [10, 74, 11]);
+    assert_eq!(iodes(&mut timeline, 4), [10]);
+}
+
+#[test]
+fn timeline_orders_by_time_of_clock() {
+    let mut timeline = EphemerisTimeline::default();
+    for (sec, iode) in [(21600.0, 3), (7200.0, 1), (14400.0, 2), (0.0, 0)] {
+        assert!(timeline.insert(5, ephemeris(sec, iode)));
+    }
+    assert_eq!(iodes(&mut timeline, 5), [0, 1, 2, 3]);
+    assert!(timeline.insert(6, ephemeris(28800.0, 4)));
+    let span = timeline
+        .time_span()
+        .map(|(first, last)| (first.sec, last.sec));
+    assert_eq!(span, Some((0.0, 28800.0)));
+}
+
+#[test]
+fn timeline_rejects_invalid_prns() {
+    let mut timeline = EphemerisTimeline::default();
+    assert!(!timeline.insert(0, ephemeris(0.0, 0)));
+    assert!(!timeline.insert(33, ephemeris(0.0, 0)));
+    assert!(timeline.is_empty());
+    assert!(timeline.time_span().is_none());
+}
+
+#[test]
+fn timeline_selects_per_satellite() {
+    let mut timeline = EphemerisTimeline::default();
+    // PRN 1 is in both sets, PRN 2 only uploads with the second one
+    assert!(timeline.insert(1, ephemeris(7200.0, 1)));
+    assert!(timeline.insert(1, ephemeris(14400.0, 2)));
+    assert!(timeline.insert(2, ephemeris(14400.0, 20)));
+    let iode = |prn: usize, sec: f64| {
+        timeline
+            .select(prn, &GpsTime { week: 2190, sec })
+            .map(|eph| eph.iode)
+    };
+    assert_eq!(iode(1, 7200.0), Some(1));
+    assert_eq!(iode(2, 7200.0), None);
+    // An ephemeris is taken into use less than an hour before its time of
+    // clock
+    assert_eq!(iode(1, 10800.0), Some(1));
+    assert_eq!(iode(1, 10800.1), Some(2));
+    assert_eq!(iode(2, 10800.0), None);
+    assert_eq!(iode(2, 10800.1), Some(20));
+    // Outside the four-hour fit interval of the latest ephemeris
+    assert_eq!(iode(1, 21600.0), Some(2));
+    assert_eq!(iode(1, 21601.0), None);
+    assert_eq!(iode(3, 14400.0), None);
+}
@@ -0,0 +1,294 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+    str::FromStr,
+};
+
+use constants::R2D;
+use geometry::{Azel, Dop, Ecef, Location};
+
+use crate::{
+    Error,
+    datetime::{DateTime, GpsTime},
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Output format of the truth log.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TruthFormat {
+    /// One comma-separated row per update step and channel
+    #[default]
+    Csv,
+    /// One JSON object per line, per update step and channel
+    JsonLines,
+    /// One NMEA GGA sentence per update step with the receiver position
+    Nmea,
+}
+
+impl FromStr for TruthFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(Self::Csv),
+            "json" | "jsonl" | "json-lines" => Ok(Self::JsonLines),
+            "nmea" => Ok(Self::Nmea),
+            _ => Err(Error::msg(format!(
+                "Unknown truth format '{s}', expected csv, jsonl or nmea"
+            ))),
+        }
+    }
+}
+
+/// Simulated observables of one channel at one update step.
+///
+/// All values are the ones used to generate the signal, so they can serve
+/// as ground truth when scoring receiver measurements.
+#[derive(Debug, Clone, Default)]
+pub struct TruthRecord {
+    /// Satellite PRN number
+    pub prn: usize,
+    /// Satellite position at transmission time in ECEF coordinates (meters)
+    pub satellite_position: Ecef,
+    /// Satellite velocity in ECEF coordinates (meters per second)
+    pub satellite_velocity: Ecef,
+    /// Geometric range in meters
+    pub range: f64,
+    /// Pseudorange in meters (including satellite clock and ionosphere)
+    pub pseudorange: f64,
+    /// Carrier Doppler shift in Hz
+    pub doppler: f64,
+    /// Code phase in chips
+    pub code_phase: f64,
+    /// Accumulated carrier phase in cycles
+    pub carrier_phase: f64,
+    /// Ionospheric delay in meters
+    pub iono_delay: f64,
+    /// Tropospheric delay in meters (not modelled by the simulator, always
+    /// zero)
+    pub tropo_delay: f64,
+    /// Elevation in degrees
+    pub elevation: f64,
+    /// Azimuth in degrees
+    pub azimuth: f64,
+    /// Applied signal gain (scaled by 2^7)
+    pub gain: i32,
+}
+
+/// Writes the truth log of a simulation.
+///
+/// The truth log receives one record per update step (every 100 ms by
+/// default) and active channel. In NMEA mode only the receiver position is
+/// written, as a GGA sentence per update step.
+#[derive(Debug)]
+pub struct TruthWriter {
+    /// Buffered file writer
+    writer: BufWriter<File>,
+    /// Output format
+    format: TruthFormat,
+    /// Difference between GPS time and UTC in seconds (for NMEA)
+    leap_seconds: i32,
+}
+
+impl TruthWriter {
+    /// Creates a truth log file and writes the header if the format has one.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the truth log file
+    /// * `format` - Output format
+    /// * `leap_seconds` - GPS-UTC offset used for NMEA time stamps
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - A new `TruthWriter`
+    /// * `Err(Error)` - If the file cannot be created
+    ///
+    /// # Errors
+    /// * Returns an error if the file cannot be created or written
+    pub fn new(
+        path: &PathBuf, format: TruthFormat, leap_seconds: i32,
+    ) -> Result<Self, Error> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        if format == TruthFormat::Csv {
+            writeln!(
+                writer,
+                "week,tow,rx_x,rx_y,rx_z,rx_lat_deg,rx_lon_deg,rx_height_m,\
+                 prn,sat_x,sat_y,sat_z,sat_vx,sat_vy,sat_vz,range_m,\
+                 pseudorange_m,doppler_hz,code_phase_chips,\
+                 carrier_phase_cycles,iono_m,tropo_m,el_deg,az_deg,gain"
+            )?;
+        }
+        Ok(Self {
+            writer,
+            format,
+            leap_seconds,
+        })
+    }
+
+    /// Writes the records of one update step.
+    ///
+    /// # Arguments
+    /// * `time` - GPS time of the update step
+    /// * `receiver` - Receiver position in ECEF coordinates
+    /// * `records` - One record per active channel
+    ///
+    /// # Returns
+    /// * `Ok(())` - If the records were written
+    /// * `Err(Error)` - If writing fails
+    ///
+    /// # Errors
+    /// * Returns an error if the file cannot be written
+    pub fn write_step(
+        &mut self, time: &GpsTime, receiver: &Ecef, records: &[TruthRecord],
+    ) -> Result<(), Error> {
+        let llh = Location::from(receiver);
+        let (lat, lon, height) =
+            (llh.latitude * R2D, llh.longitude * R2D, llh.height);
+        match self.format {
+            TruthFormat::Csv => {
+                for r in records {
+                    writeln!(
+                        self.writer,
+                        r"{},{:.3},{:.3},{:.3},{:.3},{:.9},{:.9},{:.3},{},{:.3},{:.3},{:.3},{:.4},{:.4},{:.4},{:.4},{:.4},{:.4},{:.6},{:.4},{:.4},{:.4},{:.3},{:.3},{}",
+                        time.week,
+                        time.sec,
+                        receiver.x,
+                        receiver.y,
+                        receiver.z,
+                        lat,
+                        lon,
+                        height,
+                        r.prn,
+                        r.satellite_position.x,
+                        r.satellite_position.y,
+                        r.satellite_position.z,
+                        r.satellite_velocity.x,
+                        r.satellite_velocity.y,
+                        r.satellite_velocity.z,
+                        r.range,
+                        r.pseudorange,
+                        r.doppler,
+                        r.code_phase,
+                        r.carrier_phase,
+                        r.iono_delay,
+                        r.tropo_delay,
+                        r.elevation,
+                        r.azimuth,
+                        r.gain
+                    )?;
+                }
+            }
+            TruthFormat::JsonLines => {
+                for r in records {
+                    writeln!(
+                        self.writer,
+                        r#"{{"week":{},"tow":{:.3},"rx_ecef":[{:.3},{:.3},{:.3}],"rx_llh":[{:.9},{:.9},{:.3}],"prn":{},"sat_pos":[{:.3},{:.3},{:.3}],"sat_vel":[{:.4},{:.4},{:.4}],"range":{:.4},"pseudorange":{:.4},"doppler":{:.4},"code_phase":{:.6},"carrier_phase":{:.4},"iono":{:.4},"tropo":{:.4},"el":{:.3},"az":{:.3},"gain":{}}}"#,
+                        time.week,
+                        time.sec,
+                        receiver.x,
+                        receiver.y,
+                        receiver.z,
+                        lat,
+                        lon,
+                        height,
+                        r.prn,
+                        r.satellite_position.x,
+                        r.satellite_position.y,
+                        r.satellite_position.z,
+                        r.satellite_velocity.x,
+                        r.satellite_velocity.y,
+                        r.satellite_velocity.z,
+                        r.range,
+                        r.pseudorange,
+                        r.doppler,
+                        r.code_phase,
+                        r.carrier_phase,
+                        r.iono_delay,
+                        r.tropo_delay,
+                        r.elevation,
+                        r.azimuth,
+                        r.gain
+                    )?;
+                }
+            }
+            TruthFormat::Nmea => {
+                let sentence = self.gga(time, &llh, records);
+                writeln!(self.writer, "{sentence}")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Flushes buffered records to the file.
+    ///
+    /// # Errors
+    /// * Returns an error if the file cannot be written
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// Formats a GGA sentence for the receiver position.
+    ///
+    /// # Arguments
+    /// * `time` - GPS time of the update step
+    /// * `llh` - Receiver position (radians and meters)
+    /// * `records` - Active channels, used for the satellite count and HDOP
+    ///
+    /// # Returns
+    /// The complete sentence including the checksum
+    fn gga(
+        &self, time: &GpsTime, llh: &Location, records: &[TruthRecord],
+    ) -> String {
+        let utc = DateTime::from(&time.add_secs(-f64::from(self.leap_seconds)));
+        let azels: Vec<Azel> = records
+            .iter()
+            .map(|r| Azel {
+                az: r.azimuth / R2D,
+                el: r.elevation / R2D,
+            })
+            .collect();
+        let hdop = Dop::from_azel(&azels).map_or(99.9, |dop| dop.hdop);
+        let (lat, ns) = Self::nmea_angle(llh.latitude * R2D, 2, ['N', 'S']);
+        let (lon, ew) = Self::nmea_angle(llh.longitude * R2D, 3, ['E', 'W']);
+        let body = format!(
+            "GPGGA,{:02}{:02}{:05.2},{lat},{ns},{lon},{ew},1,{:02},{hdop:.1},\
+             {:.1},M,0.0,M,,",
+            utc.hh,
+            utc.mm,
+            utc.sec,
+            records.len(),
+            llh.height
+        );
+        let checksum = body.bytes().fold(0u8, |acc, b| acc ^ b);
+        format!("${body}*{checksum:02X}")
+    }
+
+    /// Formats an angle in degrees as NMEA degrees and decimal minutes.
+    ///
+    /// # Arguments
+    /// * `deg` - Signed angle in degrees
+    /// * `width` - Number of digits for the whole degrees
+    /// * `hemispheres` - Hemisphere letters for positive and negative angles
+    ///
+    /// # Returns
+    /// The formatted angle and its hemisphere letter
+    fn nmea_angle(
+        deg: f64, width: usize, hemispheres: [char; 2],
+    ) -> (String, char) {
+        let hemisphere = if deg < 0.0 {
+            hemispheres[1]
+        } else {
+            hemispheres[0]
+        };
+        let deg = deg.abs();
+        let whole = deg.floor();
+        let minutes = (deg - whole) * 60.0;
+        (
+            format!("{:0width$}{minutes:08.5}", whole as u32),
+            hemisphere,
+        )
+    }
+}
@@ -0,0 +1,123 @@
+use geometry::{Ecef, Location};
+use test_case::test_case;
+
+use super::{TruthFormat, TruthRecord, TruthWriter};
+use crate::{Error, datetime::GpsTime};
+
+/// Receiver at 35.681298 N, 139.766247 E, 10 m
+const RECEIVER: [f64; 3] = [0.622_756_131_5, 2.439_381_193_3, 10.0];
+
+/// Writes one update step with two channels and returns the file lines.
+fn write_log(format: TruthFormat, name: &str) -> Result<Vec<String>, Error> {
+    let path = std::env::temp_dir()
+        .join(format!("truth-{}-{name}", std::process::id()));
+    let time = GpsTime {
+        week: 2345,
+        sec: 345_600.1,
+    };
+    let receiver = Ecef::from(&Location::from(&RECEIVER));
+    let records = [
+        TruthRecord {
+            prn: 5,
+            satellite_position: Ecef::from(&[
+                -13_000_000.0,
+                10_000_000.0,
+                20_000_000.0,
+            ]),
+            range: 21_000_123.456_78,
+            pseudorange: 21_000_130.5,
+            doppler: -1_234.567_89,
+            code_phase: 511.123_456_7,
+            elevation: 45.0,
+            azimuth: 120.0,
+            gain: 128,
+            ..TruthRecord::default()
+        },
+        TruthRecord {
+            prn: 12,
+            elevation: 60.0,
+            azimuth: 300.0,
+            ..TruthRecord::default()
+        },
+    ];
+    let mut writer = TruthWriter::new(&path, format, 18)?;
+    writer.write_step(&time, &receiver, &records)?;
+    writer.flush()?;
+    drop(writer);
+    let text = std::fs::read_to_string(&path)?;
+    std::fs::remove_file(&path)?;
+    Ok(text.lines().map(str::to_owned).collect())
+}
+
+#[test]
+fn truth_csv_format() -> Result<(), Error> {
+    let lines = write_log(TruthFormat::Csv, "csv")?;
+    assert_eq!(lines.len(), 3);
+    let header = lines[0].split(',').collect::<Vec<_>>();
+    assert_eq!(header.len(), 25);
+    assert_eq!(header[0], "week");
+    assert_eq!(header[8], "prn");
+    assert_eq!(header[24], "gain");
+    let row = lines[1].split(',').collect::<Vec<_>>();
+    assert_eq!(row.len(), header.len());
+    assert_eq!(row[0], "2345");
+    assert_eq!(row[1], "345600.100");
+    assert_eq!(row[8], "5");
+    assert_eq!(row[9], "-13000000.000");
+    assert_eq!(row[15], "21000123.4568");
+    assert_eq!(row[17], "-1234.5679");
+    assert_eq!(row[18], "511.123457");
+    assert_eq!(row[22], "45.000");
+    assert_eq!(row[23], "120.000");
+    assert_eq!(row[24], "128");
+    assert!(lines[2].contains(",12,"));
+    Ok(())
+}
+
+#[test]
+fn truth_json_lines_format() -> Result<(), Error> {
+    let lines = write_log(TruthFormat::JsonLines, "jsonl")?;
+    assert_eq!(lines.len(), 2);
+    for line in &lines {
+        assert!(line.starts_with(r#"{"week":2345,"tow":345600.100,"#));
+        assert!(line.ends_with('}'));
+    }
+    assert!(lines[0].contains(r#""prn":5,"#));
+    assert!(lines[0].contains(r#""doppler":-1234.5679,"#));
+    assert!(lines[0].contains(r#""el":45.000,"az":120.000,"gain":128}"#));
+    assert!(lines[1].contains(r#""prn":12,"#));
+    Ok(())
+}
+
+#[test]
+fn truth_nmea_format() -> Result<(), Error> {
+    let lines = write_log(TruthFormat::Nmea, "nmea")?;
+    assert_eq!(lines.len(), 1);
+    let sentence = &lines[0];
+    let (body, checksum) = sentence
+        .strip_prefix('$')
+        .and_then(|s| s.split_once('*'))
+        .ok_or_else(|| Error::msg("Invalid GGA sentence"))?;
+    let expected = body.bytes().fold(0u8, |acc, b| acc ^ b);
+    assert_eq!(checksum, format!("{expected:02X}"));
+    let fields = body.split(',').collect::<Vec<_>>();
+    assert_eq!(fields.len(), 15);
+    assert_eq!(fields[0], "GPGGA");
+    // 345600.1 s into the week is Thursday 00:00:00.1 GPS, 18 s ahead of UTC
+    assert_eq!(fields[1], "235942.10");
+    assert_eq!(&fields[2][..7], "3540.87");
+    assert_eq!(fields[3], "N");
+    assert_eq!(&fields[4][..8], "13945.97");
+    assert_eq!(fields[5], "E");
+    assert_eq!(fields[7], "02");
+    assert_eq!(fields[9], "10.0");
+    Ok(())
+}
+
+#[test_case("csv", TruthFormat::Csv)]
+#[test_case("JSONL", TruthFormat::JsonLines)]
+#[test_case("json-lines", TruthFormat::JsonLines)]
+#[test_case("nmea", TruthFormat::Nmea)]
+fn truth_format_from_str(text: &str, expected: TruthFormat) {
+    assert_eq!(text.parse::<TruthFormat>().ok(), Some(expected));
+}
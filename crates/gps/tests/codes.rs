@@ -0,0 +1,486 @@
+use gps::codes::{GalileoCodes, RangingCode};
+use test_case::test_case;
+
+/// Packs the first chips of a code into an integer, binary 1 for -1 chips,
+/// the first chip as the most significant bit.
+fn first_chips(chips: &[i8], count: usize) -> u32 {
+    chips[..count]
+        .iter()
+        .fold(0, |bits, &chip| bits << 1 | u32::from(chip < 0))
+}
+
+// First 10 chips in octal of IS-GPS-200 table 3-Ia
+#[test_case(1, 0o1440)]
+#[test_case(2, 0o1620)]
+#[test_case(3, 0o1710)]
+#[test_case(4, 0o1744)]
+#[test_case(5, 0o1133)]
+#[test_case(6, 0o1455)]
+#[test_case(7, 0o1131)]
+#[test_case(8, 0o1454)]
+#[test_case(9, 0o1626)]
+#[test_case(10, 0o1504)]
+#[test_case(11, 0o1642)]
+#[test_case(12, 0o1750)]
+#[test_case(13, 0o1764)]
+#[test_case(14, 0o1772)]
+#[test_case(15, 0o1775)]
+#[test_case(16, 0o1776)]
+#[test_case(17, 0o1156)]
+#[test_case(18, 0o1467)]
+#[test_case(19, 0o1633)]
+#[test_case(20, 0o1715)]
+#[test_case(21, 0o1746)]
+#[test_case(22, 0o1763)]
+#[test_case(23, 0o1063)]
+#[test_case(24, 0o1706)]
+#[test_case(25, 0o1743)]
+#[test_case(26, 0o1761)]
+#[test_case(27, 0o1770)]
+#[test_case(28, 0o1774)]
+#[test_case(29, 0o1127)]
+#[test_case(30, 0o1453)]
+#[test_case(31, 0o1625)]
+#[test_case(32, 0o1712)]
+#[test_case(33, 0o1745)]
+#[test_case(34, 0o1713)]
+#[test_case(35, 0o1134)]
+#[test_case(36, 0o1456)]
+#[test_case(37, 0o1713)]
+// PRN 64 to 210 of IS-GPS-200 table 3-Ib, including SBAS and QZSS
+#[test_case(64, 0o1523)]
+#[test_case(65, 0o0175)]
+#[test_case(66, 0o0617)]
+#[test_case(67, 0o0663)]
+#[test_case(68, 0o0435)]
+#[test_case(69, 0o1752)]
+#[test_case(70, 0o0254)]
+#[test_case(71, 0o0731)]
+#[test_case(72, 0o1373)]
+#[test_case(73, 0o0332)]
+#[test_case(74, 0o0723)]
+#[test_case(75, 0o1705)]
+#[test_case(76, 0o1515)]
+#[test_case(77, 0o1700)]
+#[test_case(78, 0o1256)]
+#[test_case(79, 0o0377)]
+#[test_case(80, 0o0767)]
+#[test_case(81, 0o0336)]
+#[test_case(82, 0o1412)]
+#[test_case(83, 0o1507)]
+#[test_case(84, 0o1514)]
+#[test_case(85, 0o1164)]
+#[test_case(86, 0o1500)]
+#[test_case(87, 0o0215)]
+#[test_case(88, 0o0103)]
+#[test_case(89, 0o0664)]
+#[test_case(90, 0o0532)]
+#[test_case(91, 0o1171)]
+#[test_case(92, 0o1641)]
+#[test_case(93, 0o1521)]
+#[test_case(94, 0o0227)]
+#[test_case(95, 0o0543)]
+#[test_case(96, 0o1517)]
+#[test_case(97, 0o0322)]
+#[test_case(98, 0o0242)]
+#[test_case(99, 0o1031)]
+#[test_case(100, 0o0744)]
+#[test_case(101, 0o0564)]
+#[test_case(102, 0o1067)]
+#[test_case(103, 0o1056)]
+#[test_case(104, 0o0014)]
+#[test_case(105, 0o0026)]
+#[test_case(106, 0o1342)]
+#[test_case(107, 0o1042)]
+#[test_case(108, 0o1006)]
+#[test_case(109, 0o1637)]
+#[test_case(110, 0o1666)]
+#[test_case(111, 0o1121)]
+#[test_case(112, 0o0761)]
+#[test_case(113, 0o1315)]
+#[test_case(114, 0o0766)]
+#[test_case(115, 0o1225)]
+#[test_case(116, 0o1732)]
+#[test_case(117, 0o0673)]
+#[test_case(118, 0o1220)]
+#[test_case(119, 0o1413)]
+#[test_case(120, 0o0671)]
+#[test_case(121, 0o0536)]
+#[test_case(122, 0o1510)]
+#[test_case(123, 0o1545)]
+#[test_case(124, 0o0160)]
+#[test_case(125, 0o0701)]
+#[test_case(126, 0o0013)]
+#[test_case(127, 0o1060)]
+#[test_case(128, 0o0245)]
+#[test_case(129, 0o0527)]
+#[test_case(130, 0o1436)]
+#[test_case(131, 0o1226)]
+#[test_case(132, 0o1257)]
+#[test_case(133, 0o0046)]
+#[test_case(134, 0o1071)]
+#[test_case(135, 0o0561)]
+#[test_case(136, 0o1037)]
+#[test_case(137, 0o0770)]
+#[test_case(138, 0o1327)]
+#[test_case(139, 0o1472)]
+#[test_case(140, 0o0124)]
+#[test_case(141, 0o0366)]
+#[test_case(142, 0o0133)]
+#[test_case(143, 0o0465)]
+#[test_case(144, 0o0717)]
+#[test_case(145, 0o0217)]
+#[test_case(146, 0o1742)]
+#[test_case(147, 0o1422)]
+#[test_case(148, 0o1442)]
+#[test_case(149, 0o0523)]
+#[test_case(150, 0o0736)]
+#[test_case(151, 0o1635)]
+#[test_case(152, 0o0136)]
+#[test_case(153, 0o0273)]
+#[test_case(154, 0o1026)]
+#[test_case(155, 0o0003)]
+#[test_case(156, 0o1670)]
+#[test_case(157, 0o0624)]
+#[test_case(158, 0o0235)]
+#[test_case(159, 0o0554)]
+#[test_case(160, 0o0075)]
+#[test_case(161, 0o1341)]
+#[test_case(162, 0o0042)]
+#[test_case(163, 0o0115)]
+#[test_case(164, 0o0207)]
+#[test_case(165, 0o0204)]
+#[test_case(166, 0o1576)]
+#[test_case(167, 0o1142)]
+#[test_case(168, 0o0040)]
+#[test_case(169, 0o0107)]
+#[test_case(170, 0o1643)]
+#[test_case(171, 0o0553)]
+#[test_case(172, 0o0317)]
+#[test_case(173, 0o0415)]
+#[test_case(174, 0o0123)]
+#[test_case(175, 0o1267)]
+#[test_case(176, 0o1535)]
+#[test_case(177, 0o0635)]
+#[test_case(178, 0o0760)]
+#[test_case(179, 0o0707)]
+#[test_case(180, 0o1276)]
+#[test_case(181, 0o1322)]
+#[test_case(182, 0o0211)]
+#[test_case(183, 0o1562)]
+#[test_case(184, 0o0774)]
+#[test_case(185, 0o0323)]
+#[test_case(186, 0o0112)]
+#[test_case(187, 0o1306)]
+#[test_case(188, 0o0027)]
+#[test_case(189, 0o1470)]
+#[test_case(190, 0o1505)]
+#[test_case(191, 0o1013)]
+#[test_case(192, 0o0355)]
+#[test_case(193, 0o0727)]
+#[test_case(194, 0o0170)]
+#[test_case(195, 0o0030)]
+#[test_case(196, 0o0472)]
+#[test_case(197, 0o1237)]
+#[test_case(198, 0o0414)]
+#[test_case(199, 0o1050)]
+#[test_case(200, 0o1630)]
+#[test_case(201, 0o0571)]
+#[test_case(202, 0o0732)]
+#[test_case(203, 0o1301)]
+#[test_case(204, 0o1173)]
+#[test_case(205, 0o0020)]
+#[test_case(206, 0o0447)]
+#[test_case(207, 0o1114)]
+#[test_case(208, 0o0341)]
+#[test_case(209, 0o1024)]
+#[test_case(210, 0o1046)]
+fn ca_code_first_chips(prn: usize, expected: u32) {
+    let chips = RangingCode::GpsCa.chips(prn);
+    assert_eq!(chips.map(|chips| first_chips(&chips, 10)), Some(expected));
+}
+
+// Initial and end states in octal of IS-GPS-200 table 3-IIa
+#[test_case(RangingCode::GpsL2cm, 1, 0o742_417_664, 0o552_566_002)]
+#[test_case(RangingCode::GpsL2cm, 2, 0o756_014_035, 0o034_445_034)]
+#[test_case(RangingCode::GpsL2cm, 3, 0o002_747_144, 0o723_443_711)]
+#[test_case(RangingCode::GpsL2cl, 1, 0o624_145_772, 0o267_724_236)]
+#[test_case(RangingCode::GpsL2cl, 2, 0o506_610_362, 0o167_516_066)]
+#[test_case(RangingCode::GpsL2cl, 3, 0o220_360_016, 0o771_756_405)]
+fn l2c_code_end_state(code: RangingCode, prn: usize, initial: u32, end: u32) {
+    // Modular register of 1 + x^3 + x^4 + x^5 + x^6 + x^9 + x^11 + x^13 +
+    // x^16 + x^19 + x^21 + x^24 + x^27, outputting stage 27
+    let mut register = initial;
+    let mut expected = Vec::with_capacity(code.length());
+    for chip in 0..code.length() {
+        expected.push(if register & 1 == 0 { 1 } else { -1 });
+        if chip + 1 < code.length() {
+            register = (register >> 1) ^ ((register & 1) * 0o445_112_474);
+        }
+    }
+    assert_eq!(register, end);
+    assert_eq!(code.chips(prn), Some(expected));
+}
+
+// Initial XB code states of IS-GPS-705 tables 3-Ia and 3-Ib, stages 1 to 13
+#[test_case(RangingCode::GpsL5i, 1, "0101011100100")]
+#[test_case(RangingCode::GpsL5i, 2, "1100000110101")]
+#[test_case(RangingCode::GpsL5i, 3, "0100000001000")]
+#[test_case(RangingCode::GpsL5i, 4, "1011000100110")]
+#[test_case(RangingCode::GpsL5i, 5, "1110111010111")]
+#[test_case(RangingCode::GpsL5i, 6, "0110011111010")]
+#[test_case(RangingCode::GpsL5i, 7, "1010010011111")]
+#[test_case(RangingCode::GpsL5i, 8, "1011110100100")]
+#[test_case(RangingCode::GpsL5i, 9, "1111100101011")]
+#[test_case(RangingCode::GpsL5i, 10, "0111111011110")]
+#[test_case(RangingCode::GpsL5i, 11, "0000100111010")]
+#[test_case(RangingCode::GpsL5i, 12, "1110011111001")]
+#[test_case(RangingCode::GpsL5i, 13, "0001110011100")]
+#[test_case(RangingCode::GpsL5i, 14, "0100000100111")]
+#[test_case(RangingCode::GpsL5i, 15, "0110101011010")]
+#[test_case(RangingCode::GpsL5i, 16, "0001111001001")]
+#[test_case(RangingCode::GpsL5i, 17, "0100110001111")]
+#[test_case(RangingCode::GpsL5i, 18, "1111000011110")]
+#[test_case(RangingCode::GpsL5i, 19, "1100100011111")]
+#[test_case(RangingCode::GpsL5i, 20, "0110101101101")]
+#[test_case(RangingCode::GpsL5i, 21, "0010000001000")]
+#[test_case(RangingCode::GpsL5i, 22, "1110111101111")]
+#[test_case(RangingCode::GpsL5i, 23, "1000011111110")]
+#[test_case(RangingCode::GpsL5i, 24, "1100010110100")]
+#[test_case(RangingCode::GpsL5i, 25, "1101001101101")]
+#[test_case(RangingCode::GpsL5i, 26, "1010110010110")]
+#[test_case(RangingCode::GpsL5i, 27, "0101011011110")]
+#[test_case(RangingCode::GpsL5i, 28, "0111101010110")]
+#[test_case(RangingCode::GpsL5i, 29, "0101111100001")]
+#[test_case(RangingCode::GpsL5i, 30, "1000010110111")]
+#[test_case(RangingCode::GpsL5i, 31, "0001010011110")]
+#[test_case(RangingCode::GpsL5i, 32, "0000010111001")]
+#[test_case(RangingCode::GpsL5q, 1, "1001011001100")]
+#[test_case(RangingCode::GpsL5q, 2, "0100011110110")]
+#[test_case(RangingCode::GpsL5q, 3, "1111000100011")]
+#[test_case(RangingCode::GpsL5q, 4, "0011101101010")]
+#[test_case(RangingCode::GpsL5q, 5, "0011110110010")]
+#[test_case(RangingCode::GpsL5q, 6, "0101010101001")]
+#[test_case(RangingCode::GpsL5q, 7, "1111110000001")]
+#[test_case(RangingCode::GpsL5q, 8, "0110101101000")]
+#[test_case(RangingCode::GpsL5q, 9, "1011101000011")]
+#[test_case(RangingCode::GpsL5q, 10, "0010010000110")]
+#[test_case(RangingCode::GpsL5q, 11, "0001000000101")]
+#[test_case(RangingCode::GpsL5q, 12, "0101011000101")]
+#[test_case(RangingCode::GpsL5q, 13, "0100110100101")]
+#[test_case(RangingCode::GpsL5q, 14, "1010000111111")]
+#[test_case(RangingCode::GpsL5q, 15, "1011110001111")]
+#[test_case(RangingCode::GpsL5q, 16, "1101001011111")]
+#[test_case(RangingCode::GpsL5q, 17, "1110011001000")]
+#[test_case(RangingCode::GpsL5q, 18, "1011011100100")]
+#[test_case(RangingCode::GpsL5q, 19, "0011001011011")]
+#[test_case(RangingCode::GpsL5q, 20, "1100001110001")]
+#[test_case(RangingCode::GpsL5q, 21, "0110110010000")]
+#[test_case(RangingCode::GpsL5q, 22, "0010110001110")]
+#[test_case(RangingCode::GpsL5q, 23, "1000101111101")]
+#[test_case(RangingCode::GpsL5q, 24, "0110111110011")]
+#[test_case(RangingCode::GpsL5q, 25, "0100010011011")]
+#[test_case(RangingCode::GpsL5q, 26, "0101010111100")]
+#[test_case(RangingCode::GpsL5q, 27, "1000011111010")]
+#[test_case(RangingCode::GpsL5q, 28, "1111101000010")]
+#[test_case(RangingCode::GpsL5q, 29, "0101000100100")]
+#[test_case(RangingCode::GpsL5q, 30, "1000001111001")]
+#[test_case(RangingCode::GpsL5q, 31, "0101111100101")]
+#[test_case(RangingCode::GpsL5q, 32, "1001000101010")]
+fn l5_code_initial_state(code: RangingCode, prn: usize, xb_state: &str) {
+    // XA starts at all ones, and both registers output stage 13 first
+    let chips = code.chips(prn).unwrap_or_default();
+    let expected = xb_state
+        .bytes()
+        .rev()
+        .map(|bit| if bit == b'1' { 1 } else { -1 })
+        .collect::<Vec<i8>>();
+    assert_eq!(chips.get(..13), Some(expected.as_slice()));
+}
+
+// First and last 24 chips in octal of IS-GPS-800 table 3.2-2
+#[test_case(RangingCode::GpsL1cd, 1, 0o77_001_425, 0o52_231_646)]
+#[test_case(RangingCode::GpsL1cd, 2, 0o23_342_754, 0o46_703_351)]
+#[test_case(RangingCode::GpsL1cp, 1, 0o05_752_067, 0o20_173_742)]
+#[test_case(RangingCode::GpsL1cp, 2, 0o70_146_401, 0o35_437_154)]
+fn l1c_code_chips(code: RangingCode, prn: usize, first: u32, last: u32) {
+    let chips = code.chips(prn).unwrap_or_default();
+    assert_eq!(chips.len(), code.length());
+    assert_eq!(first_chips(&chips, 24), first);
+    assert_eq!(first_chips(&chips[chips.len() - 24..], 24), last);
+}
+
+// Weil and insertion indices of IS-GPS-800 table 3.2-1
+#[test_case(RangingCode::GpsL1cd, 3, 5079, 72)]
+#[test_case(RangingCode::GpsL1cd, 8, 5104, 1)]
+#[test_case(RangingCode::GpsL1cd, 33, 4921, 378)]
+#[test_case(RangingCode::GpsL1cd, 47, 5031, 10216)]
+#[test_case(RangingCode::GpsL1cd, 63, 4851, 4353)]
+#[test_case(RangingCode::GpsL1cp, 3, 5108, 1)]
+#[test_case(RangingCode::GpsL1cp, 17, 5068, 193)]
+#[test_case(RangingCode::GpsL1cp, 33, 5092, 365)]
+#[test_case(RangingCode::GpsL1cp, 54, 4535, 10035)]
+#[test_case(RangingCode::GpsL1cp, 63, 1852, 6284)]
+fn l1c_code_weil(code: RangingCode, prn: usize, weil: usize, insertion: usize) {
+    // Legendre sequence by Euler's criterion, L(k) = 1 for the quadratic
+    // residues k^((N - 1) / 2) = 1 mod N
+    const N: usize = 10223;
+    let legendre = |k: usize| {
+        let mut power = 1;
+        for _ in 0..(N - 1) / 2 {
+            power = power * k % N;
+        }
+        u8::from(k != 0 && power == 1)
+    };
+    let legendre = (0..N).map(legendre).collect::<Vec<_>>();
+    let weil_code = |k: usize| legendre[k] ^ legendre[(k + weil) % N];
+    let expansion = [0, 1, 1, 0, 1, 0, 0];
+    let expected = (0..code.length())
+        .map(|t| match t {
+            t if t < insertion - 1 => weil_code(t),
+            t if t < insertion + 6 => expansion[t + 1 - insertion],
+            t => weil_code(t - 7),
+        })
+        .map(|bit| if bit == 0 { 1 } else { -1 })
+        .collect::<Vec<i8>>();
+    assert_eq!(code.chips(prn), Some(expected));
+}
+
+// G2 phase assignment of BDS-SIS-ICD-B1I v3.0 table 4-1
+#[test_case(1, &[1, 3])]
+#[test_case(2, &[1, 4])]
+#[test_case(3, &[1, 5])]
+#[test_case(4, &[1, 6])]
+#[test_case(5, &[1, 8])]
+#[test_case(6, &[1, 9])]
+#[test_case(7, &[1, 10])]
+#[test_case(8, &[1, 11])]
+#[test_case(9, &[2, 7])]
+#[test_case(10, &[3, 4])]
+#[test_case(11, &[3, 5])]
+#[test_case(12, &[3, 6])]
+#[test_case(13, &[3, 8])]
+#[test_case(14, &[3, 9])]
+#[test_case(15, &[3, 10])]
+#[test_case(16, &[3, 11])]
+#[test_case(17, &[4, 5])]
+#[test_case(18, &[4, 6])]
+#[test_case(19, &[4, 8])]
+#[test_case(20, &[4, 9])]
+#[test_case(21, &[4, 10])]
+#[test_case(22, &[4, 11])]
+#[test_case(23, &[5, 6])]
+#[test_case(24, &[5, 8])]
+#[test_case(25, &[5, 9])]
+#[test_case(26, &[5, 10])]
+#[test_case(27, &[5, 11])]
+#[test_case(28, &[6, 8])]
+#[test_case(29, &[6, 9])]
+#[test_case(30, &[6, 10])]
+#[test_case(31, &[6, 11])]
+#[test_case(32, &[8, 9])]
+#[test_case(33, &[8, 10])]
+#[test_case(34, &[8, 11])]
+#[test_case(35, &[9, 10])]
+#[test_case(36, &[9, 11])]
+#[test_case(37, &[10, 11])]
+#[test_case(38, &[1, 2, 7])]
+#[test_case(39, &[1, 3, 4])]
+#[test_case(40, &[1, 3, 6])]
+#[test_case(41, &[1, 3, 8])]
+#[test_case(42, &[1, 3, 10])]
+#[test_case(43, &[1, 3, 11])]
+#[test_case(44, &[1, 4, 5])]
+#[test_case(45, &[1, 4, 9])]
+#[test_case(46, &[1, 5, 6])]
+#[test_case(47, &[1, 5, 7])]
+#[test_case(48, &[1, 5, 8])]
+#[test_case(49, &[1, 5, 10])]
+#[test_case(50, &[1, 5, 11])]
+#[test_case(51, &[1, 6, 9])]
+#[test_case(52, &[1, 7, 8])]
+#[test_case(53, &[1, 7, 9])]
+#[test_case(54, &[1, 7, 11])]
+#[test_case(55, &[1, 8, 9])]
+#[test_case(56, &[1, 8, 11])]
+#[test_case(57, &[1, 9, 10])]
+#[test_case(58, &[1, 10, 11])]
+#[test_case(59, &[2, 3, 5])]
+#[test_case(60, &[2, 3, 9])]
+#[test_case(61, &[2, 5, 6])]
+#[test_case(62, &[2, 5, 7])]
+#[test_case(63, &[2, 5, 11])]
+fn b1i_code_first_chips(prn: usize, phase: &[usize]) {
+    // Both registers start at 01010101010 in stages 1 to 11, so the first
+    // chip is stage 11 of G1 plus the selected G2 stages
+    let initial = [0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0];
+    let sum = |stages: &[u8; 11]| {
+        phase.iter().fold(0, |sum, &stage| sum ^ stages[stage - 1])
+    };
+    let expected = i8::from(sum(&initial) == 0);
+    let chips = RangingCode::BeidouB1i.chips(prn).unwrap_or_default();
+    assert_eq!(chips.first(), Some(&(2 * expected - 1)));
+
+    // Stage by stage registers of the ICD, shifting from stage 1 to 11
+    let shift = |stages: &mut [u8; 11], taps: &[usize]| {
+        let feedback = taps.iter().fold(0, |sum, &tap| sum ^ stages[tap - 1]);
+        stages.rotate_right(1);
+        stages[0] = feedback;
+    };
+    let (mut g1, mut g2) = (initial, initial);
+    let mut expected = Vec::with_capacity(chips.len());
+    for _ in 0..RangingCode::BeidouB1i.length() {
+        let bit = g1[10] ^ sum(&g2);
+        expected.push(if bit == 0 { 1 } else { -1 });
+        shift(&mut g1, &[1, 7, 8, 9, 10, 11]);
+        shift(&mut g2, &[1, 2, 3, 4, 5, 8, 9, 11]);
+    }
+    assert_eq!(chips, expected);
+}
+
+#[test]
+fn galileo_codes_from_table() -> Result<(), gps::Error> {
+    let e1b = "F".repeat(1023);
+    let e1c = format!("8{}", "0".repeat(1022));
+    let codes = GalileoCodes::parse(&format!(
+        "E1B 11 {e1b}\nE1C 11 {e1c}\nE1B 12 {e1b}"
+    ))?;
+    let (b, c) = (RangingCode::GalileoE1b, RangingCode::GalileoE1c);
+    assert!(b.chips(11).is_none() && c.chips(11).is_none());
+    let b11 = b.table_chips(11, &codes).unwrap_or_default();
+    let c11 = c.table_chips(11, &codes).unwrap_or_default();
+    assert_eq!(b11.len(), b.length());
+    assert!(b11.iter().all(|&chip| chip == -1));
+    assert_eq!(c11.len(), c.length());
+    assert_eq!(c11.iter().filter(|&&chip| chip == -1).count(), 1);
+    assert_eq!(c11.first(), Some(&-1));
+    assert!(b.table_chips(12, &codes).is_some());
+    assert!(c.table_chips(12, &codes).is_none());
+    assert!(b.table_chips(51, &codes).is_none());
+    assert_eq!(
+        RangingCode::GpsCa.table_chips(1, &codes),
+        RangingCode::GpsCa.chips(1)
+    );
+    Ok(())
+}
+
+#[test_case(RangingCode::GpsCa, &[1, 63, 64, 120, 193, 210], &[0, 211])]
+#[test_case(RangingCode::GpsL2cm, &[1, 32], &[0, 33])]
+#[test_case(RangingCode::GpsL2cl, &[1, 32], &[0, 33])]
+#[test_case(RangingCode::GpsL5i, &[1, 32], &[0, 33])]
+#[test_case(RangingCode::GpsL5q, &[1, 32], &[0, 33])]
+#[test_case(RangingCode::GpsL1cd, &[1, 63], &[0, 64])]
+#[test_case(RangingCode::GpsL1cp, &[1, 63], &[0, 64])]
+#[test_case(RangingCode::BeidouB1i, &[1, 37, 38, 63], &[0, 64])]
+#[test_case(RangingCode::GalileoE1b, &[], &[1, 50])]
+#[test_case(RangingCode::GalileoE1c, &[], &[1, 50])]
+fn code_lengths(code: RangingCode, prns: &[usize], unassigned: &[usize]) {
+    for &prn in prns {
+        let chips = code.chips(prn).unwrap_or_default();
+        assert_eq!(chips.len(), code.length());
+        assert!(chips.iter().all(|&chip| chip == 1 || chip == -1));
+    }
+    for &prn in unassigned {
+        assert!(code.chips(prn).is_none());
+    }
+}
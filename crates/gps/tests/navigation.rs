@@ -0,0 +1,128 @@
+use std::path::PathBuf;
+
+use gps::{Error, SignalGeneratorBuilder};
+use test_case::test_case;
+
+const RESOURCES_DIR: &str = concat!(env!("CARGO_WORKSPACE_DIR"), "/resources");
+
+/// Writes a copy of the navigation file with an unknown header label and a
+/// malformed number, which only lenient parsing accepts.
+fn malformed_navigation_file() -> Result<PathBuf, Error> {
+    let text = std::fs::read_to_string(
+        PathBuf::from(RESOURCES_DIR).join("brdc0010.22n"),
+    )?;
+    let text = text
+        .replacen(
+            "IGS BROADCAST EPHEMERIS FILE                                \
+             COMMENT",
+            "IGS BROADCAST EPHEMERIS FILE                                \
+             COMMENT\nGPS1                                                        \
+             MARKER NAME",
+            1,
+        )
+        .replacen("0.398838041777D-08", "0.398838041777Q-08", 1);
+    let path = std::env::temp_dir()
+        .join(format!("lenient-{}.22n", std::process::id()));
+    std::fs::write(&path, text)?;
+    Ok(path)
+}
+
+/// Builds a static scenario and renders its visibility plan.
+fn plan(builder: SignalGeneratorBuilder) -> Result<String, Error> {
+    let mut generator = builder
+        .location(Some(vec![35.681_298, 139.766_247, 10.0]))?
+        .time(Some("2022-01-01 00:00:00-00".to_string()))?
+        .duration(Some(60.0))
+        .data_format(Some(8))?
+        .build()?;
+    Ok(generator.plan(30.0)?.to_csv())
+}
+
+#[test]
+fn lenient_navigation_call_order() -> Result<(), Error> {
+    let path = malformed_navigation_file()?;
+    let strict = plan(
+        SignalGeneratorBuilder::default()
+            .navigation_file(Some(path.clone()))?,
+    );
+    let before = plan(
+        SignalGeneratorBuilder::default()
+            .lenient_navigation(Some(true))
+            .navigation_file(Some(path.clone()))?,
+    );
+    let after = plan(
+        SignalGeneratorBuilder::default()
+            .navigation_file(Some(path.clone()))?
+            .lenient_navigation(Some(true)),
+    );
+    std::fs::remove_file(&path)?;
+    assert!(matches!(strict, Err(Error::NavigationRead { .. })));
+    let (before, after) = (before?, after?);
+    assert!(!before.is_empty());
+    assert_eq!(before, after);
+    Ok(())
+}
+
+#[test]
+fn navigation_file_missing() {
+    let result = SignalGeneratorBuilder::default()
+        .navigation_file(Some(PathBuf::from("missing.22n")));
+    assert!(matches!(result, Err(Error::NavigationRead { .. })));
+}
+
+#[test]
+fn navigation_files_merge_duplicates() -> Result<(), Error> {
+    let path = PathBuf::from(RESOURCES_DIR).join("brdc0010.22n");
+    let single = plan(
+        SignalGeneratorBuilder::default()
+            .navigation_file(Some(path.clone()))?,
+    )?;
+    let merged = plan(
+        SignalGeneratorBuilder::default()
+            .navigation_files(Some(vec![path.clone(), path]))?,
+    )?;
+    assert_eq!(single, merged);
+    Ok(())
+}
+
+#[test_case(false, 4, 1, 4.0; "navigation file fields")]
+#[test_case(true, 0, 0, 0.0; "gps-sdr-sim fields")]
+fn compatible_subframes(
+    compatible: bool, ura: i32, l2p_flag: i32, fit_interval: f64,
+) -> Result<(), Error> {
+    // PRN 1 with an accuracy of 7.68 m, URA index 4, and the L2 P data flag
+    let text = std::fs::read_to_string(
+        PathBuf::from(RESOURCES_DIR).join("brdc0010.22n"),
+    )?
+    .replacen(
+        "0.200000000000D+01 0.000000000000D+00 0.512227416039D-08 \
+         0.390000000000D+02",
+        "0.768000000000D+01 0.000000000000D+00 0.512227416039D-08 \
+         0.390000000000D+02",
+        1,
+    )
+    .replacen(
+        "0.219000000000D+04 0.000000000000D+00",
+        "0.219000000000D+04 0.100000000000D+01",
+        1,
+    );
+    let path = std::env::temp_dir().join(format!(
+        "compatible-{}-{compatible}.22n",
+        std::process::id()
+    ));
+    std::fs::write(&path, text)?;
+    let generator = SignalGeneratorBuilder::default()
+        .navigation_file(Some(path.clone()))?
+        .compatible_subframes(Some(compatible))
+        .location(Some(vec![35.681_298, 139.766_247, 10.0]))?
+        .time(Some("2022-01-01 00:00:00-00".to_string()))?
+        .duration(Some(60.0))
+        .data_format(Some(8))?
+        .build();
+    std::fs::remove_file(path)?;
+    let eph = &generator?.ephemerides[0];
+    assert_eq!(eph.ura, ura);
+    assert_eq!(eph.l2p_flag, l2p_flag);
+    assert!((eph.fit_interval - fit_interval).abs() < 1e-9);
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use std::{f64::consts::PI, path::PathBuf};
+
+use gps::{Error, SignalGeneratorBuilder};
+use test_case::test_case;
+
+const RESOURCES_DIR: &str = concat!(env!("CARGO_WORKSPACE_DIR"), "/resources");
+
+/// Sampling frequency of the scenarios in Hz.
+const SAMPLE_FREQUENCY: usize = 4_092_000;
+
+/// Generates 100 ms of 16-bit samples, the first step after the start, and
+/// reads them back.
+fn generate(
+    intermediate_frequency: f64, real_output: bool,
+) -> Result<Vec<i16>, Error> {
+    generate_oversampled(intermediate_frequency, real_output, None)
+}
+
+/// Generates 100 ms of 16-bit samples with an oversampling factor.
+fn generate_oversampled(
+    intermediate_frequency: f64, real_output: bool, oversampling: Option<usize>,
+) -> Result<Vec<i16>, Error> {
+    let path = std::env::temp_dir().join(format!(
+        "output-{intermediate_frequency}-{real_output}-{oversampling:?}-{}.bin",
+        std::process::id()
+    ));
+    let mut generator = SignalGeneratorBuilder::default()
+        .navigation_file(Some(
+            PathBuf::from(RESOURCES_DIR).join("brdc0010.22n"),
+        ))?
+        .location(Some(vec![35.681_298, 139.766_247, 10.0]))?
+        .time(Some("2022-01-01 00:00:00-00".to_string()))?
+        .duration(Some(0.2))
+        .frequency(Some(SAMPLE_FREQUENCY))?
+        .data_format(Some(16))?
+        .intermediate_frequency(Some(intermediate_frequency))?
+        .real_output(Some(real_output))
+        .oversampling(oversampling)?
+        .output_file(Some(path.clone()))
+        .build()?;
+    generator.initialize()?;
+    generator.run_simulation()?;
+    let bytes = std::fs::read(&path)?;
+    std::fs::remove_file(&path)?;
+    Ok(bytes
+        .chunks_exact(2)
+        .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+        .collect())
+}
+
+#[test_case(1_023_000.0)]
+#[test_case(-1_023_000.0)]
+fn spectrum_centred_at_intermediate_frequency(
+    intermediate_frequency: f64,
+) -> Result<(), Error> {
+    let samples = generate(intermediate_frequency, false)?;
+    // Pulse-pair estimate of the mean frequency: the phase of the lag-one
+    // autocorrelation, which the symmetric code spectra leave at the
+    // carriers, within the Doppler shifts of a few kHz
+    let (mut re, mut im) = (0.0, 0.0);
+    for pair in samples.chunks_exact(2).collect::<Vec<_>>().windows(2) {
+        let (i0, q0) = (f64::from(pair[0][0]), f64::from(pair[0][1]));
+        let (i1, q1) = (f64::from(pair[1][0]), f64::from(pair[1][1]));
+        re += i1 * i0 + q1 * q0;
+        im += q1 * i0 - i1 * q0;
+    }
+    let frequency = im.atan2(re) / (2.0 * PI) * 4.092e6;
+    assert!(
+        (frequency - intermediate_frequency).abs() < 10e3,
+        "mean frequency {frequency} Hz"
+    );
+    Ok(())
+}
+
+#[test]
+fn real_output_holds_in_phase_samples() -> Result<(), Error> {
+    let complex = generate(1_023_000.0, false)?;
+    let real = generate(1_023_000.0, true)?;
+    assert_eq!(2 * real.len(), complex.len());
+    assert!(real.iter().any(|&sample| sample != 0));
+    assert_eq!(real, complex.iter().step_by(2).copied().collect::<Vec<_>>());
+    Ok(())
+}
+
+#[test_case(0.0, true)]
+#[test_case(-1_023_000.0, true)]
+#[test_case(2_046_000.0, false)]
+fn invalid_intermediate_frequency(
+    intermediate_frequency: f64, real_output: bool,
+) {
+    let result = generate(intermediate_frequency, real_output);
+    assert!(matches!(result, Err(Error::InvalidIntermediateFrequency)));
+}
+
+// Oversampled by 4 with the receiver filter only, and by 8 with the
+// satellite filter in front of it
+#[test_case(4)]
+#[test_case(8)]
+fn oversampled_output_aligned(oversampling: usize) -> Result<(), Error> {
+    let direct = generate(0.0, false)?;
+    let filtered = generate_oversampled(0.0, false, Some(oversampling))?;
+    assert_eq!(direct.len(), filtered.len());
+    // The filtered samples correlate best with the samples generated
+    // without oversampling at the same instants
+    let correlation = |lag: usize| {
+        let (mut product, mut direct_power, mut filtered_power) =
+            (0.0, 0.0, 0.0);
+        for (&a, &b) in direct[8..direct.len() - 8]
+            .iter()
+            .zip(&filtered[lag..filtered.len() - 16 + lag])
+        {
+            let (a, b) = (f64::from(a), f64::from(b));
+            product += a * b;
+            direct_power += a * a;
+            filtered_power += b * b;
+        }
+        product / (direct_power * filtered_power).sqrt()
+    };
+    // Lags of -3 to 3 samples, I and Q interleaved
+    let correlations = (2..=14).step_by(2).map(correlation).collect::<Vec<_>>();
+    let peak = correlations[3];
+    assert!(peak > 0.8, "correlation {peak}");
+    assert!(correlations.iter().all(|&value| value <= peak));
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use gps::{HealthMode, SatelliteSelection};
+use test_case::test_case;
+
+#[test_case(None, &[], HealthMode::Simulate, 5, 0, true)]
+#[test_case(Some(vec![1, 5, 9]), &[], HealthMode::Simulate, 5, 0, true)]
+#[test_case(Some(vec![1, 5, 9]), &[], HealthMode::Simulate, 6, 0, false)]
+#[test_case(None, &[5], HealthMode::Simulate, 5, 0, false)]
+#[test_case(Some(vec![5]), &[5], HealthMode::Simulate, 5, 0, false)]
+#[test_case(None, &[], HealthMode::Simulate, 5, 63, true)]
+#[test_case(None, &[], HealthMode::Skip, 5, 63, false)]
+#[test_case(None, &[], HealthMode::Skip, 5, 0, true)]
+#[test_case(None, &[], HealthMode::ForceHealthy, 5, 63, true)]
+fn selection_allows(
+    include: Option<Vec<usize>>, exclude: &[usize], health_mode: HealthMode,
+    prn: usize, health: i32, expected: bool,
+) {
+    let selection = SatelliteSelection {
+        include,
+        exclude: exclude.to_vec(),
+        max_satellites: None,
+        health_mode,
+    };
+    assert_eq!(selection.allows(prn, health), expected);
+}
+
+#[test_case(None, 0, true)]
+#[test_case(None, 100, true)]
+#[test_case(Some(4), 3, true)]
+#[test_case(Some(4), 4, false)]
+#[test_case(Some(0), 0, false)]
+fn selection_has_capacity(
+    max_satellites: Option<usize>, allocated: usize, expected: bool,
+) {
+    let selection = SatelliteSelection {
+        max_satellites,
+        ..SatelliteSelection::default()
+    };
+    assert_eq!(selection.has_capacity(allocated), expected);
+}
+
+#[test_case("simulate", HealthMode::Simulate)]
+#[test_case("skip", HealthMode::Skip)]
+#[test_case("healthy", HealthMode::ForceHealthy)]
+#[test_case("force-healthy", HealthMode::ForceHealthy)]
+#[test_case("Force_Healthy", HealthMode::ForceHealthy)]
+#[test_case("SKIP", HealthMode::Skip)]
+fn health_mode_from_str(text: &str, expected: HealthMode) {
+    assert_eq!(text.parse::<HealthMode>().ok(), Some(expected));
+}
+
+#[test_case("")]
+#[test_case("ignore")]
+#[test_case("unhealthy")]
+fn health_mode_from_str_invalid(text: &str) {
+    assert!(text.parse::<HealthMode>().is_err());
+}
@@ -7,7 +7,9 @@ mod prepare;
 use prepare::{OUTPUT_DIR, RESOURCES_DIR, prepare_c_bin};
 #[allow(non_snake_case)]
 fn to_builder(args: &[Vec<String>]) -> Result<SignalGeneratorBuilder, Error> {
-    let mut builder = SignalGeneratorBuilder::default();
+    // gps-sdr-sim broadcasts zero URA, L2 P, fit interval and AODO fields
+    let mut builder =
+        SignalGeneratorBuilder::default().compatible_subframes(Some(true));
     for arg in args {
         match arg.as_slice() {
             [e, navfile] if e == "-e" => {
@@ -0,0 +1,284 @@
+//! YUMA and SEM almanac files.
+//!
+//! Both formats list the reduced Keplerian elements and clock polynomial
+//! that GPS broadcasts in subframes 4 and 5. YUMA files give every element
+//! on a labelled line in radians, SEM files give them as plain numbers in
+//! semicircles. Angles are converted to radians and the inclination to its
+//! full value, so that records of both formats are alike.
+//!
+//! The almanac week is kept as written, which is usually the GPS week modulo
+//! 1024.
+
+use std::{fs, path::Path};
+
+use crate::error::Error;
+
+/// Value of pi used by GPS to convert semicircles to radians.
+#[allow(clippy::approx_constant)]
+const GPS_PI: f64 = 3.141_592_653_589_8;
+
+/// Reference inclination that SEM inclination offsets are relative to, in
+/// semicircles.
+const SEM_REFERENCE_INCLINATION: f64 = 0.30;
+
+/// Number of values of a SEM almanac record.
+const SEM_RECORD_VALUES: usize = 15;
+
+/// Almanac of one satellite.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AlmanacRecord {
+    /// PRN number
+    pub prn: usize,
+    /// Satellite health (6-bit summary as in the navigation message)
+    pub health: u32,
+    /// Eccentricity
+    pub eccentricity: f64,
+    /// Almanac reference time in seconds of week
+    pub toa: f64,
+    /// Inclination in radians
+    pub inclination: f64,
+    /// Rate of right ascension in radians per second
+    pub omega_dot: f64,
+    /// Square root of the semi-major axis in meters^(1/2)
+    pub sqrt_a: f64,
+    /// Longitude of the ascending node at the weekly epoch in radians
+    pub omega0: f64,
+    /// Argument of perigee in radians
+    pub perigee: f64,
+    /// Mean anomaly at reference time in radians
+    pub mean_anomaly: f64,
+    /// Clock bias in seconds
+    pub af0: f64,
+    /// Clock drift in seconds per second
+    pub af1: f64,
+    /// Almanac week as written in the file
+    pub week: u32,
+}
+
+/// Contents of a YUMA or SEM almanac file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Almanac {
+    /// Satellite almanacs in file order
+    pub records: Vec<AlmanacRecord>,
+}
+
+impl Almanac {
+    /// Reads a YUMA or SEM almanac file from disk.
+    ///
+    /// YUMA files are recognized by their `********` record headers, all
+    /// other files are read as SEM.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the almanac file
+    ///
+    /// # Returns
+    /// * `Ok(Almanac)` - Successfully parsed almanac
+    /// * `Err(Error)` - If the file cannot be read or parsed
+    ///
+    /// # Errors
+    /// * Returns an error if the file cannot be read or if a value is invalid,
+    ///   with its line and column
+    pub fn read_file(path: &dyn AsRef<Path>) -> Result<Self, Error> {
+        let data = fs::read_to_string(path)?;
+        if data.trim_start().starts_with('*') {
+            Self::read_yuma(&data)
+        } else {
+            Self::read_sem(&data)
+        }
+    }
+
+    /// Parses a YUMA almanac.
+    ///
+    /// # Arguments
+    /// * `data` - String containing the YUMA almanac
+    ///
+    /// # Returns
+    /// * `Ok(Almanac)` - Successfully parsed almanac
+    /// * `Err(Error)` - If a value is invalid or a record is incomplete
+    ///
+    /// # Errors
+    /// * Returns an error with the line and column of the first invalid value,
+    ///   or of the header of an incomplete record
+    pub fn read_yuma(data: &str) -> Result<Self, Error> {
+        let mut almanac = Almanac::default();
+        // Record being read, with the line of its header and the number of
+        // values read
+        let mut current: Option<(AlmanacRecord, usize, usize)> = None;
+        for (index, line) in data.lines().enumerate() {
+            let number = index + 1;
+            if line.trim_start().starts_with('*') {
+                if let Some(record) = current.take() {
+                    almanac.records.push(complete_yuma(record)?);
+                }
+                current = Some((AlmanacRecord::default(), number, 0));
+                continue;
+            }
+            let Some((label, value)) = line.split_once(':') else {
+                continue;
+            };
+            let Some((record, _, count)) = current.as_mut() else {
+                return Err(Error::almanac(
+                    "Almanac value before record header",
+                )
+                .at((number, 1)));
+            };
+            let column =
+                label.len() + 2 + (value.len() - value.trim_start().len());
+            let float = || -> Result<f64, Error> {
+                value
+                    .trim()
+                    .parse()
+                    .map_err(|e| Error::from(e).at((number, column)))
+            };
+            let integer = || -> Result<u32, Error> {
+                value
+                    .trim()
+                    .parse()
+                    .map_err(|e| Error::from(e).at((number, column)))
+            };
+            let label = label.trim().to_ascii_lowercase();
+            match label.as_str() {
+                "id" => record.prn = integer()? as usize,
+                "health" => record.health = integer()?,
+                "eccentricity" => record.eccentricity = float()?,
+                _ if label.starts_with("time of applicability") => {
+                    record.toa = float()?;
+                }
+                _ if label.starts_with("orbital inclination") => {
+                    record.inclination = float()?;
+                }
+                _ if label.starts_with("rate of right ascen") => {
+                    record.omega_dot = float()?;
+                }
+                _ if label.starts_with("sqrt(a)") => record.sqrt_a = float()?,
+                _ if label.starts_with("right ascen at week") => {
+                    record.omega0 = float()?;
+                }
+                _ if label.starts_with("argument of perigee") => {
+                    record.perigee = float()?;
+                }
+                _ if label.starts_with("mean anom") => {
+                    record.mean_anomaly = float()?;
+                }
+                _ if label.starts_with("af0") => record.af0 = float()?,
+                _ if label.starts_with("af1") => record.af1 = float()?,
+                "week" => record.week = integer()?,
+                _ => continue,
+            }
+            *count += 1;
+        }
+        if let Some(record) = current {
+            almanac.records.push(complete_yuma(record)?);
+        }
+        Ok(almanac)
+    }
+
+    /// Parses a SEM almanac.
+    ///
+    /// The header gives the number of records and a title on the first line,
+    /// then the almanac week and reference time, which apply to all records.
+    ///
+    /// # Arguments
+    /// * `data` - String containing the SEM almanac
+    ///
+    /// # Returns
+    /// * `Ok(Almanac)` - Successfully parsed almanac
+    /// * `Err(Error)` - If a value is invalid or the file is truncated
+    ///
+    /// # Errors
+    /// * Returns an error with the line and column of the first invalid value
+    pub fn read_sem(data: &str) -> Result<Self, Error> {
+        let mut values = data.lines().enumerate().flat_map(|(index, line)| {
+            // The title after the record count may contain blanks
+            let values = if index == 0 {
+                line.split_whitespace().next().unwrap_or_default()
+            } else {
+                line
+            };
+            values.split_whitespace().map(move |value| {
+                let column = value.as_ptr() as usize - line.as_ptr() as usize;
+                (value, (index + 1, column + 1))
+            })
+        });
+        let mut next = |what: &str| {
+            values.next().ok_or_else(|| {
+                Error::almanac(format!("SEM almanac truncated before {what}"))
+            })
+        };
+        let float = |(value, position): (&str, (usize, usize))| {
+            value
+                .parse::<f64>()
+                .map_err(|e| Error::from(e).at(position))
+        };
+        let integer = |(value, position): (&str, (usize, usize))| {
+            value
+                .parse::<u32>()
+                .map_err(|e| Error::from(e).at(position))
+        };
+        let count = integer(next("record count")?)?;
+        let week = integer(next("week")?)?;
+        let toa = float(next("reference time")?)?;
+        let mut almanac = Almanac::default();
+        for _ in 0..count {
+            let mut record = [0.0; SEM_RECORD_VALUES];
+            for (i, value) in record.iter_mut().enumerate() {
+                *value = float(next(&format!("value {} of a record", i + 1))?)?;
+            }
+            let [
+                prn,
+                _svn,
+                _ura,
+                eccentricity,
+                inclination,
+                omega_dot,
+                sqrt_a,
+                omega0,
+                perigee,
+                mean_anomaly,
+                af0,
+                af1,
+                _reserved,
+                health,
+                _configuration,
+            ] = record;
+            almanac.records.push(AlmanacRecord {
+                prn: prn as usize,
+                health: health as u32,
+                eccentricity,
+                toa,
+                inclination: (SEM_REFERENCE_INCLINATION + inclination) * GPS_PI,
+                omega_dot: omega_dot * GPS_PI,
+                sqrt_a,
+                omega0: omega0 * GPS_PI,
+                perigee: perigee * GPS_PI,
+                mean_anomaly: mean_anomaly * GPS_PI,
+                af0,
+                af1,
+                week,
+            });
+        }
+        Ok(almanac)
+    }
+}
+
+/// Checks that a YUMA record has all its values.
+///
+/// # Arguments
+/// * `(record, line, count)` - The record, the line of its header and the
+///   number of values read
+///
+/// # Errors
+/// * Returns an error at the record header if a value is missing
+fn complete_yuma(
+    (record, line, count): (AlmanacRecord, usize, usize),
+) -> Result<AlmanacRecord, Error> {
+    /// Number of values of a YUMA almanac record.
+    const YUMA_RECORD_VALUES: usize = 13;
+    if count < YUMA_RECORD_VALUES {
+        return Err(Error::almanac(format!(
+            "YUMA almanac record has {count} of {YUMA_RECORD_VALUES} values"
+        ))
+        .at((line, 1)));
+    }
+    Ok(record)
+}
@@ -0,0 +1,152 @@
+//! RINEX clock files.
+//!
+//! RINEX clock files (versions 2.00 to 3.04) list clock offsets of satellites
+//! and receivers, typically at 30 second or 5 minute intervals. Only the
+//! satellite records (`AS`) are kept.
+
+use std::{fs, path::Path, str::FromStr};
+
+use jiff::civil::DateTime;
+
+use crate::{error::Error, navigation::SatelliteId, sp3::epoch_time};
+
+/// Number of data values written on the first line of a clock record.
+const VALUES_PER_FIRST_LINE: usize = 2;
+
+/// Number of data values written on a continuation line of a clock record.
+const VALUES_PER_LINE: usize = 4;
+
+/// Clock offset of a satellite at an epoch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClockRecord {
+    /// Satellite
+    pub satellite: SatelliteId,
+    /// Epoch in the time system of the file
+    pub epoch: DateTime,
+    /// Clock offset in seconds
+    pub bias: f64,
+    /// Standard deviation of the offset in seconds, if given
+    pub sigma: Option<f64>,
+}
+
+/// Contents of a RINEX clock file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RinexClock {
+    /// Format version
+    pub version: f64,
+    /// Time system of the epochs (`GPS` if the file does not state one)
+    pub time_system: String,
+    /// Satellite clock records in file order
+    pub records: Vec<ClockRecord>,
+}
+
+impl RinexClock {
+    /// Reads a RINEX clock file from disk.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the clock file
+    ///
+    /// # Returns
+    /// * `Ok(RinexClock)` - Successfully parsed clock data
+    /// * `Err(Error)` - If the file cannot be read or parsed
+    ///
+    /// # Errors
+    /// * Returns an error if the file cannot be read or if a line is invalid,
+    ///   with its line and column
+    pub fn read_file(path: &dyn AsRef<Path>) -> Result<Self, Error> {
+        let data = fs::read_to_string(path)?;
+        Self::read_string(data.as_str())
+    }
+
+    /// Parses a RINEX clock file from a string.
+    ///
+    /// # Arguments
+    /// * `data` - String containing RINEX clock data
+    ///
+    /// # Returns
+    /// * `Ok(RinexClock)` - Successfully parsed clock data
+    /// * `Err(Error)` - If the file is not a clock file or a line is invalid
+    ///
+    /// # Errors
+    /// * Returns an error with the line and column of the first invalid field
+    pub fn read_string(data: &str) -> Result<Self, Error> {
+        let mut lines = data.lines().enumerate().map(|(i, line)| (i + 1, line));
+        let (_, first) = lines
+            .next()
+            .ok_or_else(|| Error::product("Empty RINEX clock file"))?;
+        let mut tokens = first.split_whitespace();
+        let version = tokens
+            .next()
+            .unwrap_or_default()
+            .parse()
+            .map_err(|e| Error::from(e).at((1, 1)))?;
+        if !first.contains("RINEX VERSION / TYPE") || tokens.next() != Some("C")
+        {
+            return Err(Error::product("Not a RINEX clock file").at((1, 1)));
+        }
+        let mut clock = RinexClock {
+            version,
+            time_system: "GPS".to_string(),
+            records: Vec::new(),
+        };
+        for (_, line) in lines.by_ref() {
+            if line.contains("END OF HEADER") {
+                break;
+            } else if line.contains("TIME SYSTEM ID")
+                && let Some(time_system) = line.split_whitespace().next()
+            {
+                time_system.clone_into(&mut clock.time_system);
+            }
+        }
+        let mut continuation_lines = 0;
+        for (number, line) in lines {
+            if continuation_lines > 0 {
+                continuation_lines -= 1;
+                continue;
+            }
+            if line.trim().is_empty() {
+                continue;
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [kind, name, date @ .., count] = fields.get(..9).unwrap_or(&[])
+            else {
+                return Err(
+                    Error::product("Truncated clock record").at((number, 1))
+                );
+            };
+            let count: usize =
+                count.parse().map_err(|e| Error::from(e).at((number, 1)))?;
+            continuation_lines = count
+                .saturating_sub(VALUES_PER_FIRST_LINE)
+                .div_ceil(VALUES_PER_LINE);
+            if *kind != "AS" {
+                continue;
+            }
+            let satellite =
+                SatelliteId::from_str(name).map_err(|e| e.at((number, 4)))?;
+            let epoch =
+                epoch_time(&date.join(" ")).map_err(|e| e.at((number, 1)))?;
+            let mut values = fields[9..].iter().map(|value| {
+                value
+                    .replace('D', "E")
+                    .parse::<f64>()
+                    .map_err(|e| Error::from(e).at((number, 1)))
+            });
+            let bias = values.next().transpose()?.ok_or_else(|| {
+                Error::product("Missing clock offset").at((number, 1))
+            })?;
+            let sigma = if count > 1 {
+                values.next().transpose()?
+            } else {
+                None
+            };
+            clock.records.push(ClockRecord {
+                satellite,
+                epoch,
+                bias,
+                sigma,
+            });
+        }
+        Ok(clock)
+    }
+}
@@ -25,7 +25,7 @@ use crate::error::Error;
 /// - a₂ is the clock drift rate (seconds/second²)
 /// - t is the current time
 /// - t₀ is the reference time
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct SvClock {
     /// Clock bias term a₀ (seconds)
     pub bias: f64,
@@ -66,7 +66,7 @@ impl SvClock {
 ///
 /// The ephemeris data is organized into clock parameters and seven sets of
 /// orbital parameters, following the standard RINEX navigation message format.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Ephemeris {
     /// Satellite PRN (Pseudo-Random Noise) number (1-32)
     pub prn: usize,
@@ -6,7 +6,7 @@
 /// harmonic correction term to the orbit radius (Crs), the mean motion
 /// difference from computed value (Delta n), and the mean anomaly at reference
 /// time (M0).
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Orbit1 {
     /// Issue of Data Ephemeris (IODE)
     /// Used to match ephemeris sets and for timing
@@ -31,7 +31,7 @@ pub struct Orbit1 {
 /// of latitude (Cuc), the eccentricity of the orbit (e), the amplitude of the
 /// sine harmonic correction term to the argument of latitude (Cus), and the
 /// square root of the semi-major axis (√A).
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Orbit2 {
     /// Amplitude of the cosine harmonic correction term to the argument of
     /// latitude (radians)
@@ -57,7 +57,7 @@ pub struct Orbit2 {
 /// longitude of ascending node of orbit plane at weekly epoch (Ω), and the
 /// amplitude of the sine harmonic correction term to the angle of inclination
 /// (Cis).
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Orbit3 {
     /// Reference time for the ephemeris (seconds of GPS week)
     pub toe: f64,
@@ -81,7 +81,7 @@ pub struct Orbit3 {
 /// include the inclination angle at reference time (i0), the amplitude of the
 /// cosine harmonic correction term to the orbit radius (Crc), the argument of
 /// perigee (ω), and the rate of right ascension (Ω̇).
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Orbit4 {
     /// Inclination angle at reference time (radians)
     pub i0: f64,
@@ -104,7 +104,7 @@ pub struct Orbit4 {
 /// characteristics. These parameters include the rate of inclination angle (i̇),
 /// the codes on the L2 channel, the GPS week number for the ephemeris reference
 /// time, and the L2 P-code data flag.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Orbit5 {
     /// Rate of inclination angle (radians/sec)
     pub idot: f64,
@@ -128,7 +128,7 @@ pub struct Orbit5 {
 /// characteristics. These parameters include the satellite vehicle accuracy
 /// (URA), the satellite health status, the Total Group Delay (TGD) for timing
 /// correction, and the Issue of Data Clock (IODC) for timing reference.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Orbit6 {
     /// Satellite vehicle accuracy (URA) in meters
     /// User Range Accuracy index for position error estimation
@@ -150,17 +150,17 @@ pub struct Orbit6 {
 /// Seventh set of orbital parameters from the GPS navigation message.
 ///
 /// This structure contains the seventh and final set of parameters from the
-/// RINEX navigation message. It includes the transmission time of the message
-/// and three spare fields that are reserved for future use or system-specific
-/// parameters.
-#[derive(Debug, Clone, Default)]
+/// RINEX navigation message. It includes the transmission time of the message,
+/// the curve fit interval and two spare fields that are reserved for future
+/// use or system-specific parameters.
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Orbit7 {
     /// Transmission time of message (seconds of GPS week)
     /// Derived from the Z-count in the Hand Over Word (HOW)
     pub tom: f64,
 
-    /// Spare field 1 (reserved for future use)
-    pub spare1: f64,
+    /// Curve fit interval in hours (0 if not known)
+    pub fit_interval: f64,
 
     /// Spare field 2 (reserved for future use)
     pub spare2: f64,
@@ -243,9 +243,56 @@ impl From<[f64; 4]> for Orbit7 {
     fn from(data: [f64; 4]) -> Self {
         Self {
             tom: data[0],
-            spare1: data[1],
+            fit_interval: data[1],
             spare2: data[2],
             spare3: data[3],
         }
     }
 }
+
+/// Converts Orbit1 parameters back into the four fields of a broadcast orbit
+/// line.
+///
+/// This implementation is used to write RINEX navigation files.
+impl From<&Orbit1> for [f64; 4] {
+    /// Creates an array of 4 floating-point values from Orbit1 parameters.
+    ///
+    /// # Arguments
+    /// * `orbit` - The orbit parameters
+    ///
+    /// # Returns
+    /// An array containing [`iode`, `crs`, `delta_n`, `m0`]
+    fn from(orbit: &Orbit1) -> Self {
+        [orbit.iode, orbit.crs, orbit.delta_n, orbit.m0]
+    }
+}
+impl From<&Orbit2> for [f64; 4] {
+    fn from(orbit: &Orbit2) -> Self {
+        [orbit.cuc, orbit.ecc, orbit.cus, orbit.sqrta]
+    }
+}
+impl From<&Orbit3> for [f64; 4] {
+    fn from(orbit: &Orbit3) -> Self {
+        [orbit.toe, orbit.cic, orbit.omega, orbit.cis]
+    }
+}
+impl From<&Orbit4> for [f64; 4] {
+    fn from(orbit: &Orbit4) -> Self {
+        [orbit.i0, orbit.crc, orbit.omega, orbit.omega_dot]
+    }
+}
+impl From<&Orbit5> for [f64; 4] {
+    fn from(orbit: &Orbit5) -> Self {
+        [orbit.idot, orbit.code_l2, orbit.week, orbit.l2_pseudorange]
+    }
+}
+impl From<&Orbit6> for [f64; 4] {
+    fn from(orbit: &Orbit6) -> Self {
+        [orbit.sv_accuracy, orbit.sv_health, orbit.tgd, orbit.iodc]
+    }
+}
+impl From<&Orbit7> for [f64; 4] {
+    fn from(orbit: &Orbit7) -> Self {
+        [orbit.tom, orbit.fit_interval, orbit.spare2, orbit.spare3]
+    }
+}
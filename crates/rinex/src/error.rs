@@ -1,3 +1,6 @@
+use std::fmt;
+
+use pest::error::LineColLocation;
 use thiserror::Error;
 
 use crate::rule::Rule;
@@ -7,7 +10,7 @@ use crate::rule::Rule;
 #[non_exhaustive]
 pub enum Error {
     /// Error when reading RINEX file from disk
-    #[error("RINEX file cannot be read")]
+    #[error("RINEX file cannot be read: {0}")]
     ReadRinex(#[from] std::io::Error),
 
     /// Error when parsing floating point values from RINEX file
@@ -22,10 +25,30 @@ pub enum Error {
     #[error("Cannot parse RINEX file: {0}")]
     ParseFile(#[from] Box<pest::error::Error<Rule>>),
 
+    /// Error at a position of the RINEX file
+    #[error("line {line}, column {column}: {source}")]
+    Position {
+        /// Line number, starting at 1
+        line: usize,
+        /// Column number, starting at 1
+        column: usize,
+        /// Error found at the position
+        #[source]
+        source: Box<Error>,
+    },
+
     /// Error when processing a specific parsing rule
     #[error("Cannot parse rule: {0}")]
     Rule(String),
 
+    /// Error when parsing an SP3 orbit or RINEX clock file
+    #[error("Cannot parse precise product: {0}")]
+    Product(String),
+
+    /// Error when parsing a YUMA or SEM almanac file
+    #[error("Cannot parse almanac: {0}")]
+    Almanac(String),
+
     /// Error when building a RINEX object
     #[error("RINEX builder error: {0}")]
     RinexBuilder(String),
@@ -50,6 +73,52 @@ impl Error {
         Error::Rule(message.into())
     }
 
+    /// Attaches the position of the offending text to an error.
+    ///
+    /// Errors that already carry a position are returned unchanged.
+    ///
+    /// # Arguments
+    /// * `(line, column)` - Position, starting at 1
+    #[must_use]
+    pub fn at(self, (line, column): (usize, usize)) -> Self {
+        match self {
+            Error::Position { .. } | Error::ParseFile(_) => self,
+            source => Error::Position {
+                line,
+                column,
+                source: Box::new(source),
+            },
+        }
+    }
+
+    /// Returns the line and column of the error, if known.
+    ///
+    /// # Returns
+    /// * `Some((line, column))` - Position of the error, starting at 1
+    /// * `None` - If the error is not related to a position of the file
+    pub fn position(&self) -> Option<(usize, usize)> {
+        match self {
+            Error::Position { line, column, .. } => Some((*line, *column)),
+            Error::ParseFile(e) => match e.line_col {
+                LineColLocation::Pos(position)
+                | LineColLocation::Span(position, _) => Some(position),
+            },
+            _ => None,
+        }
+    }
+
+    /// Create a new precise product error
+    #[inline]
+    pub fn product(message: impl Into<String>) -> Self {
+        Error::Product(message.into())
+    }
+
+    /// Create a new almanac error
+    #[inline]
+    pub fn almanac(message: impl Into<String>) -> Self {
+        Error::Almanac(message.into())
+    }
+
     /// Create a new RINEX builder error
     #[inline]
     pub fn rinex_builder(message: impl Into<String>) -> Self {
@@ -62,3 +131,24 @@ impl Error {
         Error::EphemerisBuilder(message.into())
     }
 }
+
+/// Problem skipped while reading a RINEX file in lenient mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseWarning {
+    /// Line number, starting at 1
+    pub line: usize,
+    /// Column number, starting at 1
+    pub column: usize,
+    /// Description of the problem and of what was skipped
+    pub message: String,
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
@@ -0,0 +1,253 @@
+//! RINEX 3 and RINEX 4 navigation records.
+//!
+//! RINEX 3 mixed navigation files carry the broadcast ephemerides of every
+//! GNSS, each record introduced by a system-prefixed satellite ID such as
+//! `G01` or `E11`. RINEX 4 wraps these records in frames that also carry
+//! system time offsets (STO), Earth orientation parameters (EOP) and
+//! ionospheric model parameters (ION).
+//!
+//! Records are kept in the order of the file, with the numeric fields of the
+//! epoch line and the broadcast orbit lines in a flat list. GPS LNAV records
+//! can be converted into an `Ephemeris`. The records of RINEX 2 GLONASS files
+//! use the same layout as their RINEX 3 counterparts.
+
+use std::{fmt, str::FromStr};
+
+use jiff::{civil::DateTime, tz::TimeZone};
+
+use crate::{
+    ephemeris::{Ephemeris, EphemerisBuilder, SvClock},
+    error::Error,
+};
+
+/// Number of clock fields on the epoch line of an ephemeris record.
+const CLOCK_FIELDS: usize = 3;
+
+/// Number of fields of a broadcast orbit line.
+const ORBIT_FIELDS: usize = 4;
+
+/// Number of broadcast orbit lines of a GPS LNAV record.
+const LNAV_ORBITS: usize = 7;
+
+/// Global navigation satellite system of a satellite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SatelliteSystem {
+    /// GPS (G)
+    Gps,
+    /// GLONASS (R)
+    Glonass,
+    /// Galileo (E)
+    Galileo,
+    /// BDS (C)
+    Beidou,
+    /// QZSS (J)
+    Qzss,
+    /// NavIC/IRNSS (I)
+    Irnss,
+    /// SBAS payload (S)
+    Sbas,
+}
+
+impl SatelliteSystem {
+    /// Returns the system identifier used in RINEX files.
+    pub fn as_char(self) -> char {
+        match self {
+            Self::Gps => 'G',
+            Self::Glonass => 'R',
+            Self::Galileo => 'E',
+            Self::Beidou => 'C',
+            Self::Qzss => 'J',
+            Self::Irnss => 'I',
+            Self::Sbas => 'S',
+        }
+    }
+}
+
+impl TryFrom<char> for SatelliteSystem {
+    type Error = Error;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            'G' => Ok(Self::Gps),
+            'R' => Ok(Self::Glonass),
+            'E' => Ok(Self::Galileo),
+            'C' => Ok(Self::Beidou),
+            'J' => Ok(Self::Qzss),
+            'I' => Ok(Self::Irnss),
+            'S' => Ok(Self::Sbas),
+            _ => {
+                Err(Error::rule(format!("Unknown satellite system '{value}'")))
+            }
+        }
+    }
+}
+
+/// System-prefixed satellite identifier such as `G01` or `C19`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SatelliteId {
+    /// Satellite system
+    pub system: SatelliteSystem,
+    /// PRN, slot or SBAS number within the system (SBAS: PRN - 100)
+    pub prn: usize,
+}
+
+impl FromStr for SatelliteId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let system = chars
+            .next()
+            .ok_or_else(|| Error::rule("Empty satellite ID"))
+            .and_then(SatelliteSystem::try_from)?;
+        let prn = chars.as_str().trim().parse()?;
+        Ok(Self { system, prn })
+    }
+}
+
+impl fmt::Display for SatelliteId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{:02}", self.system.as_char(), self.prn)
+    }
+}
+
+/// Kind of a navigation record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NavRecordKind {
+    /// Broadcast ephemeris (RINEX 3 record or RINEX 4 EPH frame)
+    Ephemeris,
+    /// System time offset (RINEX 4 STO frame)
+    SystemTimeOffset,
+    /// Earth orientation parameters (RINEX 4 EOP frame)
+    EarthOrientation,
+    /// Ionospheric model parameters (RINEX 4 ION frame)
+    Ionosphere,
+}
+
+/// A navigation record of a RINEX 3 or RINEX 4 file (or a RINEX 2 GLONASS
+/// file).
+///
+/// The numeric fields are stored in file order: for ephemerides the clock
+/// bias, drift and drift rate followed by the broadcast orbit lines, four
+/// fields per line. Blank fields read as zero.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NavRecord {
+    /// Kind of the record
+    pub kind: NavRecordKind,
+    /// Transmitting satellite
+    pub satellite: SatelliteId,
+    /// RINEX 4 message type such as LNAV, CNAV, INAV, FNAV, D1 or FDMA
+    /// (`None` for RINEX 3)
+    pub message: Option<String>,
+    /// Epoch of the record in the time system of the satellite
+    pub epoch: DateTime,
+    /// Text fields of the epoch line, such as the time offset label of an
+    /// STO frame
+    pub label: Option<String>,
+    /// Numeric fields in file order
+    pub values: Vec<f64>,
+}
+
+impl NavRecord {
+    /// Returns whether the record is a GPS ephemeris in the legacy LNAV
+    /// format.
+    ///
+    /// RINEX 3 GPS records are always LNAV; RINEX 4 GPS records may also be
+    /// CNAV or CNAV-2.
+    pub fn is_gps_lnav(&self) -> bool {
+        self.kind == NavRecordKind::Ephemeris
+            && self.satellite.system == SatelliteSystem::Gps
+            && self
+                .message
+                .as_deref()
+                .is_none_or(|message| message == "LNAV")
+    }
+
+    /// Returns a broadcast orbit line of an ephemeris record.
+    ///
+    /// # Arguments
+    /// * `line` - Broadcast orbit line number, starting at 1
+    ///
+    /// # Returns
+    /// The four fields of the line, with missing fields as zero
+    pub fn orbit(&self, line: usize) -> [f64; 4] {
+        let start = CLOCK_FIELDS + (line.max(1) - 1) * ORBIT_FIELDS;
+        std::array::from_fn(|i| {
+            self.values.get(start + i).copied().unwrap_or_default()
+        })
+    }
+
+    /// Converts a GPS LNAV record into an ephemeris.
+    ///
+    /// # Returns
+    /// * `Ok(Ephemeris)` - The ephemeris in the RINEX 2 representation
+    /// * `Err(Error)` - If the record is not a complete GPS LNAV ephemeris
+    ///
+    /// # Errors
+    /// * Returns an error if the record is not a GPS LNAV ephemeris
+    /// * Returns an error if a broadcast orbit line before the seventh is
+    ///   missing
+    pub fn to_ephemeris(&self) -> Result<Ephemeris, Error> {
+        if !self.is_gps_lnav() {
+            return Err(Error::rule(format!(
+                "{} record is not a GPS LNAV ephemeris",
+                self.satellite
+            )));
+        }
+        if self.values.len() <= CLOCK_FIELDS + (LNAV_ORBITS - 1) * ORBIT_FIELDS
+        {
+            return Err(Error::rule(format!(
+                "{} ephemeris has only {} fields",
+                self.satellite,
+                self.values.len()
+            )));
+        }
+        let mut builder = EphemerisBuilder::new();
+        builder.set_prn(self.satellite.prn);
+        builder
+            .set_time_of_clock(self.epoch.to_zoned(TimeZone::UTC)?.timestamp());
+        builder.set_sv_clock(SvClock::new(
+            self.values[0],
+            self.values[1],
+            self.values[2],
+        ));
+        builder.set_orbit1(self.orbit(1).into());
+        builder.set_orbit2(self.orbit(2).into());
+        builder.set_orbit3(self.orbit(3).into());
+        builder.set_orbit4(self.orbit(4).into());
+        builder.set_orbit5(self.orbit(5).into());
+        builder.set_orbit6(self.orbit(6).into());
+        builder.set_orbit7(self.orbit(7).into());
+        builder.build()
+    }
+}
+
+/// Ionospheric correction of a RINEX 3 `IONOSPHERIC CORR` header line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IonosphericCorrection {
+    /// Correction type such as GPSA, GPSB, GAL, BDSA or QZSB
+    pub kind: String,
+    /// Parameters (Klobuchar alpha or beta, or Galileo ai0-ai2)
+    pub values: [f64; 4],
+    /// Transmission time mark (A-X for BDS), if given
+    pub time_mark: Option<char>,
+    /// Transmitting satellite number, if given
+    pub satellite: Option<usize>,
+}
+
+/// Time system correction of a RINEX 3 `TIME SYSTEM CORR` header line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeSystemCorrection {
+    /// Correction type such as GPUT, GAUT, GAGP or BDUT
+    pub kind: String,
+    /// Constant term (seconds)
+    pub a0: f64,
+    /// First-order term (seconds/second)
+    pub a1: f64,
+    /// Reference time (seconds of week)
+    pub time: i32,
+    /// Reference week number
+    pub week: i32,
+    /// Source of the correction (SBAS provider and UTC identifier), if given
+    pub source: String,
+}
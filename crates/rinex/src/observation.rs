@@ -0,0 +1,355 @@
+//! RINEX 3 observation file writer.
+//!
+//! This module writes observation data (pseudorange, carrier phase, Doppler
+//! and signal strength) in the RINEX 3.04 format, so that simulated
+//! measurements can be post-processed with standard GNSS tools.
+
+use std::{fmt::Write as _, io::Write};
+
+use jiff::Timestamp;
+
+use crate::error::Error;
+
+/// RINEX format version written by `ObservationWriter`.
+pub const OBSERVATION_VERSION: f64 = 3.04;
+
+/// Maximum number of observation types on one `SYS / # / OBS TYPES` line.
+const TYPES_PER_LINE: usize = 13;
+
+/// Calendar time of an observation epoch in the GPS time system.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ObservationTime {
+    /// Calendar year (four digits)
+    pub year: i32,
+    /// Calendar month (1-12)
+    pub month: i32,
+    /// Calendar day (1-31)
+    pub day: i32,
+    /// Hour (0-23)
+    pub hour: i32,
+    /// Minute (0-59)
+    pub minute: i32,
+    /// Seconds including the fractional part
+    pub second: f64,
+}
+
+/// Header information of a RINEX observation file.
+#[derive(Debug, Clone)]
+pub struct ObservationHeader {
+    /// Name of the program creating the file
+    pub program: String,
+    /// Name of the agency creating the file
+    pub run_by: String,
+    /// Name of the antenna marker
+    pub marker_name: String,
+    /// Name of the observer
+    pub observer: String,
+    /// Receiver type
+    pub receiver_type: String,
+    /// Antenna type
+    pub antenna_type: String,
+    /// Approximate marker position in ECEF coordinates (meters)
+    pub approx_position: [f64; 3],
+    /// Observation interval in seconds
+    pub interval: f64,
+    /// Time of the first observation epoch
+    pub first_epoch: ObservationTime,
+    /// Number of leap seconds between GPS time and UTC, if known
+    pub leap_seconds: Option<i32>,
+    /// Observation types per satellite system (e.g. `('G', ["C1C", "L1C"])`)
+    pub observation_types: Vec<(char, Vec<String>)>,
+    /// Comment lines
+    pub comments: Vec<String>,
+}
+
+impl Default for ObservationHeader {
+    fn default() -> Self {
+        Self {
+            program: String::new(),
+            run_by: String::new(),
+            marker_name: "UNKNOWN".to_string(),
+            observer: String::new(),
+            receiver_type: String::new(),
+            antenna_type: String::new(),
+            approx_position: [0.0; 3],
+            interval: 1.0,
+            first_epoch: ObservationTime::default(),
+            leap_seconds: None,
+            observation_types: Vec::new(),
+            comments: Vec::new(),
+        }
+    }
+}
+
+/// Observations of one satellite at one epoch.
+#[derive(Debug, Clone, Default)]
+pub struct SatelliteObservation {
+    /// Satellite system identifier (e.g. 'G' for GPS)
+    pub system: char,
+    /// Satellite number within the system (PRN for GPS)
+    pub prn: usize,
+    /// One value per observation type of the system, in header order
+    /// (`None` for a missing observation)
+    pub values: Vec<Option<f64>>,
+}
+
+/// Observations of all tracked satellites at one epoch.
+#[derive(Debug, Clone, Default)]
+pub struct ObservationEpoch {
+    /// Epoch time in the GPS time system
+    pub time: ObservationTime,
+    /// Epoch flag (0 for a normal epoch)
+    pub flag: u8,
+    /// Observed satellites
+    pub satellites: Vec<SatelliteObservation>,
+}
+
+/// Writes a RINEX 3 observation file.
+///
+/// The header is written when the writer is created and every call to
+/// `write_epoch` appends one epoch record.
+#[derive(Debug)]
+pub struct ObservationWriter<W: Write> {
+    /// Destination of the RINEX text
+    writer: W,
+    /// Number of observation types per satellite system
+    type_counts: Vec<(char, usize)>,
+}
+
+impl<W: Write> ObservationWriter<W> {
+    /// Creates a writer and writes the observation file header.
+    ///
+    /// # Arguments
+    /// * `writer` - Destination of the RINEX text
+    /// * `header` - Header information of the file
+    ///
+    /// # Returns
+    /// * `Ok(Self)` - A new `ObservationWriter`
+    /// * `Err(Error)` - If the header is invalid or cannot be written
+    ///
+    /// # Errors
+    /// * Returns an error if no observation types are defined or if writing
+    ///   fails
+    pub fn new(
+        mut writer: W, header: &ObservationHeader,
+    ) -> Result<Self, Error> {
+        if header.observation_types.is_empty() {
+            return Err(Error::rinex_builder("No observation types defined"));
+        }
+        write_header(&mut writer, header)?;
+        let type_counts = header
+            .observation_types
+            .iter()
+            .map(|(system, types)| (*system, types.len()))
+            .collect();
+        Ok(Self {
+            writer,
+            type_counts,
+        })
+    }
+
+    /// Writes one epoch record.
+    ///
+    /// # Arguments
+    /// * `epoch` - Epoch time, flag and satellite observations
+    ///
+    /// # Returns
+    /// * `Ok(())` - If the epoch was written
+    /// * `Err(Error)` - If the epoch is invalid or cannot be written
+    ///
+    /// # Errors
+    /// * Returns an error if a satellite belongs to a system without
+    ///   observation types, has more values than types, or if writing fails
+    pub fn write_epoch(
+        &mut self, epoch: &ObservationEpoch,
+    ) -> Result<(), Error> {
+        let t = &epoch.time;
+        writeln!(
+            self.writer,
+            "> {:4} {:02} {:02} {:02} {:02}{:11.7}  {}{:3}",
+            t.year,
+            t.month,
+            t.day,
+            t.hour,
+            t.minute,
+            t.second,
+            epoch.flag,
+            epoch.satellites.len()
+        )?;
+        for sat in &epoch.satellites {
+            let count = self
+                .type_counts
+                .iter()
+                .find(|(system, _)| *system == sat.system)
+                .map(|(_, count)| *count)
+                .ok_or_else(|| {
+                    Error::rinex_builder(format!(
+                        "No observation types for system '{}'",
+                        sat.system
+                    ))
+                })?;
+            if sat.values.len() > count {
+                return Err(Error::rinex_builder(format!(
+                    "{}{:02} has {} values for {count} observation types",
+                    sat.system,
+                    sat.prn,
+                    sat.values.len()
+                )));
+            }
+            let mut line = format!("{}{:02}", sat.system, sat.prn);
+            for value in &sat.values {
+                match value {
+                    Some(v) => {
+                        let _ = write!(line, "{v:14.3}  ");
+                    }
+                    None => line.push_str(&" ".repeat(16)),
+                }
+            }
+            writeln!(self.writer, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+
+    /// Flushes buffered output.
+    ///
+    /// # Errors
+    /// * Returns an error if the destination cannot be written
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// Returns the underlying writer.
+    ///
+    /// # Returns
+    /// The destination the RINEX text was written to
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Writes one header line with its label in columns 61-80.
+///
+/// # Arguments
+/// * `writer` - Destination of the RINEX text
+/// * `content` - Header content (truncated to 60 characters)
+/// * `label` - Header label
+///
+/// # Errors
+/// * Returns an error if writing fails
+pub(crate) fn header_line(
+    writer: &mut impl Write, content: &str, label: &str,
+) -> Result<(), Error> {
+    let content: String = content.chars().take(60).collect();
+    writeln!(writer, "{content:<60}{label}")?;
+    Ok(())
+}
+
+/// Writes the complete observation header.
+///
+/// # Arguments
+/// * `writer` - Destination of the RINEX text
+/// * `header` - Header information
+///
+/// # Errors
+/// * Returns an error if writing fails
+fn write_header(
+    writer: &mut impl Write, header: &ObservationHeader,
+) -> Result<(), Error> {
+    let system = if header.observation_types.len() == 1 {
+        header.observation_types[0].0
+    } else {
+        'M'
+    };
+    header_line(
+        writer,
+        &format!(
+            "{OBSERVATION_VERSION:9.2}{:11}{:<20}{system:<20}",
+            "", "OBSERVATION DATA"
+        ),
+        "RINEX VERSION / TYPE",
+    )?;
+    let date = Timestamp::now().strftime("%Y%m%d %H%M%S UTC").to_string();
+    header_line(
+        writer,
+        &format!(
+            "{:<20.20}{:<20.20}{date:<20}",
+            header.program, header.run_by
+        ),
+        "PGM / RUN BY / DATE",
+    )?;
+    for comment in &header.comments {
+        header_line(writer, comment, "COMMENT")?;
+    }
+    header_line(writer, &header.marker_name, "MARKER NAME")?;
+    header_line(writer, "NON_GEODETIC", "MARKER TYPE")?;
+    header_line(
+        writer,
+        &format!("{:<20.20}{:<40.40}", header.observer, header.run_by),
+        "OBSERVER / AGENCY",
+    )?;
+    header_line(
+        writer,
+        &format!("{:<20}{:<20.20}{:<20}", "0", header.receiver_type, ""),
+        "REC # / TYPE / VERS",
+    )?;
+    header_line(
+        writer,
+        &format!("{:<20}{:<20.20}", "0", header.antenna_type),
+        "ANT # / TYPE",
+    )?;
+    let [x, y, z] = header.approx_position;
+    header_line(
+        writer,
+        &format!("{x:14.4}{y:14.4}{z:14.4}"),
+        "APPROX POSITION XYZ",
+    )?;
+    header_line(
+        writer,
+        &format!("{:14.4}{:14.4}{:14.4}", 0.0, 0.0, 0.0),
+        "ANTENNA: DELTA H/E/N",
+    )?;
+    for (system, types) in &header.observation_types {
+        for (k, chunk) in types.chunks(TYPES_PER_LINE).enumerate() {
+            let mut content = if k == 0 {
+                format!("{system}  {:3}", types.len())
+            } else {
+                " ".repeat(6)
+            };
+            for obs_type in chunk {
+                let _ = write!(content, " {obs_type:3}");
+            }
+            header_line(writer, &content, "SYS / # / OBS TYPES")?;
+        }
+    }
+    header_line(writer, "DBHZ", "SIGNAL STRENGTH UNIT")?;
+    header_line(writer, &format!("{:10.3}", header.interval), "INTERVAL")?;
+    let t = &header.first_epoch;
+    header_line(
+        writer,
+        &format!(
+            "{:6}{:6}{:6}{:6}{:6}{:13.7}{:5}{:3}",
+            t.year, t.month, t.day, t.hour, t.minute, t.second, "", "GPS"
+        ),
+        "TIME OF FIRST OBS",
+    )?;
+    for (system, types) in &header.observation_types {
+        for obs_type in types.iter().filter(|t| t.starts_with('L')) {
+            header_line(
+                writer,
+                &format!("{system} {obs_type:3} {:8.5}", 0.0),
+                "SYS / PHASE SHIFT",
+            )?;
+        }
+    }
+    header_line(
+        writer,
+        &format!(" C1C {0:8.3} C1P {0:8.3} C2C {0:8.3} C2P {0:8.3}", 0.0),
+        "GLONASS COD/PHS/BIS",
+    )?;
+    if let Some(leap_seconds) = header.leap_seconds {
+        header_line(writer, &format!("{leap_seconds:6}"), "LEAP SECONDS")?;
+    }
+    header_line(writer, "", "END OF HEADER")?;
+    Ok(())
+}
@@ -2,18 +2,41 @@
 //! files.
 //!
 //! This crate provides functionality to parse RINEX navigation files containing
-//! GPS ephemeris data. It supports the standard RINEX 2.x format commonly used
-//! for distributing GPS satellite orbit information.
+//! GPS ephemeris data. It supports the RINEX 2.x GPS format as well as RINEX
+//! 3.0x mixed and RINEX 4.0x navigation files, whose records of other systems
+//! are kept in the `navigation` module's structured form.
+//!
+//! RINEX 3 observation files can be written with the `observation` module,
+//! RINEX 2.11 and 3.04 navigation files with the `writer` module. Precise
+//! orbits and clocks are read from SP3 files with the `sp3` module and from
+//! RINEX clock files with the `clock` module, and YUMA and SEM almanacs with
+//! the `almanac` module.
 
+/// YUMA and SEM almanac reader
+pub mod almanac;
+/// RINEX clock file reader
+pub mod clock;
 /// GPS satellite ephemeris data structures and builders
 pub mod ephemeris;
 /// Error types for RINEX parsing operations
 pub mod error;
+/// RINEX 3 and 4 navigation records of all systems
+pub mod navigation;
+/// RINEX observation file writer
+pub mod observation;
 /// RINEX file parsing rules and implementation
 pub mod rule;
+/// SP3 precise orbit file reader
+pub mod sp3;
 /// UTC time conversion utilities
 pub mod utc;
 /// Utility functions for RINEX parsing
 pub mod utils;
+/// RINEX navigation file writer
+pub mod writer;
+pub use almanac::Almanac;
+pub use clock::RinexClock;
 pub use error::Error;
 pub use rule::Rinex;
+pub use sp3::Sp3;
+pub use writer::NavigationFormat;
@@ -4,7 +4,7 @@ use std::{
     path::Path,
 };
 
-use jiff::Timestamp;
+use jiff::{Timestamp, civil::DateTime};
 use pest::{
     Parser,
     iterators::{Pair, Pairs},
@@ -16,10 +16,26 @@ use crate::{
         Ephemeris, EphemerisBuilder, Orbit1, Orbit2, Orbit3, Orbit4, Orbit5,
         Orbit6, Orbit7, SvClock,
     },
-    error::Error,
-    utc::DeltaUtc,
+    error::{Error, ParseWarning},
+    navigation::{
+        IonosphericCorrection, NavRecord, NavRecordKind, SatelliteId,
+        SatelliteSystem, TimeSystemCorrection,
+    },
+    utc::{DeltaUtc, gps_week_seconds},
 };
 
+/// Width of a numeric field of RINEX 3 and 4 navigation records.
+const NAV_FIELD_WIDTH: usize = 19;
+
+/// Number of numeric fields on the epoch line of a navigation record.
+const EPOCH_LINE_FIELDS: usize = 3;
+
+/// Number of numeric fields on a broadcast orbit line.
+const ORBIT_LINE_FIELDS: usize = 4;
+
+/// Column (0-based) of the label of a header line.
+const HEADER_LABEL_COLUMN: usize = 60;
+
 /// Parser implementation for RINEX files using pest grammar
 #[derive(Parser)]
 #[grammar = "rinex.pest"]
@@ -30,7 +46,14 @@ pub struct RinexParser;
 /// This structure contains all the information parsed from a RINEX navigation
 /// file, including header information, ionospheric parameters, UTC conversion
 /// parameters, and satellite ephemerides.
-#[derive(Debug)]
+///
+/// RINEX 2 GPS and GLONASS, RINEX 3 and RINEX 4 navigation files are
+/// supported. GPS LNAV ephemerides of any version end up in `ephemerides`; all
+/// other records, including those of RINEX 2 GLONASS files, are kept in
+/// `records`. The GPS Klobuchar and UTC
+/// parameters are taken from the RINEX 2 header lines, the RINEX 3 GPSA, GPSB
+/// and GPUT corrections or the RINEX 4 ION and STO frames.
+#[derive(Debug, Clone)]
 pub struct Rinex {
     /// Format version
     pub version: String,
@@ -42,18 +65,26 @@ pub struct Rinex {
     pub agency: String,
     /// Date
     pub update: String,
-    /// Comments
+    /// Comments (one line per comment header line)
     pub comments: String,
     /// Ionosphere parameters A0-A3 of almanac
-    pub ion_alpha: [f64; 4],
+    pub ion_alpha: Option<[f64; 4]>,
     /// Ionosphere parameters B0-B3 of almanac
-    pub ion_beta: [f64; 4],
+    pub ion_beta: Option<[f64; 4]>,
     /// Almanac parameters to compute time in UTC
-    pub delta_utc: DeltaUtc,
+    pub delta_utc: Option<DeltaUtc>,
     /// Delta time due to leap seconds
-    pub leap_seconds: i32,
-    /// Ephemeris data
+    pub leap_seconds: Option<i32>,
+    /// Ionospheric corrections of all systems (RINEX 3 header)
+    pub ionospheric_corrections: Vec<IonosphericCorrection>,
+    /// Time system corrections of all systems (RINEX 3 header)
+    pub time_system_corrections: Vec<TimeSystemCorrection>,
+    /// GPS LNAV ephemeris data
     pub ephemerides: Vec<Ephemeris>,
+    /// Other navigation records of RINEX 2 GLONASS, RINEX 3 and 4 files
+    pub records: Vec<NavRecord>,
+    /// Header lines and records skipped when reading in lenient mode
+    pub warnings: Vec<ParseWarning>,
 }
 impl Rinex {
     /// Reads a RINEX navigation file from the filesystem.
@@ -111,8 +142,11 @@ impl Rinex {
                 Rule::header => {
                     read_header(&mut line.into_inner(), &mut builder)?;
                 }
-                Rule::ephemerides => {
-                    read_ephemerides(&mut line.into_inner(), &mut builder)?;
+                Rule::ephemerides
+                | Rule::glonass_ephemerides
+                | Rule::navigation_v3
+                | Rule::navigation_v4 => {
+                    read_records(&mut line.into_inner(), &mut builder)?;
                 }
                 Rule::EOI => {} // Expected end of input marker by pest
                 _ => {
@@ -126,6 +160,94 @@ impl Rinex {
         let rinex = builder.build()?;
         Ok(rinex)
     }
+
+    /// Reads a RINEX navigation file from the filesystem in lenient mode.
+    ///
+    /// See [`Rinex::read_string_lenient`].
+    ///
+    /// # Arguments
+    /// * `path` - Path to the RINEX navigation file
+    ///
+    /// # Returns
+    /// * `Ok(Rinex)` - Parsed RINEX data with the skipped lines in `warnings`
+    /// * `Err(Error)` - If the file cannot be read or has no usable header
+    ///
+    /// # Errors
+    /// * Returns an error if the file cannot be read, if `END OF HEADER` is
+    ///   missing or if the version line is missing or invalid
+    pub fn read_file_lenient(path: &dyn AsRef<Path>) -> Result<Self, Error> {
+        let data = fs::read_to_string(path)?;
+        Self::read_string_lenient(data.as_str())
+    }
+
+    /// Parses a RINEX navigation file from a string in lenient mode.
+    ///
+    /// Header lines and records are parsed one by one. Unknown header labels,
+    /// malformed header lines and malformed or truncated records are skipped
+    /// and reported in `warnings` with their line and column.
+    ///
+    /// # Arguments
+    /// * `data` - String containing RINEX navigation data
+    ///
+    /// # Returns
+    /// * `Ok(Rinex)` - Parsed RINEX data with the skipped lines in `warnings`
+    /// * `Err(Error)` - If the file has no usable header
+    ///
+    /// # Errors
+    /// * Returns an error if `END OF HEADER` is missing or if the version line
+    ///   is missing or invalid
+    pub fn read_string_lenient(data: &str) -> Result<Self, Error> {
+        let lines = data.lines().collect::<Vec<_>>();
+        let end = lines
+            .iter()
+            .position(|line| line.contains("END OF HEADER"))
+            .ok_or_else(|| {
+                Error::rule("END OF HEADER not found").at((lines.len() + 1, 1))
+            })?;
+        let mut builder = RinexBuilder::new();
+        for (index, line) in lines[..end].iter().enumerate() {
+            read_header_lenient(line, index + 1, &mut builder);
+        }
+        let version = builder.version.as_deref().ok_or_else(|| {
+            Error::rinex_builder("RINEX VERSION / TYPE header missing")
+                .at((1, 1))
+        })?;
+        let frames = version.starts_with('4');
+        let glonass = version.starts_with('2')
+            && builder.type_.as_deref().is_some_and(|t| t.starts_with('G'));
+        let entry_rule = if glonass {
+            Rule::glonass_entry
+        } else {
+            Rule::record_entry
+        };
+        if builder.program.is_none() {
+            builder.add_warning(ParseWarning {
+                line: end + 1,
+                column: 1,
+                message: "PGM / RUN BY / DATE header missing".to_string(),
+            });
+            builder.set_program(String::new());
+            builder.set_agency(String::new());
+            builder.set_update(String::new());
+        }
+        let mut set = RecordSet::default();
+        for (first_line, text) in
+            split_records(&lines[end + 1..], end + 2, frames)
+        {
+            let result = RinexParser::parse(entry_rule, &text)
+                .map_err(|e| Error::ParseFile(Box::new(e)))
+                .and_then(|mut pairs| {
+                    let entry = next_pair(&mut pairs, "record_entry")?;
+                    let record = next_pair(&mut entry.into_inner(), "record")?;
+                    set.add(record, &mut builder)
+                });
+            if let Err(e) = result {
+                builder.add_warning(skipped(&e, first_line, "record"));
+            }
+        }
+        set.finish(&mut builder);
+        builder.build()
+    }
 }
 
 /// Builder for creating Rinex objects incrementally.
@@ -156,8 +278,16 @@ pub struct RinexBuilder {
     delta_utc: Option<DeltaUtc>,
     /// Number of leap seconds between GPS and UTC time
     leap_seconds: Option<i32>,
+    /// Ionospheric corrections of the RINEX 3 header
+    ionospheric_corrections: Vec<IonosphericCorrection>,
+    /// Time system corrections of the RINEX 3 header
+    time_system_corrections: Vec<TimeSystemCorrection>,
     /// Collection of satellite ephemeris data
     ephemerides: Option<Vec<Ephemeris>>,
+    /// Navigation records other than GPS LNAV ephemerides
+    records: Vec<NavRecord>,
+    /// Problems skipped in lenient mode
+    warnings: Vec<ParseWarning>,
 }
 impl RinexBuilder {
     /// Creates a new empty `RinexBuilder`.
@@ -217,6 +347,20 @@ impl RinexBuilder {
         self.comments.replace(comments);
     }
 
+    /// Appends a comment line of the RINEX file header.
+    ///
+    /// # Arguments
+    /// * `comment` - The text of one comment header line
+    pub fn add_comment(&mut self, comment: &str) {
+        match &mut self.comments {
+            Some(comments) => {
+                comments.push('\n');
+                comments.push_str(comment);
+            }
+            None => self.set_comments(comment.to_string()),
+        }
+    }
+
     /// Sets the ionospheric correction parameters (alpha).
     ///
     /// # Arguments
@@ -251,6 +395,44 @@ impl RinexBuilder {
         self.leap_seconds.replace(leap_seconds);
     }
 
+    /// Adds an ionospheric correction of the RINEX 3 header.
+    ///
+    /// The GPSA and GPSB corrections also set the Klobuchar alpha and beta
+    /// parameters.
+    ///
+    /// # Arguments
+    /// * `correction` - The ionospheric correction
+    pub fn add_ionospheric_correction(
+        &mut self, correction: IonosphericCorrection,
+    ) {
+        match correction.kind.as_str() {
+            "GPSA" => self.set_ion_alpha(correction.values),
+            "GPSB" => self.set_ion_beta(correction.values),
+            _ => {}
+        }
+        self.ionospheric_corrections.push(correction);
+    }
+
+    /// Adds a time system correction of the RINEX 3 header.
+    ///
+    /// The GPUT correction also sets the UTC time correction parameters.
+    ///
+    /// # Arguments
+    /// * `correction` - The time system correction
+    pub fn add_time_system_correction(
+        &mut self, correction: TimeSystemCorrection,
+    ) {
+        if correction.kind == "GPUT" {
+            self.set_delta_utc(DeltaUtc::new(
+                correction.a0,
+                correction.a1,
+                correction.time,
+                correction.week,
+            ));
+        }
+        self.time_system_corrections.push(correction);
+    }
+
     /// Sets the collection of satellite ephemeris data.
     ///
     /// # Arguments
@@ -259,6 +441,22 @@ impl RinexBuilder {
         self.ephemerides.replace(ephemerides);
     }
 
+    /// Sets the navigation records other than GPS LNAV ephemerides.
+    ///
+    /// # Arguments
+    /// * `records` - The navigation records in file order
+    pub fn set_records(&mut self, records: Vec<NavRecord>) {
+        self.records = records;
+    }
+
+    /// Records a problem skipped in lenient mode.
+    ///
+    /// # Arguments
+    /// * `warning` - The skipped problem
+    pub fn add_warning(&mut self, warning: ParseWarning) {
+        self.warnings.push(warning);
+    }
+
     /// Builds a Rinex object from the builder's data.
     ///
     /// # Returns
@@ -266,7 +464,8 @@ impl RinexBuilder {
     /// * `Err(Error)` - If any required field is missing
     ///
     /// # Errors
-    /// * Returns an error if any required field is not set
+    /// * Returns an error if the version, type, program, agency, update date or
+    ///   ephemerides are not set
     pub fn build(&mut self) -> Result<Rinex, Error> {
         fn take<T>(v: &mut Option<T>, msg: &str) -> Result<T, Error> {
             v.take().ok_or_else(|| Error::RinexBuilder(msg.into()))
@@ -277,12 +476,20 @@ impl RinexBuilder {
             program: take(&mut self.program, "program is none")?,
             agency: take(&mut self.agency, "agency is none")?,
             update: take(&mut self.update, "update is none")?,
-            comments: take(&mut self.comments, "comments is none")?,
-            ion_alpha: take(&mut self.ion_alpha, "ion_alpha is none")?,
-            ion_beta: take(&mut self.ion_beta, "ion_beta is none")?,
-            delta_utc: take(&mut self.delta_utc, "delta_utc is none")?,
-            leap_seconds: take(&mut self.leap_seconds, "leap_seconds is none")?,
+            comments: self.comments.take().unwrap_or_default(),
+            ion_alpha: self.ion_alpha.take(),
+            ion_beta: self.ion_beta.take(),
+            delta_utc: self.delta_utc.take(),
+            leap_seconds: self.leap_seconds.take(),
+            ionospheric_corrections: std::mem::take(
+                &mut self.ionospheric_corrections,
+            ),
+            time_system_corrections: std::mem::take(
+                &mut self.time_system_corrections,
+            ),
             ephemerides: take(&mut self.ephemerides, "ephemerides is none")?,
+            records: std::mem::take(&mut self.records),
+            warnings: std::mem::take(&mut self.warnings),
         };
         Ok(rinex)
     }
@@ -316,7 +523,7 @@ fn next_str<'a>(
 /// * `Ok(f64)` - The parsed floating-point value
 /// * `Err(ParseFloatError)` - If the string cannot be parsed as a float
 fn to_float(num: &str) -> Result<f64, ParseFloatError> {
-    num.replace('D', "E").trim().parse()
+    num.replace(['D', 'd'], "E").trim().parse()
 }
 
 /// Converts a string to a 32-bit integer.
@@ -359,64 +566,129 @@ pub fn read_header(
     header_rules: &mut Pairs<Rule>, builder: &mut RinexBuilder,
 ) -> Result<(), Error> {
     for header_rule in header_rules {
-        match header_rule.as_rule() {
-            Rule::header_version => {
-                let mut rules = header_rule.into_inner();
-                let version =
-                    next_str(&mut rules, "header_version")?.trim().to_string();
-                let type_ =
-                    next_str(&mut rules, "header_version")?.trim().to_string();
-                builder.set_version(version);
-                builder.set_type(type_);
-            }
-            Rule::header_program => {
-                let mut rules = header_rule.into_inner();
-                let program =
-                    next_str(&mut rules, "header_program")?.trim().to_string();
-                let agency =
-                    next_str(&mut rules, "header_program")?.trim().to_string();
-                let update =
-                    next_str(&mut rules, "header_program")?.trim().to_string();
-                builder.set_program(program);
-                builder.set_agency(agency);
-                builder.set_update(update);
-            }
-            Rule::header_comment => {
-                let mut rules = header_rule.into_inner();
-                // Comments might span multiple inner parts depending on
-                // grammar, but original code took only the
-                // first. Assuming simple structure.
-                let comments =
-                    next_str(&mut rules, "header_comment")?.trim().to_string();
-                builder.set_comments(comments);
-            }
-            Rule::header_ion_alpha => {
-                let mut rules = header_rule.into_inner();
-                let ion_alpha = read_ion_values(&mut rules)?;
-                builder.set_ion_alpha(ion_alpha);
-            }
-            Rule::header_ion_beta => {
-                let mut rules = header_rule.into_inner();
-                let ion_beta = read_ion_values(&mut rules)?;
-                builder.set_ion_beta(ion_beta);
-            }
-            Rule::header_delta_utc => {
-                let mut rules = header_rule.into_inner();
-                let delta_utc = read_delta_utc(&mut rules)?;
-                builder.set_delta_utc(delta_utc);
-            }
-            Rule::header_leap_secs => {
-                let mut rules = header_rule.into_inner();
-                let leap_seconds_str =
-                    next_str(&mut rules, "header_leap_secs")?;
-                let leap_seconds = to_int(leap_seconds_str)?;
-                builder.set_leap_seconds(leap_seconds);
-            }
-            _ => unreachable!(),
+        let position = header_rule.line_col();
+        read_header_line(header_rule, builder).map_err(|e| e.at(position))?;
+    }
+    Ok(())
+}
+
+/// Parses one header line and populates the builder.
+///
+/// # Arguments
+/// * `rule` - Header line rule from the pest parser
+/// * `builder` - `RinexBuilder` to populate with header data
+///
+/// # Returns
+/// * `Ok(())` - If the header line was successfully parsed
+/// * `Err(Error)` - If a field of the header line is invalid
+fn read_header_line(
+    rule: Pair<Rule>, builder: &mut RinexBuilder,
+) -> Result<(), Error> {
+    match rule.as_rule() {
+        Rule::header_version => {
+            let mut rules = rule.into_inner();
+            let version =
+                next_str(&mut rules, "header_version")?.trim().to_string();
+            let type_ =
+                next_str(&mut rules, "header_version")?.trim().to_string();
+            builder.set_version(version);
+            builder.set_type(type_);
+        }
+        Rule::header_program => {
+            let mut rules = rule.into_inner();
+            let program =
+                next_str(&mut rules, "header_program")?.trim().to_string();
+            let agency =
+                next_str(&mut rules, "header_program")?.trim().to_string();
+            let update =
+                next_str(&mut rules, "header_program")?.trim().to_string();
+            builder.set_program(program);
+            builder.set_agency(agency);
+            builder.set_update(update);
+        }
+        Rule::header_comment => {
+            let mut rules = rule.into_inner();
+            let comment = next_str(&mut rules, "header_comment")?.trim();
+            builder.add_comment(comment);
+        }
+        Rule::header_ion_alpha => {
+            let mut rules = rule.into_inner();
+            let ion_alpha = read_ion_values(&mut rules)?;
+            builder.set_ion_alpha(ion_alpha);
+        }
+        Rule::header_ion_beta => {
+            let mut rules = rule.into_inner();
+            let ion_beta = read_ion_values(&mut rules)?;
+            builder.set_ion_beta(ion_beta);
+        }
+        Rule::header_delta_utc => {
+            let mut rules = rule.into_inner();
+            let delta_utc = read_delta_utc(&mut rules)?;
+            builder.set_delta_utc(delta_utc);
+        }
+        Rule::header_leap_secs => {
+            let mut rules = rule.into_inner();
+            let leap_seconds_str = next_str(&mut rules, "header_leap_secs")?;
+            let leap_seconds = to_int(leap_seconds_str)?;
+            builder.set_leap_seconds(leap_seconds);
+        }
+        Rule::header_ionospheric_corr => {
+            let rules = rule.into_inner();
+            let correction = read_ionospheric_correction(rules)?;
+            builder.add_ionospheric_correction(correction);
         }
+        Rule::header_time_system_corr => {
+            let mut rules = rule.into_inner();
+            let correction = read_time_system_correction(&mut rules)?;
+            builder.add_time_system_correction(correction);
+        }
+        Rule::header_glonass_corr => {
+            let mut rules = rule.into_inner();
+            let correction = read_glonass_correction(&mut rules)?;
+            builder.add_time_system_correction(correction);
+        }
+        Rule::header_other => {}
+        _ => unreachable!(),
     }
     Ok(())
 }
+
+/// Parses one header line in lenient mode.
+///
+/// Unknown labels and malformed lines are recorded as warnings.
+///
+/// # Arguments
+/// * `line` - Header line without line ending
+/// * `number` - Line number, starting at 1
+/// * `builder` - `RinexBuilder` to populate with header data
+fn read_header_lenient(line: &str, number: usize, builder: &mut RinexBuilder) {
+    let text = format!("{line}\n");
+    let result = RinexParser::parse(Rule::header_entry, &text)
+        .map_err(|e| Error::ParseFile(Box::new(e)))
+        .and_then(|mut pairs| {
+            let entry = next_pair(&mut pairs, "header_entry")?;
+            let rule = next_pair(&mut entry.into_inner(), "header_line")?;
+            if rule.as_rule() == Rule::header_other {
+                return Ok(false);
+            }
+            let position = rule.line_col();
+            read_header_line(rule, builder).map_err(|e| e.at(position))?;
+            Ok(true)
+        });
+    match result {
+        Ok(true) => {}
+        Ok(false) => {
+            let label = line.get(HEADER_LABEL_COLUMN..).unwrap_or_default();
+            builder.add_warning(ParseWarning {
+                line: number,
+                column: HEADER_LABEL_COLUMN + 1,
+                message: format!("header label '{}' ignored", label.trim()),
+            });
+        }
+        Err(e) => builder.add_warning(skipped(&e, number, "header line")),
+    }
+}
+
 /// Parses ionospheric correction parameters from RINEX rules.
 ///
 /// This function extracts the four ionospheric correction parameters
@@ -468,7 +740,493 @@ fn read_delta_utc(rule: &mut Pairs<Rule>) -> Result<DeltaUtc, Error> {
     Ok(DeltaUtc::new(a0, a1, time, week))
 }
 
-/// Parses the ephemerides section of a RINEX file and populates the builder.
+/// Parses a RINEX 3 `IONOSPHERIC CORR` header line.
+///
+/// # Arguments
+/// * `rules` - Iterator over the fields of the header line
+///
+/// # Returns
+/// * `Ok(IonosphericCorrection)` - The correction, with a missing fourth
+///   parameter as zero
+/// * `Err(Error)` - If a field cannot be parsed
+fn read_ionospheric_correction(
+    rules: Pairs<Rule>,
+) -> Result<IonosphericCorrection, Error> {
+    let mut correction = IonosphericCorrection {
+        kind: String::new(),
+        values: [0.0; 4],
+        time_mark: None,
+        satellite: None,
+    };
+    let mut count = 0;
+    for rule in rules {
+        match rule.as_rule() {
+            Rule::iono_corr_type => correction.kind = rule.as_str().to_string(),
+            Rule::iono_corr_value => {
+                correction.values[count] = to_float(rule.as_str())?;
+                count += 1;
+            }
+            Rule::iono_corr_time_mark => {
+                correction.time_mark = rule.as_str().chars().next();
+            }
+            Rule::iono_corr_sv => {
+                correction.satellite = Some(to_usize(rule.as_str())?);
+            }
+            _ => unreachable!(),
+        }
+    }
+    Ok(correction)
+}
+
+/// Parses a RINEX 3 `TIME SYSTEM CORR` header line.
+///
+/// # Arguments
+/// * `rules` - Iterator over the fields of the header line
+///
+/// # Returns
+/// * `Ok(TimeSystemCorrection)` - The correction
+/// * `Err(Error)` - If a field cannot be parsed
+fn read_time_system_correction(
+    rules: &mut Pairs<Rule>,
+) -> Result<TimeSystemCorrection, Error> {
+    let context = "header_time_system_corr";
+    Ok(TimeSystemCorrection {
+        kind: next_str(rules, context)?.to_string(),
+        a0: to_float(next_str(rules, context)?)?,
+        a1: to_float(next_str(rules, context)?)?,
+        time: to_int(next_str(rules, context)?)?,
+        week: to_int(next_str(rules, context)?)?,
+        source: next_str(rules, context)?.trim().to_string(),
+    })
+}
+
+/// Parses a RINEX 2 `CORR TO SYSTEM TIME` header line.
+///
+/// The correction of the GLONASS system time to UTC(SU) is returned as a
+/// RINEX 3 GLUT correction, referenced to the GPS week and seconds of the
+/// given date.
+///
+/// # Arguments
+/// * `rules` - Iterator over the fields of the header line
+///
+/// # Returns
+/// * `Ok(TimeSystemCorrection)` - The GLUT correction (-τc)
+/// * `Err(Error)` - If a field cannot be parsed
+fn read_glonass_correction(
+    rules: &mut Pairs<Rule>,
+) -> Result<TimeSystemCorrection, Error> {
+    let context = "header_glonass_corr";
+    let year = to_int(next_str(rules, context)?)?;
+    let month = to_int(next_str(rules, context)?)?;
+    let day = to_int(next_str(rules, context)?)?;
+    let a0 = to_float(next_str(rules, context)?)?;
+    let date = DateTime::new(year as i16, month as i8, day as i8, 0, 0, 0, 0)?;
+    let (week, seconds) = gps_week_seconds(&date);
+    Ok(TimeSystemCorrection {
+        kind: "GLUT".to_string(),
+        a0,
+        a1: 0.0,
+        time: seconds.round() as i32,
+        week,
+        source: String::new(),
+    })
+}
+
+/// Parses the records of a navigation file and populates the builder.
+///
+/// GPS LNAV ephemerides are converted into `Ephemeris`, all other records
+/// are kept as `NavRecord`. The first GPS Klobuchar ION frame and the first
+/// GPUT STO frame of a RINEX 4 file provide the ionospheric and UTC
+/// parameters when the header does not.
+///
+/// # Arguments
+/// * `record_rules` - Iterator over RINEX 2 GPS or GLONASS ephemerides, RINEX 3
+///   records or RINEX 4 frames
+/// * `builder` - `RinexBuilder` to populate with the records
+///
+/// # Returns
+/// * `Ok(())` - If the records were successfully parsed
+/// * `Err(Error)` - If there was an error parsing a record
+pub fn read_records(
+    record_rules: &mut Pairs<Rule>, builder: &mut RinexBuilder,
+) -> Result<(), Error> {
+    let mut set = RecordSet::default();
+    for record_rule in record_rules {
+        set.add(record_rule, builder)?;
+    }
+    set.finish(builder);
+    Ok(())
+}
+
+/// Records read from the body of a navigation file.
+#[derive(Default)]
+struct RecordSet {
+    /// GPS LNAV ephemerides
+    ephemerides: Vec<Ephemeris>,
+    /// Other navigation records
+    records: Vec<NavRecord>,
+}
+
+impl RecordSet {
+    /// Parses a record and adds it to the set.
+    ///
+    /// # Arguments
+    /// * `rule` - An `ephemeris`, `glonass_ephemeris`, `nav_record` or
+    ///   `nav_frame` rule
+    /// * `builder` - `RinexBuilder` receiving the GPS parameters of RINEX 4
+    ///   frames
+    ///
+    /// # Returns
+    /// * `Ok(())` - If the record was added
+    /// * `Err(Error)` - If the record is invalid, with its position
+    fn add(
+        &mut self, rule: Pair<Rule>, builder: &mut RinexBuilder,
+    ) -> Result<(), Error> {
+        let position = rule.line_col();
+        let record = match rule.as_rule() {
+            Rule::ephemeris => {
+                let ephemeris = read_v2_ephemeris(rule);
+                self.ephemerides
+                    .push(ephemeris.map_err(|e| e.at(position))?);
+                return Ok(());
+            }
+            Rule::glonass_ephemeris => read_v2_glonass_record(rule),
+            Rule::nav_record => read_nav_record(rule, None),
+            Rule::nav_frame => read_nav_frame(rule),
+            rule => Err(Error::Rule(format!(
+                "Unexpected rule in navigation records: {rule:?}"
+            ))),
+        }
+        .map_err(|e| e.at(position))?;
+        if record.is_gps_lnav() {
+            self.ephemerides
+                .push(record.to_ephemeris().map_err(|e| e.at(position))?);
+            return Ok(());
+        }
+        if record.satellite.system == SatelliteSystem::Gps {
+            apply_gps_frame(&record, builder);
+        }
+        self.records.push(record);
+        Ok(())
+    }
+
+    /// Moves the records into the builder.
+    ///
+    /// # Arguments
+    /// * `builder` - `RinexBuilder` to populate with the records
+    fn finish(self, builder: &mut RinexBuilder) {
+        builder.set_ephemerides(self.ephemerides);
+        builder.set_records(self.records);
+    }
+}
+
+/// Splits the body of a navigation file into records for lenient reading.
+///
+/// RINEX 4 records start with a `>` frame header line. RINEX 2 and 3
+/// records start with a line not indented by three spaces. Blank lines are
+/// dropped.
+///
+/// # Arguments
+/// * `lines` - Lines after `END OF HEADER`
+/// * `first_line` - Line number of the first line
+/// * `frames` - Whether the file is a RINEX 4 file
+///
+/// # Returns
+/// The line number of the first line and the text of each record
+fn split_records(
+    lines: &[&str], first_line: usize, frames: bool,
+) -> Vec<(usize, String)> {
+    let mut records: Vec<(usize, String)> = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let starts_record = if frames {
+            line.starts_with('>')
+        } else {
+            !line.starts_with("   ")
+        };
+        match records.last_mut() {
+            Some((_, text)) if !starts_record => text.push_str(line),
+            _ => records.push((first_line + index, (*line).to_string())),
+        }
+        if let Some((_, text)) = records.last_mut() {
+            text.push('\n');
+        }
+    }
+    records
+}
+
+/// Describes a header line or record skipped in lenient mode.
+///
+/// # Arguments
+/// * `error` - Why the text was skipped, with a position relative to it
+/// * `first_line` - Line number of the first line of the skipped text
+/// * `what` - Kind of the skipped text
+///
+/// # Returns
+/// The warning with the position in the file
+fn skipped(error: &Error, first_line: usize, what: &str) -> ParseWarning {
+    let (line, column) = error.position().unwrap_or((1, 1));
+    let message = match error {
+        Error::ParseFile(e) => e.variant.message().into_owned(),
+        Error::Position { source, .. } => source.to_string(),
+        _ => error.to_string(),
+    };
+    ParseWarning {
+        line: first_line + line - 1,
+        column,
+        message: format!("{what} skipped: {message}"),
+    }
+}
+
+/// Takes the GPS ionospheric and UTC parameters from RINEX 4 frames.
+///
+/// # Arguments
+/// * `record` - A GPS navigation record
+/// * `builder` - `RinexBuilder` whose missing parameters are set
+fn apply_gps_frame(record: &NavRecord, builder: &mut RinexBuilder) {
+    let lnav = record.message.as_deref() == Some("LNAV");
+    match record.kind {
+        NavRecordKind::Ionosphere if lnav && builder.ion_alpha.is_none() => {
+            let v = |i| record.values.get(i).copied().unwrap_or_default();
+            builder.set_ion_alpha([v(0), v(1), v(2), v(3)]);
+            builder.set_ion_beta([v(4), v(5), v(6), v(7)]);
+        }
+        NavRecordKind::SystemTimeOffset
+            if builder.delta_utc.is_none()
+                && record
+                    .label
+                    .as_deref()
+                    .is_some_and(|label| label.starts_with("GPUT")) =>
+        {
+            let (week, seconds) = gps_week_seconds(&record.epoch);
+            let v = |i| record.values.get(i).copied().unwrap_or_default();
+            builder.set_delta_utc(DeltaUtc::new(
+                v(1),
+                v(2),
+                seconds.round() as i32,
+                week,
+            ));
+        }
+        _ => {}
+    }
+}
+
+/// Parses a RINEX 4 frame.
+///
+/// # Arguments
+/// * `frame` - The `nav_frame` rule
+///
+/// # Returns
+/// * `Ok(NavRecord)` - The record carried by the frame
+/// * `Err(Error)` - If the frame cannot be parsed
+fn read_nav_frame(frame: Pair<Rule>) -> Result<NavRecord, Error> {
+    let mut rules = frame.into_inner();
+    let kind = match next_str(&mut rules, "nav_frame type")? {
+        "EPH" => NavRecordKind::Ephemeris,
+        "STO" => NavRecordKind::SystemTimeOffset,
+        "EOP" => NavRecordKind::EarthOrientation,
+        _ => NavRecordKind::Ionosphere,
+    };
+    let satellite: SatelliteId =
+        next_str(&mut rules, "nav_frame sv")?.parse()?;
+    let message = next_str(&mut rules, "nav_frame message")?.to_string();
+    let body = next_pair(&mut rules, "nav_frame body")?;
+    if body.as_rule() == Rule::nav_record {
+        let mut record = read_nav_record(body, Some(message))?;
+        record.kind = kind;
+        return Ok(record);
+    }
+    let mut rules = body.into_inner();
+    let epoch = read_nav_epoch(next_pair(&mut rules, "frame_data epoch")?)?;
+    let first_line = next_pair(&mut rules, "frame_data values")?;
+    let (label, mut values) = if kind == NavRecordKind::SystemTimeOffset {
+        (Some(first_line.as_str().trim().to_string()), Vec::new())
+    } else {
+        (None, split_nav_fields(&first_line, EPOCH_LINE_FIELDS)?)
+    };
+    for orbit in rules {
+        let fields = next_pair(&mut orbit.into_inner(), "nav_orbit")?;
+        values.extend(split_nav_fields(&fields, ORBIT_LINE_FIELDS)?);
+    }
+    Ok(NavRecord {
+        kind,
+        satellite,
+        message: Some(message),
+        epoch,
+        label,
+        values,
+    })
+}
+
+/// Parses a RINEX 3 record (or the record of a RINEX 4 EPH frame).
+///
+/// # Arguments
+/// * `record` - The `nav_record` rule
+/// * `message` - RINEX 4 message type, if any
+///
+/// # Returns
+/// * `Ok(NavRecord)` - The ephemeris record
+/// * `Err(Error)` - If the record cannot be parsed
+fn read_nav_record(
+    record: Pair<Rule>, message: Option<String>,
+) -> Result<NavRecord, Error> {
+    let mut rules = record.into_inner();
+    let satellite: SatelliteId =
+        next_str(&mut rules, "nav_record sv")?.parse()?;
+    let epoch = read_nav_epoch(next_pair(&mut rules, "nav_record epoch")?)?;
+    let clock = next_pair(&mut rules, "nav_record clock")?;
+    let mut values = split_nav_fields(&clock, EPOCH_LINE_FIELDS)?;
+    for orbit in rules {
+        let fields = next_pair(&mut orbit.into_inner(), "nav_orbit")?;
+        values.extend(split_nav_fields(&fields, ORBIT_LINE_FIELDS)?);
+    }
+    Ok(NavRecord {
+        kind: NavRecordKind::Ephemeris,
+        satellite,
+        message,
+        epoch,
+        label: None,
+        values,
+    })
+}
+
+/// Parses a RINEX 2 GLONASS ephemeris record.
+///
+/// The record keeps the RINEX 2 fields in the RINEX 3 layout: -τn, +γn
+/// and the message frame time on the epoch line, followed by the position,
+/// velocity and acceleration of each axis and the health, frequency number
+/// and age of operation.
+///
+/// # Arguments
+/// * `record` - The `glonass_ephemeris` rule
+///
+/// # Returns
+/// * `Ok(NavRecord)` - The ephemeris record of the slot
+/// * `Err(Error)` - If a field is invalid
+fn read_v2_glonass_record(record: Pair<Rule>) -> Result<NavRecord, Error> {
+    let mut rules = record.into_inner();
+    let prn = to_usize(next_str(&mut rules, "glonass_ephemeris slot")?)?;
+    let mut epoch_rules =
+        next_pair(&mut rules, "glonass_ephemeris epoch")?.into_inner();
+    let mut field = |context| -> Result<i32, Error> {
+        Ok(to_int(next_str(&mut epoch_rules, context)?)?)
+    };
+    let year = field("epoch year")?;
+    let month = field("epoch month")?;
+    let day = field("epoch day")?;
+    let hour = field("epoch hour")?;
+    let minute = field("epoch minute")?;
+    let seconds = to_float(next_str(&mut epoch_rules, "epoch seconds")?)?;
+    // Two-digit years 80-99 are 1980-1999
+    let year = if year < 80 { year + 2000 } else { year + 1900 };
+    let epoch = DateTime::new(
+        year as i16,
+        month as i8,
+        day as i8,
+        hour as i8,
+        minute as i8,
+        0,
+        0,
+    )?
+    .checked_add(std::time::Duration::from_secs_f64(seconds))?;
+    let mut values =
+        Vec::with_capacity(EPOCH_LINE_FIELDS + 3 * ORBIT_LINE_FIELDS);
+    for group in rules {
+        for value in group.into_inner() {
+            values.push(to_float(value.as_str())?);
+        }
+    }
+    Ok(NavRecord {
+        kind: NavRecordKind::Ephemeris,
+        satellite: SatelliteId {
+            system: SatelliteSystem::Glonass,
+            prn,
+        },
+        message: None,
+        epoch,
+        label: None,
+        values,
+    })
+}
+
+/// Parses the epoch of a RINEX 3 or RINEX 4 record.
+///
+/// # Arguments
+/// * `epoch` - The `nav_epoch` rule
+///
+/// # Returns
+/// * `Ok(DateTime)` - The epoch in the time system of the record
+/// * `Err(Error)` - If a field is invalid
+fn read_nav_epoch(epoch: Pair<Rule>) -> Result<DateTime, Error> {
+    let mut rules = epoch.into_inner();
+    let mut field = |context| -> Result<i32, Error> {
+        Ok(to_int(next_str(&mut rules, context)?)?)
+    };
+    let year = field("epoch year")?;
+    let month = field("epoch month")?;
+    let day = field("epoch day")?;
+    let hour = field("epoch hour")?;
+    let minute = field("epoch minute")?;
+    let seconds = to_float(next_str(&mut rules, "epoch seconds")?)?;
+    let datetime = DateTime::new(
+        year as i16,
+        month as i8,
+        day as i8,
+        hour as i8,
+        minute as i8,
+        0,
+        0,
+    )?;
+    Ok(datetime.checked_add(std::time::Duration::from_secs_f64(seconds))?)
+}
+
+/// Splits the numeric fields of a RINEX 3 or RINEX 4 record line.
+///
+/// Fields are 19 characters wide (D19.12). Blank fields, including blank
+/// trailing fields omitted by the writer, read as zero so that the fields of
+/// the following lines keep their positions.
+///
+/// # Arguments
+/// * `fields` - The `nav_values` rule of the line after the epoch or the four
+///   leading spaces
+/// * `count` - Number of fields of the line
+///
+/// # Returns
+/// * `Ok(Vec<f64>)` - The field values (at least `count`)
+/// * `Err(Error)` - If a field is not a number, with its position
+fn split_nav_fields(
+    fields: &Pair<Rule>, count: usize,
+) -> Result<Vec<f64>, Error> {
+    let (line, column) = fields.line_col();
+    let mut values = fields
+        .as_str()
+        .trim_end()
+        .as_bytes()
+        .chunks(NAV_FIELD_WIDTH)
+        .enumerate()
+        .map(|(index, chunk)| {
+            let position = (line, column + index * NAV_FIELD_WIDTH);
+            let field = std::str::from_utf8(chunk)
+                .map_err(|e| Error::rule(format!("Invalid field: {e}")))
+                .map_err(|e| e.at(position))?
+                .trim();
+            if field.is_empty() {
+                Ok(0.0)
+            } else {
+                to_float(field).map_err(|e| Error::from(e).at(position))
+            }
+        })
+        .collect::<Result<Vec<f64>, Error>>()?;
+    if values.len() < count {
+        values.resize(count, 0.0);
+    }
+    Ok(values)
+}
+
+/// Parses the ephemerides section of a RINEX 2 file and populates the
+/// builder.
 ///
 /// This function processes the ephemeris rules from the pest parser and sets
 /// the ephemerides field in the `RinexBuilder`.
@@ -483,28 +1241,24 @@ fn read_delta_utc(rule: &mut Pairs<Rule>) -> Result<DeltaUtc, Error> {
 pub fn read_ephemerides(
     eph_rules: &mut Pairs<Rule>, builder: &mut RinexBuilder,
 ) -> Result<(), Error> {
-    let mut ephemerides: Vec<Ephemeris> = Vec::new();
-    for eph_rule in eph_rules {
-        match eph_rule.as_rule() {
-            Rule::ephemeris => {
-                let mut eph_builder = EphemerisBuilder::new();
-                let mut rules = eph_rule.into_inner();
-                read_ephemeris(&mut rules, &mut eph_builder)?;
-                let ephemeris = eph_builder.build()?;
-                ephemerides.push(ephemeris);
-            }
-            _ => {
-                return Err(Error::Rule(format!(
-                    "Unexpected rule in ephemerides section: {:?}",
-                    eph_rule.as_rule()
-                )));
-            }
-        }
-    }
-    builder.set_ephemerides(ephemerides);
-    Ok(())
+    read_records(eph_rules, builder)
 }
-#[allow(clippy::similar_names)]
+
+/// Parses a RINEX 2 ephemeris record.
+///
+/// # Arguments
+/// * `eph_rule` - The `ephemeris` rule
+///
+/// # Returns
+/// * `Ok(Ephemeris)` - The ephemeris
+/// * `Err(Error)` - If a field is invalid
+fn read_v2_ephemeris(eph_rule: Pair<Rule>) -> Result<Ephemeris, Error> {
+    let mut eph_builder = EphemerisBuilder::new();
+    let mut rules = eph_rule.into_inner();
+    read_ephemeris(&mut rules, &mut eph_builder)?;
+    eph_builder.build()
+}
+
 /// Parses a single satellite ephemeris from RINEX rules.
 ///
 /// This function processes the rules for a single satellite ephemeris entry
@@ -524,80 +1278,92 @@ fn read_ephemeris(
     // Expect a specific sequence of rules based on the grammar
     // PRN + Epoch + SV Clock + 7 Orbit lines
     for rule in rules {
-        match rule.as_rule() {
-            Rule::prn => {
-                let prn = to_usize(rule.as_str())?;
-                builder.set_prn(prn);
-            }
-            Rule::epoch => {
-                let mut epoch_rules = rule.into_inner();
-                let year = to_int(next_str(&mut epoch_rules, "epoch year")?)?;
-                let month = to_int(next_str(&mut epoch_rules, "epoch month")?)?;
-                let day = to_int(next_str(&mut epoch_rules, "epoch day")?)?;
-                let hour = to_int(next_str(&mut epoch_rules, "epoch hour")?)?;
-                let minutes =
-                    to_int(next_str(&mut epoch_rules, "epoch minutes")?)?;
-                let seconds =
-                    to_float(next_str(&mut epoch_rules, "epoch seconds")?)?;
-                let datetime = format!(
-                    "20{year}-{month:02}-{day:02}T{hour:02}:{minutes:02}:00Z"
-                );
-                let time_of_clock: Timestamp =
-                    datetime.parse::<Timestamp>()?.checked_add(
-                        std::time::Duration::from_secs_f64(seconds),
-                    )?;
-                builder.set_time_of_clock(time_of_clock);
-            }
-            Rule::sv_clk => {
-                let mut sv_clk_rules = rule.into_inner();
-                let bias =
-                    to_float(next_str(&mut sv_clk_rules, "sv_clk bias")?)?;
-                let drift =
-                    to_float(next_str(&mut sv_clk_rules, "sv_clk drift")?)?;
-                let drift_rate = to_float(next_str(
-                    &mut sv_clk_rules,
-                    "sv_clk drift_rate",
-                )?)?;
-                let sv_clock = SvClock::new(bias, drift, drift_rate);
-                builder.set_sv_clock(sv_clock);
-            }
-            Rule::orbit_1 => {
-                let mut rules = rule.into_inner();
-                let orbit: Orbit1 = to_orbit_values(&mut rules, "orbit_1")?;
-                builder.set_orbit1(orbit);
-            }
-            Rule::orbit_2 => {
-                let mut rules = rule.into_inner();
-                let orbit: Orbit2 = to_orbit_values(&mut rules, "orbit_2")?;
-                builder.set_orbit2(orbit);
-            }
-            Rule::orbit_3 => {
-                let mut rules = rule.into_inner();
-                let orbit: Orbit3 = to_orbit_values(&mut rules, "orbit_3")?;
-                builder.set_orbit3(orbit);
-            }
-            Rule::orbit_4 => {
-                let mut rules = rule.into_inner();
-                let orbit: Orbit4 = to_orbit_values(&mut rules, "orbit_4")?;
-                builder.set_orbit4(orbit);
-            }
-            Rule::orbit_5 => {
-                let mut rules = rule.into_inner();
-                let orbit: Orbit5 = to_orbit_values(&mut rules, "orbit_5")?;
-                builder.set_orbit5(orbit);
-            }
-            Rule::orbit_6 => {
-                let mut rules = rule.into_inner();
-                let orbit: Orbit6 = to_orbit_values(&mut rules, "orbit_6")?;
-                builder.set_orbit6(orbit);
-            }
-            Rule::orbit_7 => {
-                let mut rules = rule.into_inner();
-                let orbit: Orbit7 = to_orbit_values(&mut rules, "orbit_7")?;
-                builder.set_orbit7(orbit);
-            }
-            _ => unreachable!(),
+        let position = rule.line_col();
+        read_ephemeris_field(rule, builder).map_err(|e| e.at(position))?;
+    }
+    Ok(())
+}
+
+#[allow(clippy::similar_names)]
+/// Parses one field group of a RINEX 2 ephemeris.
+///
+/// # Arguments
+/// * `rule` - The PRN, epoch, SV clock or orbit rule
+/// * `builder` - `EphemerisBuilder` to populate with ephemeris data
+///
+/// # Returns
+/// * `Ok(())` - If the fields were successfully parsed
+/// * `Err(Error)` - If a field is invalid
+fn read_ephemeris_field(
+    rule: Pair<Rule>, builder: &mut EphemerisBuilder,
+) -> Result<(), Error> {
+    match rule.as_rule() {
+        Rule::prn => {
+            let prn = to_usize(rule.as_str())?;
+            builder.set_prn(prn);
+        }
+        Rule::epoch => {
+            let mut epoch_rules = rule.into_inner();
+            let year = to_int(next_str(&mut epoch_rules, "epoch year")?)?;
+            let month = to_int(next_str(&mut epoch_rules, "epoch month")?)?;
+            let day = to_int(next_str(&mut epoch_rules, "epoch day")?)?;
+            let hour = to_int(next_str(&mut epoch_rules, "epoch hour")?)?;
+            let minutes = to_int(next_str(&mut epoch_rules, "epoch minutes")?)?;
+            let seconds =
+                to_float(next_str(&mut epoch_rules, "epoch seconds")?)?;
+            let datetime = format!(
+                "20{year}-{month:02}-{day:02}T{hour:02}:{minutes:02}:00Z"
+            );
+            let time_of_clock: Timestamp = datetime
+                .parse::<Timestamp>()?
+                .checked_add(std::time::Duration::from_secs_f64(seconds))?;
+            builder.set_time_of_clock(time_of_clock);
+        }
+        Rule::sv_clk => {
+            let mut sv_clk_rules = rule.into_inner();
+            let bias = to_float(next_str(&mut sv_clk_rules, "sv_clk bias")?)?;
+            let drift = to_float(next_str(&mut sv_clk_rules, "sv_clk drift")?)?;
+            let drift_rate =
+                to_float(next_str(&mut sv_clk_rules, "sv_clk drift_rate")?)?;
+            let sv_clock = SvClock::new(bias, drift, drift_rate);
+            builder.set_sv_clock(sv_clock);
+        }
+        Rule::orbit_1 => {
+            let mut rules = rule.into_inner();
+            let orbit: Orbit1 = to_orbit_values(&mut rules, "orbit_1")?;
+            builder.set_orbit1(orbit);
+        }
+        Rule::orbit_2 => {
+            let mut rules = rule.into_inner();
+            let orbit: Orbit2 = to_orbit_values(&mut rules, "orbit_2")?;
+            builder.set_orbit2(orbit);
+        }
+        Rule::orbit_3 => {
+            let mut rules = rule.into_inner();
+            let orbit: Orbit3 = to_orbit_values(&mut rules, "orbit_3")?;
+            builder.set_orbit3(orbit);
+        }
+        Rule::orbit_4 => {
+            let mut rules = rule.into_inner();
+            let orbit: Orbit4 = to_orbit_values(&mut rules, "orbit_4")?;
+            builder.set_orbit4(orbit);
+        }
+        Rule::orbit_5 => {
+            let mut rules = rule.into_inner();
+            let orbit: Orbit5 = to_orbit_values(&mut rules, "orbit_5")?;
+            builder.set_orbit5(orbit);
+        }
+        Rule::orbit_6 => {
+            let mut rules = rule.into_inner();
+            let orbit: Orbit6 = to_orbit_values(&mut rules, "orbit_6")?;
+            builder.set_orbit6(orbit);
+        }
+        Rule::orbit_7 => {
+            let mut rules = rule.into_inner();
+            let orbit: Orbit7 = to_orbit_values(&mut rules, "orbit_7")?;
+            builder.set_orbit7(orbit);
         }
+        _ => unreachable!(),
     }
     Ok(())
 }
@@ -0,0 +1,273 @@
+//! SP3 precise orbit files.
+//!
+//! SP3-c and SP3-d files list satellite positions and clock offsets at a fixed
+//! epoch interval, typically 5 or 15 minutes, in an Earth-fixed frame. Only
+//! the position records are read; velocity and correlation records are
+//! skipped. Satellite IDs without a system letter, as written by SP3-a, are
+//! GPS satellites.
+
+use std::{fs, ops::Range, path::Path, str::FromStr};
+
+use jiff::civil::DateTime;
+
+use crate::{
+    error::Error,
+    navigation::{SatelliteId, SatelliteSystem},
+};
+
+/// Clock values at or above this magnitude mark a missing clock (999999.999999
+/// microseconds).
+const BAD_CLOCK: f64 = 999_999.0;
+
+/// Position and clock of a satellite at an SP3 epoch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sp3Position {
+    /// Satellite
+    pub satellite: SatelliteId,
+    /// Position (X, Y, Z) in kilometers
+    pub position: [f64; 3],
+    /// Clock offset in microseconds, if known
+    pub clock: Option<f64>,
+}
+
+/// Positions of all satellites at one epoch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sp3Epoch {
+    /// Epoch in the time system of the file
+    pub time: DateTime,
+    /// Satellites with a known position at the epoch
+    pub positions: Vec<Sp3Position>,
+}
+
+/// Contents of an SP3 precise orbit file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sp3 {
+    /// Format version letter (`a` to `d`)
+    pub version: char,
+    /// Coordinate system, e.g. `IGS20`
+    pub coordinate_system: String,
+    /// Orbit type, e.g. `FIT` or `HLM`
+    pub orbit_type: String,
+    /// Agency generating the orbit
+    pub agency: String,
+    /// Time system of the epochs (`GPS` if the file does not state one)
+    pub time_system: String,
+    /// Epoch interval in seconds
+    pub interval: f64,
+    /// Satellites listed in the header
+    pub satellites: Vec<SatelliteId>,
+    /// Epochs in file order
+    pub epochs: Vec<Sp3Epoch>,
+}
+
+impl Sp3 {
+    /// Reads an SP3 file from disk.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the SP3 file
+    ///
+    /// # Returns
+    /// * `Ok(Sp3)` - Successfully parsed orbit data
+    /// * `Err(Error)` - If the file cannot be read or parsed
+    ///
+    /// # Errors
+    /// * Returns an error if the file cannot be read or if a line is invalid,
+    ///   with its line and column
+    pub fn read_file(path: &dyn AsRef<Path>) -> Result<Self, Error> {
+        let data = fs::read_to_string(path)?;
+        Self::read_string(data.as_str())
+    }
+
+    /// Parses an SP3 file from a string.
+    ///
+    /// # Arguments
+    /// * `data` - String containing SP3 data
+    ///
+    /// # Returns
+    /// * `Ok(Sp3)` - Successfully parsed orbit data
+    /// * `Err(Error)` - If a line is invalid
+    ///
+    /// # Errors
+    /// * Returns an error with the line and column of the first invalid field
+    pub fn read_string(data: &str) -> Result<Self, Error> {
+        let mut lines = data.lines().enumerate().map(|(i, line)| (i + 1, line));
+        let (_, first) = lines
+            .next()
+            .ok_or_else(|| Error::product("Empty SP3 file"))?;
+        let version = match first.as_bytes() {
+            [b'#', version @ b'a'..=b'd', ..] => char::from(*version),
+            _ => {
+                return Err(Error::product("Not an SP3 file").at((1, 1)));
+            }
+        };
+        let mut sp3 = Sp3 {
+            version,
+            coordinate_system: column(first, 46..51).trim().to_string(),
+            orbit_type: column(first, 52..55).trim().to_string(),
+            agency: column(first, 56..60).trim().to_string(),
+            time_system: "GPS".to_string(),
+            interval: 0.0,
+            satellites: Vec::new(),
+            epochs: Vec::new(),
+        };
+        let mut satellite_count = None;
+        let mut time_system_read = false;
+        for (number, line) in lines {
+            if line.starts_with("EOF") {
+                break;
+            } else if line.starts_with("##") {
+                sp3.interval = float(line, 24..38, number)?;
+            } else if line.starts_with("++")
+                || line.starts_with("%f")
+                || line.starts_with("%i")
+                || line.starts_with("/*")
+                || line.starts_with('V')
+                || line.starts_with("EP")
+                || line.starts_with("EV")
+                || line.trim().is_empty()
+            {
+                // Accuracy, parameter, comment, velocity and correlation
+                // lines are not needed
+            } else if line.starts_with("%c") {
+                let time_system = column(line, 9..12).trim();
+                if !time_system_read && !time_system.is_empty() {
+                    if !time_system.starts_with("cc") {
+                        time_system.clone_into(&mut sp3.time_system);
+                    }
+                    time_system_read = true;
+                }
+            } else if line.starts_with('+') {
+                let count = if let Some(count) = satellite_count {
+                    count
+                } else {
+                    let count = column(line, 1..6)
+                        .trim()
+                        .parse::<usize>()
+                        .map_err(|e| Error::from(e).at((number, 2)))?;
+                    *satellite_count.insert(count)
+                };
+                for start in (9..60).step_by(3) {
+                    if sp3.satellites.len() < count {
+                        sp3.satellites.push(satellite(line, start, number)?);
+                    }
+                }
+            } else if let Some(epoch) = line.strip_prefix('*') {
+                sp3.epochs.push(Sp3Epoch {
+                    time: epoch_time(epoch).map_err(|e| e.at((number, 2)))?,
+                    positions: Vec::new(),
+                });
+            } else if line.starts_with('P') {
+                let epoch = sp3.epochs.last_mut().ok_or_else(|| {
+                    Error::product("Position record before first epoch")
+                        .at((number, 1))
+                })?;
+                let position = [
+                    float(line, 4..18, number)?,
+                    float(line, 18..32, number)?,
+                    float(line, 32..46, number)?,
+                ];
+                // Missing positions are written as zeros
+                if position.iter().all(|&x| x == 0.0) {
+                    continue;
+                }
+                let clock = if column(line, 46..60).trim().is_empty() {
+                    None
+                } else {
+                    Some(float(line, 46..60, number)?)
+                        .filter(|clock| clock.abs() < BAD_CLOCK)
+                };
+                epoch.positions.push(Sp3Position {
+                    satellite: satellite(line, 1, number)?,
+                    position,
+                    clock,
+                });
+            } else {
+                return Err(Error::product(format!(
+                    "Unexpected line {:?}",
+                    column(line, 0..3)
+                ))
+                .at((number, 1)));
+            }
+        }
+        Ok(sp3)
+    }
+}
+
+/// Returns the characters of a line in a column range, cut to the line.
+///
+/// # Arguments
+/// * `line` - Line of the file
+/// * `range` - Zero-based column range
+fn column(line: &str, range: Range<usize>) -> &str {
+    let end = range.end.min(line.len());
+    line.get(range.start.min(end)..end).unwrap_or("")
+}
+
+/// Parses a floating point field.
+///
+/// # Arguments
+/// * `line` - Line of the file
+/// * `range` - Zero-based column range of the field
+/// * `number` - Line number, starting at 1
+///
+/// # Errors
+/// * Returns an error with the position of the field if it is not a number
+fn float(line: &str, range: Range<usize>, number: usize) -> Result<f64, Error> {
+    let start = range.start;
+    column(line, range)
+        .trim()
+        .parse()
+        .map_err(|e| Error::from(e).at((number, start + 1)))
+}
+
+/// Parses a three-character satellite ID such as `G05`, `G 5` or ` 5`.
+///
+/// # Arguments
+/// * `line` - Line of the file
+/// * `start` - Zero-based column of the ID
+/// * `number` - Line number, starting at 1
+///
+/// # Errors
+/// * Returns an error with the position of the ID if it is invalid
+fn satellite(
+    line: &str, start: usize, number: usize,
+) -> Result<SatelliteId, Error> {
+    let id = column(line, start..start + 3);
+    let parsed = match id.strip_prefix(' ') {
+        Some(prn) => {
+            prn.trim()
+                .parse()
+                .map_err(Error::from)
+                .map(|prn| SatelliteId {
+                    system: SatelliteSystem::Gps,
+                    prn,
+                })
+        }
+        None => SatelliteId::from_str(id),
+    };
+    parsed.map_err(|e| e.at((number, start + 1)))
+}
+
+/// Parses the date and time of an epoch line after its leading `*`.
+///
+/// # Arguments
+/// * `epoch` - Year, month, day, hour, minute and seconds separated by blanks
+///
+/// # Errors
+/// * Returns an error if a field is missing or invalid
+pub(crate) fn epoch_time(epoch: &str) -> Result<DateTime, Error> {
+    let mut fields = epoch.split_whitespace();
+    let mut field = |name: &str| {
+        fields
+            .next()
+            .ok_or_else(|| Error::product(format!("Missing epoch {name}")))
+    };
+    let year = field("year")?.parse()?;
+    let month = field("month")?.parse()?;
+    let day = field("day")?.parse()?;
+    let hour = field("hour")?.parse()?;
+    let minute = field("minute")?.parse()?;
+    let seconds: f64 = field("seconds")?.parse()?;
+    let datetime = DateTime::new(year, month, day, hour, minute, 0, 0)?;
+    Ok(datetime.checked_add(std::time::Duration::from_secs_f64(seconds))?)
+}
@@ -12,7 +12,7 @@
 ///
 /// These parameters are typically extracted from the navigation message
 /// broadcast by GPS satellites.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct DeltaUtc {
     /// Constant term of the UTC offset polynomial (seconds)
     pub a0: f64,
@@ -42,3 +42,20 @@ impl DeltaUtc {
         Self { a0, a1, time, week }
     }
 }
+
+/// Number of seconds in a GPS week.
+const SECONDS_IN_WEEK: f64 = 604_800.0;
+
+/// Converts a calendar epoch in GPS time into GPS week and seconds of week.
+///
+/// # Arguments
+/// * `epoch` - Calendar date and time in the GPS time scale
+///
+/// # Returns
+/// A tuple of the full GPS week number and the seconds of week
+pub fn gps_week_seconds(epoch: &jiff::civil::DateTime) -> (i32, f64) {
+    let origin = jiff::civil::date(1980, 1, 6).at(0, 0, 0, 0);
+    let seconds = epoch.duration_since(origin).as_secs_f64();
+    let week = (seconds / SECONDS_IN_WEEK).floor();
+    (week as i32, seconds - week * SECONDS_IN_WEEK)
+}
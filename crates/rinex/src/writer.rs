@@ -0,0 +1,476 @@
+//! RINEX navigation file writer.
+//!
+//! This module serializes a `Rinex` into a RINEX 2.11 GPS navigation file or
+//! a RINEX 3.04 navigation file, so that ephemerides edited in memory can be
+//! handed to other tools and receivers.
+//!
+//! Numbers are written with the column layout of the standard (D19.12 for
+//! records, D12.4 for ionospheric parameters). RINEX 2 uses the Fortran form
+//! `0.266515184194D-03`; RINEX 3 uses one digit before the decimal point and
+//! an `E` exponent, as in current IGS products. Values read from a file of the
+//! same version are written with the same digits, so reading the written file
+//! gives back identical values. Converting RINEX 2 to RINEX 3 rounds the UTC
+//! parameters to the narrower fields of the TIME SYSTEM CORR line.
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use jiff::{civil::DateTime, tz::TimeZone};
+
+use crate::{
+    ephemeris::Ephemeris,
+    error::Error,
+    navigation::{
+        IonosphericCorrection, NavRecord, NavRecordKind, TimeSystemCorrection,
+    },
+    observation::header_line,
+    rule::Rinex,
+};
+
+/// Width of a numeric field of a navigation record.
+const FIELD_WIDTH: usize = 19;
+
+/// Number of clock fields on the epoch line of a navigation record.
+const CLOCK_FIELDS: usize = 3;
+
+/// Number of fields of a broadcast orbit line.
+const ORBIT_FIELDS: usize = 4;
+
+/// Version of a written navigation file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NavigationFormat {
+    /// RINEX 2.11 GPS navigation file
+    Rinex2,
+    /// RINEX 3.04 navigation file
+    #[default]
+    Rinex3,
+}
+
+impl NavigationFormat {
+    /// Returns the format version written in the header.
+    pub fn version(self) -> f64 {
+        match self {
+            Self::Rinex2 => 2.11,
+            Self::Rinex3 => 3.04,
+        }
+    }
+}
+
+impl Rinex {
+    /// Writes the navigation data as a RINEX navigation file.
+    ///
+    /// RINEX 2 files hold the GPS ephemerides only. RINEX 3 files hold the
+    /// GPS ephemerides followed by the ephemeris records of the other
+    /// systems; RINEX 4 messages without a RINEX 3 equivalent (CNAV, CNAV-2,
+    /// BDS CNAV and the STO, EOP and ION frames) are left out. Missing
+    /// RINEX 3 header corrections are derived from the GPS Klobuchar and UTC
+    /// parameters.
+    ///
+    /// # Arguments
+    /// * `writer` - Destination of the RINEX text
+    /// * `format` - Version of the written file
+    ///
+    /// # Returns
+    /// * `Ok(())` - If the file was written
+    /// * `Err(Error)` - If writing fails
+    ///
+    /// # Errors
+    /// * Returns an error if the destination cannot be written
+    pub fn write(
+        &self, writer: &mut impl Write, format: NavigationFormat,
+    ) -> Result<(), Error> {
+        match format {
+            NavigationFormat::Rinex2 => {
+                write_header_v2(writer, self)?;
+                for ephemeris in &self.ephemerides {
+                    write_ephemeris(writer, ephemeris, format)?;
+                }
+            }
+            NavigationFormat::Rinex3 => {
+                write_header_v3(writer, self)?;
+                for ephemeris in &self.ephemerides {
+                    write_ephemeris(writer, ephemeris, format)?;
+                }
+                for record in self.records.iter().filter(|r| is_rinex3(r)) {
+                    write_record(writer, record)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the navigation data to a RINEX navigation file.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the file to create
+    /// * `format` - Version of the written file
+    ///
+    /// # Returns
+    /// * `Ok(())` - If the file was written
+    /// * `Err(Error)` - If the file cannot be created or written
+    ///
+    /// # Errors
+    /// * Returns an error if the file cannot be created or written
+    pub fn write_file(
+        &self, path: &dyn AsRef<Path>, format: NavigationFormat,
+    ) -> Result<(), Error> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer, format)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Returns whether a record has a RINEX 3 representation.
+///
+/// # Arguments
+/// * `record` - A navigation record
+fn is_rinex3(record: &NavRecord) -> bool {
+    record.kind == NavRecordKind::Ephemeris
+        && record.message.as_deref().is_none_or(|message| {
+            message != "CNAV" && !message.starts_with("CNV")
+        })
+}
+
+/// Formats a number in the column layout of RINEX navigation files.
+///
+/// # Arguments
+/// * `value` - The number
+/// * `digits` - Number of digits after the decimal point
+/// * `width` - Field width
+/// * `format` - Version of the written file, selecting between `0.266515D-03`
+///   (RINEX 2) and `2.665152E-04` (RINEX 3)
+///
+/// # Returns
+/// The right-aligned field
+fn format_float(
+    value: f64, digits: usize, width: usize, format: NavigationFormat,
+) -> String {
+    let (text, exponent_char) = match format {
+        NavigationFormat::Rinex2 => (format!("{:.*e}", digits - 1, value), 'D'),
+        NavigationFormat::Rinex3 => (format!("{value:.digits$e}"), 'E'),
+    };
+    let (mantissa, exponent) = text.split_once('e').unwrap_or((&text, "0"));
+    let mut exponent = exponent.parse::<i32>().unwrap_or_default();
+    let mantissa = match format {
+        NavigationFormat::Rinex2 => {
+            // d.ddd...e(x) becomes 0.dddd...D(x+1), except for zero
+            let (sign, unsigned) = match mantissa.strip_prefix('-') {
+                Some(unsigned) => ("-", unsigned),
+                None => ("", mantissa),
+            };
+            if value != 0.0 {
+                exponent += 1;
+            }
+            format!("{sign}0.{}", unsigned.replace('.', ""))
+        }
+        NavigationFormat::Rinex3 => mantissa.to_string(),
+    };
+    let sign = if exponent < 0 { '-' } else { '+' };
+    let field = format!("{mantissa}{exponent_char}{sign}{:02}", exponent.abs());
+    format!("{field:>width$}")
+}
+
+/// Formats the fields of a navigation record line.
+///
+/// # Arguments
+/// * `values` - The field values
+/// * `format` - Version of the written file
+///
+/// # Returns
+/// The concatenated D19.12 fields
+fn record_fields(values: &[f64], format: NavigationFormat) -> String {
+    values
+        .iter()
+        .map(|value| format_float(*value, 12, FIELD_WIDTH, format))
+        .collect()
+}
+
+/// Writes the header of a RINEX 2.11 GPS navigation file.
+///
+/// # Arguments
+/// * `writer` - Destination of the RINEX text
+/// * `rinex` - Navigation data
+///
+/// # Errors
+/// * Returns an error if writing fails
+fn write_header_v2(
+    writer: &mut impl Write, rinex: &Rinex,
+) -> Result<(), Error> {
+    let format = NavigationFormat::Rinex2;
+    header_line(
+        writer,
+        &format!(
+            "{:9.2}{:11}{:<20}{:<20}",
+            format.version(),
+            "",
+            "N: GPS NAV DATA",
+            ""
+        ),
+        "RINEX VERSION / TYPE",
+    )?;
+    write_program(writer, rinex)?;
+    let ion = |values: &[f64; 4]| -> String {
+        let fields: String = values
+            .iter()
+            .map(|v| format_float(*v, 4, 12, format))
+            .collect();
+        format!("  {fields}")
+    };
+    if let Some(alpha) = &rinex.ion_alpha {
+        header_line(writer, &ion(alpha), "ION ALPHA")?;
+    }
+    if let Some(beta) = &rinex.ion_beta {
+        header_line(writer, &ion(beta), "ION BETA")?;
+    }
+    if let Some(utc) = &rinex.delta_utc {
+        header_line(
+            writer,
+            &format!(
+                "   {}{:9}{:9}",
+                record_fields(&[utc.a0, utc.a1], format),
+                utc.time,
+                utc.week
+            ),
+            "DELTA-UTC: A0,A1,T,W",
+        )?;
+    }
+    if let Some(leap_seconds) = rinex.leap_seconds {
+        header_line(writer, &format!("{leap_seconds:6}"), "LEAP SECONDS")?;
+    }
+    header_line(writer, "", "END OF HEADER")
+}
+
+/// Writes the header of a RINEX 3.04 navigation file.
+///
+/// # Arguments
+/// * `writer` - Destination of the RINEX text
+/// * `rinex` - Navigation data
+///
+/// # Errors
+/// * Returns an error if writing fails
+fn write_header_v3(
+    writer: &mut impl Write, rinex: &Rinex,
+) -> Result<(), Error> {
+    let format = NavigationFormat::Rinex3;
+    let system = if rinex.records.iter().any(is_rinex3) {
+        "M: MIXED"
+    } else {
+        "G: GPS"
+    };
+    header_line(
+        writer,
+        &format!(
+            "{:9.2}{:11}{:<20}{system:<20}",
+            format.version(),
+            "",
+            "N: GNSS NAV DATA"
+        ),
+        "RINEX VERSION / TYPE",
+    )?;
+    write_program(writer, rinex)?;
+    let mut ionospheric = rinex.ionospheric_corrections.clone();
+    if ionospheric.is_empty() {
+        let klobuchar = [("GPSA", rinex.ion_alpha), ("GPSB", rinex.ion_beta)];
+        for (kind, values) in klobuchar {
+            if let Some(values) = values {
+                ionospheric.push(IonosphericCorrection {
+                    kind: kind.to_string(),
+                    values,
+                    time_mark: None,
+                    satellite: None,
+                });
+            }
+        }
+    }
+    for correction in &ionospheric {
+        let values: String = correction
+            .values
+            .iter()
+            .map(|value| format_float(*value, 4, 12, format))
+            .collect();
+        let source = match (correction.time_mark, correction.satellite) {
+            (None, None) => String::new(),
+            (time_mark, satellite) => format!(
+                " {} {:>2}",
+                time_mark.unwrap_or(' '),
+                satellite.map(|sv| sv.to_string()).unwrap_or_default()
+            ),
+        };
+        let content = format!("{:<4} {values}{source}", correction.kind);
+        header_line(writer, &content, "IONOSPHERIC CORR")?;
+    }
+    let mut time_system = rinex.time_system_corrections.clone();
+    if time_system.is_empty()
+        && let Some(utc) = &rinex.delta_utc
+    {
+        time_system.push(TimeSystemCorrection {
+            kind: "GPUT".to_string(),
+            a0: utc.a0,
+            a1: utc.a1,
+            time: utc.time,
+            week: utc.week,
+            source: String::new(),
+        });
+    }
+    for correction in &time_system {
+        header_line(
+            writer,
+            &format!(
+                "{:<4} {}{} {:6} {:4} {}",
+                correction.kind,
+                format_float(correction.a0, 10, 17, format),
+                format_float(correction.a1, 9, 16, format),
+                correction.time,
+                correction.week,
+                correction.source
+            ),
+            "TIME SYSTEM CORR",
+        )?;
+    }
+    if let Some(leap_seconds) = rinex.leap_seconds {
+        header_line(writer, &format!("{leap_seconds:6}"), "LEAP SECONDS")?;
+    }
+    header_line(writer, "", "END OF HEADER")
+}
+
+/// Writes the program and comment header lines.
+///
+/// # Arguments
+/// * `writer` - Destination of the RINEX text
+/// * `rinex` - Navigation data
+///
+/// # Errors
+/// * Returns an error if writing fails
+fn write_program(writer: &mut impl Write, rinex: &Rinex) -> Result<(), Error> {
+    header_line(
+        writer,
+        &format!(
+            "{:<20.20}{:<20.20}{:<20.20}",
+            rinex.program, rinex.agency, rinex.update
+        ),
+        "PGM / RUN BY / DATE",
+    )?;
+    for comment in rinex.comments.lines() {
+        header_line(writer, comment, "COMMENT")?;
+    }
+    Ok(())
+}
+
+/// Writes a GPS ephemeris record.
+///
+/// # Arguments
+/// * `writer` - Destination of the RINEX text
+/// * `ephemeris` - The ephemeris
+/// * `format` - Version of the written file
+///
+/// # Errors
+/// * Returns an error if writing fails
+fn write_ephemeris(
+    writer: &mut impl Write, ephemeris: &Ephemeris, format: NavigationFormat,
+) -> Result<(), Error> {
+    let toc = ephemeris.time_of_clock.to_zoned(TimeZone::UTC).datetime();
+    let clock = &ephemeris.sv_clock;
+    let clock =
+        record_fields(&[clock.bias, clock.drift, clock.drift_rate], format);
+    match format {
+        NavigationFormat::Rinex2 => {
+            let seconds = f64::from(toc.second())
+                + f64::from(toc.subsec_nanosecond()) * 1e-9;
+            writeln!(
+                writer,
+                "{:2} {:02} {:2} {:2} {:2} {:2}{seconds:5.1}{clock}",
+                ephemeris.prn,
+                toc.year() % 100,
+                toc.month(),
+                toc.day(),
+                toc.hour(),
+                toc.minute()
+            )?;
+        }
+        NavigationFormat::Rinex3 => {
+            writeln!(
+                writer,
+                "G{:02} {}{clock}",
+                ephemeris.prn,
+                epoch_v3(&toc)
+            )?;
+        }
+    }
+    let orbits: [[f64; 4]; 7] = [
+        (&ephemeris.orbit1).into(),
+        (&ephemeris.orbit2).into(),
+        (&ephemeris.orbit3).into(),
+        (&ephemeris.orbit4).into(),
+        (&ephemeris.orbit5).into(),
+        (&ephemeris.orbit6).into(),
+        (&ephemeris.orbit7).into(),
+    ];
+    let indent = match format {
+        NavigationFormat::Rinex2 => "   ",
+        NavigationFormat::Rinex3 => "    ",
+    };
+    for orbit in orbits {
+        writeln!(writer, "{indent}{}", record_fields(&orbit, format))?;
+    }
+    Ok(())
+}
+
+/// Writes a RINEX 3 ephemeris record of any system.
+///
+/// # Arguments
+/// * `writer` - Destination of the RINEX text
+/// * `record` - The ephemeris record
+///
+/// # Errors
+/// * Returns an error if writing fails
+fn write_record(
+    writer: &mut impl Write, record: &NavRecord,
+) -> Result<(), Error> {
+    let format = NavigationFormat::Rinex3;
+    let mut clock = record
+        .values
+        .iter()
+        .take(CLOCK_FIELDS)
+        .copied()
+        .collect::<Vec<_>>();
+    clock.resize(CLOCK_FIELDS, 0.0);
+    writeln!(
+        writer,
+        "{} {}{}",
+        record.satellite,
+        epoch_v3(&record.epoch),
+        record_fields(&clock, format)
+    )?;
+    for orbit in record
+        .values
+        .get(CLOCK_FIELDS..)
+        .unwrap_or_default()
+        .chunks(ORBIT_FIELDS)
+    {
+        writeln!(writer, "    {}", record_fields(orbit, format))?;
+    }
+    Ok(())
+}
+
+/// Formats the epoch of a RINEX 3 record.
+///
+/// # Arguments
+/// * `epoch` - Epoch in the time system of the record
+///
+/// # Returns
+/// The epoch as `YYYY MM DD HH MM SS`
+fn epoch_v3(epoch: &DateTime) -> String {
+    format!(
+        "{:04} {:02} {:02} {:02} {:02} {:02}",
+        epoch.year(),
+        epoch.month(),
+        epoch.day(),
+        epoch.hour(),
+        epoch.minute(),
+        epoch.second()
+    )
+}
@@ -1,5 +1,15 @@
 use pest::Parser;
-use rinex::{error::Error, rule::*};
+use rinex::{
+    Almanac, RinexClock, Sp3,
+    error::Error,
+    navigation::{NavRecordKind, SatelliteId, SatelliteSystem},
+    observation::{
+        ObservationEpoch, ObservationHeader, ObservationTime,
+        ObservationWriter, SatelliteObservation,
+    },
+    rule::*,
+    writer::NavigationFormat,
+};
 #[test]
 fn rinex_parser() -> Result<(), Error> {
     let _ = RinexParser::parse(Rule::rinex, RINEX_DATA).map_err(Box::new)?;
@@ -10,6 +20,483 @@ fn rinex_read() -> Result<(), Error> {
     let _ = Rinex::read_string(RINEX_DATA)?;
     Ok(())
 }
+#[test]
+fn observation_writer() -> Result<(), Error> {
+    let header = ObservationHeader {
+        program: "test".to_string(),
+        interval: 1.0,
+        observation_types: vec![(
+            'G',
+            ["C1C", "L1C", "D1C", "S1C"].map(String::from).to_vec(),
+        )],
+        ..Default::default()
+    };
+    let mut writer = ObservationWriter::new(Vec::new(), &header)?;
+    writer.write_epoch(&ObservationEpoch {
+        time: ObservationTime {
+            year: 2024,
+            month: 6,
+            day: 1,
+            hour: 0,
+            minute: 0,
+            second: 1.0,
+        },
+        flag: 0,
+        satellites: vec![SatelliteObservation {
+            system: 'G',
+            prn: 5,
+            values: vec![
+                Some(22_213_582.902),
+                None,
+                Some(-2763.899),
+                Some(40.5),
+            ],
+        }],
+    })?;
+    let text = String::from_utf8(writer.into_inner()).unwrap_or_default();
+    let (header, body) =
+        text.split_once("END OF HEADER\n").ok_or(Error::Unknown)?;
+    // Every header line carries its label in columns 61-80
+    for line in header.lines().chain(["END OF HEADER"]) {
+        assert!(line.len() <= 80, "{line}");
+    }
+    for line in header.lines().filter(|line| !line.trim().is_empty()) {
+        assert!(line.len() > 60, "{line}");
+    }
+    assert!(header.starts_with("     3.04           OBSERVATION DATA    G"));
+    assert!(header.contains(&format!(
+        "{:<60}SYS / # / OBS TYPES",
+        "G    4 C1C L1C D1C S1C"
+    )));
+    assert_eq!(
+        body,
+        "> 2024 06 01 00 00  1.0000000  0  1\n\
+         G05  22213582.902                       -2763.899          40.500\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn observation_writer_unknown_system() -> Result<(), Error> {
+    let header = ObservationHeader {
+        observation_types: vec![('G', vec!["C1C".to_string()])],
+        ..Default::default()
+    };
+    let mut writer = ObservationWriter::new(Vec::new(), &header)?;
+    let epoch = ObservationEpoch {
+        satellites: vec![SatelliteObservation {
+            system: 'E',
+            prn: 1,
+            values: vec![Some(1.0)],
+        }],
+        ..Default::default()
+    };
+    assert!(writer.write_epoch(&epoch).is_err());
+    assert!(
+        ObservationWriter::new(Vec::new(), &ObservationHeader::default())
+            .is_err()
+    );
+    Ok(())
+}
+
+#[test]
+fn rinex3_read() -> Result<(), Error> {
+    let _ = RinexParser::parse(Rule::rinex, RINEX3_DATA).map_err(Box::new)?;
+    let rinex = Rinex::read_string(RINEX3_DATA)?;
+    assert_eq!(rinex.version, "3.04");
+    assert_eq!(
+        rinex.comments,
+        "Merged GPS/GLO/GAL/BDS navigation file\nbased on CONGO and MGEX \
+         tracking data"
+    );
+    assert_eq!(
+        rinex.ion_alpha,
+        Some([1.1176e-8, 7.4506e-9, -5.9605e-8, -5.9605e-8])
+    );
+    assert_eq!(
+        rinex.ion_beta,
+        Some([9.0112e4, 1.3107e5, -1.9661e5, -6.5536e4])
+    );
+    let delta_utc = rinex.delta_utc.as_ref().ok_or(Error::rule("no UTC"))?;
+    assert!(close(delta_utc.a0, -1.862_645_149_2e-9));
+    assert_eq!(delta_utc.time, 147_456);
+    assert_eq!(delta_utc.week, 2317);
+    assert_eq!(rinex.leap_seconds, Some(18));
+    assert_eq!(rinex.ionospheric_corrections.len(), 4);
+    let bds = &rinex.ionospheric_corrections[3];
+    assert_eq!(bds.kind, "BDSA");
+    assert_eq!(bds.time_mark, Some('A'));
+    assert_eq!(bds.satellite, Some(20));
+    assert_eq!(rinex.time_system_corrections.len(), 2);
+    assert_eq!(rinex.time_system_corrections[1].kind, "GAUT");
+
+    assert_eq!(rinex.ephemerides.len(), 1);
+    let eph = &rinex.ephemerides[0];
+    assert_eq!(eph.prn, 1);
+    assert!(close(eph.sv_clock.bias, 2.665_151_841_939e-4));
+
+    let ids = rinex
+        .records
+        .iter()
+        .map(|record| record.satellite.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, ["R05", "E11", "C19"]);
+    assert!(rinex.records.iter().all(|record| {
+        record.kind == NavRecordKind::Ephemeris && record.message.is_none()
+    }));
+    assert_eq!(rinex.records[0].values.len(), 3 + 3 * 4);
+    let galileo = &rinex.records[1];
+    assert_eq!(galileo.values.len(), 3 + 7 * 4);
+    let orbit5 = [1.003_613_238_584e-10, 517.0, 2316.0, 0.0];
+    assert!(
+        galileo
+            .orbit(5)
+            .iter()
+            .zip(orbit5)
+            .all(|(a, b)| close(*a, b))
+    );
+    assert!(close(galileo.orbit(7)[0], 519_065.0));
+    Ok(())
+}
+#[test]
+fn rinex2_glonass_read() -> Result<(), Error> {
+    let rinex = Rinex::read_string(RINEX2_GLONASS_DATA)?;
+    assert_eq!(rinex.version, "2.11");
+    assert!(rinex.ephemerides.is_empty());
+    assert_eq!(rinex.leap_seconds, Some(12));
+    let glut = &rinex.time_system_corrections[0];
+    assert_eq!(glut.kind, "GLUT");
+    assert!(close(glut.a0, 3.799_796_104_43e-7));
+    assert_eq!((glut.week, glut.time), (945, 86_400));
+
+    let ids = rinex
+        .records
+        .iter()
+        .map(|record| record.satellite.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, ["R03", "R04"]);
+    let record = &rinex.records[0];
+    assert_eq!(record.satellite.system, SatelliteSystem::Glonass);
+    assert_eq!(record.epoch.to_string(), "1998-02-15T00:15:00");
+    assert_eq!(record.values.len(), 3 + 3 * 4);
+    assert!(close(record.values[2], 10_800.0));
+    assert!(close(record.orbit(1)[0], 10_627.590_332_0));
+    assert!(close(record.orbit(2)[3], 21.0));
+    assert!(close(rinex.records[1].orbit(3)[2], 0.0));
+
+    let data =
+        RINEX2_GLONASS_DATA.replace("0.102263259888D+01", "0.1022X3259888D+01");
+    assert!(Rinex::read_string(&data).is_err());
+    let lenient = Rinex::read_string_lenient(&data)?;
+    assert_eq!(lenient.records, rinex.records[..1]);
+    assert_eq!(lenient.warnings.len(), 1);
+    assert_eq!(lenient.warnings[0].line, 13);
+    Ok(())
+}
+#[test]
+fn rinex4_read() -> Result<(), Error> {
+    let _ = RinexParser::parse(Rule::rinex, RINEX4_DATA).map_err(Box::new)?;
+    let rinex = Rinex::read_string(RINEX4_DATA)?;
+    assert_eq!(rinex.version, "4.00");
+    assert_eq!(rinex.leap_seconds, Some(18));
+    assert_eq!(rinex.ephemerides.len(), 1);
+    assert_eq!(rinex.ephemerides[0].prn, 1);
+    assert_eq!(
+        rinex.ion_alpha,
+        Some([
+            1.117_587_089_539e-8,
+            7.450_580_596_924e-9,
+            -5.960_464_477_539e-8,
+            -5.960_464_477_539e-8
+        ])
+    );
+    assert_eq!(
+        rinex.ion_beta,
+        Some([90112.0, 131_072.0, -196_608.0, -65536.0])
+    );
+    let delta_utc = rinex.delta_utc.as_ref().ok_or(Error::rule("no UTC"))?;
+    assert!(close(delta_utc.a0, -1.862_645_149_231e-9));
+    assert!(close(delta_utc.a1, -6.217_248_937_901e-15));
+    assert_eq!(delta_utc.week, 2316);
+    assert_eq!(delta_utc.time, 518_400);
+
+    let kinds = rinex
+        .records
+        .iter()
+        .map(|record| (record.kind, record.message.as_deref()))
+        .collect::<Vec<_>>();
+    assert_eq!(kinds, [
+        (NavRecordKind::Ephemeris, Some("CNAV")),
+        (NavRecordKind::Ephemeris, Some("INAV")),
+        (NavRecordKind::SystemTimeOffset, Some("LNAV")),
+        (NavRecordKind::Ionosphere, Some("LNAV")),
+        (NavRecordKind::EarthOrientation, Some("CNVX")),
+    ]);
+    assert!(!rinex.records[0].is_gps_lnav());
+    assert!(rinex.records[0].to_ephemeris().is_err());
+    assert_eq!(rinex.records[2].label.as_deref(), Some("GPUT"));
+    assert_eq!(rinex.records[4].values.len(), 3 + 2 * 4);
+    Ok(())
+}
+#[test]
+fn satellite_id() -> Result<(), Error> {
+    let id: SatelliteId = "C19".parse()?;
+    assert_eq!(id.system, SatelliteSystem::Beidou);
+    assert_eq!(id.prn, 19);
+    assert_eq!(id.to_string(), "C19");
+    assert_eq!("G 5".parse::<SatelliteId>()?.to_string(), "G05");
+    assert!("X01".parse::<SatelliteId>().is_err());
+    Ok(())
+}
+#[test]
+fn rinex_leading_dot_float() -> Result<(), Error> {
+    let data = RINEX_DATA
+        .replace("    0.2142D-07  0.2235D-07", "     .2142D-07  0.2235D-07")
+        .replace("0.0-0.426051672548D-03", "0.0 -.426051672548D-03");
+    let rinex = Rinex::read_string(&data)?;
+    assert_eq!(rinex.ion_alpha.map(|alpha| alpha[0]), Some(0.2142e-7));
+    assert!(close(
+        rinex.ephemerides[1].sv_clock.bias,
+        -0.426_051_672_548e-3
+    ));
+    Ok(())
+}
+#[test]
+fn rinex_error_position() {
+    let data = RINEX_DATA.replace("0.2235D-07", "0.2235X-07");
+    let error = Rinex::read_string(&data).err();
+    assert_eq!(error.and_then(|e| e.position()), Some((4, 17)));
+
+    let data = RINEX_DATA.replace(" 2 24  6  1", " 2 24 13  1");
+    let error = Rinex::read_string(&data).err();
+    assert_eq!(error.as_ref().and_then(Error::position), Some((17, 4)));
+
+    let error = Rinex::read_string(truncated(RINEX_DATA)).err();
+    assert_eq!(error.and_then(|e| e.position()), Some((31, 80)));
+}
+#[test]
+fn rinex_lenient() -> Result<(), Error> {
+    let strict = Rinex::read_string(RINEX_DATA)?;
+    let data = truncated(RINEX_DATA)
+        .replace(
+            "IGS BROADCAST EPHEMERIS FILE                                COMMENT",
+            "IGS BROADCAST EPHEMERIS FILE                                \
+             COMMENT\nGPS1                                                        \
+             MARKER NAME",
+        )
+        .replace("0.2235D-07", "0.2235X-07")
+        .replace("0.418195990970D-08", "0.418195990970Q-08");
+    assert!(Rinex::read_string(&data).is_err());
+
+    let rinex = Rinex::read_string_lenient(&data)?;
+    assert_eq!(rinex.ephemerides.len(), strict.ephemerides.len() - 2);
+    assert_eq!(rinex.ion_alpha, None);
+    assert_eq!(rinex.ion_beta, strict.ion_beta);
+    let positions = rinex
+        .warnings
+        .iter()
+        .map(|warning| (warning.line, warning.column))
+        .collect::<Vec<_>>();
+    assert_eq!(positions, [(4, 61), (5, 17), (19, 43), (33, 1)]);
+    assert!(rinex.warnings[0].message.contains("MARKER NAME"));
+    Ok(())
+}
+#[test]
+fn rinex2_write() -> Result<(), Error> {
+    let rinex = Rinex::read_string(RINEX_DATA)?;
+    let mut output = Vec::new();
+    rinex.write(&mut output, NavigationFormat::Rinex2)?;
+    let text =
+        String::from_utf8(output).map_err(|e| Error::rule(e.to_string()))?;
+    assert!(text.starts_with(
+        "     2.11           N: GPS NAV DATA                         RINEX \
+         VERSION / TYPE\n"
+    ));
+    // everything but the version line is written as read
+    let original = RINEX_DATA.trim_end().lines().skip(1).collect::<Vec<_>>();
+    let written = text.lines().skip(1).collect::<Vec<_>>();
+    assert_eq!(written, original);
+
+    let reread = Rinex::read_string(&text)?;
+    assert_eq!(reread.ephemerides, rinex.ephemerides);
+    assert_eq!(reread.delta_utc, rinex.delta_utc);
+    assert_eq!(reread.ion_alpha, rinex.ion_alpha);
+    assert_eq!(reread.ion_beta, rinex.ion_beta);
+    Ok(())
+}
+#[test]
+fn rinex3_write() -> Result<(), Error> {
+    let rinex = Rinex::read_string(RINEX3_DATA)?;
+    let mut output = Vec::new();
+    rinex.write(&mut output, NavigationFormat::Rinex3)?;
+    let text =
+        String::from_utf8(output).map_err(|e| Error::rule(e.to_string()))?;
+    let corrections = |data: &str| -> Vec<String> {
+        data.lines()
+            .filter(|line| line.ends_with(" CORR"))
+            .map(str::to_string)
+            .collect()
+    };
+    assert_eq!(corrections(&text), corrections(RINEX3_DATA));
+
+    let reread = Rinex::read_string(&text)?;
+    assert_eq!(reread.comments, rinex.comments);
+    assert_eq!(reread.ephemerides, rinex.ephemerides);
+    assert_eq!(reread.records, rinex.records);
+    assert_eq!(
+        reread.ionospheric_corrections,
+        rinex.ionospheric_corrections
+    );
+    assert_eq!(
+        reread.time_system_corrections,
+        rinex.time_system_corrections
+    );
+    assert_eq!(reread.leap_seconds, rinex.leap_seconds);
+
+    // RINEX 4 ephemerides without a RINEX 3 equivalent are left out
+    let rinex = Rinex::read_string(RINEX4_DATA)?;
+    let mut output = Vec::new();
+    rinex.write(&mut output, NavigationFormat::Rinex3)?;
+    let text =
+        String::from_utf8(output).map_err(|e| Error::rule(e.to_string()))?;
+    let reread = Rinex::read_string(&text)?;
+    assert_eq!(reread.ephemerides, rinex.ephemerides);
+    assert!(
+        reread
+            .records
+            .iter()
+            .all(|record| record.kind == NavRecordKind::Ephemeris)
+    );
+    Ok(())
+}
+#[test]
+fn rinex2_to_rinex3() -> Result<(), Error> {
+    let rinex = Rinex::read_string(RINEX_DATA)?;
+    let mut output = Vec::new();
+    rinex.write(&mut output, NavigationFormat::Rinex3)?;
+    let text =
+        String::from_utf8(output).map_err(|e| Error::rule(e.to_string()))?;
+    assert!(
+        text.contains("GPSA   2.1420E-08  2.2350E-08 -1.1920E-07 -5.9600E-08")
+    );
+
+    let reread = Rinex::read_string(&text)?;
+    assert_eq!(reread.version, "3.04");
+    assert_eq!(reread.ephemerides, rinex.ephemerides);
+    assert_eq!(reread.ion_alpha, rinex.ion_alpha);
+    assert_eq!(reread.ion_beta, rinex.ion_beta);
+    let (Some(utc), Some(expected)) = (&reread.delta_utc, &rinex.delta_utc)
+    else {
+        panic!("missing DELTA-UTC");
+    };
+    // TIME SYSTEM CORR holds 11 significant digits of A0 and 10 of A1
+    assert!((utc.a0 - expected.a0).abs() <= expected.a0.abs() * 1e-10);
+    assert!((utc.a1 - expected.a1).abs() <= expected.a1.abs() * 1e-9);
+    assert_eq!((utc.time, utc.week), (expected.time, expected.week));
+    Ok(())
+}
+#[test]
+fn sp3_read() -> Result<(), Error> {
+    let sp3 = Sp3::read_string(SP3_DATA)?;
+    assert_eq!(sp3.version, 'd');
+    assert_eq!(sp3.coordinate_system, "IGS20");
+    assert_eq!(sp3.agency, "IGS");
+    assert_eq!(sp3.time_system, "GPS");
+    assert!(close(sp3.interval, 900.0));
+    let names: Vec<_> =
+        sp3.satellites.iter().map(ToString::to_string).collect();
+    assert_eq!(names, ["G01", "G02", "G03"]);
+    assert_eq!(sp3.epochs.len(), 2);
+    // The zero position of G02 marks a missing record
+    let first = &sp3.epochs[0];
+    assert_eq!(first.positions.len(), 2);
+    assert!(close(first.positions[0].position[0], -13_175.575_071));
+    assert!(
+        first.positions[0]
+            .clock
+            .is_some_and(|c| close(c, -89.430_773))
+    );
+    assert_eq!(first.positions[1].satellite.to_string(), "G03");
+    assert_eq!(first.positions[1].clock, None);
+    assert_eq!(sp3.epochs[1].time.minute(), 15);
+
+    let data = SP3_DATA.replace("16417.259139", "16417.25913X");
+    let error = Sp3::read_string(&data).err();
+    assert_eq!(error.and_then(|e| e.position()), Some((12, 19)));
+    Ok(())
+}
+#[test]
+fn clock_read() -> Result<(), Error> {
+    let clock = RinexClock::read_string(CLOCK_DATA)?;
+    assert!(close(clock.version, 3.0));
+    assert_eq!(clock.time_system, "GPS");
+    // Receiver records are skipped, continuation lines are not records
+    assert_eq!(clock.records.len(), 3);
+    let record = &clock.records[0];
+    assert_eq!(record.satellite.to_string(), "G01");
+    assert!(close(record.bias, -8.943_077_3e-5));
+    assert!(record.sigma.is_some_and(|sigma| close(sigma, 1.234e-11)));
+    assert_eq!(clock.records[1].sigma, None);
+    assert_eq!(clock.records[2].epoch.second(), 30);
+
+    let error = RinexClock::read_string(SP3_DATA).err();
+    assert_eq!(error.and_then(|e| e.position()), Some((1, 1)));
+    Ok(())
+}
+#[test]
+fn yuma_read() -> Result<(), Error> {
+    let almanac = Almanac::read_yuma(YUMA_DATA)?;
+    assert_eq!(almanac.records.len(), 2);
+    let record = &almanac.records[0];
+    assert_eq!((record.prn, record.health, record.week), (1, 0, 145));
+    assert!(close(record.toa, 405_504.0));
+    assert!(close(record.inclination, 0.988_574_586_2));
+    assert!(close(record.omega_dot, -0.741_745_161_7e-8));
+    assert!(close(record.sqrt_a, 5_153.689_453));
+    assert!(close(record.af1, 0.109_139_364_2e-10));
+    assert_eq!(almanac.records[1].health, 63);
+
+    let data = YUMA_DATA.replace("0.8646164460", "0.86461644X0");
+    let error = Almanac::read_yuma(&data).err();
+    assert_eq!(error.and_then(|e| e.position()), Some((10, 29)));
+    // A record without its last value is reported at its header
+    let error = Almanac::read_yuma(truncated(YUMA_DATA)).err();
+    assert_eq!(error.and_then(|e| e.position()), Some((16, 1)));
+    Ok(())
+}
+#[test]
+fn sem_read() -> Result<(), Error> {
+    let almanac = Almanac::read_sem(SEM_DATA)?;
+    assert_eq!(almanac.records.len(), 2);
+    let record = &almanac.records[0];
+    assert_eq!((record.prn, record.health, record.week), (1, 0, 145));
+    assert!(close(record.toa, 405_504.0));
+    assert!(close(record.eccentricity, 1.156_806_945_800_78e-2));
+    // Angles are given in semicircles and the inclination relative to 0.3
+    let semicircle = std::f64::consts::PI;
+    assert!(close(
+        record.inclination,
+        (0.3 + 1.468_181_610_107_42e-2) * semicircle
+    ));
+    assert!(close(record.perigee, 2.752_178_907_394_41e-1 * semicircle));
+    assert!(close(record.af0, 4.758_834_838_867_19e-4));
+    assert_eq!((almanac.records[1].prn, almanac.records[1].health), (2, 63));
+
+    let data = SEM_DATA.replace("5.15368945312500E+03", "5.1536894531X500E+03");
+    let error = Almanac::read_sem(&data).err();
+    assert_eq!(error.and_then(|e| e.position()), Some((7, 68)));
+    assert!(Almanac::read_sem(truncated(SEM_DATA)).is_err());
+    Ok(())
+}
+/// Drops the last line of a RINEX file.
+fn truncated(data: &str) -> &str {
+    let data = data.trim_end();
+    data.rfind('\n').map_or(data, |end| &data[..end])
+}
+/// Returns whether two parsed values agree to 12 significant digits.
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() <= b.abs() * 1e-12
+}
 const RINEX_DATA: &str = r"     2              NAVIGATION DATA                         RINEX VERSION / TYPE
 CCRINEXN V1.6.0 UX  CDDIS               02-JUN-24 23:31     PGM / RUN BY / DATE
 IGS BROADCAST EPHEMERIS FILE                                COMMENT
@@ -44,3 +531,189 @@ IGS BROADCAST EPHEMERIS FILE                                COMMENT
     0.511218000000D+06 0.400000000000D+01 0.000000000000D+00 0.000000000000D+00
 
     ";
+
+const RINEX3_DATA: &str = r"     3.04           N: GNSS NAV DATA    M: MIXED            RINEX VERSION / TYPE
+BCEmerge           montenbruck         20240602 012100 GMT  PGM / RUN BY / DATE
+Merged GPS/GLO/GAL/BDS navigation file                      COMMENT
+  based on CONGO and MGEX tracking data                     COMMENT
+GPSA   1.1176E-08  7.4506E-09 -5.9605E-08 -5.9605E-08       IONOSPHERIC CORR
+GPSB   9.0112E+04  1.3107E+05 -1.9661E+05 -6.5536E+04       IONOSPHERIC CORR
+GAL    5.1250E+01  4.2969E-01  1.1688E-02  0.0000E+00       IONOSPHERIC CORR
+BDSA   1.0245E-08  2.9802E-08 -4.1723E-07  5.9605E-07 A 20  IONOSPHERIC CORR
+GPUT -1.8626451492E-09-6.217248938E-15 147456 2317          TIME SYSTEM CORR
+GAUT  0.0000000000E+00 0.000000000E+00 518400 2316          TIME SYSTEM CORR
+    18    18  2185     7GPS                                 LEAP SECONDS
+                                                            END OF HEADER
+G01 2024 06 01 00 00 00 2.665151841939E-04-5.570655048359E-12 0.000000000000E+00
+     3.900000000000E+01 7.765625000000E+01 5.858815471750E-09 3.012700775740E+00
+     3.973022103310E-06 1.338359597140E-02 6.590038537980E-06 5.153763774870E+03
+     5.184000000000E+05 1.098960638050E-07-5.901340726530E-02-5.401670932770E-08
+     9.547791985920E-01 2.482812500000E+02 1.032149639850E+00-7.994975879980E-09
+     2.310810540250E-10 1.000000000000E+00 2.316000000000E+03 0.000000000000E+00
+     2.800000000000E+00 0.000000000000E+00-1.955777406690E-08 3.900000000000E+01
+     5.112180000000E+05 4.000000000000E+00
+R05 2024 06 01 00 15 00 7.874425500631E-05 0.000000000000E+00 5.184000000000E+05
+    -1.294860791016E+04-1.598567962646E+00 0.000000000000E+00 0.000000000000E+00
+    -1.013617822266E+04 1.747779846191E+00 3.725290298462E-09 1.000000000000E+00
+     1.956022509766E+04 2.298479080200E-01-1.862645149231E-09 0.000000000000E+00
+E11 2024 06 01 00 10 00-5.427026795223E-04-8.015810457384E-12 0.000000000000E+00
+     1.000000000000E+02-2.259375000000E+01 2.901192279098E-09-2.163929349185E+00
+    -1.028180122375E-06 1.858428889047E-04 7.219240069389E-06 5.440613586426E+03
+     5.184000000000E+05 4.470348358154E-08-1.548612226066E+00 9.313225746155E-09
+     9.801290318069E-01 1.876562500000E+02-5.488738153427E-01-5.558803839040E-09
+     1.003613238584E-10 5.170000000000E+02 2.316000000000E+03
+     3.120000000000E+00 0.000000000000E+00-1.862645149231E-09-2.095475792885E-09
+     5.190650000000E+05
+C19 2024 06 01 00 00 00-1.033570198342E-03 4.409806850900E-11 0.000000000000E+00
+     1.000000000000E+00-4.953125000000E+01 3.960879844010E-09-2.768025524025E+00
+    -1.584831625223E-06 8.091353066266E-03 1.068785786629E-05 5.282621381760E+03
+     5.184000000000E+05-1.210719347000E-08-2.881929398515E+00-9.313225746155E-08
+     9.577622437088E-01 1.597187500000E+02-9.987823441170E-01-6.807426766376E-09
+    -5.571660653035E-10 0.000000000000E+00 8.600000000000E+02 0.000000000000E+00
+     2.000000000000E+00 0.000000000000E+00-5.900000000000E-09-9.000000000000E-09
+     5.184270000000E+05 0.000000000000E+00
+";
+
+const RINEX2_GLONASS_DATA: &str = r"     2.11           G: GLONASS NAV DATA                     RINEX VERSION / TYPE
+ASRINEXG V1.1.0 VM  AIUB                19-FEB-98 10:42     PGM / RUN BY / DATE
+STATION ZIMMERWALD                                          COMMENT
+  1998     2    16    0.379979610443D-06                    CORR TO SYSTEM TIME
+    12                                                      LEAP SECONDS
+                                                            END OF HEADER
+ 3 98  2 15  0 15  0.0 0.163525342941D-03 0.363797880709D-11 0.108000000000D+05
+    0.106275903320D+05-0.348924636841D+00 0.931322574615D-09 0.000000000000D+00
+   -0.944422070313D+04 0.288163375854D+01 0.931322574615D-09 0.210000000000D+02
+    0.212257280273D+05 0.144599342346D+01-0.186264514923D-08 0.300000000000D+01
+ 4 98  2 15  0 15  0.0 0.179599039257D-03 0.636646291241D-11 0.122400000000D+05
+    0.562136621094D+04-0.289074897766D+00-0.931322574615D-09 0.000000000000D+00
+   -0.236819248047D+05 0.102263259888D+01 0.931322574615D-09 0.120000000000D+02
+    0.762532910156D+04 0.339257907867D+01 0.000000000000D+00 0.300000000000D+01
+";
+
+const RINEX4_DATA: &str = r"     4.00           N: GNSS NAV DATA    M: MIXED            RINEX VERSION / TYPE
+BCEmerge           montenbruck         20240602 012100 GMT  PGM / RUN BY / DATE
+10.5066/F7Z899JZ                                            DOI
+    18    18  2185     7GPS                                 LEAP SECONDS
+                                                            END OF HEADER
+> EPH G01 LNAV
+G01 2024 06 01 00 00 00 2.665151841939E-04-5.570655048359E-12 0.000000000000E+00
+     3.900000000000E+01 7.765625000000E+01 5.858815471750E-09 3.012700775740E+00
+     3.973022103310E-06 1.338359597140E-02 6.590038537980E-06 5.153763774870E+03
+     5.184000000000E+05 1.098960638050E-07-5.901340726530E-02-5.401670932770E-08
+     9.547791985920E-01 2.482812500000E+02 1.032149639850E+00-7.994975879980E-09
+     2.310810540250E-10 1.000000000000E+00 2.316000000000E+03 0.000000000000E+00
+     2.800000000000E+00 0.000000000000E+00-1.955777406690E-08 3.900000000000E+01
+     5.112180000000E+05 4.000000000000E+00
+> EPH G01 CNAV
+G01 2024 06 01 00 00 00 2.665151841939E-04-5.570655048359E-12 0.000000000000E+00
+     3.900000000000E+01 7.765625000000E+01 5.858815471750E-09 3.012700775740E+00
+     3.973022103310E-06 1.338359597140E-02 6.590038537980E-06 5.153763774870E+03
+     5.184000000000E+05 1.098960638050E-07-5.901340726530E-02-5.401670932770E-08
+     9.547791985920E-01 2.482812500000E+02 1.032149639850E+00-7.994975879980E-09
+     2.310810540250E-10 1.000000000000E+00 2.316000000000E+03 0.000000000000E+00
+     2.800000000000E+00 0.000000000000E+00-1.955777406690E-08 3.900000000000E+01
+     5.112180000000E+05 4.000000000000E+00 0.000000000000E+00 0.000000000000E+00
+     0.000000000000E+00 0.000000000000E+00
+> EPH E11 INAV
+E11 2024 06 01 00 10 00-5.427026795223E-04-8.015810457384E-12 0.000000000000E+00
+     1.000000000000E+02-2.259375000000E+01 2.901192279098E-09-2.163929349185E+00
+    -1.028180122375E-06 1.858428889047E-04 7.219240069389E-06 5.440613586426E+03
+     5.184000000000E+05 4.470348358154E-08-1.548612226066E+00 9.313225746155E-09
+     9.801290318069E-01 1.876562500000E+02-5.488738153427E-01-5.558803839040E-09
+     1.003613238584E-10 5.170000000000E+02 2.316000000000E+03
+     3.120000000000E+00 0.000000000000E+00-1.862645149231E-09-2.095475792885E-09
+     5.190650000000E+05
+> STO G01 LNAV
+    2024 06 01 00 00 00 GPUT
+     5.112180000000E+05-1.862645149231E-09-6.217248937901E-15 0.000000000000E+00
+> ION G01 LNAV
+    2024 06 01 00 00 00 1.117587089539E-08 7.450580596924E-09-5.960464477539E-08
+    -5.960464477539E-08 9.011200000000E+04 1.310720000000E+05-1.966080000000E+05
+    -6.553600000000E+04 1.000000000000E+00
+> EOP G01 CNVX
+    2024 06 01 00 00 00 1.000000000000E-03 2.000000000000E-04 0.000000000000E+00
+     5.112180000000E+05 3.000000000000E-01 1.000000000000E-04 0.000000000000E+00
+     5.112180000000E+05 0.000000000000E+00 0.000000000000E+00 0.000000000000E+00
+";
+const SP3_DATA: &str = r"#dP2022  1  1  0  0  0.00000000       3 ORBIT IGS20 FIT  IGS
+## 2190 518400.00000000   900.00000000 59580 0.0000000000000
++    3   G01G02  3  0  0  0  0  0  0  0  0  0  0  0  0  0  0
++          0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0  0
+++         2  2  2  0  0  0  0  0  0  0  0  0  0  0  0  0  0
+%c G  cc GPS ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc
+%c cc cc ccc ccc cccc cccc cccc cccc ccccc ccccc ccccc ccccc
+%f  1.2500000  1.025000000  0.00000000000  0.000000000000000
+%i    0    0    0    0      0      0      0      0         0
+/* SYNTHETIC TEST ORBIT
+*  2022  1  1  0  0  0.00000000
+PG01 -13175.575071  16417.259139  15704.672701    -89.430773
+PG02      0.000000      0.000000      0.000000 999999.999999
+P  3  20123.456789  -5432.109876  16789.012345 999999.999999
+*  2022  1  1  0 15  0.00000000
+PG01 -12990.121212  15511.313131  16822.414141    -89.431001
+EOF
+";
+const CLOCK_DATA: &str = r"     3.00           C                   G                   RINEX VERSION / TYPE
+CCLOCK              IGS                 20220103 000000 UTC PGM / RUN BY / DATE
+GPS                                                         TIME SYSTEM ID
+     2    AS    AR                                          # / TYPES OF DATA
+     2                                                      # OF SOLN SATS
+G01 G02                                                     PRN LIST
+                                                            END OF HEADER
+AR ALGO 2022 01 01 00 00  0.000000  2    1.234567890000E-09  2.000000000000E-11
+AS G01  2022 01 01 00 00  0.000000  2   -8.943077300000E-05  1.234000000000E-11
+AS G02  2022 01 01 00 00  0.000000  1    4.512345600000E-04
+AS G01  2022 01 01 00 00 30.000000  6   -8.943079100000E-05  1.234000000000E-11
+    0.000000000000E+00  0.000000000000E+00  0.000000000000E+00  0.000000000000E+00
+";
+const YUMA_DATA: &str = r"******** Week 145 almanac for PRN-01 ********
+ID:                         01
+Health:                     000
+Eccentricity:               0.1156806946E-001
+Time of Applicability(s):  405504.0000
+Orbital Inclination(rad):   0.9885745862
+Rate of Right Ascen(r/s):  -0.7417451617E-008
+SQRT(A)  (m 1/2):           5153.689453
+Right Ascen at Week(rad):   0.2113454990E+001
+Argument of Perigee(rad):   0.8646164460
+Mean Anom(rad):             0.1843690464E+001
+Af0(s):                     0.4758834839E-003
+Af1(s/s):                   0.1091393642E-010
+week:                        145
+
+******** Week 145 almanac for PRN-02 ********
+ID:                         02
+Health:                     063
+Eccentricity:               0.2052927017E-001
+Time of Applicability(s):  405504.0000
+Orbital Inclination(rad):   0.9393019676
+Rate of Right Ascen(r/s):  -0.7954617716E-008
+SQRT(A)  (m 1/2):           5153.641602
+Right Ascen at Week(rad):   0.1006108403E+001
+Argument of Perigee(rad):  -0.1567285061E+001
+Mean Anom(rad):             0.2580934286E+001
+Af0(s):                    -0.4720687866E-003
+Af1(s/s):                   0.0000000000E+000
+week:                        145
+";
+const SEM_DATA: &str = r"2 CURRENT.ALM
+145 405504
+
+1
+63
+0
+ 1.15680694580078E-02  1.46818161010742E-02 -2.36105182557367E-09  5.15368945312500E+03
+ 6.72730565071106E-01  2.75217890739441E-01  5.86867570877075E-01  4.75883483886719E-04
+ 1.09139364212751E-11  0.00000000000000E+00
+0
+11
+
+2
+61
+0
+ 2.05292701721191E-02 -1.02003812789917E-03 -2.53203187510371E-09  5.15364160156250E+03
+ 3.20251822471619E-01 -4.98883247375488E-01  8.21541547775269E-01 -4.72068786621094E-04
+ 0.00000000000000E+00  0.00000000000000E+00
+63
+11
+";
@@ -0,0 +1,180 @@
+//! Bit-level writer and reader.
+//!
+//! RTCM 3 data fields are packed MSB first without alignment. Signed fields
+//! use two's complement unless stated otherwise.
+
+use crate::Error;
+
+/// Packs data fields MSB first into a byte buffer.
+#[derive(Debug, Clone, Default)]
+pub struct BitWriter {
+    /// Packed bytes (the last byte may be partially filled)
+    bytes: Vec<u8>,
+    /// Number of bits written
+    len: usize,
+}
+
+impl BitWriter {
+    /// Creates an empty writer.
+    ///
+    /// # Returns
+    /// A new `BitWriter`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of bits written so far.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether no bits were written yet.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Appends the low `bits` bits of an unsigned value.
+    ///
+    /// # Arguments
+    /// * `value` - Value to append (higher bits are ignored)
+    /// * `bits` - Field width in bits (at most 64)
+    pub fn put_u(&mut self, value: u64, bits: usize) {
+        for i in (0..bits).rev() {
+            if self.len.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            if (value >> i) & 1 == 1 {
+                let last = self.bytes.len() - 1;
+                self.bytes[last] |= 0x80 >> (self.len % 8);
+            }
+            self.len += 1;
+        }
+    }
+
+    /// Appends a signed value in two's complement.
+    ///
+    /// # Arguments
+    /// * `value` - Value to append
+    /// * `bits` - Field width in bits (at most 64)
+    pub fn put_i(&mut self, value: i64, bits: usize) {
+        self.put_u(value as u64, bits);
+    }
+
+    /// Appends a boolean as a single bit.
+    ///
+    /// # Arguments
+    /// * `value` - Bit value
+    pub fn put_bool(&mut self, value: bool) {
+        self.put_u(u64::from(value), 1);
+    }
+
+    /// Appends a scaled value as an unsigned field.
+    ///
+    /// # Arguments
+    /// * `field` - Name of the data field (for error messages)
+    /// * `value` - Value in physical units
+    /// * `scale` - Resolution of the field (LSB) in physical units
+    /// * `bits` - Field width in bits
+    ///
+    /// # Errors
+    /// * `Error::FieldOutOfRange` - If the scaled value does not fit
+    pub fn put_scaled_u(
+        &mut self, field: &'static str, value: f64, scale: f64, bits: usize,
+    ) -> Result<(), Error> {
+        let scaled = (value / scale).round();
+        if !(0.0..2f64.powi(bits as i32)).contains(&scaled) {
+            return Err(Error::field_out_of_range(field, value));
+        }
+        self.put_u(scaled as u64, bits);
+        Ok(())
+    }
+
+    /// Appends a scaled value as a signed two's complement field.
+    ///
+    /// # Arguments
+    /// * `field` - Name of the data field (for error messages)
+    /// * `value` - Value in physical units
+    /// * `scale` - Resolution of the field (LSB) in physical units
+    /// * `bits` - Field width in bits
+    ///
+    /// # Errors
+    /// * `Error::FieldOutOfRange` - If the scaled value does not fit
+    pub fn put_scaled_i(
+        &mut self, field: &'static str, value: f64, scale: f64, bits: usize,
+    ) -> Result<(), Error> {
+        let scaled = (value / scale).round();
+        let limit = 2f64.powi(bits as i32 - 1);
+        if !(-limit..limit).contains(&scaled) {
+            return Err(Error::field_out_of_range(field, value));
+        }
+        self.put_i(scaled as i64, bits);
+        Ok(())
+    }
+
+    /// Returns the packed bytes, padding the last byte with zeros.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// Reads data fields MSB first from a byte buffer.
+#[derive(Debug, Clone)]
+pub struct BitReader<'a> {
+    /// Source bytes
+    data: &'a [u8],
+    /// Position of the next bit
+    pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    /// Creates a reader positioned at the first bit.
+    ///
+    /// # Arguments
+    /// * `data` - Source bytes
+    ///
+    /// # Returns
+    /// A new `BitReader`
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    /// Returns the position of the next bit.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Reads an unsigned field.
+    ///
+    /// # Arguments
+    /// * `bits` - Field width in bits (at most 64)
+    ///
+    /// # Returns
+    /// * `Some(value)` - The field value
+    /// * `None` - If the data ends before the field
+    pub fn get_u(&mut self, bits: usize) -> Option<u64> {
+        if self.pos + bits > self.data.len() * 8 {
+            return None;
+        }
+        let mut value = 0u64;
+        for _ in 0..bits {
+            let bit = (self.data[self.pos / 8] >> (7 - self.pos % 8)) & 1;
+            value = (value << 1) | u64::from(bit);
+            self.pos += 1;
+        }
+        Some(value)
+    }
+
+    /// Reads a signed two's complement field.
+    ///
+    /// # Arguments
+    /// * `bits` - Field width in bits (1 to 64)
+    ///
+    /// # Returns
+    /// * `Some(value)` - The sign-extended field value
+    /// * `None` - If the data ends before the field
+    pub fn get_i(&mut self, bits: usize) -> Option<i64> {
+        let value = self.get_u(bits)?;
+        let shift = 64 - bits;
+        Some(((value << shift) as i64) >> shift)
+    }
+}
@@ -0,0 +1,71 @@
+//! CRC-24Q parity.
+//!
+//! RTCM 3 frames (and the GPS CNAV message) are protected by the 24-bit
+//! Qualcomm CRC with generator polynomial 0x1864CFB, a zero initial value and
+//! no reflection.
+
+/// Generator polynomial of CRC-24Q without the leading x^24 term.
+const POLYNOMIAL: u32 = 0x86_4CFB;
+
+/// Byte-wise lookup table of CRC-24Q.
+const TABLE: [u32; 256] = build_table();
+
+/// Builds the byte-wise lookup table at compile time.
+///
+/// # Returns
+/// The CRC of every single byte value
+const fn build_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = (i as u32) << 16;
+        let mut bit = 0;
+        while bit < 8 {
+            crc <<= 1;
+            if crc & 0x100_0000 != 0 {
+                crc ^= POLYNOMIAL;
+            }
+            bit += 1;
+        }
+        table[i] = crc & 0xFF_FFFF;
+        i += 1;
+    }
+    table
+}
+
+/// Computes the CRC-24Q of a byte sequence.
+///
+/// # Arguments
+/// * `data` - Bytes to protect
+///
+/// # Returns
+/// The 24-bit parity in the low bits of the result
+pub fn crc24q(data: &[u8]) -> u32 {
+    data.iter().fold(0, |crc, &byte| {
+        ((crc << 8) & 0xFF_FFFF) ^ TABLE[(((crc >> 16) as u8) ^ byte) as usize]
+    })
+}
+
+/// Computes the CRC-24Q of the first `len` bits of a byte sequence.
+///
+/// Bits are taken MSB first. This form is needed for messages whose length
+/// is not a multiple of eight bits, such as the GPS CNAV message.
+///
+/// # Arguments
+/// * `data` - Bytes holding the bits to protect
+/// * `len` - Number of bits
+///
+/// # Returns
+/// The 24-bit parity in the low bits of the result
+pub fn crc24q_bits(data: &[u8], len: usize) -> u32 {
+    let mut crc = 0u32;
+    for i in 0..len.min(data.len() * 8) {
+        let bit = u32::from((data[i / 8] >> (7 - i % 8)) & 1);
+        let feedback = ((crc >> 23) & 1) ^ bit;
+        crc = (crc << 1) & 0xFF_FFFF;
+        if feedback != 0 {
+            crc ^= POLYNOMIAL;
+        }
+    }
+    crc
+}
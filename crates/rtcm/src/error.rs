@@ -0,0 +1,47 @@
+use thiserror::Error;
+
+/// Custom error type for the RTCM crate
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Error when a value does not fit into its data field
+    #[error("Value {value} out of range for data field {field}")]
+    FieldOutOfRange {
+        /// Name of the data field
+        field: &'static str,
+        /// Value that caused the error
+        value: f64,
+    },
+
+    /// Error when a message payload exceeds the 1023 bytes of a frame
+    #[error("Message payload too long: {0} bytes (maximum 1023)")]
+    PayloadTooLong(usize),
+
+    /// Error when a message has invalid content
+    #[error("Invalid message: {0}")]
+    InvalidMessage(String),
+
+    /// Error when a frame cannot be decoded
+    #[error("Invalid frame: {0}")]
+    InvalidFrame(String),
+}
+
+impl Error {
+    /// Create a new error for a value outside its data field
+    #[inline]
+    pub fn field_out_of_range(field: &'static str, value: f64) -> Self {
+        Error::FieldOutOfRange { field, value }
+    }
+
+    /// Create a new invalid message error
+    #[inline]
+    pub fn invalid_message(message: impl Into<String>) -> Self {
+        Error::InvalidMessage(message.into())
+    }
+
+    /// Create a new invalid frame error
+    #[inline]
+    pub fn invalid_frame(message: impl Into<String>) -> Self {
+        Error::InvalidFrame(message.into())
+    }
+}
@@ -0,0 +1,618 @@
+//! RTCM 3 messages and transport framing.
+//!
+//! The data field numbers (DFxxx) and resolutions follow RTCM 10403.3.
+
+use constants::SPEED_OF_LIGHT;
+
+use crate::{
+    Error,
+    bits::{BitReader, BitWriter},
+    crc::crc24q,
+};
+
+/// Transport frame preamble.
+const PREAMBLE: u8 = 0xD3;
+
+/// Maximum payload length of a transport frame in bytes.
+const MAX_PAYLOAD: usize = 1023;
+
+/// Distance light travels in one millisecond (meters).
+const RANGE_MS: f64 = SPEED_OF_LIGHT * 1e-3;
+
+/// Signal ID of GPS L1 C/A in the MSM signal mask.
+pub const GPS_L1CA_SIGNAL: u8 = 2;
+
+/// Wraps a message payload into a transport frame.
+///
+/// The frame consists of the preamble, six reserved bits, the 10-bit payload
+/// length, the payload and the CRC-24Q parity over all preceding bytes.
+///
+/// # Arguments
+/// * `payload` - Message payload
+///
+/// # Returns
+/// * `Ok(Vec<u8>)` - The complete frame
+/// * `Err(Error)` - If the payload is too long
+///
+/// # Errors
+/// * `Error::PayloadTooLong` - If the payload exceeds 1023 bytes
+pub fn frame(payload: &[u8]) -> Result<Vec<u8>, Error> {
+    if payload.len() > MAX_PAYLOAD {
+        return Err(Error::PayloadTooLong(payload.len()));
+    }
+    let mut out = Vec::with_capacity(payload.len() + 6);
+    out.push(PREAMBLE);
+    out.push((payload.len() >> 8) as u8);
+    out.push(payload.len() as u8);
+    out.extend_from_slice(payload);
+    let crc = crc24q(&out);
+    out.extend_from_slice(&[(crc >> 16) as u8, (crc >> 8) as u8, crc as u8]);
+    Ok(out)
+}
+
+/// Extracts the payload of the transport frame at the start of a buffer.
+///
+/// # Arguments
+/// * `data` - Buffer starting with a frame
+///
+/// # Returns
+/// * `Ok((payload, length))` - The payload and the total frame length
+/// * `Err(Error)` - If the buffer does not start with a valid frame
+///
+/// # Errors
+/// * `Error::InvalidFrame` - If the preamble, length or parity is wrong
+pub fn parse_frame(data: &[u8]) -> Result<(&[u8], usize), Error> {
+    if data.first() != Some(&PREAMBLE) {
+        return Err(Error::invalid_frame("missing preamble"));
+    }
+    if data.len() < 6 {
+        return Err(Error::invalid_frame("frame too short"));
+    }
+    let len = (usize::from(data[1] & 0x03) << 8) | usize::from(data[2]);
+    let total = len + 6;
+    if data.len() < total {
+        return Err(Error::invalid_frame(format!(
+            "expected {total} bytes, got {}",
+            data.len()
+        )));
+    }
+    let crc = (u32::from(data[total - 3]) << 16)
+        | (u32::from(data[total - 2]) << 8)
+        | u32::from(data[total - 1]);
+    if crc24q(&data[..total - 3]) != crc {
+        return Err(Error::invalid_frame("parity mismatch"));
+    }
+    Ok((&data[3..total - 3], total))
+}
+
+/// Reads the message number (DF002) of a payload.
+///
+/// # Arguments
+/// * `payload` - Message payload
+///
+/// # Returns
+/// * `Some(number)` - The message number
+/// * `None` - If the payload is shorter than 12 bits
+pub fn message_number(payload: &[u8]) -> Option<u16> {
+    BitReader::new(payload).get_u(12).map(|n| n as u16)
+}
+
+/// Stationary reference station antenna reference point (message 1005).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StationArp {
+    /// Reference station ID (DF003, 0-4095)
+    pub station_id: u16,
+    /// Antenna reference point in ECEF coordinates (meters)
+    pub position: [f64; 3],
+}
+
+impl StationArp {
+    /// Encodes the message into a transport frame.
+    ///
+    /// The station is flagged as a physical GPS reference station.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<u8>)` - The framed message
+    /// * `Err(Error)` - If a field is out of range
+    ///
+    /// # Errors
+    /// * `Error::FieldOutOfRange` - If the station ID or a coordinate does not
+    ///   fit its data field
+    pub fn encode(&self) -> Result<Vec<u8>, Error> {
+        let mut w = BitWriter::new();
+        w.put_u(1005, 12); // DF002
+        put_station_id(&mut w, self.station_id)?; // DF003
+        w.put_u(0, 6); // DF021 ITRF realization year
+        w.put_bool(true); // DF022 GPS
+        w.put_bool(false); // DF023 GLONASS
+        w.put_bool(false); // DF024 Galileo
+        w.put_bool(false); // DF141 physical reference station
+        w.put_scaled_i("DF025", self.position[0], 1e-4, 38)?;
+        w.put_bool(false); // DF142 single receiver oscillator
+        w.put_u(0, 1); // DF001 reserved
+        w.put_scaled_i("DF026", self.position[1], 1e-4, 38)?;
+        w.put_u(0, 2); // DF364 quarter cycle indicator
+        w.put_scaled_i("DF027", self.position[2], 1e-4, 38)?;
+        frame(&w.into_bytes())
+    }
+}
+
+/// GPS broadcast ephemeris of one satellite (message 1019).
+///
+/// Angles are in semicircles and angular rates in semicircles per second, as
+/// broadcast in the navigation message.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GpsEphemeris {
+    /// Satellite PRN (DF009, 1-32)
+    pub prn: u8,
+    /// GPS week number (DF076, transmitted modulo 1024)
+    pub week: u16,
+    /// SV accuracy index (DF077)
+    pub ura: u8,
+    /// Code on L2 (DF078)
+    pub code_on_l2: u8,
+    /// Rate of inclination angle (DF079, semicircles/s)
+    pub idot: f64,
+    /// Issue of data, ephemeris (DF071)
+    pub iode: u8,
+    /// Time of clock (DF081, seconds of week)
+    pub toc: f64,
+    /// Clock drift rate (DF082, s/s²)
+    pub af2: f64,
+    /// Clock drift (DF083, s/s)
+    pub af1: f64,
+    /// Clock bias (DF084, s)
+    pub af0: f64,
+    /// Issue of data, clock (DF085)
+    pub iodc: u16,
+    /// Sine harmonic correction to the orbit radius (DF086, m)
+    pub crs: f64,
+    /// Mean motion difference (DF087, semicircles/s)
+    pub delta_n: f64,
+    /// Mean anomaly at reference time (DF088, semicircles)
+    pub m0: f64,
+    /// Cosine harmonic correction to the argument of latitude (DF089, rad)
+    pub cuc: f64,
+    /// Eccentricity (DF090)
+    pub eccentricity: f64,
+    /// Sine harmonic correction to the argument of latitude (DF091, rad)
+    pub cus: f64,
+    /// Square root of the semi-major axis (DF092, m^1/2)
+    pub sqrt_a: f64,
+    /// Time of ephemeris (DF093, seconds of week)
+    pub toe: f64,
+    /// Cosine harmonic correction to the inclination (DF094, rad)
+    pub cic: f64,
+    /// Longitude of ascending node at weekly epoch (DF095, semicircles)
+    pub omega0: f64,
+    /// Sine harmonic correction to the inclination (DF096, rad)
+    pub cis: f64,
+    /// Inclination at reference time (DF097, semicircles)
+    pub i0: f64,
+    /// Cosine harmonic correction to the orbit radius (DF098, m)
+    pub crc: f64,
+    /// Argument of perigee (DF099, semicircles)
+    pub omega: f64,
+    /// Rate of right ascension (DF100, semicircles/s)
+    pub omega_dot: f64,
+    /// Group delay differential (DF101, s)
+    pub tgd: f64,
+    /// SV health (DF102)
+    pub health: u8,
+    /// L2 P data flag (DF103)
+    pub l2p_data_flag: bool,
+    /// Fit interval flag (DF137)
+    pub fit_interval: bool,
+}
+
+impl GpsEphemeris {
+    /// Encodes the message into a transport frame.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<u8>)` - The framed message
+    /// * `Err(Error)` - If a field is out of range
+    ///
+    /// # Errors
+    /// * `Error::FieldOutOfRange` - If a parameter does not fit its data field
+    pub fn encode(&self) -> Result<Vec<u8>, Error> {
+        if !(1..=32).contains(&self.prn) {
+            return Err(Error::field_out_of_range(
+                "DF009",
+                f64::from(self.prn),
+            ));
+        }
+        let mut w = BitWriter::new();
+        w.put_u(1019, 12); // DF002
+        w.put_u(u64::from(self.prn), 6); // DF009
+        w.put_u(u64::from(self.week % 1024), 10); // DF076
+        w.put_u(u64::from(self.ura), 4); // DF077
+        w.put_u(u64::from(self.code_on_l2), 2); // DF078
+        w.put_scaled_i("DF079", self.idot, P2_43, 14)?;
+        w.put_u(u64::from(self.iode), 8); // DF071
+        w.put_scaled_u("DF081", self.toc, 16.0, 16)?;
+        w.put_scaled_i("DF082", self.af2, P2_55, 8)?;
+        w.put_scaled_i("DF083", self.af1, P2_43, 16)?;
+        w.put_scaled_i("DF084", self.af0, P2_31, 22)?;
+        w.put_u(u64::from(self.iodc), 10); // DF085
+        w.put_scaled_i("DF086", self.crs, P2_5, 16)?;
+        w.put_scaled_i("DF087", self.delta_n, P2_43, 16)?;
+        w.put_scaled_i("DF088", self.m0, P2_31, 32)?;
+        w.put_scaled_i("DF089", self.cuc, P2_29, 16)?;
+        w.put_scaled_u("DF090", self.eccentricity, P2_33, 32)?;
+        w.put_scaled_i("DF091", self.cus, P2_29, 16)?;
+        w.put_scaled_u("DF092", self.sqrt_a, P2_19, 32)?;
+        w.put_scaled_u("DF093", self.toe, 16.0, 16)?;
+        w.put_scaled_i("DF094", self.cic, P2_29, 16)?;
+        w.put_scaled_i("DF095", self.omega0, P2_31, 32)?;
+        w.put_scaled_i("DF096", self.cis, P2_29, 16)?;
+        w.put_scaled_i("DF097", self.i0, P2_31, 32)?;
+        w.put_scaled_i("DF098", self.crc, P2_5, 16)?;
+        w.put_scaled_i("DF099", self.omega, P2_31, 32)?;
+        w.put_scaled_i("DF100", self.omega_dot, P2_43, 24)?;
+        w.put_scaled_i("DF101", self.tgd, P2_31, 8)?;
+        w.put_u(u64::from(self.health), 6); // DF102
+        w.put_bool(self.l2p_data_flag); // DF103
+        w.put_bool(self.fit_interval); // DF137
+        frame(&w.into_bytes())
+    }
+}
+
+/// Multiple signal message type.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MsmType {
+    /// MSM4: full pseudorange and phase range with CNR
+    Msm4,
+    /// MSM7: extended resolution pseudorange, phase range, phase range rate
+    /// and CNR
+    #[default]
+    Msm7,
+}
+
+impl MsmType {
+    /// Returns the GPS message number of this MSM type.
+    ///
+    /// # Returns
+    /// 1074 for MSM4 and 1077 for MSM7
+    pub fn gps_message_number(self) -> u16 {
+        match self {
+            Self::Msm4 => 1074,
+            Self::Msm7 => 1077,
+        }
+    }
+}
+
+impl std::str::FromStr for MsmType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "4" | "msm4" | "1074" => Ok(Self::Msm4),
+            "7" | "msm7" | "1077" => Ok(Self::Msm7),
+            _ => Err(Error::invalid_message(format!(
+                "Unknown MSM type '{s}', expected 4 or 7"
+            ))),
+        }
+    }
+}
+
+/// Observation of one signal of a satellite.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MsmSignal {
+    /// Signal ID in the signal mask (1-32, 2 for GPS L1 C/A)
+    pub id: u8,
+    /// Pseudorange in meters
+    pub pseudorange: f64,
+    /// Carrier phase range in meters
+    pub phase_range: f64,
+    /// Phase range rate in meters per second (MSM7 only)
+    pub phase_range_rate: f64,
+    /// Carrier-to-noise density ratio in dB-Hz
+    pub cnr: f64,
+    /// Time since the carrier phase is continuously tracked, in milliseconds
+    pub lock_time_ms: u32,
+    /// Whether a half-cycle ambiguity is unresolved
+    pub half_cycle: bool,
+}
+
+/// Observations of one satellite.
+#[derive(Debug, Clone, Default)]
+pub struct MsmSatellite {
+    /// Satellite ID in the satellite mask (PRN for GPS, 1-64)
+    pub id: u8,
+    /// Observed signals
+    pub signals: Vec<MsmSignal>,
+}
+
+/// One GPS multiple signal message epoch (message 1074 or 1077).
+#[derive(Debug, Clone, Default)]
+pub struct MsmEpoch {
+    /// Reference station ID (DF003, 0-4095)
+    pub station_id: u16,
+    /// GPS time of week in milliseconds (DF004)
+    pub tow_ms: u32,
+    /// Whether more MSM messages follow for the same epoch (DF393)
+    pub multiple_message: bool,
+    /// MSM type
+    pub msm: MsmType,
+    /// Observed satellites
+    pub satellites: Vec<MsmSatellite>,
+}
+
+impl MsmEpoch {
+    /// Encodes the epoch as a GPS MSM into a transport frame.
+    ///
+    /// The rough range and rate of every satellite are taken from its first
+    /// signal, and the signal fields carry the remainders.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<u8>)` - The framed message
+    /// * `Err(Error)` - If the epoch cannot be encoded
+    ///
+    /// # Errors
+    /// * `Error::InvalidMessage` - If a satellite has no signals or an ID is
+    ///   outside the mask, or if the cell mask exceeds 64 cells
+    /// * `Error::FieldOutOfRange` - If an observation does not fit its data
+    ///   field
+    /// * `Error::PayloadTooLong` - If the message exceeds one frame
+    pub fn encode(&self) -> Result<Vec<u8>, Error> {
+        let mut satellites: Vec<&MsmSatellite> =
+            self.satellites.iter().collect();
+        satellites.sort_by_key(|sat| sat.id);
+        let mut signal_ids: Vec<u8> = satellites
+            .iter()
+            .flat_map(|sat| sat.signals.iter().map(|sig| sig.id))
+            .collect();
+        signal_ids.sort_unstable();
+        signal_ids.dedup();
+        if satellites.len() * signal_ids.len() > 64 {
+            return Err(Error::invalid_message(format!(
+                "{} satellites with {} signals exceed 64 cells",
+                satellites.len(),
+                signal_ids.len()
+            )));
+        }
+        let mut w = BitWriter::new();
+        let cells = self.put_header(&mut w, &satellites, &signal_ids)?;
+        // Satellite data
+        let rough: Vec<(f64, f64)> = satellites
+            .iter()
+            .map(|sat| {
+                let first = &sat.signals[0];
+                let range_ms =
+                    (first.pseudorange / RANGE_MS / P2_10).round() * P2_10;
+                (range_ms, first.phase_range_rate.round())
+            })
+            .collect();
+        for &(range_ms, _) in &rough {
+            w.put_scaled_u("DF397", range_ms.floor(), 1.0, 8)?;
+        }
+        if self.msm == MsmType::Msm7 {
+            for _ in &rough {
+                w.put_u(0, 4); // Extended satellite information
+            }
+        }
+        for &(range_ms, _) in &rough {
+            w.put_scaled_u("DF398", range_ms - range_ms.floor(), P2_10, 10)?;
+        }
+        if self.msm == MsmType::Msm7 {
+            for &(_, rate) in &rough {
+                w.put_scaled_i("DF399", rate, 1.0, 14)?;
+            }
+        }
+        // Signal data
+        let mut cell_rough = Vec::with_capacity(cells.len());
+        for (sat, &(range_ms, rate)) in satellites.iter().zip(&rough) {
+            for &id in &signal_ids {
+                if sat.signals.iter().any(|sig| sig.id == id) {
+                    cell_rough.push((range_ms, rate));
+                }
+            }
+        }
+        self.put_signal_data(&mut w, &cells, &cell_rough)?;
+        frame(&w.into_bytes())
+    }
+
+    /// Appends the MSM header including the satellite, signal and cell
+    /// masks.
+    ///
+    /// # Arguments
+    /// * `w` - Destination writer
+    /// * `satellites` - Observed satellites sorted by ID
+    /// * `signal_ids` - Sorted, distinct signal IDs
+    ///
+    /// # Returns
+    /// * `Ok(cells)` - The observed signals in cell order
+    /// * `Err(Error)` - If an ID is invalid
+    ///
+    /// # Errors
+    /// * `Error::InvalidMessage` - If a satellite has no signals or an ID is
+    ///   outside the mask
+    fn put_header<'a>(
+        &self, w: &mut BitWriter, satellites: &[&'a MsmSatellite],
+        signal_ids: &[u8],
+    ) -> Result<Vec<&'a MsmSignal>, Error> {
+        w.put_u(u64::from(self.msm.gps_message_number()), 12); // DF002
+        put_station_id(w, self.station_id)?; // DF003
+        w.put_u(u64::from(self.tow_ms), 30); // DF004
+        w.put_bool(self.multiple_message); // DF393
+        w.put_u(0, 3); // DF409 IODS
+        w.put_u(0, 7); // DF001 reserved
+        w.put_u(0, 2); // DF411 clock steering
+        w.put_u(0, 2); // DF412 external clock
+        w.put_bool(false); // DF417 divergence-free smoothing
+        w.put_u(0, 3); // DF418 smoothing interval
+        // DF394 satellite mask
+        let mut mask = 0u64;
+        for sat in satellites {
+            if !(1..=64).contains(&sat.id) || sat.signals.is_empty() {
+                return Err(Error::invalid_message(format!(
+                    "Invalid satellite {} with {} signals",
+                    sat.id,
+                    sat.signals.len()
+                )));
+            }
+            mask |= 1 << (64 - u32::from(sat.id));
+        }
+        w.put_u(mask, 64);
+        // DF395 signal mask
+        let mut mask = 0u64;
+        for &id in signal_ids {
+            if !(1..=32).contains(&id) {
+                return Err(Error::invalid_message(format!(
+                    "Invalid signal ID {id}"
+                )));
+            }
+            mask |= 1 << (32 - u32::from(id));
+        }
+        w.put_u(mask, 32);
+        // DF396 cell mask
+        let mut cells: Vec<&MsmSignal> = Vec::new();
+        for sat in satellites {
+            for &id in signal_ids {
+                let signal = sat.signals.iter().find(|sig| sig.id == id);
+                w.put_bool(signal.is_some());
+                cells.extend(signal);
+            }
+        }
+        Ok(cells)
+    }
+
+    /// Appends the signal data of all cells.
+    ///
+    /// # Arguments
+    /// * `w` - Destination writer
+    /// * `cells` - Observed signals in cell order
+    /// * `cell_rough` - Rough range (ms) and rate (m/s) of each cell's
+    ///   satellite
+    ///
+    /// # Errors
+    /// * `Error::FieldOutOfRange` - If an observation does not fit its data
+    ///   field
+    fn put_signal_data(
+        &self, w: &mut BitWriter, cells: &[&MsmSignal],
+        cell_rough: &[(f64, f64)],
+    ) -> Result<(), Error> {
+        let (pr_bits, pr_scale, cp_bits, cp_scale) = match self.msm {
+            MsmType::Msm4 => (15, P2_24, 22, P2_29),
+            MsmType::Msm7 => (20, P2_29, 24, P2_31),
+        };
+        for (sig, &(range_ms, _)) in cells.iter().zip(cell_rough) {
+            w.put_scaled_i(
+                "fine pseudorange",
+                sig.pseudorange / RANGE_MS - range_ms,
+                pr_scale,
+                pr_bits,
+            )?;
+        }
+        for (sig, &(range_ms, _)) in cells.iter().zip(cell_rough) {
+            w.put_scaled_i(
+                "fine phase range",
+                sig.phase_range / RANGE_MS - range_ms,
+                cp_scale,
+                cp_bits,
+            )?;
+        }
+        for sig in cells {
+            match self.msm {
+                MsmType::Msm4 => {
+                    w.put_u(
+                        u64::from(lock_time_indicator(sig.lock_time_ms)),
+                        4,
+                    );
+                }
+                MsmType::Msm7 => w.put_u(
+                    u64::from(extended_lock_time_indicator(sig.lock_time_ms)),
+                    10,
+                ),
+            }
+        }
+        for sig in cells {
+            w.put_bool(sig.half_cycle); // DF420
+        }
+        for sig in cells {
+            let cnr = sig.cnr.max(0.0);
+            match self.msm {
+                MsmType::Msm4 => w.put_u(cnr.round().min(63.0) as u64, 6),
+                MsmType::Msm7 => {
+                    w.put_u((cnr * 16.0).round().min(1023.0) as u64, 10);
+                }
+            }
+        }
+        if self.msm == MsmType::Msm7 {
+            for (sig, &(_, rate)) in cells.iter().zip(cell_rough) {
+                w.put_scaled_i("DF404", sig.phase_range_rate - rate, 1e-4, 15)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 2^-5
+const P2_5: f64 = 1.0 / 32.0;
+/// 2^-10
+const P2_10: f64 = 1.0 / 1024.0;
+/// 2^-19
+const P2_19: f64 = 1.0 / 524_288.0;
+/// 2^-24
+const P2_24: f64 = 1.0 / 16_777_216.0;
+/// 2^-29
+const P2_29: f64 = 1.0 / 536_870_912.0;
+/// 2^-31
+const P2_31: f64 = 1.0 / 2_147_483_648.0;
+/// 2^-33
+const P2_33: f64 = 1.0 / 8_589_934_592.0;
+/// 2^-43
+const P2_43: f64 = 1.0 / 8_796_093_022_208.0;
+/// 2^-55
+const P2_55: f64 = 1.0 / 36_028_797_018_963_968.0;
+
+/// Appends the reference station ID (DF003).
+///
+/// # Arguments
+/// * `w` - Destination writer
+/// * `station_id` - Reference station ID
+///
+/// # Errors
+/// * `Error::FieldOutOfRange` - If the ID exceeds 4095
+fn put_station_id(w: &mut BitWriter, station_id: u16) -> Result<(), Error> {
+    if station_id > 4095 {
+        return Err(Error::field_out_of_range("DF003", f64::from(station_id)));
+    }
+    w.put_u(u64::from(station_id), 12);
+    Ok(())
+}
+
+/// Converts a lock time to the 4-bit MSM lock time indicator (DF402).
+///
+/// # Arguments
+/// * `ms` - Lock time in milliseconds
+///
+/// # Returns
+/// The indicator, 0 below 32 ms and 15 from 524288 ms on
+pub fn lock_time_indicator(ms: u32) -> u8 {
+    if ms < 32 {
+        0
+    } else {
+        (ms.ilog2() - 4).min(15) as u8
+    }
+}
+
+/// Converts a lock time to the 10-bit extended lock time indicator (DF407).
+///
+/// # Arguments
+/// * `ms` - Lock time in milliseconds
+///
+/// # Returns
+/// The indicator, equal to the lock time below 64 ms and 704 from
+/// 67108864 ms on
+pub fn extended_lock_time_indicator(ms: u32) -> u16 {
+    if ms < 64 {
+        return ms as u16;
+    }
+    let k = ms.ilog2();
+    if k >= 26 {
+        return 704;
+    }
+    // Within [2^k, 2^(k+1)) the resolution is 2^(k-5) ms
+    ((u64::from(ms) + (1u64 << k) * u64::from(k - 5)) >> (k - 5)) as u16
+}
@@ -0,0 +1,26 @@
+//! RTCM 3 message encoder for GNSS reference station data.
+//!
+//! This crate encodes the messages a virtual reference station needs to feed
+//! an RTK rover:
+//! - 1005: Stationary reference station antenna reference point (ARP)
+//! - 1019: GPS broadcast ephemeris
+//! - 1074/1077: GPS multiple signal messages (MSM4 and MSM7)
+//!
+//! Every encoder returns a complete transport frame (preamble, length,
+//! payload and CRC-24Q parity) that can be written to a file or a socket as
+//! is.
+
+/// Bit-level writer and reader for message payloads
+pub mod bits;
+/// CRC-24Q parity used by RTCM 3 frames
+pub mod crc;
+/// Error types for RTCM encoding
+pub mod error;
+/// RTCM 3 message types and transport framing
+pub mod message;
+pub use crc::crc24q;
+pub use error::Error;
+pub use message::{
+    GpsEphemeris, MsmEpoch, MsmSatellite, MsmSignal, MsmType, StationArp,
+    frame, parse_frame,
+};
@@ -0,0 +1,191 @@
+use rtcm::{
+    Error, GpsEphemeris, MsmEpoch, MsmSatellite, MsmSignal, MsmType,
+    StationArp,
+    bits::{BitReader, BitWriter},
+    crc::crc24q_bits,
+    crc24q, frame,
+    message::{
+        GPS_L1CA_SIGNAL, extended_lock_time_indicator, lock_time_indicator,
+        message_number,
+    },
+    parse_frame,
+};
+use test_case::test_case;
+
+/// Distance light travels in one millisecond (meters)
+const RANGE_MS: f64 = 299_792.458;
+
+#[test]
+fn crc24q_check_value() {
+    assert_eq!(crc24q(b""), 0);
+    assert_eq!(crc24q(b"123456789"), 0x00CD_E703);
+    assert_eq!(crc24q_bits(b"123456789", 72), 0x00CD_E703);
+}
+
+#[test]
+fn bit_writer_round_trip() {
+    let mut w = BitWriter::new();
+    w.put_u(0b101, 3);
+    w.put_i(-5, 7);
+    w.put_u(0xABCD, 16);
+    w.put_bool(true);
+    assert_eq!(w.len(), 27);
+    let bytes = w.into_bytes();
+    assert_eq!(bytes.len(), 4);
+    let mut r = BitReader::new(&bytes);
+    assert_eq!(r.get_u(3), Some(0b101));
+    assert_eq!(r.get_i(7), Some(-5));
+    assert_eq!(r.get_u(16), Some(0xABCD));
+    assert_eq!(r.get_u(1), Some(1));
+    assert_eq!(r.get_u(8), None);
+}
+
+#[test]
+fn frame_round_trip() -> Result<(), Error> {
+    let framed = frame(&[0x3E, 0xD0, 0x00])?;
+    assert_eq!(&framed[..3], &[0xD3, 0x00, 0x03]);
+    let (payload, len) = parse_frame(&framed)?;
+    assert_eq!(payload, &[0x3E, 0xD0, 0x00]);
+    assert_eq!(len, 9);
+    let mut corrupted = framed.clone();
+    corrupted[4] ^= 1;
+    assert!(parse_frame(&corrupted).is_err());
+    assert!(frame(&[0; 1024]).is_err());
+    Ok(())
+}
+
+#[test]
+fn station_arp() -> Result<(), Error> {
+    let arp = StationArp {
+        station_id: 2003,
+        position: [-3_959_617.482_2, 3_350_136.614_5, 3_699_531.458_6],
+    };
+    let framed = arp.encode()?;
+    let (payload, _) = parse_frame(&framed)?;
+    assert_eq!(payload.len(), 19);
+    let mut r = BitReader::new(payload);
+    assert_eq!(r.get_u(12), Some(1005));
+    assert_eq!(r.get_u(12), Some(2003));
+    assert_eq!(r.get_u(6), Some(0));
+    assert_eq!(r.get_u(4), Some(0b1000));
+    assert_eq!(r.get_i(38), Some(-39_596_174_822));
+    assert_eq!(r.get_u(2), Some(0));
+    assert_eq!(r.get_i(38), Some(33_501_366_145));
+    assert_eq!(r.get_u(2), Some(0));
+    assert_eq!(r.get_i(38), Some(36_995_314_586));
+    assert!(
+        StationArp {
+            station_id: 4096,
+            ..arp
+        }
+        .encode()
+        .is_err()
+    );
+    Ok(())
+}
+
+#[test]
+fn gps_ephemeris() -> Result<(), Error> {
+    let eph = GpsEphemeris {
+        prn: 5,
+        week: 2190,
+        toc: 518_400.0,
+        toe: 518_400.0,
+        iode: 39,
+        iodc: 39,
+        sqrt_a: 5_153.763_774_87,
+        eccentricity: 0.013_383_595_971_4,
+        m0: -0.3,
+        af0: 2.665e-4,
+        ..Default::default()
+    };
+    let framed = eph.encode()?;
+    let (payload, _) = parse_frame(&framed)?;
+    // 488 bits
+    assert_eq!(payload.len(), 61);
+    assert_eq!(message_number(payload), Some(1019));
+    let mut r = BitReader::new(payload);
+    r.get_u(12);
+    assert_eq!(r.get_u(6), Some(5));
+    assert_eq!(r.get_u(10), Some(2190 % 1024));
+    assert!(GpsEphemeris { prn: 0, ..eph }.encode().is_err());
+    Ok(())
+}
+
+#[test_case(0, 0; "no lock")]
+#[test_case(31, 0; "below 32 ms")]
+#[test_case(32, 1; "32 ms")]
+#[test_case(1000, 5; "one second")]
+#[test_case(600_000, 15; "saturated")]
+fn lock_time(ms: u32, expected: u8) {
+    assert_eq!(lock_time_indicator(ms), expected);
+}
+
+#[test_case(63, 63; "linear range")]
+#[test_case(64, 64; "first step")]
+#[test_case(128, 96; "second step")]
+#[test_case(1000, 190; "one second")]
+#[test_case(67_108_864, 704; "saturated")]
+fn extended_lock_time(ms: u32, expected: u16) {
+    assert_eq!(extended_lock_time_indicator(ms), expected);
+}
+
+#[test_case(MsmType::Msm4, 2.0 * RANGE_MS / 16_777_216.0; "msm4")]
+#[test_case(MsmType::Msm7, 2.0 * RANGE_MS / 536_870_912.0; "msm7")]
+fn msm_round_trip(msm: MsmType, tolerance: f64) -> Result<(), Error> {
+    let ranges = [(5, 22_213_582.902), (24, 20_202_075.793)];
+    let epoch = MsmEpoch {
+        station_id: 1,
+        tow_ms: 518_401_000,
+        msm,
+        satellites: ranges
+            .iter()
+            .map(|&(id, range)| MsmSatellite {
+                id,
+                signals: vec![MsmSignal {
+                    id: GPS_L1CA_SIGNAL,
+                    pseudorange: range,
+                    phase_range: range + 0.1,
+                    phase_range_rate: -526.123,
+                    cnr: 44.5,
+                    lock_time_ms: 1000,
+                    half_cycle: false,
+                }],
+            })
+            .collect(),
+        ..Default::default()
+    };
+    let framed = epoch.encode()?;
+    let (payload, _) = parse_frame(&framed)?;
+    let mut r = BitReader::new(payload);
+    assert_eq!(r.get_u(12), Some(u64::from(msm.gps_message_number())));
+    assert_eq!(r.get_u(12), Some(1));
+    assert_eq!(r.get_u(30), Some(518_401_000));
+    r.get_u(1 + 3 + 7 + 2 + 2 + 1 + 3);
+    assert_eq!(r.get_u(64), Some((1 << 59) | (1 << 40)));
+    assert_eq!(r.get_u(32), Some(1 << 30));
+    assert_eq!(r.get_u(2), Some(0b11));
+    let mut rough = [0.0; 2];
+    for value in &mut rough {
+        *value = r.get_u(8).unwrap_or_default() as f64;
+    }
+    if msm == MsmType::Msm7 {
+        r.get_u(8);
+    }
+    for value in &mut rough {
+        *value += r.get_u(10).unwrap_or_default() as f64 / 1024.0;
+    }
+    if msm == MsmType::Msm7 {
+        assert_eq!(r.get_i(14), Some(-526));
+        assert_eq!(r.get_i(14), Some(-526));
+    }
+    let (bits, scale) = match msm {
+        MsmType::Msm4 => (15, 16_777_216.0),
+        MsmType::Msm7 => (20, 536_870_912.0),
+    };
+    for (&(_, range), rough) in ranges.iter().zip(rough) {
+        let fine = r.get_i(bits).unwrap_or_default() as f64 / scale;
+        assert!(((rough + fine) * RANGE_MS - range).abs() < tolerance);
+    }
+    Ok(())
+}